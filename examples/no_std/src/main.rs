@@ -0,0 +1,127 @@
+//! Hash, sign and verify a block with only `core` and `alloc`.
+//!
+//! On a host target this runs as an ordinary program. On a bare-metal
+//! target the crate and this example build without `std`, using a board
+//! RNG for the seed. From this directory:
+//!
+//! cargo run
+//! cargo build --target thumbv7em-none-eabihf
+//!
+//! A real firmware would take its allocator, panic handler and entry point
+//! from its board support crates; minimal stand-ins are defined below.
+#![cfg_attr(target_os = "none", no_std, no_main)]
+
+extern crate alloc;
+
+use xno_connect::blocks::{send_block_builder, BlockSigner};
+use xno_connect::error::Result;
+use xno_connect::keys::{Seed, SeedRng};
+use xno_connect::types::{BlockHash, Raw, Work};
+
+/// Stand-in for a hardware TRNG. Never use a counter for real seeds.
+struct BoardRng(u8);
+
+impl SeedRng for BoardRng {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+        for byte in dest {
+            self.0 = self.0.wrapping_mul(31).wrapping_add(7);
+            *byte = self.0;
+        }
+        Ok(())
+    }
+}
+
+/// Sign a send block and check the signature. Returns whether it verified.
+fn run() -> bool {
+    let seed = match Seed::random_with(&mut BoardRng(1)) {
+        Ok(seed) => seed,
+        Err(_) => return false,
+    };
+    let sender = seed.derive(0);
+    let destination = seed.derive(1).account();
+
+    let block = send_block_builder(
+        sender.account(),
+        BlockHash::ZERO,
+        sender.account(),
+        Raw::from(1000u128),
+        &destination,
+    )
+    .work(Work::new(0))
+    .sign(&sender)
+    .build();
+
+    match block {
+        Ok(block) => BlockSigner::verify(&block),
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(target_os = "none"))]
+fn main() {
+    assert!(run());
+    println!("signed and verified without std-only APIs");
+}
+
+#[cfg(target_os = "none")]
+mod bare_metal {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::cell::UnsafeCell;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    const HEAP_SIZE: usize = 64 * 1024;
+
+    /// Bump allocator over a static buffer; memory is never freed.
+    struct BumpAllocator {
+        heap: UnsafeCell<[u8; HEAP_SIZE]>,
+        next: AtomicUsize,
+    }
+
+    unsafe impl Sync for BumpAllocator {}
+
+    unsafe impl GlobalAlloc for BumpAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let base = self.heap.get() as usize;
+            let mut offset = self.next.load(Ordering::Relaxed);
+            loop {
+                let start = (base + offset + layout.align() - 1) & !(layout.align() - 1);
+                let end = start - base + layout.size();
+                if end > HEAP_SIZE {
+                    return core::ptr::null_mut();
+                }
+                match self.next.compare_exchange_weak(
+                    offset,
+                    end,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => return start as *mut u8,
+                    Err(current) => offset = current,
+                }
+            }
+        }
+
+        unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: BumpAllocator = BumpAllocator {
+        heap: UnsafeCell::new([0; HEAP_SIZE]),
+        next: AtomicUsize::new(0),
+    };
+
+    #[panic_handler]
+    fn panic(_info: &core::panic::PanicInfo) -> ! {
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+
+    #[no_mangle]
+    pub extern "C" fn _start() -> ! {
+        let _verified = super::run();
+        loop {
+            core::hint::spin_loop();
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::env;
+use std::sync::Arc;
 
 use xno_connect::prelude::{Account, BlockHash, RpcClient, Wallet};
+use xno_connect::work::{CpuWorkGenerator, WorkProvider};
 
 // Run
 // cargo run --release --example rpc_and_wallet --features work-cpu
@@ -29,10 +31,12 @@ async fn main() {
 
     // Wallet provides access to multiple accounts derived from a single seed
     let mut wallet = Wallet::from_hex_seed(&env::var("NANO_SEED").unwrap()).unwrap();
-    // _local means that work will be computed locally
+    // A CPU work provider means that work will be computed locally
+    let provider = Arc::new(WorkProvider::new().with_cpu(CpuWorkGenerator::new()));
     let result = wallet
         .account(0)
-        .send_local(&destination, 1.into(), &client) // Send single raw
+        .with_work_provider(provider)
+        .send(&destination, 1.into(), &client) // Send single raw
         .await;
 
     println!("Result: {:?}", result);
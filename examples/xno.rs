@@ -0,0 +1,204 @@
+//! Command-line tool for common wallet and node operations.
+//!
+//! Run
+//! cargo run --release --example xno --features cli -- <command> [args]
+//!
+//! Commands:
+//!
+//! balance <account|index>               Confirmed and receivable balance
+//! send <index> <destination> <amount>   Send an amount in XNO
+//! receive-all <index>                   Receive every receivable block
+//! history <account|index> [count]       Recent transactions (default 10)
+//! work <hash> [send|receive]            Generate work on the CPU
+//! watch <account>...                    Print confirmations as they arrive
+//!
+//! Accounts given as an index are derived from `NANO_SEED`. The node is
+//! taken from `NANO_RPC_URL` and `NANO_WS_URL`; a `.env` file is read if
+//! present. The seed is never accepted as an argument, so it does not end
+//! up in shell history.
+
+use std::env;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use xno_connect::prelude::{Account, BlockHash, Raw, RpcClient, Subtype, Wallet, WebSocketClient};
+use xno_connect::websocket::{ParsedMessage, SubscriptionBuilder};
+use xno_connect::work::{CpuWorkGenerator, WorkProvider, WorkThreshold};
+
+const DEFAULT_RPC_URL: &str = "http://localhost:7076";
+const DEFAULT_WS_URL: &str = "ws://localhost:7078";
+
+type CliResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
+
+const USAGE: &str = "usage: xno <command> [args]
+
+commands:
+  balance <account|index>
+  send <index> <destination> <amount>
+  receive-all <index>
+  history <account|index> [count]
+  work <hash> [send|receive]
+  watch <account>...";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenvy::dotenv().ok();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some((command, args)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::FAILURE;
+    };
+
+    let result = match (command.as_str(), args) {
+        ("balance", [account]) => balance(account).await,
+        ("send", [index, destination, amount]) => send(index, destination, amount).await,
+        ("receive-all", [index]) => receive_all(index).await,
+        ("history", [account]) => history(account, "10").await,
+        ("history", [account, count]) => history(account, count).await,
+        ("work", [hash]) => work(hash, "send"),
+        ("work", [hash, subtype]) => work(hash, subtype),
+        ("watch", accounts) if !accounts.is_empty() => watch(accounts).await,
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn balance(account: &str) -> CliResult {
+    let account = resolve_account(account)?;
+    let balance = rpc_client().account_balance(&account).await?;
+    println!("account     {}", account);
+    println!("balance     {} XNO", balance.balance.to_nano_string());
+    println!(
+        "receivable  {} XNO",
+        balance
+            .receivable
+            .unwrap_or(balance.pending)
+            .to_nano_string()
+    );
+    Ok(())
+}
+
+async fn send(index: &str, destination: &str, amount: &str) -> CliResult {
+    let destination = Account::from_address_str_checked(destination)?;
+    let amount = Raw::from_nano_str(amount)?;
+    let mut wallet = wallet()?;
+    let response = wallet
+        .account(parse_index(index)?)
+        .with_work_provider(cpu_work_provider())
+        .send(&destination, amount, &rpc_client())
+        .await?;
+    println!("{}", response.hash);
+    Ok(())
+}
+
+async fn receive_all(index: &str) -> CliResult {
+    let mut wallet = wallet()?;
+    let hashes = wallet
+        .account(parse_index(index)?)
+        .with_work_provider(cpu_work_provider())
+        .receive_all(&rpc_client())
+        .await?;
+    for hash in &hashes {
+        println!("{}", hash);
+    }
+    eprintln!("received {} block(s)", hashes.len());
+    Ok(())
+}
+
+async fn history(account: &str, count: &str) -> CliResult {
+    let account = resolve_account(account)?;
+    let count = count
+        .parse()
+        .map_err(|_| format!("invalid count {}", count))?;
+    let history = rpc_client().account_history(&account, count).await?;
+    for entry in history.history {
+        println!(
+            "{:>8}  {:<8} {:>20} XNO  {}  {}",
+            entry.height,
+            entry.block_type,
+            entry.amount.to_nano_string(),
+            entry.account,
+            entry.hash
+        );
+    }
+    Ok(())
+}
+
+fn work(hash: &str, subtype: &str) -> CliResult {
+    let hash = BlockHash::from_hex(hash)?;
+    let subtype = match subtype {
+        "send" => Subtype::Send,
+        "receive" => Subtype::Receive,
+        other => return Err(format!("unknown subtype {}, use send or receive", other).into()),
+    };
+    let threshold = WorkThreshold::MAINNET.for_subtype(subtype);
+    let work = CpuWorkGenerator::new().generate(&hash, threshold, None)?;
+    println!("{}", work);
+    Ok(())
+}
+
+async fn watch(accounts: &[String]) -> CliResult {
+    let accounts = accounts
+        .iter()
+        .map(|account| Account::from_address_str_checked(account))
+        .collect::<xno_connect::Result<Vec<_>>>()?;
+    let url = env::var("NANO_WS_URL").unwrap_or_else(|_| DEFAULT_WS_URL.into());
+    let mut client = WebSocketClient::connect(url).await?;
+    client
+        .subscribe(
+            SubscriptionBuilder::new()
+                .confirmations()
+                .accounts(&accounts),
+        )
+        .await?;
+
+    while let Some(message) = client.receive().await? {
+        if let ParsedMessage::Confirmation(confirmation) = message {
+            println!(
+                "{}  {} XNO  {}",
+                confirmation.account,
+                confirmation.amount.to_nano_string(),
+                confirmation.hash
+            );
+        }
+    }
+    Ok(())
+}
+
+fn rpc_client() -> RpcClient {
+    RpcClient::new(env::var("NANO_RPC_URL").unwrap_or_else(|_| DEFAULT_RPC_URL.into()))
+}
+
+fn cpu_work_provider() -> Arc<WorkProvider> {
+    Arc::new(WorkProvider::new().with_cpu(CpuWorkGenerator::new()))
+}
+
+fn wallet() -> CliResult<Wallet> {
+    let seed = env::var("NANO_SEED").map_err(|_| "NANO_SEED is not set")?;
+    Ok(Wallet::from_hex_seed(&seed)?)
+}
+
+fn parse_index(index: &str) -> CliResult<u32> {
+    index
+        .parse()
+        .map_err(|_| format!("invalid account index {}", index).into())
+}
+
+/// Accept an address, or an index derived from `NANO_SEED`.
+fn resolve_account(account: &str) -> CliResult<Account> {
+    match account.parse::<u32>() {
+        Ok(index) => Ok(wallet()?.address(index)),
+        Err(_) => Ok(Account::from_address_str_checked(account)?),
+    }
+}
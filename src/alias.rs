@@ -0,0 +1,368 @@
+//! Human-readable account names.
+//!
+//! A [`Recipient`] is either an account address or an alias such as
+//! `@esteban`. Aliases are turned into accounts by a [`Resolver`]; the
+//! wallet's send helpers accept any `impl Into<Recipient>` but refuse
+//! aliases that have not been resolved, so every send goes to an address
+//! the caller has seen.
+//!
+//! With the `alias` feature, [`NanoToResolver`] resolves names registered
+//! with nano.to. Implement [`Resolver`] to plug in other naming systems.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::alias::{Recipient, StaticResolver};
+//!
+//! # async fn example() -> xno_connect::error::Result<()> {
+//! let account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3".parse()?;
+//! let resolver = StaticResolver::new().with_alias("faucet", account);
+//!
+//! let recipient: Recipient = "@faucet".parse()?;
+//! let resolved = recipient.resolve(&resolver).await?;
+//! assert_eq!(resolved.to_string(), "@faucet");
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::{AliasError, Error, Result};
+use crate::types::Account;
+
+/// Destination of a send: an address or a name for one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Recipient {
+    /// An account address.
+    Account(Account),
+    /// An alias that has not been resolved yet, without the leading `@`.
+    Alias(String),
+    /// An alias and the account it resolved to.
+    Resolved {
+        /// Alias, without the leading `@`.
+        alias: String,
+        /// Account the alias resolved to.
+        account: Account,
+    },
+}
+
+impl Recipient {
+    /// Create an unresolved alias, with or without the leading `@`.
+    ///
+    /// Names are case-insensitive and stored in lowercase. They may
+    /// contain ASCII letters, digits, `_`, `-` and `.`.
+    pub fn alias(name: &str) -> Result<Self> {
+        let name = name.strip_prefix('@').unwrap_or(name);
+        let valid = !name.is_empty()
+            && name.len() <= 64
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if !valid {
+            return Err(Error::Alias(AliasError::InvalidName(name.into())));
+        }
+        Ok(Recipient::Alias(name.to_ascii_lowercase()))
+    }
+
+    /// Get the account, if known.
+    pub fn account(&self) -> Option<&Account> {
+        match self {
+            Recipient::Account(account) | Recipient::Resolved { account, .. } => Some(account),
+            Recipient::Alias(_) => None,
+        }
+    }
+
+    /// Get the alias, if this recipient was given by name.
+    pub fn alias_name(&self) -> Option<&str> {
+        match self {
+            Recipient::Alias(alias) | Recipient::Resolved { alias, .. } => Some(alias),
+            Recipient::Account(_) => None,
+        }
+    }
+
+    /// Resolve an alias with `resolver`. Accounts and already resolved
+    /// aliases are returned unchanged.
+    pub async fn resolve<R: Resolver + ?Sized>(self, resolver: &R) -> Result<Recipient> {
+        match self {
+            Recipient::Alias(alias) => {
+                let account = resolver.resolve(&alias).await?;
+                Ok(Recipient::Resolved { alias, account })
+            }
+            resolved => Ok(resolved),
+        }
+    }
+
+    /// Get the account, failing with [`AliasError::Unresolved`] for an
+    /// unresolved alias.
+    pub fn into_account(self) -> Result<Account> {
+        match self {
+            Recipient::Account(account) | Recipient::Resolved { account, .. } => Ok(account),
+            Recipient::Alias(alias) => Err(Error::Alias(AliasError::Unresolved(alias))),
+        }
+    }
+}
+
+impl From<Account> for Recipient {
+    fn from(account: Account) -> Self {
+        Recipient::Account(account)
+    }
+}
+
+impl From<&Account> for Recipient {
+    fn from(account: &Account) -> Self {
+        Recipient::Account(account.clone())
+    }
+}
+
+impl FromStr for Recipient {
+    type Err = Error;
+
+    /// Parse an address (`nano_…`, `xno_…`) or an alias (`@name`).
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with('@') {
+            Recipient::alias(s)
+        } else {
+            Ok(Recipient::Account(s.parse()?))
+        }
+    }
+}
+
+impl fmt::Display for Recipient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Recipient::Account(account) => write!(f, "{}", account),
+            Recipient::Alias(alias) | Recipient::Resolved { alias, .. } => {
+                write!(f, "@{}", alias)
+            }
+        }
+    }
+}
+
+/// Naming system turning aliases into accounts.
+#[allow(async_fn_in_trait)]
+pub trait Resolver {
+    /// Resolve `name`, lowercase and without the leading `@`.
+    ///
+    /// Fails with [`AliasError::NotFound`] if no account is registered
+    /// under the name.
+    async fn resolve(&self, name: &str) -> Result<Account>;
+}
+
+/// Resolver with a fixed set of names, e.g. an address book.
+#[derive(Debug, Clone, Default)]
+pub struct StaticResolver {
+    names: BTreeMap<String, Account>,
+}
+
+impl StaticResolver {
+    /// Create a resolver that knows no names.
+    pub fn new() -> Self {
+        StaticResolver::default()
+    }
+
+    /// Register `account` under `name`, with or without the leading `@`.
+    pub fn with_alias(mut self, name: &str, account: Account) -> Self {
+        let name = name.strip_prefix('@').unwrap_or(name);
+        self.names.insert(name.to_ascii_lowercase(), account);
+        self
+    }
+}
+
+impl Resolver for StaticResolver {
+    async fn resolve(&self, name: &str) -> Result<Account> {
+        self.names
+            .get(&name.to_ascii_lowercase())
+            .cloned()
+            .ok_or_else(|| Error::Alias(AliasError::NotFound(name.into())))
+    }
+}
+
+/// Names registered with nano.to.
+///
+/// Downloads the public list of known names and keeps it for a TTL, so
+/// resolving many names costs one request.
+#[cfg(feature = "alias")]
+#[derive(Debug)]
+pub struct NanoToResolver {
+    client: reqwest::Client,
+    url: String,
+    ttl: core::time::Duration,
+    names: std::sync::Mutex<Option<(std::time::Instant, BTreeMap<String, Account>)>>,
+}
+
+#[cfg(feature = "alias")]
+impl NanoToResolver {
+    /// Public list of known names.
+    pub const DEFAULT_URL: &'static str = "https://nano.to/known.json";
+
+    /// Default time the list is reused before downloading it again.
+    pub const DEFAULT_TTL: core::time::Duration = core::time::Duration::from_secs(600);
+
+    /// Create a resolver using the public list.
+    pub fn new() -> Self {
+        Self::with_url(Self::DEFAULT_URL)
+    }
+
+    /// Create a resolver reading the list from another URL, e.g. a mirror.
+    pub fn with_url(url: &str) -> Self {
+        NanoToResolver {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            ttl: Self::DEFAULT_TTL,
+            names: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Set how long the list is reused.
+    pub fn with_ttl(mut self, ttl: core::time::Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    async fn fetch(&self) -> Result<BTreeMap<String, Account>> {
+        use alloc::string::ToString;
+
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| Error::Alias(AliasError::Unavailable(e.to_string())))?;
+        let status = response.status();
+        if !status.is_success() {
+            let error = alloc::format!("HTTP {}", status.as_u16());
+            return Err(Error::Alias(
+                if status.as_u16() == 429 || status.is_server_error() {
+                    AliasError::Unavailable(error)
+                } else {
+                    AliasError::InvalidResponse(error)
+                },
+            ));
+        }
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Alias(AliasError::InvalidResponse(e.to_string())))?;
+        parse_known(&body)
+    }
+}
+
+#[cfg(feature = "alias")]
+impl Default for NanoToResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alias")]
+impl Resolver for NanoToResolver {
+    async fn resolve(&self, name: &str) -> Result<Account> {
+        let name = name.to_ascii_lowercase();
+        let cached = {
+            let names = self.names.lock().unwrap_or_else(|e| e.into_inner());
+            match &*names {
+                Some((fetched, names)) if fetched.elapsed() < self.ttl => {
+                    Some(names.get(&name).cloned())
+                }
+                _ => None,
+            }
+        };
+        let found = match cached {
+            Some(found) => found,
+            None => {
+                let names = self.fetch().await?;
+                let found = names.get(&name).cloned();
+                *self.names.lock().unwrap_or_else(|e| e.into_inner()) =
+                    Some((std::time::Instant::now(), names));
+                found
+            }
+        };
+        found.ok_or(Error::Alias(AliasError::NotFound(name)))
+    }
+}
+
+/// Map names to accounts in a `known.json` list, skipping malformed
+/// entries.
+#[cfg(feature = "alias")]
+fn parse_known(body: &serde_json::Value) -> Result<BTreeMap<String, Account>> {
+    let entries = body.as_array().ok_or_else(|| {
+        Error::Alias(AliasError::InvalidResponse(
+            "expected a list of names".into(),
+        ))
+    })?;
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            let name = entry.get("name")?.as_str()?;
+            let account = entry.get("address")?.as_str()?.parse().ok()?;
+            Some((name.to_ascii_lowercase(), account))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    const ADDRESS: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+
+    #[test]
+    fn test_parse_recipient() {
+        let account: Account = ADDRESS.parse().unwrap();
+        let recipient: Recipient = ADDRESS.parse().unwrap();
+        assert_eq!(recipient, Recipient::Account(account.clone()));
+        assert_eq!(recipient.to_string(), ADDRESS);
+
+        let alias: Recipient = "@Esteban".parse().unwrap();
+        assert_eq!(alias, Recipient::Alias("esteban".into()));
+        assert_eq!(alias.account(), None);
+        assert!(matches!(
+            alias.into_account(),
+            Err(Error::Alias(AliasError::Unresolved(_)))
+        ));
+
+        assert!("@".parse::<Recipient>().is_err());
+        assert!("@bad name".parse::<Recipient>().is_err());
+        assert!("esteban".parse::<Recipient>().is_err());
+        assert_eq!(Recipient::from(&account).into_account().unwrap(), account);
+    }
+
+    #[tokio::test]
+    async fn test_static_resolver() {
+        let account: Account = ADDRESS.parse().unwrap();
+        let resolver = StaticResolver::new().with_alias("@Faucet", account.clone());
+
+        let resolved = Recipient::alias("faucet")
+            .unwrap()
+            .resolve(&resolver)
+            .await
+            .unwrap();
+        assert_eq!(resolved.account(), Some(&account));
+        assert_eq!(resolved.alias_name(), Some("faucet"));
+        assert_eq!(resolved.to_string(), "@faucet");
+
+        let missing = Recipient::alias("nobody").unwrap().resolve(&resolver).await;
+        assert!(matches!(
+            missing,
+            Err(Error::Alias(AliasError::NotFound(_)))
+        ));
+    }
+
+    #[cfg(feature = "alias")]
+    #[test]
+    fn test_parse_known() {
+        let body = serde_json::json!([
+            {"name": "Esteban", "address": ADDRESS},
+            {"name": "broken", "address": "nano_bad"},
+            {"address": ADDRESS}
+        ]);
+        let names = parse_known(&body).unwrap();
+        assert_eq!(names.len(), 1);
+        assert_eq!(names["esteban"], ADDRESS.parse().unwrap());
+        assert!(parse_known(&serde_json::json!({})).is_err());
+    }
+}
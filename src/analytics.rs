@@ -0,0 +1,274 @@
+//! Account activity analytics.
+//!
+//! Summarizes an account's history into totals, counterparties and monthly
+//! aggregates suitable for dashboards.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xno_connect::analytics;
+//! use xno_connect::rpc::RpcClient;
+//!
+//! # async fn example() -> xno_connect::Result<()> {
+//! let client = RpcClient::new("https://rpc.nano.to");
+//! let account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3".parse()?;
+//! let history = client.account_history(&account, 1000).await?;
+//!
+//! let summary = analytics::summarize(&history.history);
+//! println!("Received: {} raw", summary.total_received);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use serde::Serialize;
+
+use crate::rpc::AccountHistoryEntry;
+use crate::types::{Account, Raw};
+
+/// Summary of an account's activity.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ActivitySummary {
+    /// Number of history entries considered.
+    pub entries: usize,
+    /// Number of send entries.
+    pub sends: usize,
+    /// Number of receive entries.
+    pub receives: usize,
+    /// Total amount sent.
+    pub total_sent: Raw,
+    /// Total amount received.
+    pub total_received: Raw,
+    /// Average amount per send.
+    pub average_sent: Raw,
+    /// Average amount per receive.
+    pub average_received: Raw,
+    /// Earliest known local timestamp (Unix seconds).
+    pub first_activity: Option<u64>,
+    /// Latest known local timestamp (Unix seconds).
+    pub last_activity: Option<u64>,
+    /// Counterparties ranked by total volume, largest first.
+    pub counterparties: Vec<CounterpartySummary>,
+    /// Monthly aggregates, oldest first.
+    pub monthly: Vec<MonthlySummary>,
+}
+
+/// Activity with a single counterparty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CounterpartySummary {
+    /// Counterparty account.
+    pub account: Account,
+    /// Amount sent to the counterparty.
+    pub sent: Raw,
+    /// Amount received from the counterparty.
+    pub received: Raw,
+    /// Number of transfers with the counterparty.
+    pub transactions: usize,
+}
+
+impl CounterpartySummary {
+    /// Total amount moved in either direction.
+    pub fn volume(&self) -> Raw {
+        self.sent.saturating_add(self.received)
+    }
+}
+
+/// Activity within one calendar month (UTC).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MonthlySummary {
+    /// Year.
+    pub year: i32,
+    /// Month (1-12).
+    pub month: u8,
+    /// Amount sent during the month.
+    pub sent: Raw,
+    /// Amount received during the month.
+    pub received: Raw,
+    /// Number of transfers during the month.
+    pub transactions: usize,
+}
+
+/// Summarize account history.
+///
+/// Only `send` and `receive` entries contribute to amounts and
+/// counterparties. Entries with a zero or unparsable `local_timestamp`
+/// (common for bootstrapped blocks) are left out of the activity range and
+/// monthly aggregates.
+pub fn summarize(history: &[AccountHistoryEntry]) -> ActivitySummary {
+    let mut summary = ActivitySummary {
+        entries: history.len(),
+        sends: 0,
+        receives: 0,
+        total_sent: Raw::ZERO,
+        total_received: Raw::ZERO,
+        average_sent: Raw::ZERO,
+        average_received: Raw::ZERO,
+        first_activity: None,
+        last_activity: None,
+        counterparties: Vec::new(),
+        monthly: Vec::new(),
+    };
+    let mut counterparties: BTreeMap<[u8; 32], CounterpartySummary> = BTreeMap::new();
+    let mut monthly: BTreeMap<(i32, u8), MonthlySummary> = BTreeMap::new();
+
+    for entry in history {
+        let timestamp = entry.local_timestamp.parse::<u64>().ok().filter(|t| *t > 0);
+        if let Some(t) = timestamp {
+            summary.first_activity = Some(summary.first_activity.map_or(t, |f| f.min(t)));
+            summary.last_activity = Some(summary.last_activity.map_or(t, |l| l.max(t)));
+        }
+
+        let is_send = match entry.block_type.as_str() {
+            "send" => true,
+            "receive" => false,
+            _ => continue,
+        };
+
+        let counterparty = counterparties
+            .entry(*entry.account.public_key().as_bytes())
+            .or_insert_with(|| CounterpartySummary {
+                account: entry.account.clone(),
+                sent: Raw::ZERO,
+                received: Raw::ZERO,
+                transactions: 0,
+            });
+        counterparty.transactions += 1;
+
+        let month = timestamp.map(|t| {
+            let (year, month) = year_month(t);
+            monthly
+                .entry((year, month))
+                .or_insert_with(|| MonthlySummary {
+                    year,
+                    month,
+                    sent: Raw::ZERO,
+                    received: Raw::ZERO,
+                    transactions: 0,
+                })
+        });
+
+        if is_send {
+            summary.sends += 1;
+            summary.total_sent += entry.amount;
+            counterparty.sent += entry.amount;
+        } else {
+            summary.receives += 1;
+            summary.total_received += entry.amount;
+            counterparty.received += entry.amount;
+        }
+        if let Some(month) = month {
+            month.transactions += 1;
+            if is_send {
+                month.sent += entry.amount;
+            } else {
+                month.received += entry.amount;
+            }
+        }
+    }
+
+    if summary.sends > 0 {
+        summary.average_sent = summary.total_sent / summary.sends as u128;
+    }
+    if summary.receives > 0 {
+        summary.average_received = summary.total_received / summary.receives as u128;
+    }
+
+    summary.counterparties = counterparties.into_values().collect();
+    summary.counterparties.sort_by(|a, b| {
+        b.volume()
+            .cmp(&a.volume())
+            .then(b.transactions.cmp(&a.transactions))
+    });
+    summary.monthly = monthly.into_values().collect();
+    summary
+}
+
+/// Convert a Unix timestamp to a UTC (year, month).
+fn year_month(timestamp: u64) -> (i32, u8) {
+    // Civil-from-days conversion on the proleptic Gregorian calendar
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year as i32, month as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    const ALICE: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+    const BOB: &str = "nano_3e3j5tkog48pnny9dmfzj1r16pg8t1e76dz5tmac6iq689wyjfpiij4txtdo";
+
+    fn entry(block_type: &str, account: &str, amount: u128, timestamp: u64) -> AccountHistoryEntry {
+        serde_json::from_str(&format!(
+            r#"{{
+                "type": "{}",
+                "account": "{}",
+                "amount": "{}",
+                "local_timestamp": "{}",
+                "height": "1",
+                "hash": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+            }}"#,
+            block_type, account, amount, timestamp
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_year_month() {
+        assert_eq!(year_month(0), (1970, 1));
+        assert_eq!(year_month(951_782_400), (2000, 2)); // 2000-02-29
+        assert_eq!(year_month(1_704_067_199), (2023, 12)); // 2023-12-31 23:59:59
+        assert_eq!(year_month(1_704_067_200), (2024, 1));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let history = [
+            entry("send", BOB, 30, 1_706_745_600),      // 2024-02-01
+            entry("receive", ALICE, 10, 1_704_067_200), // 2024-01-01
+            entry("receive", BOB, 50, 1_704_153_600),   // 2024-01-02
+            entry("change", ALICE, 0, 0),
+        ];
+        let summary = summarize(&history);
+
+        assert_eq!(summary.entries, 4);
+        assert_eq!(summary.sends, 1);
+        assert_eq!(summary.receives, 2);
+        assert_eq!(summary.total_sent, Raw::new(30));
+        assert_eq!(summary.total_received, Raw::new(60));
+        assert_eq!(summary.average_received, Raw::new(30));
+        assert_eq!(summary.first_activity, Some(1_704_067_200));
+        assert_eq!(summary.last_activity, Some(1_706_745_600));
+
+        assert_eq!(summary.counterparties.len(), 2);
+        assert_eq!(summary.counterparties[0].account.as_str(), BOB);
+        assert_eq!(summary.counterparties[0].volume(), Raw::new(80));
+
+        assert_eq!(summary.monthly.len(), 2);
+        assert_eq!(
+            (summary.monthly[0].year, summary.monthly[0].month),
+            (2024, 1)
+        );
+        assert_eq!(summary.monthly[0].received, Raw::new(60));
+        assert_eq!(summary.monthly[1].sent, Raw::new(30));
+
+        assert!(serde_json::to_string(&summary).is_ok());
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        let summary = summarize(&[]);
+        assert_eq!(summary.entries, 0);
+        assert_eq!(summary.average_sent, Raw::ZERO);
+        assert!(summary.first_activity.is_none());
+    }
+}
@@ -0,0 +1,369 @@
+//! Local transaction annotations.
+//!
+//! Nano blocks have no memo field, so notes and tags are kept off-chain,
+//! keyed by block hash. An [`AnnotationStore`] holds them;
+//! [`MemoryAnnotationStore`] serializes to JSON for persistence and
+//! [`FileAnnotationStore`] keeps a JSON file in sync.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::annotation::{Annotation, AnnotationStore, MemoryAnnotationStore};
+//! use xno_connect::types::BlockHash;
+//!
+//! let mut store = MemoryAnnotationStore::new();
+//! let hash = BlockHash::from_bytes([1u8; 32]);
+//! store
+//!     .set(hash, Annotation::new().with_note("Rent").with_tag("housing"))
+//!     .unwrap();
+//!
+//! assert_eq!(store.get(&hash).unwrap().unwrap().note.as_deref(), Some("Rent"));
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result, StorageError};
+use crate::types::BlockHash;
+
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::rpc::AccountHistoryEntry;
+
+/// Note and tags attached to a block.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Annotation {
+    /// Free-text note.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Category tags, sorted and without duplicates.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Annotation {
+    /// Create an empty annotation.
+    pub fn new() -> Self {
+        Annotation::default()
+    }
+
+    /// Set the note.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// Add a tag.
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.add_tag(tag);
+        self
+    }
+
+    /// Add a tag, keeping tags sorted and unique.
+    pub fn add_tag(&mut self, tag: impl Into<String>) {
+        let tag = tag.into();
+        if let Err(pos) = self.tags.binary_search(&tag) {
+            self.tags.insert(pos, tag);
+        }
+    }
+
+    /// Remove a tag. Returns `true` if it was present.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        match self.tags.iter().position(|t| t == tag) {
+            Some(pos) => {
+                self.tags.remove(pos);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Check if the annotation has a tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Check if there is no note and no tags.
+    pub fn is_empty(&self) -> bool {
+        self.note.is_none() && self.tags.is_empty()
+    }
+}
+
+/// Storage for annotations.
+pub trait AnnotationStore {
+    /// Get the annotation for a block.
+    fn get(&self, hash: &BlockHash) -> Result<Option<Annotation>>;
+
+    /// Set the annotation for a block. An empty annotation removes it.
+    fn set(&mut self, hash: BlockHash, annotation: Annotation) -> Result<()>;
+
+    /// Remove the annotation for a block.
+    fn remove(&mut self, hash: &BlockHash) -> Result<Option<Annotation>>;
+
+    /// Get all annotations.
+    fn all(&self) -> Result<Vec<(BlockHash, Annotation)>>;
+
+    /// Get the hashes of all blocks with a tag.
+    fn tagged(&self, tag: &str) -> Result<Vec<BlockHash>> {
+        Ok(self
+            .all()?
+            .into_iter()
+            .filter(|(_, annotation)| annotation.has_tag(tag))
+            .map(|(hash, _)| hash)
+            .collect())
+    }
+}
+
+/// In-memory [`AnnotationStore`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryAnnotationStore {
+    annotations: BTreeMap<[u8; 32], Annotation>,
+}
+
+impl MemoryAnnotationStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        MemoryAnnotationStore::default()
+    }
+
+    /// Number of annotated blocks.
+    pub fn len(&self) -> usize {
+        self.annotations.len()
+    }
+
+    /// Check if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.annotations.is_empty()
+    }
+
+    /// Serialize to a JSON object keyed by block hash.
+    pub fn to_json(&self) -> Result<String> {
+        let map: BTreeMap<String, &Annotation> = self
+            .annotations
+            .iter()
+            .map(|(hash, annotation)| (BlockHash::from_bytes(*hash).to_hex(), annotation))
+            .collect();
+        serde_json::to_string_pretty(&map)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))
+    }
+
+    /// Deserialize from JSON produced by [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self> {
+        let map: BTreeMap<String, Annotation> = serde_json::from_str(json)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))?;
+        let mut store = MemoryAnnotationStore::new();
+        for (hash, annotation) in map {
+            store.set(BlockHash::from_hex(&hash)?, annotation)?;
+        }
+        Ok(store)
+    }
+}
+
+impl AnnotationStore for MemoryAnnotationStore {
+    fn get(&self, hash: &BlockHash) -> Result<Option<Annotation>> {
+        Ok(self.annotations.get(hash.as_bytes()).cloned())
+    }
+
+    fn set(&mut self, hash: BlockHash, annotation: Annotation) -> Result<()> {
+        if annotation.is_empty() {
+            self.annotations.remove(hash.as_bytes());
+        } else {
+            self.annotations.insert(*hash.as_bytes(), annotation);
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self, hash: &BlockHash) -> Result<Option<Annotation>> {
+        Ok(self.annotations.remove(hash.as_bytes()))
+    }
+
+    fn all(&self) -> Result<Vec<(BlockHash, Annotation)>> {
+        Ok(self
+            .annotations
+            .iter()
+            .map(|(hash, annotation)| (BlockHash::from_bytes(*hash), annotation.clone()))
+            .collect())
+    }
+}
+
+/// [`AnnotationStore`] persisted to a JSON file.
+///
+/// The file is read once on open and rewritten after every change.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct FileAnnotationStore {
+    path: std::path::PathBuf,
+    store: MemoryAnnotationStore,
+}
+
+#[cfg(feature = "std")]
+impl FileAnnotationStore {
+    /// Open a store, loading the file if it exists.
+    pub fn open(path: impl Into<std::path::PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let store = match std::fs::read_to_string(&path) {
+            Ok(json) => MemoryAnnotationStore::from_json(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => MemoryAnnotationStore::new(),
+            Err(e) => return Err(Error::Storage(StorageError::Io(e.to_string()))),
+        };
+        Ok(FileAnnotationStore { path, store })
+    }
+
+    fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, self.store.to_json()?)
+            .map_err(|e| Error::Storage(StorageError::Io(e.to_string())))
+    }
+}
+
+#[cfg(feature = "std")]
+impl AnnotationStore for FileAnnotationStore {
+    fn get(&self, hash: &BlockHash) -> Result<Option<Annotation>> {
+        self.store.get(hash)
+    }
+
+    fn set(&mut self, hash: BlockHash, annotation: Annotation) -> Result<()> {
+        self.store.set(hash, annotation)?;
+        self.save()
+    }
+
+    fn remove(&mut self, hash: &BlockHash) -> Result<Option<Annotation>> {
+        let removed = self.store.remove(hash)?;
+        if removed.is_some() {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    fn all(&self) -> Result<Vec<(BlockHash, Annotation)>> {
+        self.store.all()
+    }
+}
+
+/// History entry with its local annotation.
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+#[derive(Debug, Clone, Serialize)]
+pub struct AnnotatedEntry {
+    /// History entry from the node.
+    #[serde(flatten)]
+    pub entry: AccountHistoryEntry,
+    /// Local annotation, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<Annotation>,
+}
+
+/// Attach stored annotations to history entries.
+///
+/// The result serializes to JSON for export, with each entry's `note` and
+/// `tags` nested under `annotation`.
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+pub fn annotate_history<S: AnnotationStore + ?Sized>(
+    history: &[AccountHistoryEntry],
+    store: &S,
+) -> Result<Vec<AnnotatedEntry>> {
+    history
+        .iter()
+        .map(|entry| {
+            Ok(AnnotatedEntry {
+                entry: entry.clone(),
+                annotation: store.get(&entry.hash)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> BlockHash {
+        BlockHash::from_bytes([byte; 32])
+    }
+
+    #[test]
+    fn test_tags_sorted_and_unique() {
+        let mut annotation = Annotation::new()
+            .with_tag("rent")
+            .with_tag("housing")
+            .with_tag("rent");
+        assert_eq!(annotation.tags, ["housing", "rent"]);
+        assert!(annotation.remove_tag("rent"));
+        assert!(!annotation.has_tag("rent"));
+    }
+
+    #[test]
+    fn test_memory_store_round_trip() {
+        let mut store = MemoryAnnotationStore::new();
+        store
+            .set(
+                hash(1),
+                Annotation::new().with_note("Coffee").with_tag("food"),
+            )
+            .unwrap();
+        store
+            .set(hash(2), Annotation::new().with_tag("food"))
+            .unwrap();
+        store.set(hash(3), Annotation::new()).unwrap();
+
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.tagged("food").unwrap(), [hash(1), hash(2)]);
+
+        let restored = MemoryAnnotationStore::from_json(&store.to_json().unwrap()).unwrap();
+        assert_eq!(restored, store);
+
+        assert!(store.remove(&hash(1)).unwrap().is_some());
+        assert!(store.get(&hash(1)).unwrap().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_file_store_persists() {
+        let path = std::env::temp_dir().join(alloc::format!(
+            "xno-annotations-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let mut store = FileAnnotationStore::open(&path).unwrap();
+        store
+            .set(hash(7), Annotation::new().with_note("Invoice #42"))
+            .unwrap();
+
+        let reopened = FileAnnotationStore::open(&path).unwrap();
+        assert_eq!(
+            reopened.get(&hash(7)).unwrap().unwrap().note.as_deref(),
+            Some("Invoice #42")
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "rpc")]
+    #[test]
+    fn test_annotate_history() {
+        let entry: AccountHistoryEntry = serde_json::from_str(
+            r#"{
+                "type": "send",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "amount": "1000",
+                "local_timestamp": "1551532723",
+                "height": "2",
+                "hash": "0101010101010101010101010101010101010101010101010101010101010101"
+            }"#,
+        )
+        .unwrap();
+        let mut store = MemoryAnnotationStore::new();
+        store
+            .set(hash(1), Annotation::new().with_note("Lunch"))
+            .unwrap();
+
+        let annotated = annotate_history(&[entry], &store).unwrap();
+        assert_eq!(
+            annotated[0].annotation.as_ref().unwrap().note.as_deref(),
+            Some("Lunch")
+        );
+
+        let json = serde_json::to_string(&annotated).unwrap();
+        assert!(json.contains("\"hash\":\"0101"));
+        assert!(json.contains("\"note\":\"Lunch\""));
+    }
+}
@@ -0,0 +1,362 @@
+//! Verifiable account history archives.
+//!
+//! An [`AccountArchive`] packages an account's full chain of signed blocks,
+//! their hashes and any confirmation [`Receipt`]s into one JSON document
+//! with an integrity manifest. A third party can check it offline with
+//! [`AccountArchive::verify`]: hashes are recomputed, the chain is validated
+//! from its open block, and the manifest digests must match the contents.
+//!
+//! Only state blocks are supported; accounts whose chains begin with legacy
+//! blocks cannot be archived.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::archive::AccountArchive;
+//! use xno_connect::blocks::create_open_block;
+//! use xno_connect::keys::Seed;
+//! use xno_connect::types::{BlockHash, Raw};
+//!
+//! # fn main() -> xno_connect::error::Result<()> {
+//! let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+//! let open = create_open_block(
+//!     &keypair,
+//!     keypair.account(),
+//!     Raw::new(100),
+//!     &BlockHash::from_bytes([2u8; 32]),
+//!     None,
+//! );
+//!
+//! let json = AccountArchive::new(vec![open])?.to_json()?;
+//! AccountArchive::from_json(&json)?.verify()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::{BlockHasher, ChainValidator};
+use crate::error::{ArchiveError, Error, Result};
+use crate::receipt::Receipt;
+use crate::types::{Account, BlockHash, Raw, StateBlock};
+
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::error::BlockError;
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::rpc::RpcClient;
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::types::{Block, Subtype};
+
+/// Current archive format version.
+pub const ARCHIVE_FORMAT: u32 = 1;
+
+/// A block and its hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchivedBlock {
+    /// Block hash.
+    pub hash: BlockHash,
+    /// Signed block.
+    pub block: StateBlock,
+}
+
+/// Integrity manifest describing an archive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    /// Archive format version.
+    pub format: u32,
+    /// Archived account.
+    pub account: Account,
+    /// Number of blocks.
+    pub block_count: u64,
+    /// Hash of the last block.
+    pub head: BlockHash,
+    /// Balance after the last block.
+    pub balance: Raw,
+    /// Blake2b-256 over the wire format of every block, in chain order.
+    pub blocks_digest: String,
+    /// Blake2b-256 over the JSON of every receipt, in archive order.
+    pub receipts_digest: String,
+}
+
+/// An account chain packaged for offline verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountArchive {
+    /// Integrity manifest.
+    pub manifest: ArchiveManifest,
+    /// Blocks from the open block to the frontier.
+    pub blocks: Vec<ArchivedBlock>,
+    /// Confirmation receipts for archived blocks.
+    #[serde(default)]
+    pub receipts: Vec<Receipt>,
+}
+
+impl AccountArchive {
+    /// Create an archive from a full account chain, oldest block first.
+    pub fn new(blocks: Vec<StateBlock>) -> Result<Self> {
+        let blocks: Vec<ArchivedBlock> = blocks
+            .into_iter()
+            .map(|block| ArchivedBlock {
+                hash: BlockHasher::hash_state_block(&block),
+                block,
+            })
+            .collect();
+        let manifest = manifest(&blocks, &[])?;
+        let archive = AccountArchive {
+            manifest,
+            blocks,
+            receipts: Vec::new(),
+        };
+        archive.verify_chain(&ChainValidator::new().without_work_check())?;
+        Ok(archive)
+    }
+
+    /// Fetch an account's chain from a node and archive it.
+    ///
+    /// Walks back from the frontier with `block_info`, so this makes one
+    /// request per block.
+    #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+    pub async fn fetch(account: &Account, client: &RpcClient) -> Result<Self> {
+        let info = client.account_info(account).await?;
+        let mut blocks = Vec::new();
+        let mut hash = info.frontier;
+        while !hash.is_zero() {
+            let response = client.block_info(&hash).await?;
+            let Block::State(mut block) = response.contents.to_block()? else {
+                return Err(Error::InvalidBlock(BlockError::InvalidSubtype));
+            };
+            block.subtype = match response.subtype.as_deref() {
+                Some("send") => Some(Subtype::Send),
+                Some("receive") => Some(Subtype::Receive),
+                Some("open") => Some(Subtype::Open),
+                Some("change") => Some(Subtype::Change),
+                Some("epoch") => Some(Subtype::Epoch),
+                _ => None,
+            };
+            hash = block.previous;
+            blocks.push(block);
+        }
+        blocks.reverse();
+        Self::new(blocks)
+    }
+
+    /// Attach a confirmation receipt for an archived block.
+    pub fn add_receipt(&mut self, receipt: Receipt) -> Result<()> {
+        let index = self.receipts.len();
+        if !self.contains(&receipt.hash) || receipt.verify_block().is_err() {
+            return Err(Error::InvalidArchive(ArchiveError::InvalidReceipt(index)));
+        }
+        self.receipts.push(receipt);
+        self.manifest = manifest(&self.blocks, &self.receipts)?;
+        Ok(())
+    }
+
+    /// Check if a block is in the archive.
+    pub fn contains(&self, hash: &BlockHash) -> bool {
+        self.blocks.iter().any(|entry| entry.hash == *hash)
+    }
+
+    /// Serialize to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| Error::InvalidArchive(ArchiveError::Serialization(e.to_string())))
+    }
+
+    /// Deserialize from JSON. Call [`verify`](Self::verify) before trusting it.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::InvalidArchive(ArchiveError::Serialization(e.to_string())))
+    }
+
+    /// Verify the archive, skipping work validation.
+    ///
+    /// Work thresholds have changed over the network's lifetime, so old
+    /// blocks may not meet today's values; use
+    /// [`verify_with`](Self::verify_with) to check work as well.
+    pub fn verify(&self) -> Result<()> {
+        self.verify_with(&ChainValidator::new().without_work_check())
+    }
+
+    /// Verify the archive with a configured chain validator.
+    ///
+    /// Receipts are checked for block hash and signature; vote quorum can
+    /// be checked separately with [`Receipt::verify`].
+    pub fn verify_with(&self, validator: &ChainValidator) -> Result<()> {
+        if self.manifest.format != ARCHIVE_FORMAT {
+            return Err(Error::InvalidArchive(ArchiveError::UnsupportedFormat(
+                self.manifest.format,
+            )));
+        }
+        for (index, entry) in self.blocks.iter().enumerate() {
+            if BlockHasher::hash_state_block(&entry.block) != entry.hash {
+                return Err(Error::InvalidArchive(ArchiveError::HashMismatch(index)));
+            }
+        }
+        self.verify_chain(validator)?;
+        for (index, receipt) in self.receipts.iter().enumerate() {
+            if !self.contains(&receipt.hash) || receipt.verify_block().is_err() {
+                return Err(Error::InvalidArchive(ArchiveError::InvalidReceipt(index)));
+            }
+        }
+
+        let expected = manifest(&self.blocks, &self.receipts)?;
+        let mismatch = if expected.account != self.manifest.account {
+            Some("account")
+        } else if expected.block_count != self.manifest.block_count {
+            Some("block_count")
+        } else if expected.head != self.manifest.head {
+            Some("head")
+        } else if expected.balance != self.manifest.balance {
+            Some("balance")
+        } else if expected.blocks_digest != self.manifest.blocks_digest {
+            Some("blocks_digest")
+        } else if expected.receipts_digest != self.manifest.receipts_digest {
+            Some("receipts_digest")
+        } else {
+            None
+        };
+        match mismatch {
+            Some(field) => Err(Error::InvalidArchive(ArchiveError::ManifestMismatch(field))),
+            None => Ok(()),
+        }
+    }
+
+    fn verify_chain(&self, validator: &ChainValidator) -> Result<()> {
+        let Some(first) = self.blocks.first() else {
+            return Err(Error::InvalidArchive(ArchiveError::Empty));
+        };
+        if !first.block.is_open() {
+            return Err(Error::InvalidArchive(ArchiveError::InvalidChain(0)));
+        }
+        let blocks: Vec<StateBlock> = self.blocks.iter().map(|e| e.block.clone()).collect();
+        match validator.validate(&blocks).violations.first() {
+            Some(violation) => Err(Error::InvalidArchive(ArchiveError::InvalidChain(
+                violation.index,
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn manifest(blocks: &[ArchivedBlock], receipts: &[Receipt]) -> Result<ArchiveManifest> {
+    let last = blocks
+        .last()
+        .ok_or(Error::InvalidArchive(ArchiveError::Empty))?;
+
+    let mut hasher = Blake2b::<U32>::new();
+    for entry in blocks {
+        hasher.update(entry.block.to_bytes());
+    }
+    let blocks_digest = hex::encode_upper(hasher.finalize());
+
+    let mut hasher = Blake2b::<U32>::new();
+    for receipt in receipts {
+        let json = serde_json::to_vec(receipt)
+            .map_err(|e| Error::InvalidArchive(ArchiveError::Serialization(e.to_string())))?;
+        hasher.update(json);
+    }
+    let receipts_digest = hex::encode_upper(hasher.finalize());
+
+    Ok(ArchiveManifest {
+        format: ARCHIVE_FORMAT,
+        account: blocks[0].block.account.clone(),
+        block_count: blocks.len() as u64,
+        head: last.hash,
+        balance: last.block.balance,
+        blocks_digest,
+        receipts_digest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{create_open_block, create_send_block};
+    use crate::keys::{KeyPair, Seed};
+
+    fn keypair(index: u32) -> KeyPair {
+        Seed::from_bytes([5u8; 32]).derive(index)
+    }
+
+    fn chain() -> Vec<StateBlock> {
+        let owner = keypair(0);
+        let open = create_open_block(
+            &owner,
+            owner.account(),
+            Raw::new(100),
+            &BlockHash::from_bytes([1u8; 32]),
+            None,
+        );
+        let send = create_send_block(
+            &owner,
+            BlockHasher::hash_state_block(&open),
+            owner.account(),
+            Raw::new(100),
+            Raw::new(30),
+            &keypair(1).account(),
+            None,
+        );
+        vec![open, send]
+    }
+
+    #[test]
+    fn test_round_trip_with_receipt() {
+        let blocks = chain();
+        let mut archive = AccountArchive::new(blocks.clone()).unwrap();
+        archive
+            .add_receipt(Receipt::new(blocks[1].clone()).unwrap())
+            .unwrap();
+
+        assert_eq!(archive.manifest.block_count, 2);
+        assert_eq!(archive.manifest.balance, Raw::new(70));
+
+        let restored = AccountArchive::from_json(&archive.to_json().unwrap()).unwrap();
+        assert_eq!(restored, archive);
+        restored.verify().unwrap();
+    }
+
+    #[test]
+    fn test_tampering_detected() {
+        let archive = AccountArchive::new(chain()).unwrap();
+
+        let mut tampered = archive.clone();
+        tampered.blocks[1].block.balance = Raw::new(90);
+        assert_eq!(
+            tampered.verify(),
+            Err(Error::InvalidArchive(ArchiveError::HashMismatch(1)))
+        );
+
+        let mut tampered = archive.clone();
+        tampered.manifest.balance = Raw::new(1);
+        assert_eq!(
+            tampered.verify(),
+            Err(Error::InvalidArchive(ArchiveError::ManifestMismatch(
+                "balance"
+            )))
+        );
+
+        let mut tampered = archive;
+        tampered.blocks.remove(0);
+        assert_eq!(
+            tampered.verify(),
+            Err(Error::InvalidArchive(ArchiveError::InvalidChain(0)))
+        );
+    }
+
+    #[test]
+    fn test_rejects_foreign_receipt() {
+        let mut archive = AccountArchive::new(chain()).unwrap();
+        let other = create_open_block(
+            &keypair(2),
+            keypair(2).account(),
+            Raw::new(1),
+            &BlockHash::from_bytes([3u8; 32]),
+            None,
+        );
+        assert!(archive.add_receipt(Receipt::new(other).unwrap()).is_err());
+        assert!(AccountArchive::new(Vec::new()).is_err());
+    }
+}
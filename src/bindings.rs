@@ -0,0 +1,338 @@
+//! JavaScript bindings.
+//!
+//! `#[wasm_bindgen]` wrappers around [`Wallet`], [`RpcClient`] and
+//! [`BlockBuilder`]. Addresses, hashes and amounts cross the boundary as
+//! strings (raw amounts in decimal), keys and signatures as `Uint8Array`,
+//! and RPC calls return a `Promise`.
+//!
+//! ```js
+//! import init, { JsBlockBuilder, JsRpcClient, JsWallet } from "xno-connect";
+//!
+//! await init();
+//! const wallet = JsWallet.fromSeed(seedHex);
+//! const client = new JsRpcClient("https://rpc.nano.to");
+//! const info = await client.accountInfo(wallet.address(0));
+//!
+//! const builder = new JsBlockBuilder()
+//!   .previous(info.frontier)
+//!   .representative(info.representative)
+//!   .balance("1000000")
+//!   .linkAsAccount(destination)
+//!   .subtype("send");
+//! const block = wallet.signBlock(0, builder);
+//! const hash = await client.process(block);
+//! ```
+
+// The wasm_bindgen macro expands to unsafe FFI glue
+#![allow(unsafe_code)]
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::future::Future;
+use js_sys::Promise;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::future_to_promise;
+
+use crate::blocks::BlockBuilder;
+use crate::error::Result;
+use crate::keys::{KeyPair, Seed};
+use crate::rpc::RpcClient;
+use crate::types::{Account, BlockHash, Raw, Signature, StateBlock, Subtype, Work};
+use crate::wallet::Wallet;
+
+/// Run an RPC future as a `Promise` resolving to a plain JS object.
+fn promise<F>(future: F) -> Promise
+where
+    F: Future<Output = Result<serde_json::Value>> + 'static,
+{
+    future_to_promise(async move {
+        match future.await {
+            Ok(value) => js_sys::JSON::parse(&value.to_string()),
+            Err(e) => Err(JsError::from(e).into()),
+        }
+    })
+}
+
+fn key_bytes(bytes: &[u8]) -> core::result::Result<[u8; 32], JsError> {
+    bytes
+        .try_into()
+        .map_err(|_| JsError::new("expected 32 bytes"))
+}
+
+/// HD wallet deriving accounts from a seed.
+#[wasm_bindgen]
+pub struct JsWallet {
+    inner: Wallet,
+}
+
+#[wasm_bindgen]
+impl JsWallet {
+    /// Create a wallet from a random seed.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> core::result::Result<JsWallet, JsError> {
+        Ok(JsWallet {
+            inner: Wallet::new()?,
+        })
+    }
+
+    /// Create a wallet from a hex-encoded seed.
+    #[wasm_bindgen(js_name = fromSeed)]
+    pub fn from_seed(seed: &str) -> core::result::Result<JsWallet, JsError> {
+        Ok(JsWallet {
+            inner: Wallet::from_hex_seed(seed)?,
+        })
+    }
+
+    /// Create a wallet from 32 seed bytes.
+    #[wasm_bindgen(js_name = fromSeedBytes)]
+    pub fn from_seed_bytes(seed: &[u8]) -> core::result::Result<JsWallet, JsError> {
+        Ok(JsWallet {
+            inner: Wallet::from_seed(Seed::from_bytes(key_bytes(seed)?)),
+        })
+    }
+
+    /// Get the seed as hex.
+    pub fn seed(&self) -> String {
+        self.inner.seed().to_hex()
+    }
+
+    /// Get the address at `index`.
+    pub fn address(&mut self, index: u32) -> String {
+        self.inner.address(index).to_string()
+    }
+
+    /// Get the public key at `index`.
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&mut self, index: u32) -> Vec<u8> {
+        self.inner.keypair(index).public_key().as_bytes().to_vec()
+    }
+
+    /// Sign a 32-byte block hash with the key at `index`.
+    #[wasm_bindgen(js_name = signHash)]
+    pub fn sign_hash(&mut self, index: u32, hash: &[u8]) -> core::result::Result<Vec<u8>, JsError> {
+        let hash = BlockHash::from_bytes(key_bytes(hash)?);
+        Ok(self.inner.keypair(index).sign(&hash).as_bytes().to_vec())
+    }
+
+    /// Sign a block for the account at `index` and return its JSON.
+    ///
+    /// The block's account is set to the signing account.
+    #[wasm_bindgen(js_name = signBlock)]
+    pub fn sign_block(
+        &mut self,
+        index: u32,
+        builder: &JsBlockBuilder,
+    ) -> core::result::Result<String, JsError> {
+        let keypair = self.inner.keypair(index);
+        let block = builder
+            .inner
+            .clone()
+            .account(keypair.account())
+            .sign(keypair)
+            .build()?;
+        Ok(serde_json::to_string(&block)?)
+    }
+}
+
+/// Nano node RPC client.
+#[wasm_bindgen]
+pub struct JsRpcClient {
+    inner: RpcClient,
+}
+
+#[wasm_bindgen]
+impl JsRpcClient {
+    /// Create a client for a node URL.
+    #[wasm_bindgen(constructor)]
+    pub fn new(url: &str) -> JsRpcClient {
+        JsRpcClient {
+            inner: RpcClient::new(url),
+        }
+    }
+
+    /// Send a header with every request, e.g. `Authorization`.
+    #[wasm_bindgen(js_name = withHeader)]
+    pub fn with_header(self, name: &str, value: &str) -> JsRpcClient {
+        JsRpcClient {
+            inner: self.inner.with_header(name, value),
+        }
+    }
+
+    /// Get the node URL.
+    pub fn url(&self) -> String {
+        self.inner.url().to_string()
+    }
+
+    /// Resolves to `{ balance, receivable }` in raw.
+    #[wasm_bindgen(js_name = accountBalance)]
+    pub fn account_balance(&self, account: &str) -> core::result::Result<Promise, JsError> {
+        let account: Account = account.parse()?;
+        let client = self.inner.clone();
+        Ok(promise(async move {
+            let balance = client.account_balance(&account).await?;
+            Ok(serde_json::json!({
+                "balance": balance.balance.to_string(),
+                "receivable": balance.receivable.unwrap_or(balance.pending).to_string(),
+            }))
+        }))
+    }
+
+    /// Resolves to `{ frontier, balance, representative, blockCount }`.
+    #[wasm_bindgen(js_name = accountInfo)]
+    pub fn account_info(&self, account: &str) -> core::result::Result<Promise, JsError> {
+        let account: Account = account.parse()?;
+        let client = self.inner.clone();
+        Ok(promise(async move {
+            let info = client.account_info(&account).await?;
+            Ok(serde_json::json!({
+                "frontier": info.frontier.to_hex(),
+                "balance": info.balance.to_string(),
+                "representative": info.representative.map(|rep| rep.to_string()),
+                "blockCount": info.block_count,
+            }))
+        }))
+    }
+
+    /// Resolves to `{ count, unchecked, cemented }`.
+    #[wasm_bindgen(js_name = blockCount)]
+    pub fn block_count(&self) -> Promise {
+        let client = self.inner.clone();
+        promise(async move {
+            let count = client.block_count().await?;
+            Ok(serde_json::json!({
+                "count": count.count,
+                "unchecked": count.unchecked,
+                "cemented": count.cemented,
+            }))
+        })
+    }
+
+    /// Publish a block given as JSON; resolves to its hash.
+    pub fn process(&self, block: &str) -> core::result::Result<Promise, JsError> {
+        let block: StateBlock = serde_json::from_str(block)?;
+        let client = self.inner.clone();
+        Ok(promise(async move {
+            let response = client.process(block).await?;
+            Ok(serde_json::Value::String(response.hash.to_hex()))
+        }))
+    }
+
+    /// Generate work on the node; resolves to the work as hex.
+    #[wasm_bindgen(js_name = workGenerate)]
+    pub fn work_generate(&self, hash: &str) -> core::result::Result<Promise, JsError> {
+        let hash = BlockHash::from_hex(hash)?;
+        let client = self.inner.clone();
+        Ok(promise(async move {
+            let response = client.work_generate(&hash).await?;
+            Ok(serde_json::Value::String(response.work.to_hex()))
+        }))
+    }
+}
+
+/// State block builder.
+///
+/// Setters return the builder, so calls chain as in Rust.
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct JsBlockBuilder {
+    inner: BlockBuilder,
+}
+
+impl JsBlockBuilder {
+    fn map(self, f: impl FnOnce(BlockBuilder) -> BlockBuilder) -> JsBlockBuilder {
+        JsBlockBuilder {
+            inner: f(self.inner),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl JsBlockBuilder {
+    /// Create an empty builder.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> JsBlockBuilder {
+        JsBlockBuilder::default()
+    }
+
+    /// Set the account.
+    pub fn account(self, account: &str) -> core::result::Result<JsBlockBuilder, JsError> {
+        let account: Account = account.parse()?;
+        Ok(self.map(|b| b.account(account)))
+    }
+
+    /// Set the previous block hash (hex).
+    pub fn previous(self, hash: &str) -> core::result::Result<JsBlockBuilder, JsError> {
+        let hash = BlockHash::from_hex(hash)?;
+        Ok(self.map(|b| b.previous(hash)))
+    }
+
+    /// Set the representative.
+    pub fn representative(self, account: &str) -> core::result::Result<JsBlockBuilder, JsError> {
+        let account: Account = account.parse()?;
+        Ok(self.map(|b| b.representative(account)))
+    }
+
+    /// Set the balance after this block, in raw.
+    pub fn balance(self, raw: &str) -> core::result::Result<JsBlockBuilder, JsError> {
+        let balance: Raw = raw.parse()?;
+        Ok(self.map(|b| b.balance(balance)))
+    }
+
+    /// Set the link to a destination account (sends).
+    #[wasm_bindgen(js_name = linkAsAccount)]
+    pub fn link_as_account(self, account: &str) -> core::result::Result<JsBlockBuilder, JsError> {
+        let account: Account = account.parse()?;
+        Ok(self.map(|b| b.link_as_account(&account)))
+    }
+
+    /// Set the link to a source block hash (receives).
+    #[wasm_bindgen(js_name = linkAsBlock)]
+    pub fn link_as_block(self, hash: &str) -> core::result::Result<JsBlockBuilder, JsError> {
+        let hash = BlockHash::from_hex(hash)?;
+        Ok(self.map(|b| b.link_as_block(&hash)))
+    }
+
+    /// Set the subtype: `send`, `receive`, `open`, `change` or `epoch`.
+    pub fn subtype(self, subtype: &str) -> core::result::Result<JsBlockBuilder, JsError> {
+        let subtype = match subtype {
+            "send" => Subtype::Send,
+            "receive" => Subtype::Receive,
+            "open" => Subtype::Open,
+            "change" => Subtype::Change,
+            "epoch" => Subtype::Epoch,
+            _ => return Err(JsError::new("unknown subtype")),
+        };
+        Ok(self.map(|b| b.subtype(subtype)))
+    }
+
+    /// Set the work (hex).
+    pub fn work(self, work: &str) -> core::result::Result<JsBlockBuilder, JsError> {
+        let work = Work::from_hex(work)?;
+        Ok(self.map(|b| b.work(work)))
+    }
+
+    /// Set a signature made elsewhere, e.g. by a hardware wallet.
+    pub fn signature(self, signature: &[u8]) -> core::result::Result<JsBlockBuilder, JsError> {
+        let bytes: [u8; 64] = signature
+            .try_into()
+            .map_err(|_| JsError::new("expected 64 bytes"))?;
+        Ok(self.map(|b| b.signature(Signature::from_bytes(bytes))))
+    }
+
+    /// Sign with a 32-byte private key.
+    pub fn sign(self, private_key: &[u8]) -> core::result::Result<JsBlockBuilder, JsError> {
+        let keypair = KeyPair::from_private_key(key_bytes(private_key)?);
+        Ok(self.map(|b| b.sign(&keypair)))
+    }
+
+    /// Get the block hash as hex.
+    pub fn hash(&self) -> core::result::Result<String, JsError> {
+        Ok(self.inner.hash()?.to_hex())
+    }
+
+    /// Build the block and return its JSON.
+    pub fn build(&self) -> core::result::Result<String, JsError> {
+        let block = self.inner.clone().build()?;
+        Ok(serde_json::to_string(&block)?)
+    }
+}
@@ -1,9 +1,12 @@
 //! Fluent block builder for creating Nano state blocks.
 
-use crate::blocks::{BlockHasher, BlockSigner};
+use crate::blocks::{AsyncSigner, BlockHasher, BlockSigner, UnsignedBlock};
+use crate::constants::ACCOUNT_VERSION_EPOCH_2;
 use crate::error::{BlockError, Error, Result};
 use crate::keys::KeyPair;
+use crate::network::Network;
 use crate::types::{Account, BlockHash, Link, Raw, Signature, StateBlock, Subtype, Work};
+use crate::work::WorkValidator;
 
 /// Builder for creating state blocks.
 ///
@@ -43,6 +46,9 @@ pub struct BlockBuilder {
     subtype: Option<Subtype>,
     signature: Option<Signature>,
     work: Option<Work>,
+    account_version: Option<u8>,
+    legacy_epoch_acknowledged: bool,
+    network: Option<Network>,
 }
 
 impl BlockBuilder {
@@ -111,17 +117,81 @@ impl BlockBuilder {
         self
     }
 
+    /// Set the account version reported by `account_info`.
+    ///
+    /// Accounts below epoch v2 use different work thresholds, so building for
+    /// them fails unless [`acknowledge_legacy_epoch`](Self::acknowledge_legacy_epoch)
+    /// is also called.
+    pub fn account_version(mut self, version: u8) -> Self {
+        self.account_version = Some(version);
+        self
+    }
+
+    /// Allow building blocks for accounts not yet upgraded to epoch v2.
+    pub fn acknowledge_legacy_epoch(mut self) -> Self {
+        self.legacy_epoch_acknowledged = true;
+        self
+    }
+
+    /// Validate work against the thresholds of a network when building.
+    ///
+    /// Without a subtype, the lower receive threshold is used.
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
     /// Sign the block with the given keypair.
     ///
     /// This computes the block hash and signs it.
     pub fn sign(mut self, keypair: &KeyPair) -> Self {
-        if let Ok(block) = self.clone().build_unsigned() {
+        if let Ok(block) = self.clone().build_fields() {
             let signature = BlockSigner::sign(&block, keypair);
             self.signature = Some(signature);
         }
         self
     }
 
+    /// Sign the block with an asynchronous signer.
+    ///
+    /// The account defaults to the signer's if not set. Fails if the block
+    /// is incomplete, the signer fails, or the returned signature does not
+    /// verify against the block's account.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use xno_connect::prelude::*;
+    /// use xno_connect::blocks::BlockBuilder;
+    ///
+    /// # async fn example() -> xno_connect::error::Result<()> {
+    /// let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+    /// let block = BlockBuilder::new()
+    ///     .previous(BlockHash::ZERO)
+    ///     .representative(keypair.account())
+    ///     .balance(Raw::new(1))
+    ///     .link_as_block(&BlockHash::from_bytes([2u8; 32]))
+    ///     .sign_async(&keypair)
+    ///     .await?
+    ///     .build()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sign_async<S: AsyncSigner + ?Sized>(mut self, signer: &S) -> Result<Self> {
+        if self.account.is_none() {
+            self.account = Some(signer.account());
+        }
+        let hash = BlockHasher::hash_state_block(&self.build_fields()?);
+        let signature = signer.sign_hash(&hash).await?;
+
+        let account = self.account.as_ref().expect("account set above");
+        if !BlockSigner::verify_hash(&hash, account.public_key(), &signature) {
+            return Err(Error::InvalidSignature);
+        }
+        self.signature = Some(signature);
+        Ok(self)
+    }
+
     /// Set a pre-computed signature.
     pub fn signature(mut self, signature: Signature) -> Self {
         self.signature = Some(signature);
@@ -129,7 +199,13 @@ impl BlockBuilder {
     }
 
     /// Build the block without signature or work.
-    fn build_unsigned(&self) -> Result<StateBlock> {
+    fn build_fields(&self) -> Result<StateBlock> {
+        if let Some(version) = self.account_version {
+            if version < ACCOUNT_VERSION_EPOCH_2 && !self.legacy_epoch_acknowledged {
+                return Err(Error::InvalidBlock(BlockError::LegacyEpoch(version)));
+            }
+        }
+
         let account = self
             .account
             .clone()
@@ -158,19 +234,40 @@ impl BlockBuilder {
 
     /// Build the state block.
     ///
-    /// Returns an error if any required fields are missing.
+    /// Returns an error if any required fields are missing, if the account
+    /// version is below epoch v2 and has not been acknowledged, or if a
+    /// network is set and the work does not meet its threshold.
     pub fn build(self) -> Result<StateBlock> {
-        let mut block = self.build_unsigned()?;
+        let mut block = self.build_fields()?;
+        if let (Some(network), Some(work)) = (&self.network, self.work) {
+            let threshold = match self.subtype {
+                Some(subtype) => network.work_threshold.for_subtype(subtype),
+                None => network.work_threshold.for_receive(),
+            };
+            if !WorkValidator::validate(work, &block.work_root(), threshold) {
+                return Err(Error::InvalidBlock(BlockError::InsufficientWork));
+            }
+        }
         block.signature = self.signature;
         block.work = self.work;
         Ok(block)
     }
 
+    /// Build a template for signing elsewhere.
+    ///
+    /// Work, if set, is included; any signature is dropped. See
+    /// [`UnsignedBlock`].
+    pub fn build_unsigned(self) -> Result<UnsignedBlock> {
+        let mut block = self.build_fields()?;
+        block.work = self.work;
+        Ok(UnsignedBlock::new(block))
+    }
+
     /// Get the hash of the block being built.
     ///
     /// Returns an error if required fields are missing.
     pub fn hash(&self) -> Result<BlockHash> {
-        let block = self.build_unsigned()?;
+        let block = self.build_fields()?;
         Ok(BlockHasher::hash_state_block(&block))
     }
 }
@@ -246,6 +343,7 @@ mod tests {
     use super::*;
     use crate::keys::Seed;
     use crate::types::PublicKey;
+    use crate::work::WorkThreshold;
 
     fn test_keypair() -> KeyPair {
         let seed =
@@ -304,8 +402,7 @@ mod tests {
             .balance(Raw::from_nano(1).unwrap())
             .link(Link::ZERO);
 
-        let signature =
-            BlockSigner::sign(&block_builder.clone().build_unsigned().unwrap(), &keypair);
+        let signature = BlockSigner::sign(&block_builder.clone().build_fields().unwrap(), &keypair);
         let block = block_builder.signature(signature).build().unwrap();
 
         assert!(block.signature.is_some());
@@ -429,4 +526,109 @@ mod tests {
 
         assert_eq!(hash1, hash2);
     }
+
+    #[test]
+    fn test_legacy_epoch_requires_acknowledgment() {
+        let keypair = test_keypair();
+        let account = keypair.account();
+
+        let builder = BlockBuilder::new()
+            .account(account.clone())
+            .previous(BlockHash::ZERO)
+            .representative(account)
+            .balance(Raw::from_nano(1).unwrap())
+            .link(Link::ZERO)
+            .account_version(1);
+
+        assert!(matches!(
+            builder.clone().build(),
+            Err(Error::InvalidBlock(BlockError::LegacyEpoch(1)))
+        ));
+        assert!(builder.clone().hash().is_err());
+        assert!(builder.acknowledge_legacy_epoch().build().is_ok());
+    }
+
+    #[test]
+    fn test_epoch_2_account_version_builds() {
+        let keypair = test_keypair();
+        let account = keypair.account();
+
+        let block = BlockBuilder::new()
+            .account(account.clone())
+            .previous(BlockHash::ZERO)
+            .representative(account)
+            .balance(Raw::from_nano(1).unwrap())
+            .link(Link::ZERO)
+            .account_version(ACCOUNT_VERSION_EPOCH_2)
+            .build();
+
+        assert!(block.is_ok());
+    }
+
+    #[test]
+    fn test_network_work_validation() {
+        let keypair = test_keypair();
+        let account = keypair.account();
+        let network = Network::new(
+            "strict-send",
+            Network::DEV.genesis,
+            WorkThreshold {
+                send: u64::MAX,
+                receive: 0,
+            },
+        );
+
+        let builder = BlockBuilder::new()
+            .account(account.clone())
+            .previous(BlockHash::ZERO)
+            .representative(account)
+            .balance(Raw::from_nano(1).unwrap())
+            .link(Link::ZERO)
+            .work(Work::ZERO)
+            .network(network);
+
+        assert!(matches!(
+            builder.clone().subtype(Subtype::Send).build(),
+            Err(Error::InvalidBlock(BlockError::InsufficientWork))
+        ));
+        assert!(builder.clone().subtype(Subtype::Receive).build().is_ok());
+        assert!(builder.build().is_ok());
+    }
+
+    /// Signer claiming one account but signing with another key.
+    struct WrongKey(KeyPair, KeyPair);
+
+    impl AsyncSigner for WrongKey {
+        fn account(&self) -> Account {
+            self.0.account()
+        }
+
+        async fn sign_hash(&self, hash: &BlockHash) -> Result<Signature> {
+            Ok(self.1.sign(hash))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_async() {
+        let keypair = test_keypair();
+        let builder = BlockBuilder::new()
+            .previous(BlockHash::ZERO)
+            .representative(keypair.account())
+            .balance(Raw::new(1))
+            .link(Link::ZERO);
+
+        let signed = builder.clone().sign_async(&keypair).await.unwrap().build();
+        let expected = builder
+            .clone()
+            .account(keypair.account())
+            .sign(&keypair)
+            .build();
+        assert_eq!(signed.unwrap().signature, expected.unwrap().signature);
+
+        let other = Seed::from_bytes([9u8; 32]).derive(0);
+        assert_eq!(
+            builder.sign_async(&WrongKey(keypair, other)).await.err(),
+            Some(Error::InvalidSignature)
+        );
+    }
 }
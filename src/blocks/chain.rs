@@ -0,0 +1,446 @@
+//! Account-chain validation.
+//!
+//! [`ChainValidator`] checks an ordered run of state blocks belonging to one
+//! account without trusting the node that served them: every block must link
+//! to the one before it, its balance change must agree with its subtype, and
+//! its signature and work must be valid.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::blocks::{BlockHasher, BlockSigner};
+use crate::error::{BlockError, Error, Result};
+use crate::network::Network;
+use crate::types::{Account, BlockHash, Link, Raw, StateBlock, Subtype};
+use crate::work::{WorkThreshold, WorkValidator};
+
+/// Problem found with a block in an account chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainFault {
+    /// Block belongs to a different account.
+    AccountMismatch,
+    /// Previous pointer does not reference the preceding block.
+    PreviousMismatch {
+        /// Hash of the preceding block.
+        expected: BlockHash,
+    },
+    /// Open block appears after the start of the chain.
+    UnexpectedOpen,
+    /// Declared subtype disagrees with the balance change.
+    SubtypeMismatch {
+        /// Subtype set on the block.
+        declared: Subtype,
+        /// Subtype implied by the balance change.
+        actual: Subtype,
+    },
+    /// Receive or open block without a source.
+    MissingSource,
+    /// Block is not signed.
+    MissingSignature,
+    /// Signature does not verify.
+    InvalidSignature,
+    /// Block has no work.
+    MissingWork,
+    /// Work is below the threshold for the subtype.
+    InsufficientWork,
+}
+
+impl fmt::Display for ChainFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainFault::AccountMismatch => write!(f, "block belongs to another account"),
+            ChainFault::PreviousMismatch { expected } => {
+                write!(f, "previous does not match {}", expected)
+            }
+            ChainFault::UnexpectedOpen => write!(f, "open block inside chain"),
+            ChainFault::SubtypeMismatch { declared, actual } => write!(
+                f,
+                "declared {} but balance change implies {}",
+                declared, actual
+            ),
+            ChainFault::MissingSource => write!(f, "missing source link"),
+            ChainFault::MissingSignature => write!(f, "missing signature"),
+            ChainFault::InvalidSignature => write!(f, "invalid signature"),
+            ChainFault::MissingWork => write!(f, "missing work"),
+            ChainFault::InsufficientWork => write!(f, "insufficient work"),
+        }
+    }
+}
+
+impl From<ChainFault> for Error {
+    fn from(fault: ChainFault) -> Self {
+        match fault {
+            ChainFault::AccountMismatch | ChainFault::PreviousMismatch { .. } => {
+                Error::InvalidBlock(BlockError::PreviousMismatch)
+            }
+            ChainFault::UnexpectedOpen | ChainFault::SubtypeMismatch { .. } => {
+                Error::InvalidBlock(BlockError::InvalidSubtype)
+            }
+            ChainFault::MissingSource => Error::InvalidBlock(BlockError::InvalidLink),
+            ChainFault::MissingSignature => {
+                Error::InvalidBlock(BlockError::MissingField("signature"))
+            }
+            ChainFault::InvalidSignature => Error::InvalidSignature,
+            ChainFault::MissingWork => Error::InvalidBlock(BlockError::MissingField("work")),
+            ChainFault::InsufficientWork => Error::InvalidBlock(BlockError::InsufficientWork),
+        }
+    }
+}
+
+/// Fault located at a block in the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainViolation {
+    /// Position of the block in the validated slice.
+    pub index: usize,
+    /// Block hash.
+    pub hash: BlockHash,
+    /// What is wrong with the block.
+    pub fault: ChainFault,
+}
+
+impl fmt::Display for ChainViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "block {} ({}): {}", self.index, self.hash, self.fault)
+    }
+}
+
+/// Outcome of validating an account chain.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ChainReport {
+    /// Hash of the last block validated.
+    pub head: Option<BlockHash>,
+    /// Balance after the last block validated.
+    pub balance: Option<Raw>,
+    /// Every fault found, in chain order.
+    pub violations: Vec<ChainViolation>,
+}
+
+impl ChainReport {
+    /// Check if no faults were found.
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Convert to a result, failing on the first fault.
+    pub fn into_result(self) -> Result<BlockHash> {
+        if let Some(violation) = self.violations.into_iter().next() {
+            return Err(violation.fault.into());
+        }
+        self.head
+            .ok_or(Error::InvalidBlock(BlockError::MissingField("block")))
+    }
+}
+
+/// Validator for a sequence of blocks from one account chain.
+///
+/// Blocks must be ordered oldest first; `account_history` returns newest
+/// first, so reverse it before validating. Without an anchor the first
+/// block's previous pointer and balance change cannot be checked unless it
+/// is an open block.
+#[derive(Debug, Clone)]
+pub struct ChainValidator {
+    threshold: WorkThreshold,
+    check_work: bool,
+    anchor: Option<(BlockHash, Raw)>,
+    epoch_signers: Vec<Account>,
+}
+
+impl Default for ChainValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChainValidator {
+    /// Create a validator using mainnet work thresholds.
+    pub fn new() -> Self {
+        ChainValidator {
+            threshold: WorkThreshold::MAINNET,
+            check_work: true,
+            anchor: None,
+            epoch_signers: Vec::new(),
+        }
+    }
+
+    /// Use the work thresholds of a network.
+    pub fn with_network(mut self, network: &Network) -> Self {
+        self.threshold = WorkThreshold::for_network(network);
+        self
+    }
+
+    /// Use custom work thresholds.
+    pub fn with_threshold(mut self, threshold: WorkThreshold) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Skip work validation.
+    pub fn without_work_check(mut self) -> Self {
+        self.check_work = false;
+        self
+    }
+
+    /// Start from a known block, e.g. a previously validated frontier.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - Hash of the block preceding the first block to validate
+    /// * `balance` - Account balance after that block
+    pub fn with_anchor(mut self, hash: BlockHash, balance: Raw) -> Self {
+        self.anchor = Some((hash, balance));
+        self
+    }
+
+    /// Accept epoch blocks signed by this signer.
+    ///
+    /// Epoch blocks are signed by the network's epoch key, not the account
+    /// owner. Call once per signer, e.g. for the epoch v1 and v2 keys; an
+    /// epoch block no configured signer verifies is reported as
+    /// [`ChainFault::InvalidSignature`].
+    pub fn with_epoch_signer(mut self, signer: Account) -> Self {
+        self.epoch_signers.push(signer);
+        self
+    }
+
+    /// Validate blocks, collecting every fault found.
+    pub fn validate(&self, blocks: &[StateBlock]) -> ChainReport {
+        let mut report = ChainReport::default();
+        let mut tip = self.anchor;
+        let account = blocks.first().map(|block| &block.account);
+
+        for (index, block) in blocks.iter().enumerate() {
+            let hash = BlockHasher::hash_state_block(block);
+            let mut fault = |fault| {
+                report
+                    .violations
+                    .push(ChainViolation { index, hash, fault });
+            };
+
+            if Some(&block.account) != account {
+                fault(ChainFault::AccountMismatch);
+            }
+
+            if block.is_open() {
+                if tip.is_some() {
+                    fault(ChainFault::UnexpectedOpen);
+                }
+            } else if let Some((expected, _)) = tip {
+                if block.previous != expected {
+                    fault(ChainFault::PreviousMismatch { expected });
+                }
+            }
+
+            let previous_balance = if block.is_open() {
+                Some(Raw::ZERO)
+            } else {
+                tip.map(|(_, balance)| balance)
+            };
+            let actual = previous_balance.map(|previous| match block.balance.cmp(&previous) {
+                core::cmp::Ordering::Less => Subtype::Send,
+                core::cmp::Ordering::Greater if block.is_open() => Subtype::Open,
+                core::cmp::Ordering::Greater => Subtype::Receive,
+                core::cmp::Ordering::Equal if block.is_open() => Subtype::Open,
+                core::cmp::Ordering::Equal => Subtype::Change,
+            });
+            // An epoch link marks an epoch block whatever subtype is declared,
+            // unless the balance moved
+            let subtype =
+                if is_epoch_link(&block.link) && matches!(actual, None | Some(Subtype::Change)) {
+                    Subtype::Epoch
+                } else {
+                    block
+                        .subtype
+                        .or(actual)
+                        .unwrap_or_else(|| block.infer_subtype(None))
+                };
+            if let (Some(declared), Some(actual)) = (block.subtype, actual) {
+                let consistent =
+                    declared == actual || (declared == Subtype::Epoch && actual == Subtype::Change);
+                if !consistent {
+                    fault(ChainFault::SubtypeMismatch { declared, actual });
+                }
+            }
+            if matches!(subtype, Subtype::Receive | Subtype::Open) && block.link.is_zero() {
+                fault(ChainFault::MissingSource);
+            }
+
+            match &block.signature {
+                None => fault(ChainFault::MissingSignature),
+                Some(signature) if subtype == Subtype::Epoch => {
+                    let signed = self.epoch_signers.iter().any(|signer| {
+                        BlockSigner::verify_hash(&hash, signer.public_key(), signature)
+                    });
+                    if !signed {
+                        fault(ChainFault::InvalidSignature);
+                    }
+                }
+                Some(_) => {
+                    if !BlockSigner::verify(block) {
+                        fault(ChainFault::InvalidSignature);
+                    }
+                }
+            }
+
+            if self.check_work {
+                match block.work {
+                    None => fault(ChainFault::MissingWork),
+                    Some(work) => {
+                        let threshold = self.threshold.for_subtype(subtype);
+                        if !WorkValidator::validate(work, &block.work_root(), threshold) {
+                            fault(ChainFault::InsufficientWork);
+                        }
+                    }
+                }
+            }
+
+            tip = Some((hash, block.balance));
+        }
+
+        report.head = tip.map(|(hash, _)| hash);
+        report.balance = tip.map(|(_, balance)| balance);
+        report
+    }
+}
+
+/// Check if `link` is an epoch link: `epoch v1 block` or `epoch v2 block`
+/// padded with zeros.
+fn is_epoch_link(link: &Link) -> bool {
+    let (text, padding) = link.as_bytes().split_at(14);
+    (text == b"epoch v1 block" || text == b"epoch v2 block") && padding.iter().all(|b| *b == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{create_open_block, create_receive_block, create_send_block};
+    use crate::keys::{KeyPair, Seed};
+
+    fn keypair(index: u32) -> KeyPair {
+        Seed::from_bytes([3u8; 32]).derive(index)
+    }
+
+    fn chain() -> Vec<StateBlock> {
+        let owner = keypair(0);
+        let rep = keypair(1).account();
+        let open = create_open_block(
+            &owner,
+            rep.clone(),
+            Raw::new(100),
+            &BlockHash::from_bytes([1u8; 32]),
+            None,
+        );
+        let send = create_send_block(
+            &owner,
+            BlockHasher::hash_state_block(&open),
+            rep.clone(),
+            Raw::new(100),
+            Raw::new(40),
+            &keypair(2).account(),
+            None,
+        );
+        let receive = create_receive_block(
+            &owner,
+            BlockHasher::hash_state_block(&send),
+            rep,
+            Raw::new(60),
+            Raw::new(5),
+            &BlockHash::from_bytes([2u8; 32]),
+            None,
+        );
+        vec![open, send, receive]
+    }
+
+    #[test]
+    fn test_valid_chain() {
+        let blocks = chain();
+        let report = ChainValidator::new().without_work_check().validate(&blocks);
+        assert!(report.is_valid(), "{:?}", report.violations);
+        assert_eq!(report.balance, Some(Raw::new(65)));
+        assert_eq!(
+            report.into_result().unwrap(),
+            BlockHasher::hash_state_block(&blocks[2])
+        );
+    }
+
+    #[test]
+    fn test_broken_link_and_subtype() {
+        let mut blocks = chain();
+        blocks[2].previous = BlockHash::from_bytes([9u8; 32]);
+        blocks[1].subtype = Some(Subtype::Receive);
+
+        let report = ChainValidator::new().without_work_check().validate(&blocks);
+        let faults: Vec<_> = report
+            .violations
+            .iter()
+            .map(|v| (v.index, &v.fault))
+            .collect();
+        assert!(faults.contains(&(
+            1,
+            &ChainFault::SubtypeMismatch {
+                declared: Subtype::Receive,
+                actual: Subtype::Send
+            }
+        )));
+        assert!(faults.contains(&(2, &ChainFault::InvalidSignature)));
+        assert!(faults
+            .iter()
+            .any(|(i, f)| *i == 2 && matches!(f, ChainFault::PreviousMismatch { .. })));
+    }
+
+    #[test]
+    fn test_epoch_signature_fails_closed() {
+        let blocks = chain();
+        let owner = keypair(0);
+        let epoch_key = keypair(3);
+        let mut link = [0u8; 32];
+        link[..14].copy_from_slice(b"epoch v2 block");
+        let mut epoch = StateBlock::new(
+            owner.account(),
+            BlockHasher::hash_state_block(&blocks[2]),
+            keypair(4).account(),
+            Raw::new(65),
+            Link::from_bytes(link),
+        );
+
+        // A representative change signed by the owner but linked as an epoch
+        epoch.signature = Some(BlockSigner::sign(&epoch, &owner));
+        let mut forged = blocks.clone();
+        forged.push(epoch.clone());
+        let report = ChainValidator::new().without_work_check().validate(&forged);
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].fault, ChainFault::InvalidSignature);
+
+        epoch.signature = Some(BlockSigner::sign(&epoch, &epoch_key));
+        let mut upgraded = blocks;
+        upgraded.push(epoch);
+        let validator = ChainValidator::new().without_work_check();
+        assert!(!validator.validate(&upgraded).is_valid());
+        let report = validator
+            .with_epoch_signer(keypair(5).account())
+            .with_epoch_signer(epoch_key.account())
+            .validate(&upgraded);
+        assert!(report.is_valid(), "{:?}", report.violations);
+    }
+
+    #[test]
+    fn test_anchor_and_work() {
+        let blocks = chain();
+        let anchor = BlockHasher::hash_state_block(&blocks[0]);
+
+        let report = ChainValidator::new()
+            .without_work_check()
+            .with_anchor(anchor, Raw::new(100))
+            .validate(&blocks[1..]);
+        assert!(report.is_valid());
+
+        let report = ChainValidator::new().validate(&blocks[1..]);
+        assert!(report
+            .violations
+            .iter()
+            .all(|v| v.fault == ChainFault::MissingWork));
+        assert!(matches!(
+            report.into_result(),
+            Err(Error::InvalidBlock(BlockError::MissingField("work")))
+        ));
+    }
+}
@@ -9,12 +9,18 @@
 //!     balance ||     // 16 bytes (big-endian u128)
 //!     link           // 32 bytes
 //! )
+//!
+//! Legacy blocks hash their fields directly, without a preamble:
+//! - send: previous || destination || balance
+//! - receive: previous || source
+//! - open: source || representative || account
+//! - change: previous || representative
 
 use blake2::digest::consts::U32;
 use blake2::{Blake2b, Digest};
 
 use crate::constants::STATE_BLOCK_PREAMBLE;
-use crate::types::{Account, BlockHash, Link, Raw, StateBlock};
+use crate::types::{Account, Block, BlockHash, Link, Raw, StateBlock};
 
 /// Block hasher for computing block hashes.
 pub struct BlockHasher;
@@ -39,6 +45,35 @@ impl BlockHasher {
         )
     }
 
+    /// Compute the hash of any block, legacy or state.
+    pub fn hash_block(block: &Block) -> BlockHash {
+        let mut hasher = Blake2b::<U32>::new();
+        match block {
+            Block::State(state) => return Self::hash_state_block(state),
+            Block::Send(send) => {
+                hasher.update(send.previous.as_bytes());
+                hasher.update(send.destination.public_key().as_bytes());
+                hasher.update(send.balance.to_be_bytes());
+            }
+            Block::Receive(receive) => {
+                hasher.update(receive.previous.as_bytes());
+                hasher.update(receive.source.as_bytes());
+            }
+            Block::Open(open) => {
+                hasher.update(open.source.as_bytes());
+                hasher.update(open.representative.public_key().as_bytes());
+                hasher.update(open.account.public_key().as_bytes());
+            }
+            Block::Change(change) => {
+                hasher.update(change.previous.as_bytes());
+                hasher.update(change.representative.public_key().as_bytes());
+            }
+        }
+
+        let hash: [u8; 32] = hasher.finalize().into();
+        BlockHash::from_bytes(hash)
+    }
+
     /// Compute the hash from individual parts.
     ///
     /// This is useful when you don't have a full StateBlock struct yet.
@@ -52,7 +87,7 @@ impl BlockHasher {
         let mut hasher = Blake2b::<U32>::new();
 
         // Preamble (identifies this as a state block)
-        hasher.update(&STATE_BLOCK_PREAMBLE);
+        hasher.update(STATE_BLOCK_PREAMBLE);
 
         // Account public key
         hasher.update(account.public_key().as_bytes());
@@ -64,7 +99,7 @@ impl BlockHasher {
         hasher.update(representative.public_key().as_bytes());
 
         // Balance (16 bytes, big-endian)
-        hasher.update(&balance.to_be_bytes());
+        hasher.update(balance.to_be_bytes());
 
         // Link
         hasher.update(link.as_bytes());
@@ -120,6 +155,28 @@ mod tests {
         assert!(!hash.is_zero());
     }
 
+    // Live network genesis open block
+    #[test]
+    fn test_hash_legacy_open_block() {
+        let genesis = Account::from_address_str_checked(
+            "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+        )
+        .unwrap();
+        let block = Block::Open(crate::types::OpenBlock {
+            block_type: "open".into(),
+            source: BlockHash::from_bytes(*genesis.public_key().as_bytes()),
+            representative: genesis.clone(),
+            account: genesis,
+            signature: crate::types::Signature::from_bytes([0u8; 64]),
+            work: crate::types::Work::ZERO,
+        });
+
+        assert_eq!(
+            BlockHasher::hash_block(&block).to_hex(),
+            "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+        );
+    }
+
     // Test on existing block
     #[test]
     fn test_hash_receive_block() {
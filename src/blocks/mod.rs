@@ -1,14 +1,24 @@
 //! Block operations for creating, hashing, and signing Nano blocks.
 
 mod builder;
+mod chain;
 mod hash;
+mod prepared;
 mod sign;
 mod state;
+mod typed;
+mod unsigned;
 
 pub use builder::{
     change_block_builder, open_block_builder, receive_block_builder, send_block_builder,
     BlockBuilder,
 };
+pub use chain::{ChainFault, ChainReport, ChainValidator, ChainViolation};
 pub use hash::BlockHasher;
-pub use sign::BlockSigner;
+pub use prepared::PartiallyPreparedBlock;
+pub use sign::{AsyncSigner, BlockSigner};
 pub use state::{create_change_block, create_open_block, create_receive_block, create_send_block};
+pub use typed::{
+    ChangeBlockBuilder, HasLink, NoLink, OpenBlockBuilder, ReceiveBlockBuilder, SendBlockBuilder,
+};
+pub use unsigned::UnsignedBlock;
@@ -0,0 +1,372 @@
+//! Partially prepared blocks for cold/hot wallet splits.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+#[cfg(feature = "serde")]
+use alloc::string::ToString;
+#[cfg(feature = "cbor")]
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::{BlockHasher, BlockSigner, UnsignedBlock};
+#[cfg(feature = "serde")]
+use crate::error::StorageError;
+use crate::error::{BlockError, Error, Result};
+use crate::keys::KeyPair;
+use crate::types::{BlockHash, Signature, StateBlock, Work};
+use crate::work::WorkValidator;
+
+/// A block moving between an online host and an offline signer.
+///
+/// Carries the block, the hash the signer is expected to sign, the work
+/// difficulty the block must meet and free-form metadata such as a memo.
+/// The flow is:
+///
+/// 1. Online: [`new`](Self::new), then [`attach_work`](Self::attach_work),
+///    and export with [`to_json`](Self::to_json).
+/// 2. Offline: import, review [`block`](Self::block) and
+///    [`metadata`](Self::metadata), [`sign`](Self::sign) and export again.
+/// 3. Online: import and [`finalize`](Self::finalize), or submit with
+///    [`RpcClient::process_prepared`](crate::rpc::RpcClient::process_prepared).
+///
+/// Every step checks the hash against the block fields, the work against
+/// the difficulty and the signature against the account, whenever they are
+/// present. Importing does the same, so a file edited in transit is
+/// rejected.
+///
+/// # Example
+///
+/// ```
+/// use xno_connect::blocks::{BlockBuilder, PartiallyPreparedBlock};
+/// use xno_connect::prelude::*;
+///
+/// # fn main() -> xno_connect::error::Result<()> {
+/// let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+/// let block = BlockBuilder::new()
+///     .account(keypair.account())
+///     .previous(BlockHash::from_bytes([3u8; 32]))
+///     .representative(keypair.account())
+///     .balance(Raw::new(1))
+///     .link_as_account(&keypair.account())
+///     .build_unsigned()?;
+///
+/// // Online: prepare and export. A low difficulty keeps the example fast.
+/// let prepared = PartiallyPreparedBlock::new(&block, 0).with_metadata("memo", "rent");
+/// let prepared = prepared.attach_work(Work::new(7))?;
+/// let json = prepared.to_json()?;
+///
+/// // Offline: sign.
+/// let signed = PartiallyPreparedBlock::from_json(&json)?.sign(&keypair)?;
+///
+/// // Online: verify and finalize.
+/// let block = PartiallyPreparedBlock::from_json(&signed.to_json()?)?.finalize()?;
+/// assert_eq!(block.work, Some(Work::new(7)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "PreparedBlockFields"))]
+pub struct PartiallyPreparedBlock {
+    version: u8,
+    block: StateBlock,
+    hash: BlockHash,
+    #[cfg_attr(feature = "serde", serde(with = "difficulty_hex"))]
+    difficulty: u64,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeMap::is_empty"))]
+    metadata: BTreeMap<String, String>,
+}
+
+/// Wire form of [`PartiallyPreparedBlock`], checked on conversion.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct PreparedBlockFields {
+    version: u8,
+    block: StateBlock,
+    hash: BlockHash,
+    #[serde(with = "difficulty_hex")]
+    difficulty: u64,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PreparedBlockFields> for PartiallyPreparedBlock {
+    type Error = Error;
+
+    fn try_from(fields: PreparedBlockFields) -> Result<Self> {
+        if fields.version != PartiallyPreparedBlock::VERSION {
+            return Err(Error::InvalidBlock(BlockError::UnsupportedVersion(
+                fields.version,
+            )));
+        }
+        let prepared = PartiallyPreparedBlock {
+            version: fields.version,
+            block: fields.block,
+            hash: fields.hash,
+            difficulty: fields.difficulty,
+            metadata: fields.metadata,
+        };
+        prepared.check()?;
+        Ok(prepared)
+    }
+}
+
+impl PartiallyPreparedBlock {
+    /// Format version written by this crate.
+    pub const VERSION: u8 = 1;
+
+    /// Prepare an unsigned block that needs work of at least `difficulty`,
+    /// e.g. from [`WorkThreshold::for_subtype`](crate::work::WorkThreshold::for_subtype).
+    pub fn new(block: &UnsignedBlock, difficulty: u64) -> Self {
+        PartiallyPreparedBlock {
+            version: Self::VERSION,
+            block: block.block().clone(),
+            hash: *block.hash(),
+            difficulty,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    /// Attach a metadata entry, e.g. a memo for the signer to review.
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// Get the block.
+    pub fn block(&self) -> &StateBlock {
+        &self.block
+    }
+
+    /// Get the hash to sign.
+    pub fn hash(&self) -> &BlockHash {
+        &self.hash
+    }
+
+    /// Get the required work difficulty.
+    pub fn difficulty(&self) -> u64 {
+        self.difficulty
+    }
+
+    /// Get the metadata.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Get the root to generate work for.
+    pub fn work_root(&self) -> BlockHash {
+        self.block.work_root()
+    }
+
+    /// Attach proof of work.
+    ///
+    /// Fails with [`BlockError::InsufficientWork`] if it is below the
+    /// difficulty.
+    pub fn attach_work(mut self, work: Work) -> Result<Self> {
+        self.block.work = Some(work);
+        self.check()?;
+        Ok(self)
+    }
+
+    /// Sign the block.
+    ///
+    /// Fails with [`Error::InvalidSignature`] if the keypair does not own
+    /// the block's account.
+    pub fn sign(self, keypair: &KeyPair) -> Result<Self> {
+        self.check()?;
+        let signature = BlockSigner::sign_hash(&self.hash, keypair);
+        self.apply_signature(signature)
+    }
+
+    /// Attach a signature produced elsewhere, e.g. by a hardware wallet.
+    ///
+    /// Fails with [`Error::InvalidSignature`] if it does not verify against
+    /// the block's account.
+    pub fn apply_signature(mut self, signature: Signature) -> Result<Self> {
+        self.block.signature = Some(signature);
+        self.check()?;
+        Ok(self)
+    }
+
+    /// Get the block for submission once it has valid work and signature.
+    pub fn finalize(&self) -> Result<StateBlock> {
+        self.check()?;
+        if self.block.work.is_none() {
+            return Err(Error::InvalidBlock(BlockError::MissingField("work")));
+        }
+        if self.block.signature.is_none() {
+            return Err(Error::InvalidBlock(BlockError::MissingField("signature")));
+        }
+        Ok(self.block.clone())
+    }
+
+    /// Check the hash, and the work and signature if present.
+    fn check(&self) -> Result<()> {
+        if BlockHasher::hash_state_block(&self.block) != self.hash {
+            return Err(Error::InvalidBlock(BlockError::HashMismatch));
+        }
+        if let Some(work) = self.block.work {
+            if !WorkValidator::validate(work, &self.work_root(), self.difficulty) {
+                return Err(Error::InvalidBlock(BlockError::InsufficientWork));
+            }
+        }
+        if let Some(signature) = &self.block.signature {
+            if !BlockSigner::verify_hash(&self.hash, self.block.account.public_key(), signature) {
+                return Err(Error::InvalidSignature);
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize to JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))
+    }
+
+    /// Deserialize from JSON produced by [`to_json`](Self::to_json).
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        let fields: PreparedBlockFields = serde_json::from_str(json)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))?;
+        PartiallyPreparedBlock::try_from(fields)
+    }
+
+    /// Serialize to CBOR, e.g. for QR codes.
+    #[cfg(feature = "cbor")]
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(self, &mut bytes)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))?;
+        Ok(bytes)
+    }
+
+    /// Deserialize from CBOR produced by [`to_cbor`](Self::to_cbor).
+    #[cfg(feature = "cbor")]
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        let fields: PreparedBlockFields = ciborium::from_reader(bytes)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))?;
+        PartiallyPreparedBlock::try_from(fields)
+    }
+}
+
+/// Difficulty as 16 hex digits, like the node's `difficulty` fields.
+#[cfg(feature = "serde")]
+mod difficulty_hex {
+    use alloc::format;
+    use alloc::string::String;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(difficulty: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:016x}", difficulty))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        u64::from_str_radix(&s, 16).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::BlockBuilder;
+    use crate::keys::Seed;
+    use crate::types::{Link, Raw};
+
+    const DIFFICULTY: u64 = 0xf000_0000_0000_0000;
+
+    fn prepared(keypair: &KeyPair) -> PartiallyPreparedBlock {
+        let unsigned = BlockBuilder::new()
+            .account(keypair.account())
+            .previous(BlockHash::from_bytes([3u8; 32]))
+            .representative(keypair.account())
+            .balance(Raw::new(5))
+            .link(Link::ZERO)
+            .build_unsigned()
+            .unwrap();
+        PartiallyPreparedBlock::new(&unsigned, DIFFICULTY)
+    }
+
+    fn work_for(prepared: &PartiallyPreparedBlock) -> Work {
+        (0..)
+            .map(Work::new)
+            .find(|work| WorkValidator::validate(*work, &prepared.work_root(), DIFFICULTY))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_work_and_signature_checked() {
+        let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+        let other = Seed::from_bytes([2u8; 32]).derive(0);
+        let prepared = prepared(&keypair);
+        let work = work_for(&prepared);
+        let bad_work = (0..)
+            .map(Work::new)
+            .find(|w| !WorkValidator::validate(*w, &prepared.work_root(), DIFFICULTY))
+            .unwrap();
+
+        assert_eq!(
+            prepared.clone().attach_work(bad_work).unwrap_err(),
+            Error::InvalidBlock(BlockError::InsufficientWork)
+        );
+        assert_eq!(
+            prepared.clone().sign(&other).unwrap_err(),
+            Error::InvalidSignature
+        );
+        assert_eq!(
+            prepared.finalize().unwrap_err(),
+            Error::InvalidBlock(BlockError::MissingField("work"))
+        );
+
+        let block = prepared
+            .attach_work(work)
+            .unwrap()
+            .sign(&keypair)
+            .unwrap()
+            .finalize()
+            .unwrap();
+        assert!(BlockSigner::verify(&block));
+        assert_eq!(block.work, Some(work));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip_and_tampering() {
+        let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+        let prepared = prepared(&keypair).with_metadata("memo", "invoice 7");
+        let prepared = prepared.clone().attach_work(work_for(&prepared)).unwrap();
+        let json = prepared.to_json().unwrap();
+        assert!(json.contains("\"difficulty\": \"f000000000000000\""));
+        assert_eq!(PartiallyPreparedBlock::from_json(&json).unwrap(), prepared);
+
+        assert_eq!(
+            PartiallyPreparedBlock::from_json(&json.replace("\"5\"", "\"6\"")).unwrap_err(),
+            Error::InvalidBlock(BlockError::HashMismatch)
+        );
+        assert_eq!(
+            PartiallyPreparedBlock::from_json(
+                &json.replace("f000000000000000", "ffffffffffffffff")
+            )
+            .unwrap_err(),
+            Error::InvalidBlock(BlockError::InsufficientWork)
+        );
+        assert_eq!(
+            PartiallyPreparedBlock::from_json(&json.replace("\"version\": 1", "\"version\": 2"))
+                .unwrap_err(),
+            Error::InvalidBlock(BlockError::UnsupportedVersion(2))
+        );
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_round_trip() {
+        let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+        let prepared = prepared(&keypair).sign(&keypair).unwrap();
+        let bytes = prepared.to_cbor().unwrap();
+        assert_eq!(PartiallyPreparedBlock::from_cbor(&bytes).unwrap(), prepared);
+    }
+}
@@ -1,8 +1,15 @@
 //! Block signing for Nano state blocks.
 
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use curve25519_dalek_ng::edwards::EdwardsPoint;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
 use crate::blocks::BlockHasher;
-use crate::keys::KeyPair;
-use crate::types::{BlockHash, PublicKey, Signature, StateBlock};
+use crate::error::Result;
+use crate::keys::{prepare_public_key, verify_prepared, KeyPair};
+use crate::types::{Account, Block, BlockHash, PublicKey, Signature, StateBlock};
 
 /// Block signer for signing and verifying blocks.
 pub struct BlockSigner;
@@ -34,12 +41,105 @@ impl BlockSigner {
         }
     }
 
+    /// Verify the signature of any block, legacy or state.
+    ///
+    /// Legacy send, receive and change blocks do not contain their account, so
+    /// the owning `account` must be supplied (e.g. `block_account` from
+    /// `block_info`). For open and state blocks it must match the block's own
+    /// account.
+    pub fn verify_block(block: &Block, account: &Account) -> bool {
+        if block.account().is_some_and(|own| own != account) {
+            return false;
+        }
+        match block.signature() {
+            Some(signature) => KeyPair::verify_with_public_key(
+                account.public_key(),
+                &BlockHasher::hash_block(block),
+                &signature,
+            ),
+            None => false,
+        }
+    }
+
+    /// Verify the signatures of many state blocks.
+    ///
+    /// Returns one result per block, in order, matching what
+    /// [`verify`](Self::verify) returns for each. Faster than verifying one
+    /// by one: public keys are decoded once per account, and the work is
+    /// spread across threads when the `rayon` dependency is enabled (e.g.
+    /// by the `work-cpu` feature).
+    pub fn verify_batch(blocks: &[StateBlock]) -> Vec<bool> {
+        #[cfg(feature = "rayon")]
+        {
+            blocks
+                .par_chunks(VERIFY_CHUNK)
+                .flat_map_iter(verify_chunk)
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            verify_chunk(blocks)
+        }
+    }
+
     /// Verify a signature against a block hash and public key.
     pub fn verify_hash(hash: &BlockHash, public_key: &PublicKey, signature: &Signature) -> bool {
         KeyPair::verify_with_public_key(public_key, hash, signature)
     }
 }
 
+/// Blocks verified per task by [`BlockSigner::verify_batch`].
+#[cfg(feature = "rayon")]
+const VERIFY_CHUNK: usize = 256;
+
+/// Verify a run of blocks, decoding each account's public key once.
+fn verify_chunk(blocks: &[StateBlock]) -> Vec<bool> {
+    let mut keys: BTreeMap<[u8; 32], Option<EdwardsPoint>> = BTreeMap::new();
+    blocks
+        .iter()
+        .map(|block| {
+            let Some(signature) = &block.signature else {
+                return false;
+            };
+            let public_key = block.account.public_key();
+            let point = keys
+                .entry(*public_key.as_bytes())
+                .or_insert_with(|| prepare_public_key(public_key));
+            match point {
+                Some(point) => {
+                    let hash = BlockHasher::hash_state_block(block);
+                    verify_prepared(point, public_key, hash.as_bytes(), signature)
+                }
+                None => false,
+            }
+        })
+        .collect()
+}
+
+/// Signer that may sign asynchronously, e.g. an HSM or remote service.
+///
+/// Implemented for [`KeyPair`] so local keys can be used wherever an
+/// `AsyncSigner` is accepted. Implementations report transport failures
+/// as [`SignerError`](crate::error::SignerError).
+#[allow(async_fn_in_trait)]
+pub trait AsyncSigner {
+    /// Account whose key produces the signatures.
+    fn account(&self) -> Account;
+
+    /// Sign a block hash.
+    async fn sign_hash(&self, hash: &BlockHash) -> Result<Signature>;
+}
+
+impl AsyncSigner for KeyPair {
+    fn account(&self) -> Account {
+        KeyPair::account(self)
+    }
+
+    async fn sign_hash(&self, hash: &BlockHash) -> Result<Signature> {
+        Ok(self.sign(hash))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,6 +229,36 @@ mod tests {
         assert!(!BlockSigner::verify(&block));
     }
 
+    #[test]
+    fn test_verify_batch() {
+        let keypair = test_keypair();
+        let other = Seed::from_bytes([3u8; 32]).derive(0);
+        let block = |keypair: &KeyPair, nano: u128| {
+            let mut block = StateBlock::new(
+                keypair.account(),
+                BlockHash::ZERO,
+                keypair.account(),
+                Raw::from_nano(nano).unwrap(),
+                Link::ZERO,
+            );
+            block.signature = Some(BlockSigner::sign(&block, keypair));
+            block
+        };
+
+        let mut blocks: Vec<StateBlock> = (1..=600)
+            .map(|i| block(if i % 2 == 0 { &keypair } else { &other }, i))
+            .collect();
+        blocks[10].balance = Raw::new(1);
+        blocks[300].signature = None;
+        blocks[599].signature = Some(Signature::from_bytes([0xff; 64]));
+
+        let results = BlockSigner::verify_batch(&blocks);
+        let expected: Vec<bool> = blocks.iter().map(BlockSigner::verify).collect();
+        assert_eq!(results, expected);
+        assert_eq!(results.iter().filter(|ok| !**ok).count(), 3);
+        assert!(BlockSigner::verify_batch(&[]).is_empty());
+    }
+
     #[test]
     fn test_sign_hash_directly() {
         let keypair = test_keypair();
@@ -164,4 +294,48 @@ mod tests {
         // Ed25519 signatures should be deterministic
         assert_eq!(sig1, sig2);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_verify_legacy_genesis_open_block() {
+        let json = r#"{
+            "type": "open",
+            "source": "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA",
+            "representative": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+            "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+            "work": "62f05417dd3fb691",
+            "signature": "9F0C933C8ADE004D808EA1985FA746A7E95BA2A38F867640F53EC8F180BDFE9E2C1268DEAD7C2664F356E37ABA362BC58E46DBA03E523A7B5A19E4B6EB12BB02"
+        }"#;
+        let block: Block = serde_json::from_str(json).unwrap();
+        let genesis = block.account().unwrap().clone();
+
+        assert!(BlockSigner::verify_block(&block, &genesis));
+        assert!(!BlockSigner::verify_block(
+            &block,
+            &test_keypair().account()
+        ));
+    }
+
+    #[test]
+    fn test_verify_legacy_change_block() {
+        let keypair = test_keypair();
+        let mut change = crate::types::ChangeBlock {
+            block_type: "change".into(),
+            previous: BlockHash::from_bytes([1u8; 32]),
+            representative: keypair.account(),
+            signature: Signature::from_bytes([0u8; 64]),
+            work: crate::types::Work::ZERO,
+        };
+        change.signature = keypair.sign(&BlockHasher::hash_block(&Block::Change(change.clone())));
+        let block = Block::Change(change);
+
+        assert!(BlockSigner::verify_block(&block, &keypair.account()));
+        assert!(!BlockSigner::verify_block(
+            &block,
+            &Seed::from_hex("0000000000000000000000000000000000000000000000000000000000000000")
+                .unwrap()
+                .derive(1)
+                .account()
+        ));
+    }
 }
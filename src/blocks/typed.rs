@@ -0,0 +1,565 @@
+//! Subtype-specific block builders.
+//!
+//! These wrap [`BlockBuilder`] and only expose the fields that are valid for a
+//! given subtype. The link field is tracked at the type level, so `build` and
+//! `sign` are only available once it has been set. The remaining fields are
+//! checked at runtime, so `sign` and `build` fail if any of them is missing:
+//!
+//! ```compile_fail
+//! use xno_connect::blocks::ReceiveBlockBuilder;
+//! use xno_connect::types::{Account, PublicKey};
+//!
+//! // Receive blocks link to a source hash, not an account.
+//! let destination = Account::from_public_key(&PublicKey::ZERO);
+//! ReceiveBlockBuilder::new().link_as_account(&destination);
+//! ```
+//!
+//! ```compile_fail
+//! use xno_connect::blocks::SendBlockBuilder;
+//!
+//! // A send block can't be built before its destination is set.
+//! SendBlockBuilder::new().build();
+//! ```
+
+use core::marker::PhantomData;
+
+use crate::blocks::BlockBuilder;
+use crate::error::Result;
+use crate::keys::KeyPair;
+use crate::types::{Account, BlockHash, Link, Raw, Signature, StateBlock, Subtype, Work};
+
+/// Type-state marker: the link field has not been set.
+#[derive(Debug, Clone, Copy)]
+pub struct NoLink;
+
+/// Type-state marker: the link field has been set.
+#[derive(Debug, Clone, Copy)]
+pub struct HasLink;
+
+/// Builder for send blocks.
+///
+/// Requires a destination before the block can be signed or built.
+///
+/// # Example
+///
+/// ```
+/// use xno_connect::prelude::*;
+/// use xno_connect::blocks::SendBlockBuilder;
+///
+/// # fn main() -> xno_connect::error::Result<()> {
+/// let keypair = Seed::from_hex("0000000000000000000000000000000000000000000000000000000000000000")?.derive(0);
+/// let destination = Account::from_public_key(&PublicKey::ZERO);
+///
+/// let block = SendBlockBuilder::new()
+///     .account(keypair.account())
+///     .previous(BlockHash::ZERO)
+///     .representative(keypair.account())
+///     .balance(Raw::from_nano(1)?)
+///     .destination(&destination)
+///     .sign(&keypair)?
+///     .build()?;
+/// assert_eq!(block.subtype, Some(Subtype::Send));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SendBlockBuilder<L = NoLink> {
+    inner: BlockBuilder,
+    _link: PhantomData<L>,
+}
+
+/// Builder for receive blocks.
+///
+/// Requires a source block hash before the block can be signed or built.
+#[derive(Debug, Clone)]
+pub struct ReceiveBlockBuilder<L = NoLink> {
+    inner: BlockBuilder,
+    _link: PhantomData<L>,
+}
+
+/// Builder for open blocks.
+///
+/// The previous hash is always zero. Requires a source block hash before the
+/// block can be signed or built.
+#[derive(Debug, Clone)]
+pub struct OpenBlockBuilder<L = NoLink> {
+    inner: BlockBuilder,
+    _link: PhantomData<L>,
+}
+
+/// Builder for change blocks.
+///
+/// The link field is always zero and can't be set.
+#[derive(Debug, Clone)]
+pub struct ChangeBlockBuilder {
+    inner: BlockBuilder,
+}
+
+impl SendBlockBuilder<NoLink> {
+    /// Create a new send block builder.
+    pub fn new() -> Self {
+        SendBlockBuilder {
+            inner: BlockBuilder::new().subtype(Subtype::Send),
+            _link: PhantomData,
+        }
+    }
+
+    /// Set the destination account.
+    pub fn destination(self, destination: &Account) -> SendBlockBuilder<HasLink> {
+        SendBlockBuilder {
+            inner: self.inner.link_as_account(destination),
+            _link: PhantomData,
+        }
+    }
+}
+
+impl Default for SendBlockBuilder<NoLink> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> SendBlockBuilder<L> {
+    /// Set the account that owns this block.
+    pub fn account(mut self, account: Account) -> Self {
+        self.inner = self.inner.account(account);
+        self
+    }
+
+    /// Set the previous block hash.
+    pub fn previous(mut self, hash: BlockHash) -> Self {
+        self.inner = self.inner.previous(hash);
+        self
+    }
+
+    /// Set the representative account.
+    pub fn representative(mut self, account: Account) -> Self {
+        self.inner = self.inner.representative(account);
+        self
+    }
+
+    /// Set the balance after this block.
+    pub fn balance(mut self, balance: Raw) -> Self {
+        self.inner = self.inner.balance(balance);
+        self
+    }
+
+    /// Set the proof of work.
+    pub fn work(mut self, work: Work) -> Self {
+        self.inner = self.inner.work(work);
+        self
+    }
+}
+
+impl SendBlockBuilder<HasLink> {
+    /// Sign the block with the given keypair.
+    ///
+    /// Fails if account, previous, representative or balance is missing.
+    pub fn sign(mut self, keypair: &KeyPair) -> Result<Self> {
+        self.inner.hash()?;
+        self.inner = self.inner.sign(keypair);
+        Ok(self)
+    }
+
+    /// Set a pre-computed signature.
+    pub fn signature(mut self, signature: Signature) -> Self {
+        self.inner = self.inner.signature(signature);
+        self
+    }
+
+    /// Get the hash of the block being built.
+    pub fn hash(&self) -> Result<BlockHash> {
+        self.inner.hash()
+    }
+
+    /// Build the send block.
+    pub fn build(self) -> Result<StateBlock> {
+        self.inner.build()
+    }
+}
+
+impl ReceiveBlockBuilder<NoLink> {
+    /// Create a new receive block builder.
+    pub fn new() -> Self {
+        ReceiveBlockBuilder {
+            inner: BlockBuilder::new().subtype(Subtype::Receive),
+            _link: PhantomData,
+        }
+    }
+
+    /// Set the hash of the send block being received.
+    pub fn source(self, source_hash: &BlockHash) -> ReceiveBlockBuilder<HasLink> {
+        ReceiveBlockBuilder {
+            inner: self.inner.link_as_block(source_hash),
+            _link: PhantomData,
+        }
+    }
+}
+
+impl Default for ReceiveBlockBuilder<NoLink> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> ReceiveBlockBuilder<L> {
+    /// Set the account that owns this block.
+    pub fn account(mut self, account: Account) -> Self {
+        self.inner = self.inner.account(account);
+        self
+    }
+
+    /// Set the previous block hash.
+    pub fn previous(mut self, hash: BlockHash) -> Self {
+        self.inner = self.inner.previous(hash);
+        self
+    }
+
+    /// Set the representative account.
+    pub fn representative(mut self, account: Account) -> Self {
+        self.inner = self.inner.representative(account);
+        self
+    }
+
+    /// Set the balance after this block.
+    pub fn balance(mut self, balance: Raw) -> Self {
+        self.inner = self.inner.balance(balance);
+        self
+    }
+
+    /// Set the proof of work.
+    pub fn work(mut self, work: Work) -> Self {
+        self.inner = self.inner.work(work);
+        self
+    }
+}
+
+impl ReceiveBlockBuilder<HasLink> {
+    /// Sign the block with the given keypair.
+    ///
+    /// Fails if account, previous, representative or balance is missing.
+    pub fn sign(mut self, keypair: &KeyPair) -> Result<Self> {
+        self.inner.hash()?;
+        self.inner = self.inner.sign(keypair);
+        Ok(self)
+    }
+
+    /// Set a pre-computed signature.
+    pub fn signature(mut self, signature: Signature) -> Self {
+        self.inner = self.inner.signature(signature);
+        self
+    }
+
+    /// Get the hash of the block being built.
+    pub fn hash(&self) -> Result<BlockHash> {
+        self.inner.hash()
+    }
+
+    /// Build the receive block.
+    pub fn build(self) -> Result<StateBlock> {
+        self.inner.build()
+    }
+}
+
+impl OpenBlockBuilder<NoLink> {
+    /// Create a new open block builder.
+    pub fn new() -> Self {
+        OpenBlockBuilder {
+            inner: BlockBuilder::new()
+                .previous(BlockHash::ZERO)
+                .subtype(Subtype::Open),
+            _link: PhantomData,
+        }
+    }
+
+    /// Set the hash of the send block being received.
+    pub fn source(self, source_hash: &BlockHash) -> OpenBlockBuilder<HasLink> {
+        OpenBlockBuilder {
+            inner: self.inner.link_as_block(source_hash),
+            _link: PhantomData,
+        }
+    }
+}
+
+impl Default for OpenBlockBuilder<NoLink> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L> OpenBlockBuilder<L> {
+    /// Set the account that owns this block.
+    pub fn account(mut self, account: Account) -> Self {
+        self.inner = self.inner.account(account);
+        self
+    }
+
+    /// Set the representative account.
+    pub fn representative(mut self, account: Account) -> Self {
+        self.inner = self.inner.representative(account);
+        self
+    }
+
+    /// Set the balance after this block (the amount received).
+    pub fn balance(mut self, balance: Raw) -> Self {
+        self.inner = self.inner.balance(balance);
+        self
+    }
+
+    /// Set the proof of work.
+    pub fn work(mut self, work: Work) -> Self {
+        self.inner = self.inner.work(work);
+        self
+    }
+}
+
+impl OpenBlockBuilder<HasLink> {
+    /// Sign the block with the given keypair.
+    ///
+    /// Fails if account, previous, representative or balance is missing.
+    pub fn sign(mut self, keypair: &KeyPair) -> Result<Self> {
+        self.inner.hash()?;
+        self.inner = self.inner.sign(keypair);
+        Ok(self)
+    }
+
+    /// Set a pre-computed signature.
+    pub fn signature(mut self, signature: Signature) -> Self {
+        self.inner = self.inner.signature(signature);
+        self
+    }
+
+    /// Get the hash of the block being built.
+    pub fn hash(&self) -> Result<BlockHash> {
+        self.inner.hash()
+    }
+
+    /// Build the open block.
+    pub fn build(self) -> Result<StateBlock> {
+        self.inner.build()
+    }
+}
+
+impl ChangeBlockBuilder {
+    /// Create a new change block builder.
+    pub fn new() -> Self {
+        ChangeBlockBuilder {
+            inner: BlockBuilder::new()
+                .link(Link::ZERO)
+                .subtype(Subtype::Change),
+        }
+    }
+
+    /// Set the account that owns this block.
+    pub fn account(mut self, account: Account) -> Self {
+        self.inner = self.inner.account(account);
+        self
+    }
+
+    /// Set the previous block hash.
+    pub fn previous(mut self, hash: BlockHash) -> Self {
+        self.inner = self.inner.previous(hash);
+        self
+    }
+
+    /// Set the new representative account.
+    pub fn representative(mut self, account: Account) -> Self {
+        self.inner = self.inner.representative(account);
+        self
+    }
+
+    /// Set the current (unchanged) balance.
+    pub fn balance(mut self, balance: Raw) -> Self {
+        self.inner = self.inner.balance(balance);
+        self
+    }
+
+    /// Set the proof of work.
+    pub fn work(mut self, work: Work) -> Self {
+        self.inner = self.inner.work(work);
+        self
+    }
+
+    /// Sign the block with the given keypair.
+    ///
+    /// Fails if account, previous, representative or balance is missing.
+    pub fn sign(mut self, keypair: &KeyPair) -> Result<Self> {
+        self.inner.hash()?;
+        self.inner = self.inner.sign(keypair);
+        Ok(self)
+    }
+
+    /// Set a pre-computed signature.
+    pub fn signature(mut self, signature: Signature) -> Self {
+        self.inner = self.inner.signature(signature);
+        self
+    }
+
+    /// Get the hash of the block being built.
+    pub fn hash(&self) -> Result<BlockHash> {
+        self.inner.hash()
+    }
+
+    /// Build the change block.
+    pub fn build(self) -> Result<StateBlock> {
+        self.inner.build()
+    }
+}
+
+impl Default for ChangeBlockBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::BlockSigner;
+    use crate::error::{BlockError, Error};
+    use crate::keys::Seed;
+    use crate::types::PublicKey;
+
+    fn test_keypair() -> KeyPair {
+        let seed =
+            Seed::from_hex("0000000000000000000000000000000000000000000000000000000000000000")
+                .unwrap();
+        seed.derive(0)
+    }
+
+    fn test_hash() -> BlockHash {
+        BlockHash::from_hex("991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_send_block_builder() {
+        let keypair = test_keypair();
+        let destination = Account::from_public_key(&PublicKey::ZERO);
+
+        let block = SendBlockBuilder::new()
+            .account(keypair.account())
+            .previous(test_hash())
+            .representative(keypair.account())
+            .balance(Raw::new(500))
+            .destination(&destination)
+            .sign(&keypair)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(block.subtype, Some(Subtype::Send));
+        assert_eq!(block.link.as_public_key(), *destination.public_key());
+        assert!(BlockSigner::verify(&block));
+    }
+
+    #[test]
+    fn test_receive_block_builder() {
+        let keypair = test_keypair();
+
+        let block = ReceiveBlockBuilder::new()
+            .source(&test_hash())
+            .account(keypair.account())
+            .previous(test_hash())
+            .representative(keypair.account())
+            .balance(Raw::new(500))
+            .sign(&keypair)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(block.subtype, Some(Subtype::Receive));
+        assert_eq!(block.link.as_block_hash(), test_hash());
+        assert!(BlockSigner::verify(&block));
+    }
+
+    #[test]
+    fn test_open_block_builder() {
+        let keypair = test_keypair();
+
+        let block = OpenBlockBuilder::new()
+            .account(keypair.account())
+            .representative(keypair.account())
+            .balance(Raw::new(500))
+            .source(&test_hash())
+            .sign(&keypair)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(block.subtype, Some(Subtype::Open));
+        assert!(block.previous.is_zero());
+        assert!(BlockSigner::verify(&block));
+    }
+
+    #[test]
+    fn test_change_block_builder() {
+        let keypair = test_keypair();
+        let new_rep = Account::from_public_key(&PublicKey::ZERO);
+
+        let block = ChangeBlockBuilder::new()
+            .account(keypair.account())
+            .previous(test_hash())
+            .representative(new_rep.clone())
+            .balance(Raw::new(500))
+            .sign(&keypair)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(block.subtype, Some(Subtype::Change));
+        assert!(block.link.is_zero());
+        assert_eq!(block.representative, new_rep);
+        assert!(BlockSigner::verify(&block));
+    }
+
+    #[test]
+    fn test_sign_requires_all_fields() {
+        let keypair = test_keypair();
+        let destination = Account::from_public_key(&PublicKey::ZERO);
+
+        let result = SendBlockBuilder::new()
+            .account(keypair.account())
+            .destination(&destination)
+            .sign(&keypair);
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidBlock(BlockError::MissingField("previous")))
+        ));
+    }
+
+    #[test]
+    fn test_typed_builder_matches_generic_builder() {
+        let keypair = test_keypair();
+        let destination = Account::from_public_key(&PublicKey::ZERO);
+
+        let typed = SendBlockBuilder::new()
+            .account(keypair.account())
+            .previous(test_hash())
+            .representative(keypair.account())
+            .balance(Raw::new(500))
+            .destination(&destination);
+        let generic = crate::blocks::send_block_builder(
+            keypair.account(),
+            test_hash(),
+            keypair.account(),
+            Raw::new(500),
+            &destination,
+        );
+
+        assert_eq!(typed.hash().unwrap(), generic.hash().unwrap());
+    }
+
+    #[test]
+    fn test_typed_builder_missing_field() {
+        let result = SendBlockBuilder::new()
+            .destination(&Account::from_public_key(&PublicKey::ZERO))
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidBlock(BlockError::MissingField(_)))
+        ));
+    }
+}
@@ -0,0 +1,205 @@
+//! Unsigned block templates for offline signing.
+
+#[cfg(feature = "serde")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::{BlockHasher, BlockSigner};
+#[cfg(feature = "serde")]
+use crate::error::{BlockError, StorageError};
+use crate::error::{Error, Result};
+use crate::keys::KeyPair;
+use crate::types::{BlockHash, Signature, StateBlock, Work};
+
+/// Block waiting to be signed, with its hash.
+///
+/// Produced by [`BlockBuilder::build_unsigned`](crate::blocks::BlockBuilder::build_unsigned)
+/// on an online machine. Serialize it to JSON, sign it on an air-gapped
+/// machine with [`sign`](Self::sign), and bring the signed block back.
+/// Meanwhile the online side can generate work for [`work_root`](Self::work_root).
+///
+/// The hash is checked against the block fields when deserializing, so
+/// a template edited in transit is rejected.
+///
+/// # Example
+///
+/// ```
+/// use xno_connect::blocks::{BlockBuilder, BlockSigner, UnsignedBlock};
+/// use xno_connect::prelude::*;
+///
+/// # fn main() -> xno_connect::error::Result<()> {
+/// let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+///
+/// // Online: build the template and export it.
+/// let template = BlockBuilder::new()
+///     .account(keypair.account())
+///     .previous(BlockHash::ZERO)
+///     .representative(keypair.account())
+///     .balance(Raw::new(1))
+///     .link_as_block(&BlockHash::from_bytes([2u8; 32]))
+///     .build_unsigned()?;
+/// let json = template.to_json()?;
+///
+/// // Offline: sign it.
+/// let signed = UnsignedBlock::from_json(&json)?.sign(&keypair)?;
+/// assert!(BlockSigner::verify(&signed));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UnsignedBlockFields"))]
+pub struct UnsignedBlock {
+    block: StateBlock,
+    hash: BlockHash,
+}
+
+/// Wire form of [`UnsignedBlock`], checked on conversion.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct UnsignedBlockFields {
+    block: StateBlock,
+    hash: BlockHash,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UnsignedBlockFields> for UnsignedBlock {
+    type Error = Error;
+
+    fn try_from(fields: UnsignedBlockFields) -> Result<Self> {
+        let unsigned = UnsignedBlock::new(fields.block);
+        if unsigned.hash != fields.hash {
+            return Err(Error::InvalidBlock(BlockError::HashMismatch));
+        }
+        Ok(unsigned)
+    }
+}
+
+impl UnsignedBlock {
+    /// Create a template from a block, dropping any signature.
+    pub fn new(mut block: StateBlock) -> Self {
+        block.signature = None;
+        let hash = BlockHasher::hash_state_block(&block);
+        UnsignedBlock { block, hash }
+    }
+
+    /// Get the block.
+    pub fn block(&self) -> &StateBlock {
+        &self.block
+    }
+
+    /// Get the hash to sign.
+    pub fn hash(&self) -> &BlockHash {
+        &self.hash
+    }
+
+    /// Get the root to generate work for.
+    pub fn work_root(&self) -> BlockHash {
+        self.block.work_root()
+    }
+
+    /// Attach proof of work. Work is not part of the hash.
+    pub fn with_work(mut self, work: Work) -> Self {
+        self.block.work = Some(work);
+        self
+    }
+
+    /// Sign the block.
+    ///
+    /// Fails with [`Error::InvalidSignature`] if the keypair does not own
+    /// the block's account.
+    pub fn sign(&self, keypair: &KeyPair) -> Result<StateBlock> {
+        self.apply_signature(BlockSigner::sign_hash(&self.hash, keypair))
+    }
+
+    /// Attach a signature produced elsewhere.
+    ///
+    /// Fails with [`Error::InvalidSignature`] if it does not verify against
+    /// the block's account.
+    pub fn apply_signature(&self, signature: Signature) -> Result<StateBlock> {
+        if !BlockSigner::verify_hash(&self.hash, self.block.account.public_key(), &signature) {
+            return Err(Error::InvalidSignature);
+        }
+        Ok(self.block.clone().with_signature(signature))
+    }
+
+    /// Serialize to JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))
+    }
+
+    /// Deserialize from JSON produced by [`to_json`](Self::to_json).
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        let fields: UnsignedBlockFields = serde_json::from_str(json)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))?;
+        UnsignedBlock::try_from(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::BlockBuilder;
+    use crate::keys::Seed;
+    use crate::types::{Link, Raw};
+
+    fn template(keypair: &KeyPair) -> UnsignedBlock {
+        BlockBuilder::new()
+            .account(keypair.account())
+            .previous(BlockHash::from_bytes([3u8; 32]))
+            .representative(keypair.account())
+            .balance(Raw::new(5))
+            .link(Link::ZERO)
+            .build_unsigned()
+            .unwrap()
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip_and_sign() {
+        let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+        let unsigned = template(&keypair);
+        let imported = UnsignedBlock::from_json(&unsigned.to_json().unwrap()).unwrap();
+        assert_eq!(imported, unsigned);
+
+        let signed = imported.sign(&keypair).unwrap();
+        assert_eq!(BlockHasher::hash_state_block(&signed), *unsigned.hash());
+        assert!(BlockSigner::verify(&signed));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_tampered_template_rejected() {
+        let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+        let json = template(&keypair)
+            .to_json()
+            .unwrap()
+            .replace("\"5\"", "\"6\"");
+        assert_eq!(
+            UnsignedBlock::from_json(&json).unwrap_err(),
+            Error::InvalidBlock(BlockError::HashMismatch)
+        );
+    }
+
+    #[test]
+    fn test_wrong_key_rejected() {
+        let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+        let other = Seed::from_bytes([2u8; 32]).derive(0);
+        assert_eq!(
+            template(&keypair).sign(&other).unwrap_err(),
+            Error::InvalidSignature
+        );
+    }
+
+    #[test]
+    fn test_work_root() {
+        let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+        let unsigned = template(&keypair).with_work(Work::new(42));
+        assert_eq!(unsigned.work_root(), BlockHash::from_bytes([3u8; 32]));
+        assert_eq!(unsigned.sign(&keypair).unwrap().work, Some(Work::new(42)));
+    }
+}
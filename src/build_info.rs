@@ -0,0 +1,183 @@
+//! Description of the linked build.
+
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::network::Network;
+
+/// Cargo features this crate was compiled with, in declaration order.
+const FEATURES: &[(&str, bool)] = &[
+    ("std", cfg!(feature = "std")),
+    ("serde", cfg!(feature = "serde")),
+    ("rpc", cfg!(feature = "rpc")),
+    ("websocket", cfg!(feature = "websocket")),
+    ("websocket-tls", cfg!(feature = "websocket-tls")),
+    ("work-cpu", cfg!(feature = "work-cpu")),
+    ("vanity", cfg!(feature = "vanity")),
+    ("work-server", cfg!(feature = "work-server")),
+    (
+        "experimental-multisig",
+        cfg!(feature = "experimental-multisig"),
+    ),
+    ("encryption", cfg!(feature = "encryption")),
+    ("coingecko", cfg!(feature = "coingecko")),
+    ("cli", cfg!(feature = "cli")),
+    ("tracing", cfg!(feature = "tracing")),
+    ("metrics", cfg!(feature = "metrics")),
+    ("cbor", cfg!(feature = "cbor")),
+    ("store-sqlite", cfg!(feature = "store-sqlite")),
+    ("alias", cfg!(feature = "alias")),
+    ("testkit", cfg!(feature = "testkit")),
+    ("wasm-rpc", cfg!(feature = "wasm-rpc")),
+    ("wasm-websocket", cfg!(feature = "wasm-websocket")),
+    ("bindings", cfg!(feature = "bindings")),
+];
+
+/// Version requirements of dependencies that affect behavior, as declared
+/// in this crate's manifest. Keep in sync with `Cargo.toml`.
+const DEPENDENCIES: &[(&str, &str)] = &[
+    ("ed25519-dalek", "2.2.0"),
+    ("blake2", "0.10"),
+    ("serde_json", "1"),
+    ("reqwest", "0.12"),
+    ("tokio", "1"),
+    ("tokio-tungstenite-wasm", "0.8"),
+    ("rayon", "1"),
+];
+
+/// Capabilities and defaults of the linked build.
+///
+/// Include it in bug reports, or check it at runtime before offering
+/// features such as local work generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct BuildInfo {
+    /// Crate version.
+    pub version: &'static str,
+    /// Enabled Cargo features.
+    pub features: Vec<&'static str>,
+    /// Declared version requirements of critical dependencies.
+    pub dependencies: Vec<(&'static str, &'static str)>,
+    /// Target architecture, e.g. `x86_64` or `wasm32`.
+    pub target_arch: &'static str,
+    /// Target operating system, empty for bare targets.
+    pub target_os: &'static str,
+    /// Default network name.
+    pub network: &'static str,
+    /// Default node RPC port.
+    pub rpc_port: u16,
+    /// Default node WebSocket port.
+    pub websocket_port: u16,
+    /// Default send/change work threshold.
+    pub work_threshold_send: u64,
+    /// Default receive/open work threshold.
+    pub work_threshold_receive: u64,
+}
+
+impl BuildInfo {
+    /// Check if a Cargo feature is enabled.
+    pub fn has_feature(&self, feature: &str) -> bool {
+        self.features.contains(&feature)
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "xno-connect {}", self.version)?;
+        writeln!(f, "features: {}", self.features.join(", "))?;
+        writeln!(f, "target: {} {}", self.target_arch, self.target_os)?;
+        write!(
+            f,
+            "network: {} (rpc {}, websocket {})",
+            self.network, self.rpc_port, self.websocket_port
+        )
+    }
+}
+
+/// Describe the linked build of this crate.
+///
+/// # Example
+///
+/// ```
+/// let info = xno_connect::build_info();
+/// if info.has_feature("work-cpu") {
+///     println!("local work generation available");
+/// }
+/// println!("{}", info);
+/// ```
+pub fn build_info() -> BuildInfo {
+    let network = Network::LIVE;
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES
+            .iter()
+            .filter(|(_, enabled)| *enabled)
+            .map(|(name, _)| *name)
+            .collect(),
+        dependencies: DEPENDENCIES.to_vec(),
+        target_arch: target_arch(),
+        target_os: target_os(),
+        network: network.name,
+        rpc_port: network.rpc_port,
+        websocket_port: network.websocket_port,
+        work_threshold_send: network.work_threshold.send,
+        work_threshold_receive: network.work_threshold.receive,
+    }
+}
+
+fn target_arch() -> &'static str {
+    if cfg!(target_arch = "x86_64") {
+        "x86_64"
+    } else if cfg!(target_arch = "aarch64") {
+        "aarch64"
+    } else if cfg!(target_arch = "wasm32") {
+        "wasm32"
+    } else if cfg!(target_arch = "x86") {
+        "x86"
+    } else if cfg!(target_arch = "arm") {
+        "arm"
+    } else if cfg!(target_arch = "riscv64") {
+        "riscv64"
+    } else {
+        "unknown"
+    }
+}
+
+fn target_os() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "linux"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "android") {
+        "android"
+    } else if cfg!(target_os = "ios") {
+        "ios"
+    } else {
+        ""
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info() {
+        let info = build_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info.has_feature("rpc"), cfg!(feature = "rpc"));
+        assert_eq!(info.has_feature("work-cpu"), cfg!(feature = "work-cpu"));
+        assert_eq!(info.rpc_port, 7076);
+        assert!(info.to_string().starts_with("xno-connect "));
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_value(&info).unwrap();
+            assert_eq!(json["network"], "live");
+        }
+    }
+}
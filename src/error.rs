@@ -1,8 +1,14 @@
 //! Error types for the XNO-connect library.
 
-use alloc::string::String;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
 use core::fmt;
 
+use crate::types::{BlockHash, Raw};
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
 /// Result type alias for XNO-connect operations.
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -27,8 +33,41 @@ pub enum Error {
     InvalidWork,
     /// Invalid amount value or overflow.
     InvalidAmount(AmountError),
+    /// An amount to send exceeds the account balance.
+    InsufficientBalance {
+        /// Balance of the account.
+        balance: Raw,
+        /// Amount requested.
+        amount: Raw,
+    },
     /// Hex decoding error.
     HexDecode(HexError),
+    /// Invalid `nano:` URI.
+    InvalidUri(UriError),
+    /// Receipt verification failed.
+    InvalidReceipt(ReceiptError),
+    /// Account archive verification failed.
+    InvalidArchive(ArchiveError),
+    /// Invalid wallet backup.
+    InvalidBackup(BackupError),
+    /// External signer failed.
+    Signer(SignerError),
+    /// Local storage error.
+    Storage(StorageError),
+    /// An idempotent send id was already used with different parameters.
+    SendIdConflict(String),
+    /// A payment can't be refunded.
+    InvalidRefund(RefundError),
+    /// Price oracle error.
+    Fiat(FiatError),
+    /// Alias resolution error.
+    Alias(AliasError),
+    /// Aggregate signing failed.
+    #[cfg(feature = "experimental-multisig")]
+    Multisig(MultisigError),
+    /// Encrypting or decrypting key material failed.
+    #[cfg(feature = "encryption")]
+    Encryption(EncryptionError),
     /// RPC communication error.
     #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
     Rpc(RpcError),
@@ -51,7 +90,28 @@ impl fmt::Display for Error {
             Error::InvalidSignature => write!(f, "invalid signature"),
             Error::InvalidWork => write!(f, "invalid work: insufficient difficulty"),
             Error::InvalidAmount(e) => write!(f, "invalid amount: {}", e),
+            Error::InsufficientBalance { balance, amount } => write!(
+                f,
+                "insufficient balance: {} raw requested, {} raw available",
+                amount, balance
+            ),
             Error::HexDecode(e) => write!(f, "hex decode error: {}", e),
+            Error::InvalidUri(e) => write!(f, "invalid URI: {}", e),
+            Error::InvalidReceipt(e) => write!(f, "invalid receipt: {}", e),
+            Error::InvalidArchive(e) => write!(f, "invalid archive: {}", e),
+            Error::InvalidBackup(e) => write!(f, "invalid wallet backup: {}", e),
+            Error::Signer(e) => write!(f, "signer error: {}", e),
+            Error::Storage(e) => write!(f, "storage error: {}", e),
+            Error::SendIdConflict(id) => {
+                write!(f, "send id {} was already used for a different send", id)
+            }
+            Error::InvalidRefund(e) => write!(f, "invalid refund: {}", e),
+            Error::Fiat(e) => write!(f, "price oracle error: {}", e),
+            Error::Alias(e) => write!(f, "alias error: {}", e),
+            #[cfg(feature = "experimental-multisig")]
+            Error::Multisig(e) => write!(f, "multisig error: {}", e),
+            #[cfg(feature = "encryption")]
+            Error::Encryption(e) => write!(f, "encryption error: {}", e),
             #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
             Error::Rpc(e) => write!(f, "RPC error: {}", e),
             #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
@@ -69,6 +129,19 @@ impl std::error::Error for Error {
             Error::InvalidBlock(e) => Some(e),
             Error::InvalidAmount(e) => Some(e),
             Error::HexDecode(e) => Some(e),
+            Error::InvalidUri(e) => Some(e),
+            Error::InvalidReceipt(e) => Some(e),
+            Error::InvalidArchive(e) => Some(e),
+            Error::InvalidBackup(e) => Some(e),
+            Error::Signer(e) => Some(e),
+            Error::Storage(e) => Some(e),
+            Error::InvalidRefund(e) => Some(e),
+            Error::Fiat(e) => Some(e),
+            Error::Alias(e) => Some(e),
+            #[cfg(feature = "experimental-multisig")]
+            Error::Multisig(e) => Some(e),
+            #[cfg(feature = "encryption")]
+            Error::Encryption(e) => Some(e),
             #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
             Error::Rpc(e) => Some(e),
             #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
@@ -79,6 +152,229 @@ impl std::error::Error for Error {
     }
 }
 
+/// Machine-readable error document in the style of RFC 7807.
+///
+/// Produced by [`Error::to_problem`] for HTTP services that embed the crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Problem {
+    /// URI identifying the error type.
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub problem_type: String,
+    /// Short summary of the error type.
+    pub title: String,
+    /// Suggested HTTP status code.
+    pub status: u16,
+    /// Human-readable explanation of this occurrence.
+    pub detail: String,
+    /// Whether retrying the same request may succeed.
+    pub retryable: bool,
+    /// Additional fields describing the error.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "BTreeMap::is_empty"))]
+    pub context: BTreeMap<String, String>,
+}
+
+impl Error {
+    /// Classify a node error.
+    ///
+    /// Returns `None` unless this is an [`RpcError::NodeError`].
+    #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+    pub fn node_error_kind(&self) -> Option<NodeErrorKind> {
+        match self {
+            Error::Rpc(e) => e.node_error_kind(),
+            _ => None,
+        }
+    }
+
+    /// Stable identifier for the error variant, e.g. `invalid_account`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::InvalidSeed => "invalid_seed",
+            Error::InvalidPrivateKey => "invalid_private_key",
+            Error::InvalidPublicKey => "invalid_public_key",
+            Error::InvalidAccount(_) => "invalid_account",
+            Error::InvalidBlockHash => "invalid_block_hash",
+            Error::InvalidBlock(_) => "invalid_block",
+            Error::InvalidSignature => "invalid_signature",
+            Error::InvalidWork => "invalid_work",
+            Error::InvalidAmount(_) => "invalid_amount",
+            Error::InsufficientBalance { .. } => "insufficient_balance",
+            Error::HexDecode(_) => "hex_decode",
+            Error::InvalidUri(_) => "invalid_uri",
+            Error::InvalidReceipt(_) => "invalid_receipt",
+            Error::InvalidArchive(_) => "invalid_archive",
+            Error::InvalidBackup(_) => "invalid_backup",
+            Error::Signer(_) => "signer",
+            Error::Storage(_) => "storage",
+            Error::SendIdConflict(_) => "send_id_conflict",
+            Error::InvalidRefund(_) => "invalid_refund",
+            Error::Fiat(_) => "fiat",
+            Error::Alias(_) => "alias",
+            #[cfg(feature = "experimental-multisig")]
+            Error::Multisig(_) => "multisig",
+            #[cfg(feature = "encryption")]
+            Error::Encryption(_) => "encryption",
+            #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+            Error::Rpc(_) => "rpc",
+            #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+            Error::WebSocket(_) => "websocket",
+            Error::WorkGeneration(_) => "work_generation",
+        }
+    }
+
+    /// Check if retrying the failed operation may succeed.
+    ///
+    /// True for transient network, timeout and I/O failures; input
+    /// validation errors are never retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Storage(StorageError::Io(_)) => true,
+            Error::Signer(SignerError::Unavailable(_)) => true,
+            Error::Fiat(FiatError::Unavailable(_)) => true,
+            Error::Alias(AliasError::Unavailable(_)) => true,
+            #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+            Error::Rpc(e) => {
+                matches!(e, RpcError::ConnectionFailed(_) | RpcError::Timeout)
+                    || matches!(e, RpcError::HttpStatus(code) if *code == 429 || *code >= 500)
+            }
+            #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+            Error::WebSocket(e) => matches!(
+                e,
+                WebSocketError::ConnectionFailed(_)
+                    | WebSocketError::ConnectionClosed
+                    | WebSocketError::Timeout
+            ),
+            Error::WorkGeneration(e) => matches!(
+                e,
+                WorkError::Timeout | WorkError::NoSource | WorkError::ServerError(_)
+            ),
+            _ => false,
+        }
+    }
+
+    /// Convert to a [`Problem`] document.
+    pub fn to_problem(&self) -> Problem {
+        let mut context = BTreeMap::new();
+        let (title, status) = match self {
+            Error::Storage(_) => ("Storage failure", 500),
+            Error::InsufficientBalance { balance, amount } => {
+                context.insert("balance".into(), balance.to_string());
+                context.insert("amount".into(), amount.to_string());
+                ("Insufficient balance", 422)
+            }
+            Error::SendIdConflict(id) => {
+                context.insert("send_id".into(), id.clone());
+                ("Send id conflict", 409)
+            }
+            Error::InvalidRefund(e) => {
+                let status = match e {
+                    RefundError::NotPayment => 400,
+                    RefundError::NotReceived => 409,
+                    RefundError::AlreadyRefunded(hash) => {
+                        context.insert("refund_hash".into(), hash.to_hex());
+                        409
+                    }
+                };
+                ("Invalid refund", status)
+            }
+            Error::Fiat(e) => {
+                let status = match e {
+                    FiatError::UnsupportedCurrency(_) => 400,
+                    FiatError::Unavailable(_) => 503,
+                    FiatError::InvalidResponse(_) => 502,
+                };
+                ("Price oracle failure", status)
+            }
+            Error::Alias(e) => {
+                let status = match e {
+                    AliasError::InvalidName(_) | AliasError::Unresolved(_) => 400,
+                    AliasError::NotFound(_) => 404,
+                    AliasError::Unavailable(_) => 503,
+                    AliasError::InvalidResponse(_) => 502,
+                };
+                ("Alias resolution failure", status)
+            }
+            Error::Signer(e) => {
+                let status = match e {
+                    SignerError::Unavailable(_) => 503,
+                    SignerError::Rejected(_) => 403,
+                };
+                ("Signer failure", status)
+            }
+            #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+            Error::Rpc(e) => {
+                match e {
+                    RpcError::NodeError(msg) => {
+                        context.insert("node_error".into(), msg.clone());
+                    }
+                    RpcError::HttpStatus(code) => {
+                        context.insert("upstream_status".into(), code.to_string());
+                    }
+                    RpcError::SchemaMismatch { action, path, .. } => {
+                        context.insert("action".into(), action.clone());
+                        context.insert("path".into(), path.clone());
+                    }
+                    _ => {}
+                }
+                let status = match e {
+                    RpcError::Timeout => 504,
+                    RpcError::InvalidConfig(_) => 500,
+                    _ => 502,
+                };
+                ("Node RPC failure", status)
+            }
+            #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+            Error::WebSocket(e) => {
+                let status = if *e == WebSocketError::Timeout {
+                    504
+                } else {
+                    502
+                };
+                ("Node WebSocket failure", status)
+            }
+            Error::WorkGeneration(e) => {
+                let status = match e {
+                    WorkError::Timeout => 504,
+                    WorkError::NoSource | WorkError::ServerError(_) => 503,
+                    _ => 500,
+                };
+                ("Work generation failure", status)
+            }
+            Error::InvalidBlock(e) => {
+                match e {
+                    BlockError::MissingField(field) => {
+                        context.insert("field".into(), String::from(*field));
+                    }
+                    BlockError::InvalidLength(len) => {
+                        context.insert("length".into(), len.to_string());
+                    }
+                    BlockError::LegacyEpoch(version) => {
+                        context.insert("epoch_version".into(), version.to_string());
+                    }
+                    _ => {}
+                }
+                ("Invalid block", 400)
+            }
+            _ => ("Invalid input", 400),
+        };
+
+        Problem {
+            problem_type: format!("urn:xno-connect:error:{}", self.code()),
+            title: title.into(),
+            status,
+            detail: self.to_string(),
+            retryable: self.is_retryable(),
+            context,
+        }
+    }
+
+    /// Serialize as an RFC 7807-style problem JSON document.
+    #[cfg(feature = "serde")]
+    pub fn to_problem_json(&self) -> String {
+        serde_json::to_string(&self.to_problem()).unwrap_or_default()
+    }
+}
+
 /// Account-specific error details.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AccountError {
@@ -90,6 +386,12 @@ pub enum AccountError {
     InvalidEncoding,
     /// Checksum mismatch.
     ChecksumMismatch,
+    /// Address contains non-ASCII characters.
+    NonAscii,
+    /// Vanity pattern is malformed or cannot match any address.
+    InvalidPattern,
+    /// The random number generator failed during a vanity search.
+    RandomUnavailable,
 }
 
 impl fmt::Display for AccountError {
@@ -99,6 +401,9 @@ impl fmt::Display for AccountError {
             AccountError::InvalidLength => write!(f, "invalid length"),
             AccountError::InvalidEncoding => write!(f, "invalid base32 encoding"),
             AccountError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            AccountError::NonAscii => write!(f, "non-ASCII characters"),
+            AccountError::InvalidPattern => write!(f, "invalid vanity pattern"),
+            AccountError::RandomUnavailable => write!(f, "random number generator failed"),
         }
     }
 }
@@ -117,6 +422,19 @@ pub enum BlockError {
     InvalidLink,
     /// Previous block hash mismatch.
     PreviousMismatch,
+    /// Serialized block has the wrong number of bytes.
+    InvalidLength(usize),
+    /// Account has not been upgraded to epoch v2 and the caller did not
+    /// acknowledge building for it.
+    LegacyEpoch(u8),
+    /// Work does not meet the network threshold.
+    InsufficientWork,
+    /// Block hash does not match the block fields.
+    HashMismatch,
+    /// Block JSON is malformed or not a state block.
+    InvalidJson(String),
+    /// Serialized format version is not supported.
+    UnsupportedVersion(u8),
 }
 
 impl fmt::Display for BlockError {
@@ -126,6 +444,18 @@ impl fmt::Display for BlockError {
             BlockError::InvalidSubtype => write!(f, "invalid block subtype"),
             BlockError::InvalidLink => write!(f, "invalid link field"),
             BlockError::PreviousMismatch => write!(f, "previous block hash mismatch"),
+            BlockError::InvalidLength(len) => write!(f, "invalid serialized length: {}", len),
+            BlockError::LegacyEpoch(version) => write!(
+                f,
+                "account is on epoch v{} and must be acknowledged explicitly",
+                version
+            ),
+            BlockError::InsufficientWork => write!(f, "work does not meet the network threshold"),
+            BlockError::HashMismatch => write!(f, "block hash does not match its fields"),
+            BlockError::InvalidJson(msg) => write!(f, "invalid block JSON: {}", msg),
+            BlockError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version: {}", version)
+            }
         }
     }
 }
@@ -142,6 +472,10 @@ pub enum AmountError {
     InvalidFormat,
     /// Negative value not allowed.
     Negative,
+    /// More fractional digits than raw can represent.
+    PrecisionLoss,
+    /// Every unique amount offset is taken by an open invoice.
+    TagSpaceExhausted,
 }
 
 impl fmt::Display for AmountError {
@@ -150,6 +484,8 @@ impl fmt::Display for AmountError {
             AmountError::Overflow => write!(f, "amount overflow"),
             AmountError::InvalidFormat => write!(f, "invalid format"),
             AmountError::Negative => write!(f, "negative values not allowed"),
+            AmountError::PrecisionLoss => write!(f, "more than 30 fractional digits"),
+            AmountError::TagSpaceExhausted => write!(f, "no unique amount offset left"),
         }
     }
 }
@@ -178,6 +514,329 @@ impl fmt::Display for HexError {
 #[cfg(feature = "std")]
 impl std::error::Error for HexError {}
 
+/// URI parsing error details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UriError {
+    /// Missing or unsupported scheme (must be "nano:").
+    InvalidScheme,
+    /// Malformed query parameter.
+    InvalidParameter,
+    /// Invalid percent-encoding.
+    InvalidEncoding,
+}
+
+impl fmt::Display for UriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UriError::InvalidScheme => write!(f, "invalid scheme (expected 'nano:')"),
+            UriError::InvalidParameter => write!(f, "invalid query parameter"),
+            UriError::InvalidEncoding => write!(f, "invalid percent-encoding"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UriError {}
+
+/// Local storage error details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    /// Reading or writing the backing file failed.
+    Io(String),
+    /// Stored data could not be (de)serialized.
+    Serialization(String),
+    /// A database query failed.
+    Database(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::Io(msg) => write!(f, "I/O failed: {}", msg),
+            StorageError::Serialization(msg) => write!(f, "serialization failed: {}", msg),
+            StorageError::Database(msg) => write!(f, "database query failed: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StorageError {}
+
+/// Receipt verification error details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceiptError {
+    /// Stored hash does not match the block contents.
+    HashMismatch,
+    /// Block signature is invalid.
+    InvalidBlockSignature,
+    /// A vote signature is invalid.
+    InvalidVoteSignature,
+    /// A vote does not cover the receipt's block.
+    UnrelatedVote,
+    /// Votes do not reach the quorum weight.
+    InsufficientWeight,
+    /// Receipt could not be (de)serialized.
+    Serialization(String),
+}
+
+impl fmt::Display for ReceiptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReceiptError::HashMismatch => write!(f, "hash does not match block"),
+            ReceiptError::InvalidBlockSignature => write!(f, "invalid block signature"),
+            ReceiptError::InvalidVoteSignature => write!(f, "invalid vote signature"),
+            ReceiptError::UnrelatedVote => write!(f, "vote does not cover block"),
+            ReceiptError::InsufficientWeight => write!(f, "insufficient vote weight"),
+            ReceiptError::Serialization(msg) => write!(f, "serialization failed: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReceiptError {}
+
+/// Reasons a payment can't be refunded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefundError {
+    /// Block is not a send to the refunding account.
+    NotPayment,
+    /// Payment has not been received yet.
+    NotReceived,
+    /// A send of the same amount to the payer follows the receive.
+    AlreadyRefunded(BlockHash),
+}
+
+impl fmt::Display for RefundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RefundError::NotPayment => write!(f, "block is not a payment to this account"),
+            RefundError::NotReceived => write!(f, "payment has not been received"),
+            RefundError::AlreadyRefunded(hash) => write!(f, "already refunded by {}", hash),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RefundError {}
+
+/// Account archive error details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveError {
+    /// Unknown archive format version.
+    UnsupportedFormat(u32),
+    /// Archive contains no blocks.
+    Empty,
+    /// Manifest does not match the archive contents.
+    ManifestMismatch(&'static str),
+    /// Stored hash does not match the block at this index.
+    HashMismatch(usize),
+    /// Account chain is broken at this index.
+    InvalidChain(usize),
+    /// Receipt is invalid or not for a block in the archive.
+    InvalidReceipt(usize),
+    /// Serialization failed.
+    Serialization(String),
+}
+
+impl fmt::Display for ArchiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchiveError::UnsupportedFormat(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            ArchiveError::Empty => write!(f, "no blocks"),
+            ArchiveError::ManifestMismatch(field) => write!(f, "manifest {} mismatch", field),
+            ArchiveError::HashMismatch(index) => write!(f, "hash mismatch at block {}", index),
+            ArchiveError::InvalidChain(index) => write!(f, "chain broken at block {}", index),
+            ArchiveError::InvalidReceipt(index) => write!(f, "invalid receipt {}", index),
+            ArchiveError::Serialization(msg) => write!(f, "serialization failed: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ArchiveError {}
+
+/// Wallet backup error details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupError {
+    /// Input is not in any supported backup format.
+    UnknownFormat,
+    /// A required field is missing.
+    MissingField(&'static str),
+    /// A field or line could not be parsed.
+    Malformed(String),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::UnknownFormat => write!(f, "unknown format"),
+            BackupError::MissingField(field) => write!(f, "missing field: {}", field),
+            BackupError::Malformed(msg) => write!(f, "malformed: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BackupError {}
+
+/// External signer error details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerError {
+    /// Signer could not be reached or timed out.
+    Unavailable(String),
+    /// Signer refused to sign.
+    Rejected(String),
+}
+
+impl fmt::Display for SignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerError::Unavailable(msg) => write!(f, "unavailable: {}", msg),
+            SignerError::Rejected(msg) => write!(f, "rejected: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SignerError {}
+
+/// Price oracle error details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FiatError {
+    /// The oracle has no price in this currency.
+    UnsupportedCurrency(String),
+    /// The oracle could not be reached and no usable cached price exists.
+    Unavailable(String),
+    /// The oracle returned a response that could not be parsed.
+    InvalidResponse(String),
+}
+
+impl fmt::Display for FiatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FiatError::UnsupportedCurrency(currency) => {
+                write!(f, "unsupported currency: {}", currency)
+            }
+            FiatError::Unavailable(msg) => write!(f, "unavailable: {}", msg),
+            FiatError::InvalidResponse(msg) => write!(f, "invalid response: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FiatError {}
+
+/// Alias resolution error details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AliasError {
+    /// The name is not a valid alias.
+    InvalidName(String),
+    /// No account is registered under the name.
+    NotFound(String),
+    /// An alias was passed where a resolved account is required.
+    Unresolved(String),
+    /// The naming service could not be reached.
+    Unavailable(String),
+    /// The naming service returned a response that could not be parsed.
+    InvalidResponse(String),
+}
+
+impl fmt::Display for AliasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AliasError::InvalidName(name) => write!(f, "invalid alias: {}", name),
+            AliasError::NotFound(name) => write!(f, "alias not found: @{}", name),
+            AliasError::Unresolved(name) => write!(f, "alias not resolved: @{}", name),
+            AliasError::Unavailable(msg) => write!(f, "unavailable: {}", msg),
+            AliasError::InvalidResponse(msg) => write!(f, "invalid response: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AliasError {}
+
+/// Aggregate signing error details.
+#[cfg(feature = "experimental-multisig")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultisigError {
+    /// No public keys were given.
+    NoParticipants,
+    /// The same public key was given twice.
+    DuplicateKey,
+    /// The key is not part of the aggregate.
+    NotParticipant,
+    /// Expected one nonce or partial signature per participant.
+    CountMismatch {
+        /// Number of participants.
+        expected: usize,
+        /// Number received.
+        actual: usize,
+    },
+    /// A nonce is not a valid curve point or is missing.
+    InvalidNonce,
+    /// A revealed nonce does not match its commitment.
+    CommitmentMismatch,
+    /// A participant's partial signature does not verify.
+    InvalidPartialSignature,
+    /// The system random number generator failed.
+    RandomUnavailable,
+}
+
+#[cfg(feature = "experimental-multisig")]
+impl fmt::Display for MultisigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultisigError::NoParticipants => write!(f, "no participants"),
+            MultisigError::DuplicateKey => write!(f, "duplicate public key"),
+            MultisigError::NotParticipant => write!(f, "key is not a participant"),
+            MultisigError::CountMismatch { expected, actual } => {
+                write!(f, "expected {} entries, got {}", expected, actual)
+            }
+            MultisigError::InvalidNonce => write!(f, "invalid or missing nonce"),
+            MultisigError::CommitmentMismatch => write!(f, "nonce does not match commitment"),
+            MultisigError::InvalidPartialSignature => write!(f, "invalid partial signature"),
+            MultisigError::RandomUnavailable => write!(f, "random number generator failed"),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "experimental-multisig"))]
+impl std::error::Error for MultisigError {}
+
+/// Key encryption error details.
+#[cfg(feature = "encryption")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncryptionError {
+    /// Wrong password, or the data was modified.
+    DecryptionFailed,
+    /// Data is not an encrypted seed of a known version.
+    InvalidFormat,
+    /// Key derivation parameters were rejected.
+    InvalidParams(String),
+    /// The system random number generator failed.
+    RandomUnavailable,
+}
+
+#[cfg(feature = "encryption")]
+impl fmt::Display for EncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncryptionError::DecryptionFailed => {
+                write!(f, "decryption failed: wrong password or corrupted data")
+            }
+            EncryptionError::InvalidFormat => write!(f, "invalid format"),
+            EncryptionError::InvalidParams(msg) => write!(f, "invalid parameters: {}", msg),
+            EncryptionError::RandomUnavailable => write!(f, "random number generator failed"),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "encryption"))]
+impl std::error::Error for EncryptionError {}
+
 impl From<hex::FromHexError> for Error {
     fn from(e: hex::FromHexError) -> Self {
         match e {
@@ -201,10 +860,25 @@ pub enum RpcError {
     Timeout,
     /// Invalid response format.
     InvalidResponse(String),
+    /// A response is valid JSON but doesn't have the expected shape, as
+    /// when a node or provider adds, drops or retypes a field.
+    SchemaMismatch {
+        /// Action of the request.
+        action: String,
+        /// Path of the field that failed, like `blocks.0.amount`.
+        path: String,
+        /// Why the field failed.
+        message: String,
+        /// The response, truncated. Left out of the `Display` output, since
+        /// it may hold data that shouldn't reach logs.
+        response: String,
+    },
     /// Node returned an error.
     NodeError(String),
     /// HTTP status error.
     HttpStatus(u16),
+    /// Client configuration is invalid.
+    InvalidConfig(String),
 }
 
 #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
@@ -214,8 +888,15 @@ impl fmt::Display for RpcError {
             RpcError::ConnectionFailed(msg) => write!(f, "connection failed: {}", msg),
             RpcError::Timeout => write!(f, "request timeout"),
             RpcError::InvalidResponse(msg) => write!(f, "invalid response: {}", msg),
+            RpcError::SchemaMismatch {
+                action,
+                path,
+                message,
+                ..
+            } => write!(f, "invalid {} response at {}: {}", action, path, message),
             RpcError::NodeError(msg) => write!(f, "node error: {}", msg),
             RpcError::HttpStatus(code) => write!(f, "HTTP status: {}", code),
+            RpcError::InvalidConfig(msg) => write!(f, "invalid configuration: {}", msg),
         }
     }
 }
@@ -223,6 +904,117 @@ impl fmt::Display for RpcError {
 #[cfg(all(any(feature = "rpc", feature = "wasm-rpc"), feature = "std"))]
 impl std::error::Error for RpcError {}
 
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+impl RpcError {
+    /// Classify a [`NodeError`](RpcError::NodeError) message.
+    ///
+    /// Returns `None` for other variants.
+    pub fn node_error_kind(&self) -> Option<NodeErrorKind> {
+        match self {
+            RpcError::NodeError(msg) => Some(NodeErrorKind::from_message(msg)),
+            _ => None,
+        }
+    }
+}
+
+/// Known error messages returned by the node.
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeErrorKind {
+    /// Account has no blocks.
+    AccountNotFound,
+    /// Block is not in the ledger.
+    BlockNotFound,
+    /// Another block already uses the same previous block.
+    Fork,
+    /// Block was already processed.
+    OldBlock,
+    /// Previous block is not in the ledger.
+    GapPrevious,
+    /// Source block is not in the ledger.
+    GapSource,
+    /// Epoch open block is waiting for a receivable.
+    GapEpochOpenPending,
+    /// Signature does not match the account.
+    BadSignature,
+    /// Work is below the threshold.
+    InsufficientWork,
+    /// Balance does not match the amount in the link.
+    BalanceMismatch,
+    /// Send amount exceeds the balance.
+    NegativeSpend,
+    /// Source block is not receivable by this account.
+    Unreceivable,
+    /// Representative changed in a block that may not change it.
+    RepresentativeMismatch,
+    /// Block follows a block type it may not follow.
+    BlockPosition,
+    /// Attempt to open the burn account.
+    OpenedBurnAccount,
+    /// Block is malformed.
+    InvalidBlock,
+    /// Account address is malformed.
+    BadAccount,
+    /// Action requires RPC control, which is disabled.
+    ControlDisabled,
+    /// Action is not known to the node.
+    UnknownCommand,
+    /// Any other message.
+    Unknown(String),
+}
+
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+impl NodeErrorKind {
+    /// Parse a node error message.
+    pub fn from_message(message: &str) -> Self {
+        const PREFIXES: &[(&str, NodeErrorKind)] = &[
+            ("account not found", NodeErrorKind::AccountNotFound),
+            ("block not found", NodeErrorKind::BlockNotFound),
+            ("fork", NodeErrorKind::Fork),
+            ("old block", NodeErrorKind::OldBlock),
+            ("gap previous", NodeErrorKind::GapPrevious),
+            ("gap source", NodeErrorKind::GapSource),
+            ("gap epoch open", NodeErrorKind::GapEpochOpenPending),
+            ("bad signature", NodeErrorKind::BadSignature),
+            ("insufficient work", NodeErrorKind::InsufficientWork),
+            (
+                "block work is less than threshold",
+                NodeErrorKind::InsufficientWork,
+            ),
+            ("balance mismatch", NodeErrorKind::BalanceMismatch),
+            ("negative spend", NodeErrorKind::NegativeSpend),
+            ("unreceivable", NodeErrorKind::Unreceivable),
+            (
+                "representative mismatch",
+                NodeErrorKind::RepresentativeMismatch,
+            ),
+            ("block position", NodeErrorKind::BlockPosition),
+            ("opened burn account", NodeErrorKind::OpenedBurnAccount),
+            ("block is invalid", NodeErrorKind::InvalidBlock),
+            ("invalid block", NodeErrorKind::InvalidBlock),
+            ("bad account number", NodeErrorKind::BadAccount),
+            ("rpc control is disabled", NodeErrorKind::ControlDisabled),
+            ("unknown command", NodeErrorKind::UnknownCommand),
+        ];
+
+        let lower = message.trim().to_ascii_lowercase();
+        PREFIXES
+            .iter()
+            .find(|(prefix, _)| lower.starts_with(prefix))
+            .map(|(_, kind)| kind.clone())
+            .unwrap_or_else(|| NodeErrorKind::Unknown(message.into()))
+    }
+
+    /// Check if the block was built on an outdated frontier.
+    ///
+    /// Rebuilding it on the account's current frontier may succeed.
+    /// [`OldBlock`](NodeErrorKind::OldBlock) is not stale: that block is
+    /// already in the ledger, and rebuilding it would publish it twice.
+    pub fn is_stale_frontier(&self) -> bool {
+        matches!(self, NodeErrorKind::Fork | NodeErrorKind::GapPrevious)
+    }
+}
+
 /// WebSocket-specific error details.
 #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -235,6 +1027,8 @@ pub enum WebSocketError {
     InvalidMessage(String),
     /// Subscription failed.
     SubscriptionFailed(String),
+    /// Timed out waiting for a message.
+    Timeout,
 }
 
 #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
@@ -245,6 +1039,7 @@ impl fmt::Display for WebSocketError {
             WebSocketError::ConnectionClosed => write!(f, "connection closed"),
             WebSocketError::InvalidMessage(msg) => write!(f, "invalid message: {}", msg),
             WebSocketError::SubscriptionFailed(msg) => write!(f, "subscription failed: {}", msg),
+            WebSocketError::Timeout => write!(f, "timed out waiting for message"),
         }
     }
 }
@@ -264,6 +1059,10 @@ pub enum WorkError {
     MaxIterations,
     /// External work server error.
     ServerError(String),
+    /// Work source did not respond in time.
+    Timeout,
+    /// No configured work source produced work.
+    NoSource,
 }
 
 impl fmt::Display for WorkError {
@@ -272,6 +1071,8 @@ impl fmt::Display for WorkError {
             WorkError::Cancelled => write!(f, "work generation cancelled"),
             WorkError::MaxIterations => write!(f, "max iterations reached"),
             WorkError::ServerError(msg) => write!(f, "server error: {}", msg),
+            WorkError::Timeout => write!(f, "work source timed out"),
+            WorkError::NoSource => write!(f, "no work source produced work"),
         }
     }
 }
@@ -341,4 +1142,98 @@ mod tests {
             "server error: timeout"
         );
     }
+
+    #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+    #[test]
+    fn test_node_error_kind() {
+        assert_eq!(NodeErrorKind::from_message("Fork"), NodeErrorKind::Fork);
+        assert_eq!(
+            NodeErrorKind::from_message("Gap previous block"),
+            NodeErrorKind::GapPrevious
+        );
+        assert_eq!(
+            NodeErrorKind::from_message("Block work is less than threshold"),
+            NodeErrorKind::InsufficientWork
+        );
+        assert_eq!(
+            NodeErrorKind::from_message("Something new"),
+            NodeErrorKind::Unknown("Something new".into())
+        );
+        assert!(NodeErrorKind::Fork.is_stale_frontier());
+        assert!(!NodeErrorKind::OldBlock.is_stale_frontier());
+        assert!(!NodeErrorKind::GapSource.is_stale_frontier());
+
+        let error = Error::Rpc(RpcError::NodeError("Account not found".into()));
+        assert_eq!(
+            error.node_error_kind(),
+            Some(NodeErrorKind::AccountNotFound)
+        );
+        assert_eq!(Error::Rpc(RpcError::Timeout).node_error_kind(), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_problem_json() {
+        let error = Error::InvalidBlock(BlockError::MissingField("balance"));
+        let json: serde_json::Value = serde_json::from_str(&error.to_problem_json()).unwrap();
+        assert_eq!(json["type"], "urn:xno-connect:error:invalid_block");
+        assert_eq!(json["status"], 400);
+        assert_eq!(json["retryable"], false);
+        assert_eq!(json["detail"], error.to_string());
+        assert_eq!(json["context"]["field"], "balance");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&Error::InvalidSeed.to_problem_json()).unwrap();
+        assert!(json.get("context").is_none());
+    }
+
+    #[test]
+    fn test_problem_retryable() {
+        let problem = Error::WorkGeneration(WorkError::Timeout).to_problem();
+        assert!(problem.retryable);
+        assert_eq!(problem.status, 504);
+        assert!(!Error::WorkGeneration(WorkError::Cancelled).is_retryable());
+        assert!(Error::Storage(StorageError::Io("disk full".into())).is_retryable());
+    }
+
+    #[test]
+    fn test_problem_insufficient_balance() {
+        let error = Error::InsufficientBalance {
+            balance: Raw::new(5),
+            amount: Raw::new(7),
+        };
+        assert_eq!(
+            error.to_string(),
+            "insufficient balance: 7 raw requested, 5 raw available"
+        );
+        let problem = error.to_problem();
+        assert_eq!(problem.status, 422);
+        assert_eq!(problem.context["balance"], "5");
+        assert!(!problem.retryable);
+    }
+
+    #[test]
+    fn test_problem_invalid_refund() {
+        let refund = BlockHash::from_bytes([1u8; 32]);
+        let problem = Error::InvalidRefund(RefundError::AlreadyRefunded(refund)).to_problem();
+        assert_eq!(problem.status, 409);
+        assert_eq!(problem.context["refund_hash"], refund.to_hex());
+        assert_eq!(
+            Error::InvalidRefund(RefundError::NotPayment)
+                .to_problem()
+                .status,
+            400
+        );
+    }
+
+    #[cfg(feature = "rpc")]
+    #[test]
+    fn test_problem_rpc() {
+        let problem = Error::Rpc(RpcError::HttpStatus(503)).to_problem();
+        assert!(problem.retryable);
+        assert_eq!(problem.status, 502);
+        assert_eq!(problem.context["upstream_status"], "503");
+        assert!(!Error::Rpc(RpcError::HttpStatus(404)).is_retryable());
+        assert!(!Error::Rpc(RpcError::NodeError("Account not found".into())).is_retryable());
+    }
 }
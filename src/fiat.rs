@@ -0,0 +1,435 @@
+//! Fiat currency conversion.
+//!
+//! A [`PriceOracle`] quotes the price of one XNO in a fiat currency.
+//! [`CachedOracle`] wraps any oracle with a time-to-live cache and can
+//! keep serving an old price, marked stale, while the source is down.
+//! With the `coingecko` feature, [`CoinGeckoOracle`] fetches prices from
+//! the public CoinGecko API.
+//!
+//! Prices are floating point; conversions are approximate and meant for
+//! display and invoicing, not for accounting in raw.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::fiat::FixedPriceOracle;
+//! use xno_connect::types::Amount;
+//!
+//! # async fn example() -> xno_connect::error::Result<()> {
+//! let oracle = FixedPriceOracle::new().with_price("USD", 1.25);
+//! let amount: Amount = "2 nano".parse()?;
+//! let fiat = amount.to_fiat(&oracle, "usd").await?;
+//! assert_eq!(fiat.to_string(), "2.50 USD");
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::time::Duration;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime};
+
+use crate::error::{Error, FiatError, Result};
+use crate::types::{Amount, Unit};
+
+/// Default time a [`CachedOracle`] reuses a price before refreshing.
+pub const DEFAULT_PRICE_TTL: Duration = Duration::from_secs(60);
+
+/// Price of one XNO in a fiat currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+    /// Currency code, uppercase.
+    pub currency: String,
+    /// Price of one XNO.
+    pub price: f64,
+    /// When the price was fetched from the source.
+    pub fetched_at: SystemTime,
+    /// Whether the price is older than the cache TTL because the source
+    /// could not be reached.
+    pub stale: bool,
+}
+
+impl Quote {
+    /// Create a fresh quote fetched now.
+    pub fn new(currency: &str, price: f64) -> Self {
+        Quote {
+            currency: currency.to_ascii_uppercase(),
+            price,
+            fetched_at: SystemTime::now(),
+            stale: false,
+        }
+    }
+
+    /// Time since the price was fetched.
+    pub fn age(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(self.fetched_at)
+            .unwrap_or_default()
+    }
+}
+
+/// Source of XNO prices.
+#[allow(async_fn_in_trait)]
+pub trait PriceOracle {
+    /// Get the price of one XNO in `currency`, e.g. `"USD"`.
+    ///
+    /// Currency codes are case-insensitive.
+    async fn quote(&self, currency: &str) -> Result<Quote>;
+}
+
+/// Amount converted to a fiat currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiatAmount {
+    /// Value in the currency.
+    pub value: f64,
+    /// Quote used for the conversion.
+    pub quote: Quote,
+}
+
+impl FiatAmount {
+    /// Check if the conversion used a stale price.
+    pub fn is_stale(&self) -> bool {
+        self.quote.stale
+    }
+}
+
+impl fmt::Display for FiatAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2} {}", self.value, self.quote.currency)
+    }
+}
+
+impl Amount {
+    /// Convert to a fiat currency at the oracle's price.
+    ///
+    /// Fails if the oracle's price is not a finite number.
+    pub async fn to_fiat<O: PriceOracle + ?Sized>(
+        &self,
+        oracle: &O,
+        currency: &str,
+    ) -> Result<FiatAmount> {
+        let quote = oracle.quote(currency).await?;
+        let nano = self.to_unit_string(Unit::Nano);
+        let value = nano
+            .parse::<f64>()
+            .ok()
+            .map(|nano| nano * quote.price)
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| {
+                Error::Fiat(FiatError::InvalidResponse(format!(
+                    "cannot convert {} XNO at price {}",
+                    nano, quote.price
+                )))
+            })?;
+        Ok(FiatAmount { value, quote })
+    }
+
+    /// Convert a fiat value to an amount at the oracle's price, rounded to
+    /// 6 decimal places of XNO.
+    pub async fn from_fiat<O: PriceOracle + ?Sized>(
+        value: f64,
+        oracle: &O,
+        currency: &str,
+    ) -> Result<Amount> {
+        let quote = oracle.quote(currency).await?;
+        if !quote.price.is_finite() || quote.price <= 0.0 || !value.is_finite() || value < 0.0 {
+            return Err(Error::Fiat(FiatError::InvalidResponse(format!(
+                "cannot convert {} at price {}",
+                value, quote.price
+            ))));
+        }
+        Amount::from_unit_str(&format!("{:.6}", value / quote.price), Unit::Nano)
+    }
+}
+
+/// Oracle with fixed prices, for tests and offline use.
+#[derive(Debug, Clone, Default)]
+pub struct FixedPriceOracle {
+    prices: BTreeMap<String, f64>,
+}
+
+impl FixedPriceOracle {
+    /// Create an oracle with no prices.
+    pub fn new() -> Self {
+        FixedPriceOracle::default()
+    }
+
+    /// Set the price of one XNO in a currency.
+    pub fn with_price(mut self, currency: &str, price: f64) -> Self {
+        self.prices.insert(currency.to_ascii_uppercase(), price);
+        self
+    }
+}
+
+impl PriceOracle for FixedPriceOracle {
+    async fn quote(&self, currency: &str) -> Result<Quote> {
+        let currency = currency.to_ascii_uppercase();
+        match self.prices.get(&currency) {
+            Some(price) => Ok(Quote::new(&currency, *price)),
+            None => Err(Error::Fiat(FiatError::UnsupportedCurrency(currency))),
+        }
+    }
+}
+
+/// Oracle that caches another oracle's prices.
+///
+/// A price is reused for the TTL. After that the source is asked again; if
+/// it fails with a retryable error and the cached price is younger than
+/// `max_stale`, the cached price is returned with [`Quote::stale`] set.
+#[derive(Debug)]
+pub struct CachedOracle<O> {
+    inner: O,
+    ttl: Duration,
+    max_stale: Duration,
+    quotes: Mutex<BTreeMap<String, (Instant, Quote)>>,
+}
+
+impl<O: PriceOracle> CachedOracle<O> {
+    /// Cache prices for [`DEFAULT_PRICE_TTL`], without serving stale ones.
+    pub fn new(inner: O) -> Self {
+        CachedOracle {
+            inner,
+            ttl: DEFAULT_PRICE_TTL,
+            max_stale: Duration::ZERO,
+            quotes: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Set how long a price is reused.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set how old a cached price may be when served as stale.
+    pub fn with_max_stale(mut self, max_stale: Duration) -> Self {
+        self.max_stale = max_stale;
+        self
+    }
+
+    /// Drop all cached prices.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, (Instant, Quote)>> {
+        self.quotes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<O: PriceOracle> PriceOracle for CachedOracle<O> {
+    async fn quote(&self, currency: &str) -> Result<Quote> {
+        let currency = currency.to_ascii_uppercase();
+        let cached = self.lock().get(&currency).cloned();
+        if let Some((stored, quote)) = &cached {
+            if stored.elapsed() < self.ttl {
+                return Ok(quote.clone());
+            }
+        }
+
+        match self.inner.quote(&currency).await {
+            Ok(quote) => {
+                self.lock()
+                    .insert(currency, (Instant::now(), quote.clone()));
+                Ok(quote)
+            }
+            Err(e) => match cached {
+                Some((stored, mut quote))
+                    if e.is_retryable() && stored.elapsed() < self.max_stale =>
+                {
+                    quote.stale = true;
+                    Ok(quote)
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
+
+/// Prices from the public CoinGecko API.
+#[cfg(feature = "coingecko")]
+#[derive(Debug, Clone)]
+pub struct CoinGeckoOracle {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[cfg(feature = "coingecko")]
+impl CoinGeckoOracle {
+    /// Public API endpoint.
+    pub const DEFAULT_URL: &'static str = "https://api.coingecko.com/api/v3";
+
+    /// Create an oracle using the public API.
+    pub fn new() -> Self {
+        Self::with_url(Self::DEFAULT_URL)
+    }
+
+    /// Create an oracle using another base URL, e.g. the Pro API.
+    pub fn with_url(url: &str) -> Self {
+        CoinGeckoOracle {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "coingecko")]
+impl Default for CoinGeckoOracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "coingecko")]
+impl PriceOracle for CoinGeckoOracle {
+    async fn quote(&self, currency: &str) -> Result<Quote> {
+        let vs = currency.to_ascii_lowercase();
+        let response = self
+            .client
+            .get(format!("{}/simple/price", self.url))
+            .query(&[("ids", "nano"), ("vs_currencies", vs.as_str())])
+            .send()
+            .await
+            .map_err(|e| Error::Fiat(FiatError::Unavailable(e.to_string())))?;
+        let status = response.status();
+        if !status.is_success() {
+            let error = format!("HTTP {}", status.as_u16());
+            return Err(Error::Fiat(
+                if status.as_u16() == 429 || status.is_server_error() {
+                    FiatError::Unavailable(error)
+                } else {
+                    FiatError::InvalidResponse(error)
+                },
+            ));
+        }
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Fiat(FiatError::InvalidResponse(e.to_string())))?;
+        parse_coingecko(&body, currency)
+    }
+}
+
+/// Extract the price from a `simple/price` response.
+#[cfg(feature = "coingecko")]
+fn parse_coingecko(body: &serde_json::Value, currency: &str) -> Result<Quote> {
+    let vs = currency.to_ascii_lowercase();
+    match body.get("nano").and_then(|prices| prices.get(&vs)) {
+        Some(price) => price
+            .as_f64()
+            .map(|price| Quote::new(currency, price))
+            .ok_or_else(|| Error::Fiat(FiatError::InvalidResponse(price.to_string()))),
+        None if body.get("nano").is_some() => Err(Error::Fiat(FiatError::UnsupportedCurrency(
+            currency.to_ascii_uppercase(),
+        ))),
+        None => Err(Error::Fiat(FiatError::InvalidResponse(body.to_string()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Oracle counting calls and failing on demand.
+    struct Flaky {
+        calls: AtomicUsize,
+        down: AtomicBool,
+    }
+
+    impl PriceOracle for Flaky {
+        async fn quote(&self, currency: &str) -> Result<Quote> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.down.load(Ordering::SeqCst) {
+                return Err(Error::Fiat(FiatError::Unavailable("down".into())));
+            }
+            Ok(Quote::new(currency, 1.0 + call as f64))
+        }
+    }
+
+    fn flaky() -> Flaky {
+        Flaky {
+            calls: AtomicUsize::new(0),
+            down: AtomicBool::new(false),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_to_fiat() {
+        let oracle = FixedPriceOracle::new().with_price("EUR", 0.8);
+        let amount: Amount = "1.5 nano".parse().unwrap();
+        let fiat = amount.to_fiat(&oracle, "eur").await.unwrap();
+        assert!((fiat.value - 1.2).abs() < 1e-9);
+        assert_eq!(fiat.to_string(), "1.20 EUR");
+        assert!(!fiat.is_stale());
+    }
+
+    #[tokio::test]
+    async fn test_to_fiat_rejects_invalid_price() {
+        let oracle = FixedPriceOracle::new().with_price("USD", f64::NAN);
+        let amount: Amount = "1 nano".parse().unwrap();
+        assert!(matches!(
+            amount.to_fiat(&oracle, "usd").await,
+            Err(Error::Fiat(FiatError::InvalidResponse(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_from_fiat() {
+        let oracle = FixedPriceOracle::new().with_price("USD", 2.0);
+        let amount = Amount::from_fiat(5.0, &oracle, "USD").await.unwrap();
+        assert_eq!(amount.raw(), "2.5 nano".parse::<Amount>().unwrap().raw());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_currency() {
+        let oracle = FixedPriceOracle::new();
+        assert_eq!(
+            oracle.quote("jpy").await.unwrap_err(),
+            Error::Fiat(FiatError::UnsupportedCurrency("JPY".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cached_within_ttl() {
+        let oracle = CachedOracle::new(flaky());
+        assert_eq!(oracle.quote("usd").await.unwrap().price, 1.0);
+        assert_eq!(oracle.quote("USD").await.unwrap().price, 1.0);
+        assert_eq!(oracle.inner.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stale_quote_served_when_source_down() {
+        let oracle = CachedOracle::new(flaky())
+            .with_ttl(Duration::ZERO)
+            .with_max_stale(Duration::from_secs(60));
+        oracle.quote("USD").await.unwrap();
+        oracle.inner.down.store(true, Ordering::SeqCst);
+
+        let quote = oracle.quote("USD").await.unwrap();
+        assert!(quote.stale);
+        assert_eq!(quote.price, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_stale_quote_not_served_by_default() {
+        let oracle = CachedOracle::new(flaky()).with_ttl(Duration::ZERO);
+        oracle.quote("USD").await.unwrap();
+        oracle.inner.down.store(true, Ordering::SeqCst);
+        assert!(oracle.quote("USD").await.is_err());
+    }
+
+    #[cfg(feature = "coingecko")]
+    #[test]
+    fn test_parse_coingecko() {
+        let body = serde_json::json!({ "nano": { "usd": 0.87 } });
+        assert_eq!(parse_coingecko(&body, "USD").unwrap().price, 0.87);
+        assert_eq!(
+            parse_coingecko(&body, "xyz").unwrap_err(),
+            Error::Fiat(FiatError::UnsupportedCurrency("XYZ".into()))
+        );
+    }
+}
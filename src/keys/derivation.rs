@@ -5,10 +5,17 @@
 //!
 //! The index is encoded as a 32-bit big-endian integer.
 
+use alloc::vec::Vec;
 use blake2::digest::consts::U32;
 use blake2::{Blake2b, Digest};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 
+use zeroize::Zeroize;
+
+use crate::keys::keypair::public_key_from_private;
 use crate::keys::KeyPair;
+use crate::types::PublicKey;
 
 /// Derive a keypair from a seed at the given index.
 ///
@@ -22,12 +29,67 @@ use crate::keys::KeyPair;
 /// # Returns
 /// A keypair containing the derived private and public keys.
 pub fn derive_keypair(seed: &[u8; 32], index: u32) -> KeyPair {
+    let mut private_key = derive_private_key(seed, index);
+    let keypair = KeyPair::from_private_key(private_key);
+    private_key.zeroize();
+    keypair
+}
+
+/// Derive only the public key at the given index.
+///
+/// The private key is wiped as soon as the public key is computed, so no
+/// secret material outlives the call.
+pub fn derive_public_key(seed: &[u8; 32], index: u32) -> PublicKey {
+    let mut private_key = derive_private_key(seed, index);
+    let public_key = public_key_from_private(&private_key);
+    private_key.zeroize();
+    public_key
+}
+
+fn derive_private_key(seed: &[u8; 32], index: u32) -> [u8; 32] {
     let mut hasher = Blake2b::<U32>::new();
     hasher.update(seed);
-    hasher.update(&index.to_be_bytes());
+    hasher.update(index.to_be_bytes());
+    hasher.finalize().into()
+}
+
+/// Derive the keypairs for indices `start..end`, in order.
+///
+/// Derivations run in parallel when the `rayon` dependency is enabled
+/// (e.g. by the `work-cpu` or `vanity` feature). Returns an empty vector if
+/// `end <= start`.
+pub fn derive_range(seed: &[u8; 32], start: u32, end: u32) -> Vec<KeyPair> {
+    #[cfg(feature = "rayon")]
+    {
+        (start..end)
+            .into_par_iter()
+            .map(|index| derive_keypair(seed, index))
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        (start..end)
+            .map(|index| derive_keypair(seed, index))
+            .collect()
+    }
+}
 
-    let private_key: [u8; 32] = hasher.finalize().into();
-    KeyPair::from_private_key(private_key)
+/// Derive only the public keys for indices `start..end`, in order, in
+/// parallel like [`derive_range`].
+pub fn derive_public_range(seed: &[u8; 32], start: u32, end: u32) -> Vec<PublicKey> {
+    #[cfg(feature = "rayon")]
+    {
+        (start..end)
+            .into_par_iter()
+            .map(|index| derive_public_key(seed, index))
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        (start..end)
+            .map(|index| derive_public_key(seed, index))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -72,6 +134,30 @@ mod tests {
         assert_ne!(kp0.public_key(), kp2.public_key());
     }
 
+    #[test]
+    fn test_derive_range_matches_single() {
+        let keypairs = derive_range(&ZERO_SEED, 5, 70);
+        assert_eq!(keypairs.len(), 65);
+        for (offset, keypair) in keypairs.iter().enumerate() {
+            let single = derive_keypair(&ZERO_SEED, 5 + offset as u32);
+            assert_eq!(keypair.public_key(), single.public_key());
+        }
+        assert!(derive_range(&ZERO_SEED, 3, 3).is_empty());
+        assert!(derive_range(&ZERO_SEED, 4, 3).is_empty());
+    }
+
+    #[test]
+    fn test_derive_public_matches_keypair() {
+        let public_keys = derive_public_range(&ZERO_SEED, 0, 10);
+        for (index, public_key) in public_keys.iter().enumerate() {
+            assert_eq!(
+                public_key,
+                derive_keypair(&ZERO_SEED, index as u32).public_key()
+            );
+            assert_eq!(*public_key, derive_public_key(&ZERO_SEED, index as u32));
+        }
+    }
+
     #[test]
     fn test_same_index_produces_same_key() {
         let kp1 = derive_keypair(&ZERO_SEED, 42);
@@ -0,0 +1,375 @@
+//! Password-encrypted seeds.
+//!
+//! The key is derived from the password with Argon2id and the seed is
+//! sealed with XChaCha20-Poly1305. The format version and key derivation
+//! parameters are authenticated along with the seed, so tampering with any
+//! part of the encrypted data makes decryption fail.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::keys::{EncryptedSeed, KdfParams, Seed};
+//!
+//! # fn main() -> xno_connect::error::Result<()> {
+//! let seed = Seed::from_bytes([1; 32]);
+//! // Cheap parameters keep the example fast; use the defaults in practice.
+//! let params = KdfParams::new(1024, 1, 1);
+//! let encrypted = EncryptedSeed::encrypt_with_params(&seed, b"hunter2", params)?;
+//!
+//! let stored = encrypted.to_hex();
+//! let restored = EncryptedSeed::from_hex(&stored)?.decrypt(b"hunter2")?;
+//! assert_eq!(restored, seed);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use zeroize::Zeroizing;
+
+use crate::error::{EncryptionError, Error, Result};
+use crate::keys::Seed;
+
+/// Current format version.
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+/// Version byte followed by the three Argon2 parameters.
+const HEADER_LEN: usize = 1 + 3 * 4;
+const ENCODED_LEN: usize = HEADER_LEN + SALT_LEN + NONCE_LEN + 32 + TAG_LEN;
+
+/// Argon2id cost parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParams {
+    /// Memory size in KiB.
+    pub memory_kib: u32,
+    /// Number of passes.
+    pub iterations: u32,
+    /// Degree of parallelism.
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    /// Largest accepted memory size, 1 GiB.
+    pub const MAX_MEMORY_KIB: u32 = 1024 * 1024;
+    /// Largest accepted number of passes.
+    pub const MAX_ITERATIONS: u32 = 64;
+    /// Largest accepted degree of parallelism.
+    pub const MAX_PARALLELISM: u32 = 16;
+
+    /// Create parameters.
+    pub const fn new(memory_kib: u32, iterations: u32, parallelism: u32) -> Self {
+        KdfParams {
+            memory_kib,
+            iterations,
+            parallelism,
+        }
+    }
+
+    /// Reject parameters above the `MAX_*` limits.
+    ///
+    /// Parsed data is untrusted and the parameters are used before the
+    /// authentication tag can be checked, so they are bounded up front.
+    fn check_limits(&self) -> Result<()> {
+        if self.memory_kib > Self::MAX_MEMORY_KIB
+            || self.iterations > Self::MAX_ITERATIONS
+            || self.parallelism > Self::MAX_PARALLELISM
+        {
+            return Err(Error::Encryption(EncryptionError::InvalidParams(
+                "cost parameters exceed the supported maximum".to_string(),
+            )));
+        }
+        Ok(())
+    }
+
+    fn argon2(&self) -> Result<Argon2<'static>> {
+        self.check_limits()?;
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+            .map_err(|e| Error::Encryption(EncryptionError::InvalidParams(e.to_string())))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+}
+
+impl Default for KdfParams {
+    /// 64 MiB, 3 passes, 1 lane.
+    fn default() -> Self {
+        KdfParams::new(64 * 1024, 3, 1)
+    }
+}
+
+/// A seed encrypted with a password.
+///
+/// Only the ciphertext is kept in memory; [`decrypt`](Self::decrypt)
+/// returns a [`Seed`] that is zeroed when dropped, and the derived key and
+/// plaintext buffers are zeroed before it returns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncryptedSeed {
+    params: KdfParams,
+    salt: [u8; SALT_LEN],
+    nonce: [u8; NONCE_LEN],
+    ciphertext: Vec<u8>,
+}
+
+impl EncryptedSeed {
+    /// Encrypt a seed with the default key derivation parameters.
+    pub fn encrypt(seed: &Seed, password: &[u8]) -> Result<Self> {
+        Self::encrypt_with_params(seed, password, KdfParams::default())
+    }
+
+    /// Encrypt a seed with custom key derivation parameters.
+    pub fn encrypt_with_params(seed: &Seed, password: &[u8], params: KdfParams) -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::getrandom(&mut salt)
+            .and_then(|_| getrandom::getrandom(&mut nonce))
+            .map_err(|_| Error::Encryption(EncryptionError::RandomUnavailable))?;
+
+        let key = derive_key(password, &salt, &params)?;
+        let header = header(&params);
+        let ciphertext = XChaCha20Poly1305::new(key.as_ref().into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: seed.as_bytes(),
+                    aad: &header,
+                },
+            )
+            .map_err(|_| Error::Encryption(EncryptionError::DecryptionFailed))?;
+
+        Ok(EncryptedSeed {
+            params,
+            salt,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Decrypt the seed.
+    ///
+    /// Fails with [`EncryptionError::DecryptionFailed`] if the password is
+    /// wrong or the data was modified.
+    pub fn decrypt(&self, password: &[u8]) -> Result<Seed> {
+        let key = derive_key(password, &self.salt, &self.params)?;
+        let header = header(&self.params);
+        let plaintext = Zeroizing::new(
+            XChaCha20Poly1305::new(key.as_ref().into())
+                .decrypt(
+                    XNonce::from_slice(&self.nonce),
+                    Payload {
+                        msg: &self.ciphertext,
+                        aad: &header,
+                    },
+                )
+                .map_err(|_| Error::Encryption(EncryptionError::DecryptionFailed))?,
+        );
+
+        let mut bytes = Zeroizing::new([0u8; 32]);
+        if plaintext.len() != bytes.len() {
+            return Err(Error::Encryption(EncryptionError::InvalidFormat));
+        }
+        bytes.copy_from_slice(&plaintext);
+        Ok(Seed::from_bytes(*bytes))
+    }
+
+    /// Check a password without keeping the decrypted seed.
+    pub fn verify_password(&self, password: &[u8]) -> bool {
+        self.decrypt(password).is_ok()
+    }
+
+    /// Re-encrypt under a new password, with fresh salt and nonce.
+    pub fn change_password(&self, old_password: &[u8], new_password: &[u8]) -> Result<Self> {
+        let seed = self.decrypt(old_password)?;
+        Self::encrypt_with_params(&seed, new_password, self.params)
+    }
+
+    /// Get the key derivation parameters.
+    pub fn params(&self) -> &KdfParams {
+        &self.params
+    }
+
+    /// Serialize to bytes for storage.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(ENCODED_LEN);
+        bytes.extend_from_slice(&header(&self.params));
+        bytes.extend_from_slice(&self.salt);
+        bytes.extend_from_slice(&self.nonce);
+        bytes.extend_from_slice(&self.ciphertext);
+        bytes
+    }
+
+    /// Parse bytes produced by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != ENCODED_LEN || bytes[0] != VERSION {
+            return Err(Error::Encryption(EncryptionError::InvalidFormat));
+        }
+        let word = |at: usize| {
+            u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+        };
+        let params = KdfParams::new(word(1), word(5), word(9));
+        params.check_limits()?;
+
+        let mut salt = [0u8; SALT_LEN];
+        salt.copy_from_slice(&bytes[HEADER_LEN..HEADER_LEN + SALT_LEN]);
+        let mut nonce = [0u8; NONCE_LEN];
+        let nonce_start = HEADER_LEN + SALT_LEN;
+        nonce.copy_from_slice(&bytes[nonce_start..nonce_start + NONCE_LEN]);
+
+        Ok(EncryptedSeed {
+            params,
+            salt,
+            nonce,
+            ciphertext: bytes[nonce_start + NONCE_LEN..].to_vec(),
+        })
+    }
+
+    /// Convert to hex string.
+    pub fn to_hex(&self) -> String {
+        hex::encode_upper(self.to_bytes())
+    }
+
+    /// Parse a hex string produced by [`to_hex`](Self::to_hex).
+    pub fn from_hex(s: &str) -> Result<Self> {
+        let bytes =
+            hex::decode(s).map_err(|_| Error::Encryption(EncryptionError::InvalidFormat))?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+fn header(params: &KdfParams) -> [u8; HEADER_LEN] {
+    let mut header = [0u8; HEADER_LEN];
+    header[0] = VERSION;
+    header[1..5].copy_from_slice(&params.memory_kib.to_le_bytes());
+    header[5..9].copy_from_slice(&params.iterations.to_le_bytes());
+    header[9..13].copy_from_slice(&params.parallelism.to_le_bytes());
+    header
+}
+
+fn derive_key(password: &[u8], salt: &[u8], params: &KdfParams) -> Result<Zeroizing<[u8; 32]>> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    params
+        .argon2()?
+        .hash_password_into(password, salt, key.as_mut())
+        .map_err(|e| Error::Encryption(EncryptionError::InvalidParams(e.to_string())))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: KdfParams = KdfParams::new(1024, 1, 1);
+
+    fn seed() -> Seed {
+        Seed::from_bytes([7; 32])
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed(), b"password", PARAMS).unwrap();
+        assert_eq!(encrypted.decrypt(b"password").unwrap(), seed());
+        assert!(encrypted.verify_password(b"password"));
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed(), b"password", PARAMS).unwrap();
+        assert_eq!(
+            encrypted.decrypt(b"wrong").unwrap_err(),
+            Error::Encryption(EncryptionError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_fresh_salt_and_nonce() {
+        let a = EncryptedSeed::encrypt_with_params(&seed(), b"password", PARAMS).unwrap();
+        let b = EncryptedSeed::encrypt_with_params(&seed(), b"password", PARAMS).unwrap();
+        assert_ne!(a.to_bytes(), b.to_bytes());
+    }
+
+    #[test]
+    fn test_serialization_round_trip() {
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed(), b"password", PARAMS).unwrap();
+        let bytes = encrypted.to_bytes();
+        assert_eq!(bytes.len(), ENCODED_LEN);
+
+        let parsed = EncryptedSeed::from_hex(&encrypted.to_hex()).unwrap();
+        assert_eq!(parsed, encrypted);
+        assert_eq!(parsed.params(), &PARAMS);
+        assert_eq!(parsed.decrypt(b"password").unwrap(), seed());
+    }
+
+    #[test]
+    fn test_tampered_params_rejected() {
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed(), b"password", PARAMS).unwrap();
+        let mut bytes = encrypted.to_bytes();
+        bytes[5] = 2;
+        let tampered = EncryptedSeed::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            tampered.decrypt(b"password").unwrap_err(),
+            Error::Encryption(EncryptionError::DecryptionFailed)
+        );
+    }
+
+    #[test]
+    fn test_invalid_format() {
+        assert_eq!(
+            EncryptedSeed::from_bytes(&[VERSION; 10]).unwrap_err(),
+            Error::Encryption(EncryptionError::InvalidFormat)
+        );
+        let mut bytes = [0u8; ENCODED_LEN];
+        bytes[0] = VERSION + 1;
+        assert_eq!(
+            EncryptedSeed::from_bytes(&bytes).unwrap_err(),
+            Error::Encryption(EncryptionError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn test_excessive_params_rejected() {
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed(), b"password", PARAMS).unwrap();
+        for (at, value) in [
+            (1, KdfParams::MAX_MEMORY_KIB + 1),
+            (5, KdfParams::MAX_ITERATIONS + 1),
+            (9, KdfParams::MAX_PARALLELISM + 1),
+        ] {
+            let mut bytes = encrypted.to_bytes();
+            bytes[at..at + 4].copy_from_slice(&value.to_le_bytes());
+            assert!(matches!(
+                EncryptedSeed::from_bytes(&bytes),
+                Err(Error::Encryption(EncryptionError::InvalidParams(_)))
+            ));
+        }
+
+        let result = EncryptedSeed::encrypt_with_params(
+            &seed(),
+            b"password",
+            KdfParams::new(KdfParams::MAX_MEMORY_KIB + 1, 1, 1),
+        );
+        assert!(matches!(
+            result,
+            Err(Error::Encryption(EncryptionError::InvalidParams(_)))
+        ));
+    }
+
+    #[test]
+    fn test_change_password() {
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed(), b"old", PARAMS).unwrap();
+        let changed = encrypted.change_password(b"old", b"new").unwrap();
+        assert!(!changed.verify_password(b"old"));
+        assert_eq!(changed.decrypt(b"new").unwrap(), seed());
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let result =
+            EncryptedSeed::encrypt_with_params(&seed(), b"password", KdfParams::new(1, 0, 1));
+        assert!(matches!(
+            result,
+            Err(Error::Encryption(EncryptionError::InvalidParams(_)))
+        ));
+    }
+}
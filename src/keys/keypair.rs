@@ -6,11 +6,14 @@ use alloc::string::String;
 use blake2::{Blake2b512, Digest};
 use core::fmt;
 use curve25519_dalek_ng::{
-    constants::ED25519_BASEPOINT_TABLE, edwards::CompressedEdwardsY, scalar::Scalar,
+    constants::ED25519_BASEPOINT_TABLE,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
 };
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::error::{Error, Result};
+use crate::security::SecretString;
 use crate::types::{Account, BlockHash, PublicKey, Signature};
 
 /// Secret key (32 bytes).
@@ -50,6 +53,11 @@ impl SecretKey {
     pub fn to_hex(&self) -> String {
         hex::encode_upper(self.0)
     }
+
+    /// Convert to a hex string that is wiped from memory when dropped.
+    pub fn to_hex_secret(&self) -> SecretString {
+        SecretString::new(self.to_hex())
+    }
 }
 
 impl fmt::Debug for SecretKey {
@@ -70,7 +78,7 @@ fn clamp_scalar(bytes: &mut [u8; 32]) {
 fn expand_private_key(private_key: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
     let mut hasher = Blake2b512::new();
     hasher.update(private_key);
-    let hash: [u8; 64] = hasher.finalize().into();
+    let mut hash: [u8; 64] = hasher.finalize().into();
 
     let mut scalar_bytes = [0u8; 32];
     scalar_bytes.copy_from_slice(&hash[0..32]);
@@ -78,6 +86,7 @@ fn expand_private_key(private_key: &[u8; 32]) -> ([u8; 32], [u8; 32]) {
 
     let mut hash_prefix = [0u8; 32];
     hash_prefix.copy_from_slice(&hash[32..64]);
+    hash.zeroize();
 
     (scalar_bytes, hash_prefix)
 }
@@ -101,10 +110,11 @@ impl KeyPair {
     ///
     /// The public key is derived using Nano's Ed25519 with Blake2b-512 expansion.
     pub fn from_private_key(private_key: [u8; 32]) -> Self {
-        let (scalar_bytes, hash_prefix) = expand_private_key(&private_key);
+        let (mut scalar_bytes, hash_prefix) = expand_private_key(&private_key);
 
         // Use from_bits to interpret the bytes as a scalar without reduction
         let scalar = Scalar::from_bits(scalar_bytes);
+        scalar_bytes.zeroize();
 
         // Compute public key: A = s * G
         let public_point = &scalar * &ED25519_BASEPOINT_TABLE;
@@ -134,6 +144,18 @@ impl KeyPair {
         &self.public_key
     }
 
+    /// Expanded secret scalar used for signing.
+    #[cfg(feature = "experimental-multisig")]
+    pub(super) fn scalar(&self) -> &Scalar {
+        &self.scalar
+    }
+
+    /// Secret prefix used for nonce derivation.
+    #[cfg(feature = "experimental-multisig")]
+    pub(super) fn hash_prefix(&self) -> &[u8; 32] {
+        &self.hash_prefix
+    }
+
     /// Get the account address for this keypair.
     pub fn account(&self) -> Account {
         self.public_key.to_account()
@@ -155,10 +177,11 @@ impl KeyPair {
 
         // Step 1: Generate deterministic nonce r
         let mut hasher = Blake2b512::new();
-        hasher.update(&self.hash_prefix);
+        hasher.update(self.hash_prefix);
         hasher.update(message);
-        let r_hash: [u8; 64] = hasher.finalize().into();
-        let r = Scalar::from_bytes_mod_order_wide(&r_hash);
+        let mut r_hash: [u8; 64] = hasher.finalize().into();
+        let mut r = Scalar::from_bytes_mod_order_wide(&r_hash);
+        r_hash.zeroize();
 
         // Step 2: R = r * G
         let big_r = &r * &ED25519_BASEPOINT_TABLE;
@@ -166,7 +189,7 @@ impl KeyPair {
 
         // Step 3: k = H(R || A || message) mod L
         let mut hasher = Blake2b512::new();
-        hasher.update(&big_r_bytes);
+        hasher.update(big_r_bytes);
         hasher.update(self.public_key.as_bytes());
         hasher.update(message);
         let k_hash: [u8; 64] = hasher.finalize().into();
@@ -174,6 +197,8 @@ impl KeyPair {
 
         // Step 4: s = r + k * a (mod L)
         let s = r + k * self.scalar;
+        // The nonce reveals the secret scalar together with the signature
+        r.zeroize();
 
         // Construct signature (R || s)
         let mut sig_bytes = [0u8; 64];
@@ -183,6 +208,14 @@ impl KeyPair {
         Signature::from_bytes(sig_bytes)
     }
 
+    /// Sign an off-chain message using the "Nano Signed Message" domain.
+    ///
+    /// The signature cannot be replayed as a block signature; verify it with
+    /// [`verify_nano_message`](crate::signing::verify_nano_message).
+    pub fn sign_nano_message(&self, message: &[u8]) -> Signature {
+        crate::signing::sign_nano_message(self, message)
+    }
+
     /// Verify a signature.
     pub fn verify(&self, hash: &BlockHash, signature: &Signature) -> bool {
         Self::verify_with_public_key(&self.public_key, hash, signature)
@@ -224,7 +257,7 @@ impl KeyPair {
 
         // s must be reduced mod L - check it's canonical
         let s = Scalar::from_canonical_bytes(s_bytes);
-        if s.is_none().into() {
+        if s.is_none() {
             return false;
         }
         let s = s.unwrap();
@@ -238,7 +271,7 @@ impl KeyPair {
 
         // Compute k = H(R || A || message) mod L using Blake2b-512
         let mut hasher = Blake2b512::new();
-        hasher.update(&r_bytes);
+        hasher.update(r_bytes);
         hasher.update(public_key.as_bytes());
         hasher.update(message);
         let k_hash: [u8; 64] = hasher.finalize().into();
@@ -252,6 +285,61 @@ impl KeyPair {
     }
 }
 
+/// Compute the public key for a private key without building a
+/// [`KeyPair`], wiping the intermediate secrets before returning.
+pub(crate) fn public_key_from_private(private_key: &[u8; 32]) -> PublicKey {
+    let (mut scalar_bytes, mut hash_prefix) = expand_private_key(private_key);
+    let mut scalar = Scalar::from_bits(scalar_bytes);
+    let public_key =
+        PublicKey::from_bytes((&scalar * &ED25519_BASEPOINT_TABLE).compress().to_bytes());
+    scalar.zeroize();
+    scalar_bytes.zeroize();
+    hash_prefix.zeroize();
+    public_key
+}
+
+/// Decompress a public key for [`verify_prepared`].
+pub(crate) fn prepare_public_key(public_key: &PublicKey) -> Option<EdwardsPoint> {
+    CompressedEdwardsY(*public_key.as_bytes()).decompress()
+}
+
+/// Verify a signature against an already decompressed public key.
+///
+/// Accepts exactly the signatures
+/// [`KeyPair::verify_message_with_public_key`] accepts, but runs in variable
+/// time, which is safe since all inputs are public.
+pub(crate) fn verify_prepared(
+    a_point: &EdwardsPoint,
+    public_key: &PublicKey,
+    message: &[u8],
+    signature: &Signature,
+) -> bool {
+    let sig_bytes = signature.as_bytes();
+    let mut r_bytes = [0u8; 32];
+    r_bytes.copy_from_slice(&sig_bytes[..32]);
+    let r_point = match CompressedEdwardsY(r_bytes).decompress() {
+        Some(p) => p,
+        None => return false,
+    };
+
+    let mut s_bytes = [0u8; 32];
+    s_bytes.copy_from_slice(&sig_bytes[32..]);
+    let s = match Scalar::from_canonical_bytes(s_bytes) {
+        Some(s) => s,
+        None => return false,
+    };
+
+    let mut hasher = Blake2b512::new();
+    hasher.update(r_bytes);
+    hasher.update(public_key.as_bytes());
+    hasher.update(message);
+    let k_hash: [u8; 64] = hasher.finalize().into();
+    let k = Scalar::from_bytes_mod_order_wide(&k_hash);
+
+    // s * G - k * A == R
+    EdwardsPoint::vartime_double_scalar_mul_basepoint(&-k, a_point, &s) == r_point
+}
+
 impl fmt::Debug for KeyPair {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("KeyPair")
@@ -264,6 +352,7 @@ impl fmt::Debug for KeyPair {
 impl Zeroize for KeyPair {
     fn zeroize(&mut self) {
         self.secret_key.zeroize();
+        self.scalar.zeroize();
         self.hash_prefix.zeroize();
     }
 }
@@ -3,9 +3,26 @@
 //! This module provides secure key generation, derivation, and signing.
 
 mod derivation;
+#[cfg(feature = "encryption")]
+mod encrypted;
 mod keypair;
+#[cfg(feature = "experimental-multisig")]
+pub mod multisig;
+mod rng;
 mod seed;
+#[cfg(feature = "vanity")]
+mod vanity;
+mod watch;
 
-pub use derivation::derive_keypair;
+pub use derivation::{derive_keypair, derive_public_key, derive_public_range, derive_range};
+#[cfg(feature = "encryption")]
+pub use encrypted::{EncryptedSeed, KdfParams};
+pub(crate) use keypair::{prepare_public_key, verify_prepared};
 pub use keypair::{KeyPair, SecretKey};
+pub use rng::SeedRng;
+#[cfg(feature = "getrandom")]
+pub use rng::SystemRng;
 pub use seed::Seed;
+#[cfg(feature = "vanity")]
+pub use vanity::{VanityGenerator, VanityMatch, VanityMode, VanityPattern};
+pub use watch::WatchOnlyKeys;
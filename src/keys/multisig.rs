@@ -0,0 +1,469 @@
+//! Experimental n-of-n aggregate signing.
+//!
+//! MuSig-style aggregation of Nano Ed25519 keys: several keypairs jointly
+//! control one ordinary account, and together produce one ordinary
+//! signature that nodes verify like any other. No participant learns the
+//! others' secret keys.
+//!
+//! Signing takes three rounds between participants:
+//!
+//! 1. Each creates a [`SecretNonce`] and shares the commitment of its
+//!    [`PublicNonce`].
+//! 2. Once all commitments are in, each reveals its public nonce; everyone
+//!    checks it against the commitment.
+//! 3. Each computes a [`PartialSignature`]; any party combines them with
+//!    [`aggregate_signatures`].
+//!
+//! Nonces are random (hedged with the secret key and message) rather than
+//! fully deterministic: a deterministic nonce reused against different
+//! co-signer nonces would leak the secret key.
+//!
+//! This is research code and has not been audited.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::keys::multisig::{self, AggregateKey};
+//! use xno_connect::keys::Seed;
+//! use xno_connect::types::BlockHash;
+//!
+//! # fn main() -> xno_connect::error::Result<()> {
+//! let alice = Seed::from_bytes([1; 32]).derive(0);
+//! let bob = Seed::from_bytes([2; 32]).derive(0);
+//! let key = AggregateKey::new(&[*alice.public_key(), *bob.public_key()])?;
+//! let hash = BlockHash::from_bytes([7; 32]);
+//!
+//! let alice_nonce = multisig::generate_nonce(&alice, &key, &hash)?;
+//! let bob_nonce = multisig::generate_nonce(&bob, &key, &hash)?;
+//! let nonces = [alice_nonce.public(), bob_nonce.public()];
+//!
+//! let partials = [
+//!     multisig::partial_sign(&alice, &key, alice_nonce, &nonces, &hash)?,
+//!     multisig::partial_sign(&bob, &key, bob_nonce, &nonces, &hash)?,
+//! ];
+//! let signature = multisig::aggregate_signatures(&key, &hash, &nonces, &partials)?;
+//! assert!(xno_connect::keys::KeyPair::verify_with_public_key(
+//!     key.public_key(),
+//!     &hash,
+//!     &signature
+//! ));
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::vec::Vec;
+use blake2::{Blake2b512, Digest};
+use curve25519_dalek_ng::{
+    constants::ED25519_BASEPOINT_TABLE,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::Identity,
+};
+use zeroize::Zeroize;
+
+use crate::error::{Error, MultisigError, Result};
+use crate::keys::KeyPair;
+use crate::types::{Account, BlockHash, PublicKey, Signature};
+
+/// Aggregated public key of a set of participants.
+///
+/// Keys are sorted first, so every participant derives the same account
+/// regardless of the order they were listed in.
+#[derive(Debug, Clone)]
+pub struct AggregateKey {
+    public_key: PublicKey,
+    participants: Vec<(PublicKey, Scalar)>,
+}
+
+impl AggregateKey {
+    /// Aggregate the participants' public keys.
+    pub fn new(public_keys: &[PublicKey]) -> Result<Self> {
+        if public_keys.is_empty() {
+            return Err(Error::Multisig(MultisigError::NoParticipants));
+        }
+        let mut keys = public_keys.to_vec();
+        keys.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        if keys.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(Error::Multisig(MultisigError::DuplicateKey));
+        }
+
+        let mut list = Blake2b512::new();
+        list.update(b"xno-musig-keys");
+        for key in &keys {
+            list.update(key.as_bytes());
+        }
+        let list: [u8; 64] = list.finalize().into();
+
+        let mut aggregate = EdwardsPoint::identity();
+        let mut participants = Vec::with_capacity(keys.len());
+        for key in keys {
+            let mut hasher = Blake2b512::new();
+            hasher.update(b"xno-musig-coefficient");
+            hasher.update(list);
+            hasher.update(key.as_bytes());
+            let coefficient = Scalar::from_bytes_mod_order_wide(&hasher.finalize().into());
+            aggregate += coefficient * decompress(key.as_bytes()).ok_or(Error::InvalidPublicKey)?;
+            participants.push((key, coefficient));
+        }
+
+        Ok(AggregateKey {
+            public_key: PublicKey::from_bytes(aggregate.compress().to_bytes()),
+            participants,
+        })
+    }
+
+    /// Get the aggregated public key.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Get the account controlled by the participants.
+    pub fn account(&self) -> Account {
+        self.public_key.to_account()
+    }
+
+    /// Get the participants' public keys, sorted.
+    pub fn participants(&self) -> impl Iterator<Item = &PublicKey> {
+        self.participants.iter().map(|(key, _)| key)
+    }
+
+    /// Number of participants.
+    pub fn len(&self) -> usize {
+        self.participants.len()
+    }
+
+    /// Check if there are no participants; never true once constructed.
+    pub fn is_empty(&self) -> bool {
+        self.participants.is_empty()
+    }
+
+    fn coefficient(&self, key: &PublicKey) -> Option<Scalar> {
+        self.participants
+            .iter()
+            .find(|(participant, _)| participant == key)
+            .map(|(_, coefficient)| *coefficient)
+    }
+
+    fn check_count(&self, actual: usize) -> Result<()> {
+        if actual != self.participants.len() {
+            return Err(Error::Multisig(MultisigError::CountMismatch {
+                expected: self.participants.len(),
+                actual,
+            }));
+        }
+        Ok(())
+    }
+}
+
+/// Public half of a signing nonce, shared with the other participants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PublicNonce([u8; 32]);
+
+impl PublicNonce {
+    /// Create from raw bytes received from a participant.
+    pub const fn from_bytes(bytes: [u8; 32]) -> Self {
+        PublicNonce(bytes)
+    }
+
+    /// Get as raw bytes.
+    pub const fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Commitment to share before revealing the nonce.
+    pub fn commitment(&self) -> [u8; 32] {
+        let mut hasher = Blake2b512::new();
+        hasher.update(b"xno-musig-commitment");
+        hasher.update(self.0);
+        let hash: [u8; 64] = hasher.finalize().into();
+        let mut commitment = [0u8; 32];
+        commitment.copy_from_slice(&hash[..32]);
+        commitment
+    }
+
+    /// Check a revealed nonce against its earlier commitment.
+    pub fn verify_commitment(&self, commitment: &[u8; 32]) -> Result<()> {
+        if self.commitment() != *commitment {
+            return Err(Error::Multisig(MultisigError::CommitmentMismatch));
+        }
+        Ok(())
+    }
+}
+
+/// Secret signing nonce. Used once by [`partial_sign`], then dropped.
+pub struct SecretNonce {
+    scalar: Scalar,
+    public: PublicNonce,
+}
+
+impl SecretNonce {
+    /// Get the public nonce to share.
+    pub fn public(&self) -> PublicNonce {
+        self.public
+    }
+}
+
+impl Drop for SecretNonce {
+    fn drop(&mut self) {
+        self.scalar.zeroize();
+    }
+}
+
+/// One participant's share of the aggregate signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialSignature {
+    signer: PublicKey,
+    nonce: PublicNonce,
+    s: [u8; 32],
+}
+
+impl PartialSignature {
+    /// Decode from the 96-byte form made by [`to_bytes`](Self::to_bytes).
+    pub fn from_bytes(bytes: &[u8; 96]) -> Self {
+        let mut signer = [0u8; 32];
+        let mut nonce = [0u8; 32];
+        let mut s = [0u8; 32];
+        signer.copy_from_slice(&bytes[..32]);
+        nonce.copy_from_slice(&bytes[32..64]);
+        s.copy_from_slice(&bytes[64..]);
+        PartialSignature {
+            signer: PublicKey::from_bytes(signer),
+            nonce: PublicNonce(nonce),
+            s,
+        }
+    }
+
+    /// Encode as signer key, public nonce and scalar for sending to the
+    /// aggregating party.
+    pub fn to_bytes(&self) -> [u8; 96] {
+        let mut bytes = [0u8; 96];
+        bytes[..32].copy_from_slice(self.signer.as_bytes());
+        bytes[32..64].copy_from_slice(&self.nonce.0);
+        bytes[64..].copy_from_slice(&self.s);
+        bytes
+    }
+
+    /// Get the signer's public key.
+    pub fn signer(&self) -> &PublicKey {
+        &self.signer
+    }
+
+    /// Get the signer's public nonce.
+    pub fn nonce(&self) -> PublicNonce {
+        self.nonce
+    }
+}
+
+/// Create a fresh nonce for signing `hash` as part of `aggregate`.
+pub fn generate_nonce(
+    keypair: &KeyPair,
+    aggregate: &AggregateKey,
+    hash: &BlockHash,
+) -> Result<SecretNonce> {
+    let mut random = [0u8; 32];
+    getrandom::getrandom(&mut random)
+        .map_err(|_| Error::Multisig(MultisigError::RandomUnavailable))?;
+
+    let mut hasher = Blake2b512::new();
+    hasher.update(keypair.hash_prefix());
+    hasher.update(random);
+    hasher.update(aggregate.public_key.as_bytes());
+    hasher.update(hash.as_bytes());
+    random.zeroize();
+    let mut wide: [u8; 64] = hasher.finalize().into();
+    let scalar = Scalar::from_bytes_mod_order_wide(&wide);
+    wide.zeroize();
+    let public = PublicNonce((&scalar * &ED25519_BASEPOINT_TABLE).compress().to_bytes());
+    Ok(SecretNonce { scalar, public })
+}
+
+/// Sign `hash` with one participant's key.
+///
+/// `nonces` holds every participant's public nonce, in any order.
+pub fn partial_sign(
+    keypair: &KeyPair,
+    aggregate: &AggregateKey,
+    nonce: SecretNonce,
+    nonces: &[PublicNonce],
+    hash: &BlockHash,
+) -> Result<PartialSignature> {
+    let coefficient = aggregate
+        .coefficient(keypair.public_key())
+        .ok_or(Error::Multisig(MultisigError::NotParticipant))?;
+    aggregate.check_count(nonces.len())?;
+    if !nonces.contains(&nonce.public) {
+        return Err(Error::Multisig(MultisigError::InvalidNonce));
+    }
+
+    let (_, k) = challenge(aggregate, nonces, hash)?;
+    let s = nonce.scalar + k * coefficient * keypair.scalar();
+    Ok(PartialSignature {
+        signer: *keypair.public_key(),
+        nonce: nonce.public,
+        s: s.to_bytes(),
+    })
+}
+
+/// Verify every partial signature and combine them into a Nano signature.
+pub fn aggregate_signatures(
+    aggregate: &AggregateKey,
+    hash: &BlockHash,
+    nonces: &[PublicNonce],
+    partials: &[PartialSignature],
+) -> Result<Signature> {
+    aggregate.check_count(partials.len())?;
+    let (big_r, k) = challenge(aggregate, nonces, hash)?;
+
+    let mut signers = Vec::with_capacity(partials.len());
+    let mut s = Scalar::zero();
+    for partial in partials {
+        let coefficient = aggregate
+            .coefficient(&partial.signer)
+            .ok_or(Error::Multisig(MultisigError::NotParticipant))?;
+        if signers.contains(&partial.signer) {
+            return Err(Error::Multisig(MultisigError::DuplicateKey));
+        }
+        signers.push(partial.signer);
+        if !nonces.contains(&partial.nonce) {
+            return Err(Error::Multisig(MultisigError::InvalidNonce));
+        }
+
+        // s_i * G == R_i + k * c_i * A_i
+        let invalid = Error::Multisig(MultisigError::InvalidPartialSignature);
+        let s_i = Scalar::from_canonical_bytes(partial.s).ok_or(invalid.clone())?;
+        let r_i = decompress(partial.nonce.as_bytes())
+            .ok_or(Error::Multisig(MultisigError::InvalidNonce))?;
+        let a_i = decompress(partial.signer.as_bytes()).ok_or(Error::InvalidPublicKey)?;
+        if &s_i * &ED25519_BASEPOINT_TABLE != r_i + k * coefficient * a_i {
+            return Err(invalid);
+        }
+        s += s_i;
+    }
+
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(&big_r);
+    bytes[32..].copy_from_slice(&s.to_bytes());
+    Ok(Signature::from_bytes(bytes))
+}
+
+/// Combined nonce `R` and challenge `k = H(R || A || message)`.
+fn challenge(
+    aggregate: &AggregateKey,
+    nonces: &[PublicNonce],
+    hash: &BlockHash,
+) -> Result<([u8; 32], Scalar)> {
+    aggregate.check_count(nonces.len())?;
+    let mut big_r = EdwardsPoint::identity();
+    for nonce in nonces {
+        big_r +=
+            decompress(nonce.as_bytes()).ok_or(Error::Multisig(MultisigError::InvalidNonce))?;
+    }
+    let big_r = big_r.compress().to_bytes();
+
+    let mut hasher = Blake2b512::new();
+    hasher.update(big_r);
+    hasher.update(aggregate.public_key.as_bytes());
+    hasher.update(hash.as_bytes());
+    let k = Scalar::from_bytes_mod_order_wide(&hasher.finalize().into());
+    Ok((big_r, k))
+}
+
+fn decompress(bytes: &[u8; 32]) -> Option<EdwardsPoint> {
+    CompressedEdwardsY(*bytes).decompress()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{BlockBuilder, BlockSigner};
+    use crate::keys::Seed;
+    use crate::types::Raw;
+
+    fn keypairs(count: u8) -> Vec<KeyPair> {
+        (1..=count)
+            .map(|i| Seed::from_bytes([i; 32]).derive(0))
+            .collect()
+    }
+
+    fn sign_all(keys: &[KeyPair], aggregate: &AggregateKey, hash: &BlockHash) -> Signature {
+        let secret: Vec<SecretNonce> = keys
+            .iter()
+            .map(|key| generate_nonce(key, aggregate, hash).unwrap())
+            .collect();
+        let nonces: Vec<PublicNonce> = secret.iter().map(SecretNonce::public).collect();
+        let partials: Vec<PartialSignature> = keys
+            .iter()
+            .zip(secret)
+            .map(|(key, nonce)| partial_sign(key, aggregate, nonce, &nonces, hash).unwrap())
+            .collect();
+        aggregate_signatures(aggregate, hash, &nonces, &partials).unwrap()
+    }
+
+    #[test]
+    fn test_aggregate_signature_verifies_as_block() {
+        let keys = keypairs(3);
+        let public: Vec<PublicKey> = keys.iter().map(|key| *key.public_key()).collect();
+        let aggregate = AggregateKey::new(&public).unwrap();
+
+        let reversed: Vec<PublicKey> = public.iter().rev().copied().collect();
+        assert_eq!(
+            AggregateKey::new(&reversed).unwrap().account(),
+            aggregate.account()
+        );
+
+        let builder = BlockBuilder::new()
+            .account(aggregate.account())
+            .previous(BlockHash::ZERO)
+            .representative(aggregate.account())
+            .balance(Raw::new(1))
+            .link_as_block(&BlockHash::from_bytes([2; 32]));
+        let hash = builder.hash().unwrap();
+        let signature = sign_all(&keys, &aggregate, &hash);
+
+        let block = builder.signature(signature).build().unwrap();
+        assert!(BlockSigner::verify(&block));
+        assert!(KeyPair::verify_with_public_key(
+            aggregate.public_key(),
+            &hash,
+            &signature
+        ));
+    }
+
+    #[test]
+    fn test_rejects_bad_partials() {
+        let keys = keypairs(2);
+        let outsider = Seed::from_bytes([9; 32]).derive(0);
+        let aggregate = AggregateKey::new(&[*keys[0].public_key(), *keys[1].public_key()]).unwrap();
+        let hash = BlockHash::from_bytes([7; 32]);
+
+        let nonce_a = generate_nonce(&keys[0], &aggregate, &hash).unwrap();
+        let nonce_b = generate_nonce(&keys[1], &aggregate, &hash).unwrap();
+        let nonces = [nonce_a.public(), nonce_b.public()];
+        assert!(nonces[0].verify_commitment(&nonces[0].commitment()).is_ok());
+        assert!(nonces[0]
+            .verify_commitment(&nonces[1].commitment())
+            .is_err());
+
+        let stray = generate_nonce(&outsider, &aggregate, &hash).unwrap();
+        assert_eq!(
+            partial_sign(&outsider, &aggregate, stray, &nonces, &hash).unwrap_err(),
+            Error::Multisig(MultisigError::NotParticipant)
+        );
+
+        let partial_a = partial_sign(&keys[0], &aggregate, nonce_a, &nonces, &hash).unwrap();
+        assert_eq!(
+            PartialSignature::from_bytes(&partial_a.to_bytes()),
+            partial_a
+        );
+        let other_hash = BlockHash::from_bytes([8; 32]);
+        let partial_b = partial_sign(&keys[1], &aggregate, nonce_b, &nonces, &other_hash).unwrap();
+        assert_eq!(
+            aggregate_signatures(&aggregate, &hash, &nonces, &[partial_a, partial_b]).unwrap_err(),
+            Error::Multisig(MultisigError::InvalidPartialSignature)
+        );
+        assert_eq!(
+            AggregateKey::new(&[*keys[0].public_key(), *keys[0].public_key()]).unwrap_err(),
+            Error::Multisig(MultisigError::DuplicateKey)
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Random number sources for seed generation.
+
+#[cfg(feature = "getrandom")]
+use crate::error::Error;
+use crate::error::Result;
+
+/// Source of cryptographically secure random bytes.
+///
+/// Implement this to generate seeds on targets without an operating system
+/// RNG, for example from a microcontroller's hardware TRNG, and pass it to
+/// [`Seed::random_with`](crate::keys::Seed::random_with).
+pub trait SeedRng {
+    /// Fill `dest` with random bytes.
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()>;
+}
+
+impl<R: SeedRng + ?Sized> SeedRng for &mut R {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+        (**self).fill_bytes(dest)
+    }
+}
+
+/// The operating system's random number generator.
+///
+/// Used by [`Seed::random`](crate::keys::Seed::random). Requires the
+/// `getrandom` feature, which `std` enables.
+#[cfg(feature = "getrandom")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRng;
+
+#[cfg(feature = "getrandom")]
+impl SeedRng for SystemRng {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+        getrandom::getrandom(dest).map_err(|_| Error::InvalidSeed)
+    }
+}
@@ -1,11 +1,19 @@
 //! Seed generation and management.
 
 use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::error::{Error, Result};
-use crate::keys::{derive_keypair, KeyPair};
+#[cfg(feature = "getrandom")]
+use crate::keys::SystemRng;
+use crate::keys::{
+    derive_keypair, derive_public_key, derive_public_range, derive_range, KeyPair, SeedRng,
+    WatchOnlyKeys,
+};
+use crate::security::SecretString;
+use crate::types::PublicKey;
 
 /// Nano wallet seed (32 bytes).
 ///
@@ -20,10 +28,17 @@ impl Seed {
     /// Create a new random seed.
     ///
     /// Uses the system's cryptographically secure random number generator.
-    #[cfg(any(feature = "std", feature = "wasm-rpc", feature = "wasm-websocket"))]
+    #[cfg(feature = "getrandom")]
     pub fn random() -> Result<Self> {
+        Self::random_with(&mut SystemRng)
+    }
+
+    /// Create a new random seed from a caller-supplied RNG.
+    ///
+    /// Use this on `no_std` targets without an operating system RNG.
+    pub fn random_with<R: SeedRng + ?Sized>(rng: &mut R) -> Result<Self> {
         let mut bytes = [0u8; 32];
-        getrandom::getrandom(&mut bytes).map_err(|_| Error::InvalidSeed)?;
+        rng.fill_bytes(&mut bytes)?;
         Ok(Seed(bytes))
     }
 
@@ -57,12 +72,47 @@ impl Seed {
         hex::encode_upper(self.0)
     }
 
+    /// Convert to a hex string that is wiped from memory when dropped.
+    ///
+    /// Prefer this over [`to_hex`](Self::to_hex) for backups and exports.
+    pub fn to_hex_secret(&self) -> SecretString {
+        SecretString::new(self.to_hex())
+    }
+
     /// Derive a keypair at the given index.
     ///
     /// Index 0 is the first account, index 1 is the second, etc.
     pub fn derive(&self, index: u32) -> KeyPair {
         derive_keypair(&self.0, index)
     }
+
+    /// Derive the keypairs for indices `start..end`, in parallel when
+    /// available; see [`derive_range`].
+    pub fn derive_range(&self, start: u32, end: u32) -> Vec<KeyPair> {
+        derive_range(&self.0, start, end)
+    }
+
+    /// Derive only the public key at the given index.
+    ///
+    /// Enough for displaying addresses; the private key is wiped right away.
+    pub fn derive_public(&self, index: u32) -> PublicKey {
+        derive_public_key(&self.0, index)
+    }
+
+    /// Derive only the public keys for indices `start..end`, so no secret
+    /// keys are kept around.
+    pub fn derive_public_keys(&self, start: u32, end: u32) -> Vec<PublicKey> {
+        derive_public_range(&self.0, start, end)
+    }
+
+    /// Export the public keys for `count` indices from `start`, to watch
+    /// the accounts without the seed.
+    pub fn watch_only(&self, start: u32, count: u32) -> WatchOnlyKeys {
+        WatchOnlyKeys::new(
+            start,
+            self.derive_public_keys(start, start.saturating_add(count)),
+        )
+    }
 }
 
 impl fmt::Debug for Seed {
@@ -129,7 +179,7 @@ mod tests {
         assert_eq!(keypair0.public_key(), keypair0_again.public_key());
     }
 
-    #[cfg(any(feature = "std", feature = "wasm-rpc", feature = "wasm-websocket"))]
+    #[cfg(feature = "getrandom")]
     #[test]
     fn test_seed_random() {
         let seed1 = Seed::random().unwrap();
@@ -139,6 +189,25 @@ mod tests {
         assert_ne!(seed1, seed2);
     }
 
+    #[test]
+    fn test_seed_random_with() {
+        struct Counter(u8);
+        impl SeedRng for Counter {
+            fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<()> {
+                for byte in dest {
+                    *byte = self.0;
+                    self.0 = self.0.wrapping_add(1);
+                }
+                Ok(())
+            }
+        }
+
+        let mut rng = Counter(0);
+        let seed = Seed::random_with(&mut rng).unwrap();
+        assert_eq!(seed.as_bytes()[31], 31);
+        assert_ne!(Seed::random_with(&mut rng).unwrap(), seed);
+    }
+
     #[test]
     fn test_seed_equality() {
         let seed1 = Seed::from_hex(TEST_SEED_HEX).unwrap();
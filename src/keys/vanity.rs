@@ -0,0 +1,487 @@
+//! Vanity address generation.
+//!
+//! Searches random keys in parallel until the resulting address matches a
+//! pattern. Patterns apply to the part of the address after `nano_`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xno_connect::keys::{VanityGenerator, VanityPattern};
+//!
+//! let pattern = VanityPattern::prefix("1abc").unwrap();
+//! let found = VanityGenerator::new(pattern)
+//!     .on_progress(|attempts| println!("{} keys tried", attempts))
+//!     .generate(None)
+//!     .unwrap()
+//!     .unwrap();
+//! println!("{}", found.keypair.account());
+//! ```
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use rayon::prelude::*;
+
+use crate::constants::BASE32_ALPHABET;
+use crate::error::{AccountError, Error, Result};
+use crate::keys::{KeyPair, Seed};
+
+/// Number of characters in an address after the prefix.
+const ADDRESS_BODY_LENGTH: usize = 60;
+
+/// Attempts each thread makes between progress and cancellation checks.
+const BATCH_SIZE: u64 = 256;
+
+/// One element of a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// Matches any character (`.`).
+    Any,
+    /// Matches one of the listed characters (a literal or `[...]`).
+    OneOf(Vec<u8>),
+}
+
+impl Token {
+    fn matches(&self, c: u8) -> bool {
+        match self {
+            Token::Any => true,
+            Token::OneOf(chars) => chars.contains(&c),
+        }
+    }
+}
+
+/// Pattern an address must match.
+///
+/// Besides [`prefix`](Self::prefix) and [`suffix`](Self::suffix), a small
+/// regex subset is supported by [`new`](Self::new): literal characters,
+/// `.` for any character, `[...]` character classes, and the `^` and `$`
+/// anchors. Without anchors the pattern may match anywhere.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VanityPattern {
+    tokens: Vec<Token>,
+    anchor_start: bool,
+    anchor_end: bool,
+}
+
+impl VanityPattern {
+    /// Parse a pattern.
+    ///
+    /// Returns an error if the pattern uses characters outside the Nano
+    /// base32 alphabet or cannot match any address.
+    pub fn new(pattern: &str) -> Result<Self> {
+        let invalid = || Error::InvalidAccount(AccountError::InvalidPattern);
+        let pattern = pattern.to_ascii_lowercase();
+        let mut body = pattern.as_str();
+
+        let anchor_start = body.starts_with('^');
+        if anchor_start {
+            body = &body[1..];
+        }
+        let anchor_end = body.ends_with('$');
+        if anchor_end {
+            body = &body[..body.len() - 1];
+        }
+
+        let mut tokens = Vec::new();
+        let mut chars = body.bytes();
+        while let Some(c) = chars.next() {
+            let token = match c {
+                b'.' => Token::Any,
+                b'[' => {
+                    let mut class = Vec::new();
+                    loop {
+                        match chars.next() {
+                            Some(b']') => break,
+                            Some(c) if BASE32_ALPHABET.contains(&c) => class.push(c),
+                            _ => return Err(invalid()),
+                        }
+                    }
+                    if class.is_empty() {
+                        return Err(invalid());
+                    }
+                    Token::OneOf(class)
+                }
+                c if BASE32_ALPHABET.contains(&c) => Token::OneOf(alloc::vec![c]),
+                _ => return Err(invalid()),
+            };
+            tokens.push(token);
+        }
+
+        if tokens.is_empty() || tokens.len() > ADDRESS_BODY_LENGTH {
+            return Err(invalid());
+        }
+
+        // The first character encodes only one bit, so it is '1' or '3'
+        if anchor_start && !(tokens[0].matches(b'1') || tokens[0].matches(b'3')) {
+            return Err(invalid());
+        }
+
+        Ok(VanityPattern {
+            tokens,
+            anchor_start,
+            anchor_end,
+        })
+    }
+
+    /// Match addresses starting with `prefix`, e.g. `"1abc"`.
+    pub fn prefix(prefix: &str) -> Result<Self> {
+        Self::literal(prefix, true, false)
+    }
+
+    /// Match addresses ending with `suffix`.
+    pub fn suffix(suffix: &str) -> Result<Self> {
+        Self::literal(suffix, false, true)
+    }
+
+    fn literal(text: &str, anchor_start: bool, anchor_end: bool) -> Result<Self> {
+        if text
+            .bytes()
+            .any(|c| matches!(c, b'.' | b'[' | b']' | b'^' | b'$'))
+        {
+            return Err(Error::InvalidAccount(AccountError::InvalidPattern));
+        }
+        let mut pattern = String::new();
+        if anchor_start {
+            pattern.push('^');
+        }
+        pattern.push_str(text);
+        if anchor_end {
+            pattern.push('$');
+        }
+        Self::new(&pattern)
+    }
+
+    /// Check if an address matches the pattern.
+    ///
+    /// Accepts the address with or without its `nano_`/`xno_` prefix.
+    pub fn matches(&self, address: &str) -> bool {
+        let body = address.rsplit('_').next().unwrap_or(address).as_bytes();
+        if body.len() < self.tokens.len() {
+            return false;
+        }
+
+        let last_start = body.len() - self.tokens.len();
+        let starts: Vec<usize> = match (self.anchor_start, self.anchor_end) {
+            (true, true) if body.len() != self.tokens.len() => return false,
+            (true, _) => alloc::vec![0],
+            (false, true) => alloc::vec![last_start],
+            (false, false) => (0..=last_start).collect(),
+        };
+
+        starts.into_iter().any(|start| {
+            self.tokens
+                .iter()
+                .zip(&body[start..])
+                .all(|(token, c)| token.matches(*c))
+        })
+    }
+
+    /// Expected number of attempts to find a match.
+    pub fn estimated_attempts(&self) -> f64 {
+        let mut attempts = 1.0;
+        for (i, token) in self.tokens.iter().enumerate() {
+            attempts *= match token {
+                Token::Any => 1.0,
+                // Only '1' and '3' can appear first
+                _ if self.anchor_start && i == 0 => {
+                    let possible = [b'1', b'3'].iter().filter(|c| token.matches(**c)).count();
+                    2.0 / possible as f64
+                }
+                Token::OneOf(chars) => 32.0 / chars.len() as f64,
+            };
+        }
+        if !self.anchor_start && !self.anchor_end {
+            attempts /= (ADDRESS_BODY_LENGTH - self.tokens.len() + 1) as f64;
+        }
+        attempts.max(1.0)
+    }
+}
+
+/// How candidate keys are produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VanityMode {
+    /// Random private keys.
+    #[default]
+    PrivateKey,
+    /// Random seeds, trying indices `0..max_index` of each.
+    Seed {
+        /// Number of indices tried per seed.
+        max_index: u32,
+    },
+}
+
+/// A key whose address matched the pattern.
+#[derive(Debug)]
+pub struct VanityMatch {
+    /// Matching keypair.
+    pub keypair: KeyPair,
+    /// Seed the keypair was derived from, in seed mode.
+    pub seed: Option<Seed>,
+    /// Derivation index, in seed mode.
+    pub index: Option<u32>,
+    /// Total keys tried across all threads.
+    pub attempts: u64,
+}
+
+type ProgressCallback = Arc<dyn Fn(u64) + Send + Sync>;
+
+/// Parallel vanity address search.
+pub struct VanityGenerator {
+    pattern: VanityPattern,
+    mode: VanityMode,
+    threads: usize,
+    progress: Option<ProgressCallback>,
+    progress_interval: u64,
+}
+
+impl VanityGenerator {
+    /// Create a generator for the given pattern.
+    pub fn new(pattern: VanityPattern) -> Self {
+        VanityGenerator {
+            pattern,
+            mode: VanityMode::default(),
+            threads: 0,
+            progress: None,
+            progress_interval: 100_000,
+        }
+    }
+
+    /// Set how candidate keys are produced.
+    pub fn with_mode(mut self, mode: VanityMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Set the number of threads to use.
+    ///
+    /// Use 0 for auto-detection (uses all available cores).
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Call `callback` with the total attempts so far, roughly every
+    /// [`progress_interval`](Self::progress_interval) attempts.
+    pub fn on_progress<F: Fn(u64) + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Set how many attempts pass between progress callbacks.
+    pub fn progress_interval(mut self, attempts: u64) -> Self {
+        self.progress_interval = attempts.max(1);
+        self
+    }
+
+    /// Search until a match is found.
+    ///
+    /// Returns `None` if `cancelled` is set before a match is found, and an
+    /// error if the random number generator fails.
+    pub fn generate(&self, cancelled: Option<&AtomicBool>) -> Result<Option<VanityMatch>> {
+        let threads = if self.threads == 0 {
+            rayon::current_num_threads()
+        } else {
+            self.threads
+        };
+        let found = AtomicBool::new(false);
+        let attempts = AtomicU64::new(0);
+        let next_report = AtomicU64::new(self.progress_interval);
+
+        let stop = || {
+            found.load(Ordering::Relaxed) || cancelled.is_some_and(|c| c.load(Ordering::Relaxed))
+        };
+
+        let result = (0..threads).into_par_iter().find_map_any(|_| {
+            while !stop() {
+                let candidate = match self.search_batch() {
+                    Ok(candidate) => candidate,
+                    Err(e) => {
+                        found.store(true, Ordering::Relaxed);
+                        return Some(Err(e));
+                    }
+                };
+                let total = attempts.fetch_add(BATCH_SIZE, Ordering::Relaxed) + BATCH_SIZE;
+                self.report_progress(total, &next_report);
+                if candidate.is_some() {
+                    found.store(true, Ordering::Relaxed);
+                    return candidate.map(Ok);
+                }
+            }
+            None
+        });
+
+        result.transpose().map(|found| {
+            found.map(|mut m| {
+                m.attempts = attempts.load(Ordering::Relaxed);
+                m
+            })
+        })
+    }
+
+    /// Try one batch of random candidates.
+    fn search_batch(&self) -> Result<Option<VanityMatch>> {
+        let mut base = [0u8; 32];
+        getrandom::getrandom(&mut base)
+            .map_err(|_| Error::InvalidAccount(AccountError::RandomUnavailable))?;
+
+        Ok(match self.mode {
+            VanityMode::PrivateKey => (0..BATCH_SIZE).find_map(|i| {
+                let keypair = KeyPair::from_private_key(vary(&base, i));
+                self.pattern
+                    .matches(keypair.account().as_str())
+                    .then_some(VanityMatch {
+                        keypair,
+                        seed: None,
+                        index: None,
+                        attempts: 0,
+                    })
+            }),
+            VanityMode::Seed { max_index } => {
+                let max_index = max_index.max(1);
+                let seeds = (BATCH_SIZE / max_index as u64).max(1);
+                (0..seeds).find_map(|i| {
+                    let seed = Seed::from_bytes(vary(&base, i));
+                    (0..max_index).find_map(|index| {
+                        let keypair = seed.derive(index);
+                        self.pattern
+                            .matches(keypair.account().as_str())
+                            .then(|| VanityMatch {
+                                keypair,
+                                seed: Some(seed.clone()),
+                                index: Some(index),
+                                attempts: 0,
+                            })
+                    })
+                })
+            }
+        })
+    }
+
+    fn report_progress(&self, total: u64, next_report: &AtomicU64) {
+        let Some(callback) = &self.progress else {
+            return;
+        };
+        let due = next_report.load(Ordering::Relaxed);
+        if total >= due
+            && next_report
+                .compare_exchange(
+                    due,
+                    total + self.progress_interval,
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_ok()
+        {
+            callback(total);
+        }
+    }
+}
+
+/// Derive the `i`-th candidate from random base bytes.
+fn vary(base: &[u8; 32], i: u64) -> [u8; 32] {
+    let mut bytes = *base;
+    for (byte, offset) in bytes[24..].iter_mut().zip(i.to_le_bytes()) {
+        *byte ^= offset;
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(VanityPattern::prefix("3t6k").unwrap().matches(ADDRESS));
+        assert!(!VanityPattern::prefix("3t6j").unwrap().matches(ADDRESS));
+        assert!(VanityPattern::suffix("ohr3").unwrap().matches(ADDRESS));
+        assert!(VanityPattern::new("mnij").unwrap().matches(ADDRESS));
+        assert!(VanityPattern::new("^[13]t.k").unwrap().matches(ADDRESS));
+        assert!(!VanityPattern::new("^1").unwrap().matches(ADDRESS));
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        // 'l', 'v', '0' and '2' are not in the alphabet
+        assert!(VanityPattern::new("hello").is_err());
+        assert!(VanityPattern::prefix("4abc").is_err());
+        assert!(VanityPattern::new("[ab").is_err());
+        assert!(VanityPattern::new("").is_err());
+        assert!(VanityPattern::prefix("a.c").is_err());
+    }
+
+    #[test]
+    fn test_estimated_attempts() {
+        assert_eq!(
+            VanityPattern::prefix("1").unwrap().estimated_attempts(),
+            2.0
+        );
+        assert_eq!(
+            VanityPattern::prefix("1a").unwrap().estimated_attempts(),
+            64.0
+        );
+        assert_eq!(
+            VanityPattern::suffix("a").unwrap().estimated_attempts(),
+            32.0
+        );
+    }
+
+    #[test]
+    fn test_generate_private_key() {
+        let pattern = VanityPattern::prefix("1a").unwrap();
+        let found = VanityGenerator::new(pattern.clone())
+            .generate(None)
+            .unwrap()
+            .unwrap();
+
+        assert!(pattern.matches(found.keypair.account().as_str()));
+        assert!(found.seed.is_none());
+        assert!(found.attempts > 0);
+    }
+
+    #[test]
+    fn test_generate_seed_mode() {
+        let pattern = VanityPattern::suffix("a").unwrap();
+        let found = VanityGenerator::new(pattern.clone())
+            .with_mode(VanityMode::Seed { max_index: 4 })
+            .generate(None)
+            .unwrap()
+            .unwrap();
+
+        let seed = found.seed.unwrap();
+        let index = found.index.unwrap();
+        assert!(index < 4);
+        assert_eq!(seed.derive(index).account(), found.keypair.account());
+        assert!(pattern.matches(found.keypair.account().as_str()));
+    }
+
+    #[test]
+    fn test_cancel_and_progress() {
+        let calls = Arc::new(AtomicU64::new(0));
+        let counter = calls.clone();
+        let cancelled = AtomicBool::new(true);
+
+        let result = VanityGenerator::new(VanityPattern::prefix("1aaaaaaaaaa").unwrap())
+            .on_progress(move |_| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            })
+            .generate(Some(&cancelled))
+            .unwrap();
+        assert!(result.is_none());
+
+        let generator = VanityGenerator::new(VanityPattern::prefix("1").unwrap())
+            .progress_interval(1)
+            .on_progress({
+                let counter = calls.clone();
+                move |_| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }
+            });
+        assert!(generator.generate(None).unwrap().is_some());
+        assert!(calls.load(Ordering::Relaxed) > 0);
+    }
+}
@@ -0,0 +1,182 @@
+//! Watch-only public key sets.
+
+#[cfg(feature = "serde")]
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::error::{Error, Result, StorageError};
+use crate::types::{Account, PublicKey};
+
+/// Public keys for a range of seed indices, without the seed.
+///
+/// Nano derives each private key by hashing the seed with the index, so
+/// unlike BIP32 there is no extended public key: new addresses cannot be
+/// derived without the seed. Instead, export as many public keys as the
+/// online side will need with [`Seed::watch_only`](crate::keys::Seed::watch_only)
+/// and move them over as JSON.
+///
+/// # Example
+///
+/// ```
+/// use xno_connect::keys::{Seed, WatchOnlyKeys};
+///
+/// # fn main() -> xno_connect::error::Result<()> {
+/// let seed = Seed::from_bytes([1u8; 32]);
+/// let json = seed.watch_only(0, 100).to_json()?;
+///
+/// // On the online machine, with no seed:
+/// let keys = WatchOnlyKeys::from_json(&json)?;
+/// assert_eq!(keys.account(5), Some(seed.derive(5).account()));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchOnlyKeys {
+    start: u32,
+    keys: Vec<PublicKey>,
+}
+
+/// JSON form of [`WatchOnlyKeys`].
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct WatchOnlyState {
+    start: u32,
+    accounts: Vec<Account>,
+}
+
+impl WatchOnlyKeys {
+    /// Create a set whose first key is at index `start`.
+    pub fn new(start: u32, keys: Vec<PublicKey>) -> Self {
+        WatchOnlyKeys { start, keys }
+    }
+
+    /// Get the first index covered.
+    pub fn start(&self) -> u32 {
+        self.start
+    }
+
+    /// Get the index after the last one covered.
+    pub fn end(&self) -> u32 {
+        self.start.saturating_add(self.keys.len() as u32)
+    }
+
+    /// Number of keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Check if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Get the public key at a seed index, if covered.
+    pub fn public_key(&self, index: u32) -> Option<&PublicKey> {
+        let offset = index.checked_sub(self.start)?;
+        self.keys.get(offset as usize)
+    }
+
+    /// Get the account at a seed index, if covered.
+    pub fn account(&self, index: u32) -> Option<Account> {
+        self.public_key(index).map(Account::from_public_key)
+    }
+
+    /// Get all accounts, in index order.
+    pub fn accounts(&self) -> Vec<Account> {
+        self.keys.iter().map(Account::from_public_key).collect()
+    }
+
+    /// Get the seed index of an account, if it is in the set.
+    pub fn index_of(&self, account: &Account) -> Option<u32> {
+        let offset = self
+            .keys
+            .iter()
+            .position(|key| key == account.public_key())?;
+        self.start.checked_add(u32::try_from(offset).ok()?)
+    }
+
+    /// Serialize to JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        let state = WatchOnlyState {
+            start: self.start,
+            accounts: self.accounts(),
+        };
+        serde_json::to_string_pretty(&state)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))
+    }
+
+    /// Deserialize from JSON produced by [`to_json`](Self::to_json).
+    ///
+    /// Fails if the keys would run past the last seed index.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        let state: WatchOnlyState = serde_json::from_str(json)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))?;
+        if state.accounts.len() as u64 > u64::from(u32::MAX - state.start) + 1 {
+            return Err(Error::Storage(StorageError::Serialization(
+                "watch-only keys run past the last seed index".to_string(),
+            )));
+        }
+        Ok(WatchOnlyKeys {
+            start: state.start,
+            keys: state
+                .accounts
+                .iter()
+                .map(|account| *account.public_key())
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::Seed;
+
+    #[test]
+    fn test_lookup_and_round_trip() {
+        let seed = Seed::from_bytes([4u8; 32]);
+        let keys: WatchOnlyKeys = seed.watch_only(10, 5);
+        assert_eq!((keys.start(), keys.end(), keys.len()), (10, 15, 5));
+        assert_eq!(keys.account(12), Some(seed.derive(12).account()));
+        assert_eq!(keys.account(9), None);
+        assert_eq!(keys.account(15), None);
+        assert_eq!(keys.index_of(&seed.derive(14).account()), Some(14));
+        assert_eq!(keys.index_of(&seed.derive(3).account()), None);
+
+        #[cfg(feature = "serde")]
+        {
+            let imported = WatchOnlyKeys::from_json(&keys.to_json().unwrap()).unwrap();
+            assert_eq!(imported, keys);
+        }
+    }
+
+    #[test]
+    fn test_index_near_end_of_range() {
+        let seed = Seed::from_bytes([4u8; 32]);
+        let keys = WatchOnlyKeys::new(
+            u32::MAX,
+            vec![*seed.derive(u32::MAX).account().public_key()],
+        );
+        assert_eq!(
+            keys.index_of(&seed.derive(u32::MAX).account()),
+            Some(u32::MAX)
+        );
+
+        #[cfg(feature = "serde")]
+        {
+            let json = keys.to_json().unwrap();
+            assert_eq!(WatchOnlyKeys::from_json(&json).unwrap(), keys);
+
+            let overflowing = WatchOnlyKeys::new(u32::MAX, vec![PublicKey::ZERO; 2]);
+            assert!(matches!(
+                WatchOnlyKeys::from_json(&overflowing.to_json().unwrap()),
+                Err(Error::Storage(StorageError::Serialization(_)))
+            ));
+        }
+    }
+}
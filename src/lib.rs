@@ -12,6 +12,19 @@
 //! - **Work Generation**: Generate PoW locally or via external work servers
 //! - **WASM Support**: Optional WebAssembly support for browser environments
 //!
+//! ## Stability
+//!
+//! - **Stable**: `keys`, `types`, `blocks`, `error`, `network`, `rpc`,
+//!   `websocket`, `wallet` and `work`. Breaking changes only happen in a
+//!   major release, after a release in which the old API is deprecated.
+//! - **Experimental**: `alias`, `analytics`, `annotation`, `archive`,
+//!   `bindings`, `fiat`, `receipt`, `reps`, `security`, `signing`,
+//!   `tagging`, `testkit`, `testnet`, `vote`, vanity generation, the work
+//!   server and the streaming wallet helpers.
+//!   These may change in any minor release.
+//! - **Deprecated**: the `*_local` wallet methods. Set a CPU work provider
+//!   with `WalletAccount::with_work_provider` and call the plain methods.
+//!
 //! ## Example
 //!
 //! ```rust,no_run
@@ -33,10 +46,34 @@
 
 extern crate alloc;
 
+pub mod alias;
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+pub mod analytics;
+#[cfg(feature = "serde")]
+pub mod annotation;
+#[cfg(feature = "serde")]
+pub mod archive;
+#[cfg(all(feature = "bindings", target_arch = "wasm32"))]
+pub mod bindings;
 pub mod blocks;
+mod build_info;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod fiat;
 pub mod keys;
+pub mod metrics;
+pub mod network;
+#[cfg(feature = "serde")]
+pub mod receipt;
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+pub mod reps;
+pub mod security;
+pub mod signing;
+pub mod tagging;
+mod trace;
 pub mod types;
+pub mod uri;
+pub mod vote;
 pub mod work;
 
 #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
@@ -45,17 +82,22 @@ pub mod rpc;
 #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
 pub mod websocket;
 
+#[cfg(feature = "testkit")]
+pub mod testkit;
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+pub mod testnet;
+
 pub mod wallet;
 
 /// Prelude module for convenient imports.
 pub mod prelude {
-    pub use crate::blocks::{BlockBuilder, BlockHasher};
+    pub use crate::blocks::{AsyncSigner, BlockBuilder, BlockHasher};
     pub use crate::error::{Error, Result};
     pub use crate::keys::{KeyPair, SecretKey, Seed};
     pub use crate::types::{
         Account, Amount, BlockHash, PublicKey, Raw, Signature, StateBlock, Subtype, Work,
     };
-    pub use crate::work::{WorkThreshold, WorkValidator};
+    pub use crate::work::{WorkGenerator, WorkThreshold, WorkValidator};
 
     #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
     pub use crate::rpc::RpcClient;
@@ -66,6 +108,7 @@ pub mod prelude {
     pub use crate::wallet::Wallet;
 }
 
+pub use build_info::{build_info, BuildInfo};
 pub use error::{Error, Result};
 
 /// Nano network constants.
@@ -91,6 +134,24 @@ pub mod constants {
     /// Epoch v2 work threshold for receive blocks.
     pub const WORK_THRESHOLD_EPOCH_2_RECEIVE: u64 = 0xfffffe0000000000;
 
+    /// Dev network work threshold for send blocks.
+    pub const WORK_THRESHOLD_DEV_SEND: u64 = 0xffc0000000000000;
+
+    /// Dev network work threshold for receive blocks.
+    pub const WORK_THRESHOLD_DEV_RECEIVE: u64 = 0xf000000000000000;
+
+    /// Beta network work threshold for send blocks.
+    pub const WORK_THRESHOLD_BETA_SEND: u64 = 0xfffff00000000000;
+
+    /// Beta network work threshold for receive blocks.
+    pub const WORK_THRESHOLD_BETA_RECEIVE: u64 = 0xffffe00000000000;
+
+    /// Epoch v1 work threshold (all block types).
+    pub const WORK_THRESHOLD_EPOCH_1: u64 = 0xffffffc000000000;
+
+    /// Account version reported by `account_info` for epoch v2 accounts.
+    pub const ACCOUNT_VERSION_EPOCH_2: u8 = 2;
+
     /// Maximum raw supply (2^128 - 1).
     pub const MAX_SUPPLY_RAW: u128 = 340282366920938463463374607431768211455;
 
@@ -0,0 +1,166 @@
+//! Prometheus-compatible metrics.
+//!
+//! With the `metrics` feature the crate records the metrics below through the
+//! [`metrics`](https://docs.rs/metrics) facade. Install any recorder, such as
+//! `metrics-exporter-prometheus`, to export them, and call [`describe`] once
+//! to register units and help texts. Without the feature nothing is recorded.
+//!
+//! | Name | Type | Labels |
+//! |------|------|--------|
+//! | [`RPC_REQUESTS`] | counter | `action` |
+//! | [`RPC_ERRORS`] | counter | `action`, `kind` |
+//! | [`RPC_REQUEST_DURATION`] | histogram | `action` |
+//! | [`WORK_GENERATION_DURATION`] | histogram | `source` |
+//! | [`WEBSOCKET_MESSAGES`] | counter | `topic` |
+
+// Without the feature the recording functions ignore their arguments.
+#![cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::error::Error;
+#[cfg(all(feature = "metrics", any(feature = "rpc", feature = "wasm-rpc")))]
+use crate::error::RpcError;
+#[cfg(any(feature = "rpc", feature = "wasm-rpc", feature = "work-cpu"))]
+use crate::trace::Stopwatch;
+
+/// RPC requests sent, including those answered from the cache.
+pub const RPC_REQUESTS: &str = "xno_rpc_requests_total";
+/// Failed RPC requests. `kind` is one of `connection_failed`, `timeout`,
+/// `invalid_response`, `node_error`, `http_status`, `invalid_config` or
+/// `other`.
+pub const RPC_ERRORS: &str = "xno_rpc_errors_total";
+/// RPC request latency in seconds.
+pub const RPC_REQUEST_DURATION: &str = "xno_rpc_request_duration_seconds";
+/// Successful work generation time in seconds. `source` is `cpu`, `gpu`,
+/// `node`, `external`, `cache` or `distributed`.
+pub const WORK_GENERATION_DURATION: &str = "xno_work_generation_duration_seconds";
+/// WebSocket messages received from the node.
+pub const WEBSOCKET_MESSAGES: &str = "xno_websocket_messages_total";
+
+/// Register units and descriptions with the installed recorder.
+#[cfg(feature = "metrics")]
+pub fn describe() {
+    use ::metrics::{describe_counter, describe_histogram, Unit};
+
+    describe_counter!(RPC_REQUESTS, "RPC requests sent to the node");
+    describe_counter!(RPC_ERRORS, "RPC requests that failed");
+    describe_histogram!(RPC_REQUEST_DURATION, Unit::Seconds, "RPC request latency");
+    describe_histogram!(
+        WORK_GENERATION_DURATION,
+        Unit::Seconds,
+        "Time to generate proof of work"
+    );
+    describe_counter!(WEBSOCKET_MESSAGES, "WebSocket messages received");
+}
+
+/// Record a finished RPC request.
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+pub(crate) fn rpc_request(action: &str, stopwatch: &Stopwatch, error: Option<&Error>) {
+    #[cfg(feature = "metrics")]
+    {
+        let action = alloc::string::String::from(action);
+        ::metrics::counter!(RPC_REQUESTS, "action" => action.clone()).increment(1);
+        if let Some(seconds) = stopwatch.elapsed_secs() {
+            ::metrics::histogram!(RPC_REQUEST_DURATION, "action" => action.clone()).record(seconds);
+        }
+        if let Some(error) = error {
+            ::metrics::counter!(RPC_ERRORS, "action" => action, "kind" => rpc_error_kind(error))
+                .increment(1);
+        }
+    }
+}
+
+/// Label for an RPC failure.
+#[cfg(all(feature = "metrics", any(feature = "rpc", feature = "wasm-rpc")))]
+fn rpc_error_kind(error: &Error) -> &'static str {
+    match error {
+        Error::Rpc(RpcError::ConnectionFailed(_)) => "connection_failed",
+        Error::Rpc(RpcError::Timeout) => "timeout",
+        Error::Rpc(RpcError::InvalidResponse(_)) => "invalid_response",
+        Error::Rpc(RpcError::SchemaMismatch { .. }) => "schema_mismatch",
+        Error::Rpc(RpcError::NodeError(_)) => "node_error",
+        Error::Rpc(RpcError::HttpStatus(_)) => "http_status",
+        Error::Rpc(RpcError::InvalidConfig(_)) => "invalid_config",
+        _ => "other",
+    }
+}
+
+/// Record successfully generated work.
+#[cfg(any(feature = "rpc", feature = "work-cpu"))]
+pub(crate) fn work_generated(source: &'static str, stopwatch: &Stopwatch) {
+    #[cfg(feature = "metrics")]
+    if let Some(seconds) = stopwatch.elapsed_secs() {
+        ::metrics::histogram!(WORK_GENERATION_DURATION, "source" => source).record(seconds);
+    }
+}
+
+/// Record a WebSocket message received on `topic`.
+#[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+pub(crate) fn websocket_message(topic: &str) {
+    #[cfg(feature = "metrics")]
+    ::metrics::counter!(WEBSOCKET_MESSAGES, "topic" => alloc::string::String::from(topic))
+        .increment(1);
+}
+
+#[cfg(all(test, feature = "metrics", feature = "work-cpu"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use metrics::{
+        Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString,
+        Unit,
+    };
+
+    use crate::types::BlockHash;
+    use crate::work::CpuWorkGenerator;
+
+    /// Keeps the keys of recorded histogram samples.
+    #[derive(Default)]
+    struct KeyRecorder(Arc<Mutex<Vec<String>>>);
+
+    struct KeyHistogram(Key, Arc<Mutex<Vec<String>>>);
+
+    impl HistogramFn for KeyHistogram {
+        fn record(&self, _: f64) {
+            let labels: Vec<_> = self
+                .0
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            self.1
+                .lock()
+                .unwrap()
+                .push(format!("{}{{{}}}", self.0.name(), labels.join(",")));
+        }
+    }
+
+    impl Recorder for KeyRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::noop()
+        }
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(Arc::new(KeyHistogram(key.clone(), self.0.clone())))
+        }
+    }
+
+    #[test]
+    fn test_work_generation_records_duration() {
+        let recorder = KeyRecorder::default();
+        let samples = recorder.0.clone();
+        metrics::with_local_recorder(&recorder, || {
+            CpuWorkGenerator::new()
+                .generate(&BlockHash::ZERO, 0xff00_0000_0000_0000, None)
+                .unwrap();
+        });
+        assert_eq!(
+            *samples.lock().unwrap(),
+            ["xno_work_generation_duration_seconds{source=cpu}"]
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Network profiles.
+//!
+//! A [`Network`] bundles the parameters that differ between the live, beta,
+//! test and local development networks, such as work thresholds, the genesis
+//! account and default ports.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::network::Network;
+//!
+//! let dev = Network::DEV;
+//! assert!(dev.work_threshold.send < Network::LIVE.work_threshold.send);
+//! println!("Dev genesis: {}", dev.genesis_account().unwrap());
+//! ```
+
+use crate::blocks::BlockHasher;
+use crate::constants::ACCOUNT_PREFIX_NANO;
+use crate::error::Result;
+use crate::types::{Account, Block, BlockHash, OpenBlock, Signature, Work};
+use crate::work::WorkThreshold;
+
+/// Secret key of the dev network genesis account.
+///
+/// This key is public knowledge and only holds funds on local dev networks.
+pub const DEV_GENESIS_SECRET_KEY: &str =
+    "34F0A37AAD20F4A260F0A5B3CB3D7FB50673212263E58A380BC10474BB039CE4";
+
+/// Parameters of a Nano network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Network {
+    /// Network name as accepted by the node's `--network` flag.
+    pub name: &'static str,
+    /// Genesis account address.
+    pub genesis: &'static str,
+    /// Work thresholds for epoch v2 blocks.
+    pub work_threshold: WorkThreshold,
+    /// Address prefix used by the network.
+    pub address_prefix: &'static str,
+    /// Default node peering port.
+    pub peering_port: u16,
+    /// Default node RPC port.
+    pub rpc_port: u16,
+    /// Default node WebSocket port.
+    pub websocket_port: u16,
+}
+
+impl Network {
+    /// The live network.
+    pub const LIVE: Network = Network {
+        name: "live",
+        genesis: "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+        work_threshold: WorkThreshold::MAINNET,
+        address_prefix: ACCOUNT_PREFIX_NANO,
+        peering_port: 7075,
+        rpc_port: 7076,
+        websocket_port: 7078,
+    };
+
+    /// The public beta network.
+    pub const BETA: Network = Network {
+        name: "beta",
+        genesis: "nano_1betag7az9wk6rbis38s1d35hdsycz1bi95xg4g4j148p6afjk7embcurda4",
+        work_threshold: WorkThreshold::BETA,
+        address_prefix: ACCOUNT_PREFIX_NANO,
+        peering_port: 54000,
+        rpc_port: 55000,
+        websocket_port: 57000,
+    };
+
+    /// The public test network, which uses live work thresholds.
+    pub const TEST: Network = Network {
+        name: "test",
+        genesis: "nano_1jg8zygjg3pp5w644emqcbmjqpnzmubfni3kfe1s8pooeuxsw49fdq1mco9j",
+        work_threshold: WorkThreshold::MAINNET,
+        address_prefix: ACCOUNT_PREFIX_NANO,
+        peering_port: 17075,
+        rpc_port: 17076,
+        websocket_port: 17078,
+    };
+
+    /// The local dev network, with much lower work thresholds.
+    pub const DEV: Network = Network {
+        name: "dev",
+        genesis: "nano_3e3j5tkog48pnny9dmfzj1r16pg8t1e76dz5tmac6iq689wyjfpiij4txtdo",
+        work_threshold: WorkThreshold::DEV,
+        address_prefix: ACCOUNT_PREFIX_NANO,
+        peering_port: 44000,
+        rpc_port: 45000,
+        websocket_port: 47000,
+    };
+
+    /// Create a custom network profile.
+    ///
+    /// The address prefix and default ports are taken from [`Network::LIVE`].
+    pub const fn new(
+        name: &'static str,
+        genesis: &'static str,
+        work_threshold: WorkThreshold,
+    ) -> Self {
+        Network {
+            name,
+            genesis,
+            work_threshold,
+            address_prefix: ACCOUNT_PREFIX_NANO,
+            peering_port: Self::LIVE.peering_port,
+            rpc_port: Self::LIVE.rpc_port,
+            websocket_port: Self::LIVE.websocket_port,
+        }
+    }
+
+    /// Look up a built-in network by its `--network` name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "live" => Some(Self::LIVE),
+            "beta" => Some(Self::BETA),
+            "test" => Some(Self::TEST),
+            "dev" => Some(Self::DEV),
+            _ => None,
+        }
+    }
+
+    /// Set the default ports.
+    pub const fn with_ports(mut self, peering: u16, rpc: u16, websocket: u16) -> Self {
+        self.peering_port = peering;
+        self.rpc_port = rpc;
+        self.websocket_port = websocket;
+        self
+    }
+
+    /// Set the work thresholds.
+    pub const fn with_work_threshold(mut self, work_threshold: WorkThreshold) -> Self {
+        self.work_threshold = work_threshold;
+        self
+    }
+
+    /// Get the genesis account.
+    ///
+    /// Fails if a custom profile was given an invalid genesis address.
+    pub fn genesis_account(&self) -> Result<Account> {
+        self.genesis.parse()
+    }
+
+    /// Get the hash of the genesis open block.
+    ///
+    /// The genesis block opens the genesis account with its own public key as
+    /// source and representative, so its hash follows from the account alone.
+    pub fn genesis_hash(&self) -> Result<BlockHash> {
+        let account = self.genesis_account()?;
+        let open = OpenBlock {
+            block_type: "open".into(),
+            source: BlockHash::from_bytes(*account.public_key().as_bytes()),
+            representative: account.clone(),
+            account,
+            signature: Signature::from_bytes([0u8; 64]),
+            work: Work::ZERO,
+        };
+        Ok(BlockHasher::hash_block(&Block::Open(open)))
+    }
+
+    /// Default RPC URL for a node running on localhost.
+    pub fn local_rpc_url(&self) -> alloc::string::String {
+        alloc::format!("http://localhost:{}", self.rpc_port)
+    }
+
+    /// Default WebSocket URL for a node running on localhost.
+    pub fn local_websocket_url(&self) -> alloc::string::String {
+        alloc::format!("ws://localhost:{}", self.websocket_port)
+    }
+}
+
+impl Default for Network {
+    fn default() -> Self {
+        Self::LIVE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::{KeyPair, SecretKey};
+
+    #[test]
+    fn test_genesis_accounts_parse() {
+        assert_eq!(
+            Network::LIVE.genesis_account().unwrap().as_str(),
+            Network::LIVE.genesis
+        );
+        assert_eq!(
+            Network::DEV.genesis_account().unwrap().as_str(),
+            Network::DEV.genesis
+        );
+    }
+
+    #[test]
+    fn test_dev_genesis_key_matches_account() {
+        let secret = SecretKey::from_hex(DEV_GENESIS_SECRET_KEY).unwrap();
+        let keypair = KeyPair::from_secret_key(secret);
+        assert_eq!(keypair.account(), Network::DEV.genesis_account().unwrap());
+    }
+
+    #[test]
+    fn test_genesis_hash() {
+        assert_eq!(
+            Network::LIVE.genesis_hash().unwrap().to_hex(),
+            "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+        );
+    }
+
+    #[test]
+    fn test_presets() {
+        for network in [Network::LIVE, Network::BETA, Network::TEST, Network::DEV] {
+            assert_eq!(Network::from_name(network.name), Some(network));
+            assert!(network
+                .genesis_account()
+                .unwrap()
+                .as_str()
+                .starts_with(network.address_prefix));
+        }
+        assert!(Network::from_name("regtest").is_none());
+        assert_eq!(Network::BETA.work_threshold, WorkThreshold::BETA);
+        assert_eq!(Network::DEV.local_rpc_url(), "http://localhost:45000");
+    }
+
+    #[test]
+    fn test_custom_network() {
+        let network = Network::new("regtest", Network::DEV.genesis, WorkThreshold::DEV)
+            .with_work_threshold(WorkThreshold {
+                send: 0,
+                receive: 0,
+            });
+        assert_eq!(network.name, "regtest");
+        assert_eq!(network.work_threshold.send, 0);
+        assert_eq!(network.rpc_port, 7076);
+        assert_eq!(network.with_ports(1, 2, 3).websocket_port, 3);
+        assert_eq!(Network::default(), Network::LIVE);
+
+        let invalid = Network::new("regtest", "nano_invalid", WorkThreshold::DEV);
+        assert!(invalid.genesis_account().is_err());
+        assert!(invalid.genesis_hash().is_err());
+    }
+}
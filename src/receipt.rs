@@ -0,0 +1,385 @@
+//! Verifiable payment receipts.
+//!
+//! A [`Receipt`] bundles a confirmed block with its confirmation metadata and,
+//! optionally, the final votes observed for it. Receipts serialize to JSON and
+//! can be checked later, without a node, via [`Receipt::verify`].
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::blocks::create_send_block;
+//! use xno_connect::keys::Seed;
+//! use xno_connect::receipt::Receipt;
+//! use xno_connect::types::{BlockHash, Raw};
+//! use xno_connect::vote::{Vote, WeightTable, FINAL_VOTE_TIMESTAMP};
+//!
+//! # fn main() -> xno_connect::error::Result<()> {
+//! let seed = Seed::from_hex("0000000000000000000000000000000000000000000000000000000000000000")?;
+//! let sender = seed.derive(0);
+//! let rep = seed.derive(1);
+//! let block = create_send_block(
+//!     &sender,
+//!     BlockHash::ZERO,
+//!     rep.account(),
+//!     Raw::new(100),
+//!     Raw::new(10),
+//!     &rep.account(),
+//!     None,
+//! );
+//!
+//! let receipt = Receipt::new(block)?;
+//! let vote = Vote::sign(&rep, vec![receipt.hash], FINAL_VOTE_TIMESTAMP);
+//! let receipt = receipt.with_vote(vote);
+//!
+//! let json = receipt.to_json()?;
+//! let weights = WeightTable::new().with_weight(&rep.account(), Raw::new(1000));
+//! Receipt::from_json(&json)?.verify(&weights)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::{BlockHasher, BlockSigner};
+use crate::error::{BlockError, Error, ReceiptError, Result};
+use crate::types::{BlockHash, Raw, Signature, StateBlock};
+use crate::vote::{Vote, VoteArchive, WeightTable};
+
+#[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+use crate::types::{Link, Subtype};
+#[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+use crate::websocket::ConfirmationMessage;
+
+/// Confirmation metadata reported by the node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationInfo {
+    /// Confirmation type (e.g. `active_quorum`).
+    pub confirmation_type: String,
+    /// Election duration in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<String>,
+    /// Number of voters in the election.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub voters: Option<String>,
+    /// Final tally for the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tally: Option<Raw>,
+}
+
+/// Proof bundle for a confirmed block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Receipt {
+    /// Block contents.
+    pub block: StateBlock,
+    /// Block hash.
+    pub hash: BlockHash,
+    /// Block signature.
+    pub signature: Signature,
+    /// Confirmation metadata, if observed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmation: Option<ConfirmationInfo>,
+    /// Votes observed for the block.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub votes: Vec<Vote>,
+}
+
+impl Receipt {
+    /// Create a receipt for a signed block.
+    pub fn new(block: StateBlock) -> Result<Self> {
+        let signature = block
+            .signature
+            .ok_or(Error::InvalidBlock(BlockError::MissingField("signature")))?;
+        Ok(Receipt {
+            hash: BlockHasher::hash_state_block(&block),
+            signature,
+            block,
+            confirmation: None,
+            votes: Vec::new(),
+        })
+    }
+
+    /// Create a receipt from a WebSocket confirmation.
+    ///
+    /// The subscription must include block contents.
+    #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+    pub fn from_confirmation(confirmation: &ConfirmationMessage) -> Result<Self> {
+        let contents = confirmation
+            .block
+            .as_ref()
+            .ok_or(Error::InvalidBlock(BlockError::MissingField("block")))?;
+
+        let subtype = match contents.subtype.as_deref() {
+            Some("send") => Some(Subtype::Send),
+            Some("receive") => Some(Subtype::Receive),
+            Some("open") => Some(Subtype::Open),
+            Some("change") => Some(Subtype::Change),
+            Some("epoch") => Some(Subtype::Epoch),
+            _ => None,
+        };
+        let mut block = StateBlock::new(
+            contents.account.clone(),
+            contents.previous,
+            contents.representative.clone(),
+            contents.balance,
+            Link::from_hex(&contents.link)?,
+        )
+        .with_signature(contents.signature)
+        .with_work(contents.work);
+        block.subtype = subtype;
+
+        let election = confirmation.election_info.as_ref();
+        let mut receipt = Receipt::new(block)?;
+        receipt.hash = confirmation.hash;
+        receipt.confirmation = Some(ConfirmationInfo {
+            confirmation_type: confirmation.confirmation_type.clone(),
+            duration: election.map(|e| e.duration.clone()),
+            voters: election.map(|e| e.voters.clone()),
+            tally: election.and_then(|e| e.tally),
+        });
+        Ok(receipt)
+    }
+
+    /// Attach confirmation metadata.
+    pub fn with_confirmation(mut self, confirmation: ConfirmationInfo) -> Self {
+        self.confirmation = Some(confirmation);
+        self
+    }
+
+    /// Attach an observed vote.
+    pub fn with_vote(mut self, vote: Vote) -> Self {
+        self.votes.push(vote);
+        self
+    }
+
+    /// Attach the final votes stored in an archive for this block.
+    ///
+    /// Votes already present on the receipt are not duplicated.
+    pub fn attach_votes(&mut self, archive: &impl VoteArchive) -> Result<()> {
+        for vote in archive.final_votes_for(&self.hash)? {
+            if !self.votes.contains(&vote) {
+                self.votes.push(vote);
+            }
+        }
+        Ok(())
+    }
+
+    /// Serialize to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self)
+            .map_err(|e| Error::InvalidReceipt(ReceiptError::Serialization(e.to_string())))
+    }
+
+    /// Deserialize from JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::InvalidReceipt(ReceiptError::Serialization(e.to_string())))
+    }
+
+    /// Verify the block hash and signature.
+    pub fn verify_block(&self) -> Result<()> {
+        if BlockHasher::hash_state_block(&self.block) != self.hash {
+            return Err(Error::InvalidReceipt(ReceiptError::HashMismatch));
+        }
+        if self.block.signature != Some(self.signature) || !BlockSigner::verify(&self.block) {
+            return Err(Error::InvalidReceipt(ReceiptError::InvalidBlockSignature));
+        }
+        Ok(())
+    }
+
+    /// Verify the block and that its final votes reach quorum.
+    ///
+    /// Every attached vote must be validly signed and cover the block. Only
+    /// final votes count towards the tally, and each representative is
+    /// counted once. Returns the tallied weight.
+    pub fn verify(&self, weights: &WeightTable) -> Result<Raw> {
+        self.verify_block()?;
+
+        let mut voters = BTreeSet::new();
+        let mut tally = Raw::ZERO;
+        for vote in &self.votes {
+            if !vote.verify() {
+                return Err(Error::InvalidReceipt(ReceiptError::InvalidVoteSignature));
+            }
+            if !vote.contains(&self.hash) {
+                return Err(Error::InvalidReceipt(ReceiptError::UnrelatedVote));
+            }
+            if vote.is_final() && voters.insert(*vote.representative.public_key().as_bytes()) {
+                tally += weights.weight(&vote.representative);
+            }
+        }
+
+        if tally < weights.quorum() {
+            return Err(Error::InvalidReceipt(ReceiptError::InsufficientWeight));
+        }
+        Ok(tally)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::create_send_block;
+    use crate::keys::{KeyPair, Seed};
+    use crate::vote::FINAL_VOTE_TIMESTAMP;
+
+    fn keypair(index: u32) -> KeyPair {
+        Seed::from_hex("0000000000000000000000000000000000000000000000000000000000000000")
+            .unwrap()
+            .derive(index)
+    }
+
+    fn test_receipt() -> Receipt {
+        let sender = keypair(0);
+        let block = create_send_block(
+            &sender,
+            BlockHash::ZERO,
+            sender.account(),
+            Raw::new(100),
+            Raw::new(10),
+            &keypair(9).account(),
+            None,
+        );
+        Receipt::new(block).unwrap()
+    }
+
+    fn weights() -> WeightTable {
+        WeightTable::new()
+            .with_weight(&keypair(1).account(), Raw::new(600))
+            .with_weight(&keypair(2).account(), Raw::new(400))
+    }
+
+    #[test]
+    fn test_receipt_requires_signature() {
+        let mut block = test_receipt().block;
+        block.signature = None;
+        assert!(matches!(
+            Receipt::new(block),
+            Err(Error::InvalidBlock(BlockError::MissingField("signature")))
+        ));
+    }
+
+    #[test]
+    fn test_receipt_verify_quorum() {
+        let receipt = test_receipt();
+        let hash = receipt.hash;
+        let receipt = receipt
+            .with_vote(Vote::sign(&keypair(1), vec![hash], FINAL_VOTE_TIMESTAMP))
+            .with_vote(Vote::sign(&keypair(1), vec![hash], FINAL_VOTE_TIMESTAMP));
+
+        // A single 60% representative, counted once, is below the 67% quorum.
+        assert!(matches!(
+            receipt.verify(&weights()),
+            Err(Error::InvalidReceipt(ReceiptError::InsufficientWeight))
+        ));
+
+        let receipt = receipt.with_vote(Vote::sign(&keypair(2), vec![hash], FINAL_VOTE_TIMESTAMP));
+        assert_eq!(receipt.verify(&weights()).unwrap(), Raw::new(1000));
+    }
+
+    #[test]
+    fn test_receipt_non_final_votes_not_tallied() {
+        let receipt = test_receipt();
+        let hash = receipt.hash;
+        let receipt = receipt
+            .with_vote(Vote::sign(&keypair(1), vec![hash], 1))
+            .with_vote(Vote::sign(&keypair(2), vec![hash], 1));
+        assert!(matches!(
+            receipt.verify(&weights()),
+            Err(Error::InvalidReceipt(ReceiptError::InsufficientWeight))
+        ));
+    }
+
+    #[test]
+    fn test_receipt_rejects_tampering() {
+        let mut receipt = test_receipt();
+        receipt.block.balance = Raw::new(1);
+        assert!(matches!(
+            receipt.verify_block(),
+            Err(Error::InvalidReceipt(ReceiptError::HashMismatch))
+        ));
+
+        let receipt = test_receipt().with_vote(Vote::sign(
+            &keypair(1),
+            vec![BlockHash::ZERO],
+            FINAL_VOTE_TIMESTAMP,
+        ));
+        assert!(matches!(
+            receipt.verify(&weights()),
+            Err(Error::InvalidReceipt(ReceiptError::UnrelatedVote))
+        ));
+
+        let receipt = test_receipt();
+        let mut vote = Vote::sign(&keypair(1), vec![receipt.hash], FINAL_VOTE_TIMESTAMP);
+        vote.representative = keypair(2).account();
+        assert!(matches!(
+            receipt.with_vote(vote).verify(&weights()),
+            Err(Error::InvalidReceipt(ReceiptError::InvalidVoteSignature))
+        ));
+    }
+
+    #[test]
+    fn test_receipt_attach_votes() {
+        let mut receipt = test_receipt();
+        let vote = Vote::sign(&keypair(1), vec![receipt.hash], FINAL_VOTE_TIMESTAMP);
+
+        let mut archive = crate::vote::MemoryVoteArchive::new();
+        archive.store(vote.clone()).unwrap();
+        archive
+            .store(Vote::sign(
+                &keypair(2),
+                vec![receipt.hash],
+                FINAL_VOTE_TIMESTAMP,
+            ))
+            .unwrap();
+
+        receipt.votes.push(vote);
+        receipt.attach_votes(&archive).unwrap();
+        assert_eq!(receipt.votes.len(), 2);
+        assert!(receipt.verify(&weights()).is_ok());
+    }
+
+    #[test]
+    fn test_receipt_json_roundtrip() {
+        let receipt = test_receipt().with_confirmation(ConfirmationInfo {
+            confirmation_type: "active_quorum".to_string(),
+            duration: Some("250".to_string()),
+            voters: None,
+            tally: Some(Raw::new(1000)),
+        });
+        let json = receipt.to_json().unwrap();
+        assert_eq!(Receipt::from_json(&json).unwrap(), receipt);
+    }
+
+    #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+    #[test]
+    fn test_receipt_from_confirmation() {
+        let receipt = test_receipt();
+        let block = &receipt.block;
+        let json = serde_json::json!({
+            "account": block.account,
+            "amount": "10",
+            "hash": receipt.hash,
+            "confirmation_type": "active_quorum",
+            "block": {
+                "type": "state",
+                "account": block.account,
+                "previous": block.previous,
+                "representative": block.representative,
+                "balance": block.balance,
+                "link": block.link.to_hex(),
+                "signature": receipt.signature,
+                "work": "0000000000000000",
+                "subtype": "send"
+            }
+        });
+        let confirmation: ConfirmationMessage = serde_json::from_value(json).unwrap();
+        let parsed = Receipt::from_confirmation(&confirmation).unwrap();
+
+        assert_eq!(parsed.hash, receipt.hash);
+        assert_eq!(parsed.block.subtype, Some(Subtype::Send));
+        assert!(parsed.verify_block().is_ok());
+    }
+}
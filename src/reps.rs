@@ -0,0 +1,617 @@
+//! Community representative metadata.
+//!
+//! Node RPC only reports representative addresses and voting weight. A
+//! [`RepInfoProvider`] fetches the names and uptime scores published by
+//! community services, and a [`RepDirectory`] merges them for display in
+//! representative pickers and dashboards.
+//!
+//! A [`RepHealthReport`] combines node data with that metadata to score
+//! representatives and suggest one that is online, up to date and not
+//! over-weighted.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xno_connect::reps::{MyNanoNinjaProvider, NanoToProvider, RepDirectory};
+//!
+//! # async fn example() -> xno_connect::error::Result<()> {
+//! let mut directory = RepDirectory::new();
+//! directory.load(&MyNanoNinjaProvider::new()).await?;
+//! directory.load(&NanoToProvider::new()).await?;
+//!
+//! let rep = "nano_1abc...".parse()?;
+//! println!("{}", directory.label(&rep));
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::Serialize;
+
+use crate::error::{Error, Result, RpcError};
+use crate::rpc::{
+    ConfirmationQuorumResponse, RepresentativesOnlineResponse, RpcClient, TelemetryResponse,
+};
+use crate::types::{Account, Raw};
+
+/// Default Nano.to known-accounts endpoint.
+pub const NANO_TO_KNOWN_URL: &str = "https://nano.to/known.json";
+
+/// Default mynano.ninja verified-representatives endpoint.
+pub const MYNANO_NINJA_VERIFIED_URL: &str = "https://mynano.ninja/api/accounts/verified";
+
+/// Default cap on a representative's share of online weight.
+pub const DEFAULT_MAX_WEIGHT_SHARE: f64 = 0.03;
+
+/// Share of online weight needed to be a principal (voting) representative.
+pub const PRINCIPAL_WEIGHT_SHARE: f64 = 0.001;
+
+/// Metadata about a representative.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepInfo {
+    /// Representative account.
+    pub account: Account,
+    /// Human-readable name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Uptime percentage (0-100).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime: Option<f64>,
+    /// Provider-specific quality score (0-100).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<u8>,
+    /// Voting weight reported by the provider.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<Raw>,
+    /// Node version, e.g. `"V27.1"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl RepInfo {
+    /// Create metadata with only the account set.
+    pub fn new(account: Account) -> Self {
+        RepInfo {
+            account,
+            alias: None,
+            uptime: None,
+            score: None,
+            weight: None,
+            version: None,
+        }
+    }
+
+    /// Fill fields missing here from another record for the same account.
+    pub fn merge(&mut self, other: RepInfo) {
+        self.alias = self.alias.take().or(other.alias);
+        self.uptime = self.uptime.or(other.uptime);
+        self.score = self.score.or(other.score);
+        self.weight = self.weight.or(other.weight);
+        self.version = self.version.take().or(other.version);
+    }
+}
+
+/// Source of representative metadata.
+#[allow(async_fn_in_trait)]
+pub trait RepInfoProvider {
+    /// Fetch metadata for every representative the source knows about.
+    async fn fetch(&self) -> Result<Vec<RepInfo>>;
+}
+
+/// Representative aliases from Nano.to's known-accounts list.
+#[derive(Debug, Clone)]
+pub struct NanoToProvider {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl NanoToProvider {
+    /// Create a provider for the public Nano.to endpoint.
+    pub fn new() -> Self {
+        Self::with_url(NANO_TO_KNOWN_URL)
+    }
+
+    /// Create a provider for a custom endpoint or mirror.
+    pub fn with_url(url: impl Into<String>) -> Self {
+        NanoToProvider {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl Default for NanoToProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RepInfoProvider for NanoToProvider {
+    async fn fetch(&self) -> Result<Vec<RepInfo>> {
+        let json = get_json(&self.client, &self.url).await?;
+        Ok(parse_nano_to(&json))
+    }
+}
+
+/// Aliases, uptime and scores from mynano.ninja.
+#[derive(Debug, Clone)]
+pub struct MyNanoNinjaProvider {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl MyNanoNinjaProvider {
+    /// Create a provider for the public mynano.ninja endpoint.
+    pub fn new() -> Self {
+        Self::with_url(MYNANO_NINJA_VERIFIED_URL)
+    }
+
+    /// Create a provider for a custom endpoint or mirror.
+    pub fn with_url(url: impl Into<String>) -> Self {
+        MyNanoNinjaProvider {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl Default for MyNanoNinjaProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RepInfoProvider for MyNanoNinjaProvider {
+    async fn fetch(&self) -> Result<Vec<RepInfo>> {
+        let json = get_json(&self.client, &self.url).await?;
+        Ok(parse_mynano_ninja(&json))
+    }
+}
+
+/// Merged representative metadata, keyed by account.
+#[derive(Debug, Clone, Default)]
+pub struct RepDirectory {
+    reps: BTreeMap<[u8; 32], RepInfo>,
+}
+
+impl RepDirectory {
+    /// Create an empty directory.
+    pub fn new() -> Self {
+        RepDirectory::default()
+    }
+
+    /// Fetch from a provider and merge the results.
+    ///
+    /// Data already in the directory takes precedence, so load the most
+    /// trusted provider first.
+    pub async fn load<P: RepInfoProvider + ?Sized>(&mut self, provider: &P) -> Result<usize> {
+        let reps = provider.fetch().await?;
+        let count = reps.len();
+        self.extend(reps);
+        Ok(count)
+    }
+
+    /// Merge records into the directory.
+    pub fn extend(&mut self, reps: impl IntoIterator<Item = RepInfo>) {
+        for info in reps {
+            match self.reps.get_mut(info.account.public_key().as_bytes()) {
+                Some(existing) => existing.merge(info),
+                None => {
+                    self.reps
+                        .insert(*info.account.public_key().as_bytes(), info);
+                }
+            }
+        }
+    }
+
+    /// Get metadata for a representative.
+    pub fn get(&self, account: &Account) -> Option<&RepInfo> {
+        self.reps.get(account.public_key().as_bytes())
+    }
+
+    /// Get a display label: the alias if known, otherwise the address.
+    pub fn label(&self, account: &Account) -> String {
+        self.get(account)
+            .and_then(|info| info.alias.clone())
+            .unwrap_or_else(|| account.to_string())
+    }
+
+    /// Number of known representatives.
+    pub fn len(&self) -> usize {
+        self.reps.len()
+    }
+
+    /// Check if the directory is empty.
+    pub fn is_empty(&self) -> bool {
+        self.reps.is_empty()
+    }
+
+    /// Iterate over all known representatives.
+    pub fn iter(&self) -> impl Iterator<Item = &RepInfo> {
+        self.reps.values()
+    }
+}
+
+/// Health of a single online representative.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepHealth {
+    /// Representative account.
+    pub account: Account,
+    /// Voting weight.
+    pub weight: Raw,
+    /// Share of the online voting weight (0.0-1.0).
+    pub weight_share: f64,
+    /// Uptime percentage from the directory, if known.
+    pub uptime: Option<f64>,
+    /// Node version from the directory, if known.
+    pub version: Option<String>,
+    /// Whether the node runs an older major version than the network.
+    pub outdated: bool,
+    /// Combined score (0-100).
+    pub score: f64,
+}
+
+impl RepHealth {
+    /// Check if the representative has enough weight to vote.
+    pub fn is_principal(&self) -> bool {
+        self.weight_share >= PRINCIPAL_WEIGHT_SHARE
+    }
+}
+
+/// Scored snapshot of the online representatives.
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::reps::RepHealthReport;
+/// use xno_connect::rpc::RpcClient;
+///
+/// # async fn example() -> xno_connect::error::Result<()> {
+/// let client = RpcClient::new("http://localhost:7076");
+/// let report = RepHealthReport::fetch(&client, None).await?;
+/// if let Some(rep) = report.suggest() {
+///     println!("{} ({:.2}%)", rep.account, rep.weight_share * 100.0);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RepHealthReport {
+    online_stake: Raw,
+    network_version: u32,
+    max_weight_share: f64,
+    reps: Vec<RepHealth>,
+}
+
+impl RepHealthReport {
+    /// Fetch `representatives_online`, `confirmation_quorum` and
+    /// `telemetry` and score every online representative.
+    ///
+    /// Uptime and version come from `directory` when given.
+    pub async fn fetch(client: &RpcClient, directory: Option<&RepDirectory>) -> Result<Self> {
+        let online = client.representatives_online().await?;
+        let quorum = client.confirmation_quorum().await?;
+        let telemetry = client.telemetry().await?;
+        Ok(Self::from_responses(
+            &online, &quorum, &telemetry, directory,
+        ))
+    }
+
+    /// Score representatives from already fetched responses.
+    pub fn from_responses(
+        online: &RepresentativesOnlineResponse,
+        quorum: &ConfirmationQuorumResponse,
+        telemetry: &TelemetryResponse,
+        directory: Option<&RepDirectory>,
+    ) -> Self {
+        let mut report = RepHealthReport {
+            online_stake: quorum.online_stake_total,
+            network_version: telemetry.major_version.parse().unwrap_or(0),
+            max_weight_share: DEFAULT_MAX_WEIGHT_SHARE,
+            reps: Vec::new(),
+        };
+        for (account, weight) in parse_online(&online.representatives) {
+            let info = directory.and_then(|directory| directory.get(&account));
+            let weight_share = share(weight, report.online_stake);
+            let version = info.and_then(|info| info.version.clone());
+            let outdated = version
+                .as_deref()
+                .and_then(major_version)
+                .is_some_and(|major| major < report.network_version);
+            report.reps.push(RepHealth {
+                account,
+                weight,
+                weight_share,
+                uptime: info.and_then(|info| info.uptime),
+                version,
+                outdated,
+                score: 0.0,
+            });
+        }
+        report.rescore();
+        report
+    }
+
+    /// Set the largest acceptable share of online weight.
+    pub fn with_max_weight_share(mut self, max_weight_share: f64) -> Self {
+        self.max_weight_share = max_weight_share;
+        self.rescore();
+        self
+    }
+
+    /// Total online voting weight.
+    pub fn online_stake(&self) -> Raw {
+        self.online_stake
+    }
+
+    /// Major node version reported by network telemetry.
+    pub fn network_version(&self) -> u32 {
+        self.network_version
+    }
+
+    /// Get the health of a representative, if it is online.
+    pub fn get(&self, account: &Account) -> Option<&RepHealth> {
+        self.reps.iter().find(|rep| &rep.account == account)
+    }
+
+    /// Online representatives, best score first.
+    pub fn ranked(&self) -> Vec<&RepHealth> {
+        let mut reps: Vec<&RepHealth> = self.reps.iter().collect();
+        reps.sort_by(|a, b| b.score.total_cmp(&a.score));
+        reps
+    }
+
+    /// Suggest the best principal representative under the weight cap.
+    pub fn suggest(&self) -> Option<&RepHealth> {
+        self.ranked().into_iter().find(|rep| self.is_healthy(rep))
+    }
+
+    /// Check if delegating to `account` is fine: online, voting, up to
+    /// date and under the weight cap.
+    pub fn is_acceptable(&self, account: &Account) -> bool {
+        self.get(account).is_some_and(|rep| self.is_healthy(rep))
+    }
+
+    fn is_healthy(&self, rep: &RepHealth) -> bool {
+        rep.is_principal() && !rep.outdated && rep.weight_share <= self.max_weight_share
+    }
+
+    /// Score out of 100: 40 for weight headroom under the cap, 40 for
+    /// uptime and 20 for running the current version. Unknown uptime or
+    /// version earns half marks.
+    fn rescore(&mut self) {
+        let cap = self.max_weight_share;
+        for rep in &mut self.reps {
+            let weight = if rep.weight_share > cap {
+                0.0
+            } else if rep.is_principal() {
+                40.0 * (1.0 - rep.weight_share / cap)
+            } else {
+                10.0
+            };
+            let uptime = rep
+                .uptime
+                .map_or(20.0, |uptime| 0.4 * uptime.clamp(0.0, 100.0));
+            let version = match (&rep.version, rep.outdated) {
+                (_, true) => 0.0,
+                (Some(_), false) => 20.0,
+                (None, false) => 10.0,
+            };
+            rep.score = weight + uptime + version;
+        }
+    }
+}
+
+/// Parse `representatives_online` output, with or without weights.
+pub(crate) fn parse_online(json: &serde_json::Value) -> Vec<(Account, Raw)> {
+    match json {
+        serde_json::Value::Object(reps) => reps
+            .iter()
+            .filter_map(|(account, entry)| {
+                let weight = entry
+                    .get("weight")
+                    .and_then(|weight| weight.as_str())
+                    .and_then(|weight| weight.parse().ok())
+                    .unwrap_or(Raw::ZERO);
+                Some((account.parse().ok()?, weight))
+            })
+            .collect(),
+        serde_json::Value::Array(reps) => reps
+            .iter()
+            .filter_map(|account| Some((account.as_str()?.parse().ok()?, Raw::ZERO)))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn share(weight: Raw, total: Raw) -> f64 {
+    if total.is_zero() {
+        0.0
+    } else {
+        weight.as_u128() as f64 / total.as_u128() as f64
+    }
+}
+
+/// Major version from strings like `"V27.1"` or `"27.0RC1"`.
+fn major_version(version: &str) -> Option<u32> {
+    version
+        .trim_start_matches(['V', 'v'])
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .parse()
+        .ok()
+}
+
+async fn get_json(client: &reqwest::Client, url: &str) -> Result<serde_json::Value> {
+    let response =
+        client.get(url).send().await.map_err(|e| {
+            Error::Rpc(RpcError::ConnectionFailed(alloc::format!("{}: {}", url, e)))
+        })?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(Error::Rpc(RpcError::HttpStatus(status.as_u16())));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| Error::Rpc(RpcError::InvalidResponse(e.to_string())))
+}
+
+/// Parse Nano.to's `[{"name": ..., "address": ...}]` list.
+fn parse_nano_to(json: &serde_json::Value) -> Vec<RepInfo> {
+    json.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let account = entry.get("address")?.as_str()?.parse().ok()?;
+            let mut info = RepInfo::new(account);
+            info.alias = entry
+                .get("name")
+                .and_then(|name| name.as_str())
+                .map(String::from);
+            Some(info)
+        })
+        .collect()
+}
+
+/// Parse mynano.ninja's list of verified accounts.
+fn parse_mynano_ninja(json: &serde_json::Value) -> Vec<RepInfo> {
+    json.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let account = entry.get("account")?.as_str()?.parse().ok()?;
+            let mut info = RepInfo::new(account);
+            info.alias = entry
+                .get("alias")
+                .and_then(|alias| alias.as_str())
+                .filter(|alias| !alias.is_empty())
+                .map(String::from);
+            info.uptime = entry.get("uptime").and_then(|uptime| uptime.as_f64());
+            info.score = entry
+                .get("score")
+                .and_then(|score| score.as_u64())
+                .map(|score| score.min(100) as u8);
+            info.weight = entry.get("votingweight").and_then(|weight| match weight {
+                serde_json::Value::String(s) => s.parse().ok(),
+                serde_json::Value::Number(n) => n.as_u64().map(|n| Raw::new(n as u128)),
+                _ => None,
+            });
+            info.version = entry
+                .get("version")
+                .and_then(|version| version.as_str())
+                .map(String::from);
+            Some(info)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REP: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+
+    #[test]
+    fn test_parse_nano_to() {
+        let json = serde_json::json!([
+            { "name": "Nano Foundation #1", "address": REP },
+            { "name": "broken", "address": "nano_invalid" }
+        ]);
+        let reps = parse_nano_to(&json);
+        assert_eq!(reps.len(), 1);
+        assert_eq!(reps[0].alias.as_deref(), Some("Nano Foundation #1"));
+    }
+
+    #[test]
+    fn test_parse_mynano_ninja() {
+        let json = serde_json::json!([{
+            "account": REP,
+            "alias": "",
+            "uptime": 99.87,
+            "score": 98,
+            "votingweight": "1000000"
+        }]);
+        let reps = parse_mynano_ninja(&json);
+        assert_eq!(reps[0].alias, None);
+        assert_eq!(reps[0].uptime, Some(99.87));
+        assert_eq!(reps[0].score, Some(98));
+        assert_eq!(reps[0].weight, Some(Raw::new(1_000_000)));
+    }
+
+    #[test]
+    fn test_directory_merge() {
+        let account: Account = REP.parse().unwrap();
+        let mut ninja = RepInfo::new(account.clone());
+        ninja.uptime = Some(99.0);
+        let mut nano_to = RepInfo::new(account.clone());
+        nano_to.alias = Some("Genesis".into());
+        nano_to.uptime = Some(10.0);
+
+        let mut directory = RepDirectory::new();
+        directory.extend([ninja]);
+        directory.extend([nano_to]);
+
+        let info = directory.get(&account).unwrap();
+        assert_eq!(info.uptime, Some(99.0));
+        assert_eq!(directory.label(&account), "Genesis");
+        assert_eq!(directory.len(), 1);
+
+        let other: Account = "nano_1111111111111111111111111111111111111111111111111111hifc8npp"
+            .parse()
+            .unwrap();
+        assert_eq!(directory.label(&other), other.to_string());
+    }
+
+    #[test]
+    fn test_health_report() {
+        const BIG: &str = "nano_1111111111111111111111111111111111111111111111111111hifc8npp";
+        const OLD: &str = "nano_3e3j5tkog48pnny9dmfzj1r16pg8t1e76dz5tmac6iq689wyjfpiij4txtdo";
+        let online: RepresentativesOnlineResponse = serde_json::from_value(serde_json::json!({
+            "representatives": {
+                REP: { "weight": "2000" },
+                BIG: { "weight": "10000" },
+                OLD: { "weight": "1000" }
+            }
+        }))
+        .unwrap();
+        let quorum: ConfirmationQuorumResponse = serde_json::from_value(serde_json::json!({
+            "quorum_delta": "67000",
+            "online_weight_quorum_percent": "67",
+            "online_weight_minimum": "1",
+            "online_stake_total": "100000",
+            "peers_stake_total": "100000"
+        }))
+        .unwrap();
+        let telemetry: TelemetryResponse = serde_json::from_value(serde_json::json!({
+            "block_count": "1", "cemented_count": "1", "unchecked_count": "0",
+            "account_count": "1", "bandwidth_cap": "0", "peer_count": "1",
+            "protocol_version": "20", "uptime": "1", "major_version": "27",
+            "minor_version": "1", "patch_version": "0", "pre_release_version": "0",
+            "maker": "0", "timestamp": "0",
+            "genesis_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+        }))
+        .unwrap();
+
+        let mut old = RepInfo::new(OLD.parse().unwrap());
+        old.version = Some("V25.1".into());
+        old.uptime = Some(100.0);
+        let mut directory = RepDirectory::new();
+        directory.extend([old]);
+
+        let report =
+            RepHealthReport::from_responses(&online, &quorum, &telemetry, Some(&directory));
+        assert_eq!(report.network_version(), 27);
+        let big = report.get(&BIG.parse().unwrap()).unwrap();
+        assert_eq!(big.weight_share, 0.1);
+        assert!(report.get(&OLD.parse().unwrap()).unwrap().outdated);
+        assert_eq!(report.suggest().unwrap().account, REP.parse().unwrap());
+        assert!(!report.is_acceptable(&BIG.parse().unwrap()));
+
+        let report = report.with_max_weight_share(0.01);
+        assert!(report.suggest().is_none());
+        assert_eq!(major_version("27.0RC1"), Some(27));
+    }
+}
@@ -0,0 +1,244 @@
+//! Independent requests sent concurrently.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio::task::JoinSet;
+
+use crate::error::{Error, Result, RpcError};
+use crate::rpc::lenient::ResponseMode;
+use crate::rpc::responses::parse_response;
+use crate::rpc::RpcClient;
+
+/// Requests in flight at once by default in an [`RpcBatch`].
+pub const DEFAULT_BATCH_PARALLELISM: usize = 8;
+
+impl RpcClient {
+    /// Start a batch of independent requests.
+    ///
+    /// The requests are sent concurrently, at most
+    /// [`DEFAULT_BATCH_PARALLELISM`] at a time, and their results are
+    /// returned by position.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xno_connect::rpc::*;
+    ///
+    /// # async fn example() -> xno_connect::error::Result<()> {
+    /// let client = RpcClient::new("http://localhost:7076");
+    /// let account: xno_connect::types::Account = "nano_1abc...".parse()?;
+    /// let frontier = xno_connect::types::BlockHash::from_hex(
+    ///     "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+    /// )?;
+    ///
+    /// let mut batch = client.batch();
+    /// let info = batch.add::<AccountInfoResponse, _>(&AccountInfoRequest::new(&account));
+    /// let receivable = batch.add::<AccountsReceivableResponse, _>(&AccountsReceivableRequest::new(
+    ///     &[account.clone()],
+    ///     10,
+    /// ));
+    /// let work = batch.add::<WorkGenerateResponse, _>(&WorkGenerateRequest::new(&frontier));
+    ///
+    /// let mut results = batch.execute().await;
+    /// let info = results.take(info)?;
+    /// let receivable = results.take(receivable)?;
+    /// let work = results.take(work)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn batch(&self) -> RpcBatch<'_> {
+        RpcBatch {
+            client: self,
+            requests: Vec::new(),
+            parallelism: DEFAULT_BATCH_PARALLELISM,
+        }
+    }
+}
+
+/// Position of a request in an [`RpcBatch`], typed with its response.
+#[derive(Debug)]
+pub struct BatchHandle<T> {
+    index: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> BatchHandle<T> {
+    /// Get the position of the request in the batch.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Requests queued to be sent together.
+///
+/// Created by [`RpcClient::batch`]. Each request goes through the client
+/// as usual, with its interceptors, rate limit and cache.
+pub struct RpcBatch<'a> {
+    client: &'a RpcClient,
+    requests: Vec<Result<serde_json::Value>>,
+    parallelism: usize,
+}
+
+impl RpcBatch<'_> {
+    /// Send at most `parallelism` requests at a time.
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Queue `request`, to be read back as a `T`.
+    pub fn add<T: DeserializeOwned, Req: Serialize>(&mut self, request: &Req) -> BatchHandle<T> {
+        let index = self.requests.len();
+        self.requests.push(
+            serde_json::to_value(request)
+                .map_err(|e| Error::Rpc(RpcError::InvalidResponse(e.to_string()))),
+        );
+        BatchHandle {
+            index,
+            marker: PhantomData,
+        }
+    }
+
+    /// Get the number of queued requests.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Check if no requests are queued.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+
+    /// Send the requests and wait for all of them.
+    ///
+    /// A failed request doesn't stop the others; its error is returned in
+    /// its position.
+    pub async fn execute(self) -> BatchResults {
+        let mut results: Vec<Option<Result<serde_json::Value>>> =
+            (0..self.requests.len()).map(|_| None).collect();
+        let actions = self
+            .requests
+            .iter()
+            .map(|request| match request {
+                Ok(body) => body["action"].as_str().unwrap_or_default().to_string(),
+                Err(_) => String::new(),
+            })
+            .collect();
+        let mut queued = self.requests.into_iter().enumerate();
+        let mut tasks = JoinSet::new();
+        loop {
+            while tasks.len() < self.parallelism {
+                let Some((index, request)) = queued.next() else {
+                    break;
+                };
+                match request {
+                    Ok(body) => {
+                        let client = self.client.clone();
+                        tasks.spawn(async move {
+                            let result = client.request::<_, serde_json::Value>(&body).await;
+                            (index, result)
+                        });
+                    }
+                    Err(e) => results[index] = Some(Err(e)),
+                }
+            }
+            match tasks.join_next().await {
+                Some(Ok((index, result))) => results[index] = Some(result),
+                Some(Err(e)) => std::panic::resume_unwind(e.into_panic()),
+                None => break,
+            }
+        }
+        BatchResults {
+            results,
+            actions,
+            mode: self.client.response_mode(),
+        }
+    }
+}
+
+/// Results of an [`RpcBatch`], by position.
+#[derive(Debug)]
+pub struct BatchResults {
+    results: Vec<Option<Result<serde_json::Value>>>,
+    actions: Vec<String>,
+    mode: ResponseMode,
+}
+
+impl BatchResults {
+    /// Take the result of the request behind `handle`.
+    ///
+    /// Fails with the request's error, or [`RpcError::SchemaMismatch`] if
+    /// the response doesn't parse as a `T`. Fails with
+    /// [`RpcError::InvalidConfig`] for a handle from another batch.
+    pub fn take<T: DeserializeOwned>(&mut self, handle: BatchHandle<T>) -> Result<T> {
+        let json = self
+            .results
+            .get_mut(handle.index)
+            .and_then(Option::take)
+            .ok_or_else(|| {
+                Error::Rpc(RpcError::InvalidConfig(
+                    "batch handle from another batch".into(),
+                ))
+            })??;
+        parse_response(&self.actions[handle.index], &json, self.mode)
+    }
+
+    /// Get the number of results.
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    /// Check if the batch was empty.
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+
+    /// Get the untyped results in request order. Results already taken
+    /// are left out.
+    pub fn into_values(self) -> Vec<Result<serde_json::Value>> {
+        self.results.into_iter().flatten().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{BlockCountRequest, BlockCountResponse, VersionRequest, VersionResponse};
+
+    #[tokio::test]
+    async fn test_empty_batch() {
+        let client = RpcClient::new("http://127.0.0.1:1");
+        let batch = client.batch();
+        assert!(batch.is_empty());
+        assert!(batch.execute().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_results_by_position() {
+        // Nothing listens on port 1, so every request fails with its own error
+        let client = RpcClient::new("http://127.0.0.1:1");
+        let mut batch = client.batch().with_parallelism(1);
+        let count = batch.add::<BlockCountResponse, _>(&BlockCountRequest::new());
+        let version = batch.add::<VersionResponse, _>(&VersionRequest::new());
+        assert_eq!((count.index(), version.index()), (0, 1));
+
+        let mut results = batch.execute().await;
+        assert_eq!(results.len(), 2);
+        assert!(matches!(
+            results.take(version),
+            Err(Error::Rpc(RpcError::ConnectionFailed(_)))
+        ));
+        assert!(matches!(
+            results.take(BatchHandle::<BlockCountResponse> {
+                index: 5,
+                marker: PhantomData
+            }),
+            Err(Error::Rpc(RpcError::InvalidConfig(_)))
+        ));
+        assert_eq!(results.into_values().len(), 1);
+    }
+}
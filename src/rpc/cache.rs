@@ -0,0 +1,180 @@
+//! Caching of idempotent RPC responses.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::time::Duration;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Actions cached by default by [`RpcClient::with_cache`](super::RpcClient::with_cache).
+///
+/// Only responses that cannot go stale within a short TTL are cached by
+/// default. Block confirmation, account history and representative sets
+/// change over time; opt into them with
+/// [`RpcClient::with_cached_action`](super::RpcClient::with_cached_action).
+pub const DEFAULT_CACHED_ACTIONS: &[&str] = &["available_supply", "version"];
+
+/// Storage for cached RPC responses.
+///
+/// Keys identify the endpoint and full request body. Implement this to
+/// share a cache between processes, e.g. in Redis.
+pub trait ResponseCache: Send + Sync {
+    /// Get a response that has not expired.
+    fn get(&self, key: &str) -> Option<serde_json::Value>;
+
+    /// Store a response for `ttl`.
+    fn put(&self, key: String, value: serde_json::Value, ttl: Duration);
+
+    /// Remove all entries.
+    fn clear(&self);
+}
+
+/// In-memory [`ResponseCache`] with a size limit.
+///
+/// When full, expired entries are dropped first, then the entry closest to
+/// expiry.
+#[derive(Debug)]
+pub struct MemoryResponseCache {
+    max_entries: usize,
+    entries: Mutex<BTreeMap<String, (Instant, serde_json::Value)>>,
+}
+
+impl MemoryResponseCache {
+    /// Create a cache holding up to `max_entries` responses.
+    pub fn new(max_entries: usize) -> Self {
+        MemoryResponseCache {
+            max_entries: max_entries.max(1),
+            entries: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Number of stored entries, including expired ones not yet evicted.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, (Instant, serde_json::Value)>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for MemoryResponseCache {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl ResponseCache for MemoryResponseCache {
+    fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let mut entries = self.lock();
+        match entries.get(key) {
+            Some((expires, value)) if *expires > Instant::now() => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn put(&self, key: String, value: serde_json::Value, ttl: Duration) {
+        let now = Instant::now();
+        let mut entries = self.lock();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, (expires, _)| *expires > now);
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, (expires, _))| *expires)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+        entries.insert(key, (now + ttl, value));
+    }
+
+    fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+/// Cache settings attached to an [`RpcClient`](super::RpcClient).
+#[derive(Clone)]
+pub(crate) struct CacheLayer {
+    pub(crate) store: Arc<dyn ResponseCache>,
+    pub(crate) ttl: Duration,
+    pub(crate) actions: Vec<String>,
+}
+
+impl CacheLayer {
+    pub(crate) fn new(store: Arc<dyn ResponseCache>, ttl: Duration) -> Self {
+        CacheLayer {
+            store,
+            ttl,
+            actions: DEFAULT_CACHED_ACTIONS
+                .iter()
+                .map(|a| a.to_string())
+                .collect(),
+        }
+    }
+
+    /// Cache key for a prepared request, or `None` if its action is not cached.
+    pub(crate) fn key(&self, url: &str, body: &serde_json::Value) -> Option<String> {
+        let action = body.get("action")?.as_str()?;
+        if !self.actions.iter().any(|a| a == action) {
+            return None;
+        }
+        Some(alloc::format!("{} {}", url, body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_cache() {
+        let cache = MemoryResponseCache::new(2);
+        let ttl = Duration::from_secs(60);
+        cache.put("a".into(), serde_json::json!(1), ttl);
+        cache.put("b".into(), serde_json::json!(2), Duration::from_secs(120));
+        assert_eq!(cache.get("a"), Some(serde_json::json!(1)));
+
+        // Full: the entry expiring soonest is evicted
+        cache.put("c".into(), serde_json::json!(3), ttl);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b"), Some(serde_json::json!(2)));
+
+        cache.put("d".into(), serde_json::json!(4), Duration::ZERO);
+        assert_eq!(cache.get("d"), None);
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let mut layer = CacheLayer::new(Arc::new(MemoryResponseCache::default()), Duration::ZERO);
+        let info = serde_json::json!({"action": "block_info", "hash": "AB"});
+        let other = serde_json::json!({"action": "block_info", "hash": "CD"});
+        assert_eq!(layer.key("http://a", &info), None);
+
+        layer.actions.push("block_info".into());
+        let key = layer.key("http://a", &info).unwrap();
+        assert_ne!(Some(&key), layer.key("http://b", &info).as_ref());
+        assert_ne!(Some(key), layer.key("http://a", &other));
+
+        let send = serde_json::json!({"action": "process", "block": {}});
+        assert_eq!(layer.key("http://a", &send), None);
+    }
+}
@@ -0,0 +1,183 @@
+//! Streaming walks over an account chain.
+
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+
+use crate::error::Result;
+use crate::rpc::{ChainRequest, RpcClient, SuccessorsRequest};
+use crate::types::BlockHash;
+
+/// Hashes requested per page by [`ChainStream`].
+pub const DEFAULT_CHAIN_PAGE_SIZE: u64 = 1000;
+
+/// Direction of a [`ChainStream`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainDirection {
+    /// From the start block towards the open block, with `chain`.
+    Backward,
+    /// From the start block towards the frontier, with `successors`.
+    Forward,
+}
+
+/// Account chain yielded hash by hash, fetched one page at a time.
+///
+/// Created by [`RpcClient::chain_stream`]. The start block is yielded
+/// first. Each later page starts after the last hash yielded, using the
+/// RPC `offset` so no hash is repeated.
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::rpc::{ChainDirection, RpcClient};
+///
+/// # async fn example() -> xno_connect::error::Result<()> {
+/// let client = RpcClient::new("http://localhost:7076");
+/// let account = "nano_1abc...".parse()?;
+/// let info = client.account_info(&account).await?;
+///
+/// let mut chain = client.chain_stream(&info.open_block, ChainDirection::Forward);
+/// while let Some(hash) = chain.next().await? {
+///     println!("{}", hash);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ChainStream<'a> {
+    client: &'a RpcClient,
+    direction: ChainDirection,
+    page_size: u64,
+    cursor: BlockHash,
+    started: bool,
+    done: bool,
+    buffer: VecDeque<BlockHash>,
+}
+
+impl<'a> ChainStream<'a> {
+    /// Create a stream starting at `start`.
+    pub fn new(client: &'a RpcClient, start: &BlockHash, direction: ChainDirection) -> Self {
+        ChainStream {
+            client,
+            direction,
+            page_size: DEFAULT_CHAIN_PAGE_SIZE,
+            cursor: *start,
+            started: false,
+            done: false,
+            buffer: VecDeque::new(),
+        }
+    }
+
+    /// Request `page_size` hashes per RPC call.
+    pub fn with_page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Get the direction of the walk.
+    pub fn direction(&self) -> ChainDirection {
+        self.direction
+    }
+
+    /// Get the next hash.
+    ///
+    /// Returns `Ok(None)` once the end of the chain is reached.
+    pub async fn next(&mut self) -> Result<Option<BlockHash>> {
+        if self.buffer.is_empty() && !self.done {
+            self.fetch_page().await?;
+        }
+        Ok(self.buffer.pop_front())
+    }
+
+    /// Get the next batch of hashes: whatever is buffered, or a new page.
+    ///
+    /// Returns `Ok(None)` once the end of the chain is reached.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<BlockHash>>> {
+        if self.buffer.is_empty() && !self.done {
+            self.fetch_page().await?;
+        }
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(self.buffer.drain(..).collect()))
+    }
+
+    /// Read the rest of the chain into memory.
+    pub async fn collect(mut self) -> Result<Vec<BlockHash>> {
+        let mut hashes = Vec::new();
+        while let Some(page) = self.next_page().await? {
+            hashes.extend(page);
+        }
+        Ok(hashes)
+    }
+
+    async fn fetch_page(&mut self) -> Result<()> {
+        // After the first page, skip the cursor, which was already yielded
+        let offset = self.started.then_some(1);
+        let hashes = match self.direction {
+            ChainDirection::Backward => {
+                let mut request = ChainRequest::new(&self.cursor, self.page_size);
+                if let Some(offset) = offset {
+                    request = request.with_offset(offset);
+                }
+                self.client.chain_with(request).await?
+            }
+            ChainDirection::Forward => {
+                let mut request = SuccessorsRequest::new(&self.cursor, self.page_size);
+                if let Some(offset) = offset {
+                    request = request.with_offset(offset);
+                }
+                self.client.successors_with(request).await?
+            }
+        };
+        self.accept_page(hashes);
+        Ok(())
+    }
+
+    fn accept_page(&mut self, hashes: Vec<BlockHash>) {
+        self.started = true;
+        if (hashes.len() as u64) < self.page_size {
+            self.done = true;
+        }
+        if let Some(last) = hashes.last() {
+            self.cursor = *last;
+        }
+        self.buffer.extend(hashes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> BlockHash {
+        BlockHash::from_bytes([byte; 32])
+    }
+
+    #[test]
+    fn test_pages_advance_cursor() {
+        let client = RpcClient::new("http://localhost:7076");
+        let mut stream = client
+            .chain_stream(&hash(1), ChainDirection::Forward)
+            .with_page_size(2);
+
+        stream.accept_page(alloc::vec![hash(1), hash(2)]);
+        assert_eq!(stream.cursor, hash(2));
+        assert!(!stream.done);
+
+        stream.accept_page(alloc::vec![hash(3)]);
+        assert_eq!(stream.cursor, hash(3));
+        assert!(stream.done);
+        assert_eq!(
+            stream.buffer.iter().copied().collect::<Vec<_>>(),
+            [hash(1), hash(2), hash(3)]
+        );
+    }
+
+    #[test]
+    fn test_empty_page_ends_stream() {
+        let client = RpcClient::new("http://localhost:7076");
+        let mut stream = client.chain_stream(&hash(1), ChainDirection::Backward);
+        stream.accept_page(Vec::new());
+        assert!(stream.done);
+        assert_eq!(stream.cursor, hash(1));
+    }
+}
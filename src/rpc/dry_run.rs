@@ -0,0 +1,332 @@
+//! Pre-flight checks for block submission.
+
+use alloc::vec::Vec;
+
+use crate::blocks::{BlockHasher, ChainFault, ChainValidator};
+use crate::error::{NodeErrorKind, Result};
+use crate::rpc::{BlockInfoResponse, RpcClient};
+use crate::types::{Account, BlockHash, Raw, StateBlock, Subtype};
+
+/// Outcome of [`RpcClient::process_dry_run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DryRunReport {
+    /// Block hash.
+    pub hash: BlockHash,
+    /// Subtype implied by the balance change, if the previous balance is known.
+    pub subtype: Option<Subtype>,
+    /// Amount sent or received, if the previous balance is known.
+    pub amount: Option<Raw>,
+    /// Errors the node would likely return from `process`, most significant
+    /// first.
+    pub rejections: Vec<NodeErrorKind>,
+}
+
+impl DryRunReport {
+    /// Check if no problem was found.
+    pub fn is_accepted(&self) -> bool {
+        self.rejections.is_empty()
+    }
+}
+
+/// Source block of a receive or open, as found in the ledger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Source {
+    Missing,
+    NotSend,
+    Send { destination: Account, amount: Raw },
+}
+
+/// Ledger state relevant to one block.
+#[derive(Debug, Clone, Default)]
+struct Ledger {
+    /// The block itself is already in the ledger.
+    exists: bool,
+    /// Frontier and balance of the account, `None` if it is not opened.
+    frontier: Option<(BlockHash, Raw)>,
+    /// The block's previous is in the ledger, but is not the frontier.
+    previous_exists: bool,
+    source: Option<Source>,
+}
+
+impl RpcClient {
+    /// Check a block locally and against the ledger without publishing it.
+    ///
+    /// Verifies the signature, work (mainnet thresholds), previous pointer,
+    /// subtype and balance change against the account's frontier, and for
+    /// receives that the source is a send to the account for the same
+    /// amount. Does not detect a source that was already received.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xno_connect::rpc::RpcClient;
+    /// # use xno_connect::types::StateBlock;
+    ///
+    /// # async fn example(block: StateBlock) -> xno_connect::error::Result<()> {
+    /// let client = RpcClient::new("http://localhost:7076");
+    /// let report = client.process_dry_run(&block).await?;
+    /// for rejection in &report.rejections {
+    ///     println!("node would reject: {:?}", rejection);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn process_dry_run(&self, block: &StateBlock) -> Result<DryRunReport> {
+        self.process_dry_run_with(block, &ChainValidator::new())
+            .await
+    }
+
+    /// Like [`process_dry_run`](Self::process_dry_run), with the local
+    /// checks configured by `validator`, e.g. for another network.
+    pub async fn process_dry_run_with(
+        &self,
+        block: &StateBlock,
+        validator: &ChainValidator,
+    ) -> Result<DryRunReport> {
+        let hash = BlockHasher::hash_state_block(block);
+        let mut ledger = Ledger {
+            exists: self.find_block(&hash).await?.is_some(),
+            ..Ledger::default()
+        };
+        if ledger.exists {
+            return Ok(evaluate(block, validator, &ledger));
+        }
+
+        ledger.frontier = match self.account_info(&block.account).await {
+            Ok(info) => Some((info.frontier, info.balance)),
+            Err(e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => None,
+            Err(e) => return Err(e),
+        };
+        let on_frontier = ledger.frontier.map(|(frontier, _)| frontier) == Some(block.previous);
+        if !block.is_open() && !on_frontier {
+            ledger.previous_exists = self.find_block(&block.previous).await?.is_some();
+        }
+
+        let previous_balance = previous_balance(block, &ledger);
+        let receives =
+            previous_balance.map_or(block.is_open(), |previous| block.balance > previous);
+        if receives && !block.link.is_zero() {
+            let source = self.find_block(&block.link.as_block_hash()).await?;
+            ledger.source = Some(match source {
+                None => Source::Missing,
+                Some(info) => match info.send_destination() {
+                    Some(destination) => Source::Send {
+                        destination,
+                        amount: info.amount,
+                    },
+                    None => Source::NotSend,
+                },
+            });
+        }
+
+        Ok(evaluate(block, validator, &ledger))
+    }
+
+    /// Get block info, or `None` if the block is not in the ledger.
+    async fn find_block(&self, hash: &BlockHash) -> Result<Option<BlockInfoResponse>> {
+        match self.block_info(hash).await {
+            Ok(info) => Ok(Some(info)),
+            Err(e) if e.node_error_kind() == Some(NodeErrorKind::BlockNotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Balance before `block`, if known.
+fn previous_balance(block: &StateBlock, ledger: &Ledger) -> Option<Raw> {
+    if block.is_open() {
+        return Some(Raw::ZERO);
+    }
+    ledger
+        .frontier
+        .filter(|(frontier, _)| *frontier == block.previous)
+        .map(|(_, balance)| balance)
+}
+
+/// Predict the node's verdict on `block`.
+fn evaluate(block: &StateBlock, validator: &ChainValidator, ledger: &Ledger) -> DryRunReport {
+    let hash = BlockHasher::hash_state_block(block);
+    let mut rejections = Vec::new();
+    let mut reject = |kind: NodeErrorKind| {
+        if !rejections.contains(&kind) {
+            rejections.push(kind);
+        }
+    };
+
+    let previous = previous_balance(block, ledger);
+    let subtype = previous.map(|previous| match block.balance.cmp(&previous) {
+        core::cmp::Ordering::Less => Subtype::Send,
+        _ if block.is_open() => Subtype::Open,
+        core::cmp::Ordering::Greater => Subtype::Receive,
+        core::cmp::Ordering::Equal => Subtype::Change,
+    });
+    let amount = previous.map(|previous| {
+        block
+            .balance
+            .checked_sub(previous)
+            .unwrap_or_else(|| previous - block.balance)
+    });
+
+    if ledger.exists {
+        reject(NodeErrorKind::OldBlock);
+        return DryRunReport {
+            hash,
+            subtype,
+            amount,
+            rejections,
+        };
+    }
+    if block.is_open() && block.account.is_burn() {
+        reject(NodeErrorKind::OpenedBurnAccount);
+    }
+    if !block.is_open() && ledger.frontier.is_none() {
+        reject(NodeErrorKind::GapPrevious);
+    }
+
+    let validator = match ledger.frontier {
+        Some((frontier, balance)) => validator.clone().with_anchor(frontier, balance),
+        None => validator.clone(),
+    };
+    for violation in validator.validate(core::slice::from_ref(block)).violations {
+        reject(match violation.fault {
+            ChainFault::PreviousMismatch { .. } if ledger.previous_exists => NodeErrorKind::Fork,
+            ChainFault::PreviousMismatch { .. } => NodeErrorKind::GapPrevious,
+            ChainFault::UnexpectedOpen => NodeErrorKind::Fork,
+            ChainFault::MissingSource => NodeErrorKind::BalanceMismatch,
+            ChainFault::MissingSignature | ChainFault::InvalidSignature => {
+                NodeErrorKind::BadSignature
+            }
+            ChainFault::MissingWork | ChainFault::InsufficientWork => {
+                NodeErrorKind::InsufficientWork
+            }
+            ChainFault::AccountMismatch | ChainFault::SubtypeMismatch { .. } => {
+                NodeErrorKind::InvalidBlock
+            }
+        });
+    }
+
+    match &ledger.source {
+        Some(Source::Missing) => reject(NodeErrorKind::GapSource),
+        Some(Source::NotSend) => reject(NodeErrorKind::Unreceivable),
+        Some(Source::Send {
+            destination,
+            amount: sent,
+        }) => {
+            if *destination != block.account {
+                reject(NodeErrorKind::Unreceivable);
+            } else if amount.is_some_and(|amount| amount != *sent) {
+                reject(NodeErrorKind::BalanceMismatch);
+            }
+        }
+        None => {}
+    }
+
+    DryRunReport {
+        hash,
+        subtype,
+        amount,
+        rejections,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{create_open_block, create_send_block};
+    use crate::keys::{KeyPair, Seed};
+
+    fn keypair(index: u32) -> KeyPair {
+        Seed::from_bytes([5u8; 32]).derive(index)
+    }
+
+    fn validator() -> ChainValidator {
+        ChainValidator::new().without_work_check()
+    }
+
+    fn send(previous: BlockHash) -> StateBlock {
+        let owner = keypair(0);
+        create_send_block(
+            &owner,
+            previous,
+            owner.account(),
+            Raw::new(100),
+            Raw::new(30),
+            &keypair(1).account(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_send_on_frontier() {
+        let frontier = BlockHash::from_bytes([1u8; 32]);
+        let ledger = Ledger {
+            frontier: Some((frontier, Raw::new(100))),
+            ..Ledger::default()
+        };
+        let report = evaluate(&send(frontier), &validator(), &ledger);
+        assert!(report.is_accepted(), "{:?}", report.rejections);
+        assert_eq!(report.subtype, Some(Subtype::Send));
+        assert_eq!(report.amount, Some(Raw::new(30)));
+
+        let report = evaluate(&send(frontier), &ChainValidator::new(), &ledger);
+        assert_eq!(report.rejections, [NodeErrorKind::InsufficientWork]);
+    }
+
+    #[test]
+    fn test_previous_and_existing_block() {
+        let frontier = BlockHash::from_bytes([1u8; 32]);
+        let stale = BlockHash::from_bytes([2u8; 32]);
+        let mut ledger = Ledger {
+            frontier: Some((frontier, Raw::new(100))),
+            ..Ledger::default()
+        };
+        let report = evaluate(&send(stale), &validator(), &ledger);
+        assert_eq!(report.rejections, [NodeErrorKind::GapPrevious]);
+        assert_eq!(report.amount, None);
+
+        ledger.previous_exists = true;
+        let report = evaluate(&send(stale), &validator(), &ledger);
+        assert_eq!(report.rejections, [NodeErrorKind::Fork]);
+
+        ledger.exists = true;
+        let report = evaluate(&send(stale), &validator(), &ledger);
+        assert_eq!(report.rejections, [NodeErrorKind::OldBlock]);
+    }
+
+    #[test]
+    fn test_open_checks_source() {
+        let owner = keypair(0);
+        let mut block = create_open_block(
+            &owner,
+            owner.account(),
+            Raw::new(50),
+            &BlockHash::from_bytes([3u8; 32]),
+            None,
+        );
+        let mut ledger = Ledger {
+            source: Some(Source::Send {
+                destination: owner.account(),
+                amount: Raw::new(50),
+            }),
+            ..Ledger::default()
+        };
+        let report = evaluate(&block, &validator(), &ledger);
+        assert!(report.is_accepted(), "{:?}", report.rejections);
+        assert_eq!(report.subtype, Some(Subtype::Open));
+
+        ledger.source = Some(Source::Send {
+            destination: keypair(1).account(),
+            amount: Raw::new(50),
+        });
+        let report = evaluate(&block, &validator(), &ledger);
+        assert_eq!(report.rejections, [NodeErrorKind::Unreceivable]);
+
+        ledger.source = Some(Source::Missing);
+        block.signature = None;
+        let report = evaluate(&block, &validator(), &ledger);
+        assert_eq!(
+            report.rejections,
+            [NodeErrorKind::BadSignature, NodeErrorKind::GapSource]
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Lenient parsing of responses.
+//!
+//! Nodes send amounts, counts and flags as strings, but some node versions
+//! and RPC proxies send JSON numbers and booleans instead. In
+//! [`ResponseMode::Lenient`] responses are read through [`Lenient`], which
+//! converts between the two as the target type asks for a value.
+
+use alloc::string::ToString;
+
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::{forward_to_deserialize_any, Deserializer};
+use serde_json::Value;
+
+/// How closely responses must match the representation of their types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseMode {
+    /// Accept a number or boolean where a string is expected and a string
+    /// where a number or boolean is expected, so `"balance": 1` reads as a
+    /// [`Raw`](crate::types::Raw) and `"confirmed": true` as `"true"`.
+    ///
+    /// Numbers beyond 64 bits lose precision as JSON numbers, so large
+    /// amounts only read correctly as strings.
+    Lenient,
+    /// Accept only the representation the node sends, failing with
+    /// [`RpcError::SchemaMismatch`](crate::error::RpcError::SchemaMismatch)
+    /// on any other. The default.
+    #[default]
+    Strict,
+}
+
+type Error = serde_json::Error;
+
+/// Implement every number method of a deserializer with one expression.
+macro_rules! deserialize_numbers {
+    (|$deserializer:ident: $ty:ty, $visitor:ident| $body:expr) => {
+        deserialize_numbers!(@each $deserializer, $ty, $visitor, $body,
+            deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+            deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+            deserialize_f32 deserialize_f64);
+    };
+    (@each $deserializer:ident, $ty:ty, $visitor:ident, $body:expr, $($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $visitor: V) -> Result<V::Value, Error> {
+                let $deserializer: $ty = self;
+                $body
+            }
+        )*
+    };
+}
+
+/// A JSON value read leniently.
+pub(crate) struct Lenient<'de>(pub(crate) &'de Value);
+
+impl<'de> Deserializer<'de> for Lenient<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Array(items) => visitor.visit_seq(Seq(items.iter())),
+            Value::Object(map) => visitor.visit_map(Entries {
+                entries: map.iter(),
+                value: None,
+            }),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::String(s) => match parse_flag(s) {
+                Some(flag) => visitor.visit_bool(flag),
+                None => self.0.deserialize_bool(visitor),
+            },
+            Value::Number(n) => match n.as_u64() {
+                Some(0) => visitor.visit_bool(false),
+                Some(1) => visitor.visit_bool(true),
+                _ => self.0.deserialize_bool(visitor),
+            },
+            other => other.deserialize_bool(visitor),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Number(n) => visitor.visit_string(n.to_string()),
+            Value::Bool(flag) => visitor.visit_borrowed_str(if *flag { "true" } else { "false" }),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.0.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    deserialize_numbers!(|deserializer: Lenient<'de>, visitor| match deserializer.0 {
+        Value::String(s) => visit_number_str(s, visitor),
+        other => other.deserialize_any(visitor),
+    });
+
+    forward_to_deserialize_any! {
+        char bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+    }
+}
+
+/// A map key read leniently.
+struct Key<'de>(&'de str);
+
+impl<'de> Deserializer<'de> for Key<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match parse_flag(self.0) {
+            Some(flag) => visitor.visit_bool(flag),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        BorrowedStrDeserializer::<Error>::new(self.0).deserialize_enum(name, variants, visitor)
+    }
+
+    deserialize_numbers!(|deserializer: Key<'de>, visitor| visit_number_str(
+        deserializer.0,
+        visitor
+    ));
+
+    forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// Visit a number written as a string, or the string itself if it isn't
+/// one, for the visitor to reject.
+fn visit_number_str<'de, V: Visitor<'de>>(s: &'de str, visitor: V) -> Result<V::Value, Error> {
+    if let Ok(n) = s.parse::<u64>() {
+        visitor.visit_u64(n)
+    } else if let Ok(n) = s.parse::<i64>() {
+        visitor.visit_i64(n)
+    } else if let Ok(n) = s.parse::<u128>() {
+        visitor.visit_u128(n)
+    } else if let Ok(n) = s.parse::<f64>() {
+        visitor.visit_f64(n)
+    } else {
+        visitor.visit_borrowed_str(s)
+    }
+}
+
+/// Read `"true"`, `"false"`, `"1"` or `"0"` as a flag.
+fn parse_flag(s: &str) -> Option<bool> {
+    match s {
+        "true" | "1" => Some(true),
+        "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+struct Seq<'de>(core::slice::Iter<'de, Value>);
+
+impl<'de> SeqAccess<'de> for Seq<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(Lenient(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct Entries<'de> {
+    entries: serde_json::map::Iter<'de>,
+    value: Option<&'de Value>,
+}
+
+impl<'de> MapAccess<'de> for Entries<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(Key(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(Lenient(value)),
+            None => Err(de::Error::custom("value requested before key")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::{AccountBalanceResponse, BlockInfoResponse};
+    use crate::types::Raw;
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+    use serde::Deserialize;
+
+    #[test]
+    fn test_numbers_and_flags_as_strings() {
+        let json = serde_json::json!({
+            "block_account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+            "amount": 1000,
+            "balance": 5,
+            "height": 33,
+            "local_timestamp": "0",
+            "confirmed": true,
+            "contents": {
+                "type": "state",
+                "balance": 5,
+                "previous": "0000000000000000000000000000000000000000000000000000000000000000",
+                "signature": "0".repeat(128),
+                "work": "0000000000000000",
+            },
+        });
+        assert!(BlockInfoResponse::deserialize(&json).is_err());
+
+        let info = BlockInfoResponse::deserialize(Lenient(&json)).unwrap();
+        assert_eq!(info.amount, Raw::new(1000));
+        assert_eq!(info.height, "33");
+        assert_eq!(info.confirmed, "true");
+    }
+
+    #[test]
+    fn test_strings_as_numbers_and_flags() {
+        #[derive(Deserialize)]
+        struct Entry {
+            count: u64,
+            delta: i32,
+            confirmed: bool,
+            started: Option<bool>,
+            weights: BTreeMap<u32, Vec<u8>>,
+        }
+
+        let json = serde_json::json!({
+            "count": "18446744073709551615",
+            "delta": "-4",
+            "confirmed": "true",
+            "started": "0",
+            "weights": { "7": ["1", 2] },
+        });
+        let entry = Entry::deserialize(Lenient(&json)).unwrap();
+        assert_eq!(entry.count, u64::MAX);
+        assert_eq!(entry.delta, -4);
+        assert!(entry.confirmed);
+        assert_eq!(entry.started, Some(false));
+        assert_eq!(entry.weights[&7], [1, 2]);
+
+        let json = serde_json::json!({ "balance": "1", "pending": "x", "receivable": "0" });
+        assert!(AccountBalanceResponse::deserialize(Lenient(&json)).is_err());
+        assert!(Entry::deserialize(Lenient(&serde_json::json!({ "count": "maybe" }))).is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! Hooks for modifying RPC requests before they are sent.
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Function run on every outgoing request.
+pub(crate) type Interceptor = Arc<dyn Fn(&mut RequestParts) + Send + Sync>;
+
+/// An outgoing RPC request, as seen by interceptors.
+///
+/// Interceptors registered with
+/// [`RpcClient::with_interceptor`](super::RpcClient::with_interceptor) may
+/// change the URL, add headers or edit the JSON body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestParts {
+    /// Endpoint the request is posted to.
+    pub url: String,
+    /// Extra HTTP headers, in insertion order.
+    pub headers: Vec<(String, String)>,
+    /// JSON request body.
+    pub body: serde_json::Value,
+}
+
+impl RequestParts {
+    /// Get the RPC action, e.g. `account_info`.
+    pub fn action(&self) -> Option<&str> {
+        self.body.get("action").and_then(|action| action.as_str())
+    }
+
+    /// Add or replace a header.
+    ///
+    /// Header names are compared case-insensitively.
+    pub fn set_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        let value = value.into();
+        match self
+            .headers
+            .iter_mut()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(&name))
+        {
+            Some(header) => header.1 = value,
+            None => self.headers.push((name, value)),
+        }
+    }
+
+    /// Get a header value.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(existing, _)| existing.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set a top-level field in the JSON body, e.g. an API `key`.
+    pub fn set_field(&mut self, name: impl Into<String>, value: impl Into<serde_json::Value>) {
+        if let Some(body) = self.body.as_object_mut() {
+            body.insert(name.into(), value.into());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_parts() {
+        let mut parts = RequestParts {
+            url: "http://localhost:7076".into(),
+            headers: Vec::new(),
+            body: serde_json::json!({ "action": "version" }),
+        };
+        assert_eq!(parts.action(), Some("version"));
+
+        parts.set_header("Authorization", "a");
+        parts.set_header("authorization", "b");
+        assert_eq!(parts.headers.len(), 1);
+        assert_eq!(parts.header("AUTHORIZATION"), Some("b"));
+
+        parts.set_field("key", "secret");
+        assert_eq!(parts.body["key"], "secret");
+    }
+}
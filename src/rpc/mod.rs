@@ -17,10 +17,32 @@
 //! # }
 //! ```
 
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+mod batch;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+mod cache;
+mod chain;
 mod client;
+mod dry_run;
+mod lenient;
+mod middleware;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+mod ratelimit;
 mod requests;
 mod responses;
+mod scan;
 
-pub use client::RpcClient;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+pub use batch::{BatchHandle, BatchResults, RpcBatch, DEFAULT_BATCH_PARALLELISM};
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+pub use cache::{MemoryResponseCache, ResponseCache, DEFAULT_CACHED_ACTIONS};
+pub use chain::{ChainDirection, ChainStream, DEFAULT_CHAIN_PAGE_SIZE};
+pub use client::{RpcClient, RpcClientBuilder};
+pub use dry_run::DryRunReport;
+pub use lenient::ResponseMode;
+pub use middleware::RequestParts;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+pub use ratelimit::RateLimiter;
 pub use requests::*;
 pub use responses::*;
+pub use scan::DEFAULT_SCAN_PAGE_SIZE;
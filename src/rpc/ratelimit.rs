@@ -0,0 +1,92 @@
+//! Client-side pacing of RPC requests.
+
+use core::time::Duration;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Token-bucket rate limiter shared by clones of an [`RpcClient`](super::RpcClient).
+///
+/// The bucket holds up to `burst` tokens and refills at
+/// `requests_per_second`. Each request takes one token; when none are
+/// left, the request waits its turn instead of failing.
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    state: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `requests_per_second` on average and
+    /// bursts of up to `burst` requests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests_per_second` is not positive.
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        assert!(
+            requests_per_second > 0.0,
+            "requests_per_second must be positive"
+        );
+        let burst = f64::from(burst.max(1));
+        RateLimiter {
+            rate: requests_per_second,
+            burst,
+            state: Mutex::new(Bucket {
+                tokens: burst,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Wait until a request may be sent.
+    pub async fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Take a token and return how long to wait before using it.
+    ///
+    /// Tokens may go negative, so concurrent callers queue up in order.
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut bucket = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.updated = now;
+        bucket.tokens -= 1.0;
+
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reserve() {
+        let limiter = RateLimiter::new(10.0, 2);
+        let start = Instant::now();
+
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::from_millis(100));
+        assert_eq!(limiter.reserve(start), Duration::from_millis(200));
+
+        // Refilled after the queue drains
+        let later = start + Duration::from_secs(1);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+    }
+}
@@ -13,6 +13,9 @@ pub struct AccountBalanceRequest {
     pub action: String,
     /// The account address to query.
     pub account: String,
+    /// Only count confirmed blocks in the balance and receivable amount.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_only_confirmed: Option<bool>,
 }
 
 impl AccountBalanceRequest {
@@ -21,8 +24,15 @@ impl AccountBalanceRequest {
         AccountBalanceRequest {
             action: "account_balance".to_string(),
             account: account.as_str().to_string(),
+            include_only_confirmed: None,
         }
     }
+
+    /// Count only confirmed blocks in the balance and receivable amount.
+    pub fn confirmed_only(mut self) -> Self {
+        self.include_only_confirmed = Some(true);
+        self
+    }
 }
 
 /// RPC action for account_info.
@@ -44,6 +54,10 @@ pub struct AccountInfoRequest {
     /// Include receivable balance in response.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub receivable: Option<bool>,
+    /// Include the balance, height and frontier as of the confirmation
+    /// height.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_confirmed: Option<bool>,
 }
 
 impl AccountInfoRequest {
@@ -56,8 +70,16 @@ impl AccountInfoRequest {
             weight: Some(true),
             pending: None,
             receivable: Some(true),
+            include_confirmed: None,
         }
     }
+
+    /// Include the balance, height and frontier as of the confirmation
+    /// height.
+    pub fn include_confirmed(mut self) -> Self {
+        self.include_confirmed = Some(true);
+        self
+    }
 }
 
 /// RPC action for account_history.
@@ -78,6 +100,9 @@ pub struct AccountHistoryRequest {
     /// Return results in reverse chronological order.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reverse: Option<bool>,
+    /// Include all block fields.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<bool>,
 }
 
 impl AccountHistoryRequest {
@@ -90,6 +115,7 @@ impl AccountHistoryRequest {
             head: None,
             offset: None,
             reverse: None,
+            raw: None,
         }
     }
 
@@ -110,6 +136,13 @@ impl AccountHistoryRequest {
         self.reverse = Some(true);
         self
     }
+
+    /// Include all block fields, so each entry can be rebuilt with
+    /// [`AccountHistoryEntry::to_block`](crate::rpc::AccountHistoryEntry::to_block).
+    pub fn raw(mut self) -> Self {
+        self.raw = Some(true);
+        self
+    }
 }
 
 /// RPC action for accounts_receivable.
@@ -127,6 +160,9 @@ pub struct AccountsReceivableRequest {
     /// Include source account in response.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<bool>,
+    /// Return the largest amounts first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sorting: Option<bool>,
 }
 
 impl AccountsReceivableRequest {
@@ -138,6 +174,7 @@ impl AccountsReceivableRequest {
             count: count.to_string(),
             threshold: None,
             source: Some(true),
+            sorting: None,
         }
     }
 
@@ -146,6 +183,41 @@ impl AccountsReceivableRequest {
         self.threshold = Some(threshold_raw.to_string());
         self
     }
+
+    /// Select the largest amounts when there are more than `count`.
+    pub fn sorted(mut self) -> Self {
+        self.sorting = Some(true);
+        self
+    }
+}
+
+/// RPC action for accounts_balances.
+#[derive(Debug, Serialize)]
+pub struct AccountsBalancesRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// List of account addresses to query.
+    pub accounts: Vec<String>,
+    /// Only count confirmed blocks in the balances and receivable amounts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_only_confirmed: Option<bool>,
+}
+
+impl AccountsBalancesRequest {
+    /// Create a new accounts_balances request.
+    pub fn new(accounts: &[Account]) -> Self {
+        AccountsBalancesRequest {
+            action: "accounts_balances".to_string(),
+            accounts: accounts.iter().map(|a| a.as_str().to_string()).collect(),
+            include_only_confirmed: None,
+        }
+    }
+
+    /// Count only confirmed blocks in the balances and receivable amounts.
+    pub fn confirmed_only(mut self) -> Self {
+        self.include_only_confirmed = Some(true);
+        self
+    }
 }
 
 /// RPC action for block_info.
@@ -171,6 +243,118 @@ impl BlockInfoRequest {
     }
 }
 
+/// RPC action for blocks_info.
+#[derive(Debug, Serialize)]
+pub struct BlocksInfoRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// The block hashes to query.
+    pub hashes: Vec<String>,
+    /// Return block contents as JSON objects.
+    pub json_block: bool,
+}
+
+impl BlocksInfoRequest {
+    /// Create a new blocks_info request.
+    pub fn new(hashes: &[BlockHash]) -> Self {
+        BlocksInfoRequest {
+            action: "blocks_info".to_string(),
+            hashes: hashes.iter().map(|h| h.to_hex()).collect(),
+            json_block: true,
+        }
+    }
+}
+
+/// RPC action for chain.
+///
+/// Walks an account chain from a block towards its open block, or towards
+/// its frontier when [`reversed`](Self::reversed).
+#[derive(Debug, Serialize)]
+pub struct ChainRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Block to start from.
+    pub block: String,
+    /// Maximum number of hashes to return.
+    pub count: String,
+    /// Number of blocks to skip before the first one returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// Walk towards the frontier instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverse: Option<bool>,
+}
+
+impl ChainRequest {
+    /// Create a new chain request.
+    pub fn new(block: &BlockHash, count: u64) -> Self {
+        ChainRequest {
+            action: "chain".to_string(),
+            block: block.to_hex(),
+            count: count.to_string(),
+            offset: None,
+            reverse: None,
+        }
+    }
+
+    /// Skip `offset` blocks, starting with `block` itself.
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Walk in the opposite direction.
+    pub fn reversed(mut self) -> Self {
+        self.reverse = Some(true);
+        self
+    }
+}
+
+/// RPC action for successors.
+///
+/// Walks an account chain from a block towards its frontier, or towards
+/// its open block when [`reversed`](Self::reversed).
+#[derive(Debug, Serialize)]
+pub struct SuccessorsRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Block to start from.
+    pub block: String,
+    /// Maximum number of hashes to return.
+    pub count: String,
+    /// Number of blocks to skip before the first one returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// Walk towards the open block instead.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reverse: Option<bool>,
+}
+
+impl SuccessorsRequest {
+    /// Create a new successors request.
+    pub fn new(block: &BlockHash, count: u64) -> Self {
+        SuccessorsRequest {
+            action: "successors".to_string(),
+            block: block.to_hex(),
+            count: count.to_string(),
+            offset: None,
+            reverse: None,
+        }
+    }
+
+    /// Skip `offset` blocks, starting with `block` itself.
+    pub fn with_offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Walk in the opposite direction.
+    pub fn reversed(mut self) -> Self {
+        self.reverse = Some(true);
+        self
+    }
+}
+
 /// RPC action for block_count.
 #[derive(Debug, Serialize)]
 pub struct BlockCountRequest {
@@ -356,6 +540,72 @@ impl WorkCancelRequest {
     }
 }
 
+/// RPC action for work_peers. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct WorkPeersRequest {
+    /// The RPC action name.
+    pub action: String,
+}
+
+impl WorkPeersRequest {
+    /// Create a new work_peers request.
+    pub fn new() -> Self {
+        WorkPeersRequest {
+            action: "work_peers".to_string(),
+        }
+    }
+}
+
+impl Default for WorkPeersRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RPC action for work_peer_add. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct WorkPeerAddRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Address of the work peer.
+    pub address: String,
+    /// Work peer port.
+    pub port: String,
+}
+
+impl WorkPeerAddRequest {
+    /// Create a new work_peer_add request.
+    pub fn new(address: &str, port: u16) -> Self {
+        WorkPeerAddRequest {
+            action: "work_peer_add".to_string(),
+            address: address.to_string(),
+            port: port.to_string(),
+        }
+    }
+}
+
+/// RPC action for work_peers_clear. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct WorkPeersClearRequest {
+    /// The RPC action name.
+    pub action: String,
+}
+
+impl WorkPeersClearRequest {
+    /// Create a new work_peers_clear request.
+    pub fn new() -> Self {
+        WorkPeersClearRequest {
+            action: "work_peers_clear".to_string(),
+        }
+    }
+}
+
+impl Default for WorkPeersClearRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// RPC action for version.
 #[derive(Debug, Serialize)]
 pub struct VersionRequest {
@@ -378,6 +628,28 @@ impl Default for VersionRequest {
     }
 }
 
+/// RPC action for active_difficulty.
+#[derive(Debug, Serialize)]
+pub struct ActiveDifficultyRequest {
+    /// The RPC action name.
+    pub action: String,
+}
+
+impl ActiveDifficultyRequest {
+    /// Create a new active difficulty request.
+    pub fn new() -> Self {
+        ActiveDifficultyRequest {
+            action: "active_difficulty".to_string(),
+        }
+    }
+}
+
+impl Default for ActiveDifficultyRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// RPC action for peers.
 #[derive(Debug, Serialize)]
 pub struct PeersRequest {
@@ -405,6 +677,15 @@ impl Default for PeersRequest {
 pub struct TelemetryRequest {
     /// The RPC action name.
     pub action: String,
+    /// Return each peer's metrics instead of the aggregate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<bool>,
+    /// Address of a single peer to query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Port of a single peer to query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
 }
 
 impl TelemetryRequest {
@@ -412,8 +693,24 @@ impl TelemetryRequest {
     pub fn new() -> Self {
         TelemetryRequest {
             action: "telemetry".to_string(),
+            raw: None,
+            address: None,
+            port: None,
         }
     }
+
+    /// Return each peer's metrics instead of the aggregate.
+    pub fn raw(mut self) -> Self {
+        self.raw = Some(true);
+        self
+    }
+
+    /// Query a single peer.
+    pub fn for_peer(mut self, address: &str, port: u16) -> Self {
+        self.address = Some(address.to_string());
+        self.port = Some(port.to_string());
+        self
+    }
 }
 
 impl Default for TelemetryRequest {
@@ -569,6 +866,489 @@ impl BlockConfirmRequest {
     }
 }
 
+/// RPC action for unchecked.
+#[derive(Debug, Serialize)]
+pub struct UncheckedRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Maximum number of blocks to return.
+    pub count: String,
+    /// Return blocks as JSON objects.
+    pub json_block: bool,
+}
+
+impl UncheckedRequest {
+    /// Create a new unchecked request.
+    pub fn new(count: u64) -> Self {
+        UncheckedRequest {
+            action: "unchecked".to_string(),
+            count: count.to_string(),
+            json_block: true,
+        }
+    }
+}
+
+/// RPC action for unchecked_get.
+#[derive(Debug, Serialize)]
+pub struct UncheckedGetRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// The unchecked block hash.
+    pub hash: String,
+    /// Return the block as a JSON object.
+    pub json_block: bool,
+}
+
+impl UncheckedGetRequest {
+    /// Create a new unchecked_get request.
+    pub fn new(hash: &BlockHash) -> Self {
+        UncheckedGetRequest {
+            action: "unchecked_get".to_string(),
+            hash: hash.to_hex(),
+            json_block: true,
+        }
+    }
+}
+
+/// RPC action for unchecked_keys.
+#[derive(Debug, Serialize)]
+pub struct UncheckedKeysRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Dependency to start from: a previous or source block hash.
+    pub key: String,
+    /// Maximum number of entries to return.
+    pub count: String,
+    /// Return blocks as JSON objects.
+    pub json_block: bool,
+}
+
+impl UncheckedKeysRequest {
+    /// Create a new unchecked_keys request.
+    pub fn new(key: &BlockHash, count: u64) -> Self {
+        UncheckedKeysRequest {
+            action: "unchecked_keys".to_string(),
+            key: key.to_hex(),
+            count: count.to_string(),
+            json_block: true,
+        }
+    }
+}
+
+/// RPC action for bootstrap. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct BootstrapRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// IPv6 address of the peer to bootstrap from.
+    pub address: String,
+    /// Peer port.
+    pub port: String,
+    /// Skip frontier confirmation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bypass_frontier_confirmation: Option<bool>,
+    /// Attempt identifier, shown in `bootstrap_status`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl BootstrapRequest {
+    /// Create a new bootstrap request.
+    pub fn new(address: &str, port: u16) -> Self {
+        BootstrapRequest {
+            action: "bootstrap".to_string(),
+            address: address.to_string(),
+            port: port.to_string(),
+            bypass_frontier_confirmation: None,
+            id: None,
+        }
+    }
+
+    /// Skip frontier confirmation for this attempt.
+    pub fn bypass_frontier_confirmation(mut self) -> Self {
+        self.bypass_frontier_confirmation = Some(true);
+        self
+    }
+
+    /// Set the attempt identifier.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+}
+
+/// RPC action for bootstrap_lazy. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct BootstrapLazyRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Block hash to bootstrap from.
+    pub hash: String,
+    /// Stop any running lazy bootstrap and start a new one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force: Option<bool>,
+    /// Attempt identifier, shown in `bootstrap_status`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+impl BootstrapLazyRequest {
+    /// Create a new bootstrap_lazy request.
+    pub fn new(hash: &BlockHash) -> Self {
+        BootstrapLazyRequest {
+            action: "bootstrap_lazy".to_string(),
+            hash: hash.to_hex(),
+            force: None,
+            id: None,
+        }
+    }
+
+    /// Replace a running lazy bootstrap.
+    pub fn force(mut self) -> Self {
+        self.force = Some(true);
+        self
+    }
+
+    /// Set the attempt identifier.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+}
+
+/// RPC action for bootstrap_status.
+#[derive(Debug, Serialize)]
+pub struct BootstrapStatusRequest {
+    /// The RPC action name.
+    pub action: String,
+}
+
+impl BootstrapStatusRequest {
+    /// Create a new bootstrap_status request.
+    pub fn new() -> Self {
+        BootstrapStatusRequest {
+            action: "bootstrap_status".to_string(),
+        }
+    }
+}
+
+impl Default for BootstrapStatusRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Kind of statistics returned by the stats action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsType {
+    /// Event counters since the node started or the last `stats_clear`.
+    Counters,
+    /// Sampled values.
+    Samples,
+    /// Memory usage of internal containers.
+    Objects,
+    /// Database backend statistics.
+    Database,
+}
+
+/// RPC action for stats.
+#[derive(Debug, Serialize)]
+pub struct StatsRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Kind of statistics.
+    #[serde(rename = "type")]
+    pub stats_type: StatsType,
+}
+
+impl StatsRequest {
+    /// Create a new stats request.
+    pub fn new(stats_type: StatsType) -> Self {
+        StatsRequest {
+            action: "stats".to_string(),
+            stats_type,
+        }
+    }
+}
+
+/// RPC action for stats_clear. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct StatsClearRequest {
+    /// The RPC action name.
+    pub action: String,
+}
+
+impl StatsClearRequest {
+    /// Create a new stats_clear request.
+    pub fn new() -> Self {
+        StatsClearRequest {
+            action: "stats_clear".to_string(),
+        }
+    }
+}
+
+impl Default for StatsClearRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RPC action for keepalive. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct KeepaliveRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Address of the peer.
+    pub address: String,
+    /// Peer port.
+    pub port: String,
+}
+
+impl KeepaliveRequest {
+    /// Create a new keepalive request.
+    pub fn new(address: &str, port: u16) -> Self {
+        KeepaliveRequest {
+            action: "keepalive".to_string(),
+            address: address.to_string(),
+            port: port.to_string(),
+        }
+    }
+}
+
+/// RPC action for populate_backlog. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct PopulateBacklogRequest {
+    /// The RPC action name.
+    pub action: String,
+}
+
+impl PopulateBacklogRequest {
+    /// Create a new populate_backlog request.
+    pub fn new() -> Self {
+        PopulateBacklogRequest {
+            action: "populate_backlog".to_string(),
+        }
+    }
+}
+
+impl Default for PopulateBacklogRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RPC action for node_id. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct NodeIdRequest {
+    /// The RPC action name.
+    pub action: String,
+}
+
+impl NodeIdRequest {
+    /// Create a new node_id request.
+    pub fn new() -> Self {
+        NodeIdRequest {
+            action: "node_id".to_string(),
+        }
+    }
+}
+
+impl Default for NodeIdRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RPC action for stop. Requires `enable_control`.
+#[derive(Debug, Serialize)]
+pub struct StopRequest {
+    /// The RPC action name.
+    pub action: String,
+}
+
+impl StopRequest {
+    /// Create a new stop request.
+    pub fn new() -> Self {
+        StopRequest {
+            action: "stop".to_string(),
+        }
+    }
+}
+
+impl Default for StopRequest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RPC action for ledger.
+///
+/// Lists accounts in public key order, starting at `account`. Used by
+/// [`RpcClient::scan_ledger`](crate::rpc::RpcClient::scan_ledger).
+#[derive(Debug, Clone, Serialize)]
+pub struct LedgerRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// First account to list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Maximum number of accounts to return.
+    pub count: String,
+    /// Include each account's representative.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub representative: Option<bool>,
+    /// Include each account's voting weight.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weight: Option<bool>,
+    /// Include each account's receivable balance.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receivable: Option<bool>,
+    /// Only list accounts modified at or after this UNIX timestamp.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_since: Option<String>,
+    /// Only list accounts with at least this balance in raw.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<String>,
+}
+
+impl LedgerRequest {
+    /// Create a ledger request for up to `count` accounts from the start of
+    /// the ledger.
+    pub fn new(count: u64) -> Self {
+        LedgerRequest {
+            action: "ledger".to_string(),
+            account: None,
+            count: count.to_string(),
+            representative: None,
+            weight: None,
+            receivable: None,
+            modified_since: None,
+            threshold: None,
+        }
+    }
+
+    /// Start at `account` instead of the start of the ledger.
+    pub fn starting_at(mut self, account: &Account) -> Self {
+        self.account = Some(account.as_str().to_string());
+        self
+    }
+
+    /// Include each account's representative.
+    pub fn with_representative(mut self) -> Self {
+        self.representative = Some(true);
+        self
+    }
+
+    /// Include each account's voting weight.
+    pub fn with_weight(mut self) -> Self {
+        self.weight = Some(true);
+        self
+    }
+
+    /// Include each account's receivable balance.
+    pub fn with_receivable(mut self) -> Self {
+        self.receivable = Some(true);
+        self
+    }
+
+    /// Only list accounts modified at or after `timestamp` (UNIX seconds).
+    pub fn modified_since(mut self, timestamp: u64) -> Self {
+        self.modified_since = Some(timestamp.to_string());
+        self
+    }
+
+    /// Only list accounts with a balance of at least `threshold`.
+    pub fn with_threshold(mut self, threshold: crate::types::Raw) -> Self {
+        self.threshold = Some(threshold.to_string());
+        self
+    }
+}
+
+/// RPC action for frontiers.
+///
+/// Lists account frontiers in public key order, starting at `account`.
+#[derive(Debug, Clone, Serialize)]
+pub struct FrontiersRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// First account to list.
+    pub account: String,
+    /// Maximum number of frontiers to return.
+    pub count: String,
+}
+
+impl FrontiersRequest {
+    /// Create a frontiers request for up to `count` accounts from `start`.
+    pub fn new(start: &Account, count: u64) -> Self {
+        FrontiersRequest {
+            action: "frontiers".to_string(),
+            account: start.as_str().to_string(),
+            count: count.to_string(),
+        }
+    }
+
+    /// Create a frontiers request for up to `count` accounts from the start
+    /// of the ledger.
+    pub fn from_start(count: u64) -> Self {
+        Self::new(
+            &Account::from_public_key(&crate::types::PublicKey::ZERO),
+            count,
+        )
+    }
+}
+
+/// RPC action for delegators.
+///
+/// Lists the accounts delegating to a representative with their balances.
+#[derive(Debug, Clone, Serialize)]
+pub struct DelegatorsRequest {
+    /// The RPC action name.
+    pub action: String,
+    /// Representative account.
+    pub account: String,
+    /// Maximum number of delegators to return.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<String>,
+    /// List delegators after this account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// Only list delegators with at least this balance in raw.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<String>,
+}
+
+impl DelegatorsRequest {
+    /// Create a delegators request for every delegator of `representative`.
+    pub fn new(representative: &Account) -> Self {
+        DelegatorsRequest {
+            action: "delegators".to_string(),
+            account: representative.as_str().to_string(),
+            count: None,
+            start: None,
+            threshold: None,
+        }
+    }
+
+    /// Return at most `count` delegators.
+    pub fn with_count(mut self, count: u64) -> Self {
+        self.count = Some(count.to_string());
+        self
+    }
+
+    /// List delegators after `account`.
+    pub fn starting_after(mut self, account: &Account) -> Self {
+        self.start = Some(account.as_str().to_string());
+        self
+    }
+
+    /// Only list delegators with a balance of at least `threshold`.
+    pub fn with_threshold(mut self, threshold: crate::types::Raw) -> Self {
+        self.threshold = Some(threshold.to_string());
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -592,12 +1372,44 @@ mod tests {
         Work::from_hex("FE00000000000000").unwrap()
     }
 
+    #[test]
+    fn test_ledger_scan_requests() {
+        let ledger = serde_json::to_value(
+            LedgerRequest::new(100)
+                .starting_at(&test_account())
+                .with_representative()
+                .modified_since(1_700_000_000),
+        )
+        .unwrap();
+        assert_eq!(ledger["action"], "ledger");
+        assert_eq!(ledger["count"], "100");
+        assert_eq!(ledger["account"], test_account().as_str());
+        assert_eq!(ledger["representative"], true);
+        assert_eq!(ledger["modified_since"], "1700000000");
+        assert!(ledger.get("weight").is_none());
+
+        let frontiers = serde_json::to_value(FrontiersRequest::from_start(10)).unwrap();
+        assert_eq!(
+            frontiers["account"],
+            "nano_1111111111111111111111111111111111111111111111111111hifc8npp"
+        );
+
+        let delegators =
+            serde_json::to_value(DelegatorsRequest::new(&test_account()).with_count(5)).unwrap();
+        assert_eq!(delegators["count"], "5");
+        assert!(delegators.get("start").is_none());
+    }
+
     #[test]
     fn test_account_balance_request() {
         let request = AccountBalanceRequest::new(&test_account());
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains("\"action\":\"account_balance\""));
         assert!(json.contains("nano_"));
+        assert!(!json.contains("include_only_confirmed"));
+
+        let json = serde_json::to_string(&request.confirmed_only()).unwrap();
+        assert!(json.contains("\"include_only_confirmed\":true"));
     }
 
     #[test]
@@ -608,6 +1420,10 @@ mod tests {
         assert!(json.contains("\"representative\":true"));
         assert!(json.contains("\"weight\":true"));
         assert!(json.contains("\"receivable\":true"));
+        assert!(!json.contains("include_confirmed"));
+
+        let json = serde_json::to_string(&request.include_confirmed()).unwrap();
+        assert!(json.contains("\"include_confirmed\":true"));
     }
 
     #[test]
@@ -641,6 +1457,16 @@ mod tests {
         assert!(json.contains("\"reverse\":true"));
     }
 
+    #[test]
+    fn test_account_history_request_raw() {
+        let request = AccountHistoryRequest::new(&test_account(), 50);
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(!json.contains("raw"));
+
+        let json = serde_json::to_string(&request.raw()).unwrap();
+        assert!(json.contains("\"raw\":true"));
+    }
+
     #[test]
     fn test_accounts_receivable_request() {
         let accounts = [test_account()];
@@ -657,6 +1483,24 @@ mod tests {
         let request = AccountsReceivableRequest::new(&accounts, 10).with_threshold("1000000");
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains("\"threshold\":\"1000000\""));
+        assert!(!json.contains("sorting"));
+
+        let request = request.sorted();
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"sorting\":true"));
+    }
+
+    #[test]
+    fn test_accounts_balances_request() {
+        let accounts = [test_account()];
+        let request = AccountsBalancesRequest::new(&accounts);
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"action\":\"accounts_balances\""));
+        assert!(json.contains(test_account().as_str()));
+        assert!(!json.contains("include_only_confirmed"));
+
+        let json = serde_json::to_string(&request.confirmed_only()).unwrap();
+        assert!(json.contains("\"include_only_confirmed\":true"));
     }
 
     #[test]
@@ -667,6 +1511,37 @@ mod tests {
         assert!(json.contains("\"json_block\":true"));
     }
 
+    #[test]
+    fn test_blocks_info_request() {
+        let request = BlocksInfoRequest::new(&[test_block_hash()]);
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"action\":\"blocks_info\""));
+        assert!(json.contains(&test_block_hash().to_hex()));
+    }
+
+    #[test]
+    fn test_successors_request() {
+        let request = SuccessorsRequest::new(&test_block_hash(), 100);
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"action\":\"successors\""));
+        assert!(json.contains("\"count\":\"100\""));
+    }
+
+    #[test]
+    fn test_chain_request_options() {
+        let request = ChainRequest::new(&test_block_hash(), 10);
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"action\":\"chain\""));
+        assert!(!json.contains("offset"));
+
+        let request = ChainRequest::new(&test_block_hash(), 10)
+            .with_offset(1)
+            .reversed();
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"offset\":1"));
+        assert!(json.contains("\"reverse\":true"));
+    }
+
     #[test]
     fn test_block_count_request() {
         let request = BlockCountRequest::new();
@@ -762,6 +1637,28 @@ mod tests {
         assert_eq!(request.action, "version");
     }
 
+    #[test]
+    fn test_work_peer_requests() {
+        let json = serde_json::to_string(&WorkPeersRequest::new()).unwrap();
+        assert!(json.contains("\"action\":\"work_peers\""));
+
+        let json =
+            serde_json::to_string(&WorkPeerAddRequest::new("::ffff:172.17.0.1", 7000)).unwrap();
+        assert!(json.contains("\"action\":\"work_peer_add\""));
+        assert!(json.contains("\"address\":\"::ffff:172.17.0.1\""));
+        assert!(json.contains("\"port\":\"7000\""));
+
+        let json = serde_json::to_string(&WorkPeersClearRequest::new()).unwrap();
+        assert!(json.contains("\"action\":\"work_peers_clear\""));
+    }
+
+    #[test]
+    fn test_active_difficulty_request() {
+        let request = ActiveDifficultyRequest::new();
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"action\":\"active_difficulty\""));
+    }
+
     #[test]
     fn test_peers_request() {
         let request = PeersRequest::new();
@@ -868,4 +1765,70 @@ mod tests {
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains("\"action\":\"block_confirm\""));
     }
+
+    #[test]
+    fn test_unchecked_requests() {
+        let json = serde_json::to_string(&UncheckedRequest::new(5)).unwrap();
+        assert!(json.contains("\"action\":\"unchecked\""));
+        assert!(json.contains("\"json_block\":true"));
+
+        let json = serde_json::to_string(&UncheckedGetRequest::new(&test_block_hash())).unwrap();
+        assert!(json.contains("\"action\":\"unchecked_get\""));
+
+        let json =
+            serde_json::to_string(&UncheckedKeysRequest::new(&test_block_hash(), 5)).unwrap();
+        assert!(json.contains("\"action\":\"unchecked_keys\""));
+        assert!(json.contains(&test_block_hash().to_hex()));
+    }
+
+    #[test]
+    fn test_bootstrap_requests() {
+        let request = BootstrapRequest::new("::ffff:138.201.94.249", 7075).with_id("manual");
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"action\":\"bootstrap\""));
+        assert!(json.contains("\"port\":\"7075\""));
+        assert!(json.contains("\"id\":\"manual\""));
+        assert!(!json.contains("bypass_frontier_confirmation"));
+
+        let request = BootstrapLazyRequest::new(&test_block_hash()).force();
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"action\":\"bootstrap_lazy\""));
+        assert!(json.contains("\"force\":true"));
+
+        assert_eq!(BootstrapStatusRequest::default().action, "bootstrap_status");
+    }
+
+    #[test]
+    fn test_stats_request() {
+        let json = serde_json::to_string(&StatsRequest::new(StatsType::Counters)).unwrap();
+        assert!(json.contains("\"action\":\"stats\""));
+        assert!(json.contains("\"type\":\"counters\""));
+        let json = serde_json::to_string(&StatsRequest::new(StatsType::Objects)).unwrap();
+        assert!(json.contains("\"type\":\"objects\""));
+    }
+
+    #[test]
+    fn test_node_management_requests() {
+        let json = serde_json::to_string(&KeepaliveRequest::new("::1", 7075)).unwrap();
+        assert!(json.contains("\"action\":\"keepalive\""));
+        assert!(json.contains("\"port\":\"7075\""));
+
+        assert_eq!(StatsClearRequest::default().action, "stats_clear");
+        assert_eq!(PopulateBacklogRequest::default().action, "populate_backlog");
+        assert_eq!(NodeIdRequest::default().action, "node_id");
+        assert_eq!(StopRequest::default().action, "stop");
+    }
+
+    #[test]
+    fn test_telemetry_request_options() {
+        let json = serde_json::to_string(&TelemetryRequest::new()).unwrap();
+        assert_eq!(json, "{\"action\":\"telemetry\"}");
+
+        let json = serde_json::to_string(&TelemetryRequest::new().raw()).unwrap();
+        assert!(json.contains("\"raw\":true"));
+
+        let json = serde_json::to_string(&TelemetryRequest::new().for_peer("::1", 7075)).unwrap();
+        assert!(json.contains("\"address\":\"::1\""));
+        assert!(json.contains("\"port\":\"7075\""));
+    }
 }
@@ -1,11 +1,17 @@
 //! RPC response types.
 
 use alloc::collections::BTreeMap;
-use alloc::string::String;
+use alloc::string::{String, ToString};
 use alloc::vec::Vec;
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
-use crate::types::{Account, BlockHash, Raw, Signature, Work};
+use crate::error::{BlockError, Error, Result, RpcError};
+use crate::rpc::lenient::{Lenient, ResponseMode};
+use crate::types::{
+    Account, Block, BlockHash, ChangeBlock, Link, OpenBlock, Raw, ReceiveBlock, SendBlock,
+    Signature, StateBlock, Work,
+};
 
 /// Account balance response.
 #[derive(Debug, Clone, Deserialize)]
@@ -33,6 +39,7 @@ pub struct AccountInfoResponse {
     /// Last modified timestamp.
     pub modified_timestamp: String,
     /// Block count.
+    #[serde(deserialize_with = "count")]
     pub block_count: String,
     /// Account version.
     #[serde(default)]
@@ -50,15 +57,51 @@ pub struct AccountInfoResponse {
     #[serde(default)]
     pub receivable: Option<Raw>,
     /// Confirmation height.
-    #[serde(default)]
+    #[serde(default, deserialize_with = "optional_count")]
     pub confirmation_height: Option<String>,
     /// Confirmation height frontier.
     #[serde(default)]
     pub confirmation_height_frontier: Option<BlockHash>,
+    /// Balance as of the confirmation height (with `include_confirmed`).
+    #[serde(default)]
+    pub confirmed_balance: Option<Raw>,
+    /// Confirmation height (with `include_confirmed`).
+    #[serde(default, deserialize_with = "optional_count")]
+    pub confirmed_height: Option<String>,
+    /// Confirmed frontier (with `include_confirmed`).
+    #[serde(default)]
+    pub confirmed_frontier: Option<BlockHash>,
+    /// Representative as of the confirmation height (with `include_confirmed`).
+    #[serde(default)]
+    pub confirmed_representative: Option<Account>,
+    /// Receivable amount from confirmed sends (with `include_confirmed`).
+    #[serde(default)]
+    pub confirmed_receivable: Option<Raw>,
+}
+
+impl AccountInfoResponse {
+    /// Parse the account version (epoch) if the node reported one.
+    pub fn epoch(&self) -> Option<u8> {
+        self.account_version.as_deref()?.parse().ok()
+    }
+
+    /// Parse the number of blocks in the account chain.
+    pub fn height(&self) -> Option<u64> {
+        self.block_count.parse().ok()
+    }
+
+    /// Parse the height of the highest confirmed block.
+    pub fn confirmed_height(&self) -> Option<u64> {
+        self.confirmed_height
+            .as_deref()
+            .or(self.confirmation_height.as_deref())?
+            .parse()
+            .ok()
+    }
 }
 
 /// Account history entry.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountHistoryEntry {
     /// Block type.
     #[serde(rename = "type")]
@@ -70,9 +113,80 @@ pub struct AccountHistoryEntry {
     /// Local timestamp.
     pub local_timestamp: String,
     /// Block height.
+    #[serde(deserialize_with = "count")]
     pub height: String,
     /// Block hash.
     pub hash: BlockHash,
+    /// Block subtype (state blocks with `raw`).
+    #[serde(default)]
+    pub subtype: Option<String>,
+    /// Previous block hash (with `raw`).
+    #[serde(default)]
+    pub previous: Option<BlockHash>,
+    /// Representative (with `raw`).
+    #[serde(default)]
+    pub representative: Option<Account>,
+    /// Balance after the block (with `raw`).
+    #[serde(default)]
+    pub balance: Option<String>,
+    /// Link field (state blocks with `raw`).
+    #[serde(default)]
+    pub link: Option<String>,
+    /// Destination account (legacy send blocks with `raw`).
+    #[serde(default)]
+    pub destination: Option<Account>,
+    /// Source block hash (legacy receive and open blocks with `raw`).
+    #[serde(default)]
+    pub source: Option<BlockHash>,
+    /// Opened account (legacy open blocks with `raw`).
+    #[serde(default)]
+    pub opened: Option<Account>,
+    /// Signature (with `raw`).
+    #[serde(default)]
+    pub signature: Option<Signature>,
+    /// Work (with `raw`).
+    #[serde(default)]
+    pub work: Option<Work>,
+    /// Whether confirmed.
+    #[serde(default)]
+    pub confirmed: Option<String>,
+}
+
+impl AccountHistoryEntry {
+    /// Check if the node reported the block as confirmed.
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed.as_deref() == Some("true")
+    }
+
+    /// Rebuild the full block from an entry fetched with
+    /// [`AccountHistoryRequest::raw`](crate::rpc::AccountHistoryRequest::raw).
+    ///
+    /// Fails with [`BlockError::MissingField`] if the entry lacks raw fields.
+    pub fn to_block(&self) -> Result<Block> {
+        let account = match self.block_type.as_str() {
+            "state" => Some(self.account.clone()),
+            "open" => self.opened.clone(),
+            _ => None,
+        };
+        BlockContents {
+            block_type: self.block_type.clone(),
+            account,
+            previous: self.previous,
+            representative: self.representative.clone(),
+            balance: self.balance.clone(),
+            link: self.link.clone(),
+            link_as_account: None,
+            destination: self.destination.clone(),
+            source: self.source,
+            signature: self
+                .signature
+                .ok_or(Error::InvalidBlock(BlockError::MissingField("signature")))?,
+            work: self
+                .work
+                .ok_or(Error::InvalidBlock(BlockError::MissingField("work")))?,
+        }
+        .to_block()
+    }
 }
 
 /// Account history response.
@@ -94,6 +208,17 @@ pub struct AccountsReceivableResponse {
     pub blocks: BTreeMap<String, serde_json::Value>,
 }
 
+/// Accounts balances response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AccountsBalancesResponse {
+    /// Map of account -> balance.
+    #[serde(default)]
+    pub balances: BTreeMap<String, AccountBalanceResponse>,
+    /// Map of account -> error message, for accounts the node rejected.
+    #[serde(default)]
+    pub errors: BTreeMap<String, String>,
+}
+
 /// Block info response.
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlockInfoResponse {
@@ -104,6 +229,7 @@ pub struct BlockInfoResponse {
     /// Balance after block.
     pub balance: String,
     /// Block height.
+    #[serde(deserialize_with = "count")]
     pub height: String,
     /// Local timestamp.
     pub local_timestamp: String,
@@ -116,6 +242,137 @@ pub struct BlockInfoResponse {
     pub subtype: Option<String>,
 }
 
+impl BlockInfoResponse {
+    /// Get the destination account if this is a send block.
+    pub fn send_destination(&self) -> Option<Account> {
+        match self.contents.block_type.as_str() {
+            "state" if self.subtype.as_deref() == Some("send") => {
+                match &self.contents.link_as_account {
+                    Some(account) => Some(account.clone()),
+                    None => {
+                        let link = Link::from_hex(self.contents.link.as_deref()?).ok()?;
+                        Some(Account::from_public_key(&link.as_public_key()))
+                    }
+                }
+            }
+            "send" => self.contents.destination.clone(),
+            _ => None,
+        }
+    }
+
+    /// Get the hash of the send block pocketed, if this is a receive or
+    /// open block.
+    pub fn receive_source(&self) -> Option<BlockHash> {
+        match self.contents.block_type.as_str() {
+            "state" if matches!(self.subtype.as_deref(), Some("receive") | Some("open")) => {
+                let link = Link::from_hex(self.contents.link.as_deref()?).ok()?;
+                Some(link.as_block_hash())
+            }
+            "receive" | "open" => self.contents.source,
+            _ => None,
+        }
+    }
+}
+
+/// Blocks info response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlocksInfoResponse {
+    /// Map of block hash -> block info.
+    pub blocks: BTreeMap<String, BlockInfoResponse>,
+}
+
+/// Chain or successors response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChainResponse {
+    /// Block hashes in walk order, starting with the requested block
+    /// unless an offset skipped it.
+    #[serde(default, deserialize_with = "empty_string_as_default")]
+    pub blocks: Vec<BlockHash>,
+}
+
+/// The node returns an empty string instead of an empty list or map.
+fn empty_string_as_default<'de, D, T>(deserializer: D) -> core::result::Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de> + Default,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OrEmpty<T> {
+        Value(T),
+        Empty(String),
+    }
+    match OrEmpty::deserialize(deserializer)? {
+        OrEmpty::Value(value) => Ok(value),
+        OrEmpty::Empty(s) if s.is_empty() => Ok(T::default()),
+        OrEmpty::Empty(_) => Err(serde::de::Error::custom("expected a list or map")),
+    }
+}
+
+/// Counts are sent as strings by the node and as integers by some
+/// providers.
+fn count<'de, D>(deserializer: D) -> core::result::Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct CountVisitor;
+
+    impl serde::de::Visitor<'_> for CountVisitor {
+        type Value = String;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("a count as a string or integer")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> core::result::Result<String, E> {
+            Ok(value.to_string())
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, value: u64) -> core::result::Result<String, E> {
+            Ok(value.to_string())
+        }
+    }
+
+    deserializer.deserialize_any(CountVisitor)
+}
+
+/// Work is sent as a hex string by the node and as an integer by some
+/// work providers.
+fn work<'de, D>(deserializer: D) -> core::result::Result<Work, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct WorkVisitor;
+
+    impl serde::de::Visitor<'_> for WorkVisitor {
+        type Value = Work;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("work as a hex string or integer")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, value: &str) -> core::result::Result<Work, E> {
+            Work::from_hex(value).map_err(E::custom)
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, value: u64) -> core::result::Result<Work, E> {
+            Ok(Work::new(value))
+        }
+    }
+
+    deserializer.deserialize_any(WorkVisitor)
+}
+
+/// Like [`count`], for counts only some nodes send.
+fn optional_count<'de, D>(deserializer: D) -> core::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Optional(#[serde(deserialize_with = "count")] String);
+    Ok(Option::<Optional>::deserialize(deserializer)?.map(|Optional(s)| s))
+}
+
 /// Block contents within block info.
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlockContents {
@@ -135,21 +392,87 @@ pub struct BlockContents {
     /// Link as account (for sends).
     #[serde(default)]
     pub link_as_account: Option<Account>,
+    /// Destination account (legacy send blocks).
+    #[serde(default)]
+    pub destination: Option<Account>,
+    /// Source block hash (legacy receive and open blocks).
+    #[serde(default)]
+    pub source: Option<BlockHash>,
     /// Signature.
     pub signature: Signature,
     /// Work.
     pub work: Work,
 }
 
+impl BlockContents {
+    /// Convert into a typed [`Block`], including legacy block types.
+    pub fn to_block(&self) -> Result<Block> {
+        fn required<T: Clone>(field: &Option<T>, name: &'static str) -> Result<T> {
+            field
+                .clone()
+                .ok_or(Error::InvalidBlock(BlockError::MissingField(name)))
+        }
+
+        let block_type = self.block_type.clone();
+        let block = match self.block_type.as_str() {
+            "send" => Block::Send(SendBlock {
+                block_type,
+                previous: required(&self.previous, "previous")?,
+                destination: required(&self.destination, "destination")?,
+                balance: Raw::from_hex(&required(&self.balance, "balance")?)?,
+                signature: self.signature,
+                work: self.work,
+            }),
+            "receive" => Block::Receive(ReceiveBlock {
+                block_type,
+                previous: required(&self.previous, "previous")?,
+                source: required(&self.source, "source")?,
+                signature: self.signature,
+                work: self.work,
+            }),
+            "open" => Block::Open(OpenBlock {
+                block_type,
+                source: required(&self.source, "source")?,
+                representative: required(&self.representative, "representative")?,
+                account: required(&self.account, "account")?,
+                signature: self.signature,
+                work: self.work,
+            }),
+            "change" => Block::Change(ChangeBlock {
+                block_type,
+                previous: required(&self.previous, "previous")?,
+                representative: required(&self.representative, "representative")?,
+                signature: self.signature,
+                work: self.work,
+            }),
+            "state" => Block::State(
+                StateBlock::new(
+                    required(&self.account, "account")?,
+                    required(&self.previous, "previous")?,
+                    required(&self.representative, "representative")?,
+                    required(&self.balance, "balance")?.parse()?,
+                    Link::from_hex(&required(&self.link, "link")?)?,
+                )
+                .with_signature(self.signature)
+                .with_work(self.work),
+            ),
+            _ => return Err(Error::InvalidBlock(BlockError::InvalidSubtype)),
+        };
+        Ok(block)
+    }
+}
+
 /// Block count response.
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlockCountResponse {
     /// Total blocks.
+    #[serde(deserialize_with = "count")]
     pub count: String,
     /// Unchecked blocks.
+    #[serde(deserialize_with = "count")]
     pub unchecked: String,
     /// Cemented blocks.
-    #[serde(default)]
+    #[serde(default, deserialize_with = "optional_count")]
     pub cemented: Option<String>,
 }
 
@@ -164,6 +487,7 @@ pub struct ProcessResponse {
 #[derive(Debug, Clone, Deserialize)]
 pub struct WorkGenerateResponse {
     /// Generated work.
+    #[serde(deserialize_with = "work")]
     pub work: Work,
     /// Difficulty achieved.
     #[serde(default)]
@@ -176,6 +500,33 @@ pub struct WorkGenerateResponse {
     pub hash: Option<BlockHash>,
 }
 
+/// Work peers response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkPeersResponse {
+    /// Work peers as `address:port`.
+    #[serde(default, deserialize_with = "empty_string_as_default")]
+    pub work_peers: Vec<String>,
+}
+
+/// Active difficulty response.
+///
+/// Difficulties are 16 hex digits, as in work requests.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActiveDifficultyResponse {
+    /// Minimum difficulty for send and change blocks.
+    pub network_minimum: String,
+    /// Minimum difficulty for receive and open blocks.
+    #[serde(default)]
+    pub network_receive_minimum: Option<String>,
+    /// Current difficulty for send and change blocks.
+    pub network_current: String,
+    /// Current difficulty for receive and open blocks.
+    #[serde(default)]
+    pub network_receive_current: Option<String>,
+    /// Current difficulty relative to the minimum.
+    pub multiplier: String,
+}
+
 /// Version response.
 #[derive(Debug, Clone, Deserialize)]
 pub struct VersionResponse {
@@ -212,16 +563,21 @@ pub struct PeersResponse {
 #[derive(Debug, Clone, Deserialize)]
 pub struct TelemetryResponse {
     /// Block count.
+    #[serde(deserialize_with = "count")]
     pub block_count: String,
     /// Cemented count.
+    #[serde(deserialize_with = "count")]
     pub cemented_count: String,
     /// Unchecked count.
+    #[serde(deserialize_with = "count")]
     pub unchecked_count: String,
     /// Account count.
+    #[serde(deserialize_with = "count")]
     pub account_count: String,
     /// Bandwidth cap.
     pub bandwidth_cap: String,
     /// Peer count.
+    #[serde(deserialize_with = "count")]
     pub peer_count: String,
     /// Protocol version.
     pub protocol_version: String,
@@ -244,6 +600,26 @@ pub struct TelemetryResponse {
     /// Active difficulty.
     #[serde(default)]
     pub active_difficulty: Option<String>,
+    /// Peer node ID, in per-peer metrics.
+    #[serde(default)]
+    pub node_id: Option<String>,
+    /// Signature over the metrics, in per-peer metrics.
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// Peer address, in per-peer metrics.
+    #[serde(default)]
+    pub address: Option<String>,
+    /// Peer port, in per-peer metrics.
+    #[serde(default)]
+    pub port: Option<String>,
+}
+
+/// Per-peer telemetry response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TelemetryRawResponse {
+    /// Metrics reported by each peer.
+    #[serde(default, deserialize_with = "empty_string_as_default")]
+    pub metrics: Vec<TelemetryResponse>,
 }
 
 /// Representatives response.
@@ -271,6 +647,7 @@ pub struct AvailableSupplyResponse {
 #[derive(Debug, Clone, Deserialize)]
 pub struct FrontierCountResponse {
     /// Number of accounts.
+    #[serde(deserialize_with = "count")]
     pub count: String,
 }
 
@@ -292,6 +669,201 @@ pub struct ConfirmationQuorumResponse {
     pub peers_stake_total: Raw,
 }
 
+/// Unchecked blocks response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UncheckedResponse {
+    /// Map of block hash -> block contents.
+    #[serde(default, deserialize_with = "empty_string_as_default")]
+    pub blocks: BTreeMap<String, BlockContents>,
+}
+
+/// Unchecked block response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UncheckedGetResponse {
+    /// When the block was added to the unchecked table, in seconds.
+    pub modified_timestamp: String,
+    /// Block contents.
+    pub contents: BlockContents,
+}
+
+/// Unchecked block waiting on a dependency.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UncheckedEntry {
+    /// Hash of the missing dependency.
+    pub key: BlockHash,
+    /// Hash of the waiting block.
+    pub hash: BlockHash,
+    /// When the block was added to the unchecked table, in seconds.
+    #[serde(default)]
+    pub modified_timestamp: Option<String>,
+    /// Block contents.
+    pub contents: BlockContents,
+}
+
+/// Unchecked keys response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UncheckedKeysResponse {
+    /// Unchecked blocks ordered by dependency key.
+    #[serde(default, deserialize_with = "empty_string_as_default")]
+    pub unchecked: Vec<UncheckedEntry>,
+}
+
+/// Lazy bootstrap response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BootstrapLazyResponse {
+    /// "1" if a lazy bootstrap attempt was started.
+    pub started: String,
+    /// "1" if the hash was queued in the attempt.
+    #[serde(default)]
+    pub key_inserted: Option<String>,
+}
+
+/// Bootstrap connection counts.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BootstrapConnections {
+    /// Bootstrap clients.
+    #[serde(default)]
+    pub clients: String,
+    /// Open connections.
+    #[serde(default)]
+    pub connections: String,
+    /// Idle connections.
+    #[serde(default)]
+    pub idle: String,
+    /// Target number of connections.
+    #[serde(default)]
+    pub target_connections: String,
+    /// Pulls in progress.
+    #[serde(default)]
+    pub pulls: String,
+}
+
+/// Running bootstrap attempt.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BootstrapAttempt {
+    /// Attempt identifier.
+    #[serde(default)]
+    pub id: String,
+    /// Bootstrap mode, e.g. "legacy", "lazy" or "wallet_lazy".
+    #[serde(default)]
+    pub mode: String,
+    /// Blocks pulled so far.
+    #[serde(default)]
+    pub total_blocks: String,
+    /// Seconds since the attempt started.
+    #[serde(default)]
+    pub duration: String,
+    /// Mode-specific fields.
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_json::Value>,
+}
+
+/// Bootstrap status response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BootstrapStatusResponse {
+    /// Bootstrap threads.
+    #[serde(default)]
+    pub bootstrap_threads: String,
+    /// Attempts currently running.
+    #[serde(default)]
+    #[serde(deserialize_with = "count")]
+    pub running_attempts_count: String,
+    /// Attempts since the node started.
+    #[serde(default)]
+    #[serde(deserialize_with = "count")]
+    pub total_attempts_count: String,
+    /// Connection counts.
+    #[serde(default)]
+    pub connections: BootstrapConnections,
+    /// Running attempts.
+    #[serde(default, deserialize_with = "empty_string_as_default")]
+    pub attempts: Vec<BootstrapAttempt>,
+}
+
+/// One counter in a [`StatsCountersResponse`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatsEntry {
+    /// Time of the last update, as `HH:MM:SS`.
+    #[serde(default)]
+    pub time: String,
+    /// Statistic type, e.g. "ledger" or "vote".
+    #[serde(rename = "type")]
+    pub stat_type: String,
+    /// Detail within the type, e.g. "send" or "all".
+    pub detail: String,
+    /// Direction, "in" or "out".
+    pub dir: String,
+    /// Counter value.
+    pub value: String,
+}
+
+/// Counter statistics response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StatsCountersResponse {
+    /// Statistics kind, always "counters".
+    #[serde(rename = "type")]
+    pub stats_type: String,
+    /// When the statistics were collected.
+    #[serde(default)]
+    pub created: String,
+    /// Counters.
+    #[serde(default, deserialize_with = "empty_string_as_default")]
+    pub entries: Vec<StatsEntry>,
+}
+
+impl StatsCountersResponse {
+    /// Get a counter value by type, detail and direction.
+    pub fn counter(&self, stat_type: &str, detail: &str, dir: &str) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|e| e.stat_type == stat_type && e.detail == detail && e.dir == dir)
+            .and_then(|e| e.value.parse().ok())
+    }
+}
+
+/// Node ID response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeIdResponse {
+    /// Node ID public key, hex encoded.
+    pub public: String,
+    /// Node ID as an account address.
+    #[serde(default)]
+    pub as_account: Option<Account>,
+    /// Node ID in `node_` form.
+    #[serde(default)]
+    pub node_id: Option<String>,
+}
+
+/// One account of a `ledger` response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LedgerEntry {
+    /// Account frontier (latest block hash).
+    pub frontier: BlockHash,
+    /// Open block hash.
+    pub open_block: BlockHash,
+    /// Representative block hash.
+    pub representative_block: BlockHash,
+    /// Current balance.
+    pub balance: Raw,
+    /// Last modified timestamp.
+    pub modified_timestamp: String,
+    /// Block count.
+    #[serde(deserialize_with = "count")]
+    pub block_count: String,
+    /// Representative account (with `representative`).
+    #[serde(default)]
+    pub representative: Option<Account>,
+    /// Voting weight (with `weight`).
+    #[serde(default)]
+    pub weight: Option<Raw>,
+    /// Pending balance (with `receivable`, older nodes).
+    #[serde(default)]
+    pub pending: Option<Raw>,
+    /// Receivable balance (with `receivable`).
+    #[serde(default)]
+    pub receivable: Option<Raw>,
+}
+
 /// Generic error response.
 #[derive(Debug, Clone, Deserialize)]
 pub struct ErrorResponse {
@@ -303,3 +875,44 @@ pub struct ErrorResponse {
 pub fn check_error(json: &serde_json::Value) -> Option<String> {
     json.get("error").and_then(|e| e.as_str()).map(String::from)
 }
+
+/// Longest part of a response kept in an [`RpcError::SchemaMismatch`], in
+/// bytes.
+pub const MAX_MISMATCH_RESPONSE_LEN: usize = 1024;
+
+/// Parse a response as a `T`, reporting the field that doesn't match.
+pub(crate) fn parse_response<T: DeserializeOwned>(
+    action: &str,
+    json: &serde_json::Value,
+    mode: ResponseMode,
+) -> Result<T> {
+    let result = match mode {
+        ResponseMode::Lenient => serde_path_to_error::deserialize(Lenient(json)),
+        ResponseMode::Strict => serde_path_to_error::deserialize(json),
+    };
+    result.map_err(|e| {
+        let response = serde_json::to_vec(json).unwrap_or_default();
+        schema_mismatch(action, e.path(), e.inner(), &response)
+    })
+}
+
+/// Build an [`RpcError::SchemaMismatch`] for a field of a response.
+pub(crate) fn schema_mismatch(
+    action: &str,
+    path: &serde_path_to_error::Path,
+    error: &serde_json::Error,
+    response: &[u8],
+) -> Error {
+    let mut truncated =
+        String::from_utf8_lossy(&response[..response.len().min(MAX_MISMATCH_RESPONSE_LEN)])
+            .into_owned();
+    if response.len() > MAX_MISMATCH_RESPONSE_LEN {
+        truncated.push_str("...");
+    }
+    Error::Rpc(RpcError::SchemaMismatch {
+        action: action.to_string(),
+        path: path.to_string(),
+        message: error.to_string(),
+        response: truncated,
+    })
+}
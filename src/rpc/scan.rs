@@ -0,0 +1,438 @@
+//! Whole-ledger scans with bounded memory.
+//!
+//! `ledger`, `frontiers` and `delegators` responses can be tens of
+//! megabytes. The scans here request them a page at a time and hand each
+//! entry to a callback as it is parsed, so neither a JSON tree nor a map of
+//! every entry is built. Memory stays at about one page of response bytes.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use alloc::string::{String, ToString};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserializer, Serialize};
+
+use crate::error::{Error, Result, RpcError};
+use crate::rpc::lenient::{Lenient, ResponseMode};
+use crate::rpc::responses::schema_mismatch;
+use crate::rpc::{DelegatorsRequest, FrontiersRequest, LedgerEntry, LedgerRequest, RpcClient};
+use crate::types::{Account, BlockHash, Raw};
+
+/// A reasonable page size for the scans, about a megabyte of `ledger`
+/// response per page.
+pub const DEFAULT_SCAN_PAGE_SIZE: u64 = 10_000;
+
+impl RpcClient {
+    /// Visit every account of the ledger from the request's start.
+    ///
+    /// Requests pages of `request.count` accounts, continuing from the
+    /// last account of each page, until a page comes back short. Each
+    /// account is passed to `f` as it is parsed. Returns the number of
+    /// accounts visited.
+    ///
+    /// Each page starts with the last account of the previous one, so use
+    /// pages of more than one account.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xno_connect::rpc::{LedgerRequest, RpcClient};
+    /// use xno_connect::types::Raw;
+    ///
+    /// # async fn example() -> xno_connect::error::Result<()> {
+    /// let client = RpcClient::new("http://localhost:7076");
+    /// let mut total = Raw::ZERO;
+    /// client
+    ///     .scan_ledger(LedgerRequest::new(10_000), |_, entry| {
+    ///         total = total.saturating_add(entry.balance);
+    ///     })
+    ///     .await?;
+    /// println!("{} raw in opened accounts", total);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn scan_ledger<F>(&self, request: LedgerRequest, f: F) -> Result<u64>
+    where
+        F: FnMut(Account, LedgerEntry),
+    {
+        let page_size = request.count.parse().ok();
+        self.scan_pages(
+            request,
+            "accounts",
+            page_size,
+            |request, last| request.account = Some(last.as_str().to_string()),
+            f,
+        )
+        .await
+    }
+
+    /// Visit the frontier of every account from the request's start.
+    ///
+    /// Pages like [`scan_ledger`](Self::scan_ledger). Start at the
+    /// beginning of the ledger with
+    /// `FrontiersRequest::from_start(DEFAULT_SCAN_PAGE_SIZE)`.
+    pub async fn scan_frontiers<F>(&self, request: FrontiersRequest, f: F) -> Result<u64>
+    where
+        F: FnMut(Account, BlockHash),
+    {
+        let page_size = request.count.parse().ok();
+        self.scan_pages(
+            request,
+            "frontiers",
+            page_size,
+            |request, last| request.account = last.as_str().to_string(),
+            f,
+        )
+        .await
+    }
+
+    /// Visit every delegator of a representative with its balance.
+    ///
+    /// With a `count` on the request, pages through the delegators with
+    /// `start`. Without one, the node returns them all in one response,
+    /// which is still parsed entry by entry.
+    pub async fn scan_delegators<F>(&self, request: DelegatorsRequest, f: F) -> Result<u64>
+    where
+        F: FnMut(Account, Raw),
+    {
+        let page_size = request
+            .count
+            .as_deref()
+            .and_then(|count| count.parse().ok());
+        self.scan_pages(
+            request,
+            "delegators",
+            page_size,
+            |request, last| request.start = Some(last.as_str().to_string()),
+            f,
+        )
+        .await
+    }
+
+    /// Request pages until one has fewer than `page_size` entries.
+    ///
+    /// Pages may start with the last account of the previous page, which
+    /// is skipped, so pages of a single account end the scan after the
+    /// first.
+    async fn scan_pages<Req, V, F>(
+        &self,
+        mut request: Req,
+        field: &str,
+        page_size: Option<u64>,
+        continue_after: fn(&mut Req, &Account),
+        mut f: F,
+    ) -> Result<u64>
+    where
+        Req: Serialize,
+        V: DeserializeOwned,
+        F: FnMut(Account, V),
+    {
+        let mut visited = 0;
+        let mut cursor: Option<Account> = None;
+        loop {
+            let mut rows = 0;
+            let mut last = None;
+            self.request_rows(&request, field, &mut |account: Account, value: V| {
+                rows += 1;
+                if cursor.as_ref() == Some(&account) {
+                    return;
+                }
+                f(account.clone(), value);
+                visited += 1;
+                last = Some(account);
+            })
+            .await?;
+            match (page_size.filter(|&size| size > 0), last) {
+                (Some(size), Some(last)) if rows >= size => {
+                    continue_after(&mut request, &last);
+                    cursor = Some(last);
+                }
+                _ => return Ok(visited),
+            }
+        }
+    }
+}
+
+/// Parse an RPC response, passing each entry of its `field` object to `f`.
+///
+/// Other fields are skipped without being stored. A node `error` field
+/// becomes [`RpcError::NodeError`]. An empty string in place of the
+/// object, as nodes send for empty results, has no entries. Entries that
+/// don't parse fail with [`RpcError::SchemaMismatch`] for `action`.
+pub(crate) fn visit_rows<V: DeserializeOwned>(
+    bytes: &[u8],
+    action: &str,
+    field: &str,
+    mode: ResponseMode,
+    f: &mut dyn FnMut(Account, V),
+) -> Result<()> {
+    let mut response = ResponseVisitor {
+        field,
+        mode,
+        f,
+        error: None,
+    };
+    let mut track = serde_path_to_error::Track::new();
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    serde_path_to_error::Deserializer::new(&mut deserializer, &mut track)
+        .deserialize_map(&mut response)
+        .and_then(|()| deserializer.end())
+        .map_err(|e| {
+            if e.is_data() {
+                schema_mismatch(action, &track.path(), &e, bytes)
+            } else {
+                Error::Rpc(RpcError::InvalidResponse(e.to_string()))
+            }
+        })?;
+    match response.error {
+        Some(error) => Err(Error::Rpc(RpcError::NodeError(error))),
+        None => Ok(()),
+    }
+}
+
+struct ResponseVisitor<'a, V> {
+    field: &'a str,
+    mode: ResponseMode,
+    f: &'a mut dyn FnMut(Account, V),
+    error: Option<String>,
+}
+
+impl<'de, V: DeserializeOwned> Visitor<'de> for &mut ResponseVisitor<'_, V> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an RPC response object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> core::result::Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.field {
+                map.next_value_seed(Rows {
+                    mode: self.mode,
+                    f: &mut *self.f,
+                    marker: PhantomData,
+                })?;
+            } else if key == "error" {
+                self.error = Some(map.next_value()?);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Entries of the object keyed by account.
+struct Rows<'a, V> {
+    mode: ResponseMode,
+    f: &'a mut dyn FnMut(Account, V),
+    marker: PhantomData<V>,
+}
+
+impl<'de, V: DeserializeOwned> DeserializeSeed<'de> for Rows<'_, V> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> core::result::Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, V: DeserializeOwned> Visitor<'de> for Rows<'_, V> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an object keyed by account")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> core::result::Result<(), A::Error> {
+        while let Some(account) = map.next_key::<Account>()? {
+            let value = match self.mode {
+                ResponseMode::Strict => map.next_value::<V>()?,
+                // One entry at a time, so memory stays at one entry's JSON
+                ResponseMode::Lenient => {
+                    let json = map.next_value::<serde_json::Value>()?;
+                    serde_path_to_error::deserialize(Lenient(&json)).map_err(|e| {
+                        de::Error::custom(format_args!("{} at {}", e.inner(), e.path()))
+                    })?
+                }
+            };
+            (self.f)(account, value);
+        }
+        Ok(())
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> core::result::Result<(), E> {
+        if value.is_empty() {
+            Ok(())
+        } else {
+            Err(E::invalid_value(de::Unexpected::Str(value), &self))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const FIRST: &str = "nano_1111111111111111111111111111111111111111111111111111hifc8npp";
+    const SECOND: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+
+    #[test]
+    fn test_visit_rows() {
+        let body = format!(
+            r#"{{"representative": "ignored", "delegators": {{"{}": "1", "{}": "20"}}, "extra": [1, {{}}]}}"#,
+            FIRST, SECOND
+        );
+        let mut rows = Vec::new();
+        visit_rows(
+            body.as_bytes(),
+            "delegators",
+            "delegators",
+            ResponseMode::Strict,
+            &mut |account, balance: Raw| rows.push((account.to_string(), balance)),
+        )
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (FIRST.to_string(), Raw::new(1)),
+                (SECOND.to_string(), Raw::new(20))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visit_rows_empty_and_errors() {
+        let mut count = 0;
+        let mut f = |_: Account, _: Raw| count += 1;
+        visit_rows(
+            br#"{"delegators": ""}"#,
+            "delegators",
+            "delegators",
+            ResponseMode::Strict,
+            &mut f,
+        )
+        .unwrap();
+        visit_rows(
+            br#"{}"#,
+            "delegators",
+            "delegators",
+            ResponseMode::Strict,
+            &mut f,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            visit_rows(br#"{"error": "Bad account number"}"#, "delegators", "delegators", ResponseMode::Strict, &mut f),
+            Err(Error::Rpc(RpcError::NodeError(ref e))) if e == "Bad account number"
+        ));
+        let body = format!(r#"{{"delegators": {{"{}": "-1"}}}}"#, FIRST);
+        match visit_rows(
+            body.as_bytes(),
+            "delegators",
+            "delegators",
+            ResponseMode::Strict,
+            &mut f,
+        ) {
+            Err(Error::Rpc(RpcError::SchemaMismatch {
+                action,
+                path,
+                response,
+                ..
+            })) => {
+                assert_eq!(action, "delegators");
+                assert_eq!(path, format!("delegators.{}", FIRST));
+                assert_eq!(response, body);
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(matches!(
+            visit_rows(
+                br#"{"delegators": {"nano_bad": "1"}}"#,
+                "delegators",
+                "delegators",
+                ResponseMode::Strict,
+                &mut f
+            ),
+            Err(Error::Rpc(RpcError::SchemaMismatch { .. }))
+        ));
+        assert!(visit_rows(
+            br#"{"delegators": "x"}"#,
+            "delegators",
+            "delegators",
+            ResponseMode::Strict,
+            &mut f
+        )
+        .is_err());
+        assert!(matches!(
+            visit_rows(
+                br#"{} trailing"#,
+                "delegators",
+                "delegators",
+                ResponseMode::Strict,
+                &mut f
+            ),
+            Err(Error::Rpc(RpcError::InvalidResponse(_)))
+        ));
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_visit_rows_lenient() {
+        let body = format!(r#"{{"delegators": {{"{}": 7}}}}"#, FIRST);
+        let mut rows = Vec::new();
+        let mut f = |_: Account, balance: Raw| rows.push(balance);
+        assert!(matches!(
+            visit_rows(
+                body.as_bytes(),
+                "delegators",
+                "delegators",
+                ResponseMode::Strict,
+                &mut f
+            ),
+            Err(Error::Rpc(RpcError::SchemaMismatch { .. }))
+        ));
+        visit_rows(
+            body.as_bytes(),
+            "delegators",
+            "delegators",
+            ResponseMode::Lenient,
+            &mut f,
+        )
+        .unwrap();
+        assert_eq!(rows, vec![Raw::new(7)]);
+    }
+
+    #[test]
+    fn test_visit_ledger_entries() {
+        let body = format!(
+            r#"{{"accounts": {{"{}": {{
+                "frontier": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "open_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "representative_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "balance": "5",
+                "modified_timestamp": "1501793775",
+                "block_count": "33",
+                "representative": "{}"
+            }}}}}}"#,
+            SECOND, SECOND
+        );
+        let mut entries = Vec::new();
+        visit_rows(
+            body.as_bytes(),
+            "ledger",
+            "accounts",
+            ResponseMode::Strict,
+            &mut |account, entry: LedgerEntry| entries.push((account, entry)),
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].1.balance, Raw::new(5));
+        assert_eq!(entries[0].1.representative.as_ref(), Some(&entries[0].0));
+        assert_eq!(entries[0].1.weight, None);
+    }
+}
@@ -0,0 +1,123 @@
+//! Handling of secret material.
+//!
+//! The crate keeps these guarantees for seeds, private keys and keypairs:
+//!
+//! - **Debug**: [`Seed`](crate::keys::Seed), [`SecretKey`](crate::keys::SecretKey),
+//!   [`KeyPair`](crate::keys::KeyPair) and [`SecretString`] print
+//!   `[REDACTED]` instead of key bytes.
+//! - **Serde**: none of them implement `Serialize`. Secrets leave the crate
+//!   only through explicit calls such as `to_hex_secret` or an encrypted
+//!   seed export.
+//! - **Drop**: they are zeroed when dropped, including the expanded signing
+//!   scalar and nonce prefix of a keypair. Signing and derivation wipe their
+//!   intermediate values, such as the per-signature nonce.
+//!
+//! A [`Wallet`](crate::wallet::Wallet) grows its key cache without
+//! reallocating in place, so old buffers are wiped rather than freed with
+//! keys in them. Copies the compiler makes on the stack when values move
+//! cannot be wiped from safe Rust; keep secrets in long-lived values and
+//! pass them by reference.
+//!
+//! Verification and hashing only touch public data and may run in
+//! variable time. Signing and key derivation use constant-time scalar
+//! arithmetic.
+
+use alloc::string::String;
+use core::fmt;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// String holding secret text, such as an exported seed.
+///
+/// Wiped when dropped. Its `Debug` output is redacted, and it has no
+/// `Display` or `Serialize` implementation, so the text is only reachable
+/// through [`expose_secret`](Self::expose_secret).
+#[derive(Clone, Default, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// Take ownership of secret text.
+    pub fn new(secret: String) -> Self {
+        SecretString(secret)
+    }
+
+    /// Get the secret text.
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// Length of the text in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check if the text is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        SecretString::new(secret)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretString([REDACTED])")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::{KeyPair, Seed};
+    use alloc::format;
+
+    const SEED_HEX: &str = "9F0E444C69F77A49BD0BE89DB92C38FE713E0963165CCA12FAF5712D7657120F";
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let seed = Seed::from_hex(SEED_HEX).unwrap();
+        let keypair = seed.derive(0);
+        let secret_hex = keypair.secret_key().to_hex();
+
+        for output in [
+            format!("{:?}", seed),
+            format!("{:?}", keypair),
+            format!("{:?}", keypair.secret_key()),
+            format!("{:?}", seed.to_hex_secret()),
+        ] {
+            assert!(output.contains("REDACTED"), "{}", output);
+            assert!(!output.contains(SEED_HEX));
+            assert!(!output.contains(&secret_hex));
+        }
+    }
+
+    #[test]
+    fn test_hex_exports() {
+        let seed = Seed::from_hex(SEED_HEX).unwrap();
+        let exported = seed.to_hex_secret();
+        assert_eq!(exported.expose_secret(), SEED_HEX);
+        assert_eq!(exported.len(), 64);
+
+        let keypair = seed.derive(0);
+        assert_eq!(
+            keypair.secret_key().to_hex_secret().expose_secret(),
+            keypair.secret_key().to_hex()
+        );
+    }
+
+    #[test]
+    fn test_zeroize_wipes_secrets() {
+        use zeroize::Zeroize;
+
+        let mut secret = SecretString::new(SEED_HEX.into());
+        secret.zeroize();
+        assert!(secret.is_empty());
+
+        let mut keypair: KeyPair = Seed::from_hex(SEED_HEX).unwrap().derive(0);
+        keypair.zeroize();
+        assert_eq!(keypair.secret_key().as_bytes(), &[0u8; 32]);
+    }
+}
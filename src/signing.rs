@@ -0,0 +1,116 @@
+//! Off-chain message signing.
+//!
+//! Messages are hashed together with a domain separator before signing, so
+//! a message signature can never be replayed as a block signature: block
+//! hashes are computed over a different preimage and cannot collide with a
+//! signed message digest.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::keys::Seed;
+//! use xno_connect::signing::verify_nano_message;
+//!
+//! let keypair = Seed::from_bytes([1u8; 32]).derive(0);
+//! let signature = keypair.sign_nano_message(b"login:1700000000");
+//!
+//! assert!(verify_nano_message(&keypair.account(), b"login:1700000000", &signature));
+//! ```
+
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+
+use crate::keys::KeyPair;
+use crate::types::{Account, Signature};
+
+/// Domain separator for the "Nano Signed Message" convention.
+pub const NANO_MESSAGE_DOMAIN: &[u8] = b"Nano Signed Message:\n";
+
+/// Hash a message for signing under a domain separator.
+///
+/// Computes `blake2b-256(domain || len(message) as u64 LE || message)`; the
+/// length prefix keeps the boundary between domain and message unambiguous.
+pub fn message_hash(domain: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(domain);
+    hasher.update((message.len() as u64).to_le_bytes());
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Sign a message under a custom domain separator.
+pub fn sign_with_domain(keypair: &KeyPair, domain: &[u8], message: &[u8]) -> Signature {
+    keypair.sign_message(&message_hash(domain, message))
+}
+
+/// Verify a message signature under a custom domain separator.
+pub fn verify_with_domain(
+    account: &Account,
+    domain: &[u8],
+    message: &[u8],
+    signature: &Signature,
+) -> bool {
+    KeyPair::verify_message_with_public_key(
+        account.public_key(),
+        &message_hash(domain, message),
+        signature,
+    )
+}
+
+/// Sign a message using the "Nano Signed Message" domain.
+pub fn sign_nano_message(keypair: &KeyPair, message: &[u8]) -> Signature {
+    sign_with_domain(keypair, NANO_MESSAGE_DOMAIN, message)
+}
+
+/// Verify a message signed with [`sign_nano_message`].
+pub fn verify_nano_message(account: &Account, message: &[u8], signature: &Signature) -> bool {
+    verify_with_domain(account, NANO_MESSAGE_DOMAIN, message, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::Seed;
+    use crate::types::BlockHash;
+
+    fn test_keypair() -> KeyPair {
+        Seed::from_bytes([7u8; 32]).derive(0)
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keypair = test_keypair();
+        let account = keypair.account();
+        let signature = keypair.sign_nano_message(b"hello");
+
+        assert!(verify_nano_message(&account, b"hello", &signature));
+        assert!(!verify_nano_message(&account, b"hello!", &signature));
+
+        let other = Seed::from_bytes([8u8; 32]).derive(0).account();
+        assert!(!verify_nano_message(&other, b"hello", &signature));
+    }
+
+    #[test]
+    fn test_not_valid_as_block_signature() {
+        let keypair = test_keypair();
+        let message = [0xABu8; 32];
+        let signature = keypair.sign_nano_message(&message);
+
+        // Even a 32-byte message does not verify as a block hash signature
+        assert!(!keypair.verify(&BlockHash::from_bytes(message), &signature));
+        assert_ne!(signature, keypair.sign_message(&message));
+    }
+
+    #[test]
+    fn test_domains_are_separated() {
+        let keypair = test_keypair();
+        let account = keypair.account();
+        let signature = sign_with_domain(&keypair, b"my-dapp", b"hello");
+
+        assert!(verify_with_domain(
+            &account, b"my-dapp", b"hello", &signature
+        ));
+        assert!(!verify_nano_message(&account, b"hello", &signature));
+        assert_ne!(message_hash(b"ab", b"c"), message_hash(b"a", b"bc"));
+    }
+}
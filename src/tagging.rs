@@ -0,0 +1,201 @@
+//! Unique payment amounts for a shared receiving address.
+//!
+//! A merchant that reuses one address cannot tell payers apart by
+//! destination. [`AmountTagger`] adds a small raw offset to each invoice
+//! amount so that every open invoice expects a different amount, then maps
+//! a confirmed amount back to its invoice.
+//!
+//! Offsets are derived from the invoice ID, so tagging is reproducible
+//! across restarts as long as invoices are tagged in the same order. If the
+//! derived offset is taken by another open invoice, the next free offset is
+//! used.
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::tagging::AmountTagger;
+//! use xno_connect::types::Raw;
+//!
+//! # fn main() -> xno_connect::error::Result<()> {
+//! let mut tagger = AmountTagger::new(1_000_000);
+//! let price = Raw::from_nano(3)?;
+//! let a = tagger.tag("order-1", price)?;
+//! let b = tagger.tag("order-2", price)?;
+//! assert_ne!(a, b);
+//!
+//! assert_eq!(tagger.decode(b), Some("order-2"));
+//! tagger.release("order-2");
+//! assert_eq!(tagger.decode(b), None);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::collections::btree_map::Entry;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use blake2::{Blake2b512, Digest};
+
+use crate::error::{AmountError, Error, Result};
+use crate::types::Raw;
+
+/// Assigns unique amounts to open invoices.
+#[derive(Debug, Clone)]
+pub struct AmountTagger {
+    max_offset: u128,
+    key: [u8; 32],
+    /// Tagged amount -> invoice ID.
+    amounts: BTreeMap<Raw, String>,
+    /// Invoice ID -> tagged amount.
+    invoices: BTreeMap<String, Raw>,
+}
+
+impl AmountTagger {
+    /// Create a tagger adding offsets from 1 to `max_offset` raw.
+    ///
+    /// At most `max_offset` invoices with the same base amount can be open
+    /// at once. A `max_offset` of zero is treated as one.
+    pub fn new(max_offset: u128) -> Self {
+        AmountTagger {
+            max_offset: max_offset.max(1),
+            key: [0u8; 32],
+            amounts: BTreeMap::new(),
+            invoices: BTreeMap::new(),
+        }
+    }
+
+    /// Derive offsets with a secret key, so payers cannot predict the
+    /// amount of another invoice from its ID.
+    pub fn with_key(mut self, key: [u8; 32]) -> Self {
+        self.key = key;
+        self
+    }
+
+    /// Get the unique amount to request for an invoice.
+    ///
+    /// Tagging an invoice that is already open releases its previous
+    /// amount first. Fails with [`AmountError::Overflow`] if the amount
+    /// would exceed the raw range, or [`AmountError::TagSpaceExhausted`]
+    /// if every offset for this base amount is taken.
+    pub fn tag(&mut self, invoice_id: &str, base: Raw) -> Result<Raw> {
+        self.release(invoice_id);
+
+        let start = self.derive_offset(invoice_id);
+        for step in 0..self.max_offset {
+            let offset = (start + step) % self.max_offset + 1;
+            let amount = base
+                .checked_add(Raw::new(offset))
+                .ok_or(Error::InvalidAmount(AmountError::Overflow))?;
+            if let Entry::Vacant(entry) = self.amounts.entry(amount) {
+                entry.insert(invoice_id.into());
+                self.invoices.insert(invoice_id.into(), amount);
+                return Ok(amount);
+            }
+        }
+        Err(Error::InvalidAmount(AmountError::TagSpaceExhausted))
+    }
+
+    /// Get the invoice expecting exactly `amount`.
+    pub fn decode(&self, amount: Raw) -> Option<&str> {
+        self.amounts.get(&amount).map(String::as_str)
+    }
+
+    /// Get the amount assigned to an open invoice.
+    pub fn amount(&self, invoice_id: &str) -> Option<Raw> {
+        self.invoices.get(invoice_id).copied()
+    }
+
+    /// Free an invoice's amount once it is paid or expired. Returns the
+    /// amount it held.
+    pub fn release(&mut self, invoice_id: &str) -> Option<Raw> {
+        let amount = self.invoices.remove(invoice_id)?;
+        self.amounts.remove(&amount);
+        Some(amount)
+    }
+
+    /// Number of open invoices.
+    pub fn len(&self) -> usize {
+        self.invoices.len()
+    }
+
+    /// Check if there are no open invoices.
+    pub fn is_empty(&self) -> bool {
+        self.invoices.is_empty()
+    }
+
+    /// Get open invoices and their amounts, ordered by invoice ID.
+    pub fn open(&self) -> impl Iterator<Item = (&str, Raw)> {
+        self.invoices
+            .iter()
+            .map(|(id, amount)| (id.as_str(), *amount))
+    }
+
+    /// Offset before collision avoidance, in `0..max_offset`.
+    fn derive_offset(&self, invoice_id: &str) -> u128 {
+        let mut hasher = Blake2b512::new();
+        hasher.update(b"xno-amount-tag");
+        hasher.update(self.key);
+        hasher.update(invoice_id.as_bytes());
+        let hash: [u8; 64] = hasher.finalize().into();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        u128::from_le_bytes(bytes) % self.max_offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_is_deterministic() {
+        let mut a = AmountTagger::new(1_000);
+        let mut b = AmountTagger::new(1_000);
+        let base = Raw::new(5_000_000);
+        assert_eq!(a.tag("inv", base).unwrap(), b.tag("inv", base).unwrap());
+
+        let mut keyed = AmountTagger::new(1_000).with_key([9; 32]);
+        let tagged = keyed.tag("inv", base).unwrap();
+        assert!(tagged > base && tagged <= Raw::new(5_001_000));
+    }
+
+    #[test]
+    fn test_collisions_are_avoided() {
+        let mut tagger = AmountTagger::new(4);
+        let base = Raw::new(100);
+        let mut amounts: alloc::vec::Vec<Raw> = (0..4)
+            .map(|i| tagger.tag(&alloc::format!("inv-{}", i), base).unwrap())
+            .collect();
+        amounts.sort();
+        assert_eq!(amounts, [101, 102, 103, 104].map(Raw::new));
+
+        assert_eq!(
+            tagger.tag("inv-4", base).unwrap_err(),
+            Error::InvalidAmount(AmountError::TagSpaceExhausted)
+        );
+        for amount in amounts {
+            assert!(tagger.decode(amount).is_some());
+        }
+    }
+
+    #[test]
+    fn test_retag_and_release() {
+        let mut tagger = AmountTagger::new(1_000);
+        tagger.tag("inv", Raw::new(10_000)).unwrap();
+        let amount = tagger.tag("inv", Raw::new(20_000)).unwrap();
+        assert_eq!(tagger.len(), 1);
+        assert_eq!(tagger.amount("inv"), Some(amount));
+
+        assert_eq!(tagger.release("inv"), Some(amount));
+        assert!(tagger.is_empty());
+        assert_eq!(tagger.decode(amount), None);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let mut tagger = AmountTagger::new(10);
+        assert_eq!(
+            tagger.tag("inv", Raw::MAX).unwrap_err(),
+            Error::InvalidAmount(AmountError::Overflow)
+        );
+    }
+}
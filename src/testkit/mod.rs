@@ -0,0 +1,25 @@
+//! Reference data for testing integrations.
+//!
+//! [`vectors`] holds canonical Nano test vectors taken from the protocol
+//! documentation and the live network: key derivation, a state block hash,
+//! a block signature and a proof of work. [`strategy`] provides `proptest`
+//! strategies producing accounts, amounts and state blocks, including
+//! blocks with valid signatures.
+//!
+//! # Example
+//!
+//! ```
+//! use proptest::prelude::*;
+//! use xno_connect::blocks::BlockSigner;
+//! use xno_connect::testkit::{strategy, vectors};
+//!
+//! let key = &vectors::KEYS[0];
+//! assert_eq!(key.seed().derive(key.index).account(), key.account());
+//!
+//! proptest!(|(block in strategy::signed_state_block())| {
+//!     prop_assert!(BlockSigner::verify(&block));
+//! });
+//! ```
+
+pub mod strategy;
+pub mod vectors;
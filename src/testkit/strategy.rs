@@ -0,0 +1,106 @@
+//! `proptest` strategies for core types.
+//!
+//! Values cover the whole domain: any 32 bytes make a public key, so
+//! generated accounts are valid addresses but most have no known private
+//! key. Use [`keypair`] or [`signed_state_block`] where a signature has to
+//! verify.
+
+use proptest::prelude::*;
+
+use crate::blocks::BlockSigner;
+use crate::keys::KeyPair;
+use crate::types::{Account, BlockHash, Link, PublicKey, Raw, StateBlock, Work};
+
+/// Any amount, from zero to the 128-bit maximum.
+pub fn raw() -> impl Strategy<Value = Raw> {
+    any::<u128>().prop_map(Raw::new)
+}
+
+/// Any account.
+pub fn account() -> impl Strategy<Value = Account> {
+    any::<[u8; 32]>().prop_map(|bytes| Account::from_public_key(&PublicKey::from_bytes(bytes)))
+}
+
+/// A key pair from a random private key.
+pub fn keypair() -> impl Strategy<Value = KeyPair> {
+    any::<[u8; 32]>().prop_map(KeyPair::from_private_key)
+}
+
+/// Any block hash, zero included.
+pub fn block_hash() -> impl Strategy<Value = BlockHash> {
+    prop_oneof![
+        1 => Just(BlockHash::ZERO),
+        9 => any::<[u8; 32]>().prop_map(BlockHash::from_bytes),
+    ]
+}
+
+/// Any link.
+pub fn link() -> impl Strategy<Value = Link> {
+    any::<[u8; 32]>().prop_map(Link::from_bytes)
+}
+
+/// Any work value.
+pub fn work() -> impl Strategy<Value = Work> {
+    any::<u64>().prop_map(Work::new)
+}
+
+/// An unsigned state block without work.
+///
+/// The previous hash is zero about one time in ten, so open blocks are
+/// covered.
+pub fn state_block() -> impl Strategy<Value = StateBlock> {
+    (account(), block_hash(), account(), raw(), link()).prop_map(
+        |(account, previous, representative, balance, link)| {
+            StateBlock::new(account, previous, representative, balance, link)
+        },
+    )
+}
+
+/// A state block signed by its account, with random (invalid) work.
+pub fn signed_state_block() -> impl Strategy<Value = StateBlock> {
+    (keypair(), state_block(), work()).prop_map(|(keypair, block, work)| {
+        let mut block = StateBlock {
+            account: keypair.account(),
+            ..block
+        };
+        let signature = BlockSigner::sign(&block, &keypair);
+        block.signature = Some(signature);
+        block.with_work(work)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    proptest! {
+        #[test]
+        fn test_account_roundtrip(account in account()) {
+            let parsed: Account = account.as_str().parse().unwrap();
+            prop_assert_eq!(&parsed, &account);
+            prop_assert!(Account::from_address_str_checked(account.as_str()).is_ok());
+        }
+
+        #[test]
+        fn test_raw_roundtrip(raw in raw()) {
+            prop_assert_eq!(raw.to_string().parse::<Raw>().unwrap(), raw);
+            prop_assert_eq!(Raw::from_be_bytes(raw.to_be_bytes()), raw);
+        }
+
+        #[test]
+        fn test_state_block_bytes_roundtrip(block in signed_state_block()) {
+            let decoded = StateBlock::from_bytes(&block.to_bytes()).unwrap();
+            prop_assert_eq!(decoded, block);
+        }
+
+        #[test]
+        fn test_signed_state_block_verifies(block in signed_state_block()) {
+            prop_assert!(BlockSigner::verify(&block));
+
+            let mut tampered = block;
+            tampered.balance = tampered.balance.wrapping_add(Raw::new(1));
+            prop_assert!(!BlockSigner::verify(&tampered));
+        }
+    }
+}
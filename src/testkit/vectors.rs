@@ -0,0 +1,218 @@
+//! Canonical Nano test vectors.
+//!
+//! Values are hex and address strings as published, with accessors
+//! parsing them into crate types. Every vector is checked against this
+//! crate's implementation in its tests.
+
+use crate::keys::Seed;
+use crate::types::{Account, BlockHash, Link, PublicKey, Raw, Signature, StateBlock, Work};
+
+/// Seed and index with the keys and address derived from them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyVector {
+    /// Seed, hex.
+    pub seed: &'static str,
+    /// Derivation index.
+    pub index: u32,
+    /// Private key, hex.
+    pub private_key: &'static str,
+    /// Public key, hex.
+    pub public_key: &'static str,
+    /// Account address.
+    pub address: &'static str,
+}
+
+impl KeyVector {
+    /// Parse the seed.
+    pub fn seed(&self) -> Seed {
+        Seed::from_hex(self.seed).expect("valid test vector")
+    }
+
+    /// Parse the public key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_hex(self.public_key).expect("valid test vector")
+    }
+
+    /// Parse the address.
+    pub fn account(&self) -> Account {
+        self.address.parse().expect("valid test vector")
+    }
+}
+
+/// Key derivation from the all-zero seed, from the Nano documentation.
+pub const KEYS: &[KeyVector] = &[
+    KeyVector {
+        seed: "0000000000000000000000000000000000000000000000000000000000000000",
+        index: 0,
+        private_key: "9F0E444C69F77A49BD0BE89DB92C38FE713E0963165CCA12FAF5712D7657120F",
+        public_key: "C008B814A7D269A1FA3C6528B19201A24D797912DB9996FF02A1FF356E45552B",
+        address: "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7",
+    },
+    KeyVector {
+        seed: "0000000000000000000000000000000000000000000000000000000000000000",
+        index: 1,
+        private_key: "B73B723BF7BD042B66AD3332718BA98DE7312F95ED3D05A130C9204552A7AFFF",
+        public_key: "E30D22B7935BCC25412FC07427391AB4C98A4AD68BAA733300D23D82C9D20AD3",
+        address: "nano_3rrf6cus8pye6o1kzi5n6wwjof8bjb7ff4xcgesi3njxid6x64pms6onw1f9",
+    },
+];
+
+/// State block fields with the expected block hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockVector {
+    /// Account address.
+    pub account: &'static str,
+    /// Previous block hash, hex.
+    pub previous: &'static str,
+    /// Representative address.
+    pub representative: &'static str,
+    /// Balance in raw, decimal.
+    pub balance: &'static str,
+    /// Link, hex.
+    pub link: &'static str,
+    /// Expected block hash, hex.
+    pub hash: &'static str,
+}
+
+impl BlockVector {
+    /// Build the unsigned state block.
+    pub fn block(&self) -> StateBlock {
+        StateBlock::new(
+            self.account.parse().expect("valid test vector"),
+            BlockHash::from_hex(self.previous).expect("valid test vector"),
+            self.representative.parse().expect("valid test vector"),
+            self.balance.parse::<Raw>().expect("valid test vector"),
+            Link::from_hex(self.link).expect("valid test vector"),
+        )
+    }
+
+    /// Parse the expected hash.
+    pub fn hash(&self) -> BlockHash {
+        BlockHash::from_hex(self.hash).expect("valid test vector")
+    }
+}
+
+/// State blocks from the live network.
+pub const STATE_BLOCKS: &[BlockVector] = &[BlockVector {
+    account: "nano_15ds3yajhbfcnm394ujpq3t1m1axdss3oos3xkc114tf5a5b6o8nmhaenhpe",
+    previous: "64CE2D565D7EF418C96612E7838884CFB279CC1C330D540B0CA0C7DA4CD631EF",
+    representative: "nano_1iuz18n4g4wfp9gf7p1s8qkygxw7wx9qfjq6a9aq68uyrdnningdcjontgar",
+    balance: "3",
+    link: "3133E2BA03B97E8F763C5472A3AB3B2DE4916BBFA86491B8EBD6FFCEBB4F990E",
+    hash: "03A4B8F009F5F368F75E601A1732A48118556AE952A84413A72B910A82D15F37",
+}];
+
+/// Signature of a block hash by a public key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureVector {
+    /// Signing public key, hex.
+    pub public_key: &'static str,
+    /// Signed block hash, hex.
+    pub hash: &'static str,
+    /// Signature, hex.
+    pub signature: &'static str,
+}
+
+impl SignatureVector {
+    /// Parse the public key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey::from_hex(self.public_key).expect("valid test vector")
+    }
+
+    /// Parse the hash.
+    pub fn hash(&self) -> BlockHash {
+        BlockHash::from_hex(self.hash).expect("valid test vector")
+    }
+
+    /// Parse the signature.
+    pub fn signature(&self) -> Signature {
+        Signature::from_hex(self.signature).expect("valid test vector")
+    }
+}
+
+/// Signature of the live network genesis open block.
+pub const SIGNATURES: &[SignatureVector] = &[SignatureVector {
+    public_key: "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA",
+    hash: "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+    signature: "9F0C933C8ADE004D808EA1985FA746A7E95BA2A38F867640F53EC8F180BDFE9E2C1268DEAD7C2664F356E37ABA362BC58E46DBA03E523A7B5A19E4B6EB12BB02",
+}];
+
+/// Proof of work for a root with its difficulty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkVector {
+    /// Work root, hex.
+    pub root: &'static str,
+    /// Work value, hex.
+    pub work: &'static str,
+    /// Difficulty of the work for the root.
+    pub difficulty: u64,
+}
+
+impl WorkVector {
+    /// Parse the root.
+    pub fn root(&self) -> BlockHash {
+        BlockHash::from_hex(self.root).expect("valid test vector")
+    }
+
+    /// Parse the work.
+    pub fn work(&self) -> Work {
+        Work::from_hex(self.work).expect("valid test vector")
+    }
+}
+
+/// Work of the live network genesis open block, rooted at the genesis
+/// public key.
+pub const WORK: &[WorkVector] = &[WorkVector {
+    root: "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA",
+    work: "62f05417dd3fb691",
+    difficulty: 0xfffffff4000d3dac,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::BlockHasher;
+    use crate::keys::KeyPair;
+    use crate::work::WorkValidator;
+
+    #[test]
+    fn test_key_vectors() {
+        for vector in KEYS {
+            let keypair = vector.seed().derive(vector.index);
+            assert_eq!(keypair.secret_key().to_hex(), vector.private_key);
+            assert_eq!(keypair.public_key(), &vector.public_key());
+            assert_eq!(keypair.account(), vector.account());
+        }
+    }
+
+    #[test]
+    fn test_block_vectors() {
+        for vector in STATE_BLOCKS {
+            assert_eq!(
+                BlockHasher::hash_state_block(&vector.block()),
+                vector.hash()
+            );
+        }
+    }
+
+    #[test]
+    fn test_signature_vectors() {
+        for vector in SIGNATURES {
+            assert!(KeyPair::verify_with_public_key(
+                &vector.public_key(),
+                &vector.hash(),
+                &vector.signature()
+            ));
+        }
+    }
+
+    #[test]
+    fn test_work_vectors() {
+        for vector in WORK {
+            assert_eq!(
+                WorkValidator::difficulty(vector.work(), &vector.root()),
+                vector.difficulty
+            );
+        }
+    }
+}
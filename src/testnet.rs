@@ -0,0 +1,352 @@
+//! Test-network helpers.
+//!
+//! Provides a client for the test-network faucet so end-to-end tests and
+//! examples can fund fresh accounts instead of relying on pre-funded seeds,
+//! and [`LocalNetwork`] for bootstrapping a local dev node (see
+//! `docker/docker-compose.dev.yml`).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use core::time::Duration;
+//! use xno_connect::rpc::RpcClient;
+//! use xno_connect::testnet::FaucetClient;
+//!
+//! # async fn example() -> xno_connect::error::Result<()> {
+//! let faucet = FaucetClient::new("https://faucet.example/api");
+//! let rpc = RpcClient::new("http://localhost:17076");
+//! let account = "nano_1abc...".parse()?;
+//!
+//! let received = faucet
+//!     .fund_and_wait(&account, &rpc, Duration::from_secs(1), Duration::from_secs(60))
+//!     .await?;
+//! println!("Faucet sent {} raw", received);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::string::{String, ToString};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result, RpcError};
+use crate::rpc::check_error;
+use crate::types::{Account, BlockHash, Raw};
+
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+use crate::rpc::RpcClient;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+use core::time::Duration;
+
+#[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+use crate::blocks::{create_open_block, create_send_block};
+#[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+use crate::keys::{KeyPair, SecretKey};
+#[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+use crate::network::{Network, DEV_GENESIS_SECRET_KEY};
+#[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+use crate::types::Subtype;
+#[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+use crate::work::CpuWorkGenerator;
+
+/// Faucet funding request body.
+#[derive(Debug, Clone, Serialize)]
+pub struct FaucetRequest {
+    /// Account to fund.
+    pub account: String,
+}
+
+impl FaucetRequest {
+    /// Create a new faucet request.
+    pub fn new(account: &Account) -> Self {
+        FaucetRequest {
+            account: account.to_string(),
+        }
+    }
+}
+
+/// Faucet funding response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FaucetResponse {
+    /// Hash of the send block created by the faucet.
+    #[serde(default)]
+    pub hash: Option<BlockHash>,
+    /// Amount sent.
+    #[serde(default)]
+    pub amount: Option<Raw>,
+}
+
+/// Client for a test-network faucet.
+///
+/// Posts `{"account": "<address>"}` to the faucet endpoint and expects a JSON
+/// reply. A reply containing an `error` field is reported as
+/// [`RpcError::NodeError`].
+#[derive(Debug, Clone)]
+pub struct FaucetClient {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl FaucetClient {
+    /// Create a new faucet client for the given endpoint.
+    pub fn new(url: impl Into<String>) -> Self {
+        FaucetClient {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Get the faucet URL.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Request funds for an account.
+    pub async fn request_funds(&self, account: &Account) -> Result<FaucetResponse> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .json(&FaucetRequest::new(account))
+            .send()
+            .await
+            .map_err(|e| {
+                Error::Rpc(RpcError::ConnectionFailed(alloc::format!(
+                    "{}: {}", &self.url, e
+                )))
+            })?;
+
+        let status = response.status();
+        let json: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::Rpc(RpcError::InvalidResponse(e.to_string())))?;
+
+        if let Some(error) = check_error(&json) {
+            return Err(Error::Rpc(RpcError::NodeError(error)));
+        }
+        if !status.is_success() {
+            return Err(Error::Rpc(RpcError::HttpStatus(status.as_u16())));
+        }
+
+        serde_json::from_value(json)
+            .map_err(|e| Error::Rpc(RpcError::InvalidResponse(e.to_string())))
+    }
+
+    /// Request funds and poll the node until they show up as receivable.
+    ///
+    /// Returns the increase in receivable balance.
+    ///
+    /// # Arguments
+    /// * `account` - Account to fund
+    /// * `rpc` - RPC client for the test-network node
+    /// * `poll_interval` - Delay between balance checks
+    /// * `timeout` - Maximum time to wait for the funds
+    #[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+    pub async fn fund_and_wait(
+        &self,
+        account: &Account,
+        rpc: &RpcClient,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<Raw> {
+        let initial = receivable_balance(rpc, account).await?;
+        self.request_funds(account).await?;
+
+        let wait = async {
+            loop {
+                let current = receivable_balance(rpc, account).await?;
+                if current > initial {
+                    return Ok(current.saturating_sub(initial));
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| Error::Rpc(RpcError::Timeout))?
+    }
+}
+
+/// Receivable balance of an account, accepting either response field name.
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+async fn receivable_balance(rpc: &RpcClient, account: &Account) -> Result<Raw> {
+    let balance = rpc.account_balance(account).await?;
+    Ok(balance.receivable.unwrap_or(balance.pending))
+}
+
+/// Helper for driving a local dev node.
+///
+/// Sends funds out of the genesis account and opens test accounts, generating
+/// work locally against the profile's (lowered) thresholds.
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::keys::Seed;
+/// use xno_connect::rpc::RpcClient;
+/// use xno_connect::testnet::LocalNetwork;
+/// use xno_connect::types::Raw;
+///
+/// # async fn example() -> xno_connect::error::Result<()> {
+/// let local = LocalNetwork::new(RpcClient::new("http://localhost:45000"));
+/// let keypair = Seed::random()?.derive(0);
+/// local.fund_account(&keypair, Raw::from_nano(10)?).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+pub struct LocalNetwork {
+    rpc: RpcClient,
+    network: Network,
+    genesis: KeyPair,
+}
+
+#[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+impl LocalNetwork {
+    /// Create a helper for a dev network node using the well-known genesis key.
+    pub fn new(rpc: RpcClient) -> Self {
+        let secret = SecretKey::from_hex(DEV_GENESIS_SECRET_KEY).expect("valid genesis key");
+        LocalNetwork {
+            rpc,
+            network: Network::DEV,
+            genesis: KeyPair::from_secret_key(secret),
+        }
+    }
+
+    /// Use a custom network profile and genesis keypair.
+    pub fn with_network(mut self, network: Network, genesis: KeyPair) -> Self {
+        self.network = network;
+        self.genesis = genesis;
+        self
+    }
+
+    /// Get the RPC client.
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    /// Get the network profile.
+    pub fn network(&self) -> &Network {
+        &self.network
+    }
+
+    /// Get the genesis keypair.
+    pub fn genesis(&self) -> &KeyPair {
+        &self.genesis
+    }
+
+    /// Send funds from the genesis account.
+    ///
+    /// Returns the hash of the send block.
+    ///
+    /// # Arguments
+    /// * `destination` - Account to fund
+    /// * `amount` - Amount to send
+    pub async fn distribute(&self, destination: &Account, amount: Raw) -> Result<BlockHash> {
+        let info = self.rpc.account_info(&self.genesis.account()).await?;
+        let work = self
+            .generator()
+            .generate_for_subtype(&info.frontier, Subtype::Send)?;
+        let block = create_send_block(
+            &self.genesis,
+            info.frontier,
+            info.representative
+                .unwrap_or_else(|| self.genesis.account()),
+            info.balance,
+            amount,
+            destination,
+            Some(work),
+        );
+        Ok(self.rpc.process(block).await?.hash)
+    }
+
+    /// Open an account by receiving a pending send.
+    ///
+    /// The genesis account is used as representative. Returns the hash of the
+    /// open block.
+    ///
+    /// # Arguments
+    /// * `keypair` - Keypair of the account to open
+    /// * `source_hash` - Hash of the send block to receive
+    /// * `amount` - Amount being received
+    pub async fn open_account(
+        &self,
+        keypair: &KeyPair,
+        source_hash: &BlockHash,
+        amount: Raw,
+    ) -> Result<BlockHash> {
+        let root = BlockHash::from_bytes(*keypair.public_key().as_bytes());
+        let work = self
+            .generator()
+            .generate_for_subtype(&root, Subtype::Open)?;
+        let block = create_open_block(
+            keypair,
+            self.genesis.account(),
+            amount,
+            source_hash,
+            Some(work),
+        );
+        Ok(self.rpc.process(block).await?.hash)
+    }
+
+    /// Send funds from genesis and open the account with them.
+    ///
+    /// Returns the hash of the open block.
+    pub async fn fund_account(&self, keypair: &KeyPair, amount: Raw) -> Result<BlockHash> {
+        let send_hash = self.distribute(&keypair.account(), amount).await?;
+        self.open_account(keypair, &send_hash, amount).await
+    }
+
+    fn generator(&self) -> CpuWorkGenerator {
+        CpuWorkGenerator::new().with_threshold(self.network.work_threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_faucet_request_serialization() {
+        let account: Account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .parse()
+            .unwrap();
+        let json = serde_json::to_string(&FaucetRequest::new(&account)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"account":"nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"}"#
+        );
+    }
+
+    #[test]
+    fn test_faucet_response_deserialization() {
+        let json = r#"{
+            "hash": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            "amount": "1000"
+        }"#;
+        let response: FaucetResponse = serde_json::from_str(json).unwrap();
+        assert!(response.hash.is_some());
+        assert_eq!(response.amount, Some(Raw::new(1000)));
+
+        let empty: FaucetResponse = serde_json::from_str("{}").unwrap();
+        assert!(empty.hash.is_none());
+    }
+
+    #[test]
+    fn test_faucet_client_url() {
+        let client = FaucetClient::new("http://localhost:8080/faucet");
+        assert_eq!(client.url(), "http://localhost:8080/faucet");
+    }
+
+    #[cfg(all(feature = "work-cpu", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_local_network_defaults() {
+        let local = LocalNetwork::new(crate::rpc::RpcClient::new("http://localhost:45000"));
+        assert_eq!(local.network(), &Network::DEV);
+        assert_eq!(
+            local.genesis().account(),
+            Network::DEV.genesis_account().unwrap()
+        );
+    }
+}
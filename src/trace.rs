@@ -0,0 +1,313 @@
+//! Internal `tracing` instrumentation.
+//!
+//! With the `tracing` feature these macros forward to the `tracing` crate;
+//! without it they expand to nothing, so instrumented code needs no `cfg`
+//! attributes. Arguments are not evaluated when the feature is off.
+
+#[cfg(all(
+    feature = "tracing",
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket"
+    )
+))]
+macro_rules! log_trace {
+    ($($arg:tt)*) => { ::tracing::trace!($($arg)*) };
+}
+#[cfg(all(
+    not(feature = "tracing"),
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket"
+    )
+))]
+macro_rules! log_trace {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(all(
+    feature = "tracing",
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket",
+        feature = "work-cpu"
+    )
+))]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { ::tracing::debug!($($arg)*) };
+}
+#[cfg(all(
+    not(feature = "tracing"),
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket",
+        feature = "work-cpu"
+    )
+))]
+macro_rules! log_debug {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(all(
+    feature = "tracing",
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket"
+    )
+))]
+macro_rules! log_info {
+    ($($arg:tt)*) => { ::tracing::info!($($arg)*) };
+}
+#[cfg(all(
+    not(feature = "tracing"),
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket"
+    )
+))]
+macro_rules! log_info {
+    ($($arg:tt)*) => {};
+}
+
+#[cfg(all(
+    feature = "tracing",
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket"
+    )
+))]
+macro_rules! log_warn {
+    ($($arg:tt)*) => { ::tracing::warn!($($arg)*) };
+}
+#[cfg(all(
+    not(feature = "tracing"),
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket"
+    )
+))]
+macro_rules! log_warn {
+    ($($arg:tt)*) => {};
+}
+
+/// Run a future inside a debug-level span: `instrument!(["name", field = value], future)`.
+#[cfg(all(feature = "tracing", any(feature = "rpc", feature = "wasm-rpc")))]
+macro_rules! instrument {
+    ([$($span:tt)*], $future:expr) => {{
+        let span = ::tracing::debug_span!($($span)*);
+        ::tracing::Instrument::instrument($future, span)
+    }};
+}
+#[cfg(all(not(feature = "tracing"), any(feature = "rpc", feature = "wasm-rpc")))]
+macro_rules! instrument {
+    ([$($span:tt)*], $future:expr) => {
+        $future
+    };
+}
+
+/// Bind a [`Stopwatch`] for latency fields: `stopwatch!(name)`.
+#[cfg(all(
+    feature = "tracing",
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket"
+    )
+))]
+macro_rules! stopwatch {
+    ($name:ident) => {
+        let $name = $crate::trace::Stopwatch::start();
+    };
+}
+#[cfg(all(
+    not(feature = "tracing"),
+    any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket"
+    )
+))]
+macro_rules! stopwatch {
+    ($name:ident) => {};
+}
+
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+pub(crate) use instrument;
+#[cfg(any(
+    feature = "rpc",
+    feature = "wasm-rpc",
+    feature = "websocket",
+    feature = "wasm-websocket",
+    feature = "work-cpu"
+))]
+pub(crate) use log_debug;
+#[cfg(any(
+    feature = "rpc",
+    feature = "wasm-rpc",
+    feature = "websocket",
+    feature = "wasm-websocket"
+))]
+pub(crate) use {log_info, log_trace, log_warn, stopwatch};
+
+/// Measures latency for log fields and metrics.
+///
+/// Zero-sized unless a log field or metric reads it. No clock is
+/// available in WASM or `no_std` builds, where elapsed times are `None`.
+#[cfg(any(
+    feature = "rpc",
+    feature = "wasm-rpc",
+    feature = "work-cpu",
+    all(
+        feature = "tracing",
+        any(feature = "websocket", feature = "wasm-websocket")
+    )
+))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch {
+    #[cfg(all(
+        any(
+            all(
+                feature = "tracing",
+                any(
+                    feature = "rpc",
+                    feature = "wasm-rpc",
+                    feature = "websocket",
+                    feature = "wasm-websocket"
+                )
+            ),
+            all(
+                feature = "metrics",
+                any(feature = "rpc", feature = "wasm-rpc", feature = "work-cpu")
+            )
+        ),
+        feature = "std",
+        not(target_arch = "wasm32")
+    ))]
+    start: std::time::Instant,
+}
+
+#[cfg(any(
+    feature = "rpc",
+    feature = "wasm-rpc",
+    feature = "work-cpu",
+    all(
+        feature = "tracing",
+        any(feature = "websocket", feature = "wasm-websocket")
+    )
+))]
+impl Stopwatch {
+    /// Start measuring.
+    pub(crate) fn start() -> Self {
+        Stopwatch {
+            #[cfg(all(
+                any(
+                    all(
+                        feature = "tracing",
+                        any(
+                            feature = "rpc",
+                            feature = "wasm-rpc",
+                            feature = "websocket",
+                            feature = "wasm-websocket"
+                        )
+                    ),
+                    all(
+                        feature = "metrics",
+                        any(feature = "rpc", feature = "wasm-rpc", feature = "work-cpu")
+                    )
+                ),
+                feature = "std",
+                not(target_arch = "wasm32")
+            ))]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    /// Milliseconds since [`start`](Self::start), if a clock is available.
+    #[cfg(all(
+        feature = "tracing",
+        any(
+            feature = "rpc",
+            feature = "wasm-rpc",
+            feature = "websocket",
+            feature = "wasm-websocket"
+        )
+    ))]
+    pub(crate) fn elapsed_ms(&self) -> Option<u64> {
+        #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+        return Some(self.start.elapsed().as_millis() as u64);
+        #[cfg(not(all(feature = "std", not(target_arch = "wasm32"))))]
+        None
+    }
+
+    /// Seconds since [`start`](Self::start), if a clock is available.
+    #[cfg(all(
+        feature = "metrics",
+        any(feature = "rpc", feature = "wasm-rpc", feature = "work-cpu")
+    ))]
+    pub(crate) fn elapsed_secs(&self) -> Option<f64> {
+        #[cfg(not(target_arch = "wasm32"))]
+        return Some(self.start.elapsed().as_secs_f64());
+        #[cfg(target_arch = "wasm32")]
+        None
+    }
+}
+
+#[cfg(all(test, feature = "tracing", feature = "work-cpu"))]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::types::BlockHash;
+    use crate::work::CpuWorkGenerator;
+
+    /// Counts events and nothing else.
+    struct CountingSubscriber(Arc<AtomicUsize>);
+
+    impl Subscriber for CountingSubscriber {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_work_generation_emits_events() {
+        let events = Arc::new(AtomicUsize::new(0));
+        let subscriber = CountingSubscriber(events.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            CpuWorkGenerator::new()
+                .generate(&BlockHash::ZERO, 0xff00_0000_0000_0000, None)
+                .unwrap();
+        });
+        assert_eq!(events.load(Ordering::Relaxed), 2);
+    }
+}
@@ -4,6 +4,7 @@ use alloc::format;
 use alloc::string::{String, ToString};
 use core::fmt;
 use core::str::FromStr;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use crate::constants::{ACCOUNT_PREFIX_NANO, ACCOUNT_PREFIX_XNO, BASE32_ALPHABET};
@@ -80,22 +81,32 @@ impl AsRef<[u8]> for PublicKey {
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for PublicKey {
     fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_hex())
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_hex())
+        } else {
+            serializer.serialize_bytes(self.as_bytes())
+        }
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for PublicKey {
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        PublicKey::from_hex(&s).map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            PublicKey::from_hex(&s).map_err(serde::de::Error::custom)
+        } else {
+            super::binary::deserialize_array(deserializer).map(PublicKey::from_bytes)
+        }
     }
 }
 
@@ -174,22 +185,33 @@ impl From<PublicKey> for Account {
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for Account {
     fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.address)
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.address)
+        } else {
+            serializer.serialize_bytes(self.public_key().as_bytes())
+        }
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Account {
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Account::from_address_str_checked(&s).map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Account::from_address_str_checked(&s).map_err(serde::de::Error::custom)
+        } else {
+            super::binary::deserialize_array(deserializer)
+                .map(|bytes| Account::from_public_key(&PublicKey::from_bytes(bytes)))
+        }
     }
 }
 
@@ -214,27 +236,36 @@ fn encode_account(public_key: &PublicKey) -> String {
     format!("{}{}{}", ACCOUNT_PREFIX_NANO, pk_encoded, checksum_encoded)
 }
 
+/// Length of an address after the prefix: 52 characters of public key
+/// and 8 of checksum.
+const ADDRESS_BODY_LEN: usize = 60;
+
 /// Decode a Nano account address to a public key.
+///
+/// Only the prefix is looked at before the length is checked, so
+/// oversized input is rejected without being processed.
 fn decode_account(address: &str) -> Result<PublicKey> {
     use blake2::digest::consts::U5;
     use blake2::{Blake2b, Digest};
 
-    // Check prefix
-    let data = if let Some(s) = address.strip_prefix(ACCOUNT_PREFIX_NANO) {
-        s
-    } else if let Some(s) = address.strip_prefix(ACCOUNT_PREFIX_XNO) {
-        s
+    // Work on bytes so multi-byte characters can't split a slice
+    let bytes = address.as_bytes();
+    let data = if let Some(data) = bytes.strip_prefix(ACCOUNT_PREFIX_NANO.as_bytes()) {
+        data
+    } else if let Some(data) = bytes.strip_prefix(ACCOUNT_PREFIX_XNO.as_bytes()) {
+        data
     } else {
         return Err(Error::InvalidAccount(AccountError::InvalidPrefix));
     };
 
-    // Check length: 52 chars for public key + 8 chars for checksum = 60 chars
-    if data.len() != 60 {
+    if data.len() != ADDRESS_BODY_LEN {
         return Err(Error::InvalidAccount(AccountError::InvalidLength));
     }
+    if !data.is_ascii() {
+        return Err(Error::InvalidAccount(AccountError::NonAscii));
+    }
 
-    let pk_part = &data[..52];
-    let checksum_part = &data[52..];
+    let (pk_part, checksum_part) = data.split_at(52);
 
     // Decode public key
     let public_key_bytes = decode_base32_256(pk_part)
@@ -248,7 +279,7 @@ fn decode_account(address: &str) -> Result<PublicKey> {
 
     // Calculate expected checksum
     let mut hasher = Blake2b::<U5>::new();
-    hasher.update(&public_key_bytes);
+    hasher.update(public_key_bytes);
     let expected_checksum: [u8; 5] = hasher.finalize().into();
 
     if checksum_bytes != expected_checksum {
@@ -298,7 +329,7 @@ fn encode_base32_256(bytes: &[u8; 32]) -> String {
 }
 
 /// Decode 52 base32 characters to 256 bits (32 bytes).
-fn decode_base32_256(s: &str) -> core::result::Result<[u8; 32], ()> {
+fn decode_base32_256(s: &[u8]) -> core::result::Result<[u8; 32], ()> {
     if s.len() != 52 {
         return Err(());
     }
@@ -308,7 +339,7 @@ fn decode_base32_256(s: &str) -> core::result::Result<[u8; 32], ()> {
     let mut bit_count: u8 = 0;
     let mut byte_idx = 0;
 
-    for (i, c) in s.chars().enumerate() {
+    for (i, &c) in s.iter().enumerate() {
         let value = base32_char_value(c)?;
 
         if i == 0 {
@@ -355,14 +386,14 @@ fn encode_base32_40(bytes: &[u8; 5]) -> String {
 }
 
 /// Decode 8 base32 characters to 40 bits (5 bytes).
-fn decode_base32_40(s: &str) -> core::result::Result<[u8; 5], ()> {
+fn decode_base32_40(s: &[u8]) -> core::result::Result<[u8; 5], ()> {
     if s.len() != 8 {
         return Err(());
     }
 
     let mut combined: u64 = 0;
 
-    for c in s.chars() {
+    for &c in s {
         let value = base32_char_value(c)?;
         combined = (combined << 5) | (value as u64);
     }
@@ -377,40 +408,40 @@ fn decode_base32_40(s: &str) -> core::result::Result<[u8; 5], ()> {
 }
 
 /// Get the value of a base32 character.
-fn base32_char_value(c: char) -> core::result::Result<u8, ()> {
+fn base32_char_value(c: u8) -> core::result::Result<u8, ()> {
     match c {
-        '1' => Ok(0),
-        '3' => Ok(1),
-        '4' => Ok(2),
-        '5' => Ok(3),
-        '6' => Ok(4),
-        '7' => Ok(5),
-        '8' => Ok(6),
-        '9' => Ok(7),
-        'a' | 'A' => Ok(8),
-        'b' | 'B' => Ok(9),
-        'c' | 'C' => Ok(10),
-        'd' | 'D' => Ok(11),
-        'e' | 'E' => Ok(12),
-        'f' | 'F' => Ok(13),
-        'g' | 'G' => Ok(14),
-        'h' | 'H' => Ok(15),
-        'i' | 'I' => Ok(16),
-        'j' | 'J' => Ok(17),
-        'k' | 'K' => Ok(18),
-        'm' | 'M' => Ok(19),
-        'n' | 'N' => Ok(20),
-        'o' | 'O' => Ok(21),
-        'p' | 'P' => Ok(22),
-        'q' | 'Q' => Ok(23),
-        'r' | 'R' => Ok(24),
-        's' | 'S' => Ok(25),
-        't' | 'T' => Ok(26),
-        'u' | 'U' => Ok(27),
-        'w' | 'W' => Ok(28),
-        'x' | 'X' => Ok(29),
-        'y' | 'Y' => Ok(30),
-        'z' | 'Z' => Ok(31),
+        b'1' => Ok(0),
+        b'3' => Ok(1),
+        b'4' => Ok(2),
+        b'5' => Ok(3),
+        b'6' => Ok(4),
+        b'7' => Ok(5),
+        b'8' => Ok(6),
+        b'9' => Ok(7),
+        b'a' | b'A' => Ok(8),
+        b'b' | b'B' => Ok(9),
+        b'c' | b'C' => Ok(10),
+        b'd' | b'D' => Ok(11),
+        b'e' | b'E' => Ok(12),
+        b'f' | b'F' => Ok(13),
+        b'g' | b'G' => Ok(14),
+        b'h' | b'H' => Ok(15),
+        b'i' | b'I' => Ok(16),
+        b'j' | b'J' => Ok(17),
+        b'k' | b'K' => Ok(18),
+        b'm' | b'M' => Ok(19),
+        b'n' | b'N' => Ok(20),
+        b'o' | b'O' => Ok(21),
+        b'p' | b'P' => Ok(22),
+        b'q' | b'Q' => Ok(23),
+        b'r' | b'R' => Ok(24),
+        b's' | b'S' => Ok(25),
+        b't' | b'T' => Ok(26),
+        b'u' | b'U' => Ok(27),
+        b'w' | b'W' => Ok(28),
+        b'x' | b'X' => Ok(29),
+        b'y' | b'Y' => Ok(30),
+        b'z' | b'Z' => Ok(31),
         _ => Err(()),
     }
 }
@@ -476,6 +507,24 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_invalid_account_non_ascii() {
+        // 60 bytes after the prefix, with a two-byte character straddling
+        // the public key / checksum boundary
+        let invalid = format!("nano_{}é{}", &TEST_ACCOUNT[5..56], &TEST_ACCOUNT[58..]);
+        assert_eq!(invalid.len(), TEST_ACCOUNT.len());
+        assert!(matches!(
+            Account::from_address_str_checked(&invalid),
+            Err(Error::InvalidAccount(AccountError::NonAscii))
+        ));
+
+        let oversized = format!("{}{}", TEST_ACCOUNT, "1".repeat(1 << 20));
+        assert!(matches!(
+            Account::from_address_str_checked(&oversized),
+            Err(Error::InvalidAccount(AccountError::InvalidLength))
+        ));
+    }
+
     #[test]
     fn test_invalid_account_checksum() {
         let invalid = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr4";
@@ -499,6 +548,7 @@ mod tests {
         assert!(account.is_burn());
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_public_key_serde() {
         let pk = PublicKey::from_hex(TEST_PUBLIC_KEY_HEX).unwrap();
@@ -509,6 +559,7 @@ mod tests {
         assert_eq!(pk, recovered);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_account_serde() {
         let account: Account = TEST_ACCOUNT.parse().unwrap();
@@ -523,7 +574,7 @@ mod tests {
     fn test_base32_roundtrip() {
         let bytes = [0xABu8; 32];
         let encoded = encode_base32_256(&bytes);
-        let decoded = decode_base32_256(&encoded).unwrap();
+        let decoded = decode_base32_256(encoded.as_bytes()).unwrap();
         assert_eq!(bytes, decoded);
     }
 
@@ -531,7 +582,7 @@ mod tests {
     fn test_base32_checksum_roundtrip() {
         let bytes = [0xCDu8; 5];
         let encoded = encode_base32_40(&bytes);
-        let decoded = decode_base32_40(&encoded).unwrap();
+        let decoded = decode_base32_40(encoded.as_bytes()).unwrap();
         assert_eq!(bytes, decoded);
     }
 
@@ -3,17 +3,38 @@
 use alloc::format;
 use alloc::string::{String, ToString};
 use core::fmt;
-use core::ops::{Add, Sub};
+use core::iter::Sum;
+use core::ops::{Add, AddAssign, Div, Mul, Sub, SubAssign};
 use core::str::FromStr;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use crate::constants::NANO_IN_RAW;
 use crate::error::{AmountError, Error, Result};
 
+/// Number of fractional digits in one Nano (XNO).
+const NANO_DECIMALS: usize = 30;
+
+/// How to handle digits dropped when formatting with fewer decimals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Drop extra digits.
+    #[default]
+    Truncate,
+    /// Round to nearest, ties away from zero.
+    HalfUp,
+    /// Round towards positive infinity.
+    Up,
+}
+
 /// Raw amount - the smallest unit of Nano (10^-30 XNO).
 ///
 /// This is a newtype wrapper around u128 representing raw units.
 /// All internal calculations are done in raw to avoid floating point errors.
+///
+/// The `+`, `-`, `*` and `/` operators saturate instead of panicking on
+/// overflow or underflow. Use the `checked_*` methods to detect those cases,
+/// or `wrapping_*` for modular arithmetic.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Raw(u128);
 
@@ -43,6 +64,22 @@ impl Raw {
             .ok_or(Error::InvalidAmount(AmountError::Overflow))
     }
 
+    /// Parse a decimal Nano (XNO) string such as `"1.000000000000000000000000000001"`.
+    ///
+    /// Up to 30 fractional digits are accepted. Additional non-zero digits
+    /// would lose precision and are rejected.
+    pub fn from_nano_str(s: &str) -> Result<Self> {
+        parse_decimal(s, NANO_DECIMALS).map(Raw)
+    }
+
+    /// Format as Nano (XNO) with at most `decimals` fractional digits.
+    ///
+    /// Digits beyond `decimals` are handled according to `mode`. Trailing
+    /// zeros are trimmed, as in [`to_nano_string`](Self::to_nano_string).
+    pub fn format_nano(&self, decimals: usize, mode: RoundingMode) -> String {
+        format_decimal(self.0, NANO_DECIMALS, decimals, mode)
+    }
+
     /// Convert to Nano (XNO) as a string with decimal places.
     pub fn to_nano_string(&self) -> String {
         let whole = self.0 / NANO_IN_RAW;
@@ -83,6 +120,37 @@ impl Raw {
         Raw(self.0.saturating_sub(other.0))
     }
 
+    /// Wrapping addition.
+    pub fn wrapping_add(&self, other: Raw) -> Raw {
+        Raw(self.0.wrapping_add(other.0))
+    }
+
+    /// Wrapping subtraction.
+    pub fn wrapping_sub(&self, other: Raw) -> Raw {
+        Raw(self.0.wrapping_sub(other.0))
+    }
+
+    /// Checked multiplication by a scalar.
+    pub fn checked_mul(&self, factor: u128) -> Option<Raw> {
+        self.0.checked_mul(factor).map(Raw)
+    }
+
+    /// Checked division by a scalar. Returns `None` when dividing by zero.
+    pub fn checked_div(&self, divisor: u128) -> Option<Raw> {
+        self.0.checked_div(divisor).map(Raw)
+    }
+
+    /// Saturating multiplication by a scalar.
+    pub fn saturating_mul(&self, factor: u128) -> Raw {
+        Raw(self.0.saturating_mul(factor))
+    }
+
+    /// Checked sum of an iterator of amounts.
+    pub fn checked_sum<I: IntoIterator<Item = Raw>>(iter: I) -> Option<Raw> {
+        iter.into_iter()
+            .try_fold(Raw::ZERO, |acc, raw| acc.checked_add(raw))
+    }
+
     /// Convert to big-endian bytes (16 bytes).
     pub fn to_be_bytes(&self) -> [u8; 16] {
         self.0.to_be_bytes()
@@ -110,11 +178,85 @@ impl Raw {
     }
 }
 
+/// Parse a non-negative decimal string into an integer scaled by `10^scale`.
+fn parse_decimal(s: &str, scale: usize) -> Result<u128> {
+    if s.starts_with('-') {
+        return Err(Error::InvalidAmount(AmountError::Negative));
+    }
+
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    if (whole.is_empty() && frac.is_empty())
+        || !whole
+            .bytes()
+            .chain(frac.bytes())
+            .all(|b| b.is_ascii_digit())
+    {
+        return Err(Error::InvalidAmount(AmountError::InvalidFormat));
+    }
+
+    let frac = if frac.len() > scale {
+        let (kept, dropped) = frac.split_at(scale);
+        if dropped.bytes().any(|b| b != b'0') {
+            return Err(Error::InvalidAmount(AmountError::PrecisionLoss));
+        }
+        kept
+    } else {
+        frac
+    };
+
+    let overflow = Error::InvalidAmount(AmountError::Overflow);
+    let whole: u128 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| overflow.clone())?
+    };
+    let frac_value: u128 = if frac.is_empty() {
+        0
+    } else {
+        // At most 33 digits (knano), so this always fits in u128.
+        frac.parse::<u128>().expect("validated digits") * 10u128.pow((scale - frac.len()) as u32)
+    };
+
+    whole
+        .checked_mul(10u128.pow(scale as u32))
+        .and_then(|w| w.checked_add(frac_value))
+        .ok_or(overflow)
+}
+
+/// Format an integer scaled by `10^scale` with at most `decimals` fractional digits.
+fn format_decimal(value: u128, scale: usize, decimals: usize, mode: RoundingMode) -> String {
+    let decimals = decimals.min(scale);
+    let divisor = 10u128.pow((scale - decimals) as u32);
+    let mut scaled = value / divisor;
+    let remainder = value % divisor;
+
+    let round_up = match mode {
+        RoundingMode::Truncate => false,
+        RoundingMode::HalfUp => remainder >= divisor - divisor / 2,
+        RoundingMode::Up => remainder > 0,
+    };
+    if round_up {
+        // scaled <= u128::MAX / divisor, so adding one cannot overflow.
+        scaled += 1;
+    }
+
+    let unit = 10u128.pow(decimals as u32);
+    let whole = scaled / unit;
+    let frac = scaled % unit;
+
+    if frac == 0 {
+        whole.to_string()
+    } else {
+        let frac_str = format!("{:0width$}", frac, width = decimals);
+        format!("{}.{}", whole, frac_str.trim_end_matches('0'))
+    }
+}
+
 impl Add for Raw {
     type Output = Raw;
 
     fn add(self, other: Raw) -> Raw {
-        Raw(self.0 + other.0)
+        self.saturating_add(other)
     }
 }
 
@@ -122,7 +264,52 @@ impl Sub for Raw {
     type Output = Raw;
 
     fn sub(self, other: Raw) -> Raw {
-        Raw(self.0 - other.0)
+        self.saturating_sub(other)
+    }
+}
+
+impl AddAssign for Raw {
+    fn add_assign(&mut self, other: Raw) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Raw {
+    fn sub_assign(&mut self, other: Raw) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<u128> for Raw {
+    type Output = Raw;
+
+    fn mul(self, factor: u128) -> Raw {
+        self.saturating_mul(factor)
+    }
+}
+
+impl Div<u128> for Raw {
+    type Output = Raw;
+
+    /// Divide by a scalar.
+    ///
+    /// # Panics
+    /// Panics if `divisor` is zero, like integer division. Use
+    /// [`Raw::checked_div`] to avoid this.
+    fn div(self, divisor: u128) -> Raw {
+        Raw(self.0 / divisor)
+    }
+}
+
+impl Sum for Raw {
+    fn sum<I: Iterator<Item = Raw>>(iter: I) -> Raw {
+        iter.fold(Raw::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Raw> for Raw {
+    fn sum<I: Iterator<Item = &'a Raw>>(iter: I) -> Raw {
+        iter.copied().sum()
     }
 }
 
@@ -154,42 +341,156 @@ impl FromStr for Raw {
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for Raw {
     fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.0.to_string())
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.0.to_string())
+        } else {
+            serializer.serialize_bytes(&self.to_be_bytes())
+        }
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Raw {
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        s.parse().map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            s.parse().map_err(serde::de::Error::custom)
+        } else {
+            super::binary::deserialize_array(deserializer).map(Raw::from_be_bytes)
+        }
+    }
+}
+
+/// Denomination unit for displaying and parsing amounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Unit {
+    /// 1 knano = 10^33 raw.
+    KNano,
+    /// 1 nano (XNO) = 10^30 raw.
+    Nano,
+    /// 1 millinano = 10^27 raw.
+    MilliNano,
+    /// 1 micronano = 10^24 raw.
+    MicroNano,
+    /// Smallest unit.
+    #[default]
+    Raw,
+}
+
+impl Unit {
+    /// Number of decimal places between this unit and raw.
+    pub const fn decimals(&self) -> usize {
+        match self {
+            Unit::KNano => 33,
+            Unit::Nano => 30,
+            Unit::MilliNano => 27,
+            Unit::MicroNano => 24,
+            Unit::Raw => 0,
+        }
+    }
+
+    /// Raw value of one unit.
+    pub const fn raw_per_unit(&self) -> u128 {
+        10u128.pow(self.decimals() as u32)
+    }
+
+    /// Canonical lowercase symbol.
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            Unit::KNano => "knano",
+            Unit::Nano => "nano",
+            Unit::MilliNano => "millinano",
+            Unit::MicroNano => "micronano",
+            Unit::Raw => "raw",
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = Error;
+
+    /// Parse a unit symbol, case-insensitively.
+    ///
+    /// Accepts the canonical symbols plus `xno`, `mnano` and `unano`.
+    fn from_str(s: &str) -> Result<Self> {
+        let units = [
+            ("knano", Unit::KNano),
+            ("nano", Unit::Nano),
+            ("xno", Unit::Nano),
+            ("millinano", Unit::MilliNano),
+            ("mnano", Unit::MilliNano),
+            ("micronano", Unit::MicroNano),
+            ("unano", Unit::MicroNano),
+            ("raw", Unit::Raw),
+        ];
+        units
+            .iter()
+            .find(|(symbol, _)| s.eq_ignore_ascii_case(symbol))
+            .map(|(_, unit)| *unit)
+            .ok_or(Error::InvalidAmount(AmountError::InvalidFormat))
     }
 }
 
 /// Amount with unit information for display purposes.
 ///
 /// This is a wrapper around Raw that also stores the preferred display unit.
+/// Display always uses `.` as the decimal separator and no digit grouping.
+///
+/// # Example
+///
+/// ```
+/// use xno_connect::types::{Amount, Unit};
+///
+/// # fn main() -> xno_connect::error::Result<()> {
+/// let amount: Amount = "1.5 knano".parse()?;
+/// assert_eq!(amount.to_string(), "1.5 knano");
+/// assert_eq!(amount.with_unit(Unit::Nano).to_string(), "1500 nano");
+/// # Ok(())
+/// # }
+/// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Amount {
     raw: Raw,
+    unit: Unit,
 }
 
 impl Amount {
     /// Create a new amount from raw units.
     pub const fn from_raw(raw: Raw) -> Self {
-        Amount { raw }
+        Amount {
+            raw,
+            unit: Unit::Raw,
+        }
+    }
+
+    /// Create a new amount with a display unit.
+    pub const fn new(raw: Raw, unit: Unit) -> Self {
+        Amount { raw, unit }
+    }
+
+    /// Create an amount from a decimal value in the given unit.
+    pub fn from_unit_str(value: &str, unit: Unit) -> Result<Self> {
+        parse_decimal(value, unit.decimals()).map(|raw| Amount::new(Raw(raw), unit))
     }
 
     /// Create a zero amount.
     pub const fn zero() -> Self {
-        Amount { raw: Raw::ZERO }
+        Amount::from_raw(Raw::ZERO)
     }
 
     /// Get the raw value.
@@ -197,6 +498,16 @@ impl Amount {
         self.raw
     }
 
+    /// Get the display unit.
+    pub const fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Return the same amount with a different display unit.
+    pub const fn with_unit(self, unit: Unit) -> Self {
+        Amount { unit, ..self }
+    }
+
     /// Check if the amount is zero.
     pub const fn is_zero(&self) -> bool {
         self.raw.is_zero()
@@ -206,23 +517,52 @@ impl Amount {
     pub fn as_nano(&self) -> String {
         self.raw.to_nano_string()
     }
+
+    /// Format the value in `unit` without the symbol, at full precision.
+    pub fn to_unit_string(&self, unit: Unit) -> String {
+        format_decimal(
+            self.raw.0,
+            unit.decimals(),
+            unit.decimals(),
+            RoundingMode::Truncate,
+        )
+    }
 }
 
 impl From<Raw> for Amount {
     fn from(raw: Raw) -> Self {
-        Amount { raw }
+        Amount::from_raw(raw)
     }
 }
 
 impl From<u128> for Amount {
     fn from(value: u128) -> Self {
-        Amount { raw: Raw(value) }
+        Amount::from_raw(Raw(value))
+    }
+}
+
+impl From<Amount> for Raw {
+    fn from(amount: Amount) -> Raw {
+        amount.raw
     }
 }
 
 impl fmt::Display for Amount {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} raw", self.raw)
+        write!(f, "{} {}", self.to_unit_string(self.unit), self.unit)
+    }
+}
+
+impl FromStr for Amount {
+    type Err = Error;
+
+    /// Parse `"<value> <unit>"`, e.g. `"1.5 knano"`. A bare value is raw.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        match s.split_once(char::is_whitespace) {
+            Some((value, unit)) => Amount::from_unit_str(value, unit.trim().parse()?),
+            None => Amount::from_unit_str(s, Unit::Raw),
+        }
     }
 }
 
@@ -241,6 +581,41 @@ mod tests {
         assert_eq!(b.checked_sub(a), None);
     }
 
+    #[test]
+    fn test_raw_operators_saturate() {
+        assert_eq!(Raw::new(1) - Raw::new(2), Raw::ZERO);
+        assert_eq!(Raw::MAX + Raw::new(1), Raw::MAX);
+        assert_eq!(Raw::MAX * 2, Raw::MAX);
+
+        let mut raw = Raw::new(10);
+        raw -= Raw::new(20);
+        assert_eq!(raw, Raw::ZERO);
+        raw += Raw::new(5);
+        assert_eq!(raw, Raw::new(5));
+    }
+
+    #[test]
+    fn test_raw_wrapping_and_checked() {
+        assert_eq!(Raw::ZERO.wrapping_sub(Raw::new(1)), Raw::MAX);
+        assert_eq!(Raw::MAX.wrapping_add(Raw::new(1)), Raw::ZERO);
+        assert_eq!(Raw::new(7).checked_mul(3), Some(Raw::new(21)));
+        assert_eq!(Raw::MAX.checked_mul(2), None);
+        assert_eq!(Raw::new(7).checked_div(2), Some(Raw::new(3)));
+        assert_eq!(Raw::new(7).checked_div(0), None);
+        assert_eq!(Raw::new(21) / 3, Raw::new(7));
+    }
+
+    #[test]
+    fn test_raw_sum() {
+        let amounts = [Raw::new(1), Raw::new(2), Raw::new(3)];
+        assert_eq!(amounts.iter().sum::<Raw>(), Raw::new(6));
+        assert_eq!(amounts.into_iter().sum::<Raw>(), Raw::new(6));
+        assert_eq!([Raw::MAX, Raw::new(1)].into_iter().sum::<Raw>(), Raw::MAX);
+
+        assert_eq!(Raw::checked_sum(amounts), Some(Raw::new(6)));
+        assert_eq!(Raw::checked_sum([Raw::MAX, Raw::new(1)]), None);
+    }
+
     #[test]
     fn test_raw_from_nano() {
         let raw = Raw::from_nano(1).unwrap();
@@ -311,6 +686,7 @@ mod tests {
         assert!(Raw::from_nano(u128::MAX).is_err());
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_raw_serde() {
         let raw = Raw::new(12345678901234567890);
@@ -320,4 +696,112 @@ mod tests {
         let recovered: Raw = serde_json::from_str(&json).unwrap();
         assert_eq!(raw, recovered);
     }
+
+    #[test]
+    fn test_raw_from_nano_str() {
+        assert_eq!(
+            Raw::from_nano_str("1.000000000000000000000000000001").unwrap(),
+            Raw::new(NANO_IN_RAW + 1)
+        );
+        assert_eq!(Raw::from_nano_str("1").unwrap(), Raw::new(NANO_IN_RAW));
+        assert_eq!(
+            Raw::from_nano_str("0.5").unwrap(),
+            Raw::new(NANO_IN_RAW / 2)
+        );
+        assert_eq!(Raw::from_nano_str(".5").unwrap(), Raw::new(NANO_IN_RAW / 2));
+        assert_eq!(Raw::from_nano_str("2.").unwrap(), Raw::new(2 * NANO_IN_RAW));
+        assert_eq!(
+            Raw::from_nano_str("0.0000000000000000000000000000010").unwrap(),
+            Raw::new(1)
+        );
+
+        let raw = Raw::new(123_456_789_000_000_000_000_000_000_000_001);
+        assert_eq!(Raw::from_nano_str(&raw.to_nano_string()).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_raw_from_nano_str_errors() {
+        assert!(matches!(
+            Raw::from_nano_str("0.0000000000000000000000000000001"),
+            Err(Error::InvalidAmount(AmountError::PrecisionLoss))
+        ));
+        assert!(matches!(
+            Raw::from_nano_str("-1"),
+            Err(Error::InvalidAmount(AmountError::Negative))
+        ));
+        assert!(matches!(
+            Raw::from_nano_str("1e5"),
+            Err(Error::InvalidAmount(AmountError::InvalidFormat))
+        ));
+        assert!(matches!(
+            Raw::from_nano_str("."),
+            Err(Error::InvalidAmount(AmountError::InvalidFormat))
+        ));
+        assert!(matches!(
+            Raw::from_nano_str("1000000000"),
+            Err(Error::InvalidAmount(AmountError::Overflow))
+        ));
+    }
+
+    #[test]
+    fn test_raw_format_nano() {
+        let raw = Raw::from_nano_str("1.23456").unwrap();
+        assert_eq!(raw.format_nano(2, RoundingMode::Truncate), "1.23");
+        assert_eq!(raw.format_nano(3, RoundingMode::HalfUp), "1.235");
+        assert_eq!(raw.format_nano(4, RoundingMode::HalfUp), "1.2346");
+        assert_eq!(raw.format_nano(2, RoundingMode::Up), "1.24");
+        assert_eq!(raw.format_nano(30, RoundingMode::Truncate), "1.23456");
+        assert_eq!(raw.format_nano(0, RoundingMode::HalfUp), "1");
+
+        let raw = Raw::from_nano_str("0.995").unwrap();
+        assert_eq!(raw.format_nano(2, RoundingMode::HalfUp), "1");
+        assert_eq!(raw.format_nano(2, RoundingMode::Truncate), "0.99");
+
+        assert_eq!(Raw::new(1).format_nano(6, RoundingMode::Up), "0.000001");
+        assert_eq!(Raw::new(1).format_nano(6, RoundingMode::Truncate), "0");
+    }
+
+    #[test]
+    fn test_unit_conversions() {
+        assert_eq!(Unit::Nano.raw_per_unit(), NANO_IN_RAW);
+        assert_eq!(Unit::KNano.raw_per_unit(), NANO_IN_RAW * 1000);
+        assert_eq!(Unit::MilliNano.raw_per_unit(), NANO_IN_RAW / 1000);
+        assert_eq!(Unit::MicroNano.raw_per_unit(), NANO_IN_RAW / 1_000_000);
+        assert_eq!(Unit::Raw.raw_per_unit(), 1);
+
+        let amount = Amount::new(Raw::from_nano(1).unwrap(), Unit::Nano);
+        assert_eq!(amount.to_unit_string(Unit::KNano), "0.001");
+        assert_eq!(amount.to_unit_string(Unit::MilliNano), "1000");
+        assert_eq!(amount.to_unit_string(Unit::MicroNano), "1000000");
+        assert_eq!(amount.to_unit_string(Unit::Raw), NANO_IN_RAW.to_string());
+    }
+
+    #[test]
+    fn test_unit_parse() {
+        assert_eq!("knano".parse::<Unit>().unwrap(), Unit::KNano);
+        assert_eq!("Knano".parse::<Unit>().unwrap(), Unit::KNano);
+        assert_eq!("XNO".parse::<Unit>().unwrap(), Unit::Nano);
+        assert_eq!("mnano".parse::<Unit>().unwrap(), Unit::MilliNano);
+        assert_eq!("micronano".parse::<Unit>().unwrap(), Unit::MicroNano);
+        assert_eq!("raw".parse::<Unit>().unwrap(), Unit::Raw);
+        assert!("satoshi".parse::<Unit>().is_err());
+    }
+
+    #[test]
+    fn test_amount_parse_and_display() {
+        let amount: Amount = "1.5 knano".parse().unwrap();
+        assert_eq!(amount.unit(), Unit::KNano);
+        assert_eq!(amount.raw(), Raw::from_nano(1500).unwrap());
+        assert_eq!(amount.to_string(), "1.5 knano");
+
+        let amount: Amount = "0.000001 nano".parse().unwrap();
+        assert_eq!(amount.with_unit(Unit::MicroNano).to_string(), "1 micronano");
+
+        let amount: Amount = "12345".parse().unwrap();
+        assert_eq!(amount.unit(), Unit::Raw);
+        assert_eq!(amount.to_string(), "12345 raw");
+
+        assert!("1.5 raw".parse::<Amount>().is_err());
+        assert!("1.5 bogus".parse::<Amount>().is_err());
+    }
 }
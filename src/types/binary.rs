@@ -0,0 +1,126 @@
+//! Compact encodings for binary serde formats.
+//!
+//! The layouts are documented in the [`types`](super) module docs.
+
+use core::fmt;
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::Deserializer;
+
+/// Deserialize a byte string of exactly `N` bytes.
+pub(crate) fn deserialize_array<'de, D, const N: usize>(
+    deserializer: D,
+) -> Result<[u8; N], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_bytes(ArrayVisitor::<N>)
+}
+
+struct ArrayVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for ArrayVisitor<N> {
+    type Value = [u8; N];
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes", N)
+    }
+
+    fn visit_bytes<E: Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        bytes
+            .try_into()
+            .map_err(|_| E::invalid_length(bytes.len(), &self))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut array = [0u8; N];
+        for (index, byte) in array.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(index, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(N + 1, &self));
+        }
+        Ok(array)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keys::Seed;
+    use crate::types::{Account, BlockHash, Link, Raw, Signature, StateBlock, Subtype, Work};
+
+    fn block() -> StateBlock {
+        let keypair = Seed::from_bytes([4u8; 32]).derive(0);
+        let mut block = StateBlock::new(
+            keypair.account(),
+            BlockHash::from_bytes([1u8; 32]),
+            keypair.account(),
+            Raw::from_nano(3).unwrap(),
+            Link::from_bytes([2u8; 32]),
+        )
+        .with_work(Work::new(0x1234))
+        .with_subtype(Subtype::Send);
+        block.signature = Some(Signature::from_bytes([5u8; 64]));
+        block
+    }
+
+    #[test]
+    fn test_bincode_layout() {
+        let raw = bincode::serialize(&Raw::new(1)).unwrap();
+        assert_eq!(raw.len(), 8 + 16);
+        assert_eq!(raw[..8], 16u64.to_le_bytes());
+        assert_eq!(raw[23], 1);
+
+        let hash = BlockHash::from_bytes([7u8; 32]);
+        assert_eq!(bincode::serialize(&hash).unwrap()[8..], [7u8; 32]);
+        assert_eq!(
+            bincode::serialize(&Work::new(9)).unwrap(),
+            9u64.to_le_bytes()
+        );
+
+        let account = Account::from_public_key(&crate::types::PublicKey::ZERO);
+        assert_eq!(bincode::serialize(&account).unwrap().len(), 8 + 32);
+    }
+
+    #[test]
+    fn test_bincode_round_trip() {
+        let signed = block();
+        let bytes = bincode::serialize(&signed).unwrap();
+        assert_eq!(bincode::deserialize::<StateBlock>(&bytes).unwrap(), signed);
+
+        let unsigned = StateBlock {
+            signature: None,
+            work: None,
+            subtype: None,
+            ..signed
+        };
+        let bytes = bincode::serialize(&unsigned).unwrap();
+        assert_eq!(
+            bincode::deserialize::<StateBlock>(&bytes).unwrap(),
+            unsigned
+        );
+
+        let truncated = bincode::serialize(&[0u8; 31].as_slice()).unwrap();
+        assert!(bincode::deserialize::<BlockHash>(&truncated).is_err());
+    }
+
+    #[cfg(feature = "cbor")]
+    #[test]
+    fn test_cbor_round_trip() {
+        let block = block();
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&block, &mut bytes).unwrap();
+        assert!(bytes.len() < serde_json::to_vec(&block).unwrap().len() / 2);
+        let decoded: StateBlock = ciborium::from_reader(bytes.as_slice()).unwrap();
+        assert_eq!(decoded, block);
+    }
+
+    #[test]
+    fn test_json_unchanged() {
+        let json = serde_json::to_value(block()).unwrap();
+        assert_eq!(json["type"], "state");
+        assert_eq!(json["balance"], "3000000000000000000000000000000");
+        assert_eq!(json["work"], "0000000000001234");
+    }
+}
@@ -1,10 +1,13 @@
 //! Block types for Nano state blocks.
 
+#[cfg(feature = "serde")]
+use alloc::format;
 use alloc::string::{String, ToString};
 use core::fmt;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::error::{Error, Result};
+use crate::error::{BlockError, Error, Result};
 use crate::types::{Account, PublicKey, Raw, Signature, Work};
 
 /// Block hash (32 bytes).
@@ -73,22 +76,32 @@ impl AsRef<[u8]> for BlockHash {
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for BlockHash {
     fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_hex())
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_hex())
+        } else {
+            serializer.serialize_bytes(self.as_bytes())
+        }
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for BlockHash {
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        BlockHash::from_hex(&s).map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            BlockHash::from_hex(&s).map_err(serde::de::Error::custom)
+        } else {
+            super::binary::deserialize_array(deserializer).map(BlockHash::from_bytes)
+        }
     }
 }
 
@@ -193,28 +206,39 @@ impl From<PublicKey> for Link {
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for Link {
     fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_hex())
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_hex())
+        } else {
+            serializer.serialize_bytes(self.as_bytes())
+        }
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Link {
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Link::from_hex(&s).map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Link::from_hex(&s).map_err(serde::de::Error::custom)
+        } else {
+            super::binary::deserialize_array(deserializer).map(Link::from_bytes)
+        }
     }
 }
 
 /// Block subtype indicating the operation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Subtype {
     /// Send funds to another account.
     Send,
@@ -251,10 +275,12 @@ impl fmt::Display for Subtype {
 ///
 /// State blocks are the only block type used in modern Nano.
 /// They contain all information needed to represent any transaction type.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// JSON uses the node's field names and hex strings; binary serde formats
+/// get the compact layout described in [`types`](crate::types).
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StateBlock {
     /// Block type (always "state").
-    #[serde(rename = "type")]
     pub block_type: String,
 
     /// Account this block belongs to.
@@ -273,19 +299,139 @@ pub struct StateBlock {
     pub link: Link,
 
     /// Ed25519 signature of the block hash.
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<Signature>,
 
     /// Proof of work.
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub work: Option<Work>,
 
     /// Block subtype (send, receive, open, change, epoch).
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub subtype: Option<Subtype>,
 }
 
+/// JSON form of [`StateBlock`], for serializing.
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct StateBlockRef<'a> {
+    #[serde(rename = "type")]
+    block_type: &'a str,
+    account: &'a Account,
+    previous: &'a BlockHash,
+    representative: &'a Account,
+    balance: &'a Raw,
+    link: &'a Link,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<&'a Signature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    work: Option<&'a Work>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subtype: Option<&'a Subtype>,
+}
+
+/// JSON form of [`StateBlock`], for deserializing.
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct StateBlockFields {
+    #[serde(rename = "type")]
+    block_type: String,
+    account: Account,
+    previous: BlockHash,
+    representative: Account,
+    balance: Raw,
+    link: Link,
+    signature: Option<Signature>,
+    work: Option<Work>,
+    subtype: Option<Subtype>,
+}
+
+/// Binary form of [`StateBlock`].
+#[cfg(feature = "serde")]
+type StateBlockTuple = (
+    Account,
+    BlockHash,
+    Account,
+    Raw,
+    Link,
+    Option<Signature>,
+    Option<Work>,
+    Option<Subtype>,
+);
+
+#[cfg(feature = "serde")]
+impl Serialize for StateBlock {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            StateBlockRef {
+                block_type: &self.block_type,
+                account: &self.account,
+                previous: &self.previous,
+                representative: &self.representative,
+                balance: &self.balance,
+                link: &self.link,
+                signature: self.signature.as_ref(),
+                work: self.work.as_ref(),
+                subtype: self.subtype.as_ref(),
+            }
+            .serialize(serializer)
+        } else {
+            (
+                &self.account,
+                &self.previous,
+                &self.representative,
+                &self.balance,
+                &self.link,
+                &self.signature,
+                &self.work,
+                &self.subtype,
+            )
+                .serialize(serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for StateBlock {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let fields = StateBlockFields::deserialize(deserializer)?;
+            Ok(StateBlock {
+                block_type: fields.block_type,
+                account: fields.account,
+                previous: fields.previous,
+                representative: fields.representative,
+                balance: fields.balance,
+                link: fields.link,
+                signature: fields.signature,
+                work: fields.work,
+                subtype: fields.subtype,
+            })
+        } else {
+            let (account, previous, representative, balance, link, signature, work, subtype) =
+                StateBlockTuple::deserialize(deserializer)?;
+            Ok(StateBlock {
+                block_type: "state".to_string(),
+                account,
+                previous,
+                representative,
+                balance,
+                link,
+                signature,
+                work,
+                subtype,
+            })
+        }
+    }
+}
+
 impl StateBlock {
+    /// Size of the binary wire representation in bytes.
+    pub const SERIALIZED_SIZE: usize = 216;
+
     /// Create a new state block.
     pub fn new(
         account: Account,
@@ -340,6 +486,156 @@ impl StateBlock {
         self.previous.is_zero()
     }
 
+    /// Get the hash that proof of work is computed against.
+    ///
+    /// This is the previous block hash, or the account public key for open
+    /// blocks.
+    pub fn work_root(&self) -> BlockHash {
+        if self.is_open() {
+            BlockHash::from_bytes(*self.account.public_key().as_bytes())
+        } else {
+            self.previous
+        }
+    }
+
+    /// Serialize to the 216-byte wire format used by the node protocol.
+    ///
+    /// Layout: account (32) || previous (32) || representative (32) ||
+    /// balance (16, big-endian) || link (32) || signature (64) ||
+    /// work (8, big-endian). A missing signature or work is written as zeros.
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_SIZE] {
+        let mut bytes = [0u8; Self::SERIALIZED_SIZE];
+        bytes[0..32].copy_from_slice(self.account.public_key().as_bytes());
+        bytes[32..64].copy_from_slice(self.previous.as_bytes());
+        bytes[64..96].copy_from_slice(self.representative.public_key().as_bytes());
+        bytes[96..112].copy_from_slice(&self.balance.to_be_bytes());
+        bytes[112..144].copy_from_slice(self.link.as_bytes());
+        if let Some(signature) = &self.signature {
+            bytes[144..208].copy_from_slice(signature.as_bytes());
+        }
+        if let Some(work) = &self.work {
+            bytes[208..216].copy_from_slice(&work.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Deserialize from the 216-byte wire format.
+    ///
+    /// The subtype is not part of the wire format and is left unset.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != Self::SERIALIZED_SIZE {
+            return Err(Error::InvalidBlock(BlockError::InvalidLength(bytes.len())));
+        }
+
+        let field = |range: core::ops::Range<usize>| {
+            let mut arr = [0u8; 32];
+            arr.copy_from_slice(&bytes[range]);
+            arr
+        };
+        let mut balance = [0u8; 16];
+        balance.copy_from_slice(&bytes[96..112]);
+        let mut signature = [0u8; 64];
+        signature.copy_from_slice(&bytes[144..208]);
+        let mut work = [0u8; 8];
+        work.copy_from_slice(&bytes[208..216]);
+
+        Ok(StateBlock::new(
+            Account::from_public_key(&PublicKey::from_bytes(field(0..32))),
+            BlockHash(field(32..64)),
+            Account::from_public_key(&PublicKey::from_bytes(field(64..96))),
+            Raw::from_be_bytes(balance),
+            Link(field(112..144)),
+        )
+        .with_signature(Signature::from_bytes(signature))
+        .with_work(Work::from_be_bytes(work)))
+    }
+
+    /// Parse a state block from node JSON.
+    ///
+    /// More lenient than the `Deserialize` impl, to accept the shapes
+    /// returned by `block_info`, `blocks_info`, `account_history` (raw) and
+    /// `block_create`:
+    ///
+    /// - field names in any case,
+    /// - the block as a JSON-encoded string (`json_block` off),
+    /// - a decimal balance, or a 32-digit hex balance,
+    /// - `link_as_account` in place of, or alongside, `link`,
+    /// - hex in any case, and a missing or empty signature, work or subtype.
+    #[cfg(feature = "serde")]
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self> {
+        if let serde_json::Value::String(json) = value {
+            let parsed: serde_json::Value = serde_json::from_str(json)
+                .map_err(|e| Error::InvalidBlock(BlockError::InvalidJson(e.to_string())))?;
+            return Self::from_json_value(&parsed);
+        }
+        let fields = value
+            .as_object()
+            .ok_or_else(|| Error::InvalidBlock(BlockError::InvalidJson("not an object".into())))?;
+
+        let field = |name: &str| -> Option<&str> {
+            fields
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .and_then(|(_, value)| value.as_str())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let required = |name: &'static str| {
+            field(name).ok_or(Error::InvalidBlock(BlockError::MissingField(name)))
+        };
+
+        if let Some(block_type) = field("type") {
+            if !block_type.eq_ignore_ascii_case("state") {
+                return Err(Error::InvalidBlock(BlockError::InvalidJson(format!(
+                    "expected a state block, got {}",
+                    block_type
+                ))));
+            }
+        }
+
+        let balance = required("balance")?;
+        let balance = if balance.len() == 32 && !balance.bytes().all(|b| b.is_ascii_digit()) {
+            Raw::from_hex(balance)?
+        } else {
+            balance.parse()?
+        };
+
+        let link = match (field("link"), field("link_as_account")) {
+            (Some(link), as_account) => {
+                let link = Link::from_hex(link)?;
+                if let Some(account) = as_account {
+                    if Link::from_account(&account.parse()?) != link {
+                        return Err(Error::InvalidBlock(BlockError::InvalidLink));
+                    }
+                }
+                link
+            }
+            (None, Some(account)) => Link::from_account(&account.parse()?),
+            (None, None) => return Err(Error::InvalidBlock(BlockError::MissingField("link"))),
+        };
+
+        let mut block = StateBlock::new(
+            required("account")?.parse()?,
+            BlockHash::from_hex(required("previous")?)?,
+            required("representative")?.parse()?,
+            balance,
+            link,
+        );
+        if let Some(signature) = field("signature") {
+            block.signature = Some(Signature::from_hex(signature)?);
+        }
+        if let Some(work) = field("work") {
+            block.work = Some(Work::from_hex(work)?);
+        }
+        if let Some(subtype) = field("subtype") {
+            block.subtype = Some(
+                serde_json::from_value(serde_json::Value::String(subtype.to_ascii_lowercase()))
+                    .map_err(|_| Error::InvalidBlock(BlockError::InvalidSubtype))?,
+            );
+        }
+        Ok(block)
+    }
+
     /// Infer the subtype from block contents.
     pub fn infer_subtype(&self, previous_balance: Option<Raw>) -> Subtype {
         if self.previous.is_zero() {
@@ -383,6 +679,48 @@ mod tests {
 
     const TEST_HASH_HEX: &str = "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948";
 
+    fn test_state_block() -> StateBlock {
+        let account = Account::from_public_key(
+            &PublicKey::from_hex(
+                "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA",
+            )
+            .unwrap(),
+        );
+        StateBlock::new(
+            account.clone(),
+            BlockHash::from_hex(TEST_HASH_HEX).unwrap(),
+            account,
+            Raw::new(0x0102030405060708),
+            Link::from_bytes([0x11; 32]),
+        )
+        .with_signature(Signature::from_bytes([0x22; 64]))
+        .with_work(Work::new(0x0123456789ABCDEF))
+    }
+
+    #[test]
+    fn test_state_block_bytes_roundtrip() {
+        let block = test_state_block();
+        let bytes = block.to_bytes();
+
+        assert_eq!(bytes.len(), StateBlock::SERIALIZED_SIZE);
+        assert_eq!(&bytes[0..4], &[0xE8, 0x92, 0x08, 0xDD]);
+        assert_eq!(&bytes[104..112], &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(
+            &bytes[208..216],
+            &[0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF]
+        );
+
+        assert_eq!(StateBlock::from_bytes(&bytes).unwrap(), block);
+    }
+
+    #[test]
+    fn test_state_block_from_bytes_invalid_length() {
+        assert!(matches!(
+            StateBlock::from_bytes(&[0u8; 215]),
+            Err(Error::InvalidBlock(BlockError::InvalidLength(215)))
+        ));
+    }
+
     #[test]
     fn test_block_hash_from_hex() {
         let hash = BlockHash::from_hex(TEST_HASH_HEX).unwrap();
@@ -429,6 +767,118 @@ mod tests {
         assert_eq!(link.as_block_hash(), hash);
     }
 
+    /// `block_info` contents from the node RPC documentation.
+    #[cfg(feature = "serde")]
+    const NODE_BLOCK_JSON: &str = r#"{
+        "type": "state",
+        "account": "nano_1ipx847tk8o46pwxt5qjdbncjqcbwcc1rrmqnkztrfjy5k7z4imsrata9est",
+        "previous": "CE898C131AAEE25E05362F247760F8A3ACF34A9796A5AE0D9204E86B0637965E",
+        "representative": "nano_1stofnrxuz3cai7ze75o174bpm7scwj9jn3nxsn8ntzg784jf1gzn1jjdkou",
+        "balance": "5606157000000000000000000000000000000",
+        "link": "5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5",
+        "link_as_account": "nano_1qato4k7z3spc8gq1zyd8xeqfbzsoxwo36a45ozbrxcatut7up8ohyardu1z",
+        "signature": "82D41BC16F313E4B2243D14DFFA2FB04679C540C2095FEE7EAE0F2F26880AD56DD48D87A7CC5DD760C5B2D76EE2C205506AA557BF00B60D8DEE312EC7343A501",
+        "work": "8a142e07a10996d5"
+    }"#;
+
+    #[cfg(feature = "serde")]
+    fn node_block_value() -> serde_json::Value {
+        serde_json::from_str(NODE_BLOCK_JSON).unwrap()
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_block_from_node_json() {
+        let block = StateBlock::from_json_value(&node_block_value()).unwrap();
+        assert_eq!(
+            block.balance,
+            "5606157000000000000000000000000000000".parse().unwrap()
+        );
+        assert_eq!(
+            block.work,
+            Some(Work::from_hex("8a142e07a10996d5").unwrap())
+        );
+        assert!(block.signature.is_some());
+        assert_eq!(
+            crate::blocks::BlockHasher::hash_state_block(&block).to_hex(),
+            "87434F8041869A01C8F6F263B87972D7BA443A72E0A97D7A3FD0CCC2358FD6F9"
+        );
+
+        let round_trip = serde_json::to_value(&block).unwrap();
+        assert_eq!(StateBlock::from_json_value(&round_trip).unwrap(), block);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_block_from_json_string() {
+        let value = serde_json::Value::String(NODE_BLOCK_JSON.into());
+        assert_eq!(
+            StateBlock::from_json_value(&value).unwrap(),
+            StateBlock::from_json_value(&node_block_value()).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_block_from_json_lenient_fields() {
+        let expected = StateBlock::from_json_value(&node_block_value()).unwrap();
+        let mut value = node_block_value();
+        let fields = value.as_object_mut().unwrap();
+        fields.remove("link");
+        let work = fields.remove("work").unwrap();
+        fields.insert("Work".into(), work.as_str().unwrap().to_uppercase().into());
+        let signature = fields.remove("signature").unwrap();
+        fields.insert(
+            "SIGNATURE".into(),
+            signature.as_str().unwrap().to_lowercase().into(),
+        );
+        fields.insert("subtype".into(), "send".into());
+
+        let block = StateBlock::from_json_value(&value).unwrap();
+        assert_eq!(block.link, expected.link);
+        assert_eq!(block.work, expected.work);
+        assert_eq!(block.signature, expected.signature);
+        assert_eq!(block.subtype, Some(Subtype::Send));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_block_from_json_unsigned() {
+        let mut value = node_block_value();
+        let fields = value.as_object_mut().unwrap();
+        fields.insert("signature".into(), "".into());
+        fields.remove("work");
+
+        let block = StateBlock::from_json_value(&value).unwrap();
+        assert!(!block.is_signed());
+        assert!(!block.has_work());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_block_from_json_errors() {
+        let mut value = node_block_value();
+        value["type"] = "send".into();
+        assert!(matches!(
+            StateBlock::from_json_value(&value),
+            Err(Error::InvalidBlock(BlockError::InvalidJson(_)))
+        ));
+
+        let mut value = node_block_value();
+        value.as_object_mut().unwrap().remove("account");
+        assert_eq!(
+            StateBlock::from_json_value(&value).unwrap_err(),
+            Error::InvalidBlock(BlockError::MissingField("account"))
+        );
+
+        let mut value = node_block_value();
+        value["link"] = BlockHash::ZERO.to_hex().into();
+        assert_eq!(
+            StateBlock::from_json_value(&value).unwrap_err(),
+            Error::InvalidBlock(BlockError::InvalidLink)
+        );
+    }
+
     #[test]
     fn test_subtype_display() {
         assert_eq!(Subtype::Send.to_string(), "send");
@@ -507,6 +957,7 @@ mod tests {
         assert_eq!(block.infer_subtype(Some(Raw::new(1000))), Subtype::Change);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_block_hash_serde() {
         let hash = BlockHash::from_hex(TEST_HASH_HEX).unwrap();
@@ -517,6 +968,7 @@ mod tests {
         assert_eq!(hash, recovered);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_subtype_serde() {
         let subtype = Subtype::Send;
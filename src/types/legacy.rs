@@ -0,0 +1,288 @@
+//! Legacy (pre-state) block types and the [`Block`] enum.
+//!
+//! Chains opened before state blocks were introduced still contain `send`,
+//! `receive`, `open` and `change` blocks. They are hashed and signed
+//! differently from state blocks; see [`BlockHasher::hash_block`] and
+//! [`BlockSigner::verify_block`].
+//!
+//! [`BlockHasher::hash_block`]: crate::blocks::BlockHasher::hash_block
+//! [`BlockSigner::verify_block`]: crate::blocks::BlockSigner::verify_block
+
+use alloc::string::String;
+#[cfg(feature = "serde")]
+use alloc::string::ToString;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::types::{Account, BlockHash, Raw, Signature, StateBlock, Work};
+
+/// Legacy send block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SendBlock {
+    /// Block type (always "send").
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub block_type: String,
+    /// Hash of the previous block.
+    pub previous: BlockHash,
+    /// Destination account.
+    pub destination: Account,
+    /// Balance after this block (hex-encoded in JSON).
+    #[cfg_attr(feature = "serde", serde(with = "raw_hex"))]
+    pub balance: Raw,
+    /// Ed25519 signature of the block hash.
+    pub signature: Signature,
+    /// Proof of work.
+    pub work: Work,
+}
+
+/// Legacy receive block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReceiveBlock {
+    /// Block type (always "receive").
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub block_type: String,
+    /// Hash of the previous block.
+    pub previous: BlockHash,
+    /// Hash of the send block being received.
+    pub source: BlockHash,
+    /// Ed25519 signature of the block hash.
+    pub signature: Signature,
+    /// Proof of work.
+    pub work: Work,
+}
+
+/// Legacy open block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OpenBlock {
+    /// Block type (always "open").
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub block_type: String,
+    /// Hash of the send block being received.
+    pub source: BlockHash,
+    /// Representative account.
+    pub representative: Account,
+    /// Account being opened.
+    pub account: Account,
+    /// Ed25519 signature of the block hash.
+    pub signature: Signature,
+    /// Proof of work.
+    pub work: Work,
+}
+
+/// Legacy change block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ChangeBlock {
+    /// Block type (always "change").
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub block_type: String,
+    /// Hash of the previous block.
+    pub previous: BlockHash,
+    /// New representative account.
+    pub representative: Account,
+    /// Ed25519 signature of the block hash.
+    pub signature: Signature,
+    /// Proof of work.
+    pub work: Work,
+}
+
+/// Any Nano block, legacy or state.
+///
+/// Deserialization dispatches on the JSON `type` field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
+pub enum Block {
+    /// Legacy send block.
+    Send(SendBlock),
+    /// Legacy receive block.
+    Receive(ReceiveBlock),
+    /// Legacy open block.
+    Open(OpenBlock),
+    /// Legacy change block.
+    Change(ChangeBlock),
+    /// State block.
+    State(StateBlock),
+}
+
+impl Block {
+    /// Get the block type name.
+    pub fn block_type(&self) -> &'static str {
+        match self {
+            Block::Send(_) => "send",
+            Block::Receive(_) => "receive",
+            Block::Open(_) => "open",
+            Block::Change(_) => "change",
+            Block::State(_) => "state",
+        }
+    }
+
+    /// Check if this is a legacy (pre-state) block.
+    pub fn is_legacy(&self) -> bool {
+        !matches!(self, Block::State(_))
+    }
+
+    /// Hash of the previous block (zero for open blocks).
+    pub fn previous(&self) -> BlockHash {
+        match self {
+            Block::Send(b) => b.previous,
+            Block::Receive(b) => b.previous,
+            Block::Open(_) => BlockHash::ZERO,
+            Block::Change(b) => b.previous,
+            Block::State(b) => b.previous,
+        }
+    }
+
+    /// Account owning the block, if contained in the block itself.
+    ///
+    /// Legacy send, receive and change blocks do not carry their account.
+    pub fn account(&self) -> Option<&Account> {
+        match self {
+            Block::Open(b) => Some(&b.account),
+            Block::State(b) => Some(&b.account),
+            _ => None,
+        }
+    }
+
+    /// Representative, if set by this block.
+    pub fn representative(&self) -> Option<&Account> {
+        match self {
+            Block::Open(b) => Some(&b.representative),
+            Block::Change(b) => Some(&b.representative),
+            Block::State(b) => Some(&b.representative),
+            _ => None,
+        }
+    }
+
+    /// Block signature.
+    pub fn signature(&self) -> Option<Signature> {
+        match self {
+            Block::Send(b) => Some(b.signature),
+            Block::Receive(b) => Some(b.signature),
+            Block::Open(b) => Some(b.signature),
+            Block::Change(b) => Some(b.signature),
+            Block::State(b) => b.signature,
+        }
+    }
+
+    /// Proof of work.
+    pub fn work(&self) -> Option<Work> {
+        match self {
+            Block::Send(b) => Some(b.work),
+            Block::Receive(b) => Some(b.work),
+            Block::Open(b) => Some(b.work),
+            Block::Change(b) => Some(b.work),
+            Block::State(b) => b.work,
+        }
+    }
+}
+
+impl From<StateBlock> for Block {
+    fn from(block: StateBlock) -> Self {
+        Block::State(block)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Block {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let block_type = value
+            .get("type")
+            .and_then(|t| t.as_str())
+            .ok_or_else(|| D::Error::missing_field("type"))?
+            .to_string();
+
+        let block = match block_type.as_str() {
+            "send" => serde_json::from_value(value).map(Block::Send),
+            "receive" => serde_json::from_value(value).map(Block::Receive),
+            "open" => serde_json::from_value(value).map(Block::Open),
+            "change" => serde_json::from_value(value).map(Block::Change),
+            "state" => serde_json::from_value(value).map(Block::State),
+            other => {
+                return Err(D::Error::unknown_variant(
+                    other,
+                    &["send", "receive", "open", "change", "state"],
+                ))
+            }
+        };
+        block.map_err(D::Error::custom)
+    }
+}
+
+/// Legacy send balances are hex-encoded 16-byte big-endian values.
+#[cfg(feature = "serde")]
+mod raw_hex {
+    use alloc::string::String;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::types::Raw;
+
+    pub fn serialize<S: Serializer>(value: &Raw, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_hex())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Raw, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Raw::from_hex(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_legacy_send() {
+        let json = r#"{
+            "type": "send",
+            "previous": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            "destination": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+            "balance": "0000007E37BE2022C0914B2680000000",
+            "work": "0000000000000000",
+            "signature": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
+        }"#;
+        let block: Block = serde_json::from_str(json).unwrap();
+
+        match &block {
+            Block::Send(send) => {
+                assert_eq!(
+                    send.balance,
+                    Raw::from_hex("0000007E37BE2022C0914B2680000000").unwrap()
+                );
+            }
+            other => panic!("expected send block, got {:?}", other),
+        }
+        assert!(block.is_legacy());
+        assert!(block.account().is_none());
+
+        let json = serde_json::to_string(&block).unwrap();
+        assert!(json.contains("\"balance\":\"0000007E37BE2022C0914B2680000000\""));
+        assert_eq!(serde_json::from_str::<Block>(&json).unwrap(), block);
+    }
+
+    #[test]
+    fn test_deserialize_dispatches_on_type() {
+        let json = r#"{
+            "type": "change",
+            "previous": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            "representative": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+            "work": "0000000000000000",
+            "signature": "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
+        }"#;
+        let block: Block = serde_json::from_str(json).unwrap();
+        assert_eq!(block.block_type(), "change");
+        assert!(block.representative().is_some());
+
+        let unknown = json.replace("\"change\"", "\"bogus\"");
+        assert!(serde_json::from_str::<Block>(&unknown).is_err());
+    }
+}
@@ -1,13 +1,32 @@
 //! Core types for Nano cryptocurrency operations.
+//!
+//! With the `serde` feature, human-readable formats such as JSON use the
+//! node's representation: hex strings, `nano_` addresses and decimal raw
+//! amounts. Binary formats such as bincode and CBOR get a compact, stable
+//! layout instead:
+//!
+//! | Type | Encoding |
+//! |------|----------|
+//! | [`BlockHash`], [`Link`], [`PublicKey`] | 32-byte string |
+//! | [`Account`] | 32-byte string of the public key |
+//! | [`Raw`] | 16-byte string, big-endian |
+//! | [`Signature`] | 64-byte string |
+//! | [`Work`] | `u64` |
+//! | [`Subtype`] | unit enum variant, in declaration order |
+//! | [`StateBlock`] | tuple of account, previous, representative, balance, link, and optional signature, work and subtype |
 
 mod account;
 mod amount;
+#[cfg(feature = "serde")]
+mod binary;
 mod block;
+mod legacy;
 mod signature;
 mod work;
 
 pub use account::{Account, PublicKey};
-pub use amount::{Amount, Raw};
+pub use amount::{Amount, Raw, RoundingMode, Unit};
 pub use block::{BlockHash, Link, StateBlock, Subtype};
+pub use legacy::{Block, ChangeBlock, OpenBlock, ReceiveBlock, SendBlock};
 pub use signature::Signature;
 pub use work::Work;
@@ -2,6 +2,7 @@
 
 use alloc::string::String;
 use core::fmt;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
@@ -64,22 +65,32 @@ impl AsRef<[u8]> for Signature {
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for Signature {
     fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_hex())
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_hex())
+        } else {
+            serializer.serialize_bytes(self.as_bytes())
+        }
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Signature {
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Signature::from_hex(&s).map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Signature::from_hex(&s).map_err(serde::de::Error::custom)
+        } else {
+            super::binary::deserialize_array(deserializer).map(Signature::from_bytes)
+        }
     }
 }
 
@@ -112,6 +123,7 @@ mod tests {
         assert!(matches!(result, Err(Error::InvalidSignature)));
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_signature_serde() {
         let sig = Signature::from_hex(TEST_SIG_HEX).unwrap();
@@ -2,6 +2,7 @@
 
 use alloc::string::String;
 use core::fmt;
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use crate::error::{Error, Result};
@@ -94,22 +95,32 @@ impl From<Work> for u64 {
     }
 }
 
+#[cfg(feature = "serde")]
 impl Serialize for Work {
     fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_hex())
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&self.to_hex())
+        } else {
+            serializer.serialize_u64(self.0)
+        }
     }
 }
 
+#[cfg(feature = "serde")]
 impl<'de> Deserialize<'de> for Work {
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Work::from_hex(&s).map_err(serde::de::Error::custom)
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Work::from_hex(&s).map_err(serde::de::Error::custom)
+        } else {
+            u64::deserialize(deserializer).map(Work)
+        }
     }
 }
 
@@ -158,6 +169,7 @@ mod tests {
         assert!(matches!(result, Err(Error::InvalidWork)));
     }
 
+    #[cfg(feature = "serde")]
     #[test]
     fn test_work_serde() {
         let work = Work::from_hex(TEST_WORK_HEX).unwrap();
@@ -0,0 +1,330 @@
+//! `nano:` URI building and parsing.
+//!
+//! Implements the payment URI format used by wallets and point-of-sale
+//! integrations:
+//!
+//! ```text
+//! nano:<address>?amount=<raw>&label=<label>&message=<message>
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use xno_connect::uri::NanoUri;
+//! use xno_connect::types::Raw;
+//!
+//! # fn main() -> xno_connect::error::Result<()> {
+//! let uri = NanoUri::parse(
+//!     "nano:nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3?amount=1000&label=Coffee",
+//! )?;
+//! assert_eq!(uri.amount, Some(Raw::new(1000)));
+//! assert_eq!(uri.label.as_deref(), Some("Coffee"));
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::{Error, Result, UriError};
+use crate::types::{Account, Raw};
+
+/// URI scheme for Nano payment requests.
+pub const URI_SCHEME: &str = "nano";
+
+/// A parsed `nano:` payment URI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NanoUri {
+    /// Destination account.
+    pub account: Account,
+    /// Requested amount in raw.
+    pub amount: Option<Raw>,
+    /// Label for the recipient.
+    pub label: Option<String>,
+    /// Message describing the payment.
+    pub message: Option<String>,
+}
+
+impl NanoUri {
+    /// Create a URI for an account with no parameters.
+    pub fn new(account: Account) -> Self {
+        NanoUri {
+            account,
+            amount: None,
+            label: None,
+            message: None,
+        }
+    }
+
+    /// Set the requested amount.
+    pub fn with_amount(mut self, amount: Raw) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Set the recipient label.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the payment message.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Parse a `nano:` URI.
+    ///
+    /// The scheme is matched case-insensitively. Unknown query parameters are
+    /// ignored.
+    pub fn parse(s: &str) -> Result<Self> {
+        let rest = match s.split_once(':') {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case(URI_SCHEME) => rest,
+            _ => return Err(Error::InvalidUri(UriError::InvalidScheme)),
+        };
+
+        let (address, query) = match rest.split_once('?') {
+            Some((address, query)) => (address, Some(query)),
+            None => (rest, None),
+        };
+
+        let mut uri = NanoUri::new(address.parse()?);
+
+        for pair in query.into_iter().flat_map(|q| q.split('&')) {
+            if pair.is_empty() {
+                continue;
+            }
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or(Error::InvalidUri(UriError::InvalidParameter))?;
+            match key {
+                "amount" => uri.amount = Some(percent_decode(value)?.parse()?),
+                "label" => uri.label = Some(percent_decode(value)?),
+                "message" => uri.message = Some(percent_decode(value)?),
+                _ => {}
+            }
+        }
+
+        Ok(uri)
+    }
+}
+
+impl fmt::Display for NanoUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", URI_SCHEME, self.account)?;
+
+        let mut separator = '?';
+        if let Some(amount) = &self.amount {
+            write!(f, "{}amount={}", separator, amount)?;
+            separator = '&';
+        }
+        if let Some(label) = &self.label {
+            write!(f, "{}label={}", separator, percent_encode(label))?;
+            separator = '&';
+        }
+        if let Some(message) = &self.message {
+            write!(f, "{}message={}", separator, percent_encode(message))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for NanoUri {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        NanoUri::parse(s)
+    }
+}
+
+impl From<Account> for NanoUri {
+    fn from(account: Account) -> Self {
+        NanoUri::new(account)
+    }
+}
+
+impl From<NanoUri> for Account {
+    fn from(uri: NanoUri) -> Self {
+        uri.account
+    }
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn percent_encode(s: &str) -> String {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+    let mut result = String::with_capacity(s.len());
+    for &byte in s.as_bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            result.push(byte as char);
+        } else {
+            result.push('%');
+            result.push(HEX[(byte >> 4) as usize] as char);
+            result.push(HEX[(byte & 0x0F) as usize] as char);
+        }
+    }
+    result
+}
+
+/// Decode percent-escapes, treating `+` as a space.
+fn percent_decode(s: &str) -> Result<String> {
+    let bytes = s.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    // from_str_radix alone would accept a leading sign
+                    .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+                    .and_then(|h| core::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                    .ok_or(Error::InvalidUri(UriError::InvalidEncoding))?;
+                result.push(hex);
+                i += 3;
+            }
+            b'+' => {
+                result.push(b' ');
+                i += 1;
+            }
+            byte => {
+                result.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(result).map_err(|_| Error::InvalidUri(UriError::InvalidEncoding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AccountError;
+
+    const TEST_ACCOUNT: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+
+    fn test_account() -> Account {
+        TEST_ACCOUNT.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_address_only() {
+        let uri = NanoUri::parse(&format!("nano:{}", TEST_ACCOUNT)).unwrap();
+        assert_eq!(uri.account, test_account());
+        assert!(uri.amount.is_none());
+        assert!(uri.label.is_none());
+        assert!(uri.message.is_none());
+    }
+
+    #[test]
+    fn test_parse_all_parameters() {
+        let uri = NanoUri::parse(&format!(
+            "nano:{}?amount=1000000000000000000000000000000&label=Coffee%20Shop&message=Order+42",
+            TEST_ACCOUNT
+        ))
+        .unwrap();
+
+        assert_eq!(uri.amount, Some(Raw::from_nano(1).unwrap()));
+        assert_eq!(uri.label.as_deref(), Some("Coffee Shop"));
+        assert_eq!(uri.message.as_deref(), Some("Order 42"));
+    }
+
+    #[test]
+    fn test_parse_scheme_case_insensitive() {
+        let uri = NanoUri::parse(&format!("NANO:{}", TEST_ACCOUNT)).unwrap();
+        assert_eq!(uri.account, test_account());
+    }
+
+    #[test]
+    fn test_parse_ignores_unknown_parameters() {
+        let uri = NanoUri::parse(&format!("nano:{}?foo=bar&amount=5", TEST_ACCOUNT)).unwrap();
+        assert_eq!(uri.amount, Some(Raw::new(5)));
+    }
+
+    #[test]
+    fn test_parse_invalid_scheme() {
+        let result = NanoUri::parse(&format!("bitcoin:{}", TEST_ACCOUNT));
+        assert!(matches!(
+            result,
+            Err(Error::InvalidUri(UriError::InvalidScheme))
+        ));
+        assert!(matches!(
+            NanoUri::parse(TEST_ACCOUNT),
+            Err(Error::InvalidUri(UriError::InvalidScheme))
+        ));
+    }
+
+    #[test]
+    fn test_parse_invalid_account() {
+        let result = NanoUri::parse("nano:nano_invalid");
+        assert!(matches!(
+            result,
+            Err(Error::InvalidAccount(AccountError::InvalidLength))
+        ));
+    }
+
+    #[test]
+    fn test_parse_invalid_amount() {
+        let result = NanoUri::parse(&format!("nano:{}?amount=1.5", TEST_ACCOUNT));
+        assert!(matches!(result, Err(Error::InvalidAmount(_))));
+    }
+
+    #[test]
+    fn test_parse_invalid_encoding() {
+        for label in ["%G1", "%+5", "%-1"] {
+            let result = NanoUri::parse(&format!("nano:{}?label={}", TEST_ACCOUNT, label));
+            assert!(matches!(
+                result,
+                Err(Error::InvalidUri(UriError::InvalidEncoding))
+            ));
+        }
+
+        let result = NanoUri::parse(&format!("nano:{}?label", TEST_ACCOUNT));
+        assert!(matches!(
+            result,
+            Err(Error::InvalidUri(UriError::InvalidParameter))
+        ));
+    }
+
+    #[test]
+    fn test_to_string() {
+        let uri = NanoUri::new(test_account())
+            .with_amount(Raw::new(1000))
+            .with_label("Coffee Shop")
+            .with_message("Thanks!");
+
+        assert_eq!(
+            uri.to_string(),
+            format!(
+                "nano:{}?amount=1000&label=Coffee%20Shop&message=Thanks%21",
+                TEST_ACCOUNT
+            )
+        );
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let uri = NanoUri::new(test_account())
+            .with_amount(Raw::new(42))
+            .with_message("Café & bar = 100%");
+
+        let parsed: NanoUri = uri.to_string().parse().unwrap();
+        assert_eq!(parsed, uri);
+    }
+
+    #[test]
+    fn test_account_conversion() {
+        let uri: NanoUri = test_account().into();
+        assert_eq!(uri.to_string(), format!("nano:{}", TEST_ACCOUNT));
+
+        let account: Account = uri.into();
+        assert_eq!(account, test_account());
+    }
+}
@@ -0,0 +1,582 @@
+//! Representative votes.
+//!
+//! A vote is signed by a representative over the Blake2b-256 hash of:
+//!
+//! ```text
+//! "vote " || block_hash_1 || ... || block_hash_n || timestamp (u64, little-endian)
+//! ```
+//!
+//! Final votes use a timestamp of `u64::MAX`.
+//!
+//! Votes for confirmed blocks can be kept in a [`VoteArchive`] and attached to
+//! [`Receipt`](crate::receipt::Receipt) proof bundles later. The node does not
+//! expose historical votes over RPC, so archives are filled from live
+//! WebSocket `vote` messages.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::keys::KeyPair;
+use crate::types::{Account, BlockHash, Raw, Signature};
+
+use crate::error::Result;
+
+#[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+use crate::error::{AmountError, Error};
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::reps::parse_online;
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::rpc::RpcClient;
+#[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+use crate::websocket::VoteMessage;
+
+/// Prefix hashed before the block hashes of a vote.
+const VOTE_HASH_PREFIX: &[u8] = b"vote ";
+
+/// Timestamp marking a final vote.
+pub const FINAL_VOTE_TIMESTAMP: u64 = u64::MAX;
+
+/// A representative's vote for one or more blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Vote {
+    /// Voting representative.
+    pub representative: Account,
+    /// Signature over the vote hash.
+    pub signature: Signature,
+    /// Vote timestamp (including duration bits), `u64::MAX` for final votes.
+    #[cfg_attr(feature = "serde", serde(with = "u64_string"))]
+    pub timestamp: u64,
+    /// Block hashes voted for.
+    pub hashes: Vec<BlockHash>,
+}
+
+impl Vote {
+    /// Create and sign a vote.
+    pub fn sign(keypair: &KeyPair, hashes: Vec<BlockHash>, timestamp: u64) -> Self {
+        let hash = Self::compute_hash(&hashes, timestamp);
+        Vote {
+            representative: keypair.account(),
+            signature: keypair.sign(&hash),
+            timestamp,
+            hashes,
+        }
+    }
+
+    /// Compute the hash signed by the representative.
+    pub fn hash(&self) -> BlockHash {
+        Self::compute_hash(&self.hashes, self.timestamp)
+    }
+
+    /// Verify the representative's signature.
+    pub fn verify(&self) -> bool {
+        KeyPair::verify_with_public_key(
+            self.representative.public_key(),
+            &self.hash(),
+            &self.signature,
+        )
+    }
+
+    /// Check if this is a final vote.
+    pub fn is_final(&self) -> bool {
+        self.timestamp == FINAL_VOTE_TIMESTAMP
+    }
+
+    /// Check if the vote covers a block.
+    pub fn contains(&self, hash: &BlockHash) -> bool {
+        self.hashes.contains(hash)
+    }
+
+    fn compute_hash(hashes: &[BlockHash], timestamp: u64) -> BlockHash {
+        let mut hasher = Blake2b::<U32>::new();
+        hasher.update(VOTE_HASH_PREFIX);
+        for hash in hashes {
+            hasher.update(hash.as_bytes());
+        }
+        hasher.update(timestamp.to_le_bytes());
+
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&hasher.finalize());
+        BlockHash::from_bytes(bytes)
+    }
+}
+
+#[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+impl TryFrom<&VoteMessage> for Vote {
+    type Error = Error;
+
+    fn try_from(message: &VoteMessage) -> Result<Self> {
+        Ok(Vote {
+            representative: message.account.clone(),
+            signature: Signature::from_hex(&message.signature)?,
+            timestamp: message
+                .raw_timestamp()
+                .ok_or(Error::InvalidAmount(AmountError::InvalidFormat))?,
+            hashes: message.hashes()?,
+        })
+    }
+}
+
+/// Representative voting weights used to check vote quorum.
+///
+/// By default the quorum is 67% of the total weight in the table, matching the
+/// node's online weight quorum. Use [`with_quorum`](Self::with_quorum) to set
+/// it explicitly, e.g. from the `confirmation_quorum` RPC.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WeightTable {
+    weights: BTreeMap<[u8; 32], Raw>,
+    quorum: Option<Raw>,
+}
+
+impl WeightTable {
+    /// Quorum percentage applied to the total weight.
+    pub const QUORUM_PERCENT: u128 = 67;
+
+    /// Create an empty weight table.
+    pub fn new() -> Self {
+        WeightTable::default()
+    }
+
+    /// Set a representative's weight.
+    pub fn insert(&mut self, representative: &Account, weight: Raw) {
+        self.weights
+            .insert(*representative.public_key().as_bytes(), weight);
+    }
+
+    /// Set a representative's weight (builder style).
+    pub fn with_weight(mut self, representative: &Account, weight: Raw) -> Self {
+        self.insert(representative, weight);
+        self
+    }
+
+    /// Set an explicit quorum weight.
+    pub fn with_quorum(mut self, quorum: Raw) -> Self {
+        self.quorum = Some(quorum);
+        self
+    }
+
+    /// Get a representative's weight (zero if unknown).
+    pub fn weight(&self, representative: &Account) -> Raw {
+        self.weights
+            .get(representative.public_key().as_bytes())
+            .copied()
+            .unwrap_or(Raw::ZERO)
+    }
+
+    /// Total weight of all representatives in the table.
+    pub fn total(&self) -> Raw {
+        self.weights.values().sum()
+    }
+
+    /// Weight required for quorum.
+    pub fn quorum(&self) -> Raw {
+        self.quorum.unwrap_or_else(|| {
+            let total = self.total().as_u128();
+            Raw::new(total / 100 * Self::QUORUM_PERCENT + total % 100 * Self::QUORUM_PERCENT / 100)
+        })
+    }
+
+    /// Load online representative weights and the quorum delta from a node.
+    #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+    pub async fn fetch(client: &RpcClient) -> Result<Self> {
+        let online = client.representatives_online().await?;
+        let quorum = client.confirmation_quorum().await?;
+        let mut table = WeightTable::new().with_quorum(quorum.quorum_delta);
+        for (representative, weight) in parse_online(&online.representatives) {
+            table.insert(&representative, weight);
+        }
+        Ok(table)
+    }
+}
+
+/// Storage for votes observed on the network.
+pub trait VoteArchive {
+    /// Store a vote.
+    fn store(&mut self, vote: Vote) -> Result<()>;
+
+    /// Get all stored votes covering a block.
+    fn votes_for(&self, hash: &BlockHash) -> Result<Vec<Vote>>;
+
+    /// Get stored final votes covering a block.
+    fn final_votes_for(&self, hash: &BlockHash) -> Result<Vec<Vote>> {
+        Ok(self
+            .votes_for(hash)?
+            .into_iter()
+            .filter(Vote::is_final)
+            .collect())
+    }
+}
+
+/// In-memory [`VoteArchive`].
+///
+/// Only votes with a valid signature are stored, and duplicates are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryVoteArchive {
+    votes: BTreeMap<[u8; 32], Vec<Vote>>,
+}
+
+impl MemoryVoteArchive {
+    /// Create an empty archive.
+    pub fn new() -> Self {
+        MemoryVoteArchive::default()
+    }
+
+    /// Number of blocks with stored votes.
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    /// Check if the archive is empty.
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+
+    /// Record a WebSocket vote message.
+    ///
+    /// Returns `true` if the vote was valid and stored.
+    #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+    pub fn record_message(&mut self, message: &VoteMessage) -> bool {
+        match Vote::try_from(message) {
+            Ok(vote) if vote.verify() => {
+                self.insert(vote);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn insert(&mut self, vote: Vote) {
+        for hash in &vote.hashes {
+            let entry = self.votes.entry(*hash.as_bytes()).or_default();
+            if !entry.contains(&vote) {
+                entry.push(vote.clone());
+            }
+        }
+    }
+}
+
+impl VoteArchive for MemoryVoteArchive {
+    fn store(&mut self, vote: Vote) -> Result<()> {
+        if vote.verify() {
+            self.insert(vote);
+        }
+        Ok(())
+    }
+
+    fn votes_for(&self, hash: &BlockHash) -> Result<Vec<Vote>> {
+        Ok(self.votes.get(hash.as_bytes()).cloned().unwrap_or_default())
+    }
+}
+
+/// Vote weight accumulated for one block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BlockTally {
+    /// Weight of all representatives that voted for the block.
+    pub weight: Raw,
+    /// Weight of the representatives whose latest vote is final.
+    pub final_weight: Raw,
+    /// Number of representatives that voted.
+    pub voters: usize,
+    /// Number of representatives that cast a final vote.
+    pub final_voters: usize,
+}
+
+/// Representative public key -> (representative, whether its vote is final).
+type Voters = BTreeMap<[u8; 32], (Account, bool)>;
+
+/// Live per-block vote tallies, to estimate confirmation progress.
+///
+/// Feed it WebSocket vote messages; each representative counts once per
+/// block with its weight from the [`WeightTable`], and a final vote
+/// replaces an earlier non-final one. Weights can be loaded with
+/// [`WeightTable::fetch`].
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::rpc::RpcClient;
+/// use xno_connect::vote::{TallyTracker, WeightTable};
+/// use xno_connect::websocket::{subscribe_votes, ParsedMessage, WebSocketClient};
+///
+/// # async fn example() -> xno_connect::error::Result<()> {
+/// let client = RpcClient::new("http://localhost:7076");
+/// let mut ws = WebSocketClient::connect("ws://localhost:7078").await?;
+/// ws.subscribe(subscribe_votes()).await?;
+///
+/// let mut tracker = TallyTracker::new(WeightTable::fetch(&client).await?);
+/// while let Some(message) = ws.receive().await? {
+///     if let ParsedMessage::Vote(vote) = message {
+///         for hash in tracker.record_message(&vote) {
+///             println!("{}: {:.0}%", hash, tracker.progress(&hash) * 100.0);
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TallyTracker {
+    weights: WeightTable,
+    /// Block hash -> voters.
+    blocks: BTreeMap<[u8; 32], Voters>,
+}
+
+impl TallyTracker {
+    /// Create a tracker using a weight table.
+    pub fn new(weights: WeightTable) -> Self {
+        TallyTracker {
+            weights,
+            blocks: BTreeMap::new(),
+        }
+    }
+
+    /// Get the weight table.
+    pub fn weights(&self) -> &WeightTable {
+        &self.weights
+    }
+
+    /// Replace the weight table, for example after refreshing it.
+    pub fn set_weights(&mut self, weights: WeightTable) {
+        self.weights = weights;
+    }
+
+    /// Record a vote without checking its signature.
+    ///
+    /// Returns the hashes whose tally changed.
+    pub fn record(&mut self, vote: &Vote) -> Vec<BlockHash> {
+        let key = *vote.representative.public_key().as_bytes();
+        let is_final = vote.is_final();
+        let mut changed = Vec::new();
+        for hash in &vote.hashes {
+            let voters = self.blocks.entry(*hash.as_bytes()).or_default();
+            let updated = match voters.get(&key) {
+                Some((_, was_final)) => is_final && !was_final,
+                None => true,
+            };
+            if updated {
+                voters.insert(key, (vote.representative.clone(), is_final));
+                changed.push(*hash);
+            }
+        }
+        changed
+    }
+
+    /// Record a WebSocket vote message.
+    ///
+    /// Votes that fail to parse or verify are ignored. Returns the hashes
+    /// whose tally changed.
+    #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+    pub fn record_message(&mut self, message: &VoteMessage) -> Vec<BlockHash> {
+        match Vote::try_from(message) {
+            Ok(vote) if vote.verify() => self.record(&vote),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Get the tally for a block.
+    pub fn tally(&self, hash: &BlockHash) -> Option<BlockTally> {
+        let voters = self.blocks.get(hash.as_bytes())?;
+        let mut tally = BlockTally::default();
+        for (representative, is_final) in voters.values() {
+            let weight = self.weights.weight(representative);
+            tally.weight = tally.weight.saturating_add(weight);
+            tally.voters += 1;
+            if *is_final {
+                tally.final_weight = tally.final_weight.saturating_add(weight);
+                tally.final_voters += 1;
+            }
+        }
+        Some(tally)
+    }
+
+    /// Get the share of the quorum reached by final votes for a block,
+    /// from 0.0 upwards; 1.0 or more means the block is confirmed.
+    pub fn progress(&self, hash: &BlockHash) -> f64 {
+        let quorum = self.weights.quorum().as_u128();
+        match self.tally(hash) {
+            Some(tally) if quorum > 0 => tally.final_weight.as_u128() as f64 / quorum as f64,
+            _ => 0.0,
+        }
+    }
+
+    /// Check if final votes for a block have reached quorum.
+    pub fn is_confirmed(&self, hash: &BlockHash) -> bool {
+        self.tally(hash)
+            .is_some_and(|tally| tally.final_weight >= self.weights.quorum())
+    }
+
+    /// Stop tracking a block, for example once it is confirmed.
+    pub fn remove(&mut self, hash: &BlockHash) -> Option<BlockTally> {
+        let tally = self.tally(hash);
+        self.blocks.remove(hash.as_bytes());
+        tally
+    }
+
+    /// Number of blocks being tracked.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Check if no blocks are being tracked.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// Serialize a `u64` as a decimal string, as the node does in JSON.
+#[cfg(feature = "serde")]
+mod u64_string {
+    use alloc::string::{String, ToString};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::Seed;
+
+    fn test_keypair(index: u32) -> KeyPair {
+        Seed::from_hex("0000000000000000000000000000000000000000000000000000000000000000")
+            .unwrap()
+            .derive(index)
+    }
+
+    fn test_hash() -> BlockHash {
+        BlockHash::from_hex("991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_vote_sign_and_verify() {
+        let vote = Vote::sign(&test_keypair(0), vec![test_hash()], FINAL_VOTE_TIMESTAMP);
+        assert!(vote.verify());
+        assert!(vote.is_final());
+        assert!(vote.contains(&test_hash()));
+
+        let mut tampered = vote.clone();
+        tampered.timestamp = 1;
+        assert!(!tampered.verify());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_vote_serde_roundtrip() {
+        let vote = Vote::sign(&test_keypair(0), vec![test_hash()], FINAL_VOTE_TIMESTAMP);
+        let json = serde_json::to_string(&vote).unwrap();
+        assert!(json.contains("\"18446744073709551615\""));
+
+        let recovered: Vote = serde_json::from_str(&json).unwrap();
+        assert_eq!(recovered, vote);
+    }
+
+    #[test]
+    fn test_weight_table_quorum() {
+        let rep1 = test_keypair(0).account();
+        let rep2 = test_keypair(1).account();
+        let table = WeightTable::new()
+            .with_weight(&rep1, Raw::new(600))
+            .with_weight(&rep2, Raw::new(400));
+
+        assert_eq!(table.total(), Raw::new(1000));
+        assert_eq!(table.quorum(), Raw::new(670));
+        assert_eq!(table.weight(&rep2), Raw::new(400));
+        assert_eq!(table.weight(&test_keypair(2).account()), Raw::ZERO);
+        assert_eq!(table.with_quorum(Raw::new(500)).quorum(), Raw::new(500));
+    }
+
+    #[test]
+    fn test_memory_archive() {
+        let mut archive = MemoryVoteArchive::new();
+        let other = BlockHash::ZERO;
+        let final_vote = Vote::sign(
+            &test_keypair(0),
+            vec![test_hash(), other],
+            FINAL_VOTE_TIMESTAMP,
+        );
+        let normal_vote = Vote::sign(&test_keypair(1), vec![test_hash()], 1);
+
+        archive.store(final_vote.clone()).unwrap();
+        archive.store(final_vote.clone()).unwrap();
+        archive.store(normal_vote.clone()).unwrap();
+
+        let mut forged = normal_vote.clone();
+        forged.timestamp = FINAL_VOTE_TIMESTAMP;
+        archive.store(forged).unwrap();
+
+        assert_eq!(archive.len(), 2);
+        assert_eq!(
+            archive.votes_for(&test_hash()).unwrap(),
+            vec![final_vote.clone(), normal_vote]
+        );
+        assert_eq!(
+            archive.final_votes_for(&test_hash()).unwrap(),
+            vec![final_vote.clone()]
+        );
+        assert_eq!(archive.votes_for(&other).unwrap(), vec![final_vote]);
+    }
+
+    #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+    #[test]
+    fn test_vote_from_message() {
+        let vote = Vote::sign(&test_keypair(0), vec![test_hash()], FINAL_VOTE_TIMESTAMP);
+        let message: VoteMessage = serde_json::from_value(serde_json::json!({
+            "account": vote.representative,
+            "signature": vote.signature.to_hex(),
+            "sequence": "0",
+            "timestamp": FINAL_VOTE_TIMESTAMP.to_string(),
+            "blocks": [test_hash().to_hex()],
+        }))
+        .unwrap();
+        assert_eq!(Vote::try_from(&message).unwrap(), vote);
+
+        let mut archive = MemoryVoteArchive::new();
+        assert!(archive.record_message(&message));
+        assert_eq!(archive.final_votes_for(&test_hash()).unwrap(), vec![vote]);
+    }
+
+    #[test]
+    fn test_tally_tracker() {
+        let rep1 = test_keypair(0);
+        let rep2 = test_keypair(1);
+        let weights = WeightTable::new()
+            .with_weight(&rep1.account(), Raw::new(600))
+            .with_weight(&rep2.account(), Raw::new(400));
+        let mut tracker = TallyTracker::new(weights);
+
+        let normal = Vote::sign(&rep1, vec![test_hash()], 1);
+        assert_eq!(tracker.record(&normal), vec![test_hash()]);
+        assert!(tracker.record(&normal).is_empty());
+        assert_eq!(tracker.progress(&test_hash()), 0.0);
+
+        let final_vote = Vote::sign(&rep1, vec![test_hash()], FINAL_VOTE_TIMESTAMP);
+        assert_eq!(tracker.record(&final_vote), vec![test_hash()]);
+        // A later non-final vote does not downgrade a final one
+        assert!(tracker.record(&normal).is_empty());
+        assert!(!tracker.is_confirmed(&test_hash()));
+
+        tracker.record(&Vote::sign(&rep2, vec![test_hash()], FINAL_VOTE_TIMESTAMP));
+        let tally = tracker.tally(&test_hash()).unwrap();
+        assert_eq!(tally.weight, Raw::new(1000));
+        assert_eq!(tally.final_weight, Raw::new(1000));
+        assert_eq!((tally.voters, tally.final_voters), (2, 2));
+        assert!(tracker.is_confirmed(&test_hash()));
+        assert!(tracker.progress(&test_hash()) > 1.0);
+
+        assert_eq!(tracker.remove(&test_hash()), Some(tally));
+        assert!(tracker.is_empty());
+    }
+}
@@ -1,21 +1,108 @@
 //! Wallet account operations.
 
 use crate::blocks::{
-    create_change_block, create_open_block, create_receive_block, create_send_block, BlockBuilder,
+    create_change_block, create_open_block, create_receive_block, create_send_block, AsyncSigner,
+    BlockBuilder,
 };
 use crate::keys::KeyPair;
 
 #[cfg(feature = "rpc")]
-use crate::error::Result;
+use crate::constants::{ACCOUNT_VERSION_EPOCH_2, WORK_THRESHOLD_RECEIVE};
+#[cfg(feature = "rpc")]
+use crate::error::{AmountError, BlockError, Error, NodeErrorKind, Result};
 use crate::types::{Account, BlockHash, Raw, StateBlock, Subtype, Work};
 #[cfg(feature = "rpc")]
 use alloc::vec::Vec;
 
 #[cfg(feature = "rpc")]
-use crate::rpc::RpcClient;
+use crate::alias::Recipient;
+#[cfg(feature = "rpc")]
+use crate::rpc::{AccountBalanceRequest, RpcClient};
+#[cfg(feature = "rpc")]
+use crate::trace;
+#[cfg(feature = "rpc")]
+use crate::wallet::{
+    split_dust, ConsolidateReport, Receivable, ReceivePolicy, SendIntent, SendIntentStatus,
+    SendIntentStore,
+};
+#[cfg(feature = "rpc")]
+use crate::work::WorkProvider;
+#[cfg(feature = "rpc")]
+use alloc::sync::Arc;
+#[cfg(feature = "rpc")]
+use futures_util::future::{join, select, Either};
+
+#[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+use crate::work::{CpuWorkGenerator, WorkSource};
+
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+use crate::error::WebSocketError;
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+use crate::websocket::{ConfirmationMessage, ParsedMessage, SubscriptionBuilder, WebSocketClient};
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+use core::time::Duration;
+
+/// Outcome of [`WalletAccount::send_many`].
+///
+/// Sends are published in order, each on the previous one, so the
+/// payments in `sent` are final whatever happened after them. Payments
+/// after `failed` were not attempted. The failed payment itself was not
+/// sent if the node rejected its block; if the outcome is unknown, e.g.
+/// after a timeout, look up `failed_block` before paying it again.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Default)]
+pub struct SendManyReport {
+    /// Hashes of the send blocks published, in payment order.
+    pub sent: Vec<BlockHash>,
+    /// Balance after the last published send.
+    pub balance: Raw,
+    /// Index of the payment that failed, if any.
+    pub failed: Option<usize>,
+    /// Hash of the signed block for the failed payment, if one was built.
+    pub failed_block: Option<BlockHash>,
+    /// Whether `failed_block` may be in the ledger despite the error.
+    pub outcome_unknown: bool,
+    /// Error that stopped the sends, if any.
+    pub error: Option<Error>,
+}
+
+#[cfg(feature = "rpc")]
+impl SendManyReport {
+    /// Check if every payment was sent.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
 
-#[cfg(feature = "work-cpu")]
-use crate::work::CpuWorkGenerator;
+/// Outcome of [`WalletAccount::receive_and_forward`].
+///
+/// The receive is final once published. If the node rejected the send,
+/// the amount stays in the account and can be sent again; if the outcome
+/// is unknown, e.g. after a timeout, look up `failed_block` first.
+#[cfg(feature = "rpc")]
+#[derive(Debug)]
+pub struct ForwardReport {
+    /// Hash of the receive (or open) block.
+    pub received: BlockHash,
+    /// Amount received and forwarded.
+    pub amount: Raw,
+    /// Hash of the send block, or `None` if the send failed.
+    pub forwarded: Option<BlockHash>,
+    /// Hash of the signed send block, if it was built but failed.
+    pub failed_block: Option<BlockHash>,
+    /// Whether `failed_block` may be in the ledger despite the error.
+    pub outcome_unknown: bool,
+    /// Error that stopped the send, if any.
+    pub error: Option<Error>,
+}
+
+#[cfg(feature = "rpc")]
+impl ForwardReport {
+    /// Check if the amount was forwarded.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
 
 /// A single account within a wallet.
 ///
@@ -23,12 +110,54 @@ use crate::work::CpuWorkGenerator;
 pub struct WalletAccount {
     keypair: KeyPair,
     index: u32,
+    allow_legacy_epoch: bool,
+    #[cfg(feature = "rpc")]
+    work_provider: Option<Arc<WorkProvider>>,
+    #[cfg(feature = "rpc")]
+    receive_policy: ReceivePolicy,
 }
 
 impl WalletAccount {
     /// Create a new wallet account.
     pub(crate) fn new(keypair: KeyPair, index: u32) -> Self {
-        WalletAccount { keypair, index }
+        WalletAccount {
+            keypair,
+            index,
+            allow_legacy_epoch: false,
+            #[cfg(feature = "rpc")]
+            work_provider: None,
+            #[cfg(feature = "rpc")]
+            receive_policy: ReceivePolicy::default(),
+        }
+    }
+
+    /// Allow RPC operations on accounts not yet upgraded to epoch v2.
+    ///
+    /// By default, operations that build blocks from `account_info` refuse
+    /// accounts reporting an `account_version` below 2, since work generated
+    /// for epoch v2 thresholds may not match what the node expects.
+    pub fn allow_legacy_epoch(mut self) -> Self {
+        self.allow_legacy_epoch = true;
+        self
+    }
+
+    /// Generate work through a [`WorkProvider`] in the RPC operations.
+    ///
+    /// Without a provider, work is generated by the node via `work_generate`.
+    /// Keep a clone of the `Arc` to inspect
+    /// [`WorkProvider::last_report`] after an operation.
+    #[cfg(feature = "rpc")]
+    pub fn with_work_provider(mut self, provider: Arc<WorkProvider>) -> Self {
+        self.work_provider = Some(provider);
+        self
+    }
+
+    /// Choose which receivable blocks [`receive_all`](Self::receive_all)
+    /// pockets.
+    #[cfg(feature = "rpc")]
+    pub fn with_receive_policy(mut self, policy: ReceivePolicy) -> Self {
+        self.receive_policy = policy;
+        self
     }
 
     /// Get the account index.
@@ -178,21 +307,23 @@ impl WalletAccount {
         builder.build().expect("all fields provided")
     }
 
-    // ==================== Local work generation methods ====================
-
-    /// Generate work locally using CPU.
-    #[cfg(feature = "work-cpu")]
-    fn generate_work(&self, hash: &BlockHash, subtype: Subtype) -> Result<Work> {
-        let generator = CpuWorkGenerator::new();
-        generator.generate_for_subtype(hash, subtype)
-    }
-
     // ==================== RPC-dependent methods ====================
 
-    /// Get the account balance.
+    /// Get the account balance, counting only confirmed blocks.
     #[cfg(feature = "rpc")]
     pub async fn balance(&self, client: &RpcClient) -> Result<crate::rpc::AccountBalanceResponse> {
-        client.account_balance(&self.address()).await
+        client
+            .account_balance_with(AccountBalanceRequest::new(&self.address()).confirmed_only())
+            .await
+    }
+
+    /// Get the ledger and confirmed state of the account.
+    ///
+    /// Use [`ConfirmedState::unconfirmed_delta`](super::ConfirmedState::unconfirmed_delta)
+    /// to see how much of the ledger balance is not final yet.
+    #[cfg(feature = "rpc")]
+    pub async fn confirmed_state(&self, client: &RpcClient) -> Result<super::ConfirmedState> {
+        super::ConfirmedState::fetch(client, &self.address()).await
     }
 
     /// Get account info.
@@ -201,14 +332,98 @@ impl WalletAccount {
         client.account_info(&self.address()).await
     }
 
-    /// Get account history.
+    /// Refuse accounts below epoch v2 unless explicitly allowed.
+    #[cfg(feature = "rpc")]
+    fn check_epoch(&self, info: &crate::rpc::AccountInfoResponse) -> Result<()> {
+        match info.epoch() {
+            Some(version) if version < ACCOUNT_VERSION_EPOCH_2 && !self.allow_legacy_epoch => {
+                Err(Error::InvalidBlock(BlockError::LegacyEpoch(version)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Generate work for a block, using `provider` if given, otherwise the
+    /// node.
+    #[cfg(feature = "rpc")]
+    async fn obtain_work(
+        &self,
+        root: &BlockHash,
+        subtype: Subtype,
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
+    ) -> Result<Work> {
+        match provider {
+            Some(provider) => Ok(provider.generate(root, subtype, client).await?.work),
+            None => Ok(client.work_generate(root).await?.work),
+        }
+    }
+
+    /// Run `publish`, generating the next block's work with `work` at the
+    /// same time.
+    ///
+    /// The next block's root is the hash of the block being published,
+    /// known before it is published, so a chain of blocks waits on work
+    /// once rather than once per block. If publishing fails, `work` is
+    /// dropped without waiting for it, which cancels a local search still
+    /// running for it.
+    #[cfg(feature = "rpc")]
+    async fn publish_then_work<T>(
+        publish: impl core::future::Future<Output = Result<T>>,
+        work: impl core::future::Future<Output = Result<Work>>,
+    ) -> Result<(T, Result<Work>)> {
+        let (publish, work) = (core::pin::pin!(publish), core::pin::pin!(work));
+        match select(publish, work).await {
+            Either::Left((published, work)) => Ok((published?, work.await)),
+            Either::Right((work, publish)) => Ok((publish.await?, work)),
+        }
+    }
+
+    /// [`publish_then_work`](Self::publish_then_work) for a block that may
+    /// be the last of its chain, with no work to generate.
+    #[cfg(feature = "rpc")]
+    async fn publish_pipelined<T>(
+        publish: impl core::future::Future<Output = Result<T>>,
+        next_work: Option<impl core::future::Future<Output = Result<Work>>>,
+    ) -> (Result<T>, Option<Result<Work>>) {
+        match next_work {
+            Some(work) => match Self::publish_then_work(publish, work).await {
+                Ok((published, work)) => (Ok(published), Some(work)),
+                Err(e) => (Err(e), None),
+            },
+            None => (publish.await, None),
+        }
+    }
+
+    /// Publish a block of a chain built here, returning its hash.
+    ///
+    /// A block the node already has counts as published. After an error
+    /// other than a node rejection the block may still have reached the
+    /// node, so it is looked up before the error is returned.
+    #[cfg(feature = "rpc")]
+    async fn publish_chained(client: &RpcClient, block: StateBlock) -> Result<BlockHash> {
+        let hash = crate::blocks::BlockHasher::hash_state_block(&block);
+        match client.process(block).await {
+            Ok(response) => Ok(response.hash),
+            Err(e) if e.node_error_kind() == Some(NodeErrorKind::OldBlock) => Ok(hash),
+            Err(e) if e.node_error_kind().is_none() => match client.block_info(&hash).await {
+                Ok(_) => Ok(hash),
+                Err(_) => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Get up to `count` confirmed history entries, newest first.
+    ///
+    /// Blocks above the confirmation height are left out.
     #[cfg(feature = "rpc")]
     pub async fn history(
         &self,
         count: u64,
         client: &RpcClient,
     ) -> Result<crate::rpc::AccountHistoryResponse> {
-        client.account_history(&self.address(), count).await
+        super::confirmed::confirmed_history(client, &self.address(), count).await
     }
 
     /// Get receivable blocks.
@@ -236,38 +451,278 @@ impl WalletAccount {
     /// This is a high-level method that:
     /// 1. Gets the current account info
     /// 2. Creates a send block
-    /// 3. Generates work (via the node)
+    /// 3. Generates work (via the work provider, or the node)
     /// 4. Submits the block
     ///
     /// # Arguments
-    /// * `destination` - Destination account
+    /// * `destination` - Destination account, or an alias resolved with [`Recipient::resolve`]
     /// * `amount` - Amount to send
     /// * `client` - RPC client
     #[cfg(feature = "rpc")]
     pub async fn send(
+        &self,
+        destination: impl Into<Recipient>,
+        amount: Raw,
+        client: &RpcClient,
+    ) -> Result<crate::rpc::ProcessResponse> {
+        let destination = destination.into().into_account()?;
+        trace::instrument!(
+            ["wallet_send", index = self.index, destination = %destination, amount = %amount],
+            self.send_with(&destination, amount, client, self.work_provider.as_deref())
+        )
+        .await
+    }
+
+    /// [`send`](Self::send) with an explicit work provider.
+    #[cfg(feature = "rpc")]
+    async fn send_with(
         &self,
         destination: &Account,
         amount: Raw,
         client: &RpcClient,
+        provider: Option<&WorkProvider>,
     ) -> Result<crate::rpc::ProcessResponse> {
+        let block = self
+            .build_send(destination, amount, client, provider)
+            .await?;
+
+        // Submit the block
+        let response = client.process(block).await?;
+        trace::log_info!(index = self.index, hash = %response.hash, "send processed");
+        Ok(response)
+    }
+
+    /// Create a signed send block on the current frontier, with work.
+    #[cfg(feature = "rpc")]
+    async fn build_send(
+        &self,
+        destination: &Account,
+        amount: Raw,
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
+    ) -> Result<StateBlock> {
         // Get account info
         let info = self.info(client).await?;
+        self.check_epoch(&info)?;
 
         // Generate work
-        let work_response = client.work_generate(&info.frontier).await?;
+        let work = self
+            .obtain_work(&info.frontier, Subtype::Send, client, provider)
+            .await?;
 
         // Create and sign the block
-        let block = self.create_send(
+        Ok(self.create_send(
             info.frontier,
             info.representative.unwrap_or_else(|| self.address()),
             info.balance,
             amount,
             destination,
-            Some(work_response.work),
+            Some(work),
+        ))
+    }
+
+    /// Pay several recipients from this account.
+    ///
+    /// Fetches `account_info` once and publishes one send block per
+    /// payment, in order, each on the hash of the one before. Work for the
+    /// next send is generated while the previous one is published. Fails
+    /// with [`Error::InsufficientBalance`] before publishing anything if
+    /// the payments exceed the balance. Any later failure is reported in
+    /// the [`SendManyReport`] rather than returned, since earlier sends
+    /// are already on the ledger; check its `outcome_unknown` before
+    /// retrying the failed payment.
+    ///
+    /// # Arguments
+    /// * `payments` - Destination accounts and amounts
+    /// * `client` - RPC client
+    #[cfg(feature = "rpc")]
+    pub async fn send_many(
+        &self,
+        payments: &[(Account, Raw)],
+        client: &RpcClient,
+    ) -> Result<SendManyReport> {
+        trace::instrument!(
+            [
+                "wallet_send_many",
+                index = self.index,
+                payments = payments.len()
+            ],
+            self.send_many_with(payments, client, self.work_provider.as_deref())
+        )
+        .await
+    }
+
+    /// [`send_many`](Self::send_many) with an explicit work provider.
+    #[cfg(feature = "rpc")]
+    async fn send_many_with(
+        &self,
+        payments: &[(Account, Raw)],
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
+    ) -> Result<SendManyReport> {
+        let info = self.info(client).await?;
+        self.check_epoch(&info)?;
+        let total = payments
+            .iter()
+            .try_fold(Raw::ZERO, |total, (_, amount)| total.checked_add(*amount))
+            .ok_or(Error::InvalidAmount(AmountError::Overflow))?;
+        if total > info.balance {
+            return Err(Error::InsufficientBalance {
+                balance: info.balance,
+                amount: total,
+            });
+        }
+
+        let representative = info.representative.unwrap_or_else(|| self.address());
+        let report = self
+            .send_chain(
+                (info.frontier, representative, info.balance),
+                payments,
+                |block| Self::publish_chained(client, block),
+                |root| async move {
+                    self.obtain_work(&root, Subtype::Send, client, provider)
+                        .await
+                },
+            )
+            .await;
+        trace::log_info!(
+            index = self.index,
+            sent = report.sent.len(),
+            balance = %report.balance,
+            "send many processed"
         );
+        Ok(report)
+    }
 
-        // Submit the block
-        client.process(block).await
+    /// Publish one send per payment on `head`, its frontier,
+    /// representative and balance, each send on the one before.
+    ///
+    /// `work` generates send work for a root, which is requested for each
+    /// block while the one before it is published.
+    #[cfg(feature = "rpc")]
+    async fn send_chain<P, W>(
+        &self,
+        head: (BlockHash, Account, Raw),
+        payments: &[(Account, Raw)],
+        publish: impl Fn(StateBlock) -> P,
+        work: impl Fn(BlockHash) -> W,
+    ) -> SendManyReport
+    where
+        P: core::future::Future<Output = Result<BlockHash>>,
+        W: core::future::Future<Output = Result<Work>>,
+    {
+        let (mut frontier, representative, balance) = head;
+        let mut report = SendManyReport {
+            balance,
+            ..SendManyReport::default()
+        };
+        let mut pending_work = None;
+        for (position, (destination, amount)) in payments.iter().enumerate() {
+            let block_work = match pending_work.take() {
+                Some(block_work) => block_work,
+                None => work(frontier).await,
+            };
+            let block_work = match block_work {
+                Ok(block_work) => block_work,
+                Err(e) => {
+                    report.failed = Some(position);
+                    report.error = Some(e);
+                    break;
+                }
+            };
+            let block = self.create_send(
+                frontier,
+                representative.clone(),
+                report.balance,
+                *amount,
+                destination,
+                Some(block_work),
+            );
+            let hash = crate::blocks::BlockHasher::hash_state_block(&block);
+            let next_work = (position + 1 < payments.len()).then(|| work(hash));
+            let (published, next_work) = Self::publish_pipelined(publish(block), next_work).await;
+            pending_work = next_work;
+            match published {
+                Ok(published) => {
+                    frontier = published;
+                    report.sent.push(published);
+                    report.balance = report.balance.checked_sub(*amount).unwrap_or(Raw::ZERO);
+                }
+                Err(e) => {
+                    report.failed = Some(position);
+                    report.failed_block = Some(hash);
+                    // Only a rejection by the node proves the block is not in
+                    // the ledger
+                    report.outcome_unknown = e.node_error_kind().is_none();
+                    report.error = Some(e);
+                    break;
+                }
+            }
+        }
+        report
+    }
+
+    /// Send Nano at most once per `id`.
+    ///
+    /// The signed block is recorded in `store` before it is published. If
+    /// `id` is already recorded, no new block is created: a published send
+    /// returns its hash, and a pending one is published again, which the
+    /// node accepts at most once. Retrying after a timeout or restart is
+    /// therefore safe.
+    ///
+    /// Fails with [`Error::SendIdConflict`] if `id` was used for a send with
+    /// a different sender, destination or amount. If the account moved on
+    /// before a pending block was accepted, publishing it fails with a fork
+    /// error and the intent stays pending; remove it from the store to send
+    /// again.
+    ///
+    /// # Arguments
+    /// * `id` - Idempotency id, e.g. a payout id
+    /// * `destination` - Destination account, or an alias resolved with [`Recipient::resolve`]
+    /// * `amount` - Amount to send
+    /// * `client` - RPC client
+    /// * `store` - Where intents are recorded
+    #[cfg(feature = "rpc")]
+    pub async fn send_idempotent(
+        &self,
+        id: &str,
+        destination: impl Into<Recipient>,
+        amount: Raw,
+        client: &RpcClient,
+        store: &dyn SendIntentStore,
+    ) -> Result<crate::rpc::ProcessResponse> {
+        let destination = destination.into().into_account()?;
+        let fingerprint = SendIntent::fingerprint(&self.address(), &destination, amount);
+        let intent = match store.get(id)? {
+            Some(intent) => intent,
+            None => {
+                let block = self
+                    .build_send(&destination, amount, client, self.work_provider.as_deref())
+                    .await?;
+                let intent = SendIntent::new(id, fingerprint, block);
+                // Another task may have recorded the id while this one built
+                // its block; the first record wins.
+                store.insert(intent.clone())?.unwrap_or(intent)
+            }
+        };
+        if intent.fingerprint != fingerprint {
+            return Err(Error::SendIdConflict(id.into()));
+        }
+
+        let hash = intent.hash();
+        if intent.status == SendIntentStatus::Pending {
+            match client.process(intent.block.clone()).await {
+                Ok(_) => {}
+                Err(e) if e.node_error_kind() == Some(NodeErrorKind::OldBlock) => {}
+                Err(e) => return Err(e),
+            }
+            store.update(SendIntent {
+                status: SendIntentStatus::Published,
+                ..intent
+            })?;
+            trace::log_info!(index = self.index, hash = %hash, id = id, "idempotent send processed");
+        }
+        Ok(crate::rpc::ProcessResponse { hash })
     }
 
     /// Change representative.
@@ -280,23 +735,84 @@ impl WalletAccount {
         &self,
         new_representative: &Account,
         client: &RpcClient,
+    ) -> Result<crate::rpc::ProcessResponse> {
+        trace::instrument!(
+            ["wallet_change", index = self.index, representative = %new_representative],
+            self.change_representative_with(
+                new_representative,
+                client,
+                self.work_provider.as_deref()
+            )
+        )
+        .await
+    }
+
+    /// Move to a healthier representative if the current one is offline,
+    /// outdated or holds more than
+    /// [`DEFAULT_MAX_WEIGHT_SHARE`](crate::reps::DEFAULT_MAX_WEIGHT_SHARE)
+    /// of the online weight.
+    ///
+    /// Returns `None` if the current representative is fine or no better
+    /// one was found.
+    ///
+    /// Node versions and uptime come from `directory`; without one,
+    /// outdated representatives are not detected and only offline or
+    /// overweight ones are replaced.
+    ///
+    /// # Arguments
+    /// * `client` - RPC client
+    /// * `directory` - Representative metadata, such as from
+    ///   [`MyNanoNinjaProvider`](crate::reps::MyNanoNinjaProvider)
+    #[cfg(feature = "rpc")]
+    pub async fn rebalance_representative(
+        &self,
+        client: &RpcClient,
+        directory: Option<&crate::reps::RepDirectory>,
+    ) -> Result<Option<crate::rpc::ProcessResponse>> {
+        let report = crate::reps::RepHealthReport::fetch(client, directory).await?;
+        let info = self.info(client).await?;
+        let current = info.representative.unwrap_or_else(|| self.address());
+        if report.is_acceptable(&current) {
+            return Ok(None);
+        }
+        match report.suggest() {
+            Some(rep) => self
+                .change_representative(&rep.account, client)
+                .await
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// [`change_representative`](Self::change_representative) with an explicit work provider.
+    #[cfg(feature = "rpc")]
+    async fn change_representative_with(
+        &self,
+        new_representative: &Account,
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
     ) -> Result<crate::rpc::ProcessResponse> {
         // Get account info
         let info = self.info(client).await?;
+        self.check_epoch(&info)?;
 
         // Generate work
-        let work_response = client.work_generate(&info.frontier).await?;
+        let work = self
+            .obtain_work(&info.frontier, Subtype::Change, client, provider)
+            .await?;
 
         // Create and sign the block
         let block = self.create_change(
             info.frontier,
             new_representative.clone(),
             info.balance,
-            Some(work_response.work),
+            Some(work),
         );
 
         // Submit the block
-        client.process(block).await
+        let response = client.process(block).await?;
+        trace::log_info!(index = self.index, hash = %response.hash, "change processed");
+        Ok(response)
     }
 
     /// Receive a pending block.
@@ -311,41 +827,214 @@ impl WalletAccount {
         source_hash: &BlockHash,
         amount: Raw,
         client: &RpcClient,
+    ) -> Result<crate::rpc::ProcessResponse> {
+        trace::instrument!(
+            ["wallet_receive", index = self.index, source = %source_hash, amount = %amount],
+            self.receive_with(source_hash, amount, client, self.work_provider.as_deref())
+        )
+        .await
+    }
+
+    /// [`receive`](Self::receive) with an explicit work provider.
+    #[cfg(feature = "rpc")]
+    async fn receive_with(
+        &self,
+        source_hash: &BlockHash,
+        amount: Raw,
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
     ) -> Result<crate::rpc::ProcessResponse> {
         // Try to get account info (may fail if account doesn't exist yet)
         let info_result = self.info(client).await;
 
         match info_result {
             Ok(info) => {
+                self.check_epoch(&info)?;
                 // Existing account - create receive block
-                let work_response = client.work_generate(&info.frontier).await?;
+                let work = self
+                    .obtain_work(&info.frontier, Subtype::Receive, client, provider)
+                    .await?;
                 let block = self.create_receive(
                     info.frontier,
                     info.representative.unwrap_or_else(|| self.address()),
                     info.balance,
                     amount,
                     source_hash,
-                    Some(work_response.work),
+                    Some(work),
                 );
-                client.process(block).await
+                let response = client.process(block).await?;
+                trace::log_info!(index = self.index, hash = %response.hash, "receive processed");
+                Ok(response)
             }
             Err(_) => {
                 // New account - create open block
                 // For open blocks, work is computed on the account's public key
                 let pub_key_hash = BlockHash::from_bytes(*self.keypair.public_key().as_bytes());
-                let work_response = client.work_generate(&pub_key_hash).await?;
-                let block = self.create_open(
-                    self.address(),
+                let work = self
+                    .obtain_work(&pub_key_hash, Subtype::Open, client, provider)
+                    .await?;
+                let block = self.create_open(self.address(), amount, source_hash, Some(work));
+                let response = client.process(block).await?;
+                trace::log_info!(index = self.index, hash = %response.hash, "open processed");
+                Ok(response)
+            }
+        }
+    }
+
+    /// Receive a pending block and send its amount on to `destination`.
+    ///
+    /// Fetches the source block and `account_info` together, builds the
+    /// receive (or open) block, then generates work for the send on the
+    /// receive's hash while the receive is being published, so the two
+    /// blocks cost about one round of work. Fails without publishing
+    /// anything if the receive can't be built or published; a failure of
+    /// the send is reported in the [`ForwardReport`].
+    ///
+    /// # Arguments
+    /// * `source_hash` - Hash of the send block to receive
+    /// * `destination` - Destination account, or an alias resolved with [`Recipient::resolve`]
+    /// * `client` - RPC client
+    #[cfg(feature = "rpc")]
+    pub async fn receive_and_forward(
+        &self,
+        source_hash: &BlockHash,
+        destination: impl Into<Recipient>,
+        client: &RpcClient,
+    ) -> Result<ForwardReport> {
+        let destination = destination.into().into_account()?;
+        trace::instrument!(
+            [
+                "wallet_receive_and_forward",
+                index = self.index,
+                source = %source_hash,
+                destination = %destination
+            ],
+            self.receive_and_forward_with(
+                source_hash,
+                &destination,
+                client,
+                self.work_provider.as_deref(),
+            )
+        )
+        .await
+    }
+
+    /// [`receive_and_forward`](Self::receive_and_forward) with an explicit
+    /// work provider.
+    #[cfg(feature = "rpc")]
+    async fn receive_and_forward_with(
+        &self,
+        source_hash: &BlockHash,
+        destination: &Account,
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
+    ) -> Result<ForwardReport> {
+        let (source, info) = join(client.block_info(source_hash), self.info(client)).await;
+        let amount = source?.amount;
+        let receive = match info {
+            Ok(info) => {
+                self.check_epoch(&info)?;
+                let work = self
+                    .receive_work(&info.frontier, Subtype::Receive, client, provider)
+                    .await?;
+                self.create_receive(
+                    info.frontier,
+                    info.representative.unwrap_or_else(|| self.address()),
+                    info.balance,
                     amount,
                     source_hash,
-                    Some(work_response.work),
-                );
-                client.process(block).await
+                    Some(work),
+                )
+            }
+            Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => {
+                let root = BlockHash::from_bytes(*self.keypair.public_key().as_bytes());
+                let work = self
+                    .receive_work(&root, Subtype::Open, client, provider)
+                    .await?;
+                self.create_open(self.address(), amount, source_hash, Some(work))
+            }
+            Err(e) => return Err(e),
+        };
+
+        self.forward_chain(
+            receive,
+            amount,
+            destination,
+            |block| Self::publish_chained(client, block),
+            |root| async move {
+                self.obtain_work(&root, Subtype::Send, client, provider)
+                    .await
+            },
+        )
+        .await
+    }
+
+    /// Publish `receive`, then a send of `amount` to `destination` on it.
+    ///
+    /// The send builds on the receive, whose hash is known before it is
+    /// published, so `work` generates the send's work at the same time.
+    #[cfg(feature = "rpc")]
+    async fn forward_chain<P, W>(
+        &self,
+        receive: StateBlock,
+        amount: Raw,
+        destination: &Account,
+        publish: impl Fn(StateBlock) -> P,
+        work: impl FnOnce(BlockHash) -> W,
+    ) -> Result<ForwardReport>
+    where
+        P: core::future::Future<Output = Result<BlockHash>>,
+        W: core::future::Future<Output = Result<Work>>,
+    {
+        let frontier = crate::blocks::BlockHasher::hash_state_block(&receive);
+        let (representative, balance) = (receive.representative.clone(), receive.balance);
+        let (received, send_work) =
+            Self::publish_then_work(publish(receive), work(frontier)).await?;
+        trace::log_info!(index = self.index, hash = %received, "receive processed");
+
+        let mut report = ForwardReport {
+            received,
+            amount,
+            forwarded: None,
+            failed_block: None,
+            outcome_unknown: false,
+            error: None,
+        };
+        let send = match send_work {
+            Ok(work) => self.create_send(
+                received,
+                representative,
+                balance,
+                amount,
+                destination,
+                Some(work),
+            ),
+            Err(e) => {
+                report.error = Some(e);
+                return Ok(report);
+            }
+        };
+        let hash = crate::blocks::BlockHasher::hash_state_block(&send);
+        match publish(send).await {
+            Ok(forwarded) => {
+                trace::log_info!(index = self.index, hash = %forwarded, "forward processed");
+                report.forwarded = Some(forwarded);
+            }
+            Err(e) => {
+                report.failed_block = Some(hash);
+                report.outcome_unknown = e.node_error_kind().is_none();
+                report.error = Some(e);
             }
         }
+        Ok(report)
     }
 
-    /// Receive all pending blocks.
+    /// Receive pending blocks selected by the account's
+    /// [`ReceivePolicy`].
+    ///
+    /// Fetches `account_info` once and builds each receive on the previous
+    /// one. Work for the next block is generated while the previous one is
+    /// published, so a chain of receives waits on work about once.
     ///
     /// Returns the list of processed block hashes.
     ///
@@ -353,71 +1042,379 @@ impl WalletAccount {
     /// * `client` - RPC client
     #[cfg(feature = "rpc")]
     pub async fn receive_all(&self, client: &RpcClient) -> Result<Vec<BlockHash>> {
-        let mut received = Vec::new();
-
-        // Get receivable blocks
-        let receivable = self.receivable(100, client).await?;
-        let account_key = self.address().to_string();
-
-        if let Some(blocks) = receivable.blocks.get(&account_key) {
-            // Parse the receivable blocks
-            if let Some(obj) = blocks.as_object() {
-                for (hash_str, value) in obj {
-                    let source_hash = BlockHash::from_hex(hash_str)?;
-                    let amount = if let Some(amount_str) = value.as_str() {
-                        amount_str.parse::<Raw>()?
-                    } else if let Some(obj) = value.as_object() {
-                        if let Some(amount_str) = obj.get("amount").and_then(|v| v.as_str()) {
-                            amount_str.parse::<Raw>()?
-                        } else {
-                            continue;
-                        }
-                    } else {
+        trace::instrument!(
+            ["wallet_receive_all", index = self.index],
+            self.receive_all_with(client, self.work_provider.as_deref())
+        )
+        .await
+    }
+
+    /// List what [`receive_all`](Self::receive_all) would receive, in
+    /// order, without generating work or publishing anything.
+    #[cfg(feature = "rpc")]
+    pub async fn receive_all_dry_run(&self, client: &RpcClient) -> Result<Vec<Receivable>> {
+        let policy = &self.receive_policy;
+        let account = self.address();
+        let mut request = crate::rpc::AccountsReceivableRequest::new(
+            core::slice::from_ref(&account),
+            policy.fetch_count,
+        );
+        if !policy.min_amount.is_zero() {
+            request = request.with_threshold(&policy.min_amount.to_string());
+        }
+        if policy.largest_first {
+            request = request.sorted();
+        }
+        let candidates = self.receivable_candidates(client, request).await?;
+        Ok(policy.select(candidates))
+    }
+
+    /// [`receive_all`](Self::receive_all) with an explicit work provider.
+    #[cfg(feature = "rpc")]
+    async fn receive_all_with(
+        &self,
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
+    ) -> Result<Vec<BlockHash>> {
+        let (receivables, info) = join(self.receive_all_dry_run(client), self.info(client)).await;
+        let receivables = receivables?;
+        if receivables.is_empty() {
+            return Ok(Vec::new());
+        }
+        let report = self
+            .receive_chain(
+                self.chain_head(info)?,
+                &receivables,
+                |block| Self::publish_chained(client, block),
+                |root, subtype| async move { self.obtain_work(&root, subtype, client, provider).await },
+            )
+            .await;
+        match report.error {
+            Some(e) => Err(e),
+            None => Ok(report.received),
+        }
+    }
+
+    /// Fetch the receivable blocks of this account selected by `request`.
+    #[cfg(feature = "rpc")]
+    async fn receivable_candidates(
+        &self,
+        client: &RpcClient,
+        request: crate::rpc::AccountsReceivableRequest,
+    ) -> Result<Vec<Receivable>> {
+        let account = self.address();
+        let response = client.accounts_receivable_with(request).await?;
+
+        let mut candidates = Vec::new();
+        let Some(blocks) = response.blocks.get(account.as_str()) else {
+            return Ok(candidates);
+        };
+        if let Some(obj) = blocks.as_object() {
+            for (hash_str, value) in obj {
+                let hash = BlockHash::from_hex(hash_str)?;
+                let (amount, source) = if let Some(amount_str) = value.as_str() {
+                    (amount_str.parse::<Raw>()?, None)
+                } else if let Some(obj) = value.as_object() {
+                    let Some(amount_str) = obj.get("amount").and_then(|v| v.as_str()) else {
                         continue;
                     };
+                    let source = obj
+                        .get("source")
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| s.parse().ok());
+                    (amount_str.parse::<Raw>()?, source)
+                } else {
+                    continue;
+                };
+                candidates.push(Receivable {
+                    account: account.clone(),
+                    hash,
+                    amount,
+                    source,
+                });
+            }
+        } else if let Some(arr) = blocks.as_array() {
+            // Simple list of hashes (need to get amounts separately)
+            for hash_val in arr {
+                if let Some(hash_str) = hash_val.as_str() {
+                    let hash = BlockHash::from_hex(hash_str)?;
+                    let block_info = client.block_info(&hash).await?;
+                    candidates.push(Receivable {
+                        account: account.clone(),
+                        hash,
+                        amount: block_info.amount,
+                        source: Some(block_info.block_account),
+                    });
+                }
+            }
+        }
+        Ok(candidates)
+    }
+
+    /// Receive dust below `threshold` in one batch.
+    ///
+    /// Fetches `account_info` once and builds each receive on the previous
+    /// one, with work at the receive difficulty, which is far cheaper than
+    /// send work, generated while the previous block is published.
+    /// Receives at most `max_blocks` blocks; the policy's `fetch_count` and
+    /// `skip_burn_source` apply, its minimum amount does not. A failure
+    /// once receiving has started is reported in the [`ConsolidateReport`]
+    /// rather than returned, since earlier receives are already on the
+    /// ledger.
+    ///
+    /// # Arguments
+    /// * `threshold` - Amount below which a receivable is dust
+    /// * `max_blocks` - Most receive blocks to publish
+    /// * `client` - RPC client
+    #[cfg(feature = "rpc")]
+    pub async fn consolidate(
+        &self,
+        threshold: Raw,
+        max_blocks: usize,
+        client: &RpcClient,
+    ) -> Result<ConsolidateReport> {
+        trace::instrument!(
+            [
+                "wallet_consolidate",
+                index = self.index,
+                max_blocks = max_blocks
+            ],
+            self.consolidate_with(threshold, max_blocks, client, self.work_provider.as_deref())
+        )
+        .await
+    }
+
+    /// [`consolidate`](Self::consolidate) with an explicit work provider.
+    #[cfg(feature = "rpc")]
+    async fn consolidate_with(
+        &self,
+        threshold: Raw,
+        max_blocks: usize,
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
+    ) -> Result<ConsolidateReport> {
+        let account = self.address();
+        let request = crate::rpc::AccountsReceivableRequest::new(
+            core::slice::from_ref(&account),
+            self.receive_policy.fetch_count,
+        );
+        let (_, mut dust) = split_dust(
+            self.receivable_candidates(client, request).await?,
+            threshold,
+        );
+        if self.receive_policy.skip_burn_source {
+            dust.retain(|r| !r.source.as_ref().is_some_and(Account::is_burn));
+        }
+
+        let remaining = dust.len().saturating_sub(max_blocks);
+        dust.truncate(max_blocks);
+        if dust.is_empty() {
+            return Ok(ConsolidateReport {
+                remaining,
+                ..ConsolidateReport::default()
+            });
+        }
 
-                    let response = self.receive(&source_hash, amount, client).await?;
-                    received.push(response.hash);
+        let head = self.chain_head(self.info(client).await)?;
+        let mut report = self
+            .receive_chain(
+                head,
+                &dust,
+                |block| Self::publish_chained(client, block),
+                |root, subtype| async move {
+                    self.receive_work(&root, subtype, client, provider).await
+                },
+            )
+            .await;
+        report.remaining = remaining;
+        trace::log_info!(
+            index = self.index,
+            blocks = report.received.len(),
+            amount = %report.amount,
+            "dust consolidated"
+        );
+        Ok(report)
+    }
+
+    /// Frontier, representative and balance from `account_info`, or
+    /// `None` if the account is not opened yet.
+    #[cfg(feature = "rpc")]
+    fn chain_head(
+        &self,
+        info: Result<crate::rpc::AccountInfoResponse>,
+    ) -> Result<Option<(BlockHash, Account, Raw)>> {
+        match info {
+            Ok(info) => {
+                self.check_epoch(&info)?;
+                let representative = info.representative.unwrap_or_else(|| self.address());
+                Ok(Some((info.frontier, representative, info.balance)))
+            }
+            Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Receive `receivables` in order, each block on the one before.
+    ///
+    /// Builds on `head`, its frontier, representative and balance, or
+    /// opens the account if it is `None`. `work` generates work for a root
+    /// and subtype, which is requested for each block while the one before
+    /// it is published. Stops at the first error, which is recorded in the
+    /// report next to the receives published before it.
+    #[cfg(feature = "rpc")]
+    async fn receive_chain<P, W>(
+        &self,
+        mut head: Option<(BlockHash, Account, Raw)>,
+        receivables: &[Receivable],
+        publish: impl Fn(StateBlock) -> P,
+        work: impl Fn(BlockHash, Subtype) -> W,
+    ) -> ConsolidateReport
+    where
+        P: core::future::Future<Output = Result<BlockHash>>,
+        W: core::future::Future<Output = Result<Work>>,
+    {
+        let mut report = ConsolidateReport {
+            received: Vec::with_capacity(receivables.len()),
+            ..ConsolidateReport::default()
+        };
+        let mut pending_work = None;
+        for (position, receivable) in receivables.iter().enumerate() {
+            let (root, subtype) = match &head {
+                Some((frontier, ..)) => (*frontier, Subtype::Receive),
+                None => (
+                    BlockHash::from_bytes(*self.keypair.public_key().as_bytes()),
+                    Subtype::Open,
+                ),
+            };
+            let block_work = match pending_work.take() {
+                Some(block_work) => block_work,
+                None => work(root, subtype).await,
+            };
+            let block_work = match block_work {
+                Ok(block_work) => block_work,
+                Err(e) => {
+                    report.error = Some(e);
+                    break;
                 }
-            } else if let Some(arr) = blocks.as_array() {
-                // Simple list of hashes (need to get amounts separately)
-                for hash_val in arr {
-                    if let Some(hash_str) = hash_val.as_str() {
-                        let source_hash = BlockHash::from_hex(hash_str)?;
-                        // Get block info to find the amount
-                        let block_info = client.block_info(&source_hash).await?;
-                        let response = self
-                            .receive(&source_hash, block_info.amount, client)
-                            .await?;
-                        received.push(response.hash);
-                    }
+            };
+            let block = match head {
+                Some((frontier, representative, balance)) => self.create_receive(
+                    frontier,
+                    representative,
+                    balance,
+                    receivable.amount,
+                    &receivable.hash,
+                    Some(block_work),
+                ),
+                None => self.create_open(
+                    self.address(),
+                    receivable.amount,
+                    &receivable.hash,
+                    Some(block_work),
+                ),
+            };
+            let (representative, balance) = (block.representative.clone(), block.balance);
+            let hash = crate::blocks::BlockHasher::hash_state_block(&block);
+            let next_work =
+                (position + 1 < receivables.len()).then(|| work(hash, Subtype::Receive));
+            let (published, next_work) = Self::publish_pipelined(publish(block), next_work).await;
+            pending_work = next_work;
+            match published {
+                Ok(published) => {
+                    trace::log_info!(index = self.index, hash = %published, "receive processed");
+                    head = Some((published, representative, balance));
+                    report.received.push(published);
+                    report.amount += receivable.amount;
+                }
+                Err(e) => {
+                    report.failed_block = Some(hash);
+                    // Only a rejection by the node proves the block is not in
+                    // the ledger
+                    report.outcome_unknown = e.node_error_kind().is_none();
+                    report.error = Some(e);
+                    break;
                 }
             }
         }
+        report
+    }
 
-        Ok(received)
+    /// Generate receive-difficulty work, using `provider` if given,
+    /// otherwise the node.
+    #[cfg(feature = "rpc")]
+    async fn receive_work(
+        &self,
+        root: &BlockHash,
+        subtype: Subtype,
+        client: &RpcClient,
+        provider: Option<&WorkProvider>,
+    ) -> Result<Work> {
+        match provider {
+            Some(provider) => Ok(provider.generate(root, subtype, client).await?.work),
+            None => {
+                // The node defaults to send difficulty
+                let difficulty = alloc::format!("{:016x}", WORK_THRESHOLD_RECEIVE);
+                Ok(client
+                    .work_generate_with_difficulty(root, &difficulty)
+                    .await?
+                    .work)
+            }
+        }
     }
 
     /// Send and change representative in one block.
     ///
     /// # Arguments
-    /// * `destination` - Destination account
+    /// * `destination` - Destination account, or an alias resolved with [`Recipient::resolve`]
     /// * `amount` - Amount to send
     /// * `new_representative` - New representative account
     /// * `client` - RPC client
     #[cfg(feature = "rpc")]
     pub async fn send_and_change(
+        &self,
+        destination: impl Into<Recipient>,
+        amount: Raw,
+        new_representative: &Account,
+        client: &RpcClient,
+    ) -> Result<crate::rpc::ProcessResponse> {
+        let destination = destination.into().into_account()?;
+        trace::instrument!(
+            [
+                "wallet_send_and_change",
+                index = self.index,
+                destination = %destination,
+                amount = %amount,
+                representative = %new_representative
+            ],
+            self.send_and_change_with(
+                &destination,
+                amount,
+                new_representative,
+                client,
+                self.work_provider.as_deref(),
+            )
+        )
+        .await
+    }
+
+    /// [`send_and_change`](Self::send_and_change) with an explicit work provider.
+    #[cfg(feature = "rpc")]
+    async fn send_and_change_with(
         &self,
         destination: &Account,
         amount: Raw,
         new_representative: &Account,
         client: &RpcClient,
+        provider: Option<&WorkProvider>,
     ) -> Result<crate::rpc::ProcessResponse> {
         // Get account info
         let info = self.info(client).await?;
+        self.check_epoch(&info)?;
 
         // Generate work
-        let work_response = client.work_generate(&info.frontier).await?;
+        let work = self
+            .obtain_work(&info.frontier, Subtype::Send, client, provider)
+            .await?;
 
         // Create and sign the block
         let block = self.create_send_and_change(
@@ -426,140 +1423,236 @@ impl WalletAccount {
             info.balance,
             amount,
             destination,
-            Some(work_response.work),
+            Some(work),
         );
 
         // Submit the block
-        client.process(block).await
+        let response = client.process(block).await?;
+        trace::log_info!(index = self.index, hash = %response.hash, "send and change processed");
+        Ok(response)
+    }
+
+    // ==================== RPC + WebSocket methods ====================
+
+    /// Send Nano and wait for the network to confirm the block.
+    ///
+    /// Subscribes `ws_client` to confirmations for this account, submits the
+    /// send via [`send`](Self::send), then waits for the confirmation of the
+    /// resulting block hash. Block contents and election info are requested
+    /// with the subscription, so the result can be turned into a
+    /// [`Receipt`](crate::receipt::Receipt) via `Receipt::from_confirmation`.
+    ///
+    /// The node allows one confirmation subscription per connection, so the
+    /// caller's subscription, as reported by
+    /// [`WebSocketClient::confirmation_subscription`], is replaced while
+    /// waiting and restored afterwards, along with the confirmation filter.
+    /// Other messages received while waiting are dropped.
+    ///
+    /// # Arguments
+    /// * `destination` - Destination account, or an alias resolved with [`Recipient::resolve`]
+    /// * `amount` - Amount to send
+    /// * `client` - RPC client
+    /// * `ws_client` - Connected WebSocket client
+    /// * `timeout` - Maximum time to wait for confirmation
+    #[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+    pub async fn send_confirmed(
+        &self,
+        destination: impl Into<Recipient>,
+        amount: Raw,
+        client: &RpcClient,
+        ws_client: &mut WebSocketClient,
+        timeout: Duration,
+    ) -> Result<ConfirmationMessage> {
+        let previous = ws_client.confirmation_subscription().cloned();
+        let filter = ws_client.confirmation_filter().clone();
+
+        // Subscribe before submitting so the confirmation can't be missed
+        ws_client
+            .subscribe(
+                SubscriptionBuilder::new()
+                    .confirmations()
+                    .account(&self.address())
+                    .include_block()
+                    .include_election_info(),
+            )
+            .await?;
+
+        let confirmed = async {
+            let hash = self.send(destination, amount, client).await?.hash;
+            let wait = async {
+                loop {
+                    match ws_client.receive().await? {
+                        Some(ParsedMessage::Confirmation(conf)) if conf.hash == hash => {
+                            return Ok(conf);
+                        }
+                        Some(_) => continue,
+                        None => return Err(Error::WebSocket(WebSocketError::ConnectionClosed)),
+                    }
+                }
+            };
+            tokio::time::timeout(timeout, wait)
+                .await
+                .map_err(|_| Error::WebSocket(WebSocketError::Timeout))?
+        }
+        .await;
+
+        let restored = match previous {
+            Some(previous) => ws_client.subscribe(previous).await,
+            None => {
+                ws_client
+                    .unsubscribe(SubscriptionBuilder::new().confirmations())
+                    .await
+            }
+        };
+        ws_client.set_confirmation_filter(filter);
+        // The send result matters more; a broken connection shows up on the
+        // caller's next receive
+        if let Err(_error) = restored {
+            trace::log_warn!(error = %_error, "failed to restore websocket subscription");
+        }
+        confirmed
+    }
+
+    /// Stream the account history, then follow new confirmations.
+    ///
+    /// Yields existing history newest first, fetching further pages from RPC
+    /// as needed, and then continues with blocks confirmed on the WebSocket.
+    /// Blocks already yielded from history are not repeated by the live tail.
+    ///
+    /// # Arguments
+    /// * `page_size` - Number of history entries fetched per RPC request
+    /// * `client` - RPC client
+    /// * `ws_client` - Connected WebSocket client
+    #[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+    pub async fn history_stream<'a>(
+        &self,
+        page_size: u64,
+        client: &'a RpcClient,
+        ws_client: &'a mut WebSocketClient,
+    ) -> Result<super::HistoryStream<'a>> {
+        super::HistoryStream::open(self.address(), client, ws_client, page_size).await
     }
 
     // ==================== Local work generation variants ====================
+    //
+    // Deprecated shims kept so downstream code can migrate gradually. They
+    // forward to the provider-based methods with a CPU-only provider.
+
+    /// CPU-only work provider used by the deprecated `*_local` methods.
+    ///
+    /// These methods generated work on the CPU until it was found, so the
+    /// provider's per-source timeout is lifted to keep that behaviour.
+    #[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+    fn local_work_provider() -> WorkProvider {
+        WorkProvider::new()
+            .with_cpu(CpuWorkGenerator::new())
+            .with_timeout(WorkSource::Cpu, core::time::Duration::MAX)
+    }
 
     /// Send Nano using local CPU work generation.
     #[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+    #[deprecated(
+        since = "0.1.1",
+        note = "set a CPU work provider with `with_work_provider` and use `send`"
+    )]
     pub async fn send_local(
         &self,
-        destination: &Account,
+        destination: impl Into<Recipient>,
         amount: Raw,
         client: &RpcClient,
     ) -> Result<crate::rpc::ProcessResponse> {
-        let info = self.info(client).await?;
-        let work = self.generate_work(&info.frontier, Subtype::Send)?;
-        let block = self.create_send(
-            info.frontier,
-            info.representative.unwrap_or_else(|| self.address()),
-            info.balance,
+        self.send_with(
+            &destination.into().into_account()?,
             amount,
-            destination,
-            Some(work),
-        );
-        client.process(block).await
+            client,
+            Some(&Self::local_work_provider()),
+        )
+        .await
     }
 
     /// Receive a pending block using local CPU work generation.
     #[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+    #[deprecated(
+        since = "0.1.1",
+        note = "set a CPU work provider with `with_work_provider` and use `receive`"
+    )]
     pub async fn receive_local(
         &self,
         source_hash: &BlockHash,
         amount: Raw,
         client: &RpcClient,
     ) -> Result<crate::rpc::ProcessResponse> {
-        let info_result = self.info(client).await;
-
-        match info_result {
-            Ok(info) => {
-                let work = self.generate_work(&info.frontier, Subtype::Receive)?;
-                let block = self.create_receive(
-                    info.frontier,
-                    info.representative.unwrap_or_else(|| self.address()),
-                    info.balance,
-                    amount,
-                    source_hash,
-                    Some(work),
-                );
-                client.process(block).await
-            }
-            Err(_) => {
-                // For open blocks, work is on public key
-                let pub_key_hash = BlockHash::from_bytes(*self.keypair.public_key().as_bytes());
-                let work = self.generate_work(&pub_key_hash, Subtype::Open)?;
-                let block = self.create_open(self.address(), amount, source_hash, Some(work));
-                client.process(block).await
-            }
-        }
+        self.receive_with(
+            source_hash,
+            amount,
+            client,
+            Some(&Self::local_work_provider()),
+        )
+        .await
     }
 
-    /// Receive all pending blocks using local CPU work generation.
+    /// Receive pending blocks selected by the account's
+    /// [`ReceivePolicy`] using local CPU work generation.
     #[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+    #[deprecated(
+        since = "0.1.1",
+        note = "set a CPU work provider with `with_work_provider` and use `receive_all`"
+    )]
     pub async fn receive_all_local(&self, client: &RpcClient) -> Result<Vec<BlockHash>> {
-        let mut received = Vec::new();
-
-        let receivable = self.receivable(100, client).await?;
-        let account_key = self.address().to_string();
-
-        if let Some(blocks) = receivable.blocks.get(&account_key) {
-            if let Some(obj) = blocks.as_object() {
-                for (hash_str, value) in obj {
-                    let source_hash = BlockHash::from_hex(hash_str)?;
-                    let amount = if let Some(amount_str) = value.as_str() {
-                        amount_str.parse::<Raw>()?
-                    } else if let Some(obj) = value.as_object() {
-                        if let Some(amount_str) = obj.get("amount").and_then(|v| v.as_str()) {
-                            amount_str.parse::<Raw>()?
-                        } else {
-                            continue;
-                        }
-                    } else {
-                        continue;
-                    };
-
-                    let response = self.receive_local(&source_hash, amount, client).await?;
-                    received.push(response.hash);
-                }
-            }
-        }
-
-        Ok(received)
+        self.receive_all_with(client, Some(&Self::local_work_provider()))
+            .await
     }
 
     /// Change representative using local CPU work generation.
     #[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+    #[deprecated(
+        since = "0.1.1",
+        note = "set a CPU work provider with `with_work_provider` and use `change_representative`"
+    )]
     pub async fn change_representative_local(
         &self,
         new_representative: &Account,
         client: &RpcClient,
     ) -> Result<crate::rpc::ProcessResponse> {
-        let info = self.info(client).await?;
-        let work = self.generate_work(&info.frontier, Subtype::Change)?;
-        let block = self.create_change(
-            info.frontier,
-            new_representative.clone(),
-            info.balance,
-            Some(work),
-        );
-        client.process(block).await
+        self.change_representative_with(
+            new_representative,
+            client,
+            Some(&Self::local_work_provider()),
+        )
+        .await
     }
 
     /// Send and change representative using local CPU work generation.
     #[cfg(all(feature = "rpc", feature = "work-cpu", not(target_arch = "wasm32")))]
+    #[deprecated(
+        since = "0.1.1",
+        note = "set a CPU work provider with `with_work_provider` and use `send_and_change`"
+    )]
     pub async fn send_and_change_local(
         &self,
-        destination: &Account,
+        destination: impl Into<Recipient>,
         amount: Raw,
         new_representative: &Account,
         client: &RpcClient,
     ) -> Result<crate::rpc::ProcessResponse> {
-        let info = self.info(client).await?;
-        let work = self.generate_work(&info.frontier, Subtype::Send)?;
-        let block = self.create_send_and_change(
-            info.frontier,
-            new_representative.clone(),
-            info.balance,
+        self.send_and_change_with(
+            &destination.into().into_account()?,
             amount,
-            destination,
-            Some(work),
-        );
-        client.process(block).await
+            new_representative,
+            client,
+            Some(&Self::local_work_provider()),
+        )
+        .await
+    }
+}
+
+impl AsyncSigner for WalletAccount {
+    fn account(&self) -> Account {
+        self.address()
+    }
+
+    async fn sign_hash(&self, hash: &BlockHash) -> crate::error::Result<crate::types::Signature> {
+        Ok(self.keypair.sign(hash))
     }
 }
 
@@ -772,4 +1865,396 @@ mod tests {
             "Send+change block signature invalid"
         );
     }
+
+    #[cfg(feature = "rpc")]
+    #[test]
+    fn test_check_epoch() {
+        let info: crate::rpc::AccountInfoResponse = serde_json::from_str(
+            r#"{
+                "frontier": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "open_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "representative_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "balance": "1000",
+                "modified_timestamp": "1501793775",
+                "block_count": "33",
+                "account_version": "1"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(info.epoch(), Some(1));
+
+        let account = test_account();
+        assert!(matches!(
+            account.check_epoch(&info),
+            Err(Error::InvalidBlock(BlockError::LegacyEpoch(1)))
+        ));
+        assert!(account.allow_legacy_epoch().check_epoch(&info).is_ok());
+
+        let upgraded = crate::rpc::AccountInfoResponse {
+            account_version: Some("2".into()),
+            ..info
+        };
+        assert!(test_account().check_epoch(&upgraded).is_ok());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_send_idempotent_reuses_intent() {
+        use crate::wallet::MemorySendIntentStore;
+
+        let account = test_account();
+        let destination = Account::from_public_key(&PublicKey::ZERO);
+        let amount = Raw::new(10);
+        let block = account.create_send(
+            BlockHash::from_bytes([1u8; 32]),
+            account.address(),
+            Raw::new(100),
+            amount,
+            &destination,
+            None,
+        );
+        let fingerprint = SendIntent::fingerprint(&account.address(), &destination, amount);
+        let mut intent = SendIntent::new("payout-1", fingerprint, block);
+        intent.status = SendIntentStatus::Published;
+        let store = MemorySendIntentStore::new();
+        assert!(store.insert(intent.clone()).unwrap().is_none());
+        assert_eq!(store.insert(intent.clone()).unwrap(), Some(intent.clone()));
+
+        // Nothing is sent: the client points nowhere.
+        let client = RpcClient::new("http://127.0.0.1:9");
+        let response = account
+            .send_idempotent("payout-1", &destination, amount, &client, &store)
+            .await
+            .unwrap();
+        assert_eq!(response.hash, intent.hash());
+
+        let err = account
+            .send_idempotent("payout-1", &destination, Raw::new(11), &client, &store)
+            .await
+            .unwrap_err();
+        assert_eq!(err, Error::SendIdConflict("payout-1".into()));
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_send_chain() {
+        use core::cell::RefCell;
+
+        let account = test_account();
+        let frontier = BlockHash::from_bytes([1u8; 32]);
+        let payments = [
+            (Account::from_public_key(&PublicKey::ZERO), Raw::new(10)),
+            (account.address(), Raw::new(25)),
+            (Account::from_public_key(&PublicKey::ZERO), Raw::new(5)),
+        ];
+        let published = RefCell::new(Vec::new());
+        let roots = RefCell::new(Vec::new());
+        let publish = |block: StateBlock| {
+            let hash = crate::blocks::BlockHasher::hash_state_block(&block);
+            published.borrow_mut().push(block);
+            core::future::ready(Ok(hash))
+        };
+        let work = |root| {
+            roots.borrow_mut().push(root);
+            core::future::ready(Ok(Work::new(0)))
+        };
+
+        let report = account
+            .send_chain(
+                (frontier, account.address(), Raw::new(100)),
+                &payments,
+                publish,
+                work,
+            )
+            .await;
+        assert!(report.is_ok());
+        assert_eq!(report.balance, Raw::new(60));
+        let blocks = published.into_inner();
+        assert_eq!(report.sent.len(), 3);
+        let mut previous = frontier;
+        for ((block, hash), balance) in blocks.iter().zip(&report.sent).zip([90, 65, 60]) {
+            assert_eq!(block.previous, previous);
+            assert_eq!(block.balance, Raw::new(balance));
+            previous = *hash;
+        }
+        assert_eq!(
+            roots.into_inner(),
+            [frontier, report.sent[0], report.sent[1]]
+        );
+
+        // A timeout leaves the second send's outcome unknown
+        let calls = RefCell::new(0);
+        let publish = |block: StateBlock| {
+            *calls.borrow_mut() += 1;
+            core::future::ready(match *calls.borrow() {
+                1 => Ok(crate::blocks::BlockHasher::hash_state_block(&block)),
+                _ => Err(Error::Rpc(crate::error::RpcError::Timeout)),
+            })
+        };
+        let report = account
+            .send_chain(
+                (frontier, account.address(), Raw::new(100)),
+                &payments,
+                publish,
+                |_| core::future::ready(Ok(Work::new(0))),
+            )
+            .await;
+        assert_eq!(report.sent.len(), 1);
+        assert_eq!(report.failed, Some(1));
+        assert!(report.failed_block.is_some() && report.outcome_unknown);
+        assert_eq!(report.balance, Raw::new(90));
+
+        // A rejection means the block is not in the ledger
+        let report = account
+            .send_chain(
+                (frontier, account.address(), Raw::new(100)),
+                &payments,
+                |_| {
+                    core::future::ready(Err(Error::Rpc(crate::error::RpcError::NodeError(
+                        "Fork".into(),
+                    ))))
+                },
+                |_| core::future::ready(Ok(Work::new(0))),
+            )
+            .await;
+        assert_eq!(report.failed, Some(0));
+        assert!(!report.outcome_unknown);
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_send_many_fails_before_publishing() {
+        let account = test_account();
+        let destination = Account::from_public_key(&PublicKey::ZERO);
+
+        // Without account info nothing is sent, so the error is returned
+        let client = RpcClient::new("http://127.0.0.1:9");
+        let err = account
+            .send_many(&[(destination, Raw::new(1))], &client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Rpc(_)));
+
+        let report = SendManyReport::default();
+        assert!(report.is_ok() && report.sent.is_empty());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_publish_pipelined() {
+        let ready = |work| Some(core::future::ready(Ok(Work::new(work))));
+
+        let (published, work) =
+            WalletAccount::publish_pipelined(core::future::ready(Ok(1)), ready(2)).await;
+        assert_eq!(published.unwrap(), 1);
+        assert_eq!(work.unwrap().unwrap(), Work::new(2));
+
+        // A failed publish drops work that would never finish
+        let failed = core::future::ready(Err::<(), _>(Error::InvalidSignature));
+        let pending = Some(core::future::pending::<Result<Work>>());
+        let (published, work) = WalletAccount::publish_pipelined(failed, pending).await;
+        assert_eq!(published.unwrap_err(), Error::InvalidSignature);
+        assert!(work.is_none());
+
+        let none = None::<core::future::Ready<Result<Work>>>;
+        let (_, work) = WalletAccount::publish_pipelined(core::future::ready(Ok(1)), none).await;
+        assert!(work.is_none());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_publish_error_cancels_work() {
+        use crate::error::WorkError;
+        use crate::work::{WorkBackend, WorkSource};
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        struct ObservedBackend(Arc<AtomicBool>);
+
+        impl WorkBackend for ObservedBackend {
+            fn generate_work(&self, _: &BlockHash, _: u64, cancelled: &AtomicBool) -> Result<Work> {
+                while !cancelled.load(Ordering::Relaxed) {
+                    std::thread::sleep(core::time::Duration::from_millis(1));
+                }
+                self.0.store(true, Ordering::Relaxed);
+                Err(Error::WorkGeneration(WorkError::Cancelled))
+            }
+        }
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let provider = WorkProvider::new()
+            .with_gpu(ObservedBackend(stopped.clone()))
+            .with_timeout(WorkSource::Gpu, core::time::Duration::MAX);
+        let client = RpcClient::new("http://127.0.0.1:9");
+        let account = test_account();
+        let hash = BlockHash::from_bytes([1; 32]);
+
+        // The publish fails once the search is already running
+        let failed = async {
+            tokio::time::sleep(core::time::Duration::from_millis(20)).await;
+            Err::<(), _>(Error::InvalidSignature)
+        };
+        let work = Some(account.obtain_work(&hash, Subtype::Send, &client, Some(&provider)));
+        let (published, work) = WalletAccount::publish_pipelined(failed, work).await;
+        assert_eq!(published.unwrap_err(), Error::InvalidSignature);
+        assert!(work.is_none());
+
+        for _ in 0..1000 {
+            if stopped.load(Ordering::Relaxed) {
+                return;
+            }
+            tokio::time::sleep(core::time::Duration::from_millis(1)).await;
+        }
+        panic!("work search still running after a failed publish");
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_receive_chain() {
+        use core::cell::RefCell;
+
+        let account = test_account();
+        let receivables: Vec<_> = [30, 12]
+            .into_iter()
+            .enumerate()
+            .map(|(i, amount)| Receivable {
+                account: account.address(),
+                hash: BlockHash::from_bytes([i as u8 + 1; 32]),
+                amount: Raw::new(amount),
+                source: None,
+            })
+            .collect();
+        let published = RefCell::new(Vec::new());
+        let roots = RefCell::new(Vec::new());
+        let publish = |block: StateBlock| {
+            let hash = crate::blocks::BlockHasher::hash_state_block(&block);
+            published.borrow_mut().push(block);
+            core::future::ready(Ok(hash))
+        };
+        let work = |root, subtype| {
+            roots.borrow_mut().push((root, subtype));
+            core::future::ready(Ok(Work::new(0)))
+        };
+
+        // An unopened account is opened by the first receivable
+        let report = account
+            .receive_chain(None, &receivables, publish, work)
+            .await;
+        assert!(report.is_ok());
+        assert_eq!(report.amount, Raw::new(42));
+        let received = report.received;
+        let blocks = published.into_inner();
+        assert!(blocks[0].is_open());
+        assert_eq!(blocks[0].balance, Raw::new(30));
+        assert_eq!(blocks[1].previous, received[0]);
+        assert_eq!(blocks[1].balance, Raw::new(42));
+        let open_root = BlockHash::from_bytes(*account.keypair.public_key().as_bytes());
+        assert_eq!(
+            roots.into_inner(),
+            [(open_root, Subtype::Open), (received[0], Subtype::Receive)]
+        );
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_receive_chain_reports_partial_failure() {
+        use crate::error::{RpcError, WorkError};
+        use core::cell::Cell;
+
+        let account = test_account();
+        let receivables: Vec<_> = [30, 12, 5]
+            .into_iter()
+            .enumerate()
+            .map(|(i, amount)| Receivable {
+                account: account.address(),
+                hash: BlockHash::from_bytes([i as u8 + 1; 32]),
+                amount: Raw::new(amount),
+                source: None,
+            })
+            .collect();
+        let head = Some((
+            BlockHash::from_bytes([9u8; 32]),
+            account.address(),
+            Raw::new(100),
+        ));
+        let work = |_, _| core::future::ready(Ok(Work::new(0)));
+
+        // The second receive times out, so it may have reached the ledger
+        let calls = Cell::new(0);
+        let publish = |block: StateBlock| {
+            calls.set(calls.get() + 1);
+            core::future::ready(match calls.get() {
+                2 => Err(Error::Rpc(RpcError::Timeout)),
+                _ => Ok(crate::blocks::BlockHasher::hash_state_block(&block)),
+            })
+        };
+        let report = account
+            .receive_chain(head.clone(), &receivables, publish, work)
+            .await;
+        assert_eq!(report.received.len(), 1);
+        assert_eq!(report.amount, Raw::new(30));
+        assert!(report.failed_block.is_some());
+        assert!(report.outcome_unknown);
+        assert_eq!(report.error, Some(Error::Rpc(RpcError::Timeout)));
+        assert_eq!(calls.get(), 2);
+
+        // Failing to generate work builds no block
+        let publish = |block: StateBlock| {
+            core::future::ready(Ok(crate::blocks::BlockHasher::hash_state_block(&block)))
+        };
+        let no_work = |_, _| core::future::ready(Err(Error::WorkGeneration(WorkError::NoSource)));
+        let report = account
+            .receive_chain(head, &receivables, publish, no_work)
+            .await;
+        assert!(report.received.is_empty());
+        assert!(report.failed_block.is_none());
+        assert!(!report.outcome_unknown);
+        assert!(!report.is_ok());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_forward_chain() {
+        use core::cell::RefCell;
+
+        let account = test_account();
+        let destination = Account::from_public_key(&PublicKey::ZERO);
+        let receive = account.create_receive(
+            BlockHash::from_bytes([1u8; 32]),
+            account.address(),
+            Raw::new(100),
+            Raw::new(40),
+            &BlockHash::from_bytes([2u8; 32]),
+            Some(Work::new(0)),
+        );
+        let receive_hash = crate::blocks::BlockHasher::hash_state_block(&receive);
+        let published = RefCell::new(Vec::new());
+        let root = RefCell::new(None);
+        let publish = |block: StateBlock| {
+            let hash = crate::blocks::BlockHasher::hash_state_block(&block);
+            published.borrow_mut().push(block);
+            core::future::ready(Ok(hash))
+        };
+        let work = |frontier| {
+            *root.borrow_mut() = Some(frontier);
+            core::future::ready(Ok(Work::new(7)))
+        };
+
+        let report = account
+            .forward_chain(receive, Raw::new(40), &destination, publish, work)
+            .await
+            .unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.received, receive_hash);
+        assert_eq!(root.into_inner(), Some(receive_hash));
+
+        let blocks = published.into_inner();
+        let send = &blocks[1];
+        assert_eq!(send.previous, receive_hash);
+        assert_eq!(send.balance, Raw::new(100));
+        assert_eq!(send.work, Some(Work::new(7)));
+        assert_eq!(
+            report.forwarded,
+            Some(crate::blocks::BlockHasher::hash_state_block(send))
+        );
+    }
 }
@@ -0,0 +1,377 @@
+//! Import of wallet backups made by other Nano wallets.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::error::{BackupError, Error, Result};
+use crate::keys::{KeyPair, SecretKey, Seed};
+use crate::types::{Account, PublicKey};
+
+use super::{Wallet, DEFAULT_SCAN_DEPTH};
+
+/// Seed and keys recovered from a backup.
+///
+/// Supported formats:
+///
+/// - a bare 64-character hex seed;
+/// - seed JSON as written by Natrium, Nault and similar wallets:
+///   `{"seed": "...", "index": 3}`, optionally with an `accounts` list of
+///   indices and a `representative`;
+/// - the text printed by `nano_node --wallet_decrypt_unsafe`
+///   (`Seed: ...` and `Pub: ... Prv: ...` lines).
+///
+/// The node's `wallet_export` JSON keeps keys encrypted with the wallet
+/// password; read it with [`NodeWalletExport`] and use
+/// `--wallet_decrypt_unsafe` to recover the keys.
+///
+/// # Example
+///
+/// ```
+/// use xno_connect::wallet::WalletBackup;
+///
+/// # fn main() -> xno_connect::error::Result<()> {
+/// let backup = WalletBackup::parse(
+///     r#"{"seed": "0000000000000000000000000000000000000000000000000000000000000001", "index": 2}"#,
+/// )?;
+/// assert_eq!(backup.account_count(), 3);
+/// let mut wallet = backup.wallet().unwrap();
+/// println!("{}", wallet.address(2));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WalletBackup {
+    seed: Option<Seed>,
+    account_count: u32,
+    keys: Vec<KeyPair>,
+    representative: Option<Account>,
+}
+
+impl WalletBackup {
+    /// Detect the format and parse a backup.
+    pub fn parse(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.len() == 64 && input.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Ok(WalletBackup {
+                seed: Some(Seed::from_hex(input)?),
+                account_count: 1,
+                keys: Vec::new(),
+                representative: None,
+            });
+        }
+        if input.starts_with('{') {
+            return Self::from_seed_json(input);
+        }
+        if input.contains("Seed:") || input.contains("Prv:") {
+            return Self::from_decrypt_unsafe(input);
+        }
+        Err(Error::InvalidBackup(BackupError::UnknownFormat))
+    }
+
+    /// Parse seed JSON with an optional account index.
+    ///
+    /// `index` is the highest used index; `accounts` may list the used
+    /// indices as numbers or `{"index": n}` objects.
+    pub fn from_seed_json(json: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| Error::InvalidBackup(BackupError::Malformed(e.to_string())))?;
+        let seed = value
+            .get("seed")
+            .and_then(|seed| seed.as_str())
+            .ok_or(Error::InvalidBackup(BackupError::MissingField("seed")))?;
+        if seed.len() != 64 {
+            // Wallets may export the seed encrypted with a password
+            return Err(Error::InvalidBackup(BackupError::Malformed(String::from(
+                "seed is not 64 hex characters; decrypt it first",
+            ))));
+        }
+
+        let mut highest = value.get("index").and_then(index_value);
+        if let Some(accounts) = value.get("accounts").and_then(|a| a.as_array()) {
+            for entry in accounts {
+                let index = index_value(entry).or_else(|| entry.get("index").and_then(index_value));
+                highest = highest.max(index);
+            }
+        }
+        let representative = match value.get("representative").and_then(|r| r.as_str()) {
+            Some(representative) => Some(representative.parse()?),
+            None => None,
+        };
+
+        Ok(WalletBackup {
+            seed: Some(Seed::from_hex(seed)?),
+            account_count: highest.map_or(1, |index| index.saturating_add(1)),
+            keys: Vec::new(),
+            representative,
+        })
+    }
+
+    /// Parse the output of `nano_node --wallet_decrypt_unsafe`.
+    ///
+    /// Keys derived from the seed count towards
+    /// [`account_count`](Self::account_count); the others are kept as
+    /// standalone [`keys`](Self::keys).
+    pub fn from_decrypt_unsafe(output: &str) -> Result<Self> {
+        let mut seed = None;
+        let mut keys = Vec::new();
+        for line in output.lines().map(str::trim) {
+            if let Some(hex) = line.strip_prefix("Seed:") {
+                seed = Some(Seed::from_hex(hex.trim())?);
+            } else if let Some(rest) = line.strip_prefix("Pub:") {
+                let (public, private) = rest.split_once("Prv:").ok_or_else(|| {
+                    Error::InvalidBackup(BackupError::Malformed(format!("bad key line: {}", line)))
+                })?;
+                let keypair = KeyPair::from_secret_key(SecretKey::from_hex(private.trim())?);
+                let public = public.trim();
+                let expected = match public.parse::<Account>() {
+                    Ok(account) => account,
+                    Err(_) => PublicKey::from_hex(public)?.to_account(),
+                };
+                if keypair.account() != expected {
+                    return Err(Error::InvalidBackup(BackupError::Malformed(format!(
+                        "private key does not match {}",
+                        public
+                    ))));
+                }
+                keys.push(keypair);
+            }
+        }
+        if seed.is_none() && keys.is_empty() {
+            return Err(Error::InvalidBackup(BackupError::UnknownFormat));
+        }
+
+        let mut account_count = 0;
+        if let Some(seed) = &seed {
+            let depth = keys.len() as u32 + DEFAULT_SCAN_DEPTH;
+            for index in 0..depth {
+                let derived = seed.derive(index).account();
+                if let Some(position) = keys.iter().position(|key| key.account() == derived) {
+                    keys.remove(position);
+                    account_count = index + 1;
+                }
+            }
+        }
+
+        Ok(WalletBackup {
+            seed,
+            account_count,
+            keys,
+            representative: None,
+        })
+    }
+
+    /// Get the seed, if the backup had one.
+    pub fn seed(&self) -> Option<&Seed> {
+        self.seed.as_ref()
+    }
+
+    /// Number of seed accounts in use (indices `0..account_count`).
+    pub fn account_count(&self) -> u32 {
+        self.account_count
+    }
+
+    /// Standalone keys not derived from the seed.
+    pub fn keys(&self) -> &[KeyPair] {
+        &self.keys
+    }
+
+    /// Default representative, if the backup recorded one.
+    pub fn representative(&self) -> Option<&Account> {
+        self.representative.as_ref()
+    }
+
+    /// Create a wallet from the seed.
+    pub fn wallet(&self) -> Option<Wallet> {
+        self.seed.clone().map(Wallet::from_seed)
+    }
+
+    /// All accounts in the backup: seed accounts first, then standalone keys.
+    pub fn accounts(&self) -> Vec<Account> {
+        let mut accounts: Vec<Account> = match &self.seed {
+            Some(seed) => (0..self.account_count)
+                .map(|index| seed.derive(index).account())
+                .collect(),
+            None => Vec::new(),
+        };
+        accounts.extend(self.keys.iter().map(KeyPair::account));
+        accounts
+    }
+}
+
+/// Account listed in a node wallet export.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeWalletAccount {
+    /// Account address.
+    pub account: Account,
+    /// Derivation index for seed accounts; `None` for imported keys.
+    pub index: Option<u32>,
+}
+
+/// Contents of the node's `wallet_export` JSON.
+///
+/// Seed and private keys stay encrypted with the wallet password, so only
+/// the public information is read. Use it to check which accounts a
+/// decrypted backup should contain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeWalletExport {
+    /// Wallet format version.
+    pub version: Option<u32>,
+    /// Default representative.
+    pub representative: Option<Account>,
+    /// Next seed derivation index.
+    pub deterministic_index: Option<u32>,
+    /// Accounts in the wallet.
+    pub accounts: Vec<NodeWalletAccount>,
+}
+
+impl NodeWalletExport {
+    /// Parse either the `wallet_export` RPC response (`{"json": "..."}`)
+    /// or the inner JSON object.
+    pub fn parse(json: &str) -> Result<Self> {
+        let malformed = |msg: String| Error::InvalidBackup(BackupError::Malformed(msg));
+        let mut value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| malformed(e.to_string()))?;
+        if let Some(inner) = value.get("json").and_then(|inner| inner.as_str()) {
+            value = serde_json::from_str(inner).map_err(|e| malformed(e.to_string()))?;
+        }
+        let entries = value
+            .as_object()
+            .ok_or(Error::InvalidBackup(BackupError::UnknownFormat))?;
+
+        let mut export = NodeWalletExport {
+            version: None,
+            representative: None,
+            deterministic_index: None,
+            accounts: Vec::new(),
+        };
+        for (key, value) in entries {
+            let key = hex_32(key).ok_or_else(|| malformed(format!("bad key: {}", key)))?;
+            let value = value
+                .as_str()
+                .and_then(hex_32)
+                .ok_or_else(|| malformed(format!("bad value for {}", hex::encode_upper(key))))?;
+
+            // Keys 0-6 are reserved for wallet metadata
+            if key[..31].iter().all(|b| *b == 0) && key[31] <= 6 {
+                match key[31] {
+                    0 => export.version = small_number(&value),
+                    4 => export.representative = Some(PublicKey::from_bytes(value).to_account()),
+                    6 => export.deterministic_index = small_number(&value),
+                    _ => {}
+                }
+                continue;
+            }
+
+            // Seed accounts store (1 << 32 | index) instead of a key
+            let index = (value[..24].iter().all(|b| *b == 0) && value[24..28] == [0, 0, 0, 1])
+                .then(|| u32::from_be_bytes([value[28], value[29], value[30], value[31]]));
+            export.accounts.push(NodeWalletAccount {
+                account: PublicKey::from_bytes(key).to_account(),
+                index,
+            });
+        }
+        Ok(export)
+    }
+}
+
+fn index_value(value: &serde_json::Value) -> Option<u32> {
+    match value {
+        serde_json::Value::Number(n) => n.as_u64().and_then(|n| u32::try_from(n).ok()),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn hex_32(s: &str) -> Option<[u8; 32]> {
+    let bytes = hex::decode(s).ok()?;
+    bytes.try_into().ok()
+}
+
+fn small_number(value: &[u8; 32]) -> Option<u32> {
+    value[..28]
+        .iter()
+        .all(|b| *b == 0)
+        .then(|| u32::from_be_bytes([value[28], value[29], value[30], value[31]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+    #[test]
+    fn test_seed_json() {
+        let json = alloc::format!(
+            r#"{{"seed": "{}", "accounts": [0, {{"index": 4}}], "representative": "nano_1111111111111111111111111111111111111111111111111111hifc8npp"}}"#,
+            SEED
+        );
+        let backup = WalletBackup::parse(&json).unwrap();
+        assert_eq!(backup.account_count(), 5);
+        assert!(backup.representative().is_some());
+        assert_eq!(backup.accounts().len(), 5);
+
+        let backup = WalletBackup::parse(SEED).unwrap();
+        assert_eq!(backup.account_count(), 1);
+        assert!(matches!(
+            WalletBackup::parse(r#"{"seed": "U2FsdGVkX1+abc"}"#),
+            Err(Error::InvalidBackup(BackupError::Malformed(_)))
+        ));
+    }
+
+    #[test]
+    fn test_decrypt_unsafe() {
+        let seed = Seed::from_hex(SEED).unwrap();
+        let derived = seed.derive(1);
+        let adhoc = Seed::from_bytes([9; 32]).derive(0);
+        let output = alloc::format!(
+            "Seed: {}\nPub: {} Prv: {}\nPub: {} Prv: {}\n",
+            SEED,
+            derived.account(),
+            derived.secret_key().to_hex(),
+            adhoc.public_key().to_hex(),
+            adhoc.secret_key().to_hex()
+        );
+        let backup = WalletBackup::parse(&output).unwrap();
+        assert_eq!(backup.account_count(), 2);
+        assert_eq!(backup.keys().len(), 1);
+        assert_eq!(backup.keys()[0].account(), adhoc.account());
+
+        let wrong = alloc::format!(
+            "Pub: {} Prv: {}",
+            derived.account(),
+            adhoc.secret_key().to_hex()
+        );
+        assert!(WalletBackup::parse(&wrong).is_err());
+    }
+
+    #[test]
+    fn test_node_wallet_export() {
+        let seed_account = Seed::from_hex(SEED).unwrap().derive(0);
+        let adhoc = Seed::from_bytes([9; 32]).derive(0);
+        let inner = serde_json::json!({
+            "0000000000000000000000000000000000000000000000000000000000000000":
+                "0000000000000000000000000000000000000000000000000000000000000004",
+            "0000000000000000000000000000000000000000000000000000000000000005": "AB".repeat(32),
+            "0000000000000000000000000000000000000000000000000000000000000006":
+                "0000000000000000000000000000000000000000000000000000000000000001",
+            seed_account.public_key().to_hex():
+                "0000000000000000000000000000000000000000000000000000000100000000",
+            adhoc.public_key().to_hex(): "CD".repeat(32),
+        });
+        let response = serde_json::json!({ "json": inner.to_string() }).to_string();
+
+        let export = NodeWalletExport::parse(&response).unwrap();
+        assert_eq!(export.version, Some(4));
+        assert_eq!(export.deterministic_index, Some(1));
+        assert_eq!(export.accounts.len(), 2);
+        let seed_entry = export
+            .accounts
+            .iter()
+            .find(|entry| entry.account == seed_account.account())
+            .unwrap();
+        assert_eq!(seed_entry.index, Some(0));
+        assert!(export.accounts.iter().any(|entry| entry.index.is_none()));
+    }
+}
@@ -0,0 +1,325 @@
+//! Account state as of the confirmation height.
+//!
+//! Blocks above an account's confirmation height can still be replaced by a
+//! fork and must not be acted on by anything that moves value. The
+//! high-level wallet methods report balances and history at or below the
+//! confirmation height; [`ConfirmedState`] exposes what is still pending.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::error::{Error, NodeErrorKind, Result, RpcError};
+use crate::rpc::{AccountHistoryResponse, AccountInfoRequest, AccountInfoResponse, RpcClient};
+use crate::types::{Account, BlockHash, Raw};
+
+/// Net change of a balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceDelta {
+    /// The balance grew by the amount, or stayed the same.
+    Increase(Raw),
+    /// The balance shrank by the amount.
+    Decrease(Raw),
+}
+
+impl BalanceDelta {
+    /// Get the change from `from` to `to`.
+    pub fn between(from: Raw, to: Raw) -> Self {
+        match to.checked_sub(from) {
+            Some(increase) => BalanceDelta::Increase(increase),
+            None => BalanceDelta::Decrease(from - to),
+        }
+    }
+
+    /// Get the size of the change.
+    pub fn amount(&self) -> Raw {
+        match self {
+            BalanceDelta::Increase(amount) | BalanceDelta::Decrease(amount) => *amount,
+        }
+    }
+
+    /// Check if the balance did not change.
+    pub fn is_zero(&self) -> bool {
+        self.amount().is_zero()
+    }
+}
+
+/// An account's ledger state next to its confirmed state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfirmedState {
+    /// Account address.
+    pub account: Account,
+    /// Number of blocks in the chain, confirmed or not.
+    pub height: u64,
+    /// Latest block, confirmed or not. Zero for unopened accounts.
+    pub frontier: BlockHash,
+    /// Balance after the latest block, confirmed or not.
+    pub balance: Raw,
+    /// Height of the highest confirmed block.
+    pub confirmed_height: u64,
+    /// Highest confirmed block, `None` if nothing is confirmed yet.
+    pub confirmed_frontier: Option<BlockHash>,
+    /// Balance after the highest confirmed block.
+    pub confirmed_balance: Raw,
+}
+
+impl ConfirmedState {
+    /// State of an account that has no blocks.
+    pub fn unopened(account: Account) -> Self {
+        ConfirmedState {
+            account,
+            height: 0,
+            frontier: BlockHash::ZERO,
+            balance: Raw::ZERO,
+            confirmed_height: 0,
+            confirmed_frontier: None,
+            confirmed_balance: Raw::ZERO,
+        }
+    }
+
+    /// Build the state from an `account_info` response.
+    ///
+    /// Returns `None` if the confirmed balance is needed but the node did
+    /// not report it, as nodes without `include_confirmed` support do.
+    pub fn from_info(account: Account, info: &AccountInfoResponse) -> Option<Self> {
+        let height = info.height()?;
+        let confirmed_height = info.confirmed_height()?.min(height);
+        let confirmed_frontier = match confirmed_height {
+            0 => None,
+            _ if confirmed_height == height => Some(info.frontier),
+            _ => Some(
+                info.confirmed_frontier
+                    .or(info.confirmation_height_frontier)?,
+            ),
+        };
+        let confirmed_balance = match confirmed_height {
+            0 => Raw::ZERO,
+            _ if confirmed_height == height => info.balance,
+            _ => info.confirmed_balance?,
+        };
+        Some(ConfirmedState {
+            account,
+            height,
+            frontier: info.frontier,
+            balance: info.balance,
+            confirmed_height,
+            confirmed_frontier,
+            confirmed_balance,
+        })
+    }
+
+    /// Fetch the state of `account`.
+    ///
+    /// Unopened accounts get an empty state. Falls back to `block_info` on
+    /// the confirmed frontier for nodes that do not report the confirmed
+    /// balance.
+    pub(crate) async fn fetch(client: &RpcClient, account: &Account) -> Result<Self> {
+        let request = AccountInfoRequest::new(account).include_confirmed();
+        let mut info = match client.account_info_with(request).await {
+            Ok(info) => info,
+            Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => {
+                return Ok(ConfirmedState::unopened(account.clone()));
+            }
+            Err(e) => return Err(e),
+        };
+        if info.confirmed_balance.is_none() {
+            if let Some(frontier) = info
+                .confirmed_frontier
+                .or(info.confirmation_height_frontier)
+            {
+                let block = client.block_info(&frontier).await?;
+                info.confirmed_balance = block.balance.parse().ok();
+            }
+        }
+        ConfirmedState::from_info(account.clone(), &info).ok_or_else(|| {
+            Error::Rpc(RpcError::InvalidResponse(
+                "account_info without confirmation height".into(),
+            ))
+        })
+    }
+
+    /// Check if every block of the account is confirmed.
+    pub fn is_settled(&self) -> bool {
+        self.confirmed_height >= self.height
+    }
+
+    /// Get the number of blocks above the confirmation height.
+    pub fn unconfirmed_blocks(&self) -> u64 {
+        self.height.saturating_sub(self.confirmed_height)
+    }
+
+    /// Get the net balance change of the blocks above the confirmation
+    /// height.
+    pub fn unconfirmed_delta(&self) -> BalanceDelta {
+        BalanceDelta::between(self.confirmed_balance, self.balance)
+    }
+
+    /// Check if the block at `height` is confirmed.
+    pub fn is_confirmed(&self, height: u64) -> bool {
+        height <= self.confirmed_height
+    }
+}
+
+/// Latest [`ConfirmedState`] per account, never moving backwards.
+///
+/// Confirmation is final, so a lower confirmation height than one already
+/// seen means the answering node is behind, e.g. a different node behind a
+/// load balancer. The tracker keeps the higher state in that case.
+#[derive(Debug, Clone, Default)]
+pub struct ConfirmationTracker {
+    states: BTreeMap<[u8; 32], ConfirmedState>,
+}
+
+impl ConfirmationTracker {
+    /// Create an empty tracker.
+    pub fn new() -> Self {
+        ConfirmationTracker::default()
+    }
+
+    /// Record `state` unless a higher confirmation height is already known.
+    ///
+    /// Returns `true` if the tracked state changed.
+    pub fn observe(&mut self, state: ConfirmedState) -> bool {
+        let key = *state.account.public_key().as_bytes();
+        match self.states.get(&key) {
+            Some(known) if known.confirmed_height > state.confirmed_height => false,
+            Some(known) if *known == state => false,
+            _ => {
+                self.states.insert(key, state);
+                true
+            }
+        }
+    }
+
+    /// Fetch the state of `account` and record it.
+    pub async fn refresh(
+        &mut self,
+        client: &RpcClient,
+        account: &Account,
+    ) -> Result<&ConfirmedState> {
+        self.observe(ConfirmedState::fetch(client, account).await?);
+        Ok(&self.states[account.public_key().as_bytes()])
+    }
+
+    /// Get the tracked state of `account`.
+    pub fn get(&self, account: &Account) -> Option<&ConfirmedState> {
+        self.states.get(account.public_key().as_bytes())
+    }
+
+    /// Get every tracked state.
+    pub fn states(&self) -> Vec<&ConfirmedState> {
+        self.states.values().collect()
+    }
+}
+
+/// Get up to `count` history entries of `account` at or below its
+/// confirmation height, newest first.
+pub(crate) async fn confirmed_history(
+    client: &RpcClient,
+    account: &Account,
+    count: u64,
+) -> Result<AccountHistoryResponse> {
+    // Read the height first so a block confirmed in between is left out
+    // rather than included unconfirmed
+    let state = ConfirmedState::fetch(client, account).await?;
+    if state.confirmed_height == 0 {
+        return Ok(AccountHistoryResponse {
+            account: account.clone(),
+            history: Vec::new(),
+            previous: None,
+        });
+    }
+    let mut response = client.account_history(account, count).await?;
+    response.history.retain(|entry| {
+        entry
+            .height
+            .parse()
+            .is_ok_and(|height| state.is_confirmed(height))
+    });
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::Seed;
+
+    fn info(confirmed: &str) -> AccountInfoResponse {
+        let mut json = serde_json::json!({
+            "frontier": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            "open_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            "representative_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            "balance": "700",
+            "modified_timestamp": "1501793775",
+            "block_count": "5",
+            "confirmation_height": confirmed,
+            "confirmation_height_frontier": "0000000000000000000000000000000000000000000000000000000000000003",
+        });
+        if confirmed != "5" {
+            json["confirmed_balance"] = "1000".into();
+        }
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn account() -> Account {
+        Seed::from_bytes([3u8; 32]).derive(0).account()
+    }
+
+    #[test]
+    fn test_from_info() {
+        let state = ConfirmedState::from_info(account(), &info("3")).unwrap();
+        assert_eq!(state.height, 5);
+        assert_eq!(state.confirmed_height, 3);
+        assert_eq!(
+            state.confirmed_frontier,
+            Some(BlockHash::from_bytes({
+                let mut bytes = [0u8; 32];
+                bytes[31] = 3;
+                bytes
+            }))
+        );
+        assert_eq!(state.confirmed_balance, Raw::new(1000));
+        assert!(!state.is_settled());
+        assert_eq!(state.unconfirmed_blocks(), 2);
+        assert_eq!(
+            state.unconfirmed_delta(),
+            BalanceDelta::Decrease(Raw::new(300))
+        );
+        assert!(state.is_confirmed(3));
+        assert!(!state.is_confirmed(4));
+
+        let settled = ConfirmedState::from_info(account(), &info("5")).unwrap();
+        assert!(settled.is_settled());
+        assert_eq!(settled.confirmed_balance, Raw::new(700));
+        assert!(settled.unconfirmed_delta().is_zero());
+
+        let mut old_node = info("3");
+        old_node.confirmed_balance = None;
+        assert!(ConfirmedState::from_info(account(), &old_node).is_none());
+    }
+
+    #[test]
+    fn test_balance_delta() {
+        assert_eq!(
+            BalanceDelta::between(Raw::new(5), Raw::new(8)),
+            BalanceDelta::Increase(Raw::new(3))
+        );
+        assert_eq!(
+            BalanceDelta::between(Raw::new(8), Raw::new(5)).amount(),
+            Raw::new(3)
+        );
+        assert!(BalanceDelta::between(Raw::new(5), Raw::new(5)).is_zero());
+    }
+
+    #[test]
+    fn test_tracker_never_moves_backwards() {
+        let mut tracker = ConfirmationTracker::new();
+        let ahead = ConfirmedState::from_info(account(), &info("4")).unwrap();
+        let behind = ConfirmedState::from_info(account(), &info("3")).unwrap();
+
+        assert!(tracker.observe(ahead.clone()));
+        assert!(!tracker.observe(ahead.clone()));
+        assert!(!tracker.observe(behind));
+        assert_eq!(tracker.get(&account()), Some(&ahead));
+        assert_eq!(tracker.states().len(), 1);
+    }
+}
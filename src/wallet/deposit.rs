@@ -0,0 +1,687 @@
+//! Exchange-style deposit addresses.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result, StorageError};
+use crate::rpc::RpcClient;
+use crate::types::{Account, BlockHash, Link, Raw};
+use crate::websocket::{ConfirmationMessage, ParsedMessage, SubscriptionBuilder, WebSocketClient};
+
+use super::wallet::sweep_account;
+use super::{SweepResult, Wallet};
+
+/// Derived account assigned to a user.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositAddress {
+    /// Application user ID.
+    pub user_id: String,
+    /// Derivation index in the hot wallet.
+    pub index: u32,
+    /// Deposit account.
+    pub account: Account,
+}
+
+/// A confirmed send to a deposit address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deposit {
+    /// User the deposit address belongs to.
+    pub user_id: String,
+    /// Derivation index of the deposit address.
+    pub index: u32,
+    /// Deposit address.
+    pub account: Account,
+    /// Hash of the send block.
+    pub hash: BlockHash,
+    /// Amount deposited.
+    pub amount: Raw,
+    /// Sending account, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Account>,
+    /// Cemented block count when the deposit was first seen.
+    ///
+    /// `None` if the count was not yet known; it is set on the next
+    /// count update, so the confirmation margin is never skipped.
+    #[serde(default)]
+    pub seen_at: Option<u64>,
+    /// Whether the deposit has been credited to the user.
+    pub credited: bool,
+}
+
+/// When to move deposit balances to cold storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepPolicy {
+    /// Cold account receiving swept funds.
+    pub destination: Account,
+    /// Minimum balance plus receivable before an address is swept.
+    pub threshold: Raw,
+}
+
+impl SweepPolicy {
+    /// Create a policy.
+    pub fn new(destination: Account, threshold: Raw) -> Self {
+        SweepPolicy {
+            destination,
+            threshold,
+        }
+    }
+}
+
+/// Persisted form of [`DepositManager`].
+#[derive(Serialize, Deserialize)]
+struct DepositState {
+    confirmations: u64,
+    min_amount: Raw,
+    #[serde(default)]
+    sweep_policy: Option<SweepPolicy>,
+    next_index: u32,
+    height: u64,
+    addresses: Vec<DepositAddress>,
+    deposits: Vec<Deposit>,
+}
+
+/// Deposit addresses for many users on one hot wallet.
+///
+/// Each user is assigned a derived account of the wallet. Confirmed sends
+/// to those accounts are recorded as pending [`Deposit`]s, either live from
+/// WebSocket confirmations or by [`scan`](Self::scan)ning receivables, and
+/// are credited once the ledger's cemented block count has advanced by the
+/// required number of confirmations and `block_info` reports the send as
+/// confirmed. With a [`SweepPolicy`], funded addresses are emptied into a
+/// cold account by [`sweep`](Self::sweep).
+///
+/// Deposits are tracked by send block hash, so a deposit seen both live
+/// and by a scan is only credited once. Persist the manager with
+/// [`to_json`](Self::to_json) to keep that guarantee across restarts.
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::rpc::RpcClient;
+/// use xno_connect::types::Raw;
+/// use xno_connect::wallet::{DepositManager, SweepPolicy, Wallet};
+/// use xno_connect::websocket::WebSocketClient;
+///
+/// # async fn example(mut wallet: Wallet) -> xno_connect::error::Result<()> {
+/// let client = RpcClient::new("http://localhost:7076");
+/// let mut ws = WebSocketClient::connect("ws://localhost:7078").await?;
+/// let cold = "nano_1abc...".parse()?;
+///
+/// let mut deposits = DepositManager::new(10)
+///     .with_sweep_policy(SweepPolicy::new(cold, Raw::from_nano(100)?));
+/// let address = deposits.assign("user-42", &mut wallet)?;
+/// println!("deposit to {}", address);
+///
+/// deposits.start(&mut ws).await?;
+/// deposits.scan(&client, 100).await?;
+/// while let Some(credited) = deposits.next(&client, &mut ws).await? {
+///     for deposit in credited {
+///         println!("credit {} with {}", deposit.user_id, deposit.amount);
+///     }
+///     deposits.sweep(&mut wallet, &client).await?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DepositManager {
+    confirmations: u64,
+    min_amount: Raw,
+    sweep_policy: Option<SweepPolicy>,
+    next_index: u32,
+    height: u64,
+    /// User ID -> deposit address.
+    users: BTreeMap<String, DepositAddress>,
+    /// Public key -> user ID.
+    accounts: BTreeMap<[u8; 32], String>,
+    /// Send block hash -> deposit.
+    deposits: BTreeMap<[u8; 32], Deposit>,
+}
+
+impl DepositManager {
+    /// Create a manager crediting deposits after `confirmations` further
+    /// blocks have been cemented.
+    ///
+    /// A cemented block is final, so zero credits a deposit as soon as its
+    /// send is confirmed; a higher value adds a safety margin.
+    pub fn new(confirmations: u64) -> Self {
+        DepositManager {
+            confirmations,
+            min_amount: Raw::ZERO,
+            sweep_policy: None,
+            next_index: 0,
+            height: 0,
+            users: BTreeMap::new(),
+            accounts: BTreeMap::new(),
+            deposits: BTreeMap::new(),
+        }
+    }
+
+    /// Ignore deposits smaller than `amount`.
+    pub fn with_min_amount(mut self, amount: Raw) -> Self {
+        self.min_amount = amount;
+        self
+    }
+
+    /// Sweep funded addresses with a policy.
+    pub fn with_sweep_policy(mut self, policy: SweepPolicy) -> Self {
+        self.sweep_policy = Some(policy);
+        self
+    }
+
+    /// Assign new addresses from derivation index `index` onwards, for
+    /// example to keep index 0 as the wallet's main account.
+    pub fn with_first_index(mut self, index: u32) -> Self {
+        self.next_index = self.next_index.max(index);
+        self
+    }
+
+    /// Get the sweep policy.
+    pub fn sweep_policy(&self) -> Option<&SweepPolicy> {
+        self.sweep_policy.as_ref()
+    }
+
+    /// Get a user's deposit address, deriving the next unused one on first
+    /// use.
+    ///
+    /// Call [`start`](Self::start) again so the WebSocket subscription
+    /// includes a new address.
+    pub fn assign(&mut self, user_id: &str, wallet: &mut Wallet) -> Result<Account> {
+        if let Some(address) = self.users.get(user_id) {
+            return Ok(address.account.clone());
+        }
+        let index = self.next_index;
+        let account = wallet.address(index);
+        self.insert_address(DepositAddress {
+            user_id: user_id.into(),
+            index,
+            account: account.clone(),
+        });
+        self.next_index += 1;
+        Ok(account)
+    }
+
+    /// Get a user's deposit address.
+    pub fn address(&self, user_id: &str) -> Option<&DepositAddress> {
+        self.users.get(user_id)
+    }
+
+    /// Get the user a deposit address belongs to.
+    pub fn user_for(&self, account: &Account) -> Option<&str> {
+        self.accounts
+            .get(account.public_key().as_bytes())
+            .map(String::as_str)
+    }
+
+    /// Get all deposit addresses, ordered by user ID.
+    pub fn addresses(&self) -> impl Iterator<Item = &DepositAddress> {
+        self.users.values()
+    }
+
+    /// Get the deposit accounts.
+    pub fn accounts(&self) -> Vec<Account> {
+        self.users.values().map(|a| a.account.clone()).collect()
+    }
+
+    /// Subscribe to confirmations for the deposit addresses.
+    pub async fn start(&self, ws_client: &mut WebSocketClient) -> Result<()> {
+        ws_client
+            .subscribe(
+                SubscriptionBuilder::new()
+                    .confirmations()
+                    .accounts(&self.accounts())
+                    .include_block(),
+            )
+            .await
+    }
+
+    /// Record confirmed receivables the live stream may have missed, such
+    /// as deposits made while the service was down.
+    ///
+    /// Returns the deposits not seen before.
+    ///
+    /// # Arguments
+    /// * `client` - RPC client
+    /// * `count` - Maximum receivable blocks to load per address
+    pub async fn scan(&mut self, client: &RpcClient, count: u64) -> Result<Vec<Deposit>> {
+        let accounts = self.accounts();
+        if accounts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let response = client.accounts_receivable(&accounts, count).await?;
+        let mut found = Vec::new();
+        for account in &accounts {
+            let Some(blocks) = response
+                .blocks
+                .get(&account.to_string())
+                .and_then(|b| b.as_object())
+            else {
+                continue;
+            };
+            for (hash, value) in blocks {
+                let hash = BlockHash::from_hex(hash)?;
+                let (amount, source) = match value {
+                    serde_json::Value::String(amount) => (amount.parse()?, None),
+                    serde_json::Value::Object(info) => {
+                        let Some(amount) = info.get("amount").and_then(|v| v.as_str()) else {
+                            continue;
+                        };
+                        let source = info
+                            .get("source")
+                            .and_then(|v| v.as_str())
+                            .and_then(|s| s.parse().ok());
+                        (amount.parse()?, source)
+                    }
+                    _ => continue,
+                };
+                if let Some(deposit) = self.record(account, hash, amount, source) {
+                    found.push(deposit);
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// Record a confirmed send to a deposit address.
+    ///
+    /// Returns the new pending deposit, or `None` if the confirmation is
+    /// not a send to a deposit address, was already recorded, or is below
+    /// the minimum amount. The subscription must include block contents.
+    pub fn apply(&mut self, confirmation: &ConfirmationMessage) -> Option<Deposit> {
+        let block = confirmation.block.as_ref()?;
+        if block.subtype.as_deref() != Some("send") {
+            return None;
+        }
+        let destination = match &block.link_as_account {
+            Some(account) => account.clone(),
+            None => Account::from_public_key(&Link::from_hex(&block.link).ok()?.as_public_key()),
+        };
+        self.record(
+            &destination,
+            confirmation.hash,
+            confirmation.amount,
+            Some(block.account.clone()),
+        )
+    }
+
+    /// Set the ledger's cemented block count.
+    ///
+    /// Counts lower than the current one are ignored. Deposits seen
+    /// before any count was known start counting from this one.
+    pub fn set_height(&mut self, height: u64) {
+        self.height = self.height.max(height);
+        if self.height > 0 {
+            for deposit in self.deposits.values_mut() {
+                deposit.seen_at.get_or_insert(self.height);
+            }
+        }
+    }
+
+    /// Get the last known cemented block count.
+    pub fn height(&self) -> u64 {
+        self.height
+    }
+
+    /// Get pending deposits that have reached the required confirmations.
+    pub fn due(&self) -> Vec<Deposit> {
+        self.deposits
+            .values()
+            .filter(|d| {
+                !d.credited
+                    && d.seen_at
+                        .is_some_and(|seen| self.height.saturating_sub(seen) >= self.confirmations)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Mark a deposit as credited. Returns it if it was pending.
+    pub fn credit(&mut self, hash: &BlockHash) -> Option<Deposit> {
+        let deposit = self.deposits.get_mut(hash.as_bytes())?;
+        if deposit.credited {
+            return None;
+        }
+        deposit.credited = true;
+        Some(deposit.clone())
+    }
+
+    /// Refresh the cemented block count and credit due deposits.
+    ///
+    /// Each due deposit's send block is checked with `block_info` before it
+    /// is credited. Returns the deposits credited by this call.
+    pub async fn poll(&mut self, client: &RpcClient) -> Result<Vec<Deposit>> {
+        let count = client.block_count().await?;
+        let height = count.cemented.as_deref().unwrap_or(&count.count);
+        if let Ok(height) = height.parse() {
+            self.set_height(height);
+        }
+
+        let mut credited = Vec::new();
+        for deposit in self.due() {
+            let info = client.block_info(&deposit.hash).await?;
+            if info.confirmed != "true" {
+                continue;
+            }
+            if let Some(deposit) = self.credit(&deposit.hash) {
+                credited.push(deposit);
+            }
+        }
+        Ok(credited)
+    }
+
+    /// Wait for the next confirmation that credits deposits.
+    ///
+    /// Every confirmation refreshes the cemented block count, so deposits
+    /// waiting on depth are credited as the ledger advances. Returns
+    /// `Ok(None)` once the WebSocket connection is closed.
+    pub async fn next(
+        &mut self,
+        client: &RpcClient,
+        ws_client: &mut WebSocketClient,
+    ) -> Result<Option<Vec<Deposit>>> {
+        loop {
+            match ws_client.receive().await? {
+                Some(ParsedMessage::Confirmation(confirmation)) => {
+                    self.apply(&confirmation);
+                    let credited = self.poll(client).await?;
+                    if !credited.is_empty() {
+                        return Ok(Some(credited));
+                    }
+                }
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Get a recorded deposit.
+    pub fn deposit(&self, hash: &BlockHash) -> Option<&Deposit> {
+        self.deposits.get(hash.as_bytes())
+    }
+
+    /// Get all recorded deposits, ordered by hash.
+    pub fn deposits(&self) -> impl Iterator<Item = &Deposit> {
+        self.deposits.values()
+    }
+
+    /// Get deposits not yet credited.
+    pub fn pending(&self) -> Vec<&Deposit> {
+        self.deposits.values().filter(|d| !d.credited).collect()
+    }
+
+    /// Get a user's recorded deposits.
+    pub fn deposits_for(&self, user_id: &str) -> Vec<&Deposit> {
+        self.deposits
+            .values()
+            .filter(|d| d.user_id == user_id)
+            .collect()
+    }
+
+    /// Forget credited deposits, for example after they are archived.
+    ///
+    /// A forgotten deposit that is still receivable would be recorded
+    /// again by [`scan`](Self::scan), so only prune after sweeping.
+    pub fn prune_credited(&mut self) -> Vec<Deposit> {
+        let (credited, pending) = core::mem::take(&mut self.deposits)
+            .into_iter()
+            .partition(|(_, d)| d.credited);
+        self.deposits = pending;
+        credited.into_values().collect()
+    }
+
+    /// Move funds from deposit addresses to the policy's cold account.
+    ///
+    /// Addresses whose balance plus receivable reaches the policy threshold
+    /// pocket their receivables and send their full balance. Addresses
+    /// with deposits still waiting to be credited are skipped. Does
+    /// nothing without a sweep policy.
+    pub async fn sweep(
+        &mut self,
+        wallet: &mut Wallet,
+        client: &RpcClient,
+    ) -> Result<Vec<SweepResult>> {
+        let Some(policy) = self.sweep_policy.clone() else {
+            return Ok(Vec::new());
+        };
+        let accounts = self.accounts();
+        if accounts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let response = client.accounts_balances(&accounts).await?;
+        let mut results = Vec::new();
+        for (address, balance) in response.balances {
+            let Ok(account) = address.parse::<Account>() else {
+                continue;
+            };
+            let Some(user_id) = self.user_for(&account) else {
+                continue;
+            };
+            let index = self.users[user_id].index;
+            let key = account.public_key();
+            if self
+                .deposits
+                .values()
+                .any(|d| !d.credited && d.account.public_key() == key)
+            {
+                continue;
+            }
+            let receivable = balance.receivable.unwrap_or(balance.pending);
+            let Some(total) = balance.balance.checked_add(receivable) else {
+                continue;
+            };
+            if total.is_zero() || total < policy.threshold {
+                continue;
+            }
+            let account = wallet.account(index);
+            results.push(sweep_account(&account, &policy.destination, client).await);
+        }
+        Ok(results)
+    }
+
+    /// Serialize addresses, deposits and policy to JSON.
+    pub fn to_json(&self) -> Result<String> {
+        let state = DepositState {
+            confirmations: self.confirmations,
+            min_amount: self.min_amount,
+            sweep_policy: self.sweep_policy.clone(),
+            next_index: self.next_index,
+            height: self.height,
+            addresses: self.users.values().cloned().collect(),
+            deposits: self.deposits.values().cloned().collect(),
+        };
+        serde_json::to_string_pretty(&state)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))
+    }
+
+    /// Deserialize from JSON produced by [`to_json`](Self::to_json).
+    pub fn from_json(json: &str) -> Result<Self> {
+        let state: DepositState = serde_json::from_str(json)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))?;
+        let mut manager = DepositManager {
+            confirmations: state.confirmations,
+            min_amount: state.min_amount,
+            sweep_policy: state.sweep_policy,
+            next_index: state.next_index,
+            height: state.height,
+            users: BTreeMap::new(),
+            accounts: BTreeMap::new(),
+            deposits: BTreeMap::new(),
+        };
+        for address in state.addresses {
+            manager.insert_address(address);
+        }
+        for deposit in state.deposits {
+            manager.deposits.insert(*deposit.hash.as_bytes(), deposit);
+        }
+        Ok(manager)
+    }
+
+    fn insert_address(&mut self, address: DepositAddress) {
+        self.accounts.insert(
+            *address.account.public_key().as_bytes(),
+            address.user_id.clone(),
+        );
+        self.users.insert(address.user_id.clone(), address);
+    }
+
+    /// Record a send to a deposit address if it is new and large enough.
+    fn record(
+        &mut self,
+        account: &Account,
+        hash: BlockHash,
+        amount: Raw,
+        source: Option<Account>,
+    ) -> Option<Deposit> {
+        let user_id = self.accounts.get(account.public_key().as_bytes())?;
+        if amount < self.min_amount || self.deposits.contains_key(hash.as_bytes()) {
+            return None;
+        }
+        let address = &self.users[user_id];
+        let deposit = Deposit {
+            user_id: user_id.clone(),
+            index: address.index,
+            account: address.account.clone(),
+            hash,
+            amount,
+            source,
+            seen_at: (self.height > 0).then_some(self.height),
+            credited: false,
+        };
+        self.deposits.insert(*hash.as_bytes(), deposit.clone());
+        Some(deposit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::Seed;
+
+    const SENDER: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+
+    fn wallet() -> Wallet {
+        Wallet::from_seed(Seed::from_bytes([4u8; 32]))
+    }
+
+    fn send_to(destination: &Account, amount: &str, hash: &str) -> ConfirmationMessage {
+        serde_json::from_value(serde_json::json!({
+            "account": SENDER,
+            "amount": amount,
+            "hash": hash,
+            "confirmation_type": "active_quorum",
+            "block": {
+                "type": "state",
+                "account": SENDER,
+                "previous": "0000000000000000000000000000000000000000000000000000000000000000",
+                "representative": SENDER,
+                "balance": "0",
+                "link": destination.public_key().to_hex(),
+                "signature": "00".repeat(64),
+                "work": "0000000000000000",
+                "subtype": "send"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_assign_is_stable() {
+        let mut wallet = wallet();
+        let mut manager = DepositManager::new(0).with_first_index(1);
+        let alice = manager.assign("alice", &mut wallet).unwrap();
+        let bob = manager.assign("bob", &mut wallet).unwrap();
+
+        assert_eq!(manager.assign("alice", &mut wallet).unwrap(), alice);
+        assert_eq!(alice, wallet.address(1));
+        assert_eq!(bob, wallet.address(2));
+        assert_eq!(manager.user_for(&bob), Some("bob"));
+        assert_eq!(manager.address("alice").unwrap().index, 1);
+    }
+
+    #[test]
+    fn test_credit_after_confirmations() {
+        let mut wallet = wallet();
+        let mut manager = DepositManager::new(5).with_min_amount(Raw::new(100));
+        let alice = manager.assign("alice", &mut wallet).unwrap();
+        manager.set_height(1_000);
+
+        assert!(manager
+            .apply(&send_to(&alice, "99", &"AA".repeat(32)))
+            .is_none());
+        let deposit = manager
+            .apply(&send_to(&alice, "500", &"BB".repeat(32)))
+            .unwrap();
+        assert_eq!(deposit.user_id, "alice");
+        assert_eq!(deposit.source, Some(SENDER.parse().unwrap()));
+        assert_eq!(deposit.seen_at, Some(1_000));
+        assert!(manager
+            .apply(&send_to(&alice, "500", &"BB".repeat(32)))
+            .is_none());
+
+        manager.set_height(1_004);
+        assert!(manager.due().is_empty());
+        manager.set_height(1_005);
+        assert_eq!(manager.due(), core::slice::from_ref(&deposit));
+
+        let credited = manager.credit(&deposit.hash).unwrap();
+        assert!(credited.credited);
+        assert!(manager.credit(&deposit.hash).is_none());
+        assert!(manager.pending().is_empty());
+        assert_eq!(manager.prune_credited().len(), 1);
+        assert_eq!(manager.deposits().count(), 0);
+    }
+
+    #[test]
+    fn test_margin_counts_from_first_known_height() {
+        let mut wallet = wallet();
+        let mut manager = DepositManager::new(5);
+        let alice = manager.assign("alice", &mut wallet).unwrap();
+
+        let deposit = manager
+            .apply(&send_to(&alice, "500", &"BB".repeat(32)))
+            .unwrap();
+        assert_eq!(deposit.seen_at, None);
+        assert!(manager.due().is_empty());
+
+        manager.set_height(1_000);
+        assert!(manager.due().is_empty());
+        manager.set_height(1_004);
+        assert!(manager.due().is_empty());
+        manager.set_height(1_005);
+        assert_eq!(manager.due().len(), 1);
+        assert_eq!(manager.due()[0].seen_at, Some(1_000));
+    }
+
+    #[test]
+    fn test_ignores_other_accounts() {
+        let mut manager = DepositManager::new(0);
+        let stranger = Seed::from_bytes([9u8; 32]).derive(0).account();
+        assert!(manager
+            .apply(&send_to(&stranger, "500", &"AA".repeat(32)))
+            .is_none());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut wallet = wallet();
+        let cold = Seed::from_bytes([9u8; 32]).derive(0).account();
+        let mut manager =
+            DepositManager::new(3).with_sweep_policy(SweepPolicy::new(cold, Raw::new(1_000)));
+        let alice = manager.assign("alice", &mut wallet).unwrap();
+        manager.apply(&send_to(&alice, "500", &"AA".repeat(32)));
+
+        let restored = DepositManager::from_json(&manager.to_json().unwrap()).unwrap();
+        assert_eq!(restored.user_for(&alice), Some("alice"));
+        assert_eq!(restored.pending().len(), 1);
+        assert_eq!(restored.sweep_policy(), manager.sweep_policy());
+
+        let mut restored = restored;
+        assert_eq!(
+            restored.assign("bob", &mut wallet).unwrap(),
+            wallet.address(1)
+        );
+    }
+}
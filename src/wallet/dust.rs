@@ -0,0 +1,97 @@
+//! Dust receivables.
+//!
+//! Anyone can send an account tiny amounts, and each one costs a proof of
+//! work to receive. These helpers separate dust from payments worth
+//! receiving; [`WalletAccount::consolidate`] pockets dust in bulk when it
+//! is worth the work.
+//!
+//! [`WalletAccount::consolidate`]: crate::wallet::WalletAccount::consolidate
+
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::types::{BlockHash, Raw};
+use crate::wallet::Receivable;
+
+/// Default amount below which a receivable counts as dust, 10^24 raw
+/// (0.000001 XNO).
+pub const DEFAULT_DUST_THRESHOLD: Raw = Raw::new(1_000_000_000_000_000_000_000_000);
+
+/// Check if `receivable` is below `threshold`.
+pub fn is_dust(receivable: &Receivable, threshold: Raw) -> bool {
+    receivable.amount < threshold
+}
+
+/// Split `receivables` into those at or above `threshold` and the dust,
+/// keeping their order.
+pub fn split_dust(
+    receivables: Vec<Receivable>,
+    threshold: Raw,
+) -> (Vec<Receivable>, Vec<Receivable>) {
+    receivables
+        .into_iter()
+        .partition(|receivable| !is_dust(receivable, threshold))
+}
+
+/// Outcome of [`WalletAccount::consolidate`].
+///
+/// Receives are published in order, each on the previous one, so those in
+/// `received` are final whatever happened after them. If the outcome of
+/// the failed receive is unknown, e.g. after a timeout, look up
+/// `failed_block` before receiving it again.
+///
+/// [`WalletAccount::consolidate`]: crate::wallet::WalletAccount::consolidate
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsolidateReport {
+    /// Hashes of the receive blocks published, in chain order.
+    pub received: Vec<BlockHash>,
+    /// Total amount received.
+    pub amount: Raw,
+    /// Dust receivables left for a later call because of the block limit.
+    pub remaining: usize,
+    /// Hash of the signed block for the failed receive, if one was built.
+    pub failed_block: Option<BlockHash>,
+    /// Whether `failed_block` may be in the ledger despite the error.
+    pub outcome_unknown: bool,
+    /// Error that stopped the receives, if any.
+    pub error: Option<Error>,
+}
+
+impl ConsolidateReport {
+    /// Check if every selected receivable was received.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Account, PublicKey};
+
+    fn receivable(byte: u8, amount: Raw) -> Receivable {
+        Receivable {
+            account: Account::from_public_key(&PublicKey::ZERO),
+            hash: BlockHash::from_bytes([byte; 32]),
+            amount,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_split_dust() {
+        let payment = receivable(1, Raw::from_nano(1).unwrap());
+        let dust = receivable(2, Raw::new(1));
+        let edge = receivable(3, DEFAULT_DUST_THRESHOLD);
+
+        assert!(is_dust(&dust, DEFAULT_DUST_THRESHOLD));
+        assert!(!is_dust(&edge, DEFAULT_DUST_THRESHOLD));
+
+        let (kept, dust) = split_dust(
+            vec![payment.clone(), dust.clone(), edge.clone()],
+            DEFAULT_DUST_THRESHOLD,
+        );
+        assert_eq!(kept, vec![payment, edge]);
+        assert_eq!(dust.len(), 1);
+    }
+}
@@ -0,0 +1,338 @@
+//! Typed wallet events from WebSocket confirmations.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use tokio::sync::broadcast;
+
+use crate::error::Result;
+use crate::types::{Account, BlockHash, Link, Raw};
+use crate::websocket::{ConfirmationMessage, ParsedMessage, SubscriptionBuilder, WebSocketClient};
+
+/// Events buffered per subscriber before slow receivers start lagging.
+pub const DEFAULT_EVENT_CAPACITY: usize = 256;
+
+/// Something that happened to one of the wallet's accounts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalletEvent {
+    /// A send from a wallet account was confirmed.
+    Sent {
+        /// Sending wallet account.
+        account: Account,
+        /// Send block hash.
+        hash: BlockHash,
+        /// Destination account.
+        destination: Account,
+        /// Amount sent.
+        amount: Raw,
+    },
+    /// A send to a wallet account was confirmed and can be received.
+    Receivable {
+        /// Receiving wallet account.
+        account: Account,
+        /// Send block hash.
+        hash: BlockHash,
+        /// Sending account.
+        source: Account,
+        /// Amount sent.
+        amount: Raw,
+    },
+    /// A receive or open block of a wallet account was confirmed.
+    Received {
+        /// Receiving wallet account.
+        account: Account,
+        /// Receive block hash.
+        hash: BlockHash,
+        /// Hash of the send block that was received.
+        source: BlockHash,
+        /// Amount received.
+        amount: Raw,
+    },
+    /// A wallet account's representative changed.
+    RepChanged {
+        /// Wallet account.
+        account: Account,
+        /// Hash of the block that changed it.
+        hash: BlockHash,
+        /// New representative.
+        representative: Account,
+    },
+}
+
+impl WalletEvent {
+    /// Get the wallet account the event belongs to.
+    pub fn account(&self) -> &Account {
+        match self {
+            WalletEvent::Sent { account, .. }
+            | WalletEvent::Receivable { account, .. }
+            | WalletEvent::Received { account, .. }
+            | WalletEvent::RepChanged { account, .. } => account,
+        }
+    }
+
+    /// Get the block hash.
+    pub fn hash(&self) -> BlockHash {
+        match self {
+            WalletEvent::Sent { hash, .. }
+            | WalletEvent::Receivable { hash, .. }
+            | WalletEvent::Received { hash, .. }
+            | WalletEvent::RepChanged { hash, .. } => *hash,
+        }
+    }
+}
+
+/// Event bus turning confirmations for a set of accounts into
+/// [`WalletEvent`]s.
+///
+/// Every subscriber gets its own copy of each event through a
+/// [`tokio::sync::broadcast`] channel, so UI components can listen
+/// independently while one task drives [`run`](Self::run).
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::wallet::{Wallet, WalletEvent, WalletEvents};
+/// use xno_connect::websocket::WebSocketClient;
+///
+/// # async fn example(mut wallet: Wallet) -> xno_connect::error::Result<()> {
+/// let mut ws = WebSocketClient::connect("ws://localhost:7078").await?;
+/// let mut events = WalletEvents::new(&wallet.addresses(5));
+/// let mut receiver = events.subscribe();
+/// events.start(&mut ws).await?;
+///
+/// tokio::spawn(async move {
+///     while let Ok(event) = receiver.recv().await {
+///         if let WalletEvent::Receivable { account, amount, .. } = event {
+///             println!("{} can receive {}", account, amount);
+///         }
+///     }
+/// });
+/// events.run(&mut ws).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WalletEvents {
+    accounts: BTreeMap<[u8; 32], Account>,
+    representatives: BTreeMap<[u8; 32], Account>,
+    sender: broadcast::Sender<WalletEvent>,
+}
+
+impl WalletEvents {
+    /// Create an event bus for a set of accounts.
+    pub fn new(accounts: &[Account]) -> Self {
+        Self::with_capacity(accounts, DEFAULT_EVENT_CAPACITY)
+    }
+
+    /// Create an event bus buffering up to `capacity` events per subscriber.
+    pub fn with_capacity(accounts: &[Account], capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        WalletEvents {
+            accounts: accounts
+                .iter()
+                .map(|account| (*account.public_key().as_bytes(), account.clone()))
+                .collect(),
+            representatives: BTreeMap::new(),
+            sender,
+        }
+    }
+
+    /// Start watching an additional account.
+    ///
+    /// Call [`start`](Self::start) again so the subscription includes it.
+    pub fn watch(&mut self, account: &Account) {
+        self.accounts
+            .insert(*account.public_key().as_bytes(), account.clone());
+    }
+
+    /// Get a receiver for all future events.
+    pub fn subscribe(&self) -> broadcast::Receiver<WalletEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribe to confirmations for the watched accounts.
+    pub async fn start(&self, ws_client: &mut WebSocketClient) -> Result<()> {
+        let accounts: Vec<Account> = self.accounts.values().cloned().collect();
+        ws_client
+            .subscribe(
+                SubscriptionBuilder::new()
+                    .confirmations()
+                    .accounts(&accounts)
+                    .include_block(),
+            )
+            .await
+    }
+
+    /// Turn a confirmation into events and publish them.
+    ///
+    /// A send between two watched accounts yields both a `Sent` and a
+    /// `Receivable` event. The subscription must include block contents.
+    pub fn apply(&mut self, confirmation: &ConfirmationMessage) -> Vec<WalletEvent> {
+        let mut events = Vec::new();
+        let Some(block) = &confirmation.block else {
+            return events;
+        };
+        let key = *block.account.public_key().as_bytes();
+        let own = self.accounts.contains_key(&key);
+
+        match block.subtype.as_deref() {
+            Some("send") => {
+                let destination = match &block.link_as_account {
+                    Some(account) => Some(account.clone()),
+                    None => Link::from_hex(&block.link)
+                        .ok()
+                        .map(|link| Account::from_public_key(&link.as_public_key())),
+                };
+                if let Some(destination) = destination {
+                    if own {
+                        events.push(WalletEvent::Sent {
+                            account: block.account.clone(),
+                            hash: confirmation.hash,
+                            destination: destination.clone(),
+                            amount: confirmation.amount,
+                        });
+                    }
+                    if self
+                        .accounts
+                        .contains_key(destination.public_key().as_bytes())
+                    {
+                        events.push(WalletEvent::Receivable {
+                            account: destination,
+                            hash: confirmation.hash,
+                            source: block.account.clone(),
+                            amount: confirmation.amount,
+                        });
+                    }
+                }
+            }
+            Some("receive") | Some("open") if own => {
+                if let Ok(link) = Link::from_hex(&block.link) {
+                    events.push(WalletEvent::Received {
+                        account: block.account.clone(),
+                        hash: confirmation.hash,
+                        source: link.as_block_hash(),
+                        amount: confirmation.amount,
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        if own {
+            // Any state block can move the representative; only report a
+            // change against a known previous value or an explicit change
+            let previous = self
+                .representatives
+                .insert(key, block.representative.clone());
+            let changed = match previous {
+                Some(previous) => previous != block.representative,
+                None => block.subtype.as_deref() == Some("change"),
+            };
+            if changed {
+                events.push(WalletEvent::RepChanged {
+                    account: block.account.clone(),
+                    hash: confirmation.hash,
+                    representative: block.representative.clone(),
+                });
+            }
+        }
+
+        for event in &events {
+            // No receivers is not an error; events are simply dropped
+            let _ = self.sender.send(event.clone());
+        }
+        events
+    }
+
+    /// Wait for the next confirmation that produces events.
+    ///
+    /// Returns `Ok(None)` once the WebSocket connection is closed.
+    pub async fn next(
+        &mut self,
+        ws_client: &mut WebSocketClient,
+    ) -> Result<Option<Vec<WalletEvent>>> {
+        loop {
+            match ws_client.receive().await? {
+                Some(ParsedMessage::Confirmation(confirmation)) => {
+                    let events = self.apply(&confirmation);
+                    if !events.is_empty() {
+                        return Ok(Some(events));
+                    }
+                }
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Publish events until the WebSocket connection is closed.
+    pub async fn run(&mut self, ws_client: &mut WebSocketClient) -> Result<()> {
+        while self.next(ws_client).await?.is_some() {}
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+    const BOB: &str = "nano_1111111111111111111111111111111111111111111111111111hifc8npp";
+
+    fn confirmation(subtype: &str, representative: &str, link: &str) -> ConfirmationMessage {
+        serde_json::from_value(serde_json::json!({
+            "account": ALICE,
+            "amount": "250",
+            "hash": "AA".repeat(32),
+            "confirmation_type": "active_quorum",
+            "block": {
+                "type": "state",
+                "account": ALICE,
+                "previous": "00".repeat(32),
+                "representative": representative,
+                "balance": "750",
+                "link": link,
+                "signature": "00".repeat(64),
+                "work": "0000000000000000",
+                "subtype": subtype
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_send_between_watched_accounts() {
+        let alice: Account = ALICE.parse().unwrap();
+        let bob: Account = BOB.parse().unwrap();
+        let mut bus = WalletEvents::new(&[alice.clone(), bob.clone()]);
+        let mut receiver = bus.subscribe();
+
+        let events = bus.apply(&confirmation("send", ALICE, &"00".repeat(32)));
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], WalletEvent::Sent { destination, .. } if *destination == bob));
+        assert!(matches!(&events[1], WalletEvent::Receivable { source, .. } if *source == alice));
+        assert_eq!(events[1].account(), &bob);
+
+        assert_eq!(receiver.try_recv().unwrap(), events[0]);
+        assert_eq!(receiver.try_recv().unwrap(), events[1]);
+    }
+
+    #[test]
+    fn test_rep_changed() {
+        let alice: Account = ALICE.parse().unwrap();
+        let mut bus = WalletEvents::new(core::slice::from_ref(&alice));
+
+        // First sighting of the representative on a receive is not a change
+        let events = bus.apply(&confirmation("receive", ALICE, &"BB".repeat(32)));
+        assert!(matches!(events[..], [WalletEvent::Received { .. }]));
+
+        let events = bus.apply(&confirmation("send", BOB, &"00".repeat(32)));
+        assert!(matches!(
+            &events[..],
+            [WalletEvent::Sent { .. }, WalletEvent::RepChanged { representative, .. }]
+                if representative.as_str() == BOB
+        ));
+
+        let events = bus.apply(&confirmation("change", BOB, &"00".repeat(32)));
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+//! Streaming account history with a live tail.
+
+use alloc::collections::{BTreeSet, VecDeque};
+
+use crate::error::{NodeErrorKind, Result};
+use crate::rpc::{AccountHistoryEntry, RpcClient};
+use crate::types::{Account, BlockHash};
+use crate::websocket::{ConfirmationMessage, ParsedMessage, SubscriptionBuilder, WebSocketClient};
+
+/// Events kept for [`HistoryStream::cached`].
+const CACHE_LIMIT: usize = 256;
+
+/// Live hashes remembered to drop repeated confirmations.
+const SEEN_LIMIT: usize = 256;
+
+/// An item yielded by [`HistoryStream`].
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum HistoryEvent {
+    /// A confirmed block from the account's existing history (newest first).
+    Past(AccountHistoryEntry),
+    /// A block confirmed after the stream was opened.
+    Live(ConfirmationMessage),
+}
+
+impl HistoryEvent {
+    /// Get the block hash.
+    pub fn hash(&self) -> BlockHash {
+        match self {
+            HistoryEvent::Past(entry) => entry.hash,
+            HistoryEvent::Live(conf) => conf.hash,
+        }
+    }
+
+    /// Check if this event came from the live tail.
+    pub fn is_live(&self) -> bool {
+        matches!(self, HistoryEvent::Live(_))
+    }
+}
+
+/// Account history that yields past entries, then follows new confirmations.
+///
+/// Created by [`WalletAccount::history_stream`]. Past entries are fetched
+/// from RPC one page at a time as they are consumed; once the history is
+/// exhausted, the stream waits for confirmations on the WebSocket.
+///
+/// [`WalletAccount::history_stream`]: crate::wallet::WalletAccount::history_stream
+pub struct HistoryStream<'a> {
+    account: Account,
+    client: &'a RpcClient,
+    ws_client: &'a mut WebSocketClient,
+    page_size: u64,
+    buffer: VecDeque<AccountHistoryEntry>,
+    next_head: Option<BlockHash>,
+    history_done: bool,
+    seen: RecentHashes,
+    cached: VecDeque<HistoryEvent>,
+}
+
+impl<'a> HistoryStream<'a> {
+    /// Subscribe to confirmations and fetch the first history page.
+    ///
+    /// The subscription is made before fetching so no confirmation falls
+    /// between the end of the history and the start of the live tail.
+    pub(crate) async fn open(
+        account: Account,
+        client: &'a RpcClient,
+        ws_client: &'a mut WebSocketClient,
+        page_size: u64,
+    ) -> Result<Self> {
+        ws_client
+            .subscribe(
+                SubscriptionBuilder::new()
+                    .confirmations()
+                    .account(&account)
+                    .include_block(),
+            )
+            .await?;
+
+        let mut stream = HistoryStream {
+            account,
+            client,
+            ws_client,
+            page_size: page_size.max(1),
+            buffer: VecDeque::new(),
+            next_head: None,
+            history_done: false,
+            seen: RecentHashes::new(page_size.max(1) as usize + SEEN_LIMIT),
+            cached: VecDeque::new(),
+        };
+        stream.fetch_page().await?;
+        Ok(stream)
+    }
+
+    /// Get the next history event.
+    ///
+    /// Returns `Ok(None)` once the WebSocket connection is closed.
+    pub async fn next(&mut self) -> Result<Option<HistoryEvent>> {
+        loop {
+            if let Some(entry) = self.buffer.pop_front() {
+                return Ok(Some(self.yield_event(HistoryEvent::Past(entry))));
+            }
+
+            if !self.history_done {
+                self.fetch_page().await?;
+                continue;
+            }
+
+            match self.ws_client.receive().await? {
+                Some(ParsedMessage::Confirmation(conf)) => {
+                    if self.seen.insert(conf.hash) {
+                        return Ok(Some(self.yield_event(HistoryEvent::Live(conf))));
+                    }
+                }
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Get the most recent events yielded, oldest first.
+    ///
+    /// Only the last 256 events are kept.
+    pub fn cached(&self) -> &VecDeque<HistoryEvent> {
+        &self.cached
+    }
+
+    /// Check if all past history has been consumed.
+    pub fn is_live(&self) -> bool {
+        self.history_done && self.buffer.is_empty()
+    }
+
+    /// Get the account being followed.
+    pub fn account(&self) -> &Account {
+        &self.account
+    }
+
+    fn yield_event(&mut self, event: HistoryEvent) -> HistoryEvent {
+        if self.cached.len() == CACHE_LIMIT {
+            self.cached.pop_front();
+        }
+        self.cached.push_back(event.clone());
+        event
+    }
+
+    async fn fetch_page(&mut self) -> Result<()> {
+        let response = match &self.next_head {
+            Some(head) => {
+                self.client
+                    .account_history_from(&self.account, self.page_size, head)
+                    .await
+            }
+            None => {
+                self.client
+                    .account_history(&self.account, self.page_size)
+                    .await
+            }
+        };
+
+        let response = match response {
+            Ok(response) => response,
+            // Unopened accounts have no history yet
+            Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => {
+                self.history_done = true;
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        // Only the newest page can overlap the live tail: later pages hold
+        // blocks confirmed before the subscription was made
+        if self.next_head.is_none() {
+            for entry in &response.history {
+                self.seen.insert(entry.hash);
+            }
+        }
+        self.next_head = response.previous.filter(|p| !p.is_zero());
+        self.history_done =
+            self.next_head.is_none() || (response.history.len() as u64) < self.page_size;
+        self.buffer.extend(response.history);
+        Ok(())
+    }
+}
+
+/// Bounded set of the most recently inserted hashes.
+struct RecentHashes {
+    order: VecDeque<BlockHash>,
+    set: BTreeSet<[u8; 32]>,
+    limit: usize,
+}
+
+impl RecentHashes {
+    fn new(limit: usize) -> Self {
+        RecentHashes {
+            order: VecDeque::new(),
+            set: BTreeSet::new(),
+            limit,
+        }
+    }
+
+    /// Insert a hash, forgetting the oldest one when full. Returns `false`
+    /// if the hash was already present.
+    fn insert(&mut self, hash: BlockHash) -> bool {
+        if !self.set.insert(*hash.as_bytes()) {
+            return false;
+        }
+        self.order.push_back(hash);
+        if self.order.len() > self.limit {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(oldest.as_bytes());
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_event_accessors() {
+        let entry: AccountHistoryEntry = serde_json::from_str(
+            r#"{
+                "type": "send",
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "amount": "1000",
+                "local_timestamp": "1551532723",
+                "height": "2",
+                "hash": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"
+            }"#,
+        )
+        .unwrap();
+        let event = HistoryEvent::Past(entry.clone());
+
+        assert_eq!(event.hash(), entry.hash);
+        assert!(!event.is_live());
+    }
+
+    #[test]
+    fn test_recent_hashes_are_bounded() {
+        let hash = |n: u8| BlockHash::from_bytes([n; 32]);
+        let mut seen = RecentHashes::new(2);
+
+        assert!(seen.insert(hash(1)));
+        assert!(!seen.insert(hash(1)));
+        assert!(seen.insert(hash(2)));
+        assert!(seen.insert(hash(3)));
+
+        assert_eq!(seen.set.len(), 2);
+        assert!(seen.insert(hash(1)));
+        assert!(!seen.insert(hash(3)));
+    }
+}
@@ -0,0 +1,146 @@
+//! Records of idempotent sends.
+//!
+//! [`WalletAccount::send_idempotent`](super::WalletAccount::send_idempotent)
+//! stores the signed block under a caller-chosen id before publishing it. A
+//! retry with the same id republishes that block instead of creating a new
+//! one, so a timeout or crash between signing and hearing back from the
+//! node can never turn into a second send.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::sync::Mutex;
+
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+
+use crate::blocks::BlockHasher;
+use crate::error::Result;
+use crate::types::{Account, BlockHash, Raw, StateBlock};
+
+/// Progress of a recorded send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SendIntentStatus {
+    /// Signed but not known to be accepted by the node.
+    Pending,
+    /// Accepted by the node.
+    Published,
+}
+
+/// A send recorded under an idempotency id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SendIntent {
+    /// Caller-chosen id, e.g. a payout or order id.
+    pub id: String,
+    /// [`fingerprint`](Self::fingerprint) of the send parameters.
+    pub fingerprint: [u8; 32],
+    /// Signed send block, built on the frontier at the time of the first
+    /// attempt.
+    pub block: StateBlock,
+    /// Whether the node has accepted the block.
+    pub status: SendIntentStatus,
+}
+
+impl SendIntent {
+    /// Record a new pending send.
+    pub fn new(id: impl Into<String>, fingerprint: [u8; 32], block: StateBlock) -> Self {
+        SendIntent {
+            id: id.into(),
+            fingerprint,
+            block,
+            status: SendIntentStatus::Pending,
+        }
+    }
+
+    /// Blake2b-256 of the sender, destination and amount.
+    pub fn fingerprint(account: &Account, destination: &Account, amount: Raw) -> [u8; 32] {
+        let mut hasher = Blake2b::<U32>::new();
+        hasher.update(account.public_key().as_bytes());
+        hasher.update(destination.public_key().as_bytes());
+        hasher.update(amount.to_be_bytes());
+        hasher.finalize().into()
+    }
+
+    /// Hash of the send block.
+    pub fn hash(&self) -> BlockHash {
+        BlockHasher::hash_state_block(&self.block)
+    }
+
+    /// Frontier the send block was built on.
+    pub fn frontier(&self) -> BlockHash {
+        self.block.previous
+    }
+}
+
+/// Storage for [`SendIntent`]s.
+///
+/// Shared by every task that sends with the same ids. Implement this on
+/// durable storage, e.g. a database table keyed by id, so intents survive
+/// restarts.
+pub trait SendIntentStore: Send + Sync {
+    /// Get the intent recorded under `id`.
+    fn get(&self, id: &str) -> Result<Option<SendIntent>>;
+
+    /// Record `intent` unless its id is already taken.
+    ///
+    /// Must be atomic: returns the existing intent, unchanged, if there is
+    /// one, and `None` if `intent` was stored.
+    fn insert(&self, intent: SendIntent) -> Result<Option<SendIntent>>;
+
+    /// Replace the intent with the same id.
+    fn update(&self, intent: SendIntent) -> Result<()>;
+
+    /// Forget the intent recorded under `id`.
+    fn remove(&self, id: &str) -> Result<Option<SendIntent>>;
+}
+
+/// In-memory [`SendIntentStore`].
+///
+/// Only protects against retries within one process.
+#[derive(Debug, Default)]
+pub struct MemorySendIntentStore {
+    intents: Mutex<BTreeMap<String, SendIntent>>,
+}
+
+impl MemorySendIntentStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        MemorySendIntentStore::default()
+    }
+
+    /// Get all intents, ordered by id.
+    pub fn all(&self) -> Vec<SendIntent> {
+        self.lock().values().cloned().collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, SendIntent>> {
+        self.intents.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SendIntentStore for MemorySendIntentStore {
+    fn get(&self, id: &str) -> Result<Option<SendIntent>> {
+        Ok(self.lock().get(id).cloned())
+    }
+
+    fn insert(&self, intent: SendIntent) -> Result<Option<SendIntent>> {
+        let mut intents = self.lock();
+        if let Some(existing) = intents.get(&intent.id) {
+            return Ok(Some(existing.clone()));
+        }
+        intents.insert(intent.id.clone(), intent);
+        Ok(None)
+    }
+
+    fn update(&self, intent: SendIntent) -> Result<()> {
+        self.lock().insert(intent.id.clone(), intent);
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) -> Result<Option<SendIntent>> {
+        Ok(self.lock().remove(id))
+    }
+}
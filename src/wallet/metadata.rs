@@ -0,0 +1,297 @@
+//! Account labels, address book and free-form metadata for a wallet.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+#[cfg(feature = "serde")]
+use alloc::string::ToString;
+use alloc::vec::Vec;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "serde")]
+use crate::error::{Error, Result, StorageError};
+use crate::types::Account;
+
+/// Named external account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AddressBookEntry {
+    /// Display name.
+    pub name: String,
+    /// Account address.
+    pub account: Account,
+    /// Free-text note.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub note: Option<String>,
+}
+
+impl AddressBookEntry {
+    /// Create an entry.
+    pub fn new(name: impl Into<String>, account: Account) -> Self {
+        AddressBookEntry {
+            name: name.into(),
+            account,
+            note: None,
+        }
+    }
+
+    /// Set the note.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+/// Labels, address book and key-value metadata kept with a [`Wallet`].
+///
+/// Contains no key material. Serialize it with [`to_json`](Self::to_json)
+/// and store it next to the wallet's seed or encrypted seed.
+///
+/// [`Wallet`]: crate::wallet::Wallet
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WalletMetadata {
+    /// Labels of derived accounts, by derivation index.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    labels: BTreeMap<u32, String>,
+    /// Named external accounts.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    address_book: Vec<AddressBookEntry>,
+    /// Accounts tracked without their keys.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    watch_only: Vec<Account>,
+    /// Application-defined values.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    values: BTreeMap<String, String>,
+}
+
+impl WalletMetadata {
+    /// Create empty metadata.
+    pub fn new() -> Self {
+        WalletMetadata::default()
+    }
+
+    /// Get the label of a derived account.
+    pub fn label(&self, index: u32) -> Option<&str> {
+        self.labels.get(&index).map(String::as_str)
+    }
+
+    /// Set the label of a derived account. An empty label removes it.
+    pub fn set_label(&mut self, index: u32, label: impl Into<String>) {
+        let label = label.into();
+        if label.is_empty() {
+            self.labels.remove(&index);
+        } else {
+            self.labels.insert(index, label);
+        }
+    }
+
+    /// Remove the label of a derived account.
+    pub fn remove_label(&mut self, index: u32) -> Option<String> {
+        self.labels.remove(&index)
+    }
+
+    /// Get all labels, ordered by derivation index.
+    pub fn labels(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.labels
+            .iter()
+            .map(|(index, label)| (*index, label.as_str()))
+    }
+
+    /// Find the derivation index with a label.
+    pub fn index_of(&self, label: &str) -> Option<u32> {
+        self.labels
+            .iter()
+            .find(|(_, l)| l.as_str() == label)
+            .map(|(index, _)| *index)
+    }
+
+    /// Add an address book entry, replacing any entry for the same account.
+    ///
+    /// `nano_` and `xno_` forms of an address are the same account.
+    pub fn add_contact(&mut self, entry: AddressBookEntry) {
+        let key = entry.account.public_key();
+        match self
+            .address_book
+            .iter_mut()
+            .find(|e| e.account.public_key() == key)
+        {
+            Some(existing) => *existing = entry,
+            None => self.address_book.push(entry),
+        }
+    }
+
+    /// Remove the address book entry for an account.
+    pub fn remove_contact(&mut self, account: &Account) -> Option<AddressBookEntry> {
+        let key = account.public_key();
+        let pos = self
+            .address_book
+            .iter()
+            .position(|e| e.account.public_key() == key)?;
+        Some(self.address_book.remove(pos))
+    }
+
+    /// Get the address book entry for an account.
+    pub fn contact(&self, account: &Account) -> Option<&AddressBookEntry> {
+        let key = account.public_key();
+        self.address_book
+            .iter()
+            .find(|e| e.account.public_key() == key)
+    }
+
+    /// Find an address book entry by name.
+    pub fn contact_by_name(&self, name: &str) -> Option<&AddressBookEntry> {
+        self.address_book.iter().find(|e| e.name == name)
+    }
+
+    /// Get all address book entries, in insertion order.
+    pub fn address_book(&self) -> &[AddressBookEntry] {
+        &self.address_book
+    }
+
+    /// Track an account without its keys. Returns `false` if it is
+    /// already tracked.
+    pub fn add_watch_only(&mut self, account: Account) -> bool {
+        if self
+            .watch_only
+            .iter()
+            .any(|a| a.public_key() == account.public_key())
+        {
+            return false;
+        }
+        self.watch_only.push(account);
+        true
+    }
+
+    /// Stop tracking a watch-only account. Returns `true` if it was tracked.
+    pub fn remove_watch_only(&mut self, account: &Account) -> bool {
+        let len = self.watch_only.len();
+        self.watch_only
+            .retain(|a| a.public_key() != account.public_key());
+        self.watch_only.len() != len
+    }
+
+    /// Get the watch-only accounts, in insertion order.
+    pub fn watch_only(&self) -> &[Account] {
+        &self.watch_only
+    }
+
+    /// Get a metadata value.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Set a metadata value.
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    /// Remove a metadata value.
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.values.remove(key)
+    }
+
+    /// Get all metadata values, ordered by key.
+    pub fn values(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Check if there are no labels, contacts, watch-only accounts or
+    /// values.
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+            && self.address_book.is_empty()
+            && self.watch_only.is_empty()
+            && self.values.is_empty()
+    }
+
+    /// Serialize to JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))
+    }
+
+    /// Deserialize from JSON produced by [`to_json`](Self::to_json).
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| Error::Storage(StorageError::Serialization(e.to_string())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::Seed;
+
+    fn account(byte: u8) -> Account {
+        Seed::from_bytes([byte; 32]).derive(0).account()
+    }
+
+    #[test]
+    fn test_labels() {
+        let mut metadata = WalletMetadata::new();
+        metadata.set_label(3, "Savings");
+        metadata.set_label(0, "Main");
+
+        assert_eq!(metadata.label(3), Some("Savings"));
+        assert_eq!(metadata.index_of("Main"), Some(0));
+        assert_eq!(
+            metadata.labels().collect::<Vec<_>>(),
+            [(0, "Main"), (3, "Savings")]
+        );
+
+        metadata.set_label(3, "");
+        assert_eq!(metadata.label(3), None);
+    }
+
+    #[test]
+    fn test_contact_replaced_across_prefixes() {
+        let mut metadata = WalletMetadata::new();
+        let nano = account(1);
+        let xno: Account = nano.as_str().replacen("nano_", "xno_", 1).parse().unwrap();
+
+        metadata.add_contact(AddressBookEntry::new("Alice", nano.clone()));
+        metadata.add_contact(AddressBookEntry::new("Alice (exchange)", xno).with_note("deposit"));
+
+        assert_eq!(metadata.address_book().len(), 1);
+        assert_eq!(metadata.contact(&nano).unwrap().name, "Alice (exchange)");
+        assert!(metadata.contact_by_name("Alice").is_none());
+        assert!(metadata.remove_contact(&nano).is_some());
+        assert!(metadata.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_round_trip() {
+        let mut metadata = WalletMetadata::new();
+        metadata.set_label(1, "Tips");
+        metadata.add_contact(AddressBookEntry::new("Bob", account(2)));
+        metadata.set("currency", "EUR");
+        assert!(metadata.add_watch_only(account(3)));
+        assert!(!metadata.add_watch_only(account(3)));
+
+        let json = metadata.to_json().unwrap();
+        assert_eq!(WalletMetadata::from_json(&json).unwrap(), metadata);
+        assert_eq!(
+            WalletMetadata::from_json("{}").unwrap(),
+            WalletMetadata::new()
+        );
+    }
+}
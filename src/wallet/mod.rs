@@ -3,7 +3,72 @@
 //! Provides a simple interface for common wallet operations.
 
 mod account;
+#[cfg(feature = "serde")]
+mod backup;
+#[cfg(feature = "rpc")]
+mod confirmed;
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+mod deposit;
+#[cfg(feature = "rpc")]
+mod dust;
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+mod events;
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+mod history;
+#[cfg(feature = "rpc")]
+mod intent;
+mod metadata;
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+mod monitor;
+#[cfg(feature = "rpc")]
+mod policy;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+mod preset;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+mod publisher;
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+mod receivable;
+mod registry;
+#[cfg(all(feature = "store-sqlite", not(target_arch = "wasm32")))]
+mod store;
+#[allow(clippy::module_inception)]
 mod wallet;
 
 pub use account::WalletAccount;
+#[cfg(feature = "rpc")]
+pub use account::{ForwardReport, SendManyReport};
+#[cfg(feature = "serde")]
+pub use backup::{NodeWalletAccount, NodeWalletExport, WalletBackup};
+#[cfg(feature = "rpc")]
+pub use confirmed::{BalanceDelta, ConfirmationTracker, ConfirmedState};
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+pub use deposit::{Deposit, DepositAddress, DepositManager, SweepPolicy};
+#[cfg(feature = "rpc")]
+pub use dust::{is_dust, split_dust, ConsolidateReport, DEFAULT_DUST_THRESHOLD};
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+pub use events::{WalletEvent, WalletEvents, DEFAULT_EVENT_CAPACITY};
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+pub use history::{HistoryEvent, HistoryStream};
+#[cfg(feature = "rpc")]
+pub use intent::{MemorySendIntentStore, SendIntent, SendIntentStatus, SendIntentStore};
+pub use metadata::{AddressBookEntry, WalletMetadata};
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+pub use monitor::{AccountMonitor, AccountSnapshot};
+#[cfg(feature = "rpc")]
+pub use policy::{Receivable, ReceivePolicy};
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+pub use preset::{Preset, PresetKind, PresetOutcome};
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+pub use publisher::BlockPublisher;
+#[cfg(all(feature = "rpc", feature = "websocket", not(target_arch = "wasm32")))]
+pub use receivable::ReceivableTracker;
+pub use registry::{KeySource, OverlapWarning, WalletRegistry, DEFAULT_SCAN_DEPTH};
+#[cfg(all(feature = "store-sqlite", not(target_arch = "wasm32")))]
+pub use store::{HistoryKind, HistoryQuery, HistoryRecord, HistoryStore, DEFAULT_SYNC_PAGE_SIZE};
+#[cfg(feature = "encryption")]
+pub use wallet::LockedWallet;
 pub use wallet::Wallet;
+#[cfg(feature = "rpc")]
+pub use wallet::{
+    AccountBalance, RepresentativeChange, SweepResult, WalletBalance, SWEEP_GAP_LIMIT,
+};
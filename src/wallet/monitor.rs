@@ -0,0 +1,312 @@
+//! Cached account state kept current from RPC and WebSocket.
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::error::{NodeErrorKind, Result};
+use crate::rpc::{AccountInfoResponse, RpcClient};
+use crate::types::{Account, BlockHash, Raw};
+use crate::websocket::{ConfirmationMessage, ParsedMessage, WebSocketClient};
+
+use super::{Receivable, ReceivableTracker};
+
+/// Point-in-time state of a watched account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountSnapshot {
+    /// Account address.
+    pub account: Account,
+    /// Latest block hash, or `None` if the account is not opened.
+    pub frontier: Option<BlockHash>,
+    /// Confirmed balance.
+    pub balance: Raw,
+    /// Current representative.
+    pub representative: Option<Account>,
+    /// Number of blocks in the account chain.
+    pub block_count: u64,
+    /// Blocks waiting to be received.
+    pub receivable: Vec<Receivable>,
+}
+
+impl AccountSnapshot {
+    /// Check if the account has been opened.
+    pub fn is_opened(&self) -> bool {
+        self.frontier.is_some()
+    }
+
+    /// Total amount waiting to be received.
+    pub fn receivable_total(&self) -> Raw {
+        self.receivable.iter().map(|r| r.amount).sum()
+    }
+}
+
+/// Chain state tracked per account; receivables live in the tracker.
+#[derive(Debug, Clone, Default)]
+struct ChainState {
+    frontier: Option<BlockHash>,
+    balance: Raw,
+    representative: Option<Account>,
+    block_count: u64,
+}
+
+/// In-memory snapshots of watched accounts.
+///
+/// [`refresh`](Self::refresh) loads each account from RPC; afterwards,
+/// confirmations received on the WebSocket update frontiers, balances,
+/// representatives and receivables without further `account_info` calls.
+/// Every update is published to the channels returned by
+/// [`subscribe_changes`](Self::subscribe_changes).
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::rpc::RpcClient;
+/// use xno_connect::wallet::AccountMonitor;
+/// use xno_connect::websocket::WebSocketClient;
+///
+/// # async fn example() -> xno_connect::error::Result<()> {
+/// let client = RpcClient::new("http://localhost:7076");
+/// let mut ws = WebSocketClient::connect("ws://localhost:7078").await?;
+/// let account = "nano_1abc...".parse()?;
+///
+/// let mut monitor = AccountMonitor::new(&[account]);
+/// let changes = monitor.subscribe_changes();
+/// monitor.start(&client, &mut ws).await?;
+///
+/// while monitor.next(&mut ws).await?.is_some() {
+///     let snapshot = changes.recv().unwrap();
+///     println!("{} now holds {}", snapshot.account, snapshot.balance);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct AccountMonitor {
+    states: BTreeMap<[u8; 32], ChainState>,
+    receivables: ReceivableTracker,
+    receivable_count: u64,
+    subscribers: Vec<Sender<AccountSnapshot>>,
+}
+
+impl AccountMonitor {
+    /// Create a monitor for a set of accounts.
+    pub fn new(accounts: &[Account]) -> Self {
+        AccountMonitor {
+            states: accounts
+                .iter()
+                .map(|account| (*account.public_key().as_bytes(), ChainState::default()))
+                .collect(),
+            receivables: ReceivableTracker::new(accounts),
+            receivable_count: 100,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Set the maximum receivable blocks loaded per account on refresh.
+    pub fn with_receivable_count(mut self, count: u64) -> Self {
+        self.receivable_count = count;
+        self
+    }
+
+    /// Start watching an additional account.
+    ///
+    /// Call [`start`](Self::start) again so the subscription and snapshots
+    /// include it.
+    pub fn watch(&mut self, account: &Account) {
+        self.states
+            .entry(*account.public_key().as_bytes())
+            .or_default();
+        self.receivables.track(account);
+    }
+
+    /// Get a channel receiving every snapshot update.
+    pub fn subscribe_changes(&mut self) -> Receiver<AccountSnapshot> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Subscribe to confirmations, then load all accounts from RPC.
+    pub async fn start(
+        &mut self,
+        client: &RpcClient,
+        ws_client: &mut WebSocketClient,
+    ) -> Result<()> {
+        self.receivables
+            .start(client, ws_client, self.receivable_count)
+            .await?;
+        self.refresh_info(client).await
+    }
+
+    /// Reload every account from RPC.
+    pub async fn refresh(&mut self, client: &RpcClient) -> Result<()> {
+        self.receivables.seed(client, self.receivable_count).await?;
+        self.refresh_info(client).await
+    }
+
+    async fn refresh_info(&mut self, client: &RpcClient) -> Result<()> {
+        for account in self.receivables.accounts() {
+            let state = match client.account_info(&account).await {
+                Ok(info) => chain_state(&info),
+                // Unopened accounts have no chain yet
+                Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => {
+                    ChainState::default()
+                }
+                Err(e) => return Err(e),
+            };
+            self.states.insert(*account.public_key().as_bytes(), state);
+            self.publish(&account);
+        }
+        Ok(())
+    }
+
+    /// Apply a confirmation to the cached state.
+    ///
+    /// Returns the snapshots of accounts that changed. The subscription
+    /// must include block contents.
+    pub fn apply(&mut self, confirmation: &ConfirmationMessage) -> Vec<AccountSnapshot> {
+        let mut changed = Vec::new();
+
+        if let Some(block) = &confirmation.block {
+            if let Some(state) = self.states.get_mut(block.account.public_key().as_bytes()) {
+                if state.frontier != Some(confirmation.hash) {
+                    state.frontier = Some(confirmation.hash);
+                    state.balance = block.balance;
+                    state.representative = Some(block.representative.clone());
+                    state.block_count += 1;
+                    changed.push(block.account.clone());
+                }
+            }
+        }
+        if let Some(receivable) = self.receivables.apply(confirmation) {
+            if !changed.contains(&receivable.account) {
+                changed.push(receivable.account);
+            }
+        }
+
+        changed
+            .iter()
+            .filter_map(|account| self.publish(account))
+            .collect()
+    }
+
+    /// Wait for the next confirmation that changes a watched account.
+    ///
+    /// Returns `Ok(None)` once the WebSocket connection is closed.
+    pub async fn next(
+        &mut self,
+        ws_client: &mut WebSocketClient,
+    ) -> Result<Option<Vec<AccountSnapshot>>> {
+        loop {
+            match ws_client.receive().await? {
+                Some(ParsedMessage::Confirmation(confirmation)) => {
+                    let changed = self.apply(&confirmation);
+                    if !changed.is_empty() {
+                        return Ok(Some(changed));
+                    }
+                }
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Get the current snapshot of a watched account.
+    pub fn snapshot(&self, account: &Account) -> Option<AccountSnapshot> {
+        let state = self.states.get(account.public_key().as_bytes())?;
+        Some(AccountSnapshot {
+            account: account.clone(),
+            frontier: state.frontier,
+            balance: state.balance,
+            representative: state.representative.clone(),
+            block_count: state.block_count,
+            receivable: self.receivables.pending_for(account),
+        })
+    }
+
+    /// Get snapshots of all watched accounts.
+    pub fn snapshots(&self) -> Vec<AccountSnapshot> {
+        self.receivables
+            .accounts()
+            .iter()
+            .filter_map(|account| self.snapshot(account))
+            .collect()
+    }
+
+    fn publish(&mut self, account: &Account) -> Option<AccountSnapshot> {
+        let snapshot = self.snapshot(account)?;
+        self.subscribers
+            .retain(|subscriber| subscriber.send(snapshot.clone()).is_ok());
+        Some(snapshot)
+    }
+}
+
+fn chain_state(info: &AccountInfoResponse) -> ChainState {
+    ChainState {
+        frontier: Some(info.frontier),
+        balance: info.balance,
+        representative: info.representative.clone(),
+        block_count: info.block_count.parse().unwrap_or(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENDER: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+    const RECEIVER: &str = "nano_1111111111111111111111111111111111111111111111111111hifc8npp";
+
+    fn send_confirmation(hash: &str, balance: &str) -> ConfirmationMessage {
+        serde_json::from_value(serde_json::json!({
+            "account": SENDER,
+            "amount": "250",
+            "hash": hash,
+            "confirmation_type": "active_quorum",
+            "block": {
+                "type": "state",
+                "account": SENDER,
+                "previous": "00".repeat(32),
+                "representative": SENDER,
+                "balance": balance,
+                "link": "00".repeat(32),
+                "signature": "00".repeat(64),
+                "work": "0000000000000000",
+                "subtype": "send"
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_apply_updates_both_sides() {
+        let sender: Account = SENDER.parse().unwrap();
+        let receiver: Account = RECEIVER.parse().unwrap();
+        let mut monitor = AccountMonitor::new(&[sender.clone(), receiver.clone()]);
+        let changes = monitor.subscribe_changes();
+
+        let changed = monitor.apply(&send_confirmation(&"AA".repeat(32), "750"));
+        assert_eq!(changed.len(), 2);
+
+        let snapshot = monitor.snapshot(&sender).unwrap();
+        assert_eq!(snapshot.balance, Raw::new(750));
+        assert_eq!(snapshot.block_count, 1);
+        assert!(snapshot.is_opened());
+
+        let snapshot = monitor.snapshot(&receiver).unwrap();
+        assert!(!snapshot.is_opened());
+        assert_eq!(snapshot.receivable_total(), Raw::new(250));
+
+        assert_eq!(changes.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_duplicate_confirmation_ignored() {
+        let sender: Account = SENDER.parse().unwrap();
+        let mut monitor = AccountMonitor::new(core::slice::from_ref(&sender));
+        let confirmation = send_confirmation(&"AA".repeat(32), "750");
+
+        assert_eq!(monitor.apply(&confirmation).len(), 1);
+        assert!(monitor.apply(&confirmation).is_empty());
+        assert!(monitor.snapshot(&RECEIVER.parse().unwrap()).is_none());
+    }
+}
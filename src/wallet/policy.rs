@@ -0,0 +1,177 @@
+//! Which receivable blocks to pocket.
+
+use alloc::vec::Vec;
+
+use crate::types::{Account, BlockHash, Raw};
+
+/// A send block waiting to be received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receivable {
+    /// Account the funds are destined for.
+    pub account: Account,
+    /// Hash of the send block.
+    pub hash: BlockHash,
+    /// Amount sent.
+    pub amount: Raw,
+    /// Sending account, if known.
+    pub source: Option<Account>,
+}
+
+/// Rules applied by [`WalletAccount::receive_all`].
+///
+/// Every receive costs a proof of work, so pocketing dust can cost more
+/// than it is worth. The default receives everything, largest first.
+///
+/// # Example
+///
+/// ```
+/// use xno_connect::types::Raw;
+/// use xno_connect::wallet::ReceivePolicy;
+///
+/// # fn main() -> xno_connect::error::Result<()> {
+/// let policy = ReceivePolicy::new()
+///     .with_min_amount(Raw::from_nano_str("0.001")?)
+///     .with_max_blocks(10)
+///     .skip_burn_source();
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`WalletAccount::receive_all`]: crate::wallet::WalletAccount::receive_all
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivePolicy {
+    /// Smallest amount worth receiving.
+    pub min_amount: Raw,
+    /// Most blocks received per call, `None` for no limit.
+    pub max_blocks: Option<usize>,
+    /// Receive the largest amounts first, so a `max_blocks` limit keeps
+    /// the most valuable ones.
+    pub largest_first: bool,
+    /// Leave sends from the burn account unreceived.
+    pub skip_burn_source: bool,
+    /// Number of receivable blocks requested from the node per call.
+    pub fetch_count: u64,
+}
+
+impl Default for ReceivePolicy {
+    fn default() -> Self {
+        ReceivePolicy {
+            min_amount: Raw::ZERO,
+            max_blocks: None,
+            largest_first: true,
+            skip_burn_source: false,
+            fetch_count: 100,
+        }
+    }
+}
+
+impl ReceivePolicy {
+    /// Create a policy that receives everything, largest first.
+    pub fn new() -> Self {
+        ReceivePolicy::default()
+    }
+
+    /// Ignore receivables below `amount`.
+    pub fn with_min_amount(mut self, amount: Raw) -> Self {
+        self.min_amount = amount;
+        self
+    }
+
+    /// Receive at most `max_blocks` blocks per call.
+    pub fn with_max_blocks(mut self, max_blocks: usize) -> Self {
+        self.max_blocks = Some(max_blocks);
+        self
+    }
+
+    /// Receive in block hash order instead of largest first.
+    pub fn unordered(mut self) -> Self {
+        self.largest_first = false;
+        self
+    }
+
+    /// Leave sends from the burn account unreceived.
+    pub fn skip_burn_source(mut self) -> Self {
+        self.skip_burn_source = true;
+        self
+    }
+
+    /// Request up to `count` receivable blocks from the node per call.
+    pub fn with_fetch_count(mut self, count: u64) -> Self {
+        self.fetch_count = count.max(1);
+        self
+    }
+
+    /// Check if a single receivable passes the filters.
+    pub fn accepts(&self, receivable: &Receivable) -> bool {
+        if receivable.amount < self.min_amount {
+            return false;
+        }
+        !(self.skip_burn_source && receivable.source.as_ref().is_some_and(Account::is_burn))
+    }
+
+    /// Filter, order and cap `receivables` into the blocks to receive.
+    pub fn select(&self, receivables: Vec<Receivable>) -> Vec<Receivable> {
+        let mut selected: Vec<Receivable> = receivables
+            .into_iter()
+            .filter(|r| self.accepts(r))
+            .collect();
+        if self.largest_first {
+            selected.sort_by_key(|r| core::cmp::Reverse(r.amount));
+        }
+        if let Some(max) = self.max_blocks {
+            selected.truncate(max);
+        }
+        selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PublicKey;
+
+    fn receivable(byte: u8, amount: u128, source: Option<Account>) -> Receivable {
+        Receivable {
+            account: Account::from_public_key(&PublicKey::from_bytes([1u8; 32])),
+            hash: BlockHash::from_bytes([byte; 32]),
+            amount: Raw::new(amount),
+            source,
+        }
+    }
+
+    #[test]
+    fn test_default_receives_everything_largest_first() {
+        let selected = ReceivePolicy::new().select(vec![
+            receivable(1, 5, None),
+            receivable(2, 50, None),
+            receivable(3, 1, None),
+        ]);
+        let amounts: Vec<u128> = selected.iter().map(|r| r.amount.into()).collect();
+        assert_eq!(amounts, vec![50, 5, 1]);
+    }
+
+    #[test]
+    fn test_select_applies_filters() {
+        let burn = Account::from_public_key(&PublicKey::ZERO);
+        let sender = Account::from_public_key(&PublicKey::from_bytes([2u8; 32]));
+        let policy = ReceivePolicy::new()
+            .with_min_amount(Raw::new(10))
+            .with_max_blocks(2)
+            .skip_burn_source();
+
+        let selected = policy.select(vec![
+            receivable(1, 9, Some(sender.clone())),
+            receivable(2, 1_000, Some(burn)),
+            receivable(3, 10, Some(sender.clone())),
+            receivable(4, 30, None),
+            receivable(5, 20, Some(sender)),
+        ]);
+        let hashes: Vec<u8> = selected.iter().map(|r| r.hash.as_bytes()[0]).collect();
+        assert_eq!(hashes, vec![4, 5]);
+
+        let in_order = ReceivePolicy::new()
+            .unordered()
+            .select(vec![receivable(1, 1, None), receivable(2, 2, None)]);
+        assert_eq!(in_order[0].hash.as_bytes()[0], 1);
+    }
+}
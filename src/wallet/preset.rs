@@ -0,0 +1,358 @@
+//! Ready-made configurations for common merchant flows.
+
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::error::{Error, RefundError, Result};
+use crate::rpc::{ChainDirection, RpcClient};
+use crate::types::{Account, BlockHash, Raw};
+use crate::uri::NanoUri;
+use crate::work::{WorkProvider, WorkSource};
+
+#[cfg(feature = "work-cpu")]
+use crate::work::CpuWorkGenerator;
+
+use super::{BlockPublisher, WalletAccount};
+
+/// Flow a [`Preset`] runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresetKind {
+    /// Pocket a customer payment of an exact amount.
+    PosSale {
+        /// Sale amount.
+        amount: Raw,
+    },
+    /// Return a received payment to its sender.
+    ///
+    /// Only a send to the merchant account that has been received and
+    /// not yet returned is refunded.
+    Refund {
+        /// Hash of the customer's send block.
+        payment: BlockHash,
+    },
+    /// Pocket small receivables that sale flows leave behind.
+    ConsolidateDust {
+        /// Receivables below this amount are pocketed.
+        threshold: Raw,
+    },
+}
+
+/// Blocks published by [`Preset::execute`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PresetOutcome {
+    /// Hashes of receive or open blocks.
+    pub received: Vec<BlockHash>,
+    /// Hash of the send block, if one was published.
+    pub sent: Option<BlockHash>,
+    /// Total amount received or sent.
+    pub amount: Raw,
+}
+
+/// A merchant flow with its publishing and work settings chosen.
+///
+/// Each preset picks retry and work settings suited to its flow and runs
+/// through a [`BlockPublisher`]:
+///
+/// | Preset | Work | Retries |
+/// |--------|------|---------|
+/// | [`pos_sale`](Self::pos_sale) | cache, then node with a 5s timeout, then CPU | 3 |
+/// | [`refund`](Self::refund) | node | 2 |
+/// | [`consolidate_dust`](Self::consolidate_dust) | CPU, then node | 5 |
+///
+/// CPU work is only used with the `work-cpu` feature. All settings can be
+/// overridden with the `with_*` methods.
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::rpc::RpcClient;
+/// use xno_connect::types::Raw;
+/// use xno_connect::wallet::{Preset, Wallet};
+///
+/// # async fn example() -> xno_connect::error::Result<()> {
+/// let client = RpcClient::new("http://localhost:7076");
+/// let mut wallet = Wallet::from_hex_seed("0000000000000000000000000000000000000000000000000000000000000000")?;
+/// let till = wallet.account(0);
+///
+/// let sale = Preset::pos_sale(Raw::from_nano_str("0.25")?);
+/// println!("{}", sale.payment_uri(&till).unwrap());
+///
+/// let outcome = sale.execute(&till, &client).await?;
+/// if !outcome.received.is_empty() {
+///     println!("paid");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Preset {
+    kind: PresetKind,
+    max_retries: u32,
+    receivable_count: u64,
+    work_provider: Arc<WorkProvider>,
+}
+
+impl Preset {
+    /// Pocket a payment of exactly `amount`.
+    ///
+    /// Work is taken from the cache first so precomputed work keeps the
+    /// checkout fast.
+    pub fn pos_sale(amount: Raw) -> Self {
+        let provider = WorkProvider::new()
+            .with_cache()
+            .with_node()
+            .with_timeout(WorkSource::Node, Duration::from_secs(5));
+        #[cfg(feature = "work-cpu")]
+        let provider = provider.with_cpu(CpuWorkGenerator::new());
+
+        Preset {
+            kind: PresetKind::PosSale { amount },
+            max_retries: 3,
+            receivable_count: 100,
+            work_provider: Arc::new(provider),
+        }
+    }
+
+    /// Send the amount of a received payment back to its sender.
+    ///
+    /// [`execute`](Self::execute) fails with [`RefundError`] if `payment`
+    /// is not a send to the merchant account, has not been received, or
+    /// a send of the same amount to the payer already follows its
+    /// receive.
+    pub fn refund(payment: BlockHash) -> Self {
+        Preset {
+            kind: PresetKind::Refund { payment },
+            max_retries: 2,
+            receivable_count: 0,
+            work_provider: Arc::new(WorkProvider::new().with_node()),
+        }
+    }
+
+    /// Pocket every receivable below `threshold`.
+    ///
+    /// Meant for off-peak batches, so local work is preferred to spare
+    /// the node.
+    pub fn consolidate_dust(threshold: Raw) -> Self {
+        let provider = WorkProvider::new().with_node();
+        #[cfg(feature = "work-cpu")]
+        let provider = provider.with_cpu(CpuWorkGenerator::new());
+
+        Preset {
+            kind: PresetKind::ConsolidateDust { threshold },
+            max_retries: 5,
+            receivable_count: 1000,
+            work_provider: Arc::new(provider),
+        }
+    }
+
+    /// Set how many times a block is rebuilt after a stale-frontier error.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the maximum receivable blocks inspected.
+    pub fn with_receivable_count(mut self, count: u64) -> Self {
+        self.receivable_count = count;
+        self
+    }
+
+    /// Replace the preset's work provider.
+    pub fn with_work_provider(mut self, provider: Arc<WorkProvider>) -> Self {
+        self.work_provider = provider;
+        self
+    }
+
+    /// Get the flow this preset runs.
+    pub fn kind(&self) -> PresetKind {
+        self.kind
+    }
+
+    /// Get the work provider, e.g. to cache precomputed work.
+    pub fn work_provider(&self) -> &Arc<WorkProvider> {
+        &self.work_provider
+    }
+
+    /// Payment request for a point-of-sale preset.
+    ///
+    /// Returns `None` for other presets.
+    pub fn payment_uri(&self, account: &WalletAccount) -> Option<NanoUri> {
+        match self.kind {
+            PresetKind::PosSale { amount } => {
+                Some(NanoUri::new(account.address()).with_amount(amount))
+            }
+            _ => None,
+        }
+    }
+
+    /// Build a publisher with this preset's settings.
+    pub fn publisher(&self, client: &RpcClient) -> BlockPublisher {
+        BlockPublisher::new(client.clone())
+            .with_max_retries(self.max_retries)
+            .with_work_provider(self.work_provider.clone())
+    }
+
+    /// Run the flow for an account.
+    ///
+    /// A point-of-sale preset with no matching payment yet returns an
+    /// empty outcome; call it again to poll.
+    ///
+    /// # Arguments
+    /// * `account` - Merchant account
+    /// * `client` - RPC client
+    pub async fn execute(
+        &self,
+        account: &WalletAccount,
+        client: &RpcClient,
+    ) -> Result<PresetOutcome> {
+        self.execute_with(&self.publisher(client), account).await
+    }
+
+    /// Run the flow through an existing publisher.
+    ///
+    /// Use this when other tasks publish for the same account, so all
+    /// blocks are serialized by one publisher. The publisher's own retry
+    /// and work settings apply.
+    ///
+    /// # Arguments
+    /// * `publisher` - Shared block publisher
+    /// * `account` - Merchant account
+    pub async fn execute_with(
+        &self,
+        publisher: &BlockPublisher,
+        account: &WalletAccount,
+    ) -> Result<PresetOutcome> {
+        let client = publisher.client();
+        let mut outcome = PresetOutcome::default();
+
+        match self.kind {
+            PresetKind::PosSale { amount } => {
+                let pending = receivables(account, self.receivable_count, client).await?;
+                if let Some((hash, _)) = pending.into_iter().find(|(_, a)| *a == amount) {
+                    let response = publisher.receive(account, &hash, amount).await?;
+                    outcome.received.push(response.hash);
+                    outcome.amount = amount;
+                }
+            }
+            PresetKind::Refund { payment } => {
+                let info = client.block_info(&payment).await?;
+                if info.send_destination() != Some(account.address()) {
+                    return Err(Error::InvalidRefund(RefundError::NotPayment));
+                }
+                let received = client
+                    .find_receive_for_send(&payment)
+                    .await?
+                    .ok_or(Error::InvalidRefund(RefundError::NotReceived))?;
+                if let Some(refund) =
+                    find_refund(client, &received, &info.block_account, info.amount).await?
+                {
+                    return Err(Error::InvalidRefund(RefundError::AlreadyRefunded(refund)));
+                }
+                let response = publisher
+                    .send(account, &info.block_account, info.amount)
+                    .await?;
+                outcome.sent = Some(response.hash);
+                outcome.amount = info.amount;
+            }
+            PresetKind::ConsolidateDust { threshold } => {
+                let pending = receivables(account, self.receivable_count, client).await?;
+                for (hash, amount) in pending {
+                    if amount >= threshold {
+                        continue;
+                    }
+                    let response = publisher.receive(account, &hash, amount).await?;
+                    outcome.received.push(response.hash);
+                    outcome.amount = outcome.amount.saturating_add(amount);
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Find a send of `amount` to `payer` after `received` in its chain.
+async fn find_refund(
+    client: &RpcClient,
+    received: &BlockHash,
+    payer: &Account,
+    amount: Raw,
+) -> Result<Option<BlockHash>> {
+    let mut chain = client.chain_stream(received, ChainDirection::Forward);
+    while let Some(hashes) = chain.next_page().await? {
+        let blocks = client.blocks_info(&hashes).await?;
+        for hash in hashes.iter().filter(|hash| *hash != received) {
+            let refunded = blocks.blocks.get(&hash.to_hex()).is_some_and(|info| {
+                info.amount == amount && info.send_destination().as_ref() == Some(payer)
+            });
+            if refunded {
+                return Ok(Some(*hash));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Receivable blocks for an account as `(hash, amount)` pairs.
+async fn receivables(
+    account: &WalletAccount,
+    count: u64,
+    client: &RpcClient,
+) -> Result<Vec<(BlockHash, Raw)>> {
+    let response = account.receivable(count, client).await?;
+    let Some(blocks) = response
+        .blocks
+        .get(&account.address().to_string())
+        .and_then(|blocks| blocks.as_object())
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut pending = Vec::new();
+    for (hash, value) in blocks {
+        let amount = match value {
+            serde_json::Value::String(amount) => amount,
+            serde_json::Value::Object(info) => match info.get("amount").and_then(|v| v.as_str()) {
+                Some(amount) => amount,
+                None => continue,
+            },
+            _ => continue,
+        };
+        pending.push((BlockHash::from_hex(hash)?, amount.parse()?));
+    }
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet::Wallet;
+
+    #[test]
+    fn test_presets() {
+        let sale = Preset::pos_sale(Raw::new(250));
+        assert_eq!(
+            sale.kind(),
+            PresetKind::PosSale {
+                amount: Raw::new(250)
+            }
+        );
+        assert_eq!(sale.max_retries, 3);
+
+        let dust = Preset::consolidate_dust(Raw::new(10)).with_max_retries(1);
+        assert_eq!(dust.max_retries, 1);
+        assert!(matches!(dust.kind(), PresetKind::ConsolidateDust { .. }));
+    }
+
+    #[test]
+    fn test_payment_uri() {
+        let mut wallet = Wallet::from_hex_seed(&"00".repeat(32)).unwrap();
+        let till = wallet.account(0);
+
+        let uri = Preset::pos_sale(Raw::new(250)).payment_uri(&till).unwrap();
+        assert_eq!(uri.account, till.address());
+        assert_eq!(uri.amount, Some(Raw::new(250)));
+        assert!(Preset::refund(BlockHash::ZERO).payment_uri(&till).is_none());
+    }
+}
@@ -0,0 +1,418 @@
+//! Serialized block publishing per account.
+
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use std::sync::Mutex;
+
+use crate::blocks::{
+    change_block_builder, open_block_builder, receive_block_builder, send_block_builder,
+    AsyncSigner, BlockHasher,
+};
+use crate::constants::ACCOUNT_VERSION_EPOCH_2;
+use crate::error::{BlockError, Error, NodeErrorKind, Result, RpcError};
+use crate::rpc::{ProcessResponse, RpcClient};
+use crate::types::{Account, BlockHash, Raw, StateBlock, Subtype};
+use crate::work::WorkProvider;
+
+/// Locally tracked head of an account chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Frontier {
+    hash: BlockHash,
+    balance: Raw,
+    representative: Account,
+}
+
+/// Per-account frontier behind an async lock held for a whole publish.
+type FrontierLock = Arc<tokio::sync::Mutex<Option<Frontier>>>;
+
+/// Block to publish on top of the current frontier.
+#[derive(Debug, Clone, Copy)]
+enum Operation<'a> {
+    Send {
+        destination: &'a Account,
+        amount: Raw,
+    },
+    Receive {
+        source: &'a BlockHash,
+        amount: Raw,
+    },
+    Change {
+        representative: &'a Account,
+    },
+}
+
+impl Operation<'_> {
+    fn subtype(&self, frontier: &Frontier) -> Subtype {
+        match self {
+            Operation::Send { .. } => Subtype::Send,
+            Operation::Receive { .. } if frontier.hash.is_zero() => Subtype::Open,
+            Operation::Receive { .. } => Subtype::Receive,
+            Operation::Change { .. } => Subtype::Change,
+        }
+    }
+
+    /// Frontier after this operation is confirmed as block `hash`.
+    fn apply(&self, frontier: &Frontier, hash: BlockHash) -> Frontier {
+        match *self {
+            Operation::Send { amount, .. } => Frontier {
+                hash,
+                balance: frontier.balance.saturating_sub(amount),
+                representative: frontier.representative.clone(),
+            },
+            Operation::Receive { amount, .. } => Frontier {
+                hash,
+                balance: frontier.balance.saturating_add(amount),
+                representative: frontier.representative.clone(),
+            },
+            Operation::Change { representative } => Frontier {
+                hash,
+                balance: frontier.balance,
+                representative: representative.clone(),
+            },
+        }
+    }
+}
+
+/// Publishes blocks one at a time per account.
+///
+/// Blocks are signed by any [`AsyncSigner`]: a [`WalletAccount`](super::WalletAccount), a local
+/// [`KeyPair`](crate::keys::KeyPair), or a remote signer. Work comes from
+/// the provider set with [`with_work_provider`](Self::with_work_provider),
+/// or the node.
+///
+/// Concurrent [`WalletAccount::send`](super::WalletAccount::send) calls on the same account read the
+/// same frontier, so all but one are rejected as forks. A `BlockPublisher`
+/// holds a lock per account while a block is built and processed, chains
+/// each block on the frontier left by the previous one without another
+/// `account_info` call, and on a "Fork" or "Gap previous" error refreshes
+/// the frontier from the node and retries. An "Old block" error means the
+/// block is already in the ledger, so it counts as published.
+///
+/// Share one publisher (e.g. in an [`Arc`]) between all tasks.
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::rpc::RpcClient;
+/// use xno_connect::types::Raw;
+/// use xno_connect::wallet::{BlockPublisher, Wallet};
+///
+/// # async fn example() -> xno_connect::error::Result<()> {
+/// let publisher = BlockPublisher::new(RpcClient::new("http://localhost:7076"));
+/// let mut wallet = Wallet::from_hex_seed("0000000000000000000000000000000000000000000000000000000000000000")?;
+/// let account = wallet.account(0);
+/// let destination = "nano_1abc...".parse()?;
+///
+/// let (a, b) = tokio::join!(
+///     publisher.send(&account, &destination, Raw::new(1)),
+///     publisher.send(&account, &destination, Raw::new(2)),
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub struct BlockPublisher {
+    client: RpcClient,
+    max_retries: u32,
+    allow_legacy_epoch: bool,
+    work_provider: Option<Arc<WorkProvider>>,
+    accounts: Mutex<BTreeMap<[u8; 32], FrontierLock>>,
+}
+
+impl BlockPublisher {
+    /// Create a publisher that retries stale-frontier errors twice.
+    pub fn new(client: RpcClient) -> Self {
+        BlockPublisher {
+            client,
+            max_retries: 2,
+            allow_legacy_epoch: false,
+            work_provider: None,
+            accounts: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Set how many times a block is rebuilt after a stale-frontier error.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Allow publishing on accounts not yet upgraded to epoch v2, as
+    /// [`WalletAccount::allow_legacy_epoch`](super::WalletAccount::allow_legacy_epoch)
+    /// does.
+    pub fn allow_legacy_epoch(mut self) -> Self {
+        self.allow_legacy_epoch = true;
+        self
+    }
+
+    /// Generate work with this provider instead of the node.
+    pub fn with_work_provider(mut self, provider: Arc<WorkProvider>) -> Self {
+        self.work_provider = Some(provider);
+        self
+    }
+
+    /// Get the RPC client.
+    pub fn client(&self) -> &RpcClient {
+        &self.client
+    }
+
+    /// Send Nano from an account.
+    ///
+    /// # Arguments
+    /// * `signer` - Signer for the sending account
+    /// * `destination` - Destination account
+    /// * `amount` - Amount to send
+    pub async fn send<S: AsyncSigner + ?Sized>(
+        &self,
+        signer: &S,
+        destination: &Account,
+        amount: Raw,
+    ) -> Result<ProcessResponse> {
+        self.publish(
+            signer,
+            Operation::Send {
+                destination,
+                amount,
+            },
+        )
+        .await
+    }
+
+    /// Receive a pending block, opening the account if needed.
+    ///
+    /// # Arguments
+    /// * `signer` - Signer for the receiving account
+    /// * `source` - Hash of the send block
+    /// * `amount` - Amount being received
+    pub async fn receive<S: AsyncSigner + ?Sized>(
+        &self,
+        signer: &S,
+        source: &BlockHash,
+        amount: Raw,
+    ) -> Result<ProcessResponse> {
+        self.publish(signer, Operation::Receive { source, amount })
+            .await
+    }
+
+    /// Change an account's representative.
+    ///
+    /// # Arguments
+    /// * `signer` - Signer for the account to update
+    /// * `representative` - New representative
+    pub async fn change_representative<S: AsyncSigner + ?Sized>(
+        &self,
+        signer: &S,
+        representative: &Account,
+    ) -> Result<ProcessResponse> {
+        self.publish(signer, Operation::Change { representative })
+            .await
+    }
+
+    /// Forget the tracked frontier so the next block reloads it.
+    pub async fn invalidate(&self, account: &Account) {
+        *self.lock_for(account).lock().await = None;
+    }
+
+    fn lock_for(&self, account: &Account) -> FrontierLock {
+        let mut accounts = self.accounts.lock().unwrap_or_else(|e| e.into_inner());
+        accounts
+            .entry(*account.public_key().as_bytes())
+            .or_default()
+            .clone()
+    }
+
+    async fn publish<S: AsyncSigner + ?Sized>(
+        &self,
+        signer: &S,
+        operation: Operation<'_>,
+    ) -> Result<ProcessResponse> {
+        let account = signer.account();
+        let lock = self.lock_for(&account);
+        let mut tracked = lock.lock().await;
+
+        let mut attempt = 0;
+        loop {
+            let frontier = match tracked.take() {
+                Some(frontier) => frontier,
+                None => self.load(&account).await?,
+            };
+            let block = self.build(signer, &account, &frontier, operation).await?;
+            let hash = BlockHasher::hash_state_block(&block);
+
+            match self.client.process(block).await {
+                Ok(response) => {
+                    *tracked = Some(operation.apply(&frontier, response.hash));
+                    return Ok(response);
+                }
+                // The block is already in the ledger, e.g. from an earlier
+                // attempt whose response was lost
+                Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::OldBlock) => {
+                    *tracked = Some(operation.apply(&frontier, hash));
+                    return Ok(ProcessResponse { hash });
+                }
+                Err(ref e)
+                    if attempt < self.max_retries
+                        && e.node_error_kind()
+                            .is_some_and(|kind| kind.is_stale_frontier()) =>
+                {
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn load(&self, account: &Account) -> Result<Frontier> {
+        match self.client.account_info(account).await {
+            Ok(info) => match info.epoch() {
+                Some(version) if version < ACCOUNT_VERSION_EPOCH_2 && !self.allow_legacy_epoch => {
+                    Err(Error::InvalidBlock(BlockError::LegacyEpoch(version)))
+                }
+                _ => Ok(Frontier {
+                    hash: info.frontier,
+                    balance: info.balance,
+                    representative: info.representative.unwrap_or_else(|| account.clone()),
+                }),
+            },
+            Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => {
+                Ok(Frontier {
+                    hash: BlockHash::ZERO,
+                    balance: Raw::ZERO,
+                    representative: account.clone(),
+                })
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn build<S: AsyncSigner + ?Sized>(
+        &self,
+        signer: &S,
+        account: &Account,
+        frontier: &Frontier,
+        operation: Operation<'_>,
+    ) -> Result<StateBlock> {
+        let subtype = operation.subtype(frontier);
+        if subtype != Subtype::Open && frontier.hash.is_zero() {
+            return Err(Error::Rpc(RpcError::NodeError("Account not found".into())));
+        }
+
+        let builder = match operation {
+            Operation::Send {
+                destination,
+                amount,
+            } => {
+                let balance =
+                    frontier
+                        .balance
+                        .checked_sub(amount)
+                        .ok_or(Error::InsufficientBalance {
+                            balance: frontier.balance,
+                            amount,
+                        })?;
+                send_block_builder(
+                    account.clone(),
+                    frontier.hash,
+                    frontier.representative.clone(),
+                    balance,
+                    destination,
+                )
+            }
+            Operation::Receive { source, amount } if subtype == Subtype::Open => {
+                open_block_builder(
+                    account.clone(),
+                    frontier.representative.clone(),
+                    amount,
+                    source,
+                )
+            }
+            Operation::Receive { source, amount } => receive_block_builder(
+                account.clone(),
+                frontier.hash,
+                frontier.representative.clone(),
+                frontier.balance.saturating_add(amount),
+                source,
+            ),
+            Operation::Change { representative } => change_block_builder(
+                account.clone(),
+                frontier.hash,
+                representative.clone(),
+                frontier.balance,
+            ),
+        };
+
+        let root = if subtype == Subtype::Open {
+            BlockHash::from_bytes(*account.public_key().as_bytes())
+        } else {
+            frontier.hash
+        };
+        let work = match &self.work_provider {
+            Some(provider) => provider.generate(&root, subtype, &self.client).await?.work,
+            None => self.client.work_generate(&root).await?.work,
+        };
+
+        builder.work(work).sign_async(signer).await?.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PublicKey;
+
+    fn frontier() -> Frontier {
+        Frontier {
+            hash: BlockHash::from_bytes([1u8; 32]),
+            balance: Raw::new(100),
+            representative: Account::from_public_key(&PublicKey::ZERO),
+        }
+    }
+
+    #[test]
+    fn test_operation_advances_frontier() {
+        let destination = Account::from_public_key(&PublicKey::ZERO);
+        let hash = BlockHash::from_bytes([2u8; 32]);
+
+        let send = Operation::Send {
+            destination: &destination,
+            amount: Raw::new(30),
+        };
+        let next = send.apply(&frontier(), hash);
+        assert_eq!(next.hash, hash);
+        assert_eq!(next.balance, Raw::new(70));
+
+        let source = BlockHash::from_bytes([3u8; 32]);
+        let receive = Operation::Receive {
+            source: &source,
+            amount: Raw::new(5),
+        };
+        assert_eq!(receive.apply(&next, hash).balance, Raw::new(75));
+        assert_eq!(receive.subtype(&next), Subtype::Receive);
+
+        let mut unopened = frontier();
+        unopened.hash = BlockHash::ZERO;
+        assert_eq!(receive.subtype(&unopened), Subtype::Open);
+    }
+
+    #[tokio::test]
+    async fn test_send_over_balance() {
+        // Fails before work is requested, so nothing reaches the client
+        let publisher = BlockPublisher::new(RpcClient::new("http://127.0.0.1:9"));
+        let keypair = crate::keys::KeyPair::from_private_key([7u8; 32]);
+        let destination = Account::from_public_key(&PublicKey::ZERO);
+        let send = Operation::Send {
+            destination: &destination,
+            amount: Raw::new(101),
+        };
+        let err = publisher
+            .build(&keypair, &keypair.account(), &frontier(), send)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err,
+            Error::InsufficientBalance {
+                balance: Raw::new(100),
+                amount: Raw::new(101),
+            }
+        );
+    }
+}
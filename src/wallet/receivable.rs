@@ -0,0 +1,328 @@
+//! Receivable tracking without polling.
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use crate::error::Result;
+use crate::rpc::RpcClient;
+use crate::types::{Account, BlockHash, Link, Raw};
+use crate::wallet::Receivable;
+use crate::websocket::{ConfirmationMessage, ParsedMessage, SubscriptionBuilder, WebSocketClient};
+
+type Callback = Box<dyn FnMut(&Receivable) + Send>;
+
+/// Local view of the receivable blocks for a set of accounts.
+///
+/// The tracker is seeded from `accounts_receivable` and then kept current
+/// from WebSocket confirmations: confirmed sends to a tracked account are
+/// added, and confirmed receives remove the send they pocket.
+///
+/// # Example
+///
+/// ```no_run
+/// use xno_connect::rpc::RpcClient;
+/// use xno_connect::wallet::ReceivableTracker;
+/// use xno_connect::websocket::WebSocketClient;
+///
+/// # async fn example() -> xno_connect::error::Result<()> {
+/// let client = RpcClient::new("http://localhost:7076");
+/// let mut ws = WebSocketClient::connect("ws://localhost:7078").await?;
+/// let account = "nano_1abc...".parse()?;
+///
+/// let mut tracker = ReceivableTracker::new(&[account]);
+/// tracker.on_receivable(|r| println!("{} raw incoming", r.amount));
+/// tracker.start(&client, &mut ws, 100).await?;
+///
+/// while tracker.next(&mut ws).await?.is_some() {}
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReceivableTracker {
+    accounts: BTreeMap<[u8; 32], Account>,
+    pending: BTreeMap<[u8; 32], BTreeMap<[u8; 32], Receivable>>,
+    callbacks: Vec<Callback>,
+    dust_threshold: Raw,
+}
+
+impl ReceivableTracker {
+    /// Create a tracker for a set of accounts.
+    pub fn new(accounts: &[Account]) -> Self {
+        ReceivableTracker {
+            accounts: accounts
+                .iter()
+                .map(|account| (*account.public_key().as_bytes(), account.clone()))
+                .collect(),
+            pending: BTreeMap::new(),
+            callbacks: Vec::new(),
+            dust_threshold: Raw::ZERO,
+        }
+    }
+
+    /// Ignore sends below `threshold`.
+    ///
+    /// Dust is neither tracked nor passed to callbacks, so spam cannot
+    /// flood consumers. See [`DEFAULT_DUST_THRESHOLD`](super::DEFAULT_DUST_THRESHOLD).
+    pub fn ignore_dust(&mut self, threshold: Raw) {
+        self.dust_threshold = threshold;
+    }
+
+    /// Register a callback invoked for each new receivable seen live.
+    ///
+    /// Receivables loaded by [`seed`](Self::seed) do not trigger callbacks.
+    pub fn on_receivable<F>(&mut self, callback: F)
+    where
+        F: FnMut(&Receivable) + Send + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Start tracking an additional account.
+    ///
+    /// Call [`start`](Self::start) again so the WebSocket subscription and
+    /// seeded state include it.
+    pub fn track(&mut self, account: &Account) {
+        self.accounts
+            .insert(*account.public_key().as_bytes(), account.clone());
+    }
+
+    /// Get the tracked accounts.
+    pub fn accounts(&self) -> Vec<Account> {
+        self.accounts.values().cloned().collect()
+    }
+
+    /// Subscribe to confirmations, then seed from RPC.
+    ///
+    /// The subscription is made first so no send confirmed between the two
+    /// steps is missed.
+    ///
+    /// # Arguments
+    /// * `client` - RPC client
+    /// * `ws_client` - WebSocket client
+    /// * `count` - Maximum receivable blocks to load per account
+    pub async fn start(
+        &mut self,
+        client: &RpcClient,
+        ws_client: &mut WebSocketClient,
+        count: u64,
+    ) -> Result<()> {
+        ws_client
+            .subscribe(
+                SubscriptionBuilder::new()
+                    .confirmations()
+                    .accounts(&self.accounts())
+                    .include_block(),
+            )
+            .await?;
+        self.seed(client, count).await
+    }
+
+    /// Replace the tracked state with the node's current receivables.
+    pub async fn seed(&mut self, client: &RpcClient, count: u64) -> Result<()> {
+        let response = client.accounts_receivable(&self.accounts(), count).await?;
+        self.pending.clear();
+        for account in self.accounts.values() {
+            let Some(blocks) = response.blocks.get(&account.to_string()) else {
+                continue;
+            };
+            let Some(blocks) = blocks.as_object() else {
+                continue;
+            };
+            let mut entries = BTreeMap::new();
+            for (hash, value) in blocks {
+                let hash = BlockHash::from_hex(hash)?;
+                let (amount, source) = match value {
+                    serde_json::Value::String(amount) => (amount.parse()?, None),
+                    serde_json::Value::Object(info) => {
+                        let Some(amount) = info.get("amount").and_then(|v| v.as_str()) else {
+                            continue;
+                        };
+                        let source = info
+                            .get("source")
+                            .and_then(|v| v.as_str())
+                            .and_then(|s| s.parse().ok());
+                        (amount.parse()?, source)
+                    }
+                    _ => continue,
+                };
+                if amount < self.dust_threshold {
+                    continue;
+                }
+                entries.insert(
+                    *hash.as_bytes(),
+                    Receivable {
+                        account: account.clone(),
+                        hash,
+                        amount,
+                        source,
+                    },
+                );
+            }
+            if !entries.is_empty() {
+                self.pending
+                    .insert(*account.public_key().as_bytes(), entries);
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply a confirmation to the tracked state.
+    ///
+    /// Returns the new receivable if the confirmation is a send to a
+    /// tracked account. The subscription must include block contents.
+    pub fn apply(&mut self, confirmation: &ConfirmationMessage) -> Option<Receivable> {
+        let block = confirmation.block.as_ref()?;
+        match block.subtype.as_deref() {
+            Some("send") => {
+                let destination = match &block.link_as_account {
+                    Some(account) => account.clone(),
+                    None => {
+                        Account::from_public_key(&Link::from_hex(&block.link).ok()?.as_public_key())
+                    }
+                };
+                let key = *destination.public_key().as_bytes();
+                if !self.accounts.contains_key(&key) || confirmation.amount < self.dust_threshold {
+                    return None;
+                }
+                let receivable = Receivable {
+                    account: destination,
+                    hash: confirmation.hash,
+                    amount: confirmation.amount,
+                    source: Some(block.account.clone()),
+                };
+                let previous = self
+                    .pending
+                    .entry(key)
+                    .or_default()
+                    .insert(*receivable.hash.as_bytes(), receivable.clone());
+                if previous.is_some() {
+                    return None;
+                }
+                for callback in &mut self.callbacks {
+                    callback(&receivable);
+                }
+                Some(receivable)
+            }
+            Some("receive") | Some("open") => {
+                let key = *block.account.public_key().as_bytes();
+                let source = Link::from_hex(&block.link).ok()?;
+                if let Some(entries) = self.pending.get_mut(&key) {
+                    entries.remove(source.as_bytes());
+                    if entries.is_empty() {
+                        self.pending.remove(&key);
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Wait for the next new receivable.
+    ///
+    /// Other confirmations are applied as they arrive. Returns `Ok(None)`
+    /// once the WebSocket connection is closed.
+    pub async fn next(&mut self, ws_client: &mut WebSocketClient) -> Result<Option<Receivable>> {
+        loop {
+            match ws_client.receive().await? {
+                Some(ParsedMessage::Confirmation(confirmation)) => {
+                    if let Some(receivable) = self.apply(&confirmation) {
+                        return Ok(Some(receivable));
+                    }
+                }
+                Some(_) => continue,
+                None => return Ok(None),
+            }
+        }
+    }
+
+    /// Get the receivable blocks for an account.
+    pub fn pending_for(&self, account: &Account) -> Vec<Receivable> {
+        self.pending
+            .get(account.public_key().as_bytes())
+            .map(|entries| entries.values().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the total receivable amount for an account.
+    pub fn total_for(&self, account: &Account) -> Raw {
+        self.pending
+            .get(account.public_key().as_bytes())
+            .map(|entries| entries.values().map(|r| r.amount).sum())
+            .unwrap_or(Raw::ZERO)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const SENDER: &str = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3";
+    const RECEIVER: &str = "nano_1111111111111111111111111111111111111111111111111111hifc8npp";
+
+    fn confirmation(account: &str, subtype: &str, link: &str, hash: &str) -> ConfirmationMessage {
+        serde_json::from_value(serde_json::json!({
+            "account": account,
+            "amount": "1000",
+            "hash": hash,
+            "confirmation_type": "active_quorum",
+            "block": {
+                "type": "state",
+                "account": account,
+                "previous": "0000000000000000000000000000000000000000000000000000000000000000",
+                "representative": SENDER,
+                "balance": "0",
+                "link": link,
+                "signature": "00".repeat(64),
+                "work": "0000000000000000",
+                "subtype": subtype
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_send_then_receive() {
+        let receiver: Account = RECEIVER.parse().unwrap();
+        let mut tracker = ReceivableTracker::new(core::slice::from_ref(&receiver));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        tracker.on_receivable(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let send_hash = "AA".repeat(32);
+        let send = confirmation(SENDER, "send", &"00".repeat(32), &send_hash);
+        let receivable = tracker.apply(&send).unwrap();
+        assert_eq!(receivable.amount, Raw::new(1000));
+        assert_eq!(receivable.source, Some(SENDER.parse().unwrap()));
+        assert!(tracker.apply(&send).is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(tracker.total_for(&receiver), Raw::new(1000));
+
+        let receive = confirmation(RECEIVER, "receive", &send_hash, &"BB".repeat(32));
+        assert!(tracker.apply(&receive).is_none());
+        assert!(tracker.pending_for(&receiver).is_empty());
+    }
+
+    #[test]
+    fn test_ignores_untracked() {
+        let mut tracker = ReceivableTracker::new(&[SENDER.parse().unwrap()]);
+        let send = confirmation(SENDER, "send", &"00".repeat(32), &"AA".repeat(32));
+        assert!(tracker.apply(&send).is_none());
+    }
+
+    #[test]
+    fn test_ignores_dust() {
+        let receiver: Account = RECEIVER.parse().unwrap();
+        let mut tracker = ReceivableTracker::new(core::slice::from_ref(&receiver));
+        tracker.ignore_dust(Raw::new(1001));
+
+        let send = confirmation(SENDER, "send", &"00".repeat(32), &"AA".repeat(32));
+        assert!(tracker.apply(&send).is_none());
+        assert!(tracker.pending_for(&receiver).is_empty());
+    }
+}
@@ -0,0 +1,321 @@
+//! Detection of wallet entries that control the same accounts.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use core::fmt;
+
+use crate::keys::{KeyPair, Seed};
+use crate::types::Account;
+
+use super::Wallet;
+
+/// Default number of indices derived from each seed when checking overlaps.
+pub const DEFAULT_SCAN_DEPTH: u32 = 20;
+
+/// Where a registered account key came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeySource {
+    /// Derived from a registered seed.
+    Seed {
+        /// Label of the seed entry.
+        label: String,
+        /// Derivation index.
+        index: u32,
+    },
+    /// Imported as a standalone private key.
+    PrivateKey {
+        /// Label of the key entry.
+        label: String,
+    },
+}
+
+impl KeySource {
+    /// Label of the wallet entry.
+    pub fn label(&self) -> &str {
+        match self {
+            KeySource::Seed { label, .. } | KeySource::PrivateKey { label } => label,
+        }
+    }
+}
+
+/// Two wallet entries control the same account(s).
+///
+/// Publishing from both risks the entries racing on one account chain and
+/// forking each other's blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverlapWarning {
+    /// The same seed was registered twice.
+    DuplicateSeed {
+        /// Label of the existing entry.
+        existing: String,
+        /// Label of the new entry.
+        imported: String,
+    },
+    /// The same private key was registered twice.
+    DuplicateKey {
+        /// Shared account.
+        account: Account,
+        /// Label of the existing entry.
+        existing: String,
+        /// Label of the new entry.
+        imported: String,
+    },
+    /// A standalone private key is also derivable from a registered seed.
+    KeyDerivedFromSeed {
+        /// Shared account.
+        account: Account,
+        /// Label of the seed entry.
+        seed: String,
+        /// Derivation index of the key within the seed.
+        index: u32,
+        /// Label of the private key entry.
+        key: String,
+    },
+}
+
+impl OverlapWarning {
+    /// Shared account, if the overlap concerns a single account.
+    pub fn account(&self) -> Option<&Account> {
+        match self {
+            OverlapWarning::DuplicateSeed { .. } => None,
+            OverlapWarning::DuplicateKey { account, .. }
+            | OverlapWarning::KeyDerivedFromSeed { account, .. } => Some(account),
+        }
+    }
+}
+
+impl fmt::Display for OverlapWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverlapWarning::DuplicateSeed { existing, imported } => {
+                write!(f, "'{}' uses the same seed as '{}'", imported, existing)
+            }
+            OverlapWarning::DuplicateKey {
+                account,
+                existing,
+                imported,
+            } => write!(
+                f,
+                "'{}' and '{}' both control {}",
+                imported, existing, account
+            ),
+            OverlapWarning::KeyDerivedFromSeed {
+                account,
+                seed,
+                index,
+                key,
+            } => write!(
+                f,
+                "key '{}' for {} is index {} of seed '{}'",
+                key, account, index, seed
+            ),
+        }
+    }
+}
+
+/// Registry of wallet entries that flags overlapping accounts on import.
+///
+/// Seeds are recorded by a Blake2b fingerprint, never in the clear, along
+/// with the public keys of their first `scan_depth` accounts. Keys derived
+/// beyond the scan depth are not compared.
+///
+/// # Example
+///
+/// ```
+/// use xno_connect::keys::Seed;
+/// use xno_connect::wallet::{OverlapWarning, WalletRegistry};
+///
+/// let seed = Seed::from_bytes([7u8; 32]);
+/// let mut registry = WalletRegistry::new();
+/// assert!(registry.add_seed("main", &seed).is_empty());
+///
+/// let warnings = registry.add_private_key("paper", &seed.derive(3));
+/// assert!(matches!(
+///     warnings[0],
+///     OverlapWarning::KeyDerivedFromSeed { index: 3, .. }
+/// ));
+/// ```
+#[derive(Debug, Clone)]
+pub struct WalletRegistry {
+    scan_depth: u32,
+    seeds: Vec<([u8; 32], String)>,
+    keys: BTreeMap<[u8; 32], KeySource>,
+}
+
+impl WalletRegistry {
+    /// Create an empty registry scanning [`DEFAULT_SCAN_DEPTH`] indices.
+    pub fn new() -> Self {
+        WalletRegistry {
+            scan_depth: DEFAULT_SCAN_DEPTH,
+            seeds: Vec::new(),
+            keys: BTreeMap::new(),
+        }
+    }
+
+    /// Set how many indices are derived from each seed.
+    ///
+    /// Applies to seeds added afterwards.
+    pub fn with_scan_depth(mut self, depth: u32) -> Self {
+        self.scan_depth = depth;
+        self
+    }
+
+    /// Register a seed and report any overlap with existing entries.
+    pub fn add_seed(&mut self, label: impl Into<String>, seed: &Seed) -> Vec<OverlapWarning> {
+        let label = label.into();
+        let fingerprint = fingerprint(seed);
+        if let Some((_, existing)) = self.seeds.iter().find(|(f, _)| *f == fingerprint) {
+            return alloc::vec![OverlapWarning::DuplicateSeed {
+                existing: existing.clone(),
+                imported: label,
+            }];
+        }
+
+        let mut warnings = Vec::new();
+        for index in 0..self.scan_depth {
+            let keypair = seed.derive(index);
+            let source = KeySource::Seed {
+                label: label.clone(),
+                index,
+            };
+            if let Some(warning) = self.insert(&keypair, source) {
+                warnings.push(warning);
+            }
+        }
+        self.seeds.push((fingerprint, label));
+        warnings
+    }
+
+    /// Register a wallet's seed.
+    pub fn add_wallet(&mut self, label: impl Into<String>, wallet: &Wallet) -> Vec<OverlapWarning> {
+        self.add_seed(label, wallet.seed())
+    }
+
+    /// Register a standalone private key and report any overlap.
+    pub fn add_private_key(
+        &mut self,
+        label: impl Into<String>,
+        keypair: &KeyPair,
+    ) -> Vec<OverlapWarning> {
+        let source = KeySource::PrivateKey {
+            label: label.into(),
+        };
+        self.insert(keypair, source).into_iter().collect()
+    }
+
+    /// Get the entry that first registered an account.
+    pub fn owner(&self, account: &Account) -> Option<&KeySource> {
+        self.keys.get(account.public_key().as_bytes())
+    }
+
+    /// Number of distinct accounts registered.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Check if the registry is empty.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    fn insert(&mut self, keypair: &KeyPair, source: KeySource) -> Option<OverlapWarning> {
+        let key = *keypair.public_key().as_bytes();
+        let Some(existing) = self.keys.get(&key) else {
+            self.keys.insert(key, source);
+            return None;
+        };
+
+        let account = keypair.account();
+        Some(match (existing, source) {
+            (KeySource::Seed { label, index }, KeySource::PrivateKey { label: key }) => {
+                OverlapWarning::KeyDerivedFromSeed {
+                    account,
+                    seed: label.clone(),
+                    index: *index,
+                    key,
+                }
+            }
+            (KeySource::PrivateKey { label: key }, KeySource::Seed { label, index }) => {
+                OverlapWarning::KeyDerivedFromSeed {
+                    account,
+                    seed: label,
+                    index,
+                    key: key.clone(),
+                }
+            }
+            (existing, source) => OverlapWarning::DuplicateKey {
+                account,
+                existing: existing.label().into(),
+                imported: source.label().into(),
+            },
+        })
+    }
+}
+
+impl Default for WalletRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn fingerprint(seed: &Seed) -> [u8; 32] {
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(seed.as_bytes());
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_duplicate_seed() {
+        let seed = Seed::from_bytes([1u8; 32]);
+        let mut registry = WalletRegistry::new().with_scan_depth(5);
+        assert!(registry.add_seed("a", &seed).is_empty());
+        assert_eq!(registry.len(), 5);
+
+        let warnings = registry.add_seed("b", &seed);
+        assert_eq!(
+            warnings,
+            alloc::vec![OverlapWarning::DuplicateSeed {
+                existing: "a".into(),
+                imported: "b".into(),
+            }]
+        );
+        assert!(registry
+            .add_seed("c", &Seed::from_bytes([2u8; 32]))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_key_imported_before_seed() {
+        let seed = Seed::from_bytes([1u8; 32]);
+        let keypair = seed.derive(2);
+        let mut registry = WalletRegistry::new().with_scan_depth(5);
+
+        assert!(registry.add_private_key("paper", &keypair).is_empty());
+        let warnings = registry.add_seed("main", &seed);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].account(), Some(&keypair.account()));
+        assert!(matches!(
+            &warnings[0],
+            OverlapWarning::KeyDerivedFromSeed { index: 2, key, .. } if key == "paper"
+        ));
+
+        let warnings = registry.add_private_key("copy", &keypair);
+        assert!(matches!(warnings[0], OverlapWarning::DuplicateKey { .. }));
+        assert_eq!(registry.owner(&keypair.account()).unwrap().label(), "paper");
+    }
+
+    #[test]
+    fn test_beyond_scan_depth() {
+        let seed = Seed::from_bytes([1u8; 32]);
+        let mut registry = WalletRegistry::new().with_scan_depth(2);
+        registry.add_seed("main", &seed);
+        assert!(registry.add_private_key("far", &seed.derive(5)).is_empty());
+    }
+}
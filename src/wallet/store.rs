@@ -0,0 +1,659 @@
+//! SQLite-backed transaction history.
+//!
+//! [`HistoryStore`] keeps the confirmed sends and receives of tracked
+//! accounts in a local database so UIs can page, filter and search history
+//! without asking the node every time. It is brought up to date with
+//! [`sync`](HistoryStore::sync), which only fetches blocks above the last
+//! stored height, and kept current by passing WebSocket confirmations to
+//! [`ingest`](HistoryStore::ingest).
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+
+use crate::error::{Error, NodeErrorKind, Result, RpcError, StorageError};
+use crate::rpc::{AccountHistoryEntry, RpcClient};
+use crate::types::{Account, BlockHash, Raw};
+#[cfg(feature = "websocket")]
+use crate::websocket::ConfirmationMessage;
+
+/// Default number of entries fetched per `account_history` request.
+pub const DEFAULT_SYNC_PAGE_SIZE: u64 = 100;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS accounts (
+        account TEXT PRIMARY KEY,
+        height INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE TABLE IF NOT EXISTS transactions (
+        hash TEXT PRIMARY KEY,
+        account TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        amount BLOB NOT NULL,
+        counterparty TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        height INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS transactions_account_height
+        ON transactions (account, height);
+";
+
+const COLUMNS: &str = "account, hash, kind, amount, counterparty, timestamp, height";
+
+/// Whether a stored transaction moved funds out of or into the account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HistoryKind {
+    /// Funds sent to the counterparty.
+    Send,
+    /// Funds received from the counterparty, including the open block.
+    Receive,
+}
+
+impl HistoryKind {
+    /// Get the name used in `account_history` and in the database.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryKind::Send => "send",
+            HistoryKind::Receive => "receive",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "send" => Some(HistoryKind::Send),
+            "receive" => Some(HistoryKind::Receive),
+            _ => None,
+        }
+    }
+}
+
+/// A confirmed send or receive of a tracked account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryRecord {
+    /// Tracked account whose chain holds the block.
+    pub account: Account,
+    /// Block hash.
+    pub hash: BlockHash,
+    /// Send or receive.
+    pub kind: HistoryKind,
+    /// Amount transferred.
+    pub amount: Raw,
+    /// Destination of a send, or source account of a receive.
+    pub counterparty: Account,
+    /// Unix time the node saw the block, `0` if unknown.
+    pub timestamp: u64,
+    /// Height of the block in the account chain.
+    pub height: u64,
+}
+
+/// Filter for [`HistoryStore::query`] and [`HistoryStore::count`].
+///
+/// Results are ordered newest first.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    account: Option<Account>,
+    kind: Option<HistoryKind>,
+    counterparty: Option<Account>,
+    since: Option<u64>,
+    until: Option<u64>,
+    min_amount: Option<Raw>,
+    limit: Option<u64>,
+    offset: u64,
+}
+
+impl HistoryQuery {
+    /// Match every stored record.
+    pub fn new() -> Self {
+        HistoryQuery::default()
+    }
+
+    /// Only records of `account`.
+    pub fn account(mut self, account: &Account) -> Self {
+        self.account = Some(account.clone());
+        self
+    }
+
+    /// Only sends or only receives.
+    pub fn kind(mut self, kind: HistoryKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Only records exchanged with `counterparty`.
+    pub fn counterparty(mut self, counterparty: &Account) -> Self {
+        self.counterparty = Some(counterparty.clone());
+        self
+    }
+
+    /// Only records with a timestamp at or after `timestamp`.
+    pub fn since(mut self, timestamp: u64) -> Self {
+        self.since = Some(timestamp);
+        self
+    }
+
+    /// Only records with a timestamp before `timestamp`.
+    pub fn until(mut self, timestamp: u64) -> Self {
+        self.until = Some(timestamp);
+        self
+    }
+
+    /// Only records moving at least `amount`.
+    pub fn min_amount(mut self, amount: Raw) -> Self {
+        self.min_amount = Some(amount);
+        self
+    }
+
+    /// Return at most `limit` records.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` matching records.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    fn filter(&self) -> (String, Vec<Value>) {
+        let mut clauses = Vec::new();
+        let mut values = Vec::new();
+        if let Some(account) = &self.account {
+            clauses.push("account = ?");
+            values.push(Value::Text(account.as_str().into()));
+        }
+        if let Some(kind) = self.kind {
+            clauses.push("kind = ?");
+            values.push(Value::Text(kind.as_str().into()));
+        }
+        if let Some(counterparty) = &self.counterparty {
+            clauses.push("counterparty = ?");
+            values.push(Value::Text(counterparty.as_str().into()));
+        }
+        if let Some(since) = self.since {
+            clauses.push("timestamp >= ?");
+            values.push(Value::Integer(to_sql_int(since)));
+        }
+        if let Some(until) = self.until {
+            clauses.push("timestamp < ?");
+            values.push(Value::Integer(to_sql_int(until)));
+        }
+        if let Some(amount) = self.min_amount {
+            // Big-endian blobs compare in numeric order
+            clauses.push("amount >= ?");
+            values.push(Value::Blob(amount.to_be_bytes().to_vec()));
+        }
+        let filter = if clauses.is_empty() {
+            String::new()
+        } else {
+            alloc::format!(" WHERE {}", clauses.join(" AND "))
+        };
+        (filter, values)
+    }
+}
+
+/// Persistent history of confirmed sends and receives per account.
+///
+/// Safe to share between tasks; queries run on one connection guarded by a
+/// mutex, which is never held across RPC calls.
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+    page_size: u64,
+}
+
+impl HistoryStore {
+    /// Open or create a database file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(Connection::open(path).map_err(db_error)?)
+    }
+
+    /// Open a database that lives only as long as the store.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(db_error)?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA).map_err(db_error)?;
+        Ok(HistoryStore {
+            conn: Mutex::new(conn),
+            page_size: DEFAULT_SYNC_PAGE_SIZE,
+        })
+    }
+
+    /// Set how many entries each `account_history` request fetches.
+    pub fn with_page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Start tracking `account`. Its history is fetched on the next sync.
+    pub fn track(&self, account: &Account) -> Result<()> {
+        self.lock()
+            .execute(
+                "INSERT OR IGNORE INTO accounts (account) VALUES (?1)",
+                params![account.as_str()],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Stop tracking `account` and delete its records.
+    pub fn forget(&self, account: &Account) -> Result<()> {
+        let mut conn = self.lock();
+        let tx = conn.transaction().map_err(db_error)?;
+        tx.execute(
+            "DELETE FROM transactions WHERE account = ?1",
+            params![account.as_str()],
+        )
+        .map_err(db_error)?;
+        tx.execute(
+            "DELETE FROM accounts WHERE account = ?1",
+            params![account.as_str()],
+        )
+        .map_err(db_error)?;
+        tx.commit().map_err(db_error)
+    }
+
+    /// Get the tracked accounts.
+    pub fn tracked(&self) -> Result<Vec<Account>> {
+        let conn = self.lock();
+        let mut stmt = conn
+            .prepare("SELECT account FROM accounts ORDER BY account")
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(db_error)?;
+        rows.map(|row| parse_account(&row.map_err(db_error)?))
+            .collect()
+    }
+
+    /// Get the height up to which `account` has been synced, `None` if it
+    /// is not tracked.
+    pub fn synced_height(&self, account: &Account) -> Result<Option<u64>> {
+        self.lock()
+            .query_row(
+                "SELECT height FROM accounts WHERE account = ?1",
+                params![account.as_str()],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()
+            .map(|height| height.map(|h| h as u64))
+            .map_err(db_error)
+    }
+
+    /// Fetch and store the confirmed history of `account` above its synced
+    /// height, tracking it if needed.
+    ///
+    /// Returns the number of new records. Unconfirmed blocks are left for a
+    /// later sync.
+    pub async fn sync(&self, client: &RpcClient, account: &Account) -> Result<usize> {
+        self.track(account)?;
+        let synced = self.synced_height(account)?.unwrap_or(0);
+
+        // Read the confirmation height first, so blocks confirmed while the
+        // history is paged are left for the next sync
+        let confirmed = match client.account_info(account).await {
+            Ok(info) => info
+                .confirmation_height
+                .as_deref()
+                .and_then(|height| height.parse::<u64>().ok())
+                .ok_or_else(|| {
+                    Error::Rpc(RpcError::InvalidResponse(
+                        "account_info is missing confirmation_height".to_string(),
+                    ))
+                })?,
+            // Unopened accounts have no history yet
+            Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => {
+                return Ok(0);
+            }
+            Err(e) => return Err(e),
+        };
+        if confirmed <= synced {
+            return Ok(0);
+        }
+
+        let mut entries = Vec::new();
+        let mut head: Option<BlockHash> = None;
+        loop {
+            let response = match &head {
+                Some(head) => {
+                    client
+                        .account_history_from(account, self.page_size, head)
+                        .await
+                }
+                None => client.account_history(account, self.page_size).await,
+            };
+            let response = match response {
+                Ok(response) => response,
+                // Unopened accounts have no history yet
+                Err(ref e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => {
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let page_len = response.history.len() as u64;
+            let mut reached_synced = false;
+            for entry in response.history {
+                if entry_height(&entry)? <= synced {
+                    reached_synced = true;
+                    break;
+                }
+                entries.push(entry);
+            }
+
+            head = response.previous.filter(|p| !p.is_zero());
+            if reached_synced || head.is_none() || page_len < self.page_size {
+                break;
+            }
+        }
+
+        self.apply(account, entries, confirmed)
+    }
+
+    /// Sync every tracked account.
+    ///
+    /// Returns the total number of new records.
+    pub async fn sync_all(&self, client: &RpcClient) -> Result<usize> {
+        let mut added = 0;
+        for account in self.tracked()? {
+            added += self.sync(client, &account).await?;
+        }
+        Ok(added)
+    }
+
+    /// Bring the history up to date after a WebSocket confirmation.
+    ///
+    /// Syncs the block's account if it is tracked and the block is a send
+    /// or receive; anything else is ignored. Subscribe with
+    /// [`include_block`](crate::websocket::SubscriptionBuilder::include_block)
+    /// so changes can be skipped without a request.
+    #[cfg(feature = "websocket")]
+    pub async fn ingest(
+        &self,
+        client: &RpcClient,
+        confirmation: &ConfirmationMessage,
+    ) -> Result<usize> {
+        if self.synced_height(&confirmation.account)?.is_none() {
+            return Ok(0);
+        }
+        let moves_funds = match &confirmation.block {
+            Some(block) => matches!(
+                block.subtype.as_deref(),
+                Some("send" | "receive" | "open") | None
+            ),
+            None => true,
+        };
+        if !moves_funds {
+            return Ok(0);
+        }
+        self.sync(client, &confirmation.account).await
+    }
+
+    /// Get the record of a block.
+    pub fn get(&self, hash: &BlockHash) -> Result<Option<HistoryRecord>> {
+        let sql = alloc::format!("SELECT {} FROM transactions WHERE hash = ?1", COLUMNS);
+        self.lock()
+            .query_row(&sql, params![hash.to_hex()], read_row)
+            .optional()
+            .map_err(db_error)?
+            .transpose()
+    }
+
+    /// Get the records matching `query`, newest first.
+    pub fn query(&self, query: &HistoryQuery) -> Result<Vec<HistoryRecord>> {
+        let (filter, mut values) = query.filter();
+        let sql = alloc::format!(
+            "SELECT {} FROM transactions{} ORDER BY timestamp DESC, height DESC LIMIT ? OFFSET ?",
+            COLUMNS,
+            filter
+        );
+        values.push(Value::Integer(query.limit.map_or(-1, to_sql_int)));
+        values.push(Value::Integer(to_sql_int(query.offset)));
+
+        let conn = self.lock();
+        let mut stmt = conn.prepare(&sql).map_err(db_error)?;
+        let rows = stmt
+            .query_map(params_from_iter(values), read_row)
+            .map_err(db_error)?;
+        rows.map(|row| row.map_err(db_error)?).collect()
+    }
+
+    /// Count the records matching `query`, ignoring its limit and offset.
+    pub fn count(&self, query: &HistoryQuery) -> Result<u64> {
+        let (filter, values) = query.filter();
+        let sql = alloc::format!("SELECT COUNT(*) FROM transactions{}", filter);
+        self.lock()
+            .query_row(&sql, params_from_iter(values), |row| row.get::<_, i64>(0))
+            .map(|count| count as u64)
+            .map_err(db_error)
+    }
+
+    /// Store entries fetched newest first and advance the synced height.
+    ///
+    /// Stops at the first entry above `confirmed_height` so it is fetched
+    /// again later.
+    fn apply(
+        &self,
+        account: &Account,
+        entries: Vec<AccountHistoryEntry>,
+        confirmed_height: u64,
+    ) -> Result<usize> {
+        let mut conn = self.lock();
+        let tx = conn.transaction().map_err(db_error)?;
+        let mut added = 0;
+        let mut height = None;
+        for entry in entries.into_iter().rev() {
+            let entry_height = entry_height(&entry)?;
+            if entry_height > confirmed_height {
+                break;
+            }
+            height = Some(entry_height);
+            let Some(kind) = HistoryKind::parse(&entry.block_type) else {
+                continue;
+            };
+            added += tx
+                .execute(
+                    "INSERT OR IGNORE INTO transactions (account, hash, kind, amount, counterparty, timestamp, height)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        account.as_str(),
+                        entry.hash.to_hex(),
+                        kind.as_str(),
+                        entry.amount.to_be_bytes().as_slice(),
+                        entry.account.as_str(),
+                        to_sql_int(entry.local_timestamp.parse().unwrap_or(0)),
+                        to_sql_int(entry_height),
+                    ],
+                )
+                .map_err(db_error)?;
+        }
+        if let Some(height) = height {
+            tx.execute(
+                "UPDATE accounts SET height = MAX(height, ?2) WHERE account = ?1",
+                params![account.as_str(), to_sql_int(height)],
+            )
+            .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+        Ok(added)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn read_row(row: &Row<'_>) -> rusqlite::Result<Result<HistoryRecord>> {
+    let account: String = row.get(0)?;
+    let hash: String = row.get(1)?;
+    let kind: String = row.get(2)?;
+    let amount: Vec<u8> = row.get(3)?;
+    let counterparty: String = row.get(4)?;
+    let timestamp: i64 = row.get(5)?;
+    let height: i64 = row.get(6)?;
+    Ok((|| {
+        let amount: [u8; 16] = amount
+            .try_into()
+            .map_err(|_| corrupt("amount is not 16 bytes"))?;
+        Ok(HistoryRecord {
+            account: parse_account(&account)?,
+            hash: BlockHash::from_hex(&hash)?,
+            kind: HistoryKind::parse(&kind).ok_or_else(|| corrupt("unknown kind"))?,
+            amount: Raw::from_be_bytes(amount),
+            counterparty: parse_account(&counterparty)?,
+            timestamp: timestamp as u64,
+            height: height as u64,
+        })
+    })())
+}
+
+fn entry_height(entry: &AccountHistoryEntry) -> Result<u64> {
+    entry
+        .height
+        .parse()
+        .map_err(|_| corrupt("history entry has an invalid height"))
+}
+
+fn parse_account(address: &str) -> Result<Account> {
+    address.parse()
+}
+
+/// SQLite integers are signed; heights and timestamps never reach `i64::MAX`.
+fn to_sql_int(value: u64) -> i64 {
+    value.min(i64::MAX as u64) as i64
+}
+
+fn corrupt(msg: &str) -> Error {
+    Error::Storage(StorageError::Serialization(msg.to_string()))
+}
+
+fn db_error(e: rusqlite::Error) -> Error {
+    Error::Storage(StorageError::Database(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::Seed;
+
+    fn entry(kind: &str, counterparty: &Account, amount: u128, height: u64) -> AccountHistoryEntry {
+        serde_json::from_value(serde_json::json!({
+            "type": kind,
+            "account": counterparty.as_str(),
+            "amount": amount.to_string(),
+            "local_timestamp": (1_700_000_000 + height * 60).to_string(),
+            "height": height.to_string(),
+            "hash": BlockHash::from_bytes([height as u8; 32]).to_hex(),
+        }))
+        .unwrap()
+    }
+
+    fn accounts() -> (Account, Account, Account) {
+        let seed = Seed::from_bytes([9u8; 32]);
+        (
+            seed.derive(0).account(),
+            seed.derive(1).account(),
+            seed.derive(2).account(),
+        )
+    }
+
+    #[test]
+    fn test_apply_and_query() {
+        let (account, alice, bob) = accounts();
+        let store = HistoryStore::open_in_memory().unwrap();
+        store.track(&account).unwrap();
+
+        // Newest first, as returned by account_history
+        let entries = vec![
+            entry("send", &bob, 300, 3),
+            entry("send", &alice, 200, 2),
+            entry("receive", &alice, 1_000, 1),
+        ];
+        assert_eq!(store.apply(&account, entries.clone(), 3).unwrap(), 3);
+        assert_eq!(store.apply(&account, entries, 3).unwrap(), 0);
+        assert_eq!(store.synced_height(&account).unwrap(), Some(3));
+
+        let all = store.query(&HistoryQuery::new().account(&account)).unwrap();
+        assert_eq!(
+            all.iter().map(|r| r.height).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(all[2].kind, HistoryKind::Receive);
+        assert_eq!(all[2].amount, Raw::new(1_000));
+        assert_eq!(all[2].counterparty, alice);
+        assert_eq!(all[2].timestamp, 1_700_000_060);
+
+        let sends = HistoryQuery::new().kind(HistoryKind::Send);
+        assert_eq!(store.count(&sends).unwrap(), 2);
+        let with_alice = HistoryQuery::new().counterparty(&alice);
+        assert_eq!(store.count(&with_alice).unwrap(), 2);
+        let large = HistoryQuery::new().min_amount(Raw::new(250));
+        assert_eq!(store.count(&large).unwrap(), 2);
+        let recent = HistoryQuery::new().since(1_700_000_120);
+        assert_eq!(store.count(&recent).unwrap(), 2);
+
+        let page = store
+            .query(&HistoryQuery::new().limit(1).offset(1))
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].height, 2);
+        assert_eq!(store.get(&page[0].hash).unwrap(), Some(page[0].clone()));
+    }
+
+    #[test]
+    fn test_apply_stops_above_confirmation_height() {
+        let (account, alice, _) = accounts();
+        let store = HistoryStore::open_in_memory().unwrap();
+        store.track(&account).unwrap();
+
+        let pending = entry("send", &alice, 5, 2);
+        let added = store
+            .apply(&account, vec![pending, entry("receive", &alice, 10, 1)], 1)
+            .unwrap();
+
+        assert_eq!(added, 1);
+        assert_eq!(store.synced_height(&account).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn test_track_and_forget() {
+        let (account, alice, _) = accounts();
+        let store = HistoryStore::open_in_memory().unwrap();
+        assert_eq!(store.synced_height(&account).unwrap(), None);
+
+        store.track(&account).unwrap();
+        store.track(&account).unwrap();
+        assert_eq!(store.tracked().unwrap(), vec![account.clone()]);
+        store
+            .apply(&account, vec![entry("receive", &alice, 10, 1)], 1)
+            .unwrap();
+
+        store.forget(&account).unwrap();
+        assert!(store.tracked().unwrap().is_empty());
+        assert_eq!(store.count(&HistoryQuery::new()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_reopen_file() {
+        let (account, alice, _) = accounts();
+        let path =
+            std::env::temp_dir().join(alloc::format!("xno-history-{}.sqlite", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let store = HistoryStore::open(&path).unwrap();
+            store.track(&account).unwrap();
+            store
+                .apply(&account, vec![entry("receive", &alice, 10, 1)], 1)
+                .unwrap();
+        }
+        let store = HistoryStore::open(&path).unwrap();
+        assert_eq!(store.synced_height(&account).unwrap(), Some(1));
+        assert_eq!(store.count(&HistoryQuery::new()).unwrap(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
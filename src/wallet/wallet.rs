@@ -1,19 +1,123 @@
 //! High-level wallet implementation.
 
+use alloc::string::String;
 use alloc::vec::Vec;
+#[cfg(feature = "rpc")]
+use core::future::Future;
+#[cfg(feature = "rpc")]
+use core::ops::Range;
 
 use crate::error::Result;
+#[cfg(feature = "encryption")]
+use crate::keys::{EncryptedSeed, KdfParams};
 use crate::keys::{KeyPair, Seed};
 use crate::types::Account;
-use crate::wallet::WalletAccount;
+use crate::wallet::{WalletAccount, WalletMetadata};
+#[cfg(feature = "encryption")]
+use zeroize::Zeroize;
+
+#[cfg(feature = "rpc")]
+use crate::error::{AmountError, Error, NodeErrorKind};
+#[cfg(feature = "rpc")]
+use crate::rpc::{
+    AccountBalanceRequest, AccountBalanceResponse, AccountsBalancesRequest, RpcClient,
+};
+#[cfg(feature = "rpc")]
+use crate::types::{BlockHash, Raw};
+
+/// Consecutive empty accounts after which a sweep stops scanning.
+#[cfg(feature = "rpc")]
+pub const SWEEP_GAP_LIMIT: u32 = 20;
+
+/// Outcome of sweeping one derived account.
+#[cfg(feature = "rpc")]
+#[derive(Debug)]
+pub struct SweepResult {
+    /// Derivation index.
+    pub index: u32,
+    /// Swept account.
+    pub account: Account,
+    /// Hashes of the receive blocks published.
+    pub received: Vec<BlockHash>,
+    /// Hash of the send block to the destination, if one was published.
+    pub sent: Option<BlockHash>,
+    /// Amount sent to the destination.
+    pub amount: Raw,
+    /// Error that stopped this account's sweep, if any.
+    pub error: Option<Error>,
+}
+
+#[cfg(feature = "rpc")]
+impl SweepResult {
+    /// Check if the account was swept without error.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
 
+/// Outcome of moving one derived account to a representative.
 #[cfg(feature = "rpc")]
-use crate::rpc::RpcClient;
+#[derive(Debug)]
+pub struct RepresentativeChange {
+    /// Derivation index.
+    pub index: u32,
+    /// Account whose representative was changed.
+    pub account: Account,
+    /// Balance delegated by the account.
+    pub balance: Raw,
+    /// Representative before the change.
+    pub previous: Option<Account>,
+    /// Representative the account was assigned.
+    pub representative: Account,
+    /// Hash of the change block, or `None` if the account already had the
+    /// representative or the change failed.
+    pub hash: Option<BlockHash>,
+    /// Error that stopped the change, if any.
+    pub error: Option<Error>,
+}
+
+#[cfg(feature = "rpc")]
+impl RepresentativeChange {
+    /// Check if the account was moved, or already had the representative.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Balance of one account in a [`WalletBalance`].
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountBalance {
+    /// Derivation index, or `None` for a watch-only account.
+    pub index: Option<u32>,
+    /// Account address.
+    pub account: Account,
+    /// Confirmed balance.
+    pub balance: Raw,
+    /// Receivable amount.
+    pub receivable: Raw,
+}
+
+/// Balances of a wallet's accounts and their sums.
+#[cfg(feature = "rpc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletBalance {
+    /// Per-account balances, derived accounts first.
+    pub accounts: Vec<AccountBalance>,
+    /// Sum of the balances.
+    pub balance: Raw,
+    /// Sum of the receivable amounts.
+    pub receivable: Raw,
+}
 
 /// High-level wallet for managing Nano accounts.
 ///
 /// A wallet is created from a seed and can derive multiple accounts.
 ///
+/// With the `encryption` feature, a wallet with a password can be locked
+/// into a [`LockedWallet`], which keeps only the encrypted seed and the
+/// metadata until it is unlocked again.
+///
 /// # Example
 ///
 /// ```
@@ -34,6 +138,9 @@ use crate::rpc::RpcClient;
 pub struct Wallet {
     seed: Seed,
     derived_accounts: Vec<KeyPair>,
+    #[cfg(feature = "encryption")]
+    encrypted: Option<EncryptedSeed>,
+    metadata: WalletMetadata,
 }
 
 impl Wallet {
@@ -42,6 +149,9 @@ impl Wallet {
         Wallet {
             seed,
             derived_accounts: Vec::new(),
+            #[cfg(feature = "encryption")]
+            encrypted: None,
+            metadata: WalletMetadata::new(),
         }
     }
 
@@ -58,6 +168,35 @@ impl Wallet {
         Ok(Wallet::from_seed(seed))
     }
 
+    /// Set labels, address book and metadata, e.g. loaded from storage.
+    pub fn with_metadata(mut self, metadata: WalletMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Get the labels, address book and metadata.
+    ///
+    /// Available while the wallet is locked.
+    pub fn metadata(&self) -> &WalletMetadata {
+        &self.metadata
+    }
+
+    /// Get the labels, address book and metadata for editing.
+    pub fn metadata_mut(&mut self) -> &mut WalletMetadata {
+        &mut self.metadata
+    }
+
+    /// Set the label of the account at the given index. An empty label
+    /// removes it.
+    pub fn set_label(&mut self, index: u32, label: impl Into<String>) {
+        self.metadata.set_label(index, label);
+    }
+
+    /// Get the label of the account at the given index.
+    pub fn label(&self, index: u32) -> Option<&str> {
+        self.metadata.label(index)
+    }
+
     /// Get the wallet seed.
     ///
     /// Handle with care - this exposes the secret seed.
@@ -65,14 +204,67 @@ impl Wallet {
         &self.seed
     }
 
+    /// Set or replace the password used to lock the wallet.
+    ///
+    /// The wallet stays unlocked until [`lock`](Self::lock) is called.
+    #[cfg(feature = "encryption")]
+    pub fn set_password(&mut self, password: &[u8]) -> Result<()> {
+        let encrypted = EncryptedSeed::encrypt(&self.seed, password)?;
+        self.encrypted = Some(encrypted);
+        Ok(())
+    }
+
+    /// [`set_password`](Self::set_password) with custom key derivation
+    /// parameters.
+    #[cfg(feature = "encryption")]
+    pub fn set_password_with_params(&mut self, password: &[u8], params: KdfParams) -> Result<()> {
+        let encrypted = EncryptedSeed::encrypt_with_params(&self.seed, password, params)?;
+        self.encrypted = Some(encrypted);
+        Ok(())
+    }
+
+    /// Get the encrypted seed, if a password is set.
+    #[cfg(feature = "encryption")]
+    pub fn encrypted_seed(&self) -> Option<&EncryptedSeed> {
+        self.encrypted.as_ref()
+    }
+
+    /// Lock the wallet, zeroing the seed and derived keypairs.
+    ///
+    /// Gives the wallet back unchanged if no password is set, since it
+    /// could never be unlocked again. [`WalletAccount`]s obtained before
+    /// locking keep their own copy of the keypair.
+    #[cfg(feature = "encryption")]
+    #[allow(clippy::result_large_err)]
+    pub fn lock(mut self) -> core::result::Result<LockedWallet, Self> {
+        let Some(encrypted) = self.encrypted.take() else {
+            return Err(self);
+        };
+        // The seed zeroes itself when dropped with the wallet
+        self.derived_accounts.zeroize();
+        Ok(LockedWallet {
+            encrypted,
+            metadata: core::mem::take(&mut self.metadata),
+        })
+    }
+
     /// Get or derive the keypair at the given index.
     fn get_keypair(&mut self, index: u32) -> &KeyPair {
         let index_usize = index as usize;
+        let seed = &self.seed;
 
         // Derive any missing keypairs up to the requested index
-        while self.derived_accounts.len() <= index_usize {
-            let keypair = self.seed.derive(self.derived_accounts.len() as u32);
-            self.derived_accounts.push(keypair);
+        if self.derived_accounts.len() <= index_usize {
+            let start = self.derived_accounts.len() as u32;
+            let derived = seed.derive_range(start, index + 1);
+            if self.derived_accounts.capacity() < index_usize + 1 {
+                // Reallocating would leave copies of the keys in the freed
+                // buffer; clone instead so the old buffer is wiped on drop
+                let mut grown = Vec::with_capacity((index_usize + 1).next_power_of_two());
+                grown.extend(self.derived_accounts.iter().cloned());
+                self.derived_accounts = grown;
+            }
+            self.derived_accounts.extend(derived.iter().cloned());
         }
 
         &self.derived_accounts[index_usize]
@@ -91,6 +283,10 @@ impl Wallet {
 
     /// Get multiple account addresses.
     pub fn addresses(&mut self, count: u32) -> Vec<Account> {
+        if count > 0 {
+            // Derive the missing keypairs in one batch
+            self.keypair(count - 1);
+        }
         (0..count).map(|i| self.address(i)).collect()
     }
 
@@ -103,7 +299,7 @@ impl Wallet {
 
     // ==================== RPC-dependent methods ====================
 
-    /// Get the balance of an account.
+    /// Get the balance of an account, counting only confirmed blocks.
     #[cfg(feature = "rpc")]
     pub async fn balance(
         &mut self,
@@ -111,7 +307,9 @@ impl Wallet {
         client: &RpcClient,
     ) -> Result<crate::rpc::AccountBalanceResponse> {
         let account = self.address(index);
-        client.account_balance(&account).await
+        client
+            .account_balance_with(AccountBalanceRequest::new(&account).confirmed_only())
+            .await
     }
 
     /// Get account info.
@@ -125,7 +323,19 @@ impl Wallet {
         client.account_info(&account).await
     }
 
-    /// Get account history.
+    /// Get the ledger and confirmed state of an account.
+    #[cfg(feature = "rpc")]
+    pub async fn confirmed_state(
+        &mut self,
+        index: u32,
+        client: &RpcClient,
+    ) -> Result<crate::wallet::ConfirmedState> {
+        let account = self.address(index);
+        crate::wallet::ConfirmedState::fetch(client, &account).await
+    }
+
+    /// Get up to `count` confirmed history entries of an account, newest
+    /// first.
     #[cfg(feature = "rpc")]
     pub async fn history(
         &mut self,
@@ -134,13 +344,452 @@ impl Wallet {
         client: &RpcClient,
     ) -> Result<crate::rpc::AccountHistoryResponse> {
         let account = self.address(index);
-        client.account_history(&account, count).await
+        super::confirmed::confirmed_history(client, &account, count).await
+    }
+
+    /// Get the confirmed balances of the derived accounts in `range` and
+    /// of the watch-only accounts, with one `accounts_balances` request.
+    ///
+    /// Accounts the node does not report, such as unopened ones, count as
+    /// zero. Fails with [`AmountError::Overflow`](crate::error::AmountError::Overflow)
+    /// if a sum exceeds the raw range.
+    ///
+    /// # Arguments
+    /// * `range` - Derivation indices to include
+    /// * `client` - RPC client
+    #[cfg(feature = "rpc")]
+    pub async fn total_balance(
+        &mut self,
+        range: Range<u32>,
+        client: &RpcClient,
+    ) -> Result<WalletBalance> {
+        let mut accounts = Vec::new();
+        for index in range {
+            accounts.push((Some(index), self.address(index)));
+        }
+        for account in self.metadata.watch_only() {
+            accounts.push((None, account.clone()));
+        }
+        if accounts.is_empty() {
+            return Ok(WalletBalance {
+                accounts: Vec::new(),
+                balance: Raw::ZERO,
+                receivable: Raw::ZERO,
+            });
+        }
+
+        let request: Vec<Account> = accounts.iter().map(|(_, a)| a.clone()).collect();
+        let response = client
+            .accounts_balances_with(AccountsBalancesRequest::new(&request).confirmed_only())
+            .await?;
+        let reported: Vec<(Account, AccountBalanceResponse)> = response
+            .balances
+            .into_iter()
+            .filter_map(|(address, balance)| Some((address.parse().ok()?, balance)))
+            .collect();
+
+        let accounts: Vec<AccountBalance> = accounts
+            .into_iter()
+            .map(|(index, account)| {
+                let found = reported
+                    .iter()
+                    .find(|(a, _)| a.public_key() == account.public_key())
+                    .map(|(_, b)| b);
+                AccountBalance {
+                    index,
+                    balance: found.map_or(Raw::ZERO, |b| b.balance),
+                    receivable: found.map_or(Raw::ZERO, |b| b.receivable.unwrap_or(b.pending)),
+                    account,
+                }
+            })
+            .collect();
+
+        let overflow = || Error::InvalidAmount(AmountError::Overflow);
+        let balance = Raw::checked_sum(accounts.iter().map(|a| a.balance)).ok_or_else(overflow)?;
+        let receivable =
+            Raw::checked_sum(accounts.iter().map(|a| a.receivable)).ok_or_else(overflow)?;
+        Ok(WalletBalance {
+            accounts,
+            balance,
+            receivable,
+        })
+    }
+
+    /// Move all funds from this wallet's accounts to a destination.
+    ///
+    /// Scans derived accounts from index 0 until [`SWEEP_GAP_LIMIT`]
+    /// consecutive accounts hold no balance or receivables. Each funded
+    /// account pockets its receivables and then sends its full balance.
+    /// A failure on one account is recorded in its result and the sweep
+    /// continues with the next.
+    ///
+    /// # Arguments
+    /// * `destination` - Account receiving the funds
+    /// * `client` - RPC client
+    #[cfg(feature = "rpc")]
+    pub async fn sweep(
+        &mut self,
+        destination: &Account,
+        client: &RpcClient,
+    ) -> Result<Vec<SweepResult>> {
+        self.sweep_with_gap(destination, SWEEP_GAP_LIMIT, client)
+            .await
+    }
+
+    /// [`sweep`](Self::sweep) with a custom gap limit.
+    ///
+    /// # Arguments
+    /// * `destination` - Account receiving the funds
+    /// * `gap_limit` - Consecutive empty accounts after which scanning stops
+    /// * `client` - RPC client
+    #[cfg(feature = "rpc")]
+    pub async fn sweep_with_gap(
+        &mut self,
+        destination: &Account,
+        gap_limit: u32,
+        client: &RpcClient,
+    ) -> Result<Vec<SweepResult>> {
+        sweep_scan(
+            gap_limit,
+            destination,
+            |index| self.account(index),
+            |address| async move {
+                let balance = client.account_balance(&address).await?;
+                Ok((
+                    balance.balance,
+                    balance.receivable.unwrap_or(balance.pending),
+                ))
+            },
+            |account| async move { sweep_account(&account, destination, client).await },
+        )
+        .await
+    }
+
+    /// Move every funded account of the wallet to one representative.
+    ///
+    /// Scans derived accounts from index 0 until [`SWEEP_GAP_LIMIT`]
+    /// consecutive accounts are unopened, and publishes a change block,
+    /// with change work, on each account that holds a balance and has
+    /// another representative. Returns a result for every funded account.
+    /// A failure on one account is recorded in its result and the others
+    /// continue.
+    ///
+    /// # Arguments
+    /// * `new_representative` - Representative for all accounts
+    /// * `client` - RPC client
+    #[cfg(feature = "rpc")]
+    pub async fn change_all_representatives(
+        &mut self,
+        new_representative: &Account,
+        client: &RpcClient,
+    ) -> Result<Vec<RepresentativeChange>> {
+        self.distribute_representatives(&[(new_representative.clone(), 1)], client)
+            .await
+    }
+
+    /// Split the funded accounts of the wallet across representatives.
+    ///
+    /// Like [`change_all_representatives`](Self::change_all_representatives),
+    /// but each representative is given accounts holding about its share
+    /// of the wallet's balance, by relative weight. Accounts stay with
+    /// their current representative while it is under its share, so
+    /// repeating a distribution publishes few blocks. Representatives with
+    /// zero weight get no accounts; without any positive weight nothing
+    /// changes.
+    ///
+    /// # Arguments
+    /// * `representatives` - Representatives with their relative weights
+    /// * `client` - RPC client
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use xno_connect::rpc::RpcClient;
+    /// use xno_connect::wallet::Wallet;
+    ///
+    /// # async fn example(mut wallet: Wallet) -> xno_connect::error::Result<()> {
+    /// let client = RpcClient::new("http://localhost:7076");
+    /// let primary = "nano_1abc...".parse()?;
+    /// let backup = "nano_3xyz...".parse()?;
+    /// for change in wallet
+    ///     .distribute_representatives(&[(primary, 3), (backup, 1)], &client)
+    ///     .await?
+    /// {
+    ///     if let Some(error) = &change.error {
+    ///         println!("account {} failed: {}", change.index, error);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rpc")]
+    pub async fn distribute_representatives(
+        &mut self,
+        representatives: &[(Account, u32)],
+        client: &RpcClient,
+    ) -> Result<Vec<RepresentativeChange>> {
+        let mut funded = Vec::new();
+        let mut unopened = 0;
+        let mut index = 0;
+        while unopened < SWEEP_GAP_LIMIT {
+            let account = self.account(index);
+            match account.info(client).await {
+                Ok(info) => {
+                    unopened = 0;
+                    if !info.balance.is_zero() {
+                        funded.push((account, info.balance, info.representative));
+                    }
+                }
+                Err(e) if e.node_error_kind() == Some(NodeErrorKind::AccountNotFound) => {
+                    unopened += 1;
+                }
+                Err(e) => return Err(e),
+            }
+            index += 1;
+        }
+
+        let current: Vec<(Raw, Option<&Account>)> = funded
+            .iter()
+            .map(|(_, balance, representative)| (*balance, representative.as_ref()))
+            .collect();
+        let assigned = assign_representatives(&current, representatives);
+
+        let mut results = Vec::new();
+        for ((account, balance, previous), target) in funded.into_iter().zip(assigned) {
+            let Some(target) = target else {
+                continue;
+            };
+            let mut result = RepresentativeChange {
+                index: account.index(),
+                account: account.address(),
+                balance,
+                representative: representatives[target].0.clone(),
+                previous,
+                hash: None,
+                error: None,
+            };
+            if result.previous.as_ref() != Some(&result.representative) {
+                match account
+                    .change_representative(&result.representative, client)
+                    .await
+                {
+                    Ok(response) => result.hash = Some(response.hash),
+                    Err(e) => result.error = Some(e),
+                }
+            }
+            results.push(result);
+        }
+        Ok(results)
+    }
+}
+
+/// A wallet locked by [`Wallet::lock`].
+///
+/// Holds the encrypted seed and the metadata but no key material.
+/// [`unlock`](Self::unlock) leaves it intact, so a wrong password can be
+/// retried.
+#[cfg(feature = "encryption")]
+#[derive(Debug, Clone)]
+pub struct LockedWallet {
+    encrypted: EncryptedSeed,
+    metadata: WalletMetadata,
+}
+
+#[cfg(feature = "encryption")]
+impl LockedWallet {
+    /// Create a locked wallet from an encrypted seed.
+    pub fn new(encrypted: EncryptedSeed) -> Self {
+        LockedWallet {
+            encrypted,
+            metadata: WalletMetadata::new(),
+        }
+    }
+
+    /// Set labels, address book and metadata, e.g. loaded from storage.
+    pub fn with_metadata(mut self, metadata: WalletMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Get the labels, address book and metadata.
+    pub fn metadata(&self) -> &WalletMetadata {
+        &self.metadata
+    }
+
+    /// Get the labels, address book and metadata for editing.
+    pub fn metadata_mut(&mut self) -> &mut WalletMetadata {
+        &mut self.metadata
+    }
+
+    /// Get the label of the account at the given index.
+    pub fn label(&self, index: u32) -> Option<&str> {
+        self.metadata.label(index)
+    }
+
+    /// Get the encrypted seed.
+    pub fn encrypted_seed(&self) -> &EncryptedSeed {
+        &self.encrypted
+    }
+
+    /// Decrypt the seed and return the unlocked wallet.
+    ///
+    /// Fails with [`EncryptionError::DecryptionFailed`](crate::error::EncryptionError::DecryptionFailed)
+    /// if the password is wrong.
+    pub fn unlock(&self, password: &[u8]) -> Result<Wallet> {
+        let seed = self.encrypted.decrypt(password)?;
+        Ok(Wallet {
+            seed,
+            derived_accounts: Vec::new(),
+            encrypted: Some(self.encrypted.clone()),
+            metadata: self.metadata.clone(),
+        })
     }
 }
 
+/// Pick a representative for each account so that each representative's
+/// share of the total balance follows its weight.
+///
+/// Accounts are placed largest first. An account keeps its current
+/// representative while that stays within the representative's share;
+/// otherwise it goes to the representative furthest below its share.
+/// Returns indices into `representatives`, or `None` for every account
+/// if no weight is positive.
+#[cfg(feature = "rpc")]
+fn assign_representatives(
+    accounts: &[(Raw, Option<&Account>)],
+    representatives: &[(Account, u32)],
+) -> Vec<Option<usize>> {
+    let mut assigned = alloc::vec![None; accounts.len()];
+    let total_weight: f64 = representatives.iter().map(|(_, w)| f64::from(*w)).sum();
+    if total_weight == 0.0 {
+        return assigned;
+    }
+    // Shares only need to be roughly even, so floats are precise enough
+    let total: f64 = accounts.iter().map(|(b, _)| b.as_u128() as f64).sum();
+    let share = |rep: usize| total * f64::from(representatives[rep].1) / total_weight;
+    let mut delegated = alloc::vec![0.0; representatives.len()];
+
+    let mut order: Vec<usize> = (0..accounts.len()).collect();
+    order.sort_by(|&a, &b| accounts[b].0.cmp(&accounts[a].0));
+    for i in order {
+        let (balance, current) = accounts[i];
+        let balance = balance.as_u128() as f64;
+        let current = current.and_then(|current| {
+            representatives
+                .iter()
+                .position(|(rep, weight)| rep == current && *weight > 0)
+        });
+        let rep = match current {
+            Some(rep) if delegated[rep] + balance <= share(rep) => rep,
+            _ => (0..representatives.len())
+                .filter(|&rep| representatives[rep].1 > 0)
+                .min_by(|&a, &b| {
+                    let fill = |rep: usize| (delegated[rep] + balance) / share(rep);
+                    fill(a).total_cmp(&fill(b))
+                })
+                .expect("a representative has positive weight"),
+        };
+        delegated[rep] += balance;
+        assigned[i] = Some(rep);
+    }
+    assigned
+}
+
+/// Scan accounts from index 0 and sweep each funded one other than
+/// `destination`, until `gap_limit` consecutive accounts hold no balance or
+/// receivables.
+///
+/// `balance` returns an account's balance and receivables; its errors abort
+/// the scan.
+#[cfg(feature = "rpc")]
+async fn sweep_scan<B, BF, S, SF>(
+    gap_limit: u32,
+    destination: &Account,
+    mut derive: impl FnMut(u32) -> WalletAccount,
+    mut balance: B,
+    mut sweep: S,
+) -> Result<Vec<SweepResult>>
+where
+    B: FnMut(Account) -> BF,
+    BF: Future<Output = Result<(Raw, Raw)>>,
+    S: FnMut(WalletAccount) -> SF,
+    SF: Future<Output = SweepResult>,
+{
+    let mut results = Vec::new();
+    let mut empty = 0;
+    let mut index = 0;
+
+    while empty < gap_limit {
+        let account = derive(index);
+        let address = account.address();
+        let (balance, receivable) = balance(address.clone()).await?;
+
+        if balance.is_zero() && receivable.is_zero() {
+            empty += 1;
+        } else {
+            empty = 0;
+            if address != *destination {
+                results.push(sweep(account).await);
+            }
+        }
+        index += 1;
+    }
+
+    Ok(results)
+}
+
+/// Pocket receivables on one account and send its full balance.
+#[cfg(feature = "rpc")]
+pub(super) async fn sweep_account(
+    account: &WalletAccount,
+    destination: &Account,
+    client: &RpcClient,
+) -> SweepResult {
+    let mut result = SweepResult {
+        index: account.index(),
+        account: account.address(),
+        received: Vec::new(),
+        sent: None,
+        amount: Raw::ZERO,
+        error: None,
+    };
+
+    match account.receive_all(client).await {
+        Ok(received) => result.received = received,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    }
+
+    let balance = match account.info(client).await {
+        Ok(info) => info.balance,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    if balance.is_zero() {
+        return result;
+    }
+
+    match account.send(destination, balance, client).await {
+        Ok(response) => {
+            result.sent = Some(response.hash);
+            result.amount = balance;
+        }
+        Err(e) => result.error = Some(e),
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "rpc")]
+    use crate::error::RpcError;
+    #[cfg(feature = "encryption")]
+    use crate::error::{EncryptionError, Error};
 
     const TEST_SEED: &str = "0000000000000000000000000000000000000000000000000000000000000000";
 
@@ -206,6 +855,21 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_wallet_labels() {
+        let mut wallet = Wallet::from_hex_seed(TEST_SEED).unwrap();
+        wallet.set_label(0, "Main");
+        wallet.metadata_mut().set("currency", "EUR");
+
+        let json = wallet.metadata().to_json().unwrap();
+        let restored = Wallet::from_hex_seed(TEST_SEED)
+            .unwrap()
+            .with_metadata(WalletMetadata::from_json(&json).unwrap());
+        assert_eq!(restored.label(0), Some("Main"));
+        assert_eq!(restored.metadata().get("currency"), Some("EUR"));
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn test_wallet_new_random() {
@@ -217,4 +881,148 @@ mod tests {
         let mut w2 = wallet2;
         assert_ne!(w1.address(0), w2.address(0));
     }
+
+    #[cfg(feature = "encryption")]
+    const PARAMS: KdfParams = KdfParams::new(1024, 1, 1);
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_wallet_lock_and_unlock() {
+        let mut wallet = Wallet::from_hex_seed(TEST_SEED).unwrap();
+        let address = wallet.address(0);
+        wallet.set_label(0, "main");
+        wallet
+            .set_password_with_params(b"password", PARAMS)
+            .unwrap();
+
+        let locked = wallet.lock().ok().unwrap();
+        assert_eq!(locked.label(0), Some("main"));
+        assert_eq!(
+            locked.unlock(b"wrong").err().unwrap(),
+            Error::Encryption(EncryptionError::DecryptionFailed)
+        );
+
+        let mut wallet = locked.unlock(b"password").unwrap();
+        assert_eq!(wallet.address(0), address);
+        assert_eq!(wallet.label(0), Some("main"));
+        assert!(wallet.lock().is_ok());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_wallet_lock_without_password() {
+        let mut wallet = Wallet::from_hex_seed(TEST_SEED).unwrap();
+        let address = wallet.address(0);
+        let Err(mut wallet) = wallet.lock() else {
+            panic!("locked without a password");
+        };
+        assert_eq!(wallet.address(0), address);
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_locked_wallet_from_encrypted() {
+        let seed = Seed::from_hex(TEST_SEED).unwrap();
+        let encrypted = EncryptedSeed::encrypt_with_params(&seed, b"password", PARAMS).unwrap();
+        let wallet = LockedWallet::new(encrypted).unlock(b"password").unwrap();
+        assert_eq!(wallet.seed(), &seed);
+    }
+
+    #[cfg(feature = "rpc")]
+    #[test]
+    fn test_assign_representatives() {
+        let mut wallet = Wallet::from_hex_seed(TEST_SEED).unwrap();
+        let (a, b) = (wallet.address(10), wallet.address(11));
+        let reps = [(a.clone(), 3), (b.clone(), 1)];
+        let accounts = [
+            (Raw::new(50), None),
+            (Raw::new(300), Some(&b)),
+            (Raw::new(40), Some(&a)),
+            (Raw::new(10), Some(&b)),
+        ];
+        // 400 raw split 300 / 100: the largest account moves to a, which
+        // then has its share, so the others go to b
+        assert_eq!(
+            assign_representatives(&accounts, &reps),
+            [Some(1), Some(0), Some(1), Some(1)]
+        );
+
+        let balanced = [(Raw::new(300), Some(&a)), (Raw::new(100), Some(&b))];
+        assert_eq!(assign_representatives(&balanced, &reps), [Some(0), Some(1)]);
+        assert_eq!(
+            assign_representatives(&balanced, &[(a.clone(), 1)]),
+            [Some(0), Some(0)]
+        );
+        assert_eq!(
+            assign_representatives(&balanced, &[(a.clone(), 0), (b.clone(), 0)]),
+            [None, None]
+        );
+        assert!(assign_representatives(&[], &reps).is_empty());
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_sweep_scan() {
+        let mut wallet = Wallet::from_hex_seed(TEST_SEED).unwrap();
+        let destination = wallet.address(1);
+        let funded: Vec<(Account, (Raw, Raw))> = vec![
+            (wallet.address(0), (Raw::ZERO, Raw::new(5))),
+            (destination.clone(), (Raw::new(1), Raw::ZERO)),
+            (wallet.address(3), (Raw::new(20), Raw::ZERO)),
+            // Beyond the gap, never scanned
+            (wallet.address(7), (Raw::new(70), Raw::ZERO)),
+        ];
+        let mut scanned = Vec::new();
+
+        let results = sweep_scan(
+            2,
+            &destination,
+            |index| wallet.account(index),
+            |address| {
+                scanned.push(address.clone());
+                let found = funded.iter().find(|(a, _)| *a == address);
+                let balances = found.map_or((Raw::ZERO, Raw::ZERO), |(_, b)| *b);
+                async move { Ok(balances) }
+            },
+            |account| async move {
+                let index = account.index();
+                SweepResult {
+                    index,
+                    account: account.address(),
+                    received: Vec::new(),
+                    sent: None,
+                    amount: Raw::new(index as u128),
+                    error: (index == 3).then_some(Error::Rpc(RpcError::Timeout)),
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+        // Index 1 is the destination; 4 and 5 are empty and end the scan
+        assert_eq!(scanned.len(), 6);
+        let indices: Vec<u32> = results.iter().map(|r| r.index).collect();
+        assert_eq!(indices, [0, 3]);
+        assert!(results[0].is_ok());
+        assert!(!results[1].is_ok());
+        assert_eq!(results[1].amount, Raw::new(3));
+    }
+
+    #[cfg(feature = "rpc")]
+    #[tokio::test]
+    async fn test_sweep_scan_balance_error() {
+        let mut wallet = Wallet::from_hex_seed(TEST_SEED).unwrap();
+        let destination = wallet.address(1);
+
+        let result = sweep_scan(
+            2,
+            &destination,
+            |index| wallet.account(index),
+            |_| async { Err(Error::Rpc(RpcError::Timeout)) },
+            |_| async { unreachable!("nothing is funded") },
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Rpc(RpcError::Timeout))));
+    }
 }
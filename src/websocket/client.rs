@@ -1,12 +1,21 @@
 //! WebSocket client for Nano node communication.
 
 use alloc::string::{String, ToString};
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+use core::time::Duration;
 use futures_util::{SinkExt, StreamExt};
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+use std::time::Instant;
 use tokio_tungstenite_wasm::{connect, Message, WebSocketStream};
 
 use crate::error::{Error, Result, WebSocketError};
-use crate::websocket::messages::{IncomingMessage, ParsedMessage, SubscribeMessage};
-use crate::websocket::subscription::SubscriptionBuilder;
+use crate::metrics;
+use crate::trace;
+use crate::types::Account;
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+use crate::websocket::messages::AckMessage;
+use crate::websocket::messages::{IncomingMessage, ParsedMessage};
+use crate::websocket::subscription::{ConfirmationFilter, SubscriptionBuilder, Topic};
 
 /// Asynchronous WebSocket client for real-time Nano node updates.
 ///
@@ -38,6 +47,77 @@ use crate::websocket::subscription::SubscriptionBuilder;
 pub struct WebSocketClient {
     stream: WebSocketStream,
     url: String,
+    filter: ConfirmationFilter,
+    confirmation: Option<SubscriptionBuilder>,
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    keepalive: Keepalive,
+}
+
+/// Missed pongs tolerated before [`WebSocketClient::receive`] gives up.
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+pub const DEFAULT_MAX_MISSED_PONGS: u32 = 2;
+
+/// Ping bookkeeping for a connection.
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+#[derive(Debug, Clone)]
+struct Keepalive {
+    interval: Option<Duration>,
+    max_missed: u32,
+    last_ping: Instant,
+    outstanding: Option<Instant>,
+    missed: u32,
+    last_seen: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+#[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+impl Keepalive {
+    fn new(now: Instant) -> Self {
+        Keepalive {
+            interval: None,
+            max_missed: DEFAULT_MAX_MISSED_PONGS,
+            last_ping: now,
+            outstanding: None,
+            missed: 0,
+            last_seen: None,
+            latency: None,
+        }
+    }
+
+    /// When the next ping is due, if pings are enabled.
+    fn deadline(&self) -> Option<Instant> {
+        self.interval.map(|interval| self.last_ping + interval)
+    }
+
+    /// Record a ping sent at `now`.
+    ///
+    /// Fails with [`WebSocketError::Timeout`] if too many earlier pings
+    /// went unanswered.
+    fn ping(&mut self, now: Instant) -> Result<()> {
+        if self.outstanding.is_some() {
+            self.missed += 1;
+            if self.missed >= self.max_missed {
+                trace::log_warn!(missed = self.missed, "websocket keepalive timed out");
+                return Err(Error::WebSocket(WebSocketError::Timeout));
+            }
+        }
+        self.last_ping = now;
+        self.outstanding = Some(now);
+        Ok(())
+    }
+
+    /// Record any message from the node.
+    fn seen(&mut self, now: Instant) {
+        self.last_seen = Some(now);
+    }
+
+    /// Record a pong.
+    fn pong(&mut self, now: Instant) {
+        if let Some(sent) = self.outstanding.take() {
+            self.latency = Some(now.saturating_duration_since(sent));
+        }
+        self.missed = 0;
+    }
 }
 
 impl WebSocketClient {
@@ -47,11 +127,21 @@ impl WebSocketClient {
     /// * `url` - WebSocket URL (e.g., "ws://localhost:7078")
     pub async fn connect(url: impl Into<String>) -> Result<Self> {
         let url = url.into();
-        let stream = connect(&url)
-            .await
-            .map_err(|e| Error::WebSocket(WebSocketError::ConnectionFailed(e.to_string())))?;
+        trace::stopwatch!(stopwatch);
+        let stream = connect(&url).await.map_err(|e| {
+            trace::log_warn!(url = %url, error = %e, "websocket connection failed");
+            Error::WebSocket(WebSocketError::ConnectionFailed(e.to_string()))
+        })?;
+        trace::log_info!(url = %url, latency_ms = stopwatch.elapsed_ms(), "websocket connected");
 
-        Ok(WebSocketClient { stream, url })
+        Ok(WebSocketClient {
+            stream,
+            url,
+            filter: ConfirmationFilter::default(),
+            confirmation: None,
+            #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+            keepalive: Keepalive::new(Instant::now()),
+        })
     }
 
     /// Get the WebSocket URL.
@@ -59,14 +149,89 @@ impl WebSocketClient {
         &self.url
     }
 
+    /// Ping the node every `interval` while waiting in
+    /// [`receive`](Self::receive).
+    ///
+    /// Keeps proxies from dropping an idle connection. If
+    /// [`DEFAULT_MAX_MISSED_PONGS`] pings in a row go unanswered,
+    /// `receive` fails with [`WebSocketError::Timeout`] so the caller can
+    /// reconnect.
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    pub fn with_keepalive(mut self, interval: Duration) -> Self {
+        self.keepalive.interval = Some(interval);
+        self.keepalive.last_ping = Instant::now();
+        self
+    }
+
+    /// Set how many pings in a row may go unanswered. Zero is treated as one.
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    pub fn with_max_missed_pongs(mut self, max_missed: u32) -> Self {
+        self.keepalive.max_missed = max_missed.max(1);
+        self
+    }
+
+    /// Get when the last message, including pongs, arrived from the node.
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    pub fn last_seen(&self) -> Option<Instant> {
+        self.keepalive.last_seen
+    }
+
+    /// Get the round trip time of the last answered ping.
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    pub fn latency(&self) -> Option<Duration> {
+        self.keepalive.latency
+    }
+
+    /// Get the number of pings in a row that went unanswered.
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    pub fn missed_pongs(&self) -> u32 {
+        self.keepalive.missed
+    }
+
+    /// Send a ping now. The pong is handled by [`receive`](Self::receive).
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    pub async fn ping(&mut self) -> Result<()> {
+        self.keepalive.ping(Instant::now())?;
+        self.send_message(&serde_json::json!({ "action": "ping" }))
+            .await
+    }
+
     /// Send a subscription message.
+    ///
+    /// A confirmation subscription replaces the client-side
+    /// [`ConfirmationFilter`] with the one set on the builder.
     pub async fn subscribe(&mut self, builder: SubscriptionBuilder) -> Result<()> {
+        let subscription = builder.clone();
         let msg = builder.build_subscribe().ok_or_else(|| {
             Error::WebSocket(WebSocketError::SubscriptionFailed(
                 "no topic specified".to_string(),
             ))
         })?;
-        self.send_message(&msg).await
+        self.send_message(&msg).await?;
+        trace::log_debug!(topic = %msg.topic, "websocket subscribed");
+        if msg.topic == Topic::Confirmation.as_str() {
+            self.filter = subscription.filter().clone();
+            self.confirmation = Some(subscription);
+        }
+        Ok(())
+    }
+
+    /// Get the current confirmation subscription, with the account changes
+    /// of later [`update`](Self::update) calls applied.
+    ///
+    /// `None` if there is none or it was unsubscribed.
+    pub fn confirmation_subscription(&self) -> Option<&SubscriptionBuilder> {
+        self.confirmation.as_ref()
+    }
+
+    /// Get the filter applied to received confirmations.
+    pub fn confirmation_filter(&self) -> &ConfirmationFilter {
+        &self.filter
+    }
+
+    /// Replace the filter applied to received confirmations.
+    pub fn set_confirmation_filter(&mut self, filter: ConfirmationFilter) {
+        self.filter = filter;
     }
 
     /// Send an unsubscribe message.
@@ -76,13 +241,62 @@ impl WebSocketClient {
                 "no topic specified".to_string(),
             ))
         })?;
-        self.send_message(&msg).await
+        self.send_message(&msg).await?;
+        trace::log_debug!(topic = %msg.topic, "websocket unsubscribed");
+        if msg.topic == Topic::Confirmation.as_str() {
+            self.confirmation = None;
+        }
+        Ok(())
+    }
+
+    /// Change the accounts of an existing subscription without
+    /// resubscribing.
+    ///
+    /// The node supports this for the confirmation topic.
+    ///
+    /// # Arguments
+    /// * `topic` - Subscribed topic
+    /// * `add_accounts` - Accounts to start receiving messages for
+    /// * `remove_accounts` - Accounts to stop receiving messages for
+    pub async fn update_subscription(
+        &mut self,
+        topic: Topic,
+        add_accounts: &[Account],
+        remove_accounts: &[Account],
+    ) -> Result<()> {
+        self.update(
+            SubscriptionBuilder::new()
+                .topic(topic)
+                .add_accounts(add_accounts)
+                .remove_accounts(remove_accounts),
+        )
+        .await
+    }
+
+    /// Send an update message built with
+    /// [`SubscriptionBuilder::build_update`].
+    pub async fn update(&mut self, builder: SubscriptionBuilder) -> Result<()> {
+        let update = builder.clone();
+        let msg = builder.build_update().ok_or_else(|| {
+            Error::WebSocket(WebSocketError::SubscriptionFailed(
+                "no topic or accounts specified".to_string(),
+            ))
+        })?;
+        self.send_message(&msg).await?;
+        trace::log_debug!(topic = %msg.topic, "websocket subscription updated");
+        if msg.topic == Topic::Confirmation.as_str() {
+            if let Some(confirmation) = &mut self.confirmation {
+                confirmation.apply_update(&update);
+            }
+        }
+        Ok(())
     }
 
     /// Send a raw message.
-    async fn send_message(&mut self, msg: &SubscribeMessage) -> Result<()> {
+    async fn send_message<T: serde::Serialize>(&mut self, msg: &T) -> Result<()> {
         let json = serde_json::to_string(msg)
             .map_err(|e| Error::WebSocket(WebSocketError::InvalidMessage(e.to_string())))?;
+        trace::log_trace!(bytes = json.len(), "websocket message sent");
 
         self.stream
             .send(Message::Text(json.into()))
@@ -95,23 +309,70 @@ impl WebSocketClient {
     /// Receive the next message.
     ///
     /// Returns `Ok(Some(message))` on success, `Ok(None)` if the connection is closed.
+    /// Confirmations not matching the
+    /// [`confirmation_filter`](Self::confirmation_filter) are skipped.
     pub async fn receive(&mut self) -> Result<Option<ParsedMessage>> {
         loop {
-            match self.stream.next().await {
+            #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+            let next = match self.keepalive.deadline() {
+                Some(deadline) => {
+                    let deadline = tokio::time::Instant::from_std(deadline);
+                    match tokio::time::timeout_at(deadline, self.stream.next()).await {
+                        Ok(next) => next,
+                        Err(_) => {
+                            trace::log_trace!(missed = self.keepalive.missed, "websocket ping");
+                            self.ping().await?;
+                            continue;
+                        }
+                    }
+                }
+                None => self.stream.next().await,
+            };
+            #[cfg(not(all(feature = "websocket", not(target_arch = "wasm32"))))]
+            let next = self.stream.next().await;
+
+            #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+            if matches!(next, Some(Ok(_))) {
+                self.keepalive.seen(Instant::now());
+            }
+
+            match next {
                 Some(Ok(msg)) => match msg {
                     Message::Text(text) => {
+                        #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+                        if let Ok(ack) = serde_json::from_str::<AckMessage>(&text) {
+                            if ack.ack == "pong" {
+                                self.keepalive.pong(Instant::now());
+                                trace::log_trace!(latency = ?self.keepalive.latency, "websocket pong");
+                            }
+                            continue;
+                        }
                         if let Ok(incoming) = serde_json::from_str::<IncomingMessage>(&text) {
-                            return Ok(Some(incoming.parse()));
+                            trace::log_trace!(
+                                topic = %incoming.topic,
+                                bytes = text.len(),
+                                "websocket message received"
+                            );
+                            metrics::websocket_message(&incoming.topic);
+                            let parsed = incoming.parse();
+                            if let ParsedMessage::Confirmation(conf) = &parsed {
+                                if !self.filter.matches(conf) {
+                                    continue;
+                                }
+                            }
+                            return Ok(Some(parsed));
                         }
                         // Could be an ack message, skip
                         continue;
                     }
                     Message::Binary(_) => continue,
                     Message::Close(_) => {
+                        trace::log_info!(url = %self.url, "websocket closed by node");
                         return Ok(None);
                     }
                 },
                 Some(Err(e)) => {
+                    trace::log_warn!(url = %self.url, error = %e, "websocket connection lost");
                     return Err(Error::WebSocket(WebSocketError::ConnectionFailed(
                         e.to_string(),
                     )));
@@ -125,6 +386,7 @@ impl WebSocketClient {
 
     /// Close the WebSocket connection.
     pub async fn close(mut self) -> Result<()> {
+        trace::log_info!(url = %self.url, "websocket closing");
         self.stream
             .close()
             .await
@@ -142,6 +404,30 @@ impl WebSocketClient {
 mod tests {
     use super::*;
 
+    #[cfg(all(feature = "websocket", not(target_arch = "wasm32")))]
+    #[test]
+    fn test_keepalive_missed_pongs() {
+        let start = Instant::now();
+        let mut keepalive = Keepalive::new(start);
+        assert_eq!(keepalive.deadline(), None);
+        keepalive.interval = Some(Duration::from_secs(10));
+        assert_eq!(keepalive.deadline(), Some(start + Duration::from_secs(10)));
+
+        let first = start + Duration::from_secs(10);
+        keepalive.ping(first).unwrap();
+        keepalive.pong(first + Duration::from_millis(40));
+        assert_eq!(keepalive.latency, Some(Duration::from_millis(40)));
+        assert_eq!(keepalive.deadline(), Some(first + Duration::from_secs(10)));
+
+        keepalive.ping(first + Duration::from_secs(10)).unwrap();
+        keepalive.ping(first + Duration::from_secs(20)).unwrap();
+        assert_eq!(keepalive.missed, 1);
+        assert_eq!(
+            keepalive.ping(first + Duration::from_secs(30)).unwrap_err(),
+            Error::WebSocket(WebSocketError::Timeout)
+        );
+    }
+
     #[test]
     fn test_subscription_builder() {
         let builder = SubscriptionBuilder::new().confirmations().with_ack();
@@ -33,6 +33,25 @@ pub struct SubscriptionOptions {
     /// Include election info.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub include_election_info: Option<bool>,
+    /// Filter votes by representative.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub representatives: Option<Vec<String>>,
+    /// Include replayed votes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_replays: Option<bool>,
+    /// Include votes for blocks not in an active election.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_indeterminate: Option<bool>,
+    /// Confirmation types to receive: "all", "active", "active_quorum",
+    /// "active_confirmation_height" or "inactive".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirmation_type: Option<String>,
+    /// Accounts to add to an existing subscription.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounts_add: Option<Vec<String>>,
+    /// Accounts to remove from an existing subscription.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accounts_del: Option<Vec<String>>,
 }
 
 /// Incoming WebSocket message wrapper.
@@ -124,6 +143,9 @@ pub struct ElectionInfo {
 }
 
 /// Vote message content.
+///
+/// Fields are kept as the node sends them; the accessor methods parse
+/// them into typed values.
 #[derive(Debug, Clone, Deserialize)]
 pub struct VoteMessage {
     /// Account that voted.
@@ -136,6 +158,79 @@ pub struct VoteMessage {
     pub timestamp: String,
     /// Blocks voted on.
     pub blocks: Vec<String>,
+    /// Duration bits, sent separately by newer nodes.
+    #[serde(default)]
+    duration: Option<String>,
+    /// Vote type: "vote", "replay" or "indeterminate".
+    #[serde(default, rename = "type")]
+    vote_type: Option<String>,
+}
+
+impl VoteMessage {
+    /// Timestamp bits, without the duration.
+    const TIMESTAMP_MASK: u64 = !0xf;
+
+    /// Get the full timestamp field as signed by the representative,
+    /// including the duration bits.
+    pub fn raw_timestamp(&self) -> Option<u64> {
+        let timestamp: u64 = self.timestamp.parse().ok()?;
+        match self.duration.as_deref().map(str::parse::<u64>) {
+            Some(Ok(bits)) if bits <= 0xf => Some(timestamp & Self::TIMESTAMP_MASK | bits),
+            _ => Some(timestamp),
+        }
+    }
+
+    /// Check if this is a final vote.
+    pub fn is_final(&self) -> bool {
+        self.raw_timestamp()
+            .is_some_and(|t| t & Self::TIMESTAMP_MASK == Self::TIMESTAMP_MASK)
+    }
+
+    /// Get the vote time in milliseconds since the Unix epoch.
+    ///
+    /// Returns `None` for final votes, which carry no time.
+    pub fn timestamp_ms(&self) -> Option<u64> {
+        if self.is_final() {
+            return None;
+        }
+        Some(self.raw_timestamp()? & Self::TIMESTAMP_MASK)
+    }
+
+    /// Get how long the representative intends the vote to stay valid.
+    ///
+    /// Returns `None` for final votes, which never expire.
+    pub fn duration(&self) -> Option<core::time::Duration> {
+        if self.is_final() {
+            return None;
+        }
+        let bits = self.raw_timestamp()? & 0xf;
+        Some(core::time::Duration::from_millis(1 << (bits + 4)))
+    }
+
+    /// Get the sequence number.
+    pub fn sequence(&self) -> Option<u64> {
+        self.sequence.parse().ok()
+    }
+
+    /// Parse the block hashes voted on.
+    pub fn hashes(&self) -> crate::error::Result<Vec<BlockHash>> {
+        self.blocks
+            .iter()
+            .map(|hash| BlockHash::from_hex(hash))
+            .collect()
+    }
+
+    /// Get the vote type: "vote", "replay" or "indeterminate".
+    ///
+    /// Returns `None` for nodes that don't send it.
+    pub fn vote_type(&self) -> Option<&str> {
+        self.vote_type.as_deref()
+    }
+
+    /// Check if the node replayed this vote for an already confirmed block.
+    pub fn is_replay(&self) -> bool {
+        self.vote_type.as_deref() == Some("replay")
+    }
 }
 
 /// Stopped election message.
@@ -218,6 +313,7 @@ pub struct WorkMessage {
 }
 
 /// Parse an incoming message into a typed enum.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 pub enum ParsedMessage {
     /// Confirmation message.
@@ -303,6 +399,7 @@ mod tests {
                 ]),
                 include_block: Some(true),
                 include_election_info: None,
+                ..Default::default()
             }),
         };
 
@@ -341,4 +438,44 @@ mod tests {
         let msg: AckMessage = serde_json::from_str(json).unwrap();
         assert_eq!(msg.ack, "subscribe");
     }
+
+    #[test]
+    fn test_vote_message_fields() {
+        let json = serde_json::json!({
+            "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+            "signature": "00",
+            "sequence": "1700000000000",
+            "timestamp": "1700000000000",
+            "duration": "3",
+            "blocks": ["991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948"],
+            "type": "replay"
+        });
+        let vote: VoteMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(vote.raw_timestamp(), Some(1_700_000_000_000 & !0xf | 3));
+        assert_eq!(vote.timestamp_ms(), Some(1_700_000_000_000 & !0xf));
+        assert_eq!(
+            vote.duration(),
+            Some(core::time::Duration::from_millis(128))
+        );
+        assert!(!vote.is_final());
+        assert!(vote.is_replay());
+        assert_eq!(vote.vote_type(), Some("replay"));
+        assert_eq!(vote.hashes().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_final_vote_message() {
+        let json = serde_json::json!({
+            "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+            "signature": "00",
+            "sequence": "18446744073709551615",
+            "timestamp": "18446744073709551615",
+            "blocks": []
+        });
+        let vote: VoteMessage = serde_json::from_value(json).unwrap();
+        assert!(vote.is_final());
+        assert_eq!(vote.timestamp_ms(), None);
+        assert_eq!(vote.duration(), None);
+        assert!(!vote.is_replay());
+    }
 }
@@ -3,8 +3,8 @@
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 
-use crate::types::Account;
-use crate::websocket::messages::{SubscribeMessage, SubscriptionOptions};
+use crate::types::{Account, Raw, Subtype};
+use crate::websocket::messages::{ConfirmationMessage, SubscribeMessage, SubscriptionOptions};
 
 /// WebSocket topic for subscriptions.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -49,6 +49,93 @@ impl core::fmt::Display for Topic {
     }
 }
 
+/// Which confirmations the node sends on the confirmation topic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationType {
+    /// Every confirmation.
+    All,
+    /// Blocks confirmed by an election, by quorum or by confirmation height.
+    Active,
+    /// Blocks confirmed by an election reaching quorum.
+    ActiveQuorum,
+    /// Blocks confirmed as dependents of a confirmed block.
+    ActiveConfirmationHeight,
+    /// Blocks confirmed without an election on this node.
+    Inactive,
+}
+
+impl ConfirmationType {
+    /// Get the option string.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfirmationType::All => "all",
+            ConfirmationType::Active => "active",
+            ConfirmationType::ActiveQuorum => "active_quorum",
+            ConfirmationType::ActiveConfirmationHeight => "active_confirmation_height",
+            ConfirmationType::Inactive => "inactive",
+        }
+    }
+}
+
+/// Client-side filter for confirmations the node cannot filter itself.
+///
+/// Set through [`SubscriptionBuilder::subtype`] and
+/// [`SubscriptionBuilder::min_amount`]; the WebSocket client drops
+/// confirmations that do not match before returning them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfirmationFilter {
+    subtypes: Vec<Subtype>,
+    min_amount: Option<Raw>,
+}
+
+impl ConfirmationFilter {
+    /// Create a filter that matches every confirmation.
+    pub fn new() -> Self {
+        ConfirmationFilter::default()
+    }
+
+    /// Only match blocks of this subtype. May be called more than once.
+    pub fn subtype(mut self, subtype: Subtype) -> Self {
+        if !self.subtypes.contains(&subtype) {
+            self.subtypes.push(subtype);
+        }
+        self
+    }
+
+    /// Only match blocks moving at least `amount`.
+    pub fn min_amount(mut self, amount: Raw) -> Self {
+        self.min_amount = Some(amount);
+        self
+    }
+
+    /// Check if the filter matches every confirmation.
+    pub fn is_empty(&self) -> bool {
+        self.subtypes.is_empty() && self.min_amount.is_none()
+    }
+
+    /// Check if a confirmation passes the filter.
+    ///
+    /// A subtype filter needs the block contents; confirmations without a
+    /// block or subtype do not match it.
+    pub fn matches(&self, confirmation: &ConfirmationMessage) -> bool {
+        if let Some(min) = self.min_amount {
+            if confirmation.amount < min {
+                return false;
+            }
+        }
+        if self.subtypes.is_empty() {
+            return true;
+        }
+        let subtype = confirmation
+            .block
+            .as_ref()
+            .and_then(|block| block.subtype.as_deref());
+        self.subtypes
+            .iter()
+            .any(|wanted| subtype == Some(wanted.as_str()))
+    }
+}
+
 /// Builder for creating subscription requests.
 #[derive(Debug, Clone, Default)]
 pub struct SubscriptionBuilder {
@@ -57,6 +144,13 @@ pub struct SubscriptionBuilder {
     accounts: Vec<String>,
     include_block: bool,
     include_election_info: bool,
+    representatives: Vec<String>,
+    include_replays: bool,
+    include_indeterminate: bool,
+    confirmation_type: Option<ConfirmationType>,
+    filter: ConfirmationFilter,
+    accounts_add: Vec<String>,
+    accounts_del: Vec<String>,
 }
 
 impl SubscriptionBuilder {
@@ -121,28 +215,122 @@ impl SubscriptionBuilder {
         self
     }
 
+    /// Filter votes by representative.
+    pub fn representative(mut self, representative: &Account) -> Self {
+        self.representatives
+            .push(representative.as_str().to_string());
+        self
+    }
+
+    /// Filter votes by multiple representatives.
+    pub fn representatives(mut self, representatives: &[Account]) -> Self {
+        for representative in representatives {
+            self.representatives
+                .push(representative.as_str().to_string());
+        }
+        self
+    }
+
+    /// Include replayed votes for already confirmed blocks.
+    pub fn include_replays(mut self) -> Self {
+        self.include_replays = true;
+        self
+    }
+
+    /// Include votes for blocks that are not in an active election.
+    pub fn include_indeterminate(mut self) -> Self {
+        self.include_indeterminate = true;
+        self
+    }
+
+    /// Only receive confirmations of this type.
+    pub fn confirmation_type(mut self, confirmation_type: ConfirmationType) -> Self {
+        self.confirmation_type = Some(confirmation_type);
+        self
+    }
+
+    /// Only deliver confirmations of blocks with this subtype. May be
+    /// called more than once.
+    ///
+    /// The node cannot filter by subtype, so this is applied by the client
+    /// and turns on [`include_block`](Self::include_block).
+    pub fn subtype(mut self, subtype: Subtype) -> Self {
+        self.filter = self.filter.subtype(subtype);
+        self.include_block = true;
+        self
+    }
+
+    /// Only deliver confirmations moving at least `amount`.
+    ///
+    /// The node cannot filter by amount, so this is applied by the client.
+    pub fn min_amount(mut self, amount: Raw) -> Self {
+        self.filter = self.filter.min_amount(amount);
+        self
+    }
+
+    /// Get the client-side confirmation filter.
+    pub fn filter(&self) -> &ConfirmationFilter {
+        &self.filter
+    }
+
+    /// Add accounts to an existing subscription, for
+    /// [`build_update`](Self::build_update).
+    pub fn add_accounts(mut self, accounts: &[Account]) -> Self {
+        for account in accounts {
+            self.accounts_add.push(account.as_str().to_string());
+        }
+        self
+    }
+
+    /// Remove accounts from an existing subscription, for
+    /// [`build_update`](Self::build_update).
+    pub fn remove_accounts(mut self, accounts: &[Account]) -> Self {
+        for account in accounts {
+            self.accounts_del.push(account.as_str().to_string());
+        }
+        self
+    }
+
+    /// Apply the account changes of an update to this subscription.
+    pub(crate) fn apply_update(&mut self, update: &SubscriptionBuilder) {
+        self.accounts
+            .retain(|account| !update.accounts_del.contains(account));
+        for account in &update.accounts_add {
+            if !self.accounts.contains(account) {
+                self.accounts.push(account.clone());
+            }
+        }
+    }
+
     /// Build the subscribe message.
     pub fn build_subscribe(self) -> Option<SubscribeMessage> {
         let topic = self.topic?;
 
-        let options =
-            if self.accounts.is_empty() && !self.include_block && !self.include_election_info {
-                None
-            } else {
-                Some(SubscriptionOptions {
-                    accounts: if self.accounts.is_empty() {
-                        None
-                    } else {
-                        Some(self.accounts)
-                    },
-                    include_block: if self.include_block { Some(true) } else { None },
-                    include_election_info: if self.include_election_info {
-                        Some(true)
-                    } else {
-                        None
-                    },
-                })
-            };
+        let flag = |set: bool| if set { Some(true) } else { None };
+        let list = |items: Vec<String>| if items.is_empty() { None } else { Some(items) };
+        let options = SubscriptionOptions {
+            accounts: list(self.accounts),
+            include_block: flag(self.include_block),
+            include_election_info: flag(self.include_election_info),
+            representatives: list(self.representatives),
+            include_replays: flag(self.include_replays),
+            include_indeterminate: flag(self.include_indeterminate),
+            confirmation_type: self.confirmation_type.map(|t| t.as_str().to_string()),
+            accounts_add: None,
+            accounts_del: None,
+        };
+        let options = if options.accounts.is_none()
+            && options.include_block.is_none()
+            && options.include_election_info.is_none()
+            && options.representatives.is_none()
+            && options.include_replays.is_none()
+            && options.include_indeterminate.is_none()
+            && options.confirmation_type.is_none()
+        {
+            None
+        } else {
+            Some(options)
+        };
 
         Some(SubscribeMessage {
             action: "subscribe".to_string(),
@@ -152,6 +340,32 @@ impl SubscriptionBuilder {
         })
     }
 
+    /// Build an update message changing the accounts of an existing
+    /// subscription.
+    ///
+    /// Only the accounts set with [`add_accounts`](Self::add_accounts) and
+    /// [`remove_accounts`](Self::remove_accounts) are sent; other options
+    /// keep the values from the original subscription. Returns `None` if no
+    /// topic is set or there are no accounts to change.
+    pub fn build_update(self) -> Option<SubscribeMessage> {
+        let topic = self.topic?;
+        if self.accounts_add.is_empty() && self.accounts_del.is_empty() {
+            return None;
+        }
+
+        let list = |items: Vec<String>| if items.is_empty() { None } else { Some(items) };
+        Some(SubscribeMessage {
+            action: "update".to_string(),
+            topic: topic.as_str().to_string(),
+            ack: if self.ack { Some(true) } else { None },
+            options: Some(SubscriptionOptions {
+                accounts_add: list(self.accounts_add),
+                accounts_del: list(self.accounts_del),
+                ..SubscriptionOptions::default()
+            }),
+        })
+    }
+
     /// Build the unsubscribe message.
     pub fn build_unsubscribe(self) -> Option<SubscribeMessage> {
         let topic = self.topic?;
@@ -235,6 +449,27 @@ mod tests {
         assert_eq!(opts.include_block, Some(true));
     }
 
+    #[test]
+    fn test_subscription_builder_vote_options() {
+        let rep = Account::from_public_key(&PublicKey::ZERO);
+
+        let msg = subscribe_votes()
+            .representative(&rep)
+            .include_replays()
+            .include_indeterminate()
+            .build_subscribe()
+            .unwrap();
+
+        let opts = msg.options.unwrap();
+        assert_eq!(opts.representatives, Some(vec![rep.as_str().to_string()]));
+        assert_eq!(opts.include_replays, Some(true));
+        assert_eq!(opts.include_indeterminate, Some(true));
+        assert!(opts.accounts.is_none());
+
+        let json = serde_json::to_string(&subscribe_votes().build_subscribe().unwrap()).unwrap();
+        assert!(!json.contains("options"));
+    }
+
     #[test]
     fn test_unsubscribe() {
         let msg = SubscriptionBuilder::new()
@@ -257,4 +492,88 @@ mod tests {
         let msg = subscribe_telemetry().build_subscribe().unwrap();
         assert_eq!(msg.topic, "telemetry");
     }
+
+    #[test]
+    fn test_build_update() {
+        let account: Account = "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+            .parse()
+            .unwrap();
+        let msg = subscribe_confirmations()
+            .add_accounts(core::slice::from_ref(&account))
+            .build_update()
+            .unwrap();
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"action\":\"update\""));
+        assert!(json.contains("\"topic\":\"confirmation\""));
+        assert!(json.contains("\"accounts_add\":[\"nano_3t6k"));
+        assert!(!json.contains("accounts_del"));
+
+        let msg = subscribe_confirmations()
+            .remove_accounts(&[account])
+            .build_update()
+            .unwrap();
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"accounts_del\""));
+
+        assert!(subscribe_confirmations().build_update().is_none());
+    }
+
+    #[test]
+    fn test_apply_update() {
+        let first = Account::from_public_key(&PublicKey::from_bytes([1; 32]));
+        let second = Account::from_public_key(&PublicKey::from_bytes([2; 32]));
+        let mut subscription = subscribe_account_confirmations(core::slice::from_ref(&first));
+
+        subscription.apply_update(
+            &subscribe_confirmations().add_accounts(&[first.clone(), second.clone()]),
+        );
+        assert_eq!(subscription.accounts, [first.as_str(), second.as_str()]);
+
+        subscription.apply_update(&subscribe_confirmations().remove_accounts(&[first]));
+        assert_eq!(subscription.accounts, [second.as_str()]);
+    }
+
+    #[test]
+    fn test_confirmation_filter() {
+        let msg = subscribe_confirmations()
+            .confirmation_type(ConfirmationType::ActiveQuorum)
+            .subtype(Subtype::Send)
+            .build_subscribe()
+            .unwrap();
+        let opts = msg.options.unwrap();
+        assert_eq!(opts.confirmation_type.as_deref(), Some("active_quorum"));
+        assert_eq!(opts.include_block, Some(true));
+
+        let confirmation = |subtype: &str, amount: &str| -> ConfirmationMessage {
+            serde_json::from_value(serde_json::json!({
+                "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                "amount": amount,
+                "hash": "0000000000000000000000000000000000000000000000000000000000000001",
+                "confirmation_type": "active_quorum",
+                "block": {
+                    "type": "state",
+                    "account": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                    "previous": "0000000000000000000000000000000000000000000000000000000000000000",
+                    "representative": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+                    "balance": "0",
+                    "link": "0000000000000000000000000000000000000000000000000000000000000000",
+                    "signature": "0".repeat(128),
+                    "work": "0000000000000000",
+                    "subtype": subtype
+                }
+            }))
+            .unwrap()
+        };
+
+        let filter = ConfirmationFilter::new()
+            .subtype(Subtype::Send)
+            .subtype(Subtype::Receive)
+            .min_amount(Raw::new(100));
+        assert!(filter.matches(&confirmation("send", "100")));
+        assert!(filter.matches(&confirmation("receive", "500")));
+        assert!(!filter.matches(&confirmation("change", "500")));
+        assert!(!filter.matches(&confirmation("send", "99")));
+        assert!(ConfirmationFilter::new().matches(&confirmation("epoch", "0")));
+        assert!(ConfirmationFilter::new().is_empty());
+    }
 }
@@ -0,0 +1,49 @@
+//! Pluggable work generation backends.
+
+#[cfg(feature = "rpc")]
+use alloc::sync::Arc;
+use core::sync::atomic::AtomicBool;
+#[cfg(feature = "rpc")]
+use core::sync::atomic::Ordering;
+
+use crate::error::Result;
+use crate::types::{BlockHash, Work};
+
+#[cfg(feature = "work-cpu")]
+use crate::work::CpuWorkGenerator;
+
+/// A local proof of work generator, such as the CPU or a GPU.
+pub trait WorkBackend: Send + Sync + 'static {
+    /// Generate work for `hash` meeting `threshold`, stopping early once
+    /// `cancelled` is set.
+    fn generate_work(
+        &self,
+        hash: &BlockHash,
+        threshold: u64,
+        cancelled: &AtomicBool,
+    ) -> Result<Work>;
+}
+
+#[cfg(feature = "work-cpu")]
+impl WorkBackend for CpuWorkGenerator {
+    fn generate_work(
+        &self,
+        hash: &BlockHash,
+        threshold: u64,
+        cancelled: &AtomicBool,
+    ) -> Result<Work> {
+        self.generate(hash, threshold, Some(cancelled))
+    }
+}
+
+/// Sets a backend's cancel flag when dropped, so a search running on a
+/// blocking thread stops once the future waiting for it is abandoned.
+#[cfg(feature = "rpc")]
+pub(crate) struct CancelOnDrop(pub(crate) Arc<AtomicBool>);
+
+#[cfg(feature = "rpc")]
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
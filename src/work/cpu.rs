@@ -3,14 +3,94 @@
 //! Generates proof of work using CPU threads.
 
 use alloc::sync::Arc;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use core::time::Duration;
+use std::time::Instant;
 
-#[cfg(feature = "work-cpu")]
 use rayon::prelude::*;
 
 use crate::error::{Error, Result, WorkError};
+use crate::metrics;
+use crate::network::Network;
+use crate::trace;
 use crate::types::{BlockHash, Subtype, Work};
-use crate::work::{WorkThreshold, WorkValidator};
+use crate::work::{DynamicThreshold, WorkThreshold, WorkValidator};
+
+/// Measured hash rate of a [`CpuWorkGenerator`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkBenchmark {
+    /// Threads used for the measurement.
+    pub threads: usize,
+    /// Hashes computed.
+    pub hashes: u64,
+    /// Time spent hashing.
+    pub elapsed: Duration,
+    /// Thresholds the estimates are for.
+    pub threshold: WorkThreshold,
+}
+
+impl WorkBenchmark {
+    /// Hashes computed per second.
+    pub fn hashes_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.hashes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Expected time to find work meeting `threshold`.
+    ///
+    /// Work search is random, so actual times vary widely around this
+    /// average. Returns [`Duration::MAX`] if nothing was hashed.
+    pub fn expected_time(&self, threshold: u64) -> Duration {
+        let rate = self.hashes_per_second();
+        if rate <= 0.0 {
+            return Duration::MAX;
+        }
+        // Each hash meets the threshold with probability (MAX - threshold) / 2^64
+        let attempts = u64::MAX as f64 / (u64::MAX - threshold).max(1) as f64;
+        Duration::try_from_secs_f64(attempts / rate).unwrap_or(Duration::MAX)
+    }
+
+    /// Expected time to find work for a send or change block.
+    pub fn expected_send(&self) -> Duration {
+        self.expected_time(self.threshold.send)
+    }
+
+    /// Expected time to find work for a receive or open block.
+    pub fn expected_receive(&self) -> Duration {
+        self.expected_time(self.threshold.receive)
+    }
+}
+
+/// Progress of a running [`CpuWorkGenerator::generate`] call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorkProgress {
+    /// Work values tried so far, across all threads.
+    pub attempts: u64,
+    /// Time since generation started.
+    pub elapsed: Duration,
+}
+
+impl WorkProgress {
+    /// Work values tried per second so far.
+    pub fn attempts_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.attempts as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Callback receiving [`WorkProgress`] reports.
+type ProgressCallback = Arc<dyn Fn(WorkProgress) + Send + Sync>;
+
+/// Default time between progress reports.
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
 
 /// CPU-based work generator.
 ///
@@ -18,8 +98,14 @@ use crate::work::{WorkThreshold, WorkValidator};
 pub struct CpuWorkGenerator {
     /// Work threshold configuration.
     threshold: WorkThreshold,
+    /// Network difficulty to follow, if any.
+    dynamic: Option<DynamicThreshold>,
     /// Number of threads to use (0 = auto).
     threads: usize,
+    /// Called periodically while generating.
+    progress: Option<ProgressCallback>,
+    /// Time between progress reports.
+    progress_interval: Duration,
 }
 
 impl CpuWorkGenerator {
@@ -27,7 +113,10 @@ impl CpuWorkGenerator {
     pub fn new() -> Self {
         CpuWorkGenerator {
             threshold: WorkThreshold::MAINNET,
+            dynamic: None,
             threads: 0, // Auto-detect
+            progress: None,
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
         }
     }
 
@@ -37,6 +126,26 @@ impl CpuWorkGenerator {
         self
     }
 
+    /// Use the work thresholds of a network.
+    pub fn with_network(self, network: &Network) -> Self {
+        self.with_threshold(network.work_threshold)
+    }
+
+    /// Follow the network difficulty tracked by `dynamic` instead of fixed
+    /// thresholds.
+    pub fn with_dynamic_threshold(mut self, dynamic: DynamicThreshold) -> Self {
+        self.dynamic = Some(dynamic);
+        self
+    }
+
+    /// Get the thresholds generated work currently meets.
+    pub fn threshold(&self) -> WorkThreshold {
+        match &self.dynamic {
+            Some(dynamic) => dynamic.current(),
+            None => self.threshold,
+        }
+    }
+
     /// Set the number of threads to use.
     ///
     /// Use 0 for auto-detection (uses all available cores).
@@ -45,6 +154,105 @@ impl CpuWorkGenerator {
         self
     }
 
+    /// Report progress while generating.
+    ///
+    /// `callback` runs on a worker thread every 250ms by default, and once
+    /// more when generation ends. Keep it short, such as sending the report
+    /// to a channel.
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(WorkProgress) + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Set the time between progress reports.
+    pub fn with_progress_interval(mut self, interval: Duration) -> Self {
+        self.progress_interval = interval;
+        self
+    }
+
+    /// Get the number of threads used, resolving 0 to the number of cores.
+    pub fn threads(&self) -> usize {
+        if self.threads == 0 {
+            rayon::current_num_threads()
+        } else {
+            self.threads
+        }
+    }
+
+    /// Measure the hash rate by hashing for `duration`.
+    ///
+    /// Blocks the calling thread for about `duration`.
+    pub fn benchmark(&self, duration: Duration) -> WorkBenchmark {
+        self.benchmark_threads(self.threads(), duration)
+    }
+
+    /// Benchmark thread counts up to the number of cores and keep the
+    /// fastest.
+    ///
+    /// `duration` is split between the candidates. Returns the benchmark of
+    /// the chosen thread count.
+    pub fn tune(&mut self, duration: Duration) -> WorkBenchmark {
+        let max = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let mut candidates: alloc::vec::Vec<usize> =
+            core::iter::successors(Some(1), |n| Some(n * 2).filter(|next| *next < max)).collect();
+        if candidates.last() != Some(&max) {
+            candidates.push(max);
+        }
+
+        let each = duration / candidates.len() as u32;
+        let best = candidates
+            .into_iter()
+            .map(|threads| self.benchmark_threads(threads, each))
+            .max_by(|a, b| a.hashes_per_second().total_cmp(&b.hashes_per_second()))
+            .expect("at least one candidate");
+        self.threads = best.threads;
+        best
+    }
+
+    fn benchmark_threads(&self, threads: usize, duration: Duration) -> WorkBenchmark {
+        let threshold = self.threshold();
+        let run = || {
+            let start = Instant::now();
+            let hashes: u64 = (0..threads)
+                .into_par_iter()
+                .map(|i| {
+                    let hash = BlockHash::from_bytes([i as u8; 32]);
+                    let mut count = 0u64;
+                    loop {
+                        for nonce in count..count + 0x1000 {
+                            core::hint::black_box(WorkValidator::difficulty(
+                                Work::new(nonce),
+                                &hash,
+                            ));
+                        }
+                        count += 0x1000;
+                        if start.elapsed() >= duration {
+                            return count;
+                        }
+                    }
+                })
+                .sum();
+            (hashes, start.elapsed())
+        };
+
+        // Measure on a pool of exactly `threads` threads when possible
+        let (hashes, elapsed) = match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+            Ok(pool) => pool.install(run),
+            Err(_) => run(),
+        };
+        WorkBenchmark {
+            threads,
+            hashes,
+            elapsed,
+            threshold,
+        }
+    }
+
     /// Generate work for a hash with the given threshold.
     ///
     /// # Arguments
@@ -54,7 +262,6 @@ impl CpuWorkGenerator {
     ///
     /// # Returns
     /// The work value if found, or an error if cancelled.
-    #[cfg(feature = "work-cpu")]
     pub fn generate(
         &self,
         hash: &BlockHash,
@@ -62,13 +269,24 @@ impl CpuWorkGenerator {
         cancelled: Option<&AtomicBool>,
     ) -> Result<Work> {
         let found_flag = Arc::new(AtomicBool::new(false));
-
-        let num_threads = if self.threads == 0 {
-            rayon::current_num_threads()
-        } else {
-            self.threads
+        let attempts = AtomicU64::new(0);
+        let started = Instant::now();
+        let stopwatch = trace::Stopwatch::start();
+        let report = |callback: &ProgressCallback| {
+            callback(WorkProgress {
+                attempts: attempts.load(Ordering::Relaxed),
+                elapsed: started.elapsed(),
+            })
         };
 
+        let num_threads = self.threads();
+        trace::log_debug!(
+            hash = %hash,
+            threshold = %format_args!("{:016x}", threshold),
+            threads = num_threads,
+            "cpu work generation started"
+        );
+
         // Divide the search space among threads
         let chunk_size = u64::MAX / num_threads as u64;
 
@@ -80,9 +298,19 @@ impl CpuWorkGenerator {
                 start + chunk_size
             };
 
+            let mut last_report = Duration::ZERO;
             for nonce in start..end {
                 // Check cancellation/found flags every 4096 iterations
                 if nonce & 0xFFF == 0 {
+                    attempts.fetch_add(0x1000, Ordering::Relaxed);
+                    // The first thread reports for all of them
+                    if let Some(callback) = self.progress.as_ref().filter(|_| i == 0) {
+                        let elapsed = started.elapsed();
+                        if elapsed >= last_report + self.progress_interval {
+                            last_report = elapsed;
+                            report(callback);
+                        }
+                    }
                     if let Some(cancel) = cancelled {
                         if cancel.load(Ordering::Relaxed) {
                             return None;
@@ -103,10 +331,23 @@ impl CpuWorkGenerator {
             None
         });
 
+        if let Some(callback) = &self.progress {
+            report(callback);
+        }
+        trace::log_debug!(
+            found = result.is_some(),
+            attempts = attempts.load(Ordering::Relaxed),
+            latency_ms = started.elapsed().as_millis() as u64,
+            "cpu work generation finished"
+        );
+
         match result {
-            Some(nonce) => Ok(Work::new(nonce)),
+            Some(nonce) => {
+                metrics::work_generated("cpu", &stopwatch);
+                Ok(Work::new(nonce))
+            }
             None => {
-                if cancelled.map_or(false, |c| c.load(Ordering::Relaxed)) {
+                if cancelled.is_some_and(|c| c.load(Ordering::Relaxed)) {
                     Err(Error::WorkGeneration(WorkError::Cancelled))
                 } else {
                     Err(Error::WorkGeneration(WorkError::MaxIterations))
@@ -116,33 +357,29 @@ impl CpuWorkGenerator {
     }
 
     /// Generate work for a send/change block.
-    #[cfg(feature = "work-cpu")]
     pub fn generate_send(&self, hash: &BlockHash) -> Result<Work> {
-        self.generate(hash, self.threshold.send, None)
+        self.generate(hash, self.threshold().send, None)
     }
 
     /// Generate work for a receive/open block.
-    #[cfg(feature = "work-cpu")]
     pub fn generate_receive(&self, hash: &BlockHash) -> Result<Work> {
-        self.generate(hash, self.threshold.receive, None)
+        self.generate(hash, self.threshold().receive, None)
     }
 
     /// Generate work for a specific block subtype.
-    #[cfg(feature = "work-cpu")]
     pub fn generate_for_subtype(&self, hash: &BlockHash, subtype: Subtype) -> Result<Work> {
-        let threshold = self.threshold.for_subtype(subtype);
+        let threshold = self.threshold().for_subtype(subtype);
         self.generate(hash, threshold, None)
     }
 
     /// Generate work with cancellation support.
-    #[cfg(feature = "work-cpu")]
     pub fn generate_cancellable(
         &self,
         hash: &BlockHash,
         subtype: Subtype,
         cancelled: &AtomicBool,
     ) -> Result<Work> {
-        let threshold = self.threshold.for_subtype(subtype);
+        let threshold = self.threshold().for_subtype(subtype);
         self.generate(hash, threshold, Some(cancelled))
     }
 }
@@ -200,6 +437,51 @@ mod tests {
         assert_eq!(generator.threshold, WorkThreshold::MAINNET);
     }
 
+    #[test]
+    fn test_benchmark() {
+        let generator = CpuWorkGenerator::new().with_threads(1);
+        let benchmark = generator.benchmark(Duration::from_millis(20));
+        assert_eq!(benchmark.threads, 1);
+        assert!(benchmark.hashes > 0);
+        assert!(benchmark.expected_send() > benchmark.expected_receive());
+    }
+
+    #[test]
+    fn test_expected_time() {
+        let benchmark = WorkBenchmark {
+            threads: 1,
+            hashes: 1_000,
+            elapsed: Duration::from_secs(1),
+            threshold: WorkThreshold::MAINNET,
+        };
+        // Half of all hashes meet this threshold
+        let half = benchmark.expected_time(1 << 63);
+        assert!((half.as_secs_f64() - 0.002).abs() < 1e-6);
+
+        let idle = WorkBenchmark {
+            hashes: 0,
+            ..benchmark
+        };
+        assert_eq!(idle.expected_send(), Duration::MAX);
+    }
+
+    #[test]
+    fn test_progress_reported() {
+        let reports = Arc::new(std::sync::Mutex::new(alloc::vec::Vec::new()));
+        let sink = reports.clone();
+        let generator = CpuWorkGenerator::new()
+            .with_threads(1)
+            .with_progress_interval(Duration::ZERO)
+            .on_progress(move |progress| sink.lock().unwrap().push(progress));
+        let hash = BlockHash::from_bytes([7u8; 32]);
+
+        generator.generate(&hash, 0xff00000000000000, None).unwrap();
+
+        let reports = reports.lock().unwrap();
+        assert!(!reports.is_empty());
+        assert!(reports.windows(2).all(|w| w[0].attempts <= w[1].attempts));
+    }
+
     #[test]
     #[ignore] // Slow test
     fn test_cancellation() {
@@ -0,0 +1,247 @@
+//! Work generation fanned out to several work servers at once.
+//!
+//! A [`DistributedWorkProvider`] sends the same `work_generate` request to
+//! every configured server concurrently. The first valid work wins; the
+//! other requests are dropped and the servers are asked to `work_cancel`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xno_connect::rpc::RpcClient;
+//! use xno_connect::types::{BlockHash, Subtype};
+//! use xno_connect::work::DistributedWorkProvider;
+//!
+//! # async fn example() -> xno_connect::Result<()> {
+//! let provider = DistributedWorkProvider::new()
+//!     .with_server(RpcClient::new("http://10.0.0.1:7000"))
+//!     .with_server(RpcClient::new("http://10.0.0.2:7000"));
+//!
+//! let work = provider.generate(&BlockHash::ZERO, Subtype::Send).await?;
+//! println!("work {}", work);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::format;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use tokio::task::JoinSet;
+
+use crate::error::{Error, Result, WorkError};
+use crate::metrics;
+use crate::rpc::RpcClient;
+use crate::trace;
+use crate::types::{BlockHash, Subtype, Work};
+use crate::work::{DynamicThreshold, WorkThreshold, WorkValidator};
+
+/// Default time allowed for the fastest server.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Work generator racing several work servers.
+#[derive(Clone)]
+pub struct DistributedWorkProvider {
+    servers: Vec<RpcClient>,
+    threshold: WorkThreshold,
+    dynamic: Option<DynamicThreshold>,
+    timeout: Duration,
+}
+
+impl DistributedWorkProvider {
+    /// Create a provider with no servers and mainnet thresholds.
+    pub fn new() -> Self {
+        DistributedWorkProvider {
+            servers: Vec::new(),
+            threshold: WorkThreshold::MAINNET,
+            dynamic: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Add a work server.
+    pub fn with_server(mut self, client: RpcClient) -> Self {
+        self.servers.push(client);
+        self
+    }
+
+    /// Add several work servers.
+    pub fn with_servers(mut self, clients: impl IntoIterator<Item = RpcClient>) -> Self {
+        self.servers.extend(clients);
+        self
+    }
+
+    /// Set the thresholds work must meet.
+    pub fn with_threshold(mut self, threshold: WorkThreshold) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Follow the network difficulty tracked by `dynamic` instead of fixed
+    /// thresholds.
+    pub fn with_dynamic_threshold(mut self, dynamic: DynamicThreshold) -> Self {
+        self.dynamic = Some(dynamic);
+        self
+    }
+
+    /// Set the time allowed before giving up on all servers.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Get the configured servers.
+    pub fn servers(&self) -> &[RpcClient] {
+        &self.servers
+    }
+
+    /// Get the thresholds work currently must meet.
+    pub fn threshold(&self) -> WorkThreshold {
+        match &self.dynamic {
+            Some(dynamic) => dynamic.current(),
+            None => self.threshold,
+        }
+    }
+
+    /// Generate work for a block subtype.
+    ///
+    /// # Arguments
+    /// * `hash` - Work root (previous hash, or public key for open blocks)
+    /// * `subtype` - Block subtype, which selects the threshold
+    pub async fn generate(&self, hash: &BlockHash, subtype: Subtype) -> Result<Work> {
+        self.generate_difficulty(hash, self.threshold().for_subtype(subtype))
+            .await
+    }
+
+    /// Generate work meeting `threshold`.
+    ///
+    /// Fails with [`WorkError::NoSource`] if no servers are configured,
+    /// [`WorkError::Timeout`] if none answers in time, or the error of the
+    /// last server to fail if all of them do.
+    pub async fn generate_difficulty(&self, hash: &BlockHash, threshold: u64) -> Result<Work> {
+        if self.servers.is_empty() {
+            return Err(Error::WorkGeneration(WorkError::NoSource));
+        }
+
+        let stopwatch = trace::Stopwatch::start();
+        let difficulty = format!("{:016x}", threshold);
+        let mut tasks = JoinSet::new();
+        for (index, server) in self.servers.iter().enumerate() {
+            let server = server.clone();
+            let difficulty = difficulty.clone();
+            let hash = *hash;
+            tasks.spawn(async move {
+                let result = server
+                    .work_generate_with_difficulty(&hash, &difficulty)
+                    .await
+                    .map(|response| response.work);
+                (index, result)
+            });
+        }
+
+        let race = async {
+            let mut last_error = Error::WorkGeneration(WorkError::NoSource);
+            while let Some(joined) = tasks.join_next().await {
+                let (index, result) = joined
+                    .map_err(|e| Error::WorkGeneration(WorkError::ServerError(format!("{}", e))))?;
+                match result {
+                    Ok(work) if WorkValidator::validate(work, hash, threshold) => {
+                        trace::log_debug!(
+                            server = %self.servers[index].url(),
+                            hash = %hash,
+                            "distributed work generated"
+                        );
+                        return Ok((index, work));
+                    }
+                    Ok(_) => {
+                        trace::log_warn!(
+                            server = %self.servers[index].url(),
+                            "work server returned work below threshold"
+                        );
+                        last_error = Error::WorkGeneration(WorkError::ServerError(format!(
+                            "server {} returned work below threshold",
+                            index
+                        )));
+                    }
+                    Err(e) => {
+                        trace::log_warn!(
+                            server = %self.servers[index].url(),
+                            error = %e,
+                            "work server failed"
+                        );
+                        last_error = e;
+                    }
+                }
+            }
+            Err(last_error)
+        };
+
+        let outcome = tokio::time::timeout(self.timeout, race).await;
+        tasks.abort_all();
+        let winner = match &outcome {
+            Ok(Ok((index, _))) => Some(*index),
+            _ => None,
+        };
+        self.cancel_others(hash, winner);
+
+        match outcome {
+            Ok(result) => result.map(|(_, work)| {
+                metrics::work_generated("distributed", &stopwatch);
+                work
+            }),
+            Err(_) => Err(Error::WorkGeneration(WorkError::Timeout)),
+        }
+    }
+
+    /// Ask every server except `winner` to stop working on `hash`, without
+    /// waiting for the answers.
+    fn cancel_others(&self, hash: &BlockHash, winner: Option<usize>) {
+        for (index, server) in self.servers.iter().enumerate() {
+            if Some(index) == winner {
+                continue;
+            }
+            let server = server.clone();
+            let hash = *hash;
+            tokio::spawn(async move {
+                let _ = server.work_cancel(&hash).await;
+            });
+        }
+    }
+}
+
+impl Default for DistributedWorkProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_no_servers() {
+        let provider = DistributedWorkProvider::new();
+        assert_eq!(
+            provider
+                .generate(&BlockHash::ZERO, Subtype::Send)
+                .await
+                .unwrap_err(),
+            Error::WorkGeneration(WorkError::NoSource)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_all_servers_fail() {
+        let provider = DistributedWorkProvider::new()
+            .with_servers([
+                RpcClient::new("http://127.0.0.1:1"),
+                RpcClient::new("http://127.0.0.1:2"),
+            ])
+            .with_timeout(Duration::from_secs(5));
+        let err = provider
+            .generate(&BlockHash::ZERO, Subtype::Receive)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Rpc(_)));
+    }
+}
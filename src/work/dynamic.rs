@@ -0,0 +1,222 @@
+//! Work thresholds that follow the network's active difficulty.
+//!
+//! When the network is congested, nodes may require work above the base
+//! thresholds. A [`DynamicThreshold`] tracks the current difficulty, fed
+//! from `active_difficulty` WebSocket messages or the `active_difficulty`
+//! RPC, and is shared with [`CpuWorkGenerator`](crate::work::CpuWorkGenerator)
+//! and [`WorkProvider`](crate::work::WorkProvider) so new work meets it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xno_connect::rpc::RpcClient;
+//! use xno_connect::work::{DynamicThreshold, WorkProvider, WorkThreshold};
+//!
+//! # async fn example() -> xno_connect::Result<()> {
+//! let client = RpcClient::new("http://localhost:7076");
+//! let dynamic = DynamicThreshold::new(WorkThreshold::MAINNET);
+//! let provider = WorkProvider::new()
+//!     .with_node()
+//!     .with_dynamic_threshold(dynamic.clone());
+//!
+//! // Refresh periodically; the provider sees the new difficulty at once.
+//! let multiplier = dynamic.refresh(&client).await?;
+//! println!("network multiplier {:.2}", multiplier);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+#[cfg(any(
+    feature = "rpc",
+    feature = "wasm-rpc",
+    feature = "websocket",
+    feature = "wasm-websocket"
+))]
+use crate::error::{Error, Result};
+use crate::work::{WorkThreshold, WorkValidator};
+
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::error::RpcError;
+#[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+use crate::error::WebSocketError;
+#[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+use crate::rpc::RpcClient;
+#[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+use crate::websocket::ActiveDifficultyMessage;
+
+/// Work thresholds raised to the network's current difficulty.
+///
+/// Clones share the same state, so one instance can be updated while
+/// generators holding clones read it.
+#[derive(Debug, Clone)]
+pub struct DynamicThreshold {
+    base: WorkThreshold,
+    send: Arc<AtomicU64>,
+    receive: Arc<AtomicU64>,
+}
+
+impl DynamicThreshold {
+    /// Create a tracker starting at the base thresholds.
+    pub fn new(base: WorkThreshold) -> Self {
+        DynamicThreshold {
+            base,
+            send: Arc::new(AtomicU64::new(base.send)),
+            receive: Arc::new(AtomicU64::new(base.receive)),
+        }
+    }
+
+    /// Get the base thresholds.
+    pub fn base(&self) -> WorkThreshold {
+        self.base
+    }
+
+    /// Get the thresholds new work must meet.
+    ///
+    /// Never below the base thresholds.
+    pub fn current(&self) -> WorkThreshold {
+        WorkThreshold {
+            send: self.send.load(Ordering::Relaxed).max(self.base.send),
+            receive: self.receive.load(Ordering::Relaxed).max(self.base.receive),
+        }
+    }
+
+    /// Get the current send difficulty relative to the base.
+    pub fn multiplier(&self) -> f64 {
+        WorkValidator::difficulty_multiplier(self.current().send, self.base.send)
+    }
+
+    /// Raise both thresholds by `multiplier`. Values of one or less reset
+    /// them to the base.
+    pub fn set_multiplier(&self, multiplier: f64) {
+        if multiplier > 1.0 {
+            self.set_difficulty(
+                WorkValidator::from_multiplier(self.base.send, multiplier),
+                WorkValidator::from_multiplier(self.base.receive, multiplier),
+            );
+        } else {
+            self.reset();
+        }
+    }
+
+    /// Set the current send and receive difficulties.
+    pub fn set_difficulty(&self, send: u64, receive: u64) {
+        self.send.store(send, Ordering::Relaxed);
+        self.receive.store(receive, Ordering::Relaxed);
+    }
+
+    /// Go back to the base thresholds.
+    pub fn reset(&self) {
+        self.set_difficulty(self.base.send, self.base.receive);
+    }
+
+    /// Update from an `active_difficulty` WebSocket message.
+    ///
+    /// Subscribe with [`Topic::ActiveDifficulty`](crate::websocket::Topic::ActiveDifficulty).
+    #[cfg(any(feature = "websocket", feature = "wasm-websocket"))]
+    pub fn apply(&self, message: &ActiveDifficultyMessage) -> Result<()> {
+        let (send, receive) = self
+            .parse(
+                &message.network_current,
+                message.network_receive_current.as_deref(),
+            )
+            .ok_or_else(|| {
+                Error::WebSocket(WebSocketError::InvalidMessage(
+                    "invalid active difficulty".into(),
+                ))
+            })?;
+        self.set_difficulty(send, receive);
+        Ok(())
+    }
+
+    /// Fetch the current difficulty with the `active_difficulty` RPC.
+    ///
+    /// Returns the new [`multiplier`](Self::multiplier).
+    #[cfg(any(feature = "rpc", feature = "wasm-rpc"))]
+    pub async fn refresh(&self, client: &RpcClient) -> Result<f64> {
+        let response = client.active_difficulty().await?;
+        let (send, receive) = self
+            .parse(
+                &response.network_current,
+                response.network_receive_current.as_deref(),
+            )
+            .ok_or_else(|| {
+                Error::Rpc(RpcError::InvalidResponse(
+                    "invalid active difficulty".into(),
+                ))
+            })?;
+        self.set_difficulty(send, receive);
+        Ok(self.multiplier())
+    }
+
+    /// Parse hex send and receive difficulties. Nodes that only report
+    /// the send difficulty get a receive difficulty with the same
+    /// multiplier.
+    #[cfg(any(
+        feature = "rpc",
+        feature = "wasm-rpc",
+        feature = "websocket",
+        feature = "wasm-websocket",
+        test
+    ))]
+    fn parse(&self, send: &str, receive: Option<&str>) -> Option<(u64, u64)> {
+        let hex = |s: &str| u64::from_str_radix(s, 16).ok();
+        let send = hex(send)?;
+        let receive = match receive {
+            Some(receive) => hex(receive)?,
+            None => WorkValidator::from_multiplier(
+                self.base.receive,
+                WorkValidator::difficulty_multiplier(send, self.base.send),
+            ),
+        };
+        Some((send, receive))
+    }
+}
+
+impl Default for DynamicThreshold {
+    fn default() -> Self {
+        Self::new(WorkThreshold::MAINNET)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multiplier_round_trip() {
+        let dynamic = DynamicThreshold::new(WorkThreshold::MAINNET);
+        assert_eq!(dynamic.current(), WorkThreshold::MAINNET);
+
+        let shared = dynamic.clone();
+        dynamic.set_multiplier(4.0);
+        assert!((shared.multiplier() - 4.0).abs() < 0.01);
+        assert!(shared.current().send > WorkThreshold::MAINNET.send);
+        assert!(shared.current().receive > WorkThreshold::MAINNET.receive);
+
+        dynamic.set_multiplier(0.5);
+        assert_eq!(shared.current(), WorkThreshold::MAINNET);
+
+        // Difficulties below the base are ignored
+        dynamic.set_difficulty(0, 0);
+        assert_eq!(shared.current(), WorkThreshold::MAINNET);
+    }
+
+    #[test]
+    fn test_parse_fills_receive() {
+        let dynamic = DynamicThreshold::new(WorkThreshold::MAINNET);
+        let send = WorkValidator::from_multiplier(WorkThreshold::MAINNET.send, 2.0);
+        let (parsed, receive) = dynamic
+            .parse(&alloc::format!("{:016x}", send), None)
+            .unwrap();
+        assert_eq!(parsed, send);
+        assert!(
+            (WorkValidator::difficulty_multiplier(receive, WorkThreshold::MAINNET.receive) - 2.0)
+                .abs()
+                < 0.01
+        );
+        assert!(dynamic.parse("zz", None).is_none());
+    }
+}
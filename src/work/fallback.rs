@@ -0,0 +1,474 @@
+//! Work generation with fallback across several sources.
+//!
+//! A [`WorkProvider`] tries its configured sources in a fixed order —
+//! cache, GPU, CPU, node RPC, then an external work server — moving on when
+//! a source fails or exceeds its timeout.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use xno_connect::rpc::RpcClient;
+//! use xno_connect::types::{BlockHash, Subtype};
+//! use xno_connect::work::WorkProvider;
+//!
+//! # async fn example() -> xno_connect::Result<()> {
+//! let client = RpcClient::new("http://localhost:7076");
+//! let provider = WorkProvider::new()
+//!     .with_cache()
+//!     .with_node()
+//!     .with_external(RpcClient::new("http://localhost:7000"));
+//!
+//! let report = provider.generate(&BlockHash::ZERO, Subtype::Send, &client).await?;
+//! println!("work {} from {}", report.work, report.source);
+//! # Ok(())
+//! # }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::sync::atomic::AtomicBool;
+use core::time::Duration;
+use std::sync::Mutex;
+
+use crate::error::{Error, Result, WorkError};
+use crate::metrics;
+use crate::rpc::RpcClient;
+use crate::trace;
+use crate::types::{BlockHash, Subtype, Work};
+use crate::work::backend::CancelOnDrop;
+use crate::work::{DynamicThreshold, WorkBackend, WorkThreshold, WorkValidator};
+
+#[cfg(feature = "work-cpu")]
+use crate::work::CpuWorkGenerator;
+
+/// Default time allowed for each source.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A source of proof of work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WorkSource {
+    /// Previously generated work.
+    Cache,
+    /// Local GPU backend.
+    Gpu,
+    /// Local CPU backend.
+    Cpu,
+    /// `work_generate` on the node used for the operation.
+    Node,
+    /// External work server.
+    External,
+}
+
+impl WorkSource {
+    fn label(self) -> &'static str {
+        match self {
+            WorkSource::Cache => "cache",
+            WorkSource::Gpu => "gpu",
+            WorkSource::Cpu => "cpu",
+            WorkSource::Node => "node",
+            WorkSource::External => "external",
+        }
+    }
+}
+
+impl fmt::Display for WorkSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Outcome of a [`WorkProvider::generate`] call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkReport {
+    /// Generated work.
+    pub work: Work,
+    /// Source that produced the work.
+    pub source: WorkSource,
+    /// Sources tried before it, with the error each returned.
+    pub failures: Vec<(WorkSource, Error)>,
+}
+
+/// Work generator that falls back across configured sources.
+pub struct WorkProvider {
+    threshold: WorkThreshold,
+    dynamic: Option<DynamicThreshold>,
+    cache: Option<Mutex<BTreeMap<[u8; 32], Work>>>,
+    gpu: Option<Arc<dyn WorkBackend>>,
+    cpu: Option<Arc<dyn WorkBackend>>,
+    node: bool,
+    external: Option<RpcClient>,
+    timeouts: BTreeMap<WorkSource, Duration>,
+    last_report: Mutex<Option<WorkReport>>,
+}
+
+impl WorkProvider {
+    /// Create a provider with no sources and mainnet thresholds.
+    pub fn new() -> Self {
+        WorkProvider {
+            threshold: WorkThreshold::MAINNET,
+            dynamic: None,
+            cache: None,
+            gpu: None,
+            cpu: None,
+            node: false,
+            external: None,
+            timeouts: BTreeMap::new(),
+            last_report: Mutex::new(None),
+        }
+    }
+
+    /// Set the thresholds work must meet.
+    pub fn with_threshold(mut self, threshold: WorkThreshold) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Follow the network difficulty tracked by `dynamic` instead of fixed
+    /// thresholds.
+    pub fn with_dynamic_threshold(mut self, dynamic: DynamicThreshold) -> Self {
+        self.dynamic = Some(dynamic);
+        self
+    }
+
+    /// Get the thresholds work currently must meet.
+    pub fn threshold(&self) -> WorkThreshold {
+        match &self.dynamic {
+            Some(dynamic) => dynamic.current(),
+            None => self.threshold,
+        }
+    }
+
+    /// Enable the work cache.
+    pub fn with_cache(mut self) -> Self {
+        self.cache = Some(Mutex::new(BTreeMap::new()));
+        self
+    }
+
+    /// Use a GPU backend.
+    pub fn with_gpu<B: WorkBackend>(mut self, backend: B) -> Self {
+        self.gpu = Some(Arc::new(backend));
+        self
+    }
+
+    /// Use a CPU backend.
+    #[cfg(feature = "work-cpu")]
+    pub fn with_cpu(mut self, generator: CpuWorkGenerator) -> Self {
+        self.cpu = Some(Arc::new(generator));
+        self
+    }
+
+    /// Use `work_generate` on the node passed to [`generate`](Self::generate).
+    pub fn with_node(mut self) -> Self {
+        self.node = true;
+        self
+    }
+
+    /// Use an external work server.
+    pub fn with_external(mut self, client: RpcClient) -> Self {
+        self.external = Some(client);
+        self
+    }
+
+    /// Set the time allowed for a source before falling back.
+    pub fn with_timeout(mut self, source: WorkSource, timeout: Duration) -> Self {
+        self.timeouts.insert(source, timeout);
+        self
+    }
+
+    /// Store precomputed work for later use.
+    ///
+    /// Does nothing if the cache is not enabled.
+    pub fn cache_work(&self, hash: &BlockHash, work: Work) {
+        if let Some(cache) = &self.cache {
+            lock(cache).insert(*hash.as_bytes(), work);
+        }
+    }
+
+    /// Get the report of the most recent successful generation.
+    pub fn last_report(&self) -> Option<WorkReport> {
+        lock(&self.last_report).clone()
+    }
+
+    /// Generate work for a block, trying each configured source in order.
+    ///
+    /// # Arguments
+    /// * `hash` - Work root (previous hash, or public key for open blocks)
+    /// * `subtype` - Block subtype, which selects the threshold
+    /// * `client` - RPC client used for the node source
+    pub async fn generate(
+        &self,
+        hash: &BlockHash,
+        subtype: Subtype,
+        client: &RpcClient,
+    ) -> Result<WorkReport> {
+        let threshold = self.threshold().for_subtype(subtype);
+        let mut failures = Vec::new();
+
+        for source in [
+            WorkSource::Cache,
+            WorkSource::Gpu,
+            WorkSource::Cpu,
+            WorkSource::Node,
+            WorkSource::External,
+        ] {
+            let stopwatch = trace::Stopwatch::start();
+            let result = match source {
+                WorkSource::Cache => match self.take_cached(hash, threshold) {
+                    Some(work) => Ok(work),
+                    None => continue,
+                },
+                WorkSource::Gpu | WorkSource::Cpu => {
+                    let backend = match source {
+                        WorkSource::Gpu => &self.gpu,
+                        _ => &self.cpu,
+                    };
+                    match backend {
+                        Some(backend) => {
+                            self.run_backend(source, backend.clone(), *hash, threshold)
+                                .await
+                        }
+                        None => continue,
+                    }
+                }
+                WorkSource::Node if self.node => {
+                    self.run_remote(source, client, hash, threshold).await
+                }
+                WorkSource::External => match &self.external {
+                    Some(external) => self.run_remote(source, external, hash, threshold).await,
+                    None => continue,
+                },
+                WorkSource::Node => continue,
+            };
+
+            match result {
+                Ok(work) => {
+                    trace::log_debug!(
+                        hash = %hash,
+                        source = ?source,
+                        failures = failures.len(),
+                        "work generated"
+                    );
+                    // The CPU generator records its own duration.
+                    if source != WorkSource::Cpu {
+                        metrics::work_generated(source.label(), &stopwatch);
+                    }
+                    let report = WorkReport {
+                        work,
+                        source,
+                        failures,
+                    };
+                    *lock(&self.last_report) = Some(report.clone());
+                    return Ok(report);
+                }
+                Err(e) => {
+                    trace::log_warn!(hash = %hash, source = ?source, error = %e, "work source failed");
+                    failures.push((source, e));
+                }
+            }
+        }
+
+        trace::log_warn!(hash = %hash, "no work source succeeded");
+        Err(Error::WorkGeneration(WorkError::NoSource))
+    }
+
+    fn timeout(&self, source: WorkSource) -> Duration {
+        self.timeouts
+            .get(&source)
+            .copied()
+            .unwrap_or(DEFAULT_TIMEOUT)
+    }
+
+    fn take_cached(&self, hash: &BlockHash, threshold: u64) -> Option<Work> {
+        let work = lock(self.cache.as_ref()?).remove(hash.as_bytes())?;
+        WorkValidator::validate(work, hash, threshold).then_some(work)
+    }
+
+    async fn run_backend(
+        &self,
+        source: WorkSource,
+        backend: Arc<dyn WorkBackend>,
+        hash: BlockHash,
+        threshold: u64,
+    ) -> Result<Work> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+        // Stop the backend on timeout and whenever this future is dropped;
+        // it checks the flag periodically
+        let _cancel = CancelOnDrop(cancelled);
+        let task =
+            tokio::task::spawn_blocking(move || backend.generate_work(&hash, threshold, &flag));
+
+        match tokio::time::timeout(self.timeout(source), task).await {
+            Ok(joined) => joined
+                .map_err(|e| Error::WorkGeneration(WorkError::ServerError(format!("{}", e))))?,
+            Err(_) => Err(Error::WorkGeneration(WorkError::Timeout)),
+        }
+    }
+
+    async fn run_remote(
+        &self,
+        source: WorkSource,
+        client: &RpcClient,
+        hash: &BlockHash,
+        threshold: u64,
+    ) -> Result<Work> {
+        let difficulty = format!("{:016x}", threshold);
+        let request = client.work_generate_with_difficulty(hash, &difficulty);
+        let work = tokio::time::timeout(self.timeout(source), request)
+            .await
+            .map_err(|_| Error::WorkGeneration(WorkError::Timeout))??
+            .work;
+
+        if WorkValidator::validate(work, hash, threshold) {
+            Ok(work)
+        } else {
+            Err(Error::WorkGeneration(WorkError::ServerError(format!(
+                "{} returned work below threshold",
+                source
+            ))))
+        }
+    }
+}
+
+impl Default for WorkProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::sync::atomic::Ordering;
+
+    const HASH: &str = "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948";
+
+    const EASY: WorkThreshold = WorkThreshold {
+        send: 0xf000000000000000,
+        receive: 0xf000000000000000,
+    };
+
+    /// Backend that never finds work and waits to be cancelled.
+    struct StuckBackend;
+
+    impl WorkBackend for StuckBackend {
+        fn generate_work(&self, _: &BlockHash, _: u64, cancelled: &AtomicBool) -> Result<Work> {
+            while !cancelled.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            Err(Error::WorkGeneration(WorkError::Cancelled))
+        }
+    }
+
+    /// Backend that searches sequentially from zero.
+    struct SimpleBackend;
+
+    impl WorkBackend for SimpleBackend {
+        fn generate_work(&self, hash: &BlockHash, threshold: u64, _: &AtomicBool) -> Result<Work> {
+            (0..u64::MAX)
+                .map(Work::new)
+                .find(|work| WorkValidator::validate(*work, hash, threshold))
+                .ok_or(Error::WorkGeneration(WorkError::MaxIterations))
+        }
+    }
+
+    /// Backend that waits to be cancelled and records that it was.
+    struct ObservedBackend(Arc<AtomicBool>);
+
+    impl WorkBackend for ObservedBackend {
+        fn generate_work(&self, _: &BlockHash, _: u64, cancelled: &AtomicBool) -> Result<Work> {
+            while !cancelled.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            self.0.store(true, Ordering::Relaxed);
+            Err(Error::WorkGeneration(WorkError::Cancelled))
+        }
+    }
+
+    fn client() -> RpcClient {
+        RpcClient::new("http://127.0.0.1:1")
+    }
+
+    #[tokio::test]
+    async fn test_cache_hit() {
+        let hash = BlockHash::from_hex(HASH).unwrap();
+        let work = SimpleBackend
+            .generate_work(&hash, EASY.send, &AtomicBool::new(false))
+            .unwrap();
+
+        let provider = WorkProvider::new().with_threshold(EASY).with_cache();
+        provider.cache_work(&hash, work);
+
+        let report = provider
+            .generate(&hash, Subtype::Send, &client())
+            .await
+            .unwrap();
+        assert_eq!(report.source, WorkSource::Cache);
+        assert_eq!(report.work, work);
+        assert_eq!(provider.last_report(), Some(report));
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_after_timeout() {
+        let hash = BlockHash::from_hex(HASH).unwrap();
+        let provider = WorkProvider::new()
+            .with_threshold(EASY)
+            .with_cache()
+            .with_gpu(StuckBackend)
+            .with_timeout(WorkSource::Gpu, Duration::from_millis(20));
+        let provider = WorkProvider {
+            cpu: Some(Arc::new(SimpleBackend)),
+            ..provider
+        };
+
+        let report = provider
+            .generate(&hash, Subtype::Receive, &client())
+            .await
+            .unwrap();
+        assert_eq!(report.source, WorkSource::Cpu);
+        assert_eq!(
+            report.failures,
+            [(WorkSource::Gpu, Error::WorkGeneration(WorkError::Timeout))]
+        );
+        assert!(WorkValidator::validate(report.work, &hash, EASY.receive));
+    }
+
+    #[tokio::test]
+    async fn test_dropping_generate_cancels_backend() {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let provider = WorkProvider::new()
+            .with_gpu(ObservedBackend(stopped.clone()))
+            .with_timeout(WorkSource::Gpu, Duration::MAX);
+
+        let client = client();
+        let generate = provider.generate(&BlockHash::ZERO, Subtype::Send, &client);
+        assert!(tokio::time::timeout(Duration::from_millis(20), generate)
+            .await
+            .is_err());
+
+        for _ in 0..1000 {
+            if stopped.load(Ordering::Relaxed) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        panic!("backend kept running after generate was dropped");
+    }
+
+    #[tokio::test]
+    async fn test_no_source() {
+        let provider = WorkProvider::new();
+        let result = provider
+            .generate(&BlockHash::ZERO, Subtype::Send, &client())
+            .await;
+        assert_eq!(result, Err(Error::WorkGeneration(WorkError::NoSource)));
+    }
+}
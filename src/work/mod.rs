@@ -4,12 +4,36 @@
 //! for each block before it can be processed by the network.
 //! For remote work generation use RPC request.
 
+mod backend;
+#[cfg(target_has_atomic = "64")]
+mod dynamic;
 mod validate;
 
 #[cfg(feature = "work-cpu")]
 mod cpu;
 
+#[cfg(feature = "rpc")]
+mod distributed;
+#[cfg(feature = "rpc")]
+mod fallback;
+
+#[cfg(all(feature = "work-server", not(target_arch = "wasm32")))]
+mod server;
+
+pub use backend::WorkBackend;
+/// Alias of [`WorkBackend`], for code written against the generator naming.
+pub use backend::WorkBackend as WorkGenerator;
+#[cfg(target_has_atomic = "64")]
+pub use dynamic::DynamicThreshold;
 pub use validate::{WorkThreshold, WorkValidator};
 
 #[cfg(feature = "work-cpu")]
-pub use cpu::CpuWorkGenerator;
+pub use cpu::{CpuWorkGenerator, WorkBenchmark, WorkProgress};
+
+#[cfg(feature = "rpc")]
+pub use distributed::DistributedWorkProvider;
+#[cfg(feature = "rpc")]
+pub use fallback::{WorkProvider, WorkReport, WorkSource};
+
+#[cfg(all(feature = "work-server", not(target_arch = "wasm32")))]
+pub use server::WorkServer;
@@ -0,0 +1,670 @@
+//! Work server implementing the `nano-work-server` HTTP protocol.
+//!
+//! Accepts JSON `POST` requests with the `work_generate`, `work_validate`
+//! and `work_cancel` actions, so a node or wallet can use this crate as a
+//! remote work peer.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use xno_connect::work::{CpuWorkGenerator, WorkServer};
+//!
+//! # async fn example() -> xno_connect::Result<()> {
+//! WorkServer::new(CpuWorkGenerator::new())
+//!     .serve("0.0.0.0:7000")
+//!     .await
+//! # }
+//! ```
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use core::time::Duration;
+use std::sync::Mutex;
+
+use futures_util::future::{select, Either};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::Semaphore;
+
+use crate::error::{Error, Result, WorkError};
+use crate::types::{BlockHash, Work};
+use crate::work::{CpuWorkGenerator, WorkBackend, WorkThreshold, WorkValidator};
+
+/// Maximum accepted request size in bytes.
+const MAX_REQUEST_SIZE: usize = 64 * 1024;
+
+/// How long a client may stay idle while sending its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default cap on the requested difficulty, as a multiple of the base
+/// send threshold.
+const DEFAULT_MAX_MULTIPLIER: f64 = 64.0;
+
+/// Default number of searches run at once; later requests wait their turn.
+const DEFAULT_MAX_JOBS: usize = 4;
+
+/// Default time a `work_generate` request may take, including waiting for
+/// a free job slot.
+const DEFAULT_JOB_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Running jobs keyed by root hash and request id, so concurrent requests
+/// for the same root each keep their own cancel flag.
+type Jobs = BTreeMap<([u8; 32], u64), Arc<AtomicBool>>;
+
+/// Work request body.
+#[derive(Debug, Deserialize)]
+struct WorkRequest {
+    action: String,
+    #[serde(default)]
+    hash: Option<String>,
+    #[serde(default)]
+    work: Option<String>,
+    #[serde(default)]
+    difficulty: Option<String>,
+    #[serde(default)]
+    multiplier: Option<String>,
+}
+
+/// HTTP work server.
+pub struct WorkServer<B: WorkBackend = CpuWorkGenerator> {
+    backend: Arc<B>,
+    threshold: WorkThreshold,
+    max_multiplier: f64,
+    timeout: Duration,
+    permits: Arc<Semaphore>,
+    jobs: Arc<Mutex<Jobs>>,
+    next_job: Arc<AtomicU64>,
+}
+
+impl<B: WorkBackend> Clone for WorkServer<B> {
+    fn clone(&self) -> Self {
+        WorkServer {
+            backend: self.backend.clone(),
+            threshold: self.threshold,
+            max_multiplier: self.max_multiplier,
+            timeout: self.timeout,
+            permits: self.permits.clone(),
+            jobs: self.jobs.clone(),
+            next_job: self.next_job.clone(),
+        }
+    }
+}
+
+impl<B: WorkBackend> WorkServer<B> {
+    /// Create a work server backed by the given generator.
+    pub fn new(backend: B) -> Self {
+        WorkServer {
+            backend: Arc::new(backend),
+            threshold: WorkThreshold::MAINNET,
+            max_multiplier: DEFAULT_MAX_MULTIPLIER,
+            timeout: DEFAULT_JOB_TIMEOUT,
+            permits: Arc::new(Semaphore::new(DEFAULT_MAX_JOBS)),
+            jobs: Arc::new(Mutex::new(BTreeMap::new())),
+            next_job: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Set the thresholds used as base difficulty.
+    ///
+    /// Requests without a `difficulty` use the send threshold, and
+    /// `multiplier` values are relative to it.
+    pub fn with_threshold(mut self, threshold: WorkThreshold) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Set the highest difficulty `work_generate` accepts, as a multiple
+    /// of the send threshold.
+    pub fn with_max_multiplier(mut self, multiplier: f64) -> Self {
+        self.max_multiplier = multiplier;
+        self
+    }
+
+    /// Set how many searches may run at once.
+    pub fn with_max_jobs(mut self, jobs: usize) -> Self {
+        self.permits = Arc::new(Semaphore::new(jobs));
+        self
+    }
+
+    /// Set how long a `work_generate` request may take before its search
+    /// is cancelled.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Bind to an address and serve requests until an I/O error occurs.
+    pub async fn serve<A: ToSocketAddrs>(self, addr: A) -> Result<()> {
+        let listener = TcpListener::bind(addr).await.map_err(server_error)?;
+        self.serve_listener(listener).await
+    }
+
+    /// Serve requests on an already bound listener.
+    pub async fn serve_listener(self, listener: TcpListener) -> Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await.map_err(server_error)?;
+            let server = self.clone();
+            tokio::spawn(async move {
+                // A failed connection only affects its own client
+                let _ = server.handle_connection(stream).await;
+            });
+        }
+    }
+
+    /// Handle a JSON request body and return the JSON response body.
+    pub async fn handle(&self, body: &str) -> String {
+        match self.dispatch(body).await {
+            Ok(value) => value.to_string(),
+            Err(e) => json!({ "error": e.to_string() }).to_string(),
+        }
+    }
+
+    async fn dispatch(&self, body: &str) -> Result<Value> {
+        let request: WorkRequest = serde_json::from_str(body)
+            .map_err(|e| server_error(format!("invalid request: {}", e)))?;
+        let hash = request
+            .hash
+            .as_deref()
+            .ok_or_else(|| server_error("missing hash"))
+            .and_then(BlockHash::from_hex);
+
+        match request.action.as_str() {
+            "work_generate" => {
+                let hash = hash?;
+                let threshold = self.requested_difficulty(&request)?;
+                if threshold
+                    > WorkValidator::from_multiplier(self.threshold.send, self.max_multiplier)
+                {
+                    return Err(server_error(format!(
+                        "difficulty above maximum multiplier {}",
+                        self.max_multiplier
+                    )));
+                }
+                let work = self.generate(hash, threshold).await?;
+                let difficulty = WorkValidator::difficulty(work, &hash);
+                Ok(json!({
+                    "work": work.to_hex(),
+                    "difficulty": format!("{:016x}", difficulty),
+                    "multiplier": WorkValidator::difficulty_multiplier(difficulty, self.threshold.send).to_string(),
+                }))
+            }
+            "work_validate" => {
+                let hash = hash?;
+                let work = request
+                    .work
+                    .as_deref()
+                    .ok_or_else(|| server_error("missing work"))
+                    .and_then(Work::from_hex)?;
+                let difficulty = WorkValidator::difficulty(work, &hash);
+                let flag = |valid: bool| if valid { "1" } else { "0" };
+                let mut response = json!({
+                    "valid_all": flag(difficulty >= self.threshold.send),
+                    "valid_receive": flag(difficulty >= self.threshold.receive),
+                    "difficulty": format!("{:016x}", difficulty),
+                    "multiplier": WorkValidator::difficulty_multiplier(difficulty, self.threshold.send).to_string(),
+                });
+                if request.difficulty.is_some() || request.multiplier.is_some() {
+                    let threshold = self.requested_difficulty(&request)?;
+                    response["valid"] = flag(difficulty >= threshold).into();
+                }
+                Ok(response)
+            }
+            "work_cancel" => {
+                let hash = hash?;
+                let root = *hash.as_bytes();
+                for (_, flag) in self.jobs_lock().range((root, 0)..=(root, u64::MAX)) {
+                    flag.store(true, Ordering::Relaxed);
+                }
+                Ok(json!({}))
+            }
+            other => Err(server_error(format!("unknown action: {}", other))),
+        }
+    }
+
+    /// Resolve the difficulty from `difficulty` or `multiplier`.
+    fn requested_difficulty(&self, request: &WorkRequest) -> Result<u64> {
+        if let Some(difficulty) = &request.difficulty {
+            return u64::from_str_radix(difficulty, 16)
+                .map_err(|_| server_error("invalid difficulty"));
+        }
+        if let Some(value) = &request.multiplier {
+            let value: f64 = value
+                .parse()
+                .ok()
+                .filter(|m: &f64| *m > 0.0)
+                .ok_or_else(|| server_error("invalid multiplier"))?;
+            return Ok(WorkValidator::from_multiplier(self.threshold.send, value));
+        }
+        Ok(self.threshold.send)
+    }
+
+    /// Run a search once a job slot is free.
+    ///
+    /// The search is cancelled if it outlives the timeout or this future
+    /// is dropped, e.g. because the client disconnected.
+    async fn generate(&self, hash: BlockHash, threshold: u64) -> Result<Work> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let job = Job {
+            server: self,
+            key: (
+                *hash.as_bytes(),
+                self.next_job.fetch_add(1, Ordering::Relaxed),
+            ),
+            cancelled: cancelled.clone(),
+        };
+        self.jobs_lock().insert(job.key, cancelled.clone());
+
+        let search = async {
+            let permit = self
+                .permits
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(server_error)?;
+            let backend = self.backend.clone();
+            tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                backend.generate_work(&hash, threshold, &cancelled)
+            })
+            .await
+            .map_err(server_error)
+            .and_then(|r| r)
+        };
+        tokio::time::timeout(self.timeout, search)
+            .await
+            .unwrap_or(Err(Error::WorkGeneration(WorkError::Timeout)))
+    }
+
+    fn jobs_lock(&self) -> std::sync::MutexGuard<'_, Jobs> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    async fn handle_connection(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        let mut buffer = alloc::vec::Vec::new();
+        let mut chunk = [0u8; 4096];
+
+        // Read headers, then the body announced by Content-Length
+        let (header_end, content_length) = loop {
+            let n = match read_chunk(&mut stream, &mut chunk).await {
+                Some(n) => n?,
+                None => return write_response(&mut stream, "408 Request Timeout", "").await,
+            };
+            if n == 0 {
+                return Ok(());
+            }
+            buffer.extend_from_slice(&chunk[..n]);
+            if let Some(end) = find_header_end(&buffer) {
+                let headers = String::from_utf8_lossy(&buffer[..end]);
+                break (end, content_length(&headers));
+            }
+            if buffer.len() > MAX_REQUEST_SIZE {
+                return write_response(&mut stream, "413 Payload Too Large", "").await;
+            }
+        };
+        let Some(content_length) = content_length else {
+            return write_response(&mut stream, "411 Length Required", "").await;
+        };
+        if content_length > MAX_REQUEST_SIZE {
+            return write_response(&mut stream, "413 Payload Too Large", "").await;
+        }
+        while buffer.len() < header_end + content_length {
+            let n = match read_chunk(&mut stream, &mut chunk).await {
+                Some(n) => n?,
+                None => return write_response(&mut stream, "408 Request Timeout", "").await,
+            };
+            if n == 0 {
+                break;
+            }
+            buffer.extend_from_slice(&chunk[..n]);
+        }
+
+        let end = buffer.len().min(header_end + content_length);
+        let body = String::from_utf8_lossy(&buffer[header_end..end]);
+
+        // Stop handling, and so cancel any search, once the client leaves
+        let response = {
+            let handle = core::pin::pin!(self.handle(&body));
+            let closed = core::pin::pin!(wait_closed(&mut stream, &mut chunk));
+            match select(handle, closed).await {
+                Either::Left((response, _)) => response,
+                Either::Right(_) => return Ok(()),
+            }
+        };
+        write_response(&mut stream, "200 OK", &response).await
+    }
+}
+
+/// A running search, cancelled and forgotten when dropped.
+struct Job<'a, B: WorkBackend> {
+    server: &'a WorkServer<B>,
+    key: ([u8; 32], u64),
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<B: WorkBackend> Drop for Job<'_, B> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        self.server.jobs_lock().remove(&self.key);
+    }
+}
+
+/// Convert any displayable error into a work server error.
+fn server_error<E: ToString>(e: E) -> Error {
+    Error::WorkGeneration(WorkError::ServerError(e.to_string()))
+}
+
+/// Find the byte offset just past the `\r\n\r\n` header terminator.
+fn find_header_end(buffer: &[u8]) -> Option<usize> {
+    buffer
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .map(|pos| pos + 4)
+}
+
+/// Parse the Content-Length header, if present and valid.
+fn content_length(headers: &str) -> Option<usize> {
+    headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse().ok())
+}
+
+/// Read from the stream, or `None` if the client stays idle too long.
+async fn read_chunk(stream: &mut TcpStream, chunk: &mut [u8]) -> Option<std::io::Result<usize>> {
+    tokio::time::timeout(READ_TIMEOUT, stream.read(chunk))
+        .await
+        .ok()
+}
+
+/// Resolve once the client closes its side of the connection.
+async fn wait_closed(stream: &mut TcpStream, chunk: &mut [u8]) {
+    while let Ok(n) = stream.read(chunk).await {
+        if n == 0 {
+            return;
+        }
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948";
+
+    fn test_server() -> WorkServer {
+        WorkServer::new(CpuWorkGenerator::new()).with_threshold(WorkThreshold {
+            send: 0xff00000000000000,
+            receive: 0xf000000000000000,
+        })
+    }
+
+    fn parse(response: &str) -> Value {
+        serde_json::from_str(response).unwrap()
+    }
+
+    /// Searches until cancelled, tracking how many searches overlap.
+    #[derive(Default)]
+    struct StallingBackend {
+        running: core::sync::atomic::AtomicUsize,
+        peak: core::sync::atomic::AtomicUsize,
+        stopped: AtomicBool,
+    }
+
+    impl WorkBackend for StallingBackend {
+        fn generate_work(&self, _: &BlockHash, _: u64, cancelled: &AtomicBool) -> Result<Work> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(running, Ordering::SeqCst);
+            while !cancelled.load(Ordering::Relaxed) {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            self.stopped.store(true, Ordering::SeqCst);
+            Err(Error::WorkGeneration(WorkError::Cancelled))
+        }
+    }
+
+    fn generate_request() -> String {
+        format!(r#"{{"action":"work_generate","hash":"{}"}}"#, HASH)
+    }
+
+    #[tokio::test]
+    async fn test_generate_and_validate() {
+        let server = test_server();
+
+        let response = parse(
+            &server
+                .handle(&format!(
+                    r#"{{"action":"work_generate","hash":"{}"}}"#,
+                    HASH
+                ))
+                .await,
+        );
+        let work = response["work"].as_str().unwrap();
+        assert!(WorkValidator::validate(
+            Work::from_hex(work).unwrap(),
+            &BlockHash::from_hex(HASH).unwrap(),
+            0xff00000000000000
+        ));
+
+        let response = parse(
+            &server
+                .handle(&format!(
+                    r#"{{"action":"work_validate","hash":"{}","work":"{}","difficulty":"ff00000000000000"}}"#,
+                    HASH, work
+                ))
+                .await,
+        );
+        assert_eq!(response["valid_all"], "1");
+        assert_eq!(response["valid_receive"], "1");
+        assert_eq!(response["valid"], "1");
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let server = test_server();
+
+        let response = parse(&server.handle(r#"{"action":"work_generate"}"#).await);
+        assert!(response["error"].is_string());
+
+        let response = parse(&server.handle(r#"{"action":"bogus","hash":"00"}"#).await);
+        assert!(response["error"]
+            .as_str()
+            .unwrap()
+            .contains("unknown action"));
+
+        let response = parse(&server.handle("not json").await);
+        assert!(response["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_difficulty_above_max_multiplier() {
+        let server = test_server().with_max_multiplier(2.0);
+
+        let response = parse(
+            &server
+                .handle(&format!(
+                    r#"{{"action":"work_generate","hash":"{}","multiplier":"4"}}"#,
+                    HASH
+                ))
+                .await,
+        );
+        assert!(response["error"].as_str().unwrap().contains("maximum"));
+
+        let response = parse(
+            &server
+                .handle(&format!(
+                    r#"{{"action":"work_generate","hash":"{}","difficulty":"ffffffffffffffff"}}"#,
+                    HASH
+                ))
+                .await,
+        );
+        assert!(response["error"].as_str().unwrap().contains("maximum"));
+
+        // Validation only compares, so any difficulty is fine there
+        let response = parse(
+            &server
+                .handle(&format!(
+                    r#"{{"action":"work_validate","hash":"{}","work":"0000000000000000","multiplier":"4"}}"#,
+                    HASH
+                ))
+                .await,
+        );
+        assert_eq!(response["valid"], "0");
+    }
+
+    #[tokio::test]
+    async fn test_timeout_cancels_and_jobs_are_limited() {
+        let server = WorkServer::new(StallingBackend::default())
+            .with_max_jobs(1)
+            .with_timeout(Duration::from_millis(50));
+
+        let request = generate_request();
+        let (first, second) = tokio::join!(server.handle(&request), server.handle(&request));
+        assert!(parse(&first)["error"]
+            .as_str()
+            .unwrap()
+            .contains("timed out"));
+        assert!(parse(&second)["error"]
+            .as_str()
+            .unwrap()
+            .contains("timed out"));
+        assert!(server.jobs_lock().is_empty());
+
+        // The timed out search notices its cancel flag shortly after
+        for _ in 0..1000 {
+            if server.backend.stopped.load(Ordering::SeqCst) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert!(server.backend.stopped.load(Ordering::SeqCst));
+        assert_eq!(server.backend.peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_cancels_search() {
+        let server = WorkServer::new(StallingBackend::default());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(server.clone().serve_listener(listener));
+
+        let body = generate_request();
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                format!(
+                    "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        while server.backend.running.load(Ordering::SeqCst) == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        drop(stream);
+
+        for _ in 0..1000 {
+            if server.backend.stopped.load(Ordering::SeqCst) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        panic!("search still running after the client disconnected");
+    }
+
+    #[test]
+    fn test_multiplier_round_trip() {
+        let base = 0xfffffe0000000000;
+        assert_eq!(WorkValidator::from_multiplier(base, 1.0), base);
+        let difficulty = WorkValidator::from_multiplier(base, 8.0);
+        assert!((WorkValidator::difficulty_multiplier(difficulty, base) - 8.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_content_length() {
+        let headers = "POST / HTTP/1.1\r\nHost: x\r\ncontent-length: 42\r\n\r\n";
+        assert_eq!(content_length(headers), Some(42));
+        assert_eq!(content_length("POST / HTTP/1.1\r\nHost: x\r\n\r\n"), None);
+        assert_eq!(find_header_end(headers.as_bytes()), Some(headers.len()));
+    }
+
+    #[tokio::test]
+    async fn test_serve_over_http() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(test_server().serve_listener(listener));
+
+        let body = format!(r#"{{"action":"work_generate","hash":"{}"}}"#, HASH);
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(
+                format!(
+                    "POST / HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("\"work\""));
+    }
+
+    #[tokio::test]
+    async fn test_missing_content_length() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(test_server().serve_listener(listener));
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"POST / HTTP/1.1\r\nContent-Type: application/json\r\n\r\n{}")
+            .await
+            .unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 411 Length Required"));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_reaches_every_job_for_root() {
+        let server = test_server();
+        let root = *BlockHash::from_hex(HASH).unwrap().as_bytes();
+        let first = Arc::new(AtomicBool::new(false));
+        let second = Arc::new(AtomicBool::new(false));
+        server.jobs_lock().insert((root, 0), first.clone());
+        server.jobs_lock().insert((root, 1), second.clone());
+
+        server
+            .handle(&format!(r#"{{"action":"work_cancel","hash":"{}"}}"#, HASH))
+            .await;
+
+        assert!(first.load(Ordering::Relaxed));
+        assert!(second.load(Ordering::Relaxed));
+    }
+}
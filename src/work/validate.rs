@@ -8,7 +8,11 @@
 use blake2::digest::consts::U8;
 use blake2::{Blake2b, Digest};
 
-use crate::constants::{WORK_THRESHOLD_RECEIVE, WORK_THRESHOLD_SEND};
+use crate::constants::{
+    WORK_THRESHOLD_BETA_RECEIVE, WORK_THRESHOLD_BETA_SEND, WORK_THRESHOLD_DEV_RECEIVE,
+    WORK_THRESHOLD_DEV_SEND, WORK_THRESHOLD_RECEIVE, WORK_THRESHOLD_SEND,
+};
+use crate::network::Network;
 use crate::types::{BlockHash, Subtype, Work};
 
 /// Work difficulty thresholds for different block types.
@@ -27,6 +31,23 @@ impl WorkThreshold {
         receive: WORK_THRESHOLD_RECEIVE,
     };
 
+    /// Beta network thresholds.
+    pub const BETA: WorkThreshold = WorkThreshold {
+        send: WORK_THRESHOLD_BETA_SEND,
+        receive: WORK_THRESHOLD_BETA_RECEIVE,
+    };
+
+    /// Dev network thresholds.
+    pub const DEV: WorkThreshold = WorkThreshold {
+        send: WORK_THRESHOLD_DEV_SEND,
+        receive: WORK_THRESHOLD_DEV_RECEIVE,
+    };
+
+    /// Get the thresholds for a network.
+    pub const fn for_network(network: &Network) -> Self {
+        network.work_threshold
+    }
+
     /// Get the threshold for a specific block subtype.
     pub fn for_subtype(&self, subtype: Subtype) -> u64 {
         match subtype {
@@ -46,6 +67,12 @@ impl WorkThreshold {
     }
 }
 
+impl From<Network> for WorkThreshold {
+    fn from(network: Network) -> Self {
+        network.work_threshold
+    }
+}
+
 impl Default for WorkThreshold {
     fn default() -> Self {
         Self::MAINNET
@@ -63,7 +90,7 @@ impl WorkValidator {
         let mut hasher = Blake2b::<U8>::new();
 
         // Work is hashed as little-endian bytes
-        hasher.update(&work.to_le_bytes());
+        hasher.update(work.to_le_bytes());
         hasher.update(hash.as_bytes());
 
         let result: [u8; 8] = hasher.finalize().into();
@@ -109,9 +136,21 @@ impl WorkValidator {
             base as f64 / actual as f64
         }
     }
+
+    /// Get the multiplier of a difficulty relative to a base threshold.
+    pub fn difficulty_multiplier(difficulty: u64, base: u64) -> f64 {
+        (u64::MAX - base) as f64 / (u64::MAX - difficulty).max(1) as f64
+    }
+
+    /// Get the difficulty for a multiplier relative to a base threshold.
+    pub fn from_multiplier(base: u64, multiplier: f64) -> u64 {
+        let reverse = ((u64::MAX - base) as f64 / multiplier) as u64;
+        u64::MAX - reverse
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants)]
 mod tests {
     use super::*;
 
@@ -129,6 +168,16 @@ mod tests {
         assert!(difficulty > 0);
     }
 
+    #[test]
+    fn test_threshold_for_network() {
+        assert_eq!(
+            WorkThreshold::for_network(&Network::DEV),
+            WorkThreshold::DEV
+        );
+        assert_eq!(WorkThreshold::from(Network::BETA), WorkThreshold::BETA);
+        assert_eq!(WorkThreshold::from(Network::TEST), WorkThreshold::MAINNET);
+    }
+
     #[test]
     fn test_threshold_for_subtype() {
         let threshold = WorkThreshold::MAINNET;
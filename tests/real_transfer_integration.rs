@@ -5,7 +5,13 @@
 #![cfg(not(target_arch = "wasm32"))]
 
 use std::env;
-use xno_connect::{rpc::RpcClient, types::Account, wallet::Wallet};
+use std::sync::Arc;
+use xno_connect::{
+    rpc::RpcClient,
+    types::Account,
+    wallet::Wallet,
+    work::{CpuWorkGenerator, WorkProvider},
+};
 
 #[tokio::test]
 #[ignore]
@@ -14,7 +20,7 @@ use xno_connect::{rpc::RpcClient, types::Account, wallet::Wallet};
 async fn test_send_and_change_representative() {
     dotenvy::dotenv().ok();
 
-    let client = RpcClient::new(&env::var("NANO_RPC_URL").unwrap());
+    let client = RpcClient::new(env::var("NANO_RPC_URL").unwrap());
     let destination =
         Account::from_address_str_checked(&env::var("NANO_DESTINATION").unwrap()).unwrap();
     let new_rep =
@@ -23,7 +29,10 @@ async fn test_send_and_change_representative() {
     let mut wallet = Wallet::from_hex_seed(&env::var("NANO_SEED").unwrap()).unwrap();
     let result = wallet
         .account(0)
-        .send_and_change_local(&destination, 1.into(), &new_rep, &client)
+        .with_work_provider(Arc::new(
+            WorkProvider::new().with_cpu(CpuWorkGenerator::new()),
+        ))
+        .send_and_change(&destination, 1.into(), &new_rep, &client)
         .await;
 
     println!("Result: {:?}", result);
@@ -7,7 +7,9 @@
 use std::env;
 use std::time::Duration;
 use xno_connect::{
+    rpc::RpcClient,
     types::Account,
+    wallet::Wallet,
     websocket::{ParsedMessage, SubscriptionBuilder, WebSocketClient},
 };
 
@@ -115,3 +117,44 @@ async fn test_websocket_receive_with_timeout() {
     println!("Received {} confirmation(s)", count);
     println!("Test passed!");
 }
+
+#[tokio::test]
+#[ignore]
+#[cfg(not(coverage))]
+async fn test_send_confirmed() {
+    dotenvy::dotenv().ok();
+
+    let ws_url = match env::var("NANO_WS_URL") {
+        Ok(url) => url,
+        Err(_) => {
+            println!("NANO_WS_URL not set, skipping WebSocket test");
+            return;
+        }
+    };
+
+    let client = RpcClient::new(env::var("NANO_RPC_URL").unwrap());
+    let mut ws_client = WebSocketClient::connect(&ws_url)
+        .await
+        .expect("Failed to connect");
+    let destination =
+        Account::from_address_str_checked(&env::var("NANO_DESTINATION").unwrap()).unwrap();
+
+    let mut wallet = Wallet::from_hex_seed(&env::var("NANO_SEED").unwrap()).unwrap();
+    let confirmation = wallet
+        .account(0)
+        .send_confirmed(
+            &destination,
+            1.into(),
+            &client,
+            &mut ws_client,
+            Duration::from_secs(60),
+        )
+        .await
+        .expect("Send was not confirmed");
+
+    println!(
+        "Confirmed: {} ({:?})",
+        confirmation.hash, confirmation.election_info
+    );
+    assert!(!confirmation.hash.is_zero());
+}